@@ -0,0 +1,96 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, NavigationResult, SessionTrait};
+
+/// Problems with a back or forward navigation that should land on `expected_path`
+async fn check(
+    session: &BrowserSession<surfai::ChromeBrowser>,
+    navigation: &NavigationResult,
+    expected_path: &str,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut failures = Vec::new();
+    println!(
+        "   {} → reason {}, {}ms",
+        navigation.url, navigation.reason, navigation.duration_ms
+    );
+    if !navigation.success || !navigation.url.ends_with(expected_path) {
+        failures.push(format!("expected to land on {}", expected_path));
+    }
+
+    let page = session
+        .execute_script(
+            "({ restores: Number(document.getElementById('restores').textContent), \
+               observer: !!window.browserAgentObserver })",
+        )
+        .await?;
+    let restores = page["restores"].as_u64().unwrap_or_default();
+    if restores == 0 {
+        println!("   ⚠️ The browser did not keep this page in the back-forward cache");
+    } else if !navigation.is_bfcache_restore() {
+        failures.push(format!(
+            "{} was restored from the cache but reported as {}",
+            expected_path, navigation.reason
+        ));
+    }
+    if page["observer"].as_bool() != Some(true) {
+        failures.push(format!(
+            "no DOM observer on {} after {}",
+            expected_path, navigation.reason
+        ));
+    }
+    Ok(failures)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Back Forward")
+        .version("1.0")
+        .about("Goes back and forward between two pages, through the back-forward cache")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    session.set_auto_refresh(true);
+
+    session.navigate_smart(&server.url("/history")).await?;
+    session.navigate_smart(&server.url("/history/next")).await?;
+
+    let mut failures = Vec::new();
+    println!("🔙 back()");
+    let back = session.back().await?;
+    failures.extend(check(&session, &back, "/history").await?);
+    if back.is_bfcache_restore() {
+        let state = session.get_page_state(false).await?;
+        if !state.elements.iter().any(|element| {
+            element.element_id.as_deref() == Some("restored-action") && element.is_visible
+        }) {
+            failures.push("the button shown on restore was not extracted".to_string());
+        }
+    }
+
+    println!("🔜 forward()");
+    let forward = session.forward().await?;
+    failures.extend(check(&session, &forward, "/history/next").await?);
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Back and forward completed without waiting for load events");
+    Ok(())
+}
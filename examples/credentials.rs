@@ -0,0 +1,119 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::utils::{
+    CredentialChain, CredentialKey, CredentialProvider, CredentialPurpose, EnvVarProvider,
+    JsonFileProvider,
+};
+use surfai::{BrowserSession, Config, LoginConfig, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Credentials")
+        .version("1.0")
+        .about("Logs into the fixture site with credentials from environment variables")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let host = url::Url::parse(&server.url("/"))?
+        .host_str()
+        .unwrap_or_default()
+        .to_string();
+    let mut failures = Vec::new();
+
+    // A deployment would set these outside the process
+    let env = EnvVarProvider::new();
+    let (username_var, password_var) = env.variable_names(&host, &CredentialPurpose::Login);
+    std::env::set_var(&username_var, "alice");
+    std::env::set_var(&password_var, "correct horse battery staple");
+    println!("🔑 Credentials in {} and {}", username_var, password_var);
+
+    let credential = env.get(&host, &CredentialPurpose::Login).await?;
+    let rendered = format!("{:?}", credential);
+    if rendered.contains("correct horse") {
+        failures.push(format!("password shows up in Debug output: {}", rendered));
+    }
+
+    // The first provider holding the host wins
+    let file = std::env::temp_dir().join(format!("surfai-credentials-{}.json", std::process::id()));
+    let key = CredentialKey::generate();
+    let provider = JsonFileProvider::new(&file, key);
+    provider
+        .write(
+            serde_json::json!([
+                { "domain": host, "username": "bob", "password": "from-file" }
+            ])
+            .to_string()
+            .as_bytes(),
+        )
+        .await?;
+    if std::fs::read_to_string(&file)?.contains("from-file") {
+        failures.push("the credentials file holds the password in plaintext".to_string());
+    }
+    let mut chain = CredentialChain::new();
+    chain.push(provider);
+    chain.push(EnvVarProvider::new());
+    let first = chain.get(&host, &CredentialPurpose::Login).await;
+    let _ = std::fs::remove_file(&file);
+    match first {
+        Some(credential) if credential.username == "bob" => {}
+        other => failures.push(format!("expected the file's credential, got {:?}", other)),
+    }
+    let api = chain
+        .get(&host, &CredentialPurpose::Named("api".to_string()))
+        .await;
+    if api.is_some() {
+        failures.push("found a credential for a purpose nobody holds".to_string());
+    }
+
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config)
+        .await?
+        .with_credential_provider(EnvVarProvider::new());
+
+    session
+        .auto_login_with_credentials(&server.url("/login"), LoginConfig::default())
+        .await?;
+    let url = session.current_url().await?;
+    println!("🔐 Logged in, now at {}", url);
+    if !url.ends_with("/dashboard") {
+        failures.push(format!("login ended at {}", url));
+    }
+
+    std::env::remove_var(&username_var);
+    std::env::remove_var(&password_var);
+    match session
+        .auto_login_with_credentials(&server.url("/login"), LoginConfig::default())
+        .await
+    {
+        Err(surfai::BrowserAgentError::ConfigurationError(message)) => {
+            println!("🚫 Without credentials: {}", message)
+        }
+        other => failures.push(format!(
+            "login without credentials returned {:?}",
+            other.map(|_| ())
+        )),
+    }
+
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!("✅ Credentials came from the providers");
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,88 @@
+use clap::{Arg, Command};
+use surfai::browser::{FetchOptions, SubmitOutcome};
+use surfai::core::config::{CsrfSource, HeadlessMode};
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("CSRF")
+        .version("1.0")
+        .about("Passes a double-submit-cookie CSRF check with fetch and a form submit")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    let mut failures = Vec::new();
+
+    session.navigate_smart(&server.url("/csrf")).await?;
+    let token = session.csrf().current();
+    println!("🔑 Token after navigation: {:?}", token);
+    match &token {
+        Some(token) if token.source == CsrfSource::Cookie && token.header == "X-XSRF-TOKEN" => {}
+        other => failures.push(format!("expected the XSRF-TOKEN cookie, found {:?}", other)),
+    }
+
+    let payload = serde_json::json!({ "amount": 10 });
+    let response = session
+        .fetch("/csrf", FetchOptions::post_json(&payload))
+        .await?;
+    println!("📮 POST with the token: {}", response.status);
+    if response.status != 200 {
+        failures.push(format!("protected POST returned {}", response.status));
+    }
+
+    let response = session
+        .fetch("/csrf", FetchOptions::post_json(&payload).without_csrf())
+        .await?;
+    println!("📮 POST without the token: {}", response.status);
+    if response.status != 403 {
+        failures.push(format!("unprotected POST returned {}", response.status));
+    }
+
+    // The page rotates the cookie; the next use reads the new value
+    session.click("#rotate").await?;
+    let response = session
+        .fetch("/csrf", FetchOptions::post_json(&payload))
+        .await?;
+    println!("🔄 POST after rotation: {}", response.status);
+    if response.status != 200 {
+        failures.push(format!("POST after rotation returned {}", response.status));
+    }
+    if session.csrf().current().map(|token| token.value) == token.map(|token| token.value) {
+        failures.push("rotated token was not picked up".to_string());
+    }
+
+    // The form's hidden field still holds the old token until the submit refreshes it
+    let result = session.submit_and_wait("#transfer").await?;
+    let text = session
+        .execute_script("document.getElementById('csrf-result')?.textContent || ''")
+        .await?;
+    println!("📝 Form submit: {:?} {}", result.outcome, text);
+    if !matches!(result.outcome, SubmitOutcome::Navigated { .. }) || text != "Accepted" {
+        failures.push(format!("form submit ended with {}", text));
+    }
+
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!("✅ CSRF-protected requests went through");
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
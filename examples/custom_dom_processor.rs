@@ -0,0 +1,116 @@
+//! Replace the built-in DOM extraction with one that only reports form fields.
+//!
+//! Run with `cargo run --example custom_dom_processor`.
+
+use async_trait::async_trait;
+use surfai::core::config::HeadlessMode;
+use surfai::core::{ElementFilter, SelectorType};
+use surfai::dom::xpath::xpath_literal;
+use surfai::{
+    BrowserSession, BrowserTrait, Config, DomElement, DomProcessorTrait, DomState, Result,
+    SessionTrait,
+};
+
+/// Reports the inputs, selects and textareas of the page and nothing else
+struct FormFieldProcessor;
+
+#[async_trait]
+impl DomProcessorTrait for FormFieldProcessor {
+    async fn extract_dom_state<B: BrowserTrait>(
+        &self,
+        browser: &B,
+        tab: &B::TabHandle,
+        _include_screenshot: bool,
+    ) -> Result<DomState> {
+        let url = browser.get_url(tab).await?;
+        let title = browser.get_title(tab).await?;
+        let fields = browser
+            .execute_script(
+                tab,
+                r#"
+                Array.from(document.querySelectorAll('input, select, textarea')).map(field => ({
+                    tag: field.tagName.toLowerCase(),
+                    name: field.name || null,
+                    type: field.type || null,
+                    id: field.id || null
+                }))
+                "#,
+            )
+            .await?;
+
+        let mut state = DomState::new(url, title);
+        for (i, field) in fields.as_array().into_iter().flatten().enumerate() {
+            let text = |key: &str| field.get(key).and_then(|v| v.as_str()).map(str::to_string);
+            let tag = text("tag").unwrap_or_else(|| "input".to_string());
+            let mut element = DomElement::new(tag.clone(), format!("field_{}", i + 1))
+                .set_clickable(true)
+                .set_interactable(true);
+            element.element_id = text("id");
+            if let Some(name) = text("name") {
+                element.css_selector = format!("{}[name=\"{}\"]", tag, name);
+                element.xpath = format!("//{}[@name={}]", tag, xpath_literal(&name));
+                element = element.with_attribute("name".to_string(), name);
+            }
+            if let Some(kind) = text("type") {
+                element = element.with_attribute("type".to_string(), kind);
+            }
+            if let Some(id) = &element.element_id {
+                element.css_selector = format!("#{}", id);
+            }
+            element.generate_ai_label();
+            state.add_element(element);
+        }
+        Ok(state)
+    }
+
+    async fn extract_interactive_elements<B: BrowserTrait>(
+        &self,
+        browser: &B,
+        tab: &B::TabHandle,
+    ) -> Result<Vec<DomElement>> {
+        Ok(self.extract_dom_state(browser, tab, false).await?.elements)
+    }
+
+    async fn add_ai_labels(&self, elements: &mut Vec<DomElement>) -> Result<()> {
+        elements.iter_mut().for_each(DomElement::generate_ai_label);
+        Ok(())
+    }
+
+    fn filter_elements(
+        &self,
+        elements: &[DomElement],
+        criteria: &ElementFilter,
+    ) -> Vec<DomElement> {
+        elements
+            .iter()
+            .filter(|element| criteria.matches(element))
+            .cloned()
+            .collect()
+    }
+
+    fn generate_selector(&self, element: &DomElement, selector_type: SelectorType) -> String {
+        match selector_type {
+            SelectorType::XPath => element.xpath.clone(),
+            _ => element.css_selector.clone(),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let mut config = Config::default();
+    config.browser.headless = HeadlessMode::New;
+
+    let session = BrowserSession::with_config(config)
+        .await?
+        .with_dom_processor(FormFieldProcessor);
+    session.navigate_smart("https://www.google.com").await?;
+
+    // Highlighting and AI element lists now see only the form fields
+    for element in session.get_ai_elements().await? {
+        println!("{:>3}. {}", element.element_number, element.description);
+    }
+
+    session.close().await?;
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+/// Adds a list item every 20ms and flips a button's visibility every 100ms
+const TICKER_JS: &str = r#"
+    (function() {
+        const list = document.createElement('ul');
+        document.body.appendChild(list);
+        const button = document.createElement('button');
+        button.textContent = 'Buy';
+        document.body.appendChild(button);
+        let ticks = 0;
+        window.ticker = setInterval(() => {
+            const item = document.createElement('li');
+            item.textContent = 'Price ' + Math.random();
+            list.appendChild(item);
+            if (++ticks % 5 === 0) button.hidden = !button.hidden;
+        }, 20);
+        return true;
+    })()
+"#;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("DOM Activity")
+        .version("1.0")
+        .about("Records DOM change history on a page that keeps mutating")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+
+    let mut failures = Vec::new();
+    session.navigate_smart(&server.url("/dashboard")).await?;
+    let quiet = session.dom_activity().await?;
+    if quiet.heavy {
+        failures.push("a static page counted as mutating heavily".to_string());
+    }
+
+    session.execute_script(TICKER_JS).await?;
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    let busy = session.analyze_page().await?;
+    let activity = busy.dom_activity.clone().unwrap_or_default();
+    println!(
+        "📈 {} mutations, {} visibility changes in the last {}ms",
+        activity.mutations, activity.visibility_changes, activity.window_ms
+    );
+    match activity.hint() {
+        Some(hint) => println!("💡 {}", hint),
+        None => failures.push("the ticking page was not reported as busy".to_string()),
+    }
+
+    // Highlight refreshes consume pending changes; the history has to outlive them
+    session.set_auto_refresh(true);
+    session.highlight_interactive_elements().await?;
+    session
+        .execute_script("clearInterval(window.ticker); true")
+        .await?;
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    session.dom_activity().await?;
+    let history = session.dom_change_history();
+    let stats = session.dom_change_stats();
+    println!(
+        "🗂️ {} change events, {} mutations in total",
+        history.len(),
+        stats.total_mutations
+    );
+    if history.is_empty() || stats.total_mutations < activity.mutations {
+        failures.push("the change history lost events".to_string());
+    }
+    if stats.visibility_changes == 0 {
+        failures.push("visibility changes were not counted".to_string());
+    }
+
+    session.reset_dom_change_stats();
+    if !session.dom_change_history().is_empty() || session.dom_change_stats().total_mutations != 0 {
+        failures.push("reset_dom_change_stats kept counters".to_string());
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ DOM change history and activity summaries work");
+    Ok(())
+}
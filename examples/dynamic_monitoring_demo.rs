@@ -1,5 +1,7 @@
 use clap::{Arg, Command};
-use surfai::{BrowserSession, SessionTrait};
+use std::path::Path;
+use surfai::core::config::HeadlessMode;
+use surfai::{BrowserSession, Config, SessionTrait};
 use tokio::time::{sleep, Duration};
 
 #[tokio::main]
@@ -20,11 +22,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("⚡ Dynamic Navigation Showcase - Truly Responsive Detection");
     println!("🔧 Headless: {}", headless);
 
-    let mut session = if headless {
-        BrowserSession::quick_start().await?
-    } else {
-        BrowserSession::demo_mode().await?
-    };
+    // Defaults <- surfai.toml <- SURFAI_* environment <- command line
+    let mut config = Config::load(Some(Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/surfai.toml"
+    ))))?;
+    if headless {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+
+    let session = BrowserSession::with_config(config).await?;
+    session.set_auto_refresh(!headless);
     let test_sites = vec![
         ("https://www.google.com", "Google (Fast Loading)"),
         ("https://www.github.com", "GitHub (Medium Loading)"),
@@ -55,7 +64,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("   🎉 COMPLETE LOAD detected!");
                 }
 
-                let element_count = session.get_highlighted_elements().len();
+                let element_count = session.get_highlighted_elements().await.len();
                 println!("   🎯 Interactive Elements: {}", element_count);
                 let efficiency = if result.actual_load_time > 0 {
                     (result.actual_load_time as f64 / total_time as f64) * 100.0
@@ -63,6 +72,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     100.0
                 };
                 println!("   📈 Detection Efficiency: {:.1}%", efficiency);
+
+                match session.analyze_page().await {
+                    Ok(report) => {
+                        println!(
+                            "   📊 {} elements ({} clickable, {} inputs), {} links, {} forms",
+                            report.stats.total_elements,
+                            report.stats.clickable_elements,
+                            report.stats.input_elements,
+                            report.stats.links,
+                            report.stats.forms
+                        );
+                        println!(
+                            "   📝 {} words, language {}",
+                            report.word_count,
+                            report.language.as_deref().unwrap_or("unknown")
+                        );
+                        if let Some(fcp) = report.performance.first_contentful_paint_ms {
+                            println!("   🎨 First contentful paint: {:.0}ms", fcp);
+                        }
+                    }
+                    Err(e) => println!("   ⚠️ Page analysis failed: {}", e),
+                }
             }
             Err(e) => {
                 let total_time = start_time.elapsed().as_millis();
@@ -0,0 +1,147 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::dom::STAMP_ATTRIBUTE;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, ChromeBrowser, Config, SessionTrait};
+
+/// Product name in the row of the only element `selector` matches, or `None`
+/// when it matches none or several
+async fn product_at(
+    session: &BrowserSession<ChromeBrowser>,
+    selector: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let name = session
+        .execute_script(&format!(
+            r#"
+            (function() {{
+                const matches = document.querySelectorAll({});
+                if (matches.length !== 1) return null;
+                return matches[0].parentElement.firstChild.textContent.trim();
+            }})()
+            "#,
+            serde_json::to_string(selector)?
+        ))
+        .await?;
+    Ok(name.as_str().map(str::to_string))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Element Stamps")
+        .version("1.0")
+        .about("Keeps selectors pointing at the same elements after the page reorders them")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.dom.stamp_elements = true;
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+
+    let mut failures = Vec::new();
+    session.navigate_smart(&server.url("/sortable")).await?;
+    session.dom_activity().await?;
+    session.reset_dom_change_stats();
+    let state = session.get_page_state(false).await?;
+    if session.dom_activity().await?.mutations > 0 {
+        failures.push("stamping showed up as DOM changes".to_string());
+    }
+
+    let buttons: Vec<String> = state
+        .elements
+        .iter()
+        .filter(|e| e.tag_name == "button" && e.text_content.as_deref() == Some("Add to cart"))
+        .map(|e| e.css_selector.clone())
+        .collect();
+    let mut products = Vec::new();
+    for selector in &buttons {
+        println!("🏷️ {}", selector);
+        if !selector.contains(STAMP_ATTRIBUTE) {
+            failures.push(format!("{} is not stamped", selector));
+        }
+        products.push(product_at(&session, selector).await?);
+    }
+    if buttons.len() != 3 || products.iter().any(Option::is_none) {
+        failures.push(format!(
+            "expected 3 unique add-to-cart selectors, got {:?}",
+            products
+        ));
+    }
+    let subscribe = state
+        .elements
+        .iter()
+        .find(|e| e.text_content.as_deref() == Some("Subscribe"));
+    if subscribe.is_none_or(|e| e.css_selector.contains(STAMP_ATTRIBUTE)) {
+        failures.push("an element inside the form was stamped".to_string());
+    }
+
+    session.click("#sort").await?;
+    for (selector, product) in buttons.iter().zip(&products) {
+        let now = product_at(&session, selector).await?;
+        if &now != product {
+            failures.push(format!(
+                "{} moved from {:?} to {:?} after sorting",
+                selector, product, now
+            ));
+        }
+    }
+
+    let again = session.get_page_state(false).await?;
+    let restamped: Vec<String> = again
+        .elements
+        .iter()
+        .filter(|e| e.text_content.as_deref() == Some("Add to cart"))
+        .map(|e| e.css_selector.clone())
+        .collect();
+    if restamped.iter().any(|selector| !buttons.contains(selector)) {
+        failures.push("extracting again changed the stamps".to_string());
+    }
+    if let Some(mug) = buttons
+        .iter()
+        .zip(&products)
+        .find(|(_, product)| product.as_deref() == Some("Mug"))
+    {
+        session.click(mug.0.as_str()).await?;
+        let cart = session
+            .execute_script("document.getElementById('cart').textContent")
+            .await?;
+        println!("🛒 Cart: {}", cart.as_str().unwrap_or_default());
+        if !cart.as_str().unwrap_or_default().contains("Mug") {
+            failures.push("the stamped selector clicked the wrong row".to_string());
+        }
+    }
+
+    let removed = session.clear_element_stamps().await?;
+    println!("🧹 Removed {} stamps", removed);
+    if removed < buttons.len() {
+        failures.push(format!("only {} stamps were removed", removed));
+    }
+    let left = session
+        .execute_script(&format!(
+            "document.querySelectorAll('[{}]').length",
+            STAMP_ATTRIBUTE
+        ))
+        .await?;
+    if left.as_u64() != Some(0) {
+        failures.push(format!("{} stamps survived clearing", left));
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Stamped selectors survive reordering and clear cleanly");
+    Ok(())
+}
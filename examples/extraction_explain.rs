@@ -0,0 +1,69 @@
+use clap::{Arg, Command};
+use std::time::{Duration, Instant};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+const PAGES: &[&str] = &["/login", "/menus", "/links", "/countries", "/graphics"];
+
+fn average(samples: &[Duration]) -> Duration {
+    samples.iter().sum::<Duration>() / samples.len().max(1) as u32
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Extraction Explain")
+        .version("1.0")
+        .about("Times page extraction with and without explaining each element")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("rounds")
+                .long("rounds")
+                .help("How many times to extract every page per mode")
+                .default_value("5"),
+        )
+        .get_matches();
+
+    let rounds: usize = matches.get_one::<String>("rounds").unwrap().parse()?;
+    let server = FixtureServer::start().await?;
+    let mut timings = Vec::new();
+    for explain in [false, true] {
+        let mut config = Config::default();
+        if matches.get_flag("headless") {
+            config.browser.headless = HeadlessMode::New;
+        }
+        config.dom.explain = explain;
+        config.dom.collect_stats = explain;
+        config.validate()?;
+        let session = BrowserSession::with_config(config).await?;
+
+        let mut samples = Vec::new();
+        for page in PAGES {
+            session.navigate_smart(&server.url(page)).await?;
+            for _ in 0..rounds {
+                let started = Instant::now();
+                session.get_page_state(false).await?;
+                samples.push(started.elapsed());
+            }
+        }
+        println!(
+            "⏱️ explain {}: {:?} per extraction",
+            if explain { "on" } else { "off" },
+            average(&samples)
+        );
+        timings.push(average(&samples));
+        session.close().await?;
+    }
+
+    println!(
+        "📊 Explaining costs {:+.1}%",
+        (timings[1].as_secs_f64() / timings[0].as_secs_f64().max(f64::EPSILON) - 1.0) * 100.0
+    );
+
+    Ok(())
+}
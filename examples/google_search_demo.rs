@@ -1,6 +1,8 @@
 use clap::{Arg, Command};
 use std::io::{self, Write};
-use surfai::{BrowserSession, SessionTrait};
+use std::path::Path;
+use surfai::core::config::HeadlessMode;
+use surfai::{BrowserSession, Config, SessionTrait};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -19,11 +21,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("🚀 Smart Google Search Demo - STATIC AI Analysis");
 
-    let mut session = if headless {
-        BrowserSession::quick_start().await?
-    } else {
-        BrowserSession::demo_mode().await?
-    };
+    // Defaults <- surfai.toml <- SURFAI_* environment <- command line
+    let mut config = Config::load(Some(Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/surfai.toml"
+    ))))?;
+    if headless {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+
+    let mut session = BrowserSession::with_config(config).await?;
+    session.set_auto_refresh(!headless);
 
     let nav_result = session.navigate_smart("https://www.google.com").await?;
     println!("✅ Google loaded in {}ms", nav_result.duration_ms);
@@ -0,0 +1,176 @@
+use clap::{Arg, Command};
+use serde_json::Value;
+use std::net::SocketAddr;
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Status code and body of `method path`, sending `headers` as given
+async fn request(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    headers: &str,
+) -> std::io::Result<(u16, String)> {
+    let mut stream = TcpStream::connect(addr).await?;
+    stream
+        .write_all(
+            format!(
+                "{} {} HTTP/1.1\r\nHost: {}\r\n{}Connection: close\r\n\r\n",
+                method, path, addr, headers
+            )
+            .as_bytes(),
+        )
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    let status = response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .unwrap_or(0);
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body.to_string())
+        .unwrap_or_default();
+    Ok((status, body))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Inspector")
+        .version("1.0")
+        .about("Serves a fixture page's elements, highlights, screenshot and events to the inspector endpoints")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("wait")
+                .long("wait")
+                .help("Keep serving until Ctrl-C, to open the inspector page")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    let mut failures = Vec::new();
+
+    let endpoint = session.enable_inspector(0).await?;
+    println!("🔭 Inspector page: {}", endpoint.page_url());
+    let login = server.url("/login");
+    session.navigate_smart(&login).await?;
+    let elements = session.get_ai_elements().await?;
+    let highlights = session.highlight_interactive_elements().await?;
+    session.screenshot().await?;
+
+    let get = |path: &str| {
+        let path = format!("/{}?token={}", path.trim_start_matches('/'), endpoint.token);
+        async move {
+            let (status, body) = request(endpoint.addr, "GET", &path, "").await?;
+            if status != 200 {
+                return Err(format!("GET {} answered {}", path, status).into());
+            }
+            Ok::<Value, Box<dyn std::error::Error>>(serde_json::from_str(&body)?)
+        }
+    };
+
+    let observation = get("/api/observation").await?;
+    if observation["url"] != login.as_str() {
+        failures.push(format!("observation is of {}", observation["url"]));
+    }
+    let observed = observation["elements"].as_array().map_or(0, Vec::len);
+    if observed != elements.len() || observed == 0 {
+        failures.push(format!(
+            "observation has {} elements, get_ai_elements returned {}",
+            observed,
+            elements.len()
+        ));
+    }
+
+    let served = get("/api/highlights").await?;
+    let served = served.as_array().cloned().unwrap_or_default();
+    if served.len() != highlights.len() || served.is_empty() {
+        failures.push(format!(
+            "{} highlights served, {} drawn",
+            served.len(),
+            highlights.len()
+        ));
+    }
+    if served.iter().any(|highlight| highlight["rect"].is_null()) {
+        failures.push("a highlight is served without its rect".to_string());
+    }
+
+    let screenshot = get("/api/screenshot").await?;
+    if !screenshot["png"]
+        .as_str()
+        .is_some_and(|png| png.starts_with("iVBORw0KGgo"))
+    {
+        failures.push("no PNG screenshot served".to_string());
+    }
+
+    let events = get("/api/events").await?;
+    let navigated = events
+        .as_array()
+        .into_iter()
+        .flatten()
+        .any(|event| event["kind"] == "navigated" && event["url"] == login.as_str());
+    if !navigated {
+        failures.push(format!("navigation missing from events: {}", events));
+    }
+
+    let (status, page) = request(
+        endpoint.addr,
+        "GET",
+        &format!("/?token={}", endpoint.token),
+        "",
+    )
+    .await?;
+    if status != 200 || !page.contains("surfai inspector") {
+        failures.push(format!("inspector page answered {}", status));
+    }
+    let (status, _) = request(endpoint.addr, "GET", "/api/observation", "").await?;
+    if status != 401 {
+        failures.push(format!("request without the token answered {}", status));
+    }
+    let bearer = format!("Authorization: Bearer {}\r\n", endpoint.token);
+    let (status, _) = request(endpoint.addr, "GET", "/api/highlights", &bearer).await?;
+    if status != 200 {
+        failures.push(format!("bearer token request answered {}", status));
+    }
+    let (status, _) = request(endpoint.addr, "POST", "/api/observation", &bearer).await?;
+    if status != 405 {
+        failures.push(format!("POST answered {}", status));
+    }
+    println!(
+        "✅ Served {} elements and {} highlights",
+        observed,
+        served.len()
+    );
+
+    if matches.get_flag("wait") {
+        println!("⏸️ Serving until Ctrl-C");
+        tokio::signal::ctrl_c().await?;
+    }
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!("✅ Inspector endpoints serve the session");
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
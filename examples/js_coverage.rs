@@ -0,0 +1,102 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::core::JsCoverageOptions;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("JS Coverage")
+        .version("1.0")
+        .about("Measures how much of the fixture SPA's script runs, and of our own")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+
+    let mut failures = Vec::new();
+    // Same-origin from here on, so the page keeps its renderer and the recording
+    session.navigate_smart(&server.url("/login")).await?;
+    session
+        .start_js_coverage_with_options(JsCoverageOptions::default().with_unused_ranges())
+        .await?;
+    session.navigate_smart(&server.url("/spa")).await?;
+    session.click("a[href='/spa/about']").await?;
+    let coverage = session.stop_js_coverage().await?;
+    println!("📊 {}", coverage);
+    for script in &coverage.scripts {
+        println!(
+            "   {} {}/{} bytes ({:.1}%), {} unused ranges",
+            script.url,
+            script.used_bytes,
+            script.total_bytes,
+            script.percentage(),
+            script.unused_ranges.len()
+        );
+    }
+    match coverage.script("/spa") {
+        Some(bundle) if bundle.used_bytes > 0 && bundle.used_bytes <= bundle.total_bytes => {
+            if bundle.unused_ranges.is_empty() {
+                failures.push("the SPA script reported no unused ranges".to_string());
+            }
+            let unused: usize = bundle.unused_ranges.iter().map(|range| range.len()).sum();
+            if unused != bundle.unused_bytes() {
+                failures.push(format!(
+                    "unused ranges add up to {} bytes, not {}",
+                    unused,
+                    bundle.unused_bytes()
+                ));
+            }
+        }
+        Some(bundle) => failures.push(format!(
+            "the SPA script reported {}/{} bytes used",
+            bundle.used_bytes, bundle.total_bytes
+        )),
+        None => failures.push("no coverage for the SPA script".to_string()),
+    }
+    if coverage.scripts.iter().any(|script| script.url.is_empty()) {
+        failures.push("scripts without a URL were reported by default".to_string());
+    }
+
+    // The session's own footprint: everything it evaluates has no URL
+    session
+        .start_js_coverage_with_options(JsCoverageOptions::default().with_anonymous())
+        .await?;
+    session.analyze_page().await?;
+    let own = session.stop_js_coverage().await?;
+    let injected: Vec<_> = own.scripts.iter().filter(|s| s.url.is_empty()).collect();
+    println!(
+        "🧩 analyze_page evaluated {} scripts, {} bytes, {:.1}% of it run",
+        injected.len(),
+        injected.iter().map(|s| s.total_bytes).sum::<usize>(),
+        own.percentage()
+    );
+    if injected.is_empty() {
+        failures.push("the session's own scripts were not recorded".to_string());
+    }
+
+    if session.stop_js_coverage().await.is_ok() {
+        failures.push("stopping twice did not fail".to_string());
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ JavaScript coverage is recorded per script");
+    Ok(())
+}
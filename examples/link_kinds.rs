@@ -0,0 +1,124 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::dom::{DomState, LinkKind};
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Link Kinds")
+        .version("1.0")
+        .about("Checks links are resolved against <base href> and told apart by kind")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    let mut failures = Vec::new();
+
+    session.navigate_smart(&server.url("/links")).await?;
+    let expected = [
+        ("guide", LinkKind::Internal, Some(server.url("/docs/guide"))),
+        ("about", LinkKind::Internal, Some(server.url("/about"))),
+        (
+            "external",
+            LinkKind::External,
+            Some("https://example.com/pricing".to_string()),
+        ),
+        (
+            "mail",
+            LinkKind::Mailto,
+            Some("mailto:support@example.com?subject=Help".to_string()),
+        ),
+        ("phone", LinkKind::Tel, Some("tel:+15551234567".to_string())),
+        ("script", LinkKind::Javascript, None),
+        (
+            "details",
+            LinkKind::Anchor,
+            Some(server.url("/links#details")),
+        ),
+        // The base sends a bare fragment to another page
+        (
+            "docs-top",
+            LinkKind::Internal,
+            Some(server.url("/docs/#top")),
+        ),
+    ];
+
+    let elements = session.get_ai_elements().await?;
+    for (id, kind, resolved) in &expected {
+        let Some(element) = elements
+            .iter()
+            .find(|element| element.attributes.get("id").map(String::as_str) == Some(*id))
+        else {
+            failures.push(format!("no AI element for #{}", id));
+            continue;
+        };
+        println!(
+            "🔗 #{} → {:?} {:?}: {}",
+            id, element.link_kind, element.resolved_href, element.description
+        );
+        if element.link_kind != Some(*kind) || element.resolved_href != *resolved {
+            failures.push(format!(
+                "#{} is {:?} to {:?}, expected {:?} to {:?}",
+                id, element.link_kind, element.resolved_href, kind, resolved
+            ));
+        }
+    }
+
+    let details = elements
+        .iter()
+        .find(|element| element.attributes.get("id").map(String::as_str) == Some("details"));
+    match details {
+        Some(details) if details.ai_instructions.contains("does not navigate") => {}
+        other => failures.push(format!(
+            "anchor instructions are {:?}",
+            other.map(|details| &details.ai_instructions)
+        )),
+    }
+    let external = elements
+        .iter()
+        .find(|element| element.attributes.get("id").map(String::as_str) == Some("external"));
+    match external {
+        Some(external) if external.description.contains("off-site") => {}
+        other => failures.push(format!(
+            "external link described as {:?}",
+            other.map(|external| &external.description)
+        )),
+    }
+
+    let state = session.get_page_state(false).await?;
+    let decoded = DomState::from_bytes(&state.to_bytes())?;
+    let links = |state: &DomState| {
+        state
+            .elements
+            .iter()
+            .map(|element| (element.resolved_href.clone(), element.link_kind))
+            .collect::<Vec<_>>()
+    };
+    if links(&decoded) != links(&state) {
+        failures.push("links changed in the compact encoding".to_string());
+    }
+
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!("✅ Links were resolved and classified");
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,136 @@
+use clap::{Arg, Command};
+use surfai::browser::MemoryStats;
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+const PAGES: &[&str] = &[
+    "/login",
+    "/dashboard",
+    "/selectors",
+    "/form-labels",
+    "/sortable",
+    "/infinite-scroll",
+    "/wizard",
+    "/dialog",
+];
+
+/// Room for the state of the current page on top of the prefetch budget
+const PER_PAGE_BYTES: usize = 2 * 1024 * 1024;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Memory Soak")
+        .version("1.0")
+        .about("Navigates the fixture pages many times and checks memory stays capped")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("navigations")
+                .long("navigations")
+                .help("Number of navigations")
+                .default_value("200"),
+        )
+        .get_matches();
+    let navigations: usize = matches.get_one::<String>("navigations").unwrap().parse()?;
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.session.change_history_len = 20;
+    config.session.prefetch_limit = 2;
+    config.session.prefetch_max_bytes = 1024 * 1024;
+    config.validate()?;
+    let limits = config.session.clone();
+    let session = BrowserSession::with_config(config).await?;
+
+    let mut failures = Vec::new();
+    let mut peak = MemoryStats::default();
+    for i in 0..navigations {
+        let path = PAGES[i % PAGES.len()];
+        session.navigate_smart(&server.url(path)).await?;
+        match i % 4 {
+            0 => {
+                session.highlight_interactive_elements().await?;
+            }
+            1 => {
+                session.get_ai_elements().await?;
+            }
+            2 => {
+                session
+                    .prefetch(&server.url(PAGES[(i + 1) % PAGES.len()]))
+                    .await?;
+            }
+            _ => {}
+        }
+
+        let stats = session.memory_stats().await;
+        peak.highlights = peak.highlights.max(stats.highlights);
+        peak.change_events = peak.change_events.max(stats.change_events);
+        peak.prefetched_pages = peak.prefetched_pages.max(stats.prefetched_pages);
+        peak.prefetched_bytes = peak.prefetched_bytes.max(stats.prefetched_bytes);
+        peak.approx_bytes = peak.approx_bytes.max(stats.approx_bytes);
+        if (i + 1) % 20 == 0 {
+            println!("🔁 {:>3} navigations: {:?}", i + 1, stats);
+        }
+    }
+
+    println!("📊 Peak: {:?}", peak);
+    if peak.change_events > limits.change_history_len {
+        failures.push(format!(
+            "change history reached {} events, cap {}",
+            peak.change_events, limits.change_history_len
+        ));
+    }
+    if peak.prefetched_pages > limits.prefetch_limit {
+        failures.push(format!(
+            "{} pages prefetched, cap {}",
+            peak.prefetched_pages, limits.prefetch_limit
+        ));
+    }
+    if peak.prefetched_bytes > limits.prefetch_max_bytes {
+        failures.push(format!(
+            "prefetched pages took {} bytes, cap {}",
+            peak.prefetched_bytes, limits.prefetch_max_bytes
+        ));
+    }
+    if peak.approx_bytes > limits.prefetch_max_bytes + PER_PAGE_BYTES {
+        failures.push(format!(
+            "session held {} bytes, cap {}",
+            peak.approx_bytes,
+            limits.prefetch_max_bytes + PER_PAGE_BYTES
+        ));
+    }
+
+    // Numbered elements belong to the page they were listed on
+    session.get_ai_elements().await?;
+    session.navigate_smart(&server.url("/dashboard")).await?;
+    let stats = session.memory_stats().await;
+    if stats.ai_elements != 0 {
+        failures.push(format!(
+            "navigation kept {} AI elements of the previous page",
+            stats.ai_elements
+        ));
+    }
+
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!(
+            "✅ Memory stayed within the caps over {} navigations",
+            navigations
+        );
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use surfai::actions::base::ActionContext;
+use surfai::actions::Action;
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::utils::metrics::{
+    describe_metrics, ACTIONS_TOTAL, ACTIVE_SESSIONS, DOM_EXTRACTION_DURATION_SECONDS,
+    NAVIGATIONS_TOTAL, NAVIGATION_DURATION_SECONDS,
+};
+use surfai::{ActionRegistry, ActionResult, BrowserSession, Config, SessionTrait};
+
+/// Keeps every metric in memory, keyed as `name{label=value,...}`
+#[derive(Default, Clone)]
+struct CaptureRecorder {
+    counters: Arc<Mutex<BTreeMap<String, Arc<AtomicU64>>>>,
+    gauges: Arc<Mutex<BTreeMap<String, Arc<AtomicU64>>>>,
+    histograms: Arc<Mutex<BTreeMap<String, Arc<Samples>>>>,
+}
+
+#[derive(Default)]
+struct Samples(Mutex<Vec<f64>>);
+
+impl metrics::HistogramFn for Samples {
+    fn record(&self, value: f64) {
+        self.0.lock().unwrap().push(value);
+    }
+}
+
+fn render(key: &Key) -> String {
+    let mut labels: Vec<String> = key
+        .labels()
+        .map(|label| format!("{}={}", label.key(), label.value()))
+        .collect();
+    labels.sort();
+    if labels.is_empty() {
+        key.name().to_string()
+    } else {
+        format!("{}{{{}}}", key.name(), labels.join(","))
+    }
+}
+
+impl Recorder for CaptureRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut counters = self.counters.lock().unwrap();
+        Counter::from_arc(counters.entry(render(key)).or_default().clone())
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        let mut gauges = self.gauges.lock().unwrap();
+        Gauge::from_arc(gauges.entry(render(key)).or_default().clone())
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        let mut histograms = self.histograms.lock().unwrap();
+        Histogram::from_arc(histograms.entry(render(key)).or_default().clone())
+    }
+}
+
+impl CaptureRecorder {
+    fn counter(&self, key: &str) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(key)
+            .map_or(0, |value| value.load(Ordering::Relaxed))
+    }
+
+    fn gauge(&self, key: &str) -> f64 {
+        self.gauges
+            .lock()
+            .unwrap()
+            .get(key)
+            .map_or(0.0, |value| f64::from_bits(value.load(Ordering::Relaxed)))
+    }
+
+    fn samples(&self, key: &str) -> usize {
+        self.histograms
+            .lock()
+            .unwrap()
+            .get(key)
+            .map_or(0, |samples| samples.0.lock().unwrap().len())
+    }
+
+    fn print(&self) {
+        for (key, value) in self.counters.lock().unwrap().iter() {
+            println!("   {} {}", key, value.load(Ordering::Relaxed));
+        }
+        for (key, value) in self.gauges.lock().unwrap().iter() {
+            println!(
+                "   {} {}",
+                key,
+                f64::from_bits(value.load(Ordering::Relaxed))
+            );
+        }
+        for (key, samples) in self.histograms.lock().unwrap().iter() {
+            println!("   {} ({} samples)", key, samples.0.lock().unwrap().len());
+        }
+    }
+}
+
+struct EchoAction;
+
+#[async_trait]
+impl Action for EchoAction {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn description(&self) -> &str {
+        "Returns its message"
+    }
+
+    fn parameter_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": { "message": { "type": "string" } },
+            "required": ["message"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        _context: &ActionContext,
+    ) -> surfai::Result<ActionResult> {
+        Ok(ActionResult::success(
+            params["message"].as_str().unwrap_or_default().to_string(),
+        ))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Metrics")
+        .version("1.0")
+        .about("Captures the metrics a session and the action registry emit")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let recorder = CaptureRecorder::default();
+    metrics::set_global_recorder(recorder.clone())?;
+    describe_metrics();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+
+    session.navigate_smart(&server.url("/login")).await?;
+    session.navigate_smart(&server.url("/dashboard")).await?;
+    session.get_page_state(false).await?;
+
+    let mut registry = ActionRegistry::new();
+    registry.register(EchoAction);
+    let context = ActionContext::new("metrics-example".to_string());
+    registry
+        .execute_action("echo", serde_json::json!({ "message": "hi" }), &context)
+        .await?;
+    // Missing the required message, and a name nobody registered
+    let _ = registry
+        .execute_action("echo", serde_json::json!({}), &context)
+        .await;
+    let _ = registry
+        .execute_action("no-such-action", serde_json::json!({}), &context)
+        .await;
+
+    let mut failures = Vec::new();
+    let active = recorder.gauge(ACTIVE_SESSIONS);
+    session.close().await?;
+
+    println!("📈 Captured metrics:");
+    recorder.print();
+
+    let navigations = format!("{}{{outcome=success}}", NAVIGATIONS_TOTAL);
+    if recorder.counter(&navigations) != 2 {
+        failures.push(format!(
+            "{} is {}, expected 2",
+            navigations,
+            recorder.counter(&navigations)
+        ));
+    }
+    let durations = format!("{}{{outcome=success}}", NAVIGATION_DURATION_SECONDS);
+    if recorder.samples(&durations) != 2 {
+        failures.push(format!("{} has no samples for both loads", durations));
+    }
+    if recorder.samples(DOM_EXTRACTION_DURATION_SECONDS) == 0 {
+        failures.push("no DOM extraction was timed".to_string());
+    }
+    for (labels, expected) in [
+        ("action=echo,result=success", 1),
+        ("action=echo,result=error", 1),
+        ("action=unknown,result=error", 1),
+    ] {
+        let key = format!("{}{{{}}}", ACTIONS_TOTAL, labels);
+        if recorder.counter(&key) != expected {
+            failures.push(format!(
+                "{} is {}, expected {}",
+                key,
+                recorder.counter(&key),
+                expected
+            ));
+        }
+    }
+    if active != 1.0 || recorder.gauge(ACTIVE_SESSIONS) != 0.0 {
+        failures.push(format!(
+            "{} went {} -> {}, expected 1 -> 0",
+            ACTIVE_SESSIONS,
+            active,
+            recorder.gauge(ACTIVE_SESSIONS)
+        ));
+    }
+
+    if failures.is_empty() {
+        println!("✅ Navigations, extraction, actions and sessions all recorded");
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
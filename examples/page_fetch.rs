@@ -0,0 +1,116 @@
+use clap::{Arg, Command};
+use surfai::browser::FetchOptions;
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Page Fetch")
+        .version("1.0")
+        .about("Requests the fixture server's echo endpoint from inside the page")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    let mut failures = Vec::new();
+
+    // The dashboard carries a csrf-token meta tag
+    session.navigate_smart(&server.url("/dashboard")).await?;
+
+    println!("🌐 GET /echo?page=1");
+    let response = session
+        .fetch(
+            "/echo?page=1",
+            FetchOptions::get().header("X-Probe", "surfai"),
+        )
+        .await?;
+    let echo: serde_json::Value = response.json()?;
+    println!(
+        "   {} {} {}",
+        response.status,
+        response.url,
+        response.text()
+    );
+    if !response.ok() || echo["method"] != "GET" || echo["query"] != "page=1" {
+        failures.push(format!("GET echoed {}", echo));
+    }
+    if echo["headers"]["x-probe"] != "surfai" {
+        failures.push("custom header was not sent".to_string());
+    }
+    if response.content_type() != Some("application/json") {
+        failures.push(format!("content type is {:?}", response.content_type()));
+    }
+
+    println!("🌐 POST /echo with a JSON body and the page's CSRF token");
+    let payload = serde_json::json!({ "name": "surfai", "items": [1, 2, 3] });
+    let response = session
+        .fetch("/echo", FetchOptions::post_json(&payload).with_csrf())
+        .await?;
+    let echo: serde_json::Value = response.json()?;
+    println!("   {} {}", response.status, response.text());
+    if echo["method"] != "POST" {
+        failures.push(format!("POST echoed method {}", echo["method"]));
+    }
+    let body: serde_json::Value =
+        serde_json::from_str(echo["body"].as_str().unwrap_or_default()).unwrap_or_default();
+    if body != payload {
+        failures.push(format!("POST body arrived as {}", echo["body"]));
+    }
+    if echo["headers"]["x-csrf-token"] != "fixture-csrf-token" {
+        failures.push(format!(
+            "CSRF header arrived as {}",
+            echo["headers"]["x-csrf-token"]
+        ));
+    }
+
+    println!("✂️  GET /echo capped at 16 bytes");
+    let response = session
+        .fetch("/echo", FetchOptions::get().with_max_body_bytes(16))
+        .await?;
+    println!(
+        "   {} bytes, truncated: {}",
+        response.bytes().len(),
+        response.truncated
+    );
+    if !response.truncated || response.bytes().len() != 16 {
+        failures.push(format!(
+            "capped body has {} bytes, truncated {}",
+            response.bytes().len(),
+            response.truncated
+        ));
+    }
+    if response.json::<serde_json::Value>().is_ok() {
+        failures.push("truncated body parsed as JSON".to_string());
+    }
+
+    println!("🔍 GET a missing page");
+    let response = session.fetch("/no-such-page", FetchOptions::get()).await?;
+    println!("   {} {}", response.status, response.status_text);
+    if response.status != 404 || response.ok() {
+        failures.push(format!("missing page returned {}", response.status));
+    }
+
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!("✅ Page fetches behaved as expected");
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,112 @@
+use clap::{Arg, Command};
+use std::time::{Duration, Instant};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+/// Pages visited in order; each visit is one observe step of an agent
+const PAGES: &[&str] = &[
+    "/login",
+    "/form-labels",
+    "/type-modes",
+    "/sticky-header",
+    "/spa",
+];
+
+fn average(samples: &[Duration]) -> Duration {
+    samples.iter().sum::<Duration>() / samples.len().max(1) as u32
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Prefetch Latency")
+        .version("1.0")
+        .about("Compares observe latency with and without prefetching the next page")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("rounds")
+                .long("rounds")
+                .help("How many times to visit every page")
+                .default_value("3"),
+        )
+        .get_matches();
+
+    let rounds: usize = matches.get_one::<String>("rounds").unwrap().parse()?;
+    let server = FixtureServer::start().await?;
+
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    session.set_auto_refresh(false);
+
+    println!("⏱️ Cold: navigate_smart + get_page_state");
+    let mut cold = Vec::new();
+    for _ in 0..rounds {
+        for page in PAGES {
+            let started = Instant::now();
+            session.navigate_smart(&server.url(page)).await?;
+            let state = session.get_page_state(false).await?;
+            cold.push(started.elapsed());
+            println!(
+                "   {} → {} elements in {:?}",
+                page,
+                state.elements.len(),
+                started.elapsed()
+            );
+        }
+    }
+
+    println!("⏱️ Warm: activate_prefetched, the next page prefetched while the agent thinks");
+    let mut warm = Vec::new();
+    for _ in 0..rounds {
+        session.prefetch(&server.url(PAGES[0])).await?;
+        for (i, page) in PAGES.iter().enumerate() {
+            let url = server.url(page);
+            let started = Instant::now();
+            let state = match session.activate_prefetched(&url).await? {
+                Some(state) => state,
+                None => {
+                    session.navigate_smart(&url).await?;
+                    session.get_page_state(false).await?
+                }
+            };
+            warm.push(started.elapsed());
+            println!(
+                "   {} → {} elements in {:?}",
+                page,
+                state.elements.len(),
+                started.elapsed()
+            );
+
+            // Stands in for the time spent deciding what to do on this page
+            if let Some(next) = PAGES.get(i + 1) {
+                session.prefetch(&server.url(next)).await?;
+            }
+        }
+    }
+
+    let (cold, warm) = (average(&cold), average(&warm));
+    println!(
+        "\n📊 Average observe latency over {} visits",
+        rounds * PAGES.len()
+    );
+    println!("   Cold: {:?}", cold);
+    println!("   Warm: {:?}", warm);
+    if warm < cold {
+        println!(
+            "   ⚡ {:.1}x faster with prefetching",
+            cold.as_secs_f64() / warm.as_secs_f64().max(f64::EPSILON)
+        );
+    }
+
+    session.close().await?;
+    Ok(())
+}
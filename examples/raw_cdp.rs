@@ -0,0 +1,77 @@
+use clap::{Arg, Command};
+use serde_json::json;
+use std::time::Duration;
+use surfai::core::config::HeadlessMode;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Raw CDP")
+        .version("1.0")
+        .about("Calls protocol methods the crate does not wrap")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+
+    let mut failures = Vec::new();
+
+    let version = session
+        .call_browser_method_raw("Browser.getVersion", json!({}))
+        .await?;
+    let product = version["product"].as_str().unwrap_or_default().to_string();
+    println!("🔧 Browser.getVersion → {}", product);
+    if !(product.starts_with("Chrome/") || product.starts_with("HeadlessChrome/")) {
+        failures.push(format!("unexpected product string '{}'", product));
+    }
+
+    // Not wrapped by the crate: report the user as idle with the screen locked
+    session
+        .call_method_raw(
+            "Emulation.setIdleOverride",
+            json!({ "isUserActive": false, "isScreenUnlocked": false }),
+        )
+        .await?;
+    println!("🔧 Emulation.setIdleOverride accepted");
+
+    let mut events = session.subscribe_raw("Page.").await?;
+    session.call_method_raw("Page.enable", json!({})).await?;
+    session
+        .call_method_raw(
+            "Page.navigate",
+            json!({ "url": "data:text/html,<p>raw</p>" }),
+        )
+        .await?;
+    match events.next_timeout(Duration::from_secs(5)).await {
+        Some(event) => println!("📨 First Page event: {}", event.method),
+        None => failures.push("no Page event after Page.navigate".to_string()),
+    }
+
+    match session
+        .call_method_raw("Emulation.noSuchMethod", json!({}))
+        .await
+    {
+        Ok(_) => failures.push("an unknown method succeeded".to_string()),
+        Err(e) => println!("🔧 Unknown method rejected: {}", e),
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Raw protocol calls and events work");
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use clap::{Arg, Command};
+use std::time::{Duration, Instant};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, ChromeBrowser, Config, SessionHandle, SessionTrait};
+
+/// Links of the fixture SPA, clicked in turn
+const LINKS: &[&str] = &[
+    "a[href=\"/spa/about\"]",
+    "a[href=\"/spa/settings\"]",
+    "a[href=\"/spa\"]",
+];
+
+/// Run `count` clicks with refresh, returning the time each took to return
+async fn interact(
+    session: &SessionHandle<ChromeBrowser>,
+    count: usize,
+) -> Result<Vec<Duration>, Box<dyn std::error::Error>> {
+    let mut samples = Vec::new();
+    for i in 0..count {
+        let started = Instant::now();
+        session.click_with_refresh(LINKS[i % LINKS.len()]).await?;
+        samples.push(started.elapsed());
+    }
+    Ok(samples)
+}
+
+fn average(samples: &[Duration]) -> Duration {
+    samples.iter().sum::<Duration>() / samples.len().max(1) as u32
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Refresh Latency")
+        .version("1.0")
+        .about("Compares interaction latency with inline and background highlight refresh")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("interactions")
+                .long("interactions")
+                .help("Clicks per mode")
+                .default_value("20"),
+        )
+        .get_matches();
+
+    let count: usize = matches.get_one::<String>("interactions").unwrap().parse()?;
+    let server = FixtureServer::start().await?;
+
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?.into_handle();
+    session.set_auto_refresh(true);
+    session.navigate_smart(&server.url("/spa")).await?;
+
+    println!("⏱️ Inline: every click waits for the highlights to be redrawn");
+    let inline = interact(&session, count).await?;
+
+    println!("⏱️ Background: clicks return once done, refreshes are debounced");
+    session.start_background_refresh();
+    let started = Instant::now();
+    let background = interact(&session, count).await?;
+    let clicks_done = started.elapsed();
+    session.wait_for_refresh().await?;
+    let settled = started.elapsed();
+    session.stop_background_refresh().await?;
+
+    let elements = session.get_current_interactive_elements().await?;
+
+    println!("\n📊 Average click latency over {} clicks", count);
+    println!("   Inline:     {:?}", average(&inline));
+    println!("   Background: {:?}", average(&background));
+    if average(&background) < average(&inline) {
+        println!(
+            "   ⚡ {:.1}x faster per click",
+            average(&inline).as_secs_f64() / average(&background).as_secs_f64().max(f64::EPSILON)
+        );
+    }
+    println!(
+        "   Background run: clicks done in {:?}, highlights settled after {:?}",
+        clicks_done, settled
+    );
+
+    session.close().await?;
+    if elements.is_empty() {
+        println!("❌ No interactive elements after the final refresh");
+        std::process::exit(1);
+    }
+    println!(
+        "✅ {} elements numbered after wait_for_refresh",
+        elements.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::replay::{ReplayRun, ReportBuilder, RunBundleOptions, RunRecorder};
+use surfai::testing::{expect_element, expect_title, Expectations, FixtureServer};
+use surfai::tools::{run_journey_recorded, Journey};
+use surfai::{BrowserSession, Config, LocalDirSink, SessionTrait};
+
+const SIGN_IN: &str = r##"
+name: Sign in and sign out
+steps:
+  - navigate: /login
+  - fill: { label: Username, value: alice }
+  - fill: { label: Password, value: correct-horse }
+  - click: { css: "#login-button" }
+  - assert: { url_contains: /dashboard }
+  - click: { text: Sign out }
+"##;
+
+const SECTIONS: &[&str] = &[
+    "id=\"summary\"",
+    "id=\"timeline\"",
+    "id=\"navigation\"",
+    "id=\"failures\"",
+    "id=\"network\"",
+    "id=\"page\"",
+];
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Report")
+        .version("1.0")
+        .about("Runs the fixture sign-in and writes a standalone HTML report of it")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let out = std::env::temp_dir().join("surfai-report");
+    let session = BrowserSession::with_config(config)
+        .await?
+        .with_artifact_sink(LocalDirSink::new(&out));
+
+    let bundle = out.join("run");
+    let journey = Journey::from_yaml_str(SIGN_IN)?.with_base_url(&server.url("/"));
+    let recorder = RunRecorder::create(&bundle, &journey.name, RunBundleOptions::default())?;
+    run_journey_recorded(&session, &journey, &recorder).await?;
+    recorder.finish()?;
+
+    let landing = session.navigate_smart(&server.url("/dashboard")).await?;
+    let mut expectations = Expectations::new(&session).capture_failures_to(out.join("failures"));
+    expectations.check(&expect_element("#welcome")).await;
+    // Fails on purpose, so the report has an assertion failure to show
+    expectations.check(&expect_title("Admin console")).await;
+
+    let report = ReportBuilder::new(&journey.name)
+        .with_run(&ReplayRun::open(&bundle)?)?
+        .with_navigation(&landing)
+        .with_failures(expectations.failures())
+        .with_page_report(session.analyze_page().await?);
+    let stored = session.save_report(&report, "report.html").await?;
+
+    let mut failures = Vec::new();
+    let html = std::fs::read_to_string(&stored.location)?;
+    for section in SECTIONS {
+        if !html.contains(section) {
+            failures.push(format!("report has no {} section", section));
+        }
+    }
+    if report.timeline().len() != journey.steps.len() {
+        failures.push(format!(
+            "{} actions in the timeline, expected {}",
+            report.timeline().len(),
+            journey.steps.len()
+        ));
+    }
+    if !html.contains("data:image/png;base64,") {
+        failures.push("no screenshots inlined".to_string());
+    }
+    if !html.contains("Admin console") {
+        failures.push("the failed title assertion is missing".to_string());
+    }
+    // Signing in, signing out and the direct load of the dashboard
+    if report.navigations().len() < 3 {
+        failures.push(format!(
+            "only {} navigations listed",
+            report.navigations().len()
+        ));
+    }
+    if report.network_summary().resource_count.is_none() {
+        failures.push("the network summary has no final page".to_string());
+    }
+
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!("✅ Report written to {}", stored.location);
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
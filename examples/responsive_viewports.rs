@@ -0,0 +1,83 @@
+use clap::{Arg, Command};
+use surfai::core::config::{HeadlessMode, Viewport};
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Responsive Viewports")
+        .version("1.0")
+        .about("Extracts and screenshots a responsive page at phone and desktop sizes")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .help("Directory for the screenshots")
+                .default_value("responsive_screenshots"),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    session.navigate_smart(&server.url("/responsive")).await?;
+
+    let viewports = [Viewport::mobile(390, 844), Viewport::new(1440, 900)];
+    let visibility = session
+        .with_viewports(&viewports, |viewport| {
+            let session = &session;
+            async move {
+                let state = session.get_page_state(false).await?;
+                let shown = |id: &str| {
+                    state
+                        .elements
+                        .iter()
+                        .any(|e| e.element_id.as_deref() == Some(id) && e.is_visible)
+                };
+                println!(
+                    "📐 {}: desktop nav {}, menu button {}",
+                    viewport.label(),
+                    if shown("nav-home") { "shown" } else { "hidden" },
+                    if shown("menu-toggle") {
+                        "shown"
+                    } else {
+                        "hidden"
+                    }
+                );
+                Ok((shown("nav-home"), shown("menu-toggle")))
+            }
+        })
+        .await?;
+
+    // The media query swaps the desktop nav for the menu button below 600px
+    let expected = vec![(false, true), (true, false)];
+    if visibility != expected {
+        session.close().await?;
+        println!(
+            "❌ Expected visibility {:?}, got {:?}",
+            expected, visibility
+        );
+        std::process::exit(1);
+    }
+    println!("✅ is_visible follows the media query at both sizes");
+
+    let output = std::path::PathBuf::from(matches.get_one::<String>("output").unwrap());
+    std::fs::create_dir_all(&output)?;
+    for shot in session.capture_responsive_screenshots(&viewports).await? {
+        let path = output.join(format!("{}.png", shot.label));
+        std::fs::write(&path, &shot.png)?;
+        println!("📸 {} → {}", shot.label, path.display());
+    }
+
+    session.close().await?;
+    Ok(())
+}
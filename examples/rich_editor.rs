@@ -0,0 +1,74 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Rich Editor")
+        .version("1.0")
+        .about("Reads and replaces the content of a model-based rich text editor")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    session.navigate_smart(&server.url("/rich-editor")).await?;
+
+    let content = session.get_editor_content("#editor").await?;
+    println!("📝 Read through {:?}:\n{}\n", content.source, content.text);
+    println!("📝 As markdown:\n{}\n", content.markdown);
+
+    let mut failures = Vec::new();
+    if !content.text.starts_with("Release notes") {
+        failures.push("text does not start with the heading".to_string());
+    }
+    for expected in [
+        "## Release notes",
+        "**faster**",
+        "[docs](/docs)",
+        "- First fix",
+    ] {
+        if !content.markdown.contains(expected) {
+            failures.push(format!("markdown lacks {}", expected));
+        }
+    }
+
+    let typed = session
+        .set_editor_content("#editor", "Rewritten intro\nSecond paragraph")
+        .await?;
+    println!("⌨️ Editor now shows:\n{}\n", typed.after);
+    let model = session
+        .execute_script("document.getElementById('model').textContent")
+        .await?;
+    let model = model.as_str().unwrap_or_default();
+    println!("📦 Editor model: {}", model);
+    for expected in ["Rewritten intro", "Second paragraph"] {
+        if !model.contains(expected) {
+            failures.push(format!("editor model lacks '{}'", expected));
+        }
+    }
+    if model.contains("Release notes") {
+        failures.push("old content is still in the editor model".to_string());
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Editor content read in order and replaced through the model");
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::replay::{ReplayRun, RunBundleOptions, RunRecorder, RUN_BUNDLE_VERSION};
+use surfai::testing::FixtureServer;
+use surfai::tools::{run_journey_recorded, Journey};
+use surfai::{BrowserSession, Config, SessionTrait};
+
+const SIGN_IN: &str = r##"
+name: Recorded sign-in
+steps:
+  - navigate: /login
+  - fill: { label: Username, value: alice }
+  - fill: { label: Password, value: correct-horse }
+  - click: { css: "#login-button" }
+  - assert: { url_contains: /dashboard }
+"##;
+
+const SCREENSHOT_WIDTH: u32 = 400;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Run Replay")
+        .version("1.0")
+        .about("Records the fixture sign-in into a run bundle and steps back through it")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+
+    let dir = std::env::temp_dir().join("surfai-run-replay");
+    let journey = Journey::from_yaml_str(SIGN_IN)?.with_base_url(&server.url("/"));
+    let recorder = RunRecorder::create(
+        &dir,
+        &journey.name,
+        RunBundleOptions::default().with_screenshot_width(SCREENSHOT_WIDTH),
+    )?;
+    let report = run_journey_recorded(&session, &journey, &recorder).await?;
+    recorder.finish()?;
+
+    let mut failures = Vec::new();
+    if !report.passed() {
+        failures.push("the recorded journey failed".to_string());
+    }
+
+    let run = ReplayRun::open(&dir)?;
+    println!("🎞️ Replaying '{}' (format v{})", run.name(), run.version());
+    if run.version() != RUN_BUNDLE_VERSION || !run.finished() {
+        failures.push(format!(
+            "bundle is v{}, finished: {}",
+            run.version(),
+            run.finished()
+        ));
+    }
+    if run.len() != journey.steps.len() {
+        failures.push(format!(
+            "{} steps recorded, expected {}",
+            run.len(),
+            journey.steps.len()
+        ));
+    }
+
+    for step in run.steps() {
+        println!("   {}", step.describe());
+        if let Some(diff) = step.diff()? {
+            if !diff.is_empty() {
+                println!("      {}", diff);
+            }
+        } else {
+            failures.push(format!("step {} has no before/after states", step.index()));
+        }
+        match step.screenshot()? {
+            Some(png) if png_width(&png) <= Some(SCREENSHOT_WIDTH) => {}
+            Some(png) => failures.push(format!(
+                "step {} screenshot is {:?} pixels wide",
+                step.index(),
+                png_width(&png)
+            )),
+            None => failures.push(format!("step {} has no screenshot", step.index())),
+        }
+    }
+
+    // The click is the step that leaves the login page
+    if let Some(click) = run.step(4) {
+        let post = click.post_state()?;
+        if click.command() != "click" || !post.is_some_and(|state| state.url.contains("/dashboard"))
+        {
+            failures.push("step 4 did not record the move to the dashboard".to_string());
+        }
+        if click.diff()?.is_none_or(|diff| diff.is_empty()) {
+            failures.push("signing in changed nothing on the page".to_string());
+        }
+    }
+
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!("✅ Run recorded to {} and replayed", dir.display());
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Width from the PNG header
+fn png_width(png: &[u8]) -> Option<u32> {
+    png.get(16..20)
+        .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
@@ -0,0 +1,93 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, Selector, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Selectors")
+        .version("1.0")
+        .about("Clicks and types through every kind of selector")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    session.navigate_smart(&server.url("/selectors")).await?;
+
+    // Each selector and the id, name or test id its click should log
+    let clicks = [
+        (Selector::from("#css-target"), "css-target"),
+        (Selector::xpath("//button[@name='archive']"), "archive"),
+        (Selector::test_id("save"), "save"),
+        (Selector::text("forgot your password?"), "forgot"),
+        (Selector::text("Delete"), "delete"),
+        (Selector::role_named("button", "close dialog"), "close"),
+        (Selector::role_named("button", "Send"), "send"),
+    ];
+
+    let mut failures = Vec::new();
+    for (selector, expected) in &clicks {
+        session
+            .execute_script("document.getElementById('log').textContent = ''")
+            .await?;
+        if let Err(e) = session.click(selector).await {
+            failures.push(format!("{}: {}", selector, e));
+            continue;
+        }
+        let logged = session
+            .execute_script("document.getElementById('log').textContent.trim()")
+            .await?;
+        let logged = logged.as_str().unwrap_or_default().to_string();
+        println!("🖱️ {} → {}", selector, logged);
+        if logged != *expected {
+            failures.push(format!(
+                "{} clicked '{}', expected '{}'",
+                selector, logged, expected
+            ));
+        }
+    }
+
+    let email = Selector::role_named("textbox", "email address");
+    session.type_text(&email, "ada@example.com").await?;
+    let typed = session
+        .execute_script("document.getElementById('email').value")
+        .await?;
+    println!("⌨️ {} → {}", email, typed);
+    if typed.as_str() != Some("ada@example.com") {
+        failures.push(format!("{} did not receive the text", email));
+    }
+
+    if !session
+        .wait_for_elements(Selector::role("heading"), 1000)
+        .await?
+    {
+        failures.push("no heading found by role".to_string());
+    }
+    if session
+        .wait_for_elements(Selector::text("not on this page"), 500)
+        .await?
+    {
+        failures.push("text that is not on the page was found".to_string());
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Every kind of selector resolved to its element");
+    Ok(())
+}
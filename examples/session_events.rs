@@ -0,0 +1,84 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionEvent, SessionTrait};
+use tokio_stream::StreamExt;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Session Events")
+        .version("1.0")
+        .about("Prints the session event stream while a scripted run drives the fixture pages")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    println!("🧪 Fixture server on {}", server.url("/"));
+
+    let mut config = Config::default();
+    config.features.enable_highlighting = true;
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+
+    let session = BrowserSession::with_config(config).await?;
+
+    // One place to watch everything the session reports
+    let mut events = session.events();
+    let printer = tokio::spawn(async move {
+        while let Some(event) = events.next().await {
+            match event {
+                SessionEvent::Navigated {
+                    url, duration_ms, ..
+                } => println!("📡 navigated to {} in {}ms", url, duration_ms),
+                SessionEvent::DomChanged { change_types, .. } => {
+                    println!("📡 DOM changed: {}", change_types.join(", "))
+                }
+                SessionEvent::ConsoleMessage { message } => {
+                    println!("📡 console {:?}: {}", message.level, message.text)
+                }
+                SessionEvent::HighlightRefreshed { count, .. } => {
+                    println!("📡 {} elements highlighted again", count)
+                }
+                SessionEvent::Lagged { missed, .. } => {
+                    println!("📡 fell behind, {} events dropped", missed)
+                }
+                other => println!("📡 {:?}", other),
+            }
+        }
+    });
+
+    session.navigate_smart(&server.url("/login")).await?;
+    session
+        .execute_script("console.info('showcase: filling the login form')")
+        .await?;
+    session.type_text("#username", "demo").await?;
+    session.type_text("#password", "demo").await?;
+    let submitted = session.submit_and_wait("#login-form").await?;
+    println!(
+        "🔐 Login submitted: {:?} at {}",
+        submitted.outcome, submitted.url
+    );
+
+    session.navigate_smart(&server.url("/spa")).await?;
+    session
+        .click_with_refresh("a[href='/spa/settings']")
+        .await?;
+
+    session
+        .navigate_smart(&server.url("/infinite-scroll"))
+        .await?;
+    session
+        .execute_script("console.warn('showcase: done')")
+        .await?;
+
+    drop(session);
+    printer.await?;
+    Ok(())
+}
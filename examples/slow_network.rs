@@ -0,0 +1,147 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::core::NetworkProfile;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, ChromeBrowser, Config, RetryPolicy, SessionTrait};
+
+/// Milliseconds a fixed amount of page JavaScript takes to run
+async fn busy_loop_ms(
+    session: &BrowserSession<ChromeBrowser>,
+) -> Result<f64, Box<dyn std::error::Error>> {
+    let elapsed = session
+        .execute_script(
+            r#"
+            (function() {
+                const start = performance.now();
+                let x = 0;
+                for (let i = 0; i < 20000000; i++) x = (x + i) % 7919;
+                return performance.now() - start;
+            })()
+            "#,
+        )
+        .await?;
+    Ok(elapsed.as_f64().unwrap_or_default())
+}
+
+/// Navigate under `policy`, as an agent would on a flaky connection, bringing the
+/// network back after the first failure
+async fn navigate_with_retry(
+    session: &BrowserSession<ChromeBrowser>,
+    url: &str,
+    policy: &RetryPolicy,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    for attempt in 1..=policy.max_attempts {
+        match session.navigate_smart(url).await {
+            Ok(result) if !result.url.starts_with("chrome-error:") => return Ok(attempt),
+            Ok(result) => println!("   attempt {} landed on {}", attempt, result.url),
+            Err(e) => println!("   attempt {} failed: {}", attempt, e),
+        }
+        if attempt == 1 {
+            session.emulate_network(NetworkProfile::Slow3g).await?;
+        }
+        tokio::time::sleep(policy.delay_for(attempt)).await;
+    }
+    Err(format!(
+        "{} still failing after {} attempts",
+        url, policy.max_attempts
+    )
+    .into())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Slow Network")
+        .version("1.0")
+        .about("Loads fixture pages on emulated slow-3g and a throttled CPU")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    // Shorter than slow-3g takes to load a page with subresources, so the
+    // detection fallback has to honour it
+    config.session.navigation_timeout_ms = 5000;
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+
+    let mut failures = Vec::new();
+    let fast = session.navigate_smart(&server.url("/login")).await?;
+    println!("⚡ Unthrottled: {}ms", fast.duration_ms);
+    if fast.was_throttled() {
+        failures.push("an unthrottled load reported throttling".to_string());
+    }
+
+    session.emulate_network(NetworkProfile::Slow3g).await?;
+    let started = std::time::Instant::now();
+    let slow = session.navigate_smart(&server.url("/dashboard")).await?;
+    let elapsed = started.elapsed().as_millis();
+    println!(
+        "🐢 slow-3g: {}ms ({}), reason {}",
+        elapsed,
+        slow.load_quality(),
+        slow.reason
+    );
+    if slow.throttling.and_then(|throttling| throttling.network) != Some(NetworkProfile::Slow3g) {
+        failures.push("the slow-3g load did not report its throttling".to_string());
+    }
+    if elapsed < 2000 {
+        failures.push(format!("slow-3g took only {}ms", elapsed));
+    }
+    let timeout = std::time::Duration::from_millis(5000 + 2000);
+    let infinite = tokio::time::timeout(
+        timeout,
+        session.navigate_smart(&server.url("/infinite-scroll")),
+    )
+    .await;
+    match infinite {
+        Ok(Ok(result)) => println!(
+            "⏱️ Slow page settled after {}ms ({})",
+            result.duration_ms, result.reason
+        ),
+        Ok(Err(e)) => println!("⏱️ Slow page failed within the timeout: {}", e),
+        Err(_) => failures.push("navigation overran its timeout under slow-3g".to_string()),
+    }
+
+    session.emulate_network(NetworkProfile::Offline).await?;
+    let policy = RetryPolicy::new(3).with_backoff(500, 2.0);
+    match navigate_with_retry(&session, &server.url("/login"), &policy).await {
+        Ok(attempts) => println!("🔁 Loaded after {} attempts", attempts),
+        Err(e) => failures.push(e.to_string()),
+    }
+
+    let baseline = busy_loop_ms(&session).await?;
+    session.emulate_cpu(4.0).await?;
+    let throttled = busy_loop_ms(&session).await?;
+    println!(
+        "🧮 Busy loop: {:.0}ms at full speed, {:.0}ms at 4x slowdown",
+        baseline, throttled
+    );
+    if throttled < baseline * 2.0 {
+        failures.push("the CPU slowdown had no visible effect".to_string());
+    }
+
+    session.clear_network_emulation().await?;
+    session.emulate_cpu(1.0).await?;
+    let restored = session.navigate_smart(&server.url("/login")).await?;
+    if restored.was_throttled() || session.throttling().is_active() {
+        failures.push("throttling outlived clearing it".to_string());
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Navigation timeouts and retries hold up under slow-3g");
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use clap::{Arg, Command};
+use surfai::core::config::HeadlessMode;
+use surfai::dom::{SnapshotOptions, StructuralSnapshot};
+use surfai::testing::{expect_snapshot, Assertion, FixtureServer};
+use surfai::{BrowserSession, Config, SessionTrait};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Structural Snapshot")
+        .version("1.0")
+        .about("Snapshots the login page structure, then changes the page and shows the diff")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    let path = std::env::temp_dir().join(format!("surfai-login-{}.json", std::process::id()));
+
+    let mut failures = Vec::new();
+    session.navigate_smart(&server.url("/login")).await?;
+    let state = session.get_page_state(false).await?;
+    println!("🧬 Fingerprint {}", state.structural_fingerprint());
+    state.assert_matches_snapshot(&path)?;
+    if let Err(e) = expect_snapshot(&path).assert(&session).await {
+        failures.push(format!("an unchanged page did not match: {}", e));
+    }
+
+    // Digits are ignored, so a counter in a label leaves the structure alone
+    session
+        .execute_script("document.getElementById('login-button').textContent = 'Sign in (3)'")
+        .await?;
+    let relabelled = session.get_page_state(false).await?;
+    if relabelled.structural_fingerprint() != state.structural_fingerprint() {
+        failures.push("a changed counter altered the fingerprint".to_string());
+    }
+
+    session
+        .execute_script(
+            r#"
+            document.getElementById('login-button').textContent = 'Log in';
+            const link = document.createElement('a');
+            link.href = '/reset';
+            link.textContent = 'Forgot password?';
+            document.getElementById('login-form').appendChild(link);
+            document.getElementById('password').remove();
+            "#,
+        )
+        .await?;
+    let changed = session.get_page_state(false).await?;
+    match changed.assert_matches_snapshot(&path) {
+        Ok(()) => failures.push("a changed page matched the snapshot".to_string()),
+        Err(e) => {
+            println!("🔀 {}", e);
+            let current = changed.structural_snapshot(&SnapshotOptions::default())?;
+            let diff = StructuralSnapshot::load(&path)?.diff(&current);
+            if diff.added.len() != 1 || diff.removed.len() != 1 || diff.changed.len() != 1 {
+                failures.push(format!(
+                    "expected one of each change, got {}",
+                    diff.summary()
+                ));
+            }
+        }
+    }
+
+    std::fs::remove_file(&path).ok();
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Structural snapshots ignore volatile text and report real changes");
+    Ok(())
+}
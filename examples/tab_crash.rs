@@ -0,0 +1,143 @@
+use clap::{Arg, Command};
+use std::time::Duration;
+use surfai::core::config::{HeadlessMode, KeepaliveActivity, KeepaliveOptions};
+use surfai::errors::BrowserAgentError;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionEvent, SessionHandle, SessionTrait};
+use tokio_stream::StreamExt;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Tab Crash")
+        .version("1.0")
+        .about("Crashes the tab's renderer and recovers with reload_after_crash")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    // Recovery works on a shared session, with its keepalive running
+    let session = SessionHandle::new(BrowserSession::with_config(config).await?);
+    let observer = session.clone();
+    session
+        .start_keepalive_with(KeepaliveOptions {
+            interval_ms: 1000,
+            activity: KeepaliveActivity::Script(
+                "window.__keepaliveTicks = (window.__keepaliveTicks || 0) + 1".to_string(),
+            ),
+            prevent_freezing: true,
+        })
+        .await?;
+    let mut failures = Vec::new();
+
+    let login = server.url("/login");
+    session.navigate_smart(&login).await?;
+    // sessionStorage belongs to the tab, so only re-injection brings it back
+    session
+        .execute_script("sessionStorage.setItem('marker', 'kept'); true")
+        .await?;
+    session.extract_session(&server.url("/")).await?;
+
+    let mut events = session.events();
+    println!("💥 Crashing the renderer");
+    // The renderer may die before answering
+    let _ = tokio::time::timeout(
+        Duration::from_secs(5),
+        session.call_method_raw("Page.crash", serde_json::json!({})),
+    )
+    .await;
+
+    let mut crashed = None;
+    for _ in 0..50 {
+        match session.execute_script("1").await {
+            Err(BrowserAgentError::TabCrashed(url)) => {
+                crashed = Some(url);
+                break;
+            }
+            _ => tokio::time::sleep(Duration::from_millis(100)).await,
+        }
+    }
+    match &crashed {
+        Some(url) if *url == login => println!("✅ Calls fail with TabCrashed at {}", url),
+        Some(url) => failures.push(format!("crashed at {}, expected {}", url, login)),
+        None => failures.push("the crash was never detected".to_string()),
+    }
+    match session.get_page_state(false).await {
+        Err(BrowserAgentError::TabCrashed(_)) => {}
+        Err(e) => failures.push(format!("get_page_state failed with {} instead", e)),
+        Ok(_) => failures.push("get_page_state succeeded on the crashed tab".to_string()),
+    }
+
+    let mut published = 0;
+    while let Ok(Some(event)) =
+        tokio::time::timeout(Duration::from_millis(200), events.next()).await
+    {
+        if matches!(event, SessionEvent::TabCrashed { .. }) {
+            published += 1;
+        }
+    }
+    if published != 1 {
+        failures.push(format!("TabCrashed published {} times", published));
+    }
+
+    let nav_result = session.reload_after_crash().await?;
+    println!("🩹 Recovered at {}", nav_result.url);
+    if nav_result.url != login {
+        failures.push(format!(
+            "recovered at {}, expected {}",
+            nav_result.url, login
+        ));
+    }
+    let marker = observer
+        .execute_script("sessionStorage.getItem('marker')")
+        .await?;
+    if marker != "kept" {
+        failures.push(format!("session data not injected again: {}", marker));
+    }
+    let state = observer.get_page_state(false).await?;
+    if state.elements.is_empty() {
+        failures.push("no elements extracted after recovering".to_string());
+    }
+
+    // The keepalive carries on in the new tab, which has its document stamp
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    let keepalive = observer
+        .execute_script(
+            "({ ticks: window.__keepaliveTicks || 0, id: window.__surfaiDocumentId || '' })",
+        )
+        .await?;
+    if keepalive["ticks"].as_u64().unwrap_or(0) == 0 {
+        failures.push("keepalive stopped after recovering".to_string());
+    }
+    if !keepalive["id"]
+        .as_str()
+        .is_some_and(|id| id.starts_with(observer.session_id()))
+    {
+        failures.push(format!(
+            "new tab has no keepalive stamp: {}",
+            keepalive["id"]
+        ));
+    }
+
+    session.stop_keepalive();
+    session.close().await?;
+
+    if failures.is_empty() {
+        println!("✅ Recovered from the crash");
+        Ok(())
+    } else {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
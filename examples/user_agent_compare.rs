@@ -0,0 +1,122 @@
+use clap::{Arg, Command};
+use serde_json::Value;
+use std::collections::HashMap;
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, ChromeBrowser, Config, SessionTrait, UserAgentProfile};
+
+const DESKTOP_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/124.0.6367.91 Safari/537.36";
+const MAC_UA: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 \
+                      (KHTML, like Gecko) Chrome/124.0.6367.91 Safari/537.36";
+
+/// Headers the fixture server received for `/headers`, plus what the page reports
+async fn sent_headers(
+    session: &BrowserSession<ChromeBrowser>,
+    server: &FixtureServer,
+) -> Result<(Value, String), Box<dyn std::error::Error>> {
+    session.navigate_smart(&server.url("/headers")).await?;
+    let body = session.execute_script("document.body.innerText").await?;
+    let headers = serde_json::from_str(body.as_str().unwrap_or("{}"))?;
+    let user_agent = session.execute_script("navigator.userAgent").await?;
+    Ok((headers, user_agent.as_str().unwrap_or_default().to_string()))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("User Agent Compare")
+        .version("1.0")
+        .about("Loads a page as the browser and as Googlebot, then checks header overrides")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+    let chrome_version = session
+        .capabilities()
+        .browser_version
+        .as_deref()
+        .and_then(|product| product.split_once('/'))
+        .map(|(_, version)| version.to_string())
+        .unwrap_or_else(|| "124.0.6367.91".to_string());
+
+    let mut failures = Vec::new();
+    let profiles = [
+        UserAgentProfile::browser_default(),
+        UserAgentProfile::from_user_agent("desktop", DESKTOP_UA),
+        UserAgentProfile::googlebot_smartphone(&chrome_version),
+    ];
+    let runs = session
+        .compare_as(&profiles, &server.url("/user-agent"))
+        .await?;
+    for run in &runs {
+        println!(
+            "🔎 {}: {} clickable elements, {} words\n   {}",
+            run.profile, run.report.stats.clickable_elements, run.report.word_count, run.user_agent
+        );
+    }
+    if runs[1].user_agent != DESKTOP_UA {
+        failures.push("the desktop run did not report its user agent".to_string());
+    }
+    if !runs[2].user_agent.contains("Googlebot") {
+        failures.push("the Googlebot run did not report its user agent".to_string());
+    }
+    if runs[1].report.stats.clickable_elements == runs[2].report.stats.clickable_elements {
+        failures.push("the crawler and browser views extracted alike".to_string());
+    }
+
+    // A User-Agent custom header becomes the override, with client hints to match
+    let headers = HashMap::from([
+        ("User-Agent".to_string(), DESKTOP_UA.to_string()),
+        ("X-Variant".to_string(), "b".to_string()),
+    ]);
+    session.set_custom_headers(&headers).await?;
+    let (sent, reported) = sent_headers(&session, &server).await?;
+    println!("📨 With custom headers: {}", sent);
+    if sent["user-agent"] != DESKTOP_UA || reported != DESKTOP_UA {
+        failures.push("the User-Agent header and navigator.userAgent differ".to_string());
+    }
+    if sent["x-variant"] != "b" {
+        failures.push("the custom header was not sent".to_string());
+    }
+    if !sent["sec-ch-ua"]
+        .as_str()
+        .is_some_and(|brands| brands.contains("\"Google Chrome\";v=\"124\""))
+    {
+        failures.push("the client hints do not match the user agent".to_string());
+    }
+
+    // An explicit user agent wins over the header, which keeps the others
+    session.set_user_agent(MAC_UA, None).await?;
+    let (sent, reported) = sent_headers(&session, &server).await?;
+    println!("📨 With set_user_agent: {}", sent);
+    if sent["user-agent"] != MAC_UA || reported != MAC_UA {
+        failures.push("set_user_agent did not take precedence".to_string());
+    }
+    if sent["x-variant"] != "b" {
+        failures.push("the custom header was dropped by set_user_agent".to_string());
+    }
+    if sent["sec-ch-ua-platform"] != "\"macOS\"" {
+        failures.push("the platform hint does not match the user agent".to_string());
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Each profile loaded with its own user agent and matching client hints");
+    Ok(())
+}
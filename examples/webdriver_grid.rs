@@ -0,0 +1,124 @@
+use clap::{Arg, Command};
+use std::collections::BTreeMap;
+use surfai::browser::WebDriverBrowser;
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, BrowserTrait, Config, SessionTrait};
+
+/// Fixture pages extracted through both backends
+const PAGES: &[&str] = &[
+    "/login",
+    "/dashboard",
+    "/form-labels",
+    "/dialog",
+    "/selectors",
+];
+
+/// Clickable, input and total element counts per page
+type Counts = BTreeMap<&'static str, (usize, usize, usize)>;
+
+async fn extract<B: BrowserTrait>(
+    session: &BrowserSession<B>,
+    server: &FixtureServer,
+) -> Result<Counts, Box<dyn std::error::Error>> {
+    let mut counts = Counts::new();
+    for path in PAGES {
+        session.navigate_smart(&server.url(path)).await?;
+        let state = session.get_page_state(false).await?;
+        counts.insert(
+            path,
+            (
+                state.clickable_elements.len(),
+                state.input_elements.len(),
+                state.elements.len(),
+            ),
+        );
+    }
+    Ok(counts)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("WebDriver Grid")
+        .version("1.0")
+        .about("Extracts DOM state from the fixture pages through a WebDriver remote end")
+        .arg(
+            Arg::new("webdriver-url")
+                .long("webdriver-url")
+                .help("chromedriver or grid endpoint; it must reach the local fixture server")
+                .default_value("http://localhost:9515"),
+        )
+        .arg(
+            Arg::new("compare")
+                .long("compare")
+                .help("Also extract through Chrome directly and compare the counts")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.browser.webdriver_url = matches.get_one::<String>("webdriver-url").cloned();
+    config.validate()?;
+
+    let session = BrowserSession::new(WebDriverBrowser::new(), config.clone()).await?;
+    let capabilities = session.capabilities().clone();
+    println!(
+        "🌐 Connected to {}",
+        capabilities
+            .browser_version
+            .as_deref()
+            .unwrap_or("an unknown browser")
+    );
+    let remote = extract(&session, &server).await?;
+    let screenshot = session.screenshot().await;
+    session.close().await?;
+
+    let mut failures = Vec::new();
+    for (path, (clickable, inputs, total)) in &remote {
+        println!(
+            "   {} → {} clickable, {} inputs, {} elements",
+            path, clickable, inputs, total
+        );
+        if *total == 0 {
+            failures.push(format!("nothing extracted from {}", path));
+        }
+    }
+    if !matches!(&screenshot, Ok(png) if !png.is_empty()) {
+        failures.push("the remote end returned no screenshot".to_string());
+    }
+
+    if matches.get_flag("compare") {
+        let session = BrowserSession::with_config(config).await?;
+        let local = extract(&session, &server).await?;
+        session.close().await?;
+        for (path, counts) in &local {
+            if remote.get(path) != Some(counts) {
+                failures.push(format!(
+                    "{}: Chrome extracted {:?}, WebDriver {:?}",
+                    path,
+                    counts,
+                    remote.get(path)
+                ));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ DOM extraction works through WebDriver");
+    Ok(())
+}
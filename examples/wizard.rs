@@ -0,0 +1,98 @@
+use clap::{Arg, Command};
+use surfai::browser::{StepMatcher, WizardConfig, WizardOutcome, WizardStep};
+use surfai::core::config::HeadlessMode;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, Config, SessionTrait};
+
+/// The fixture checkout, with `city` left empty when `skip_city` is set
+fn checkout(skip_city: bool) -> WizardConfig {
+    let mut shipping = WizardStep::new(
+        "Shipping",
+        StepMatcher::heading("Shipping address")
+            .and_indicator(".steps li.active[data-step=shipping]"),
+    )
+    .fill("Street", "1 Main Street");
+    if !skip_city {
+        shipping = shipping.fill("City", "Berlin");
+    }
+    WizardConfig::new(StepMatcher::url_contains("#done").and_heading("Order placed"))
+        .with_next("#next")
+        .step(
+            WizardStep::new("Account", StepMatcher::heading("Create your account"))
+                .fill("Email", "ada@example.com")
+                .fill("Password", "correct horse"),
+        )
+        .step(shipping.fill("Country", "Germany"))
+        .step(WizardStep::new(
+            "Confirm",
+            StepMatcher::url_contains("#confirm").and_heading("Review your order"),
+        ))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Wizard")
+        .version("1.0")
+        .about("Walks a 3-step checkout wizard, then gets stuck on a missing field")
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run browser in headless mode")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+
+    let server = FixtureServer::start().await?;
+    let mut config = Config::default();
+    if matches.get_flag("headless") {
+        config.browser.headless = HeadlessMode::New;
+    }
+    config.validate()?;
+    let session = BrowserSession::with_config(config).await?;
+
+    let mut failures = Vec::new();
+    session.navigate_smart(&server.url("/wizard")).await?;
+    let report = session.run_wizard(&checkout(false)).await?;
+    for step in &report.steps {
+        println!(
+            "   {}. {} ({} fields, {}) {}ms",
+            step.index,
+            step.step,
+            step.filled.len(),
+            step.diff.summary(),
+            step.duration_ms
+        );
+    }
+    if !report.completed() {
+        failures.push(format!("the checkout {}", report.outcome.describe()));
+    }
+    if report.steps.len() != 3 {
+        failures.push(format!("took {} steps instead of 3", report.steps.len()));
+    }
+    if report.steps.iter().any(|step| step.diff.is_empty()) {
+        failures.push("a step recorded no DOM changes".to_string());
+    }
+
+    session.navigate_smart(&server.url("/wizard")).await?;
+    let stuck = session
+        .run_wizard(&checkout(true).without_screenshots())
+        .await?;
+    println!("🚧 Without a city: {}", stuck.outcome.describe());
+    match &stuck.outcome {
+        WizardOutcome::Stuck { step, reason } if step == "Shipping" && reason.contains("city") => {}
+        other => failures.push(format!(
+            "expected to get stuck on Shipping over the city, got: {}",
+            other.describe()
+        )),
+    }
+
+    session.close().await?;
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Wizard runs report every step and where they get stuck");
+    Ok(())
+}
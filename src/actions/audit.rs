@@ -0,0 +1,449 @@
+use crate::errors::{BrowserAgentError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Hash that the first entry of a chain links to
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+const REDACTED: &str = "[REDACTED]";
+
+/// One executed action, linked to the previous entry by hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+    pub session_id: String,
+    pub action: String,
+    /// Parameters with secret fields masked
+    pub params: Value,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub message: String,
+    pub pre_url: Option<String>,
+    pub post_url: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// SHA-256 over the entry contents and `prev_hash`
+    pub fn compute_hash(&self) -> String {
+        let content = serde_json::json!([
+            self.sequence,
+            self.timestamp.to_rfc3339(),
+            self.session_id,
+            self.action,
+            self.params,
+            self.success,
+            self.duration_ms,
+            self.message,
+            self.pre_url,
+            self.post_url,
+            self.prev_hash,
+        ]);
+        let digest = Sha256::digest(content.to_string().as_bytes());
+        format!("{:x}", digest)
+    }
+}
+
+/// Destination for audit entries
+pub trait AuditSink: Send + Sync {
+    fn append(&self, entry: &AuditEntry) -> Result<()>;
+
+    /// Entries still held by the sink, oldest first
+    fn entries(&self) -> Result<Vec<AuditEntry>>;
+}
+
+/// Keeps the most recent `capacity` entries in memory
+pub struct MemoryAuditSink {
+    capacity: usize,
+    entries: Mutex<VecDeque<AuditEntry>>,
+}
+
+impl MemoryAuditSink {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl AuditSink for MemoryAuditSink {
+    fn append(&self, entry: &AuditEntry) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry.clone());
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<AuditEntry>> {
+        Ok(self.entries.lock().unwrap().iter().cloned().collect())
+    }
+}
+
+/// Appends one JSON object per line to a file
+pub struct JsonlAuditSink {
+    path: PathBuf,
+    file: Mutex<std::fs::File>,
+}
+
+impl JsonlAuditSink {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())?;
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditSink for JsonlAuditSink {
+    fn append(&self, entry: &AuditEntry) -> Result<()> {
+        let mut file = self.file.lock().unwrap();
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<AuditEntry>> {
+        let reader = std::io::BufReader::new(std::fs::File::open(&self.path)?);
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Filter for `ActionAuditLog::query`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub action: Option<String>,
+    pub session_id: Option<String>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.from.is_none_or(|from| entry.timestamp >= from)
+            && self.to.is_none_or(|to| entry.timestamp <= to)
+            && self.action.as_ref().is_none_or(|a| &entry.action == a)
+            && self
+                .session_id
+                .as_ref()
+                .is_none_or(|s| &entry.session_id == s)
+    }
+}
+
+/// Fields of an execution that the registry reports to the audit log
+pub(crate) struct AuditRecord<'a> {
+    pub session_id: &'a str,
+    pub action: &'a str,
+    pub params: Value,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub message: String,
+    pub pre_url: Option<String>,
+    pub post_url: Option<String>,
+}
+
+/// Tamper-evident log of every action the registry executed.
+///
+/// Each entry stores the hash of its predecessor, so editing, reordering or
+/// dropping entries from the middle breaks the chain. `head` returns the latest
+/// sequence number and hash; keep it elsewhere to also detect truncation at the end.
+pub struct ActionAuditLog {
+    sink: Box<dyn AuditSink>,
+    head: Mutex<(u64, String)>,
+}
+
+impl ActionAuditLog {
+    /// Create a log, continuing the chain of entries already in `sink`
+    pub fn new<S: AuditSink + 'static>(sink: S) -> Result<Self> {
+        let head = sink
+            .entries()?
+            .last()
+            .map(|entry| (entry.sequence, entry.hash.clone()))
+            .unwrap_or((0, GENESIS_HASH.to_string()));
+        Ok(Self {
+            sink: Box::new(sink),
+            head: Mutex::new(head),
+        })
+    }
+
+    pub(crate) fn record(&self, record: AuditRecord<'_>) -> Result<AuditEntry> {
+        let mut head = self.head.lock().unwrap();
+        let mut entry = AuditEntry {
+            sequence: head.0 + 1,
+            timestamp: Utc::now(),
+            session_id: record.session_id.to_string(),
+            action: record.action.to_string(),
+            params: record.params,
+            success: record.success,
+            duration_ms: record.duration_ms,
+            message: record.message,
+            pre_url: record.pre_url,
+            post_url: record.post_url,
+            prev_hash: head.1.clone(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+
+        self.sink.append(&entry)?;
+        *head = (entry.sequence, entry.hash.clone());
+        Ok(entry)
+    }
+
+    /// Latest sequence number and hash
+    pub fn head(&self) -> (u64, String) {
+        self.head.lock().unwrap().clone()
+    }
+
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        Ok(self
+            .sink
+            .entries()?
+            .into_iter()
+            .filter(|entry| query.matches(entry))
+            .collect())
+    }
+
+    /// Check the hash chain of everything still held by the sink
+    pub fn verify(&self) -> Result<()> {
+        let entries = self.sink.entries()?;
+        Self::verify_chain(&entries)?;
+        let (sequence, hash) = self.head();
+        match entries.last() {
+            Some(last) if last.sequence != sequence || last.hash != hash => {
+                Err(BrowserAgentError::AuditIntegrity(format!(
+                    "log truncated: last entry is #{}, expected #{}",
+                    last.sequence, sequence
+                )))
+            }
+            None if sequence != 0 => Err(BrowserAgentError::AuditIntegrity(
+                "log truncated: no entries left".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that every entry hashes correctly and links to the one before it.
+    ///
+    /// The first entry may link to an entry that has rotated out of the sink.
+    pub fn verify_chain(entries: &[AuditEntry]) -> Result<()> {
+        let broken = |entry: &AuditEntry, reason: &str| {
+            Err(BrowserAgentError::AuditIntegrity(format!(
+                "chain broken at entry #{}: {}",
+                entry.sequence, reason
+            )))
+        };
+
+        for (index, entry) in entries.iter().enumerate() {
+            if entry.compute_hash() != entry.hash {
+                return broken(entry, "hash mismatch");
+            }
+            if let Some(previous) = index.checked_sub(1).map(|i| &entries[i]) {
+                if entry.prev_hash != previous.hash || entry.sequence != previous.sequence + 1 {
+                    return broken(entry, "does not follow the previous entry");
+                }
+            } else if entry.sequence == 1 && entry.prev_hash != GENESIS_HASH {
+                return broken(entry, "first entry does not start the chain");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mask parameters whose schema property is marked secret.
+///
+/// A property counts as secret when its schema has `"secret": true`,
+/// `"writeOnly": true` or `"format": "password"`.
+pub fn redact_params(params: &Value, schema: &Value) -> Value {
+    let is_secret = |property: &Value| {
+        property.get("secret").and_then(|v| v.as_bool()) == Some(true)
+            || property.get("writeOnly").and_then(|v| v.as_bool()) == Some(true)
+            || property.get("format").and_then(|v| v.as_str()) == Some("password")
+    };
+
+    match params {
+        Value::Object(map) => {
+            let properties = schema.get("properties");
+            Value::Object(
+                map.iter()
+                    .map(|(key, value)| {
+                        let property = properties.and_then(|p| p.get(key));
+                        let redacted = match property {
+                            Some(property) if is_secret(property) => {
+                                Value::String(REDACTED.to_string())
+                            }
+                            Some(property) => redact_params(value, property),
+                            None => value.clone(),
+                        };
+                        (key.clone(), redacted)
+                    })
+                    .collect(),
+            )
+        }
+        Value::Array(items) => match schema.get("items") {
+            Some(item_schema) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| redact_params(item, item_schema))
+                    .collect(),
+            ),
+            None => params.clone(),
+        },
+        _ => params.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(log: &ActionAuditLog, action: &str) -> AuditEntry {
+        log.record(AuditRecord {
+            session_id: "s1",
+            action,
+            params: json!({ "selector": "#go" }),
+            success: true,
+            duration_ms: 12,
+            message: "ok".to_string(),
+            pre_url: Some("https://example.com/".to_string()),
+            post_url: None,
+        })
+        .unwrap()
+    }
+
+    fn chain(length: usize) -> (ActionAuditLog, Vec<AuditEntry>) {
+        let log = ActionAuditLog::new(MemoryAuditSink::new(100)).unwrap();
+        let entries = (0..length)
+            .map(|i| record(&log, &format!("a{}", i)))
+            .collect();
+        (log, entries)
+    }
+
+    #[test]
+    fn recorded_chain_verifies() {
+        let (log, entries) = chain(4);
+        assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+        assert_eq!(entries[3].sequence, 4);
+        assert!(ActionAuditLog::verify_chain(&entries).is_ok());
+        assert!(log.verify().is_ok());
+        assert!(ActionAuditLog::verify_chain(&[]).is_ok());
+    }
+
+    #[test]
+    fn edited_entry_breaks_the_chain() {
+        let (_, mut entries) = chain(3);
+        entries[1].success = false;
+        let error = ActionAuditLog::verify_chain(&entries).unwrap_err();
+        assert!(
+            error.to_string().contains("entry #2: hash mismatch"),
+            "{}",
+            error
+        );
+
+        // Rehashing the edit still leaves the next entry pointing at the old hash
+        entries[1].hash = entries[1].compute_hash();
+        let error = ActionAuditLog::verify_chain(&entries).unwrap_err();
+        assert!(
+            error.to_string().contains("entry #3: does not follow"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn dropped_or_reordered_entries_break_the_chain() {
+        let (_, entries) = chain(3);
+        let dropped = [entries[0].clone(), entries[2].clone()];
+        assert!(ActionAuditLog::verify_chain(&dropped).is_err());
+        let swapped = [entries[1].clone(), entries[0].clone()];
+        assert!(ActionAuditLog::verify_chain(&swapped).is_err());
+    }
+
+    #[test]
+    fn rotated_out_head_is_accepted_but_forged_genesis_is_not() {
+        let (_, entries) = chain(3);
+        assert!(ActionAuditLog::verify_chain(&entries[1..]).is_ok());
+
+        let mut forged = entries[0].clone();
+        forged.prev_hash = entries[2].hash.clone();
+        forged.hash = forged.compute_hash();
+        let error = ActionAuditLog::verify_chain(&[forged]).unwrap_err();
+        assert!(
+            error.to_string().contains("does not start the chain"),
+            "{}",
+            error
+        );
+    }
+
+    /// Sink whose entries the test can still reach after handing it to the log
+    struct SharedSink(std::sync::Arc<MemoryAuditSink>);
+
+    impl AuditSink for SharedSink {
+        fn append(&self, entry: &AuditEntry) -> Result<()> {
+            self.0.append(entry)
+        }
+
+        fn entries(&self) -> Result<Vec<AuditEntry>> {
+            self.0.entries()
+        }
+    }
+
+    #[test]
+    fn truncated_tail_fails_verify() {
+        let sink = std::sync::Arc::new(MemoryAuditSink::new(100));
+        let log = ActionAuditLog::new(SharedSink(sink.clone())).unwrap();
+        for action in ["a", "b", "c"] {
+            record(&log, action);
+        }
+        assert!(log.verify().is_ok());
+
+        sink.entries.lock().unwrap().pop_back();
+        assert!(ActionAuditLog::verify_chain(&sink.entries().unwrap()).is_ok());
+        let error = log.verify().unwrap_err();
+        assert!(
+            error.to_string().contains("last entry is #2, expected #3"),
+            "{}",
+            error
+        );
+
+        sink.entries.lock().unwrap().clear();
+        let error = log.verify().unwrap_err();
+        assert!(error.to_string().contains("no entries left"), "{}", error);
+    }
+
+    #[test]
+    fn new_log_continues_the_existing_chain() {
+        let sink = std::sync::Arc::new(MemoryAuditSink::new(100));
+        let first = ActionAuditLog::new(SharedSink(sink.clone())).unwrap();
+        record(&first, "a");
+        let head = first.head();
+
+        let second = ActionAuditLog::new(SharedSink(sink.clone())).unwrap();
+        assert_eq!(second.head(), head);
+        let entry = record(&second, "b");
+        assert_eq!(entry.sequence, 2);
+        assert_eq!(entry.prev_hash, head.1);
+        assert!(second.verify().is_ok());
+    }
+}
@@ -1,7 +1,10 @@
+use crate::actions::ActionRegistry;
 use crate::errors::Result;
+use crate::utils::{default_clock, CancellationToken, SharedClock};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Result of an action execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,8 +47,36 @@ impl ActionResult {
         self.execution_time_ms = time_ms;
         self
     }
+
+    /// Whether the registry ran the action as a dry run
+    pub fn is_dry_run(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| data.get(DRY_RUN_KEY))
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Mark the result as a dry run's: `data` becomes an object with
+    /// `DRY_RUN_KEY` set, wrapping any data that was not an object as `result`.
+    /// Page changes are never predicted, so `predicted_diff` is `"unknown"`.
+    pub(crate) fn into_dry_run(mut self) -> Self {
+        let mut data = match self.data.take() {
+            Some(serde_json::Value::Object(map)) => map,
+            Some(other) => serde_json::Map::from_iter([("result".to_string(), other)]),
+            None => serde_json::Map::new(),
+        };
+        data.insert(DRY_RUN_KEY.to_string(), true.into());
+        data.entry("predicted_diff")
+            .or_insert_with(|| "unknown".into());
+        self.data = Some(serde_json::Value::Object(data));
+        self
+    }
 }
 
+/// Key in `ActionResult.data` marking the result of a dry run
+pub const DRY_RUN_KEY: &str = "dry_run";
+
 /// Error types for actions
 #[derive(Debug, thiserror::Error)]
 pub enum ActionError {
@@ -60,6 +91,9 @@ pub enum ActionError {
 
     #[error("Timeout: {0}")]
     Timeout(String),
+
+    #[error("Action name conflict: {0}")]
+    Conflict(String),
 }
 
 /// Base trait for all browser actions
@@ -74,6 +108,19 @@ pub trait Action: Send + Sync {
     /// Parameter schema for validation
     fn parameter_schema(&self) -> serde_json::Value;
 
+    /// Schema of the `ActionResult.data` the action produces, for callers wiring
+    /// one action's output into another's params
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Whether the registry fills `{{name}}` references in params from
+    /// `ActionContext.variables` before validating them. Composite actions that
+    /// resolve their children's params as they go return `false`.
+    fn templates_params(&self) -> bool {
+        true
+    }
+
     /// Execute the action
     async fn execute(
         &self,
@@ -81,6 +128,22 @@ pub trait Action: Send + Sync {
         context: &ActionContext,
     ) -> Result<ActionResult>;
 
+    /// What `execute` would do, without dispatching events or navigating. Runs
+    /// instead of `execute` when `ActionContext.dry_run` is set, after params
+    /// have been validated. Actions that touch the page override it to resolve
+    /// their target and report what they would act on; the default only echoes
+    /// the params.
+    async fn dry_run(
+        &self,
+        params: serde_json::Value,
+        _context: &ActionContext,
+    ) -> Result<ActionResult> {
+        Ok(ActionResult::success_with_data(
+            format!("Would run {}", self.name()),
+            serde_json::json!({ "params": params }),
+        ))
+    }
+
     /// Validate parameters before execution
     fn validate_params(&self, params: &serde_json::Value) -> Result<()> {
         // Default implementation - can be overridden
@@ -89,14 +152,28 @@ pub trait Action: Send + Sync {
 }
 
 /// Context provided to actions during execution
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ActionContext {
     pub session_id: String,
     pub browser_state: Option<crate::dom::DomState>,
     pub variables: HashMap<String, serde_json::Value>,
     pub timeout_ms: u64,
+    /// Registry that composite actions use to run their steps
+    pub registry: Option<Arc<ActionRegistry>>,
+    /// How many composite actions enclose the current one
+    pub depth: usize,
+    /// Trips to abort the action and any children it runs
+    pub cancel_token: CancellationToken,
+    /// Time source for retry backoff and other delays
+    pub clock: SharedClock,
+    /// Resolve and validate actions without running them; see `Action::dry_run`.
+    /// Composite actions pass it on to their children.
+    pub dry_run: bool,
 }
 
+/// Maximum nesting of composite actions within one plan
+pub const MAX_ACTION_DEPTH: usize = 16;
+
 impl ActionContext {
     pub fn new(session_id: String) -> Self {
         Self {
@@ -104,6 +181,11 @@ impl ActionContext {
             browser_state: None,
             variables: HashMap::new(),
             timeout_ms: 30000,
+            registry: None,
+            depth: 0,
+            cancel_token: CancellationToken::new(),
+            clock: default_clock(),
+            dry_run: false,
         }
     }
 
@@ -117,8 +199,102 @@ impl ActionContext {
         self
     }
 
+    /// Variable `name`, or the value at a dotted path such as `order.items.0`
+    pub fn get_var(&self, name: &str) -> Option<&serde_json::Value> {
+        crate::actions::variables::lookup_variable(name, &self.variables)
+    }
+
+    /// Variable `name` converted to `T`; fails with `InvalidParameters` when it has
+    /// another type
+    pub fn get_var_as<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<Option<T>> {
+        self.get_var(name)
+            .map(|value| {
+                serde_json::from_value(value.clone()).map_err(|e| {
+                    ActionError::InvalidParameters(format!("variable {}: {}", name, e)).into()
+                })
+            })
+            .transpose()
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.get_var(name).and_then(|value| value.as_str())
+    }
+
+    pub fn get_i64(&self, name: &str) -> Option<i64> {
+        self.get_var(name).and_then(|value| value.as_i64())
+    }
+
+    pub fn get_f64(&self, name: &str) -> Option<f64> {
+        self.get_var(name).and_then(|value| value.as_f64())
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.get_var(name).and_then(|value| value.as_bool())
+    }
+
+    /// Set variable `name`, replacing any earlier value
+    pub fn set_var(&mut self, name: &str, value: impl Into<serde_json::Value>) {
+        self.variables.insert(name.to_string(), value.into());
+    }
+
+    /// Set variable `name` to any serializable value
+    pub fn set_var_as<T: Serialize>(&mut self, name: &str, value: &T) -> Result<()> {
+        self.variables
+            .insert(name.to_string(), serde_json::to_value(value)?);
+        Ok(())
+    }
+
+    /// `params` with `{{name}}` references filled in from the variables
+    pub fn render(&self, params: &serde_json::Value) -> serde_json::Value {
+        crate::actions::variables::substitute_variables(params, &self.variables)
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub fn with_timeout(mut self, timeout_ms: u64) -> Self {
         self.timeout_ms = timeout_ms;
         self
     }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_cancel_token(mut self, token: CancellationToken) -> Self {
+        self.cancel_token = token;
+        self
+    }
+
+    pub fn with_registry(mut self, registry: Arc<ActionRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Context for the children of a composite action, one level deeper
+    pub fn nested(&self) -> Result<Self> {
+        if self.depth >= MAX_ACTION_DEPTH {
+            return Err(ActionError::ExecutionFailed(format!(
+                "action nesting exceeds {} levels",
+                MAX_ACTION_DEPTH
+            ))
+            .into());
+        }
+        let mut child = self.clone();
+        child.depth += 1;
+        Ok(child)
+    }
+
+    /// Registry for running child actions
+    pub fn require_registry(&self) -> Result<Arc<ActionRegistry>> {
+        self.registry.clone().ok_or_else(|| {
+            ActionError::ExecutionFailed(
+                "composite actions require ActionContext.registry".to_string(),
+            )
+            .into()
+        })
+    }
 }
@@ -0,0 +1,600 @@
+use crate::actions::base::ActionContext;
+use crate::actions::{Action, ActionError, ActionResult};
+use crate::core::ElementFilter;
+use crate::errors::Result;
+use crate::utils::{run_cancellable, RetryPolicy};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// A child action embedded in a control-flow action's parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildAction {
+    /// Registered action name (qualified name, alias, or bare name)
+    pub action: String,
+    /// Left out, the child runs with empty params
+    #[serde(default = "empty_params")]
+    pub params: Value,
+}
+
+pub(crate) fn empty_params() -> Value {
+    json!({})
+}
+
+impl ChildAction {
+    async fn run(&self, context: &ActionContext) -> Result<ActionResult> {
+        context
+            .require_registry()?
+            .execute_action(&self.action, self.params.clone(), context)
+            .await
+    }
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["action"],
+            "properties": {
+                "action": { "type": "string" },
+                "params": { "type": "object" }
+            }
+        })
+    }
+}
+
+/// Predicate evaluated against `ActionContext.browser_state` and variables
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// At least `min_matches` elements of the DOM state satisfy `filter`
+    Elements {
+        filter: ElementFilter,
+        #[serde(default = "default_min_matches")]
+        min_matches: usize,
+    },
+    /// The page URL matches this regular expression
+    UrlMatches(String),
+    /// The page title contains this text, case-insensitively
+    TitleContains(String),
+    /// A context variable is set, and equals `equals` when given
+    Variable {
+        name: String,
+        #[serde(default)]
+        equals: Option<Value>,
+    },
+    Not(Box<Condition>),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+fn default_min_matches() -> usize {
+    1
+}
+
+impl Condition {
+    pub fn evaluate(&self, context: &ActionContext) -> Result<bool> {
+        let state = || {
+            context.browser_state.as_ref().ok_or_else(|| {
+                ActionError::ExecutionFailed(
+                    "condition requires ActionContext.browser_state".to_string(),
+                )
+            })
+        };
+
+        Ok(match self {
+            Condition::Elements {
+                filter,
+                min_matches,
+            } => {
+                state()?
+                    .elements
+                    .iter()
+                    .filter(|element| filter.matches(element))
+                    .count()
+                    >= *min_matches
+            }
+            Condition::UrlMatches(pattern) => regex::Regex::new(pattern)
+                .map_err(|e| ActionError::InvalidParameters(e.to_string()))?
+                .is_match(&state()?.url),
+            Condition::TitleContains(text) => {
+                state()?.title.to_lowercase().contains(&text.to_lowercase())
+            }
+            Condition::Variable { name, equals } => match (context.variables.get(name), equals) {
+                (Some(value), Some(expected)) => value == expected,
+                (Some(value), None) => !value.is_null(),
+                (None, _) => false,
+            },
+            Condition::Not(inner) => !inner.evaluate(context)?,
+            Condition::All(conditions) => {
+                for condition in conditions {
+                    if !condition.evaluate(context)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Condition::Any(conditions) => {
+                for condition in conditions {
+                    if condition.evaluate(context)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ConditionalParams {
+    condition: Condition,
+    then: ChildAction,
+    #[serde(default, rename = "else")]
+    otherwise: Option<ChildAction>,
+}
+
+/// Runs one of two child actions depending on a `Condition`
+pub struct ConditionalAction;
+
+impl ConditionalAction {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ConditionalAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Action for ConditionalAction {
+    fn name(&self) -> &str {
+        "if"
+    }
+
+    fn description(&self) -> &str {
+        "Run `then` when the condition holds against the current page state, otherwise `else`"
+    }
+
+    fn parameter_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "required": ["condition", "then"],
+            "properties": {
+                "condition": {
+                    "type": "object",
+                    "description": "One of: elements {filter, min_matches}, url_matches, title_contains, variable {name, equals}, not, all, any"
+                },
+                "then": ChildAction::schema(),
+                "else": ChildAction::schema()
+            }
+        })
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<()> {
+        serde_json::from_value::<ConditionalParams>(params.clone())
+            .map_err(|e| ActionError::InvalidParameters(e.to_string()))?;
+        Ok(())
+    }
+
+    /// The condition is evaluated as usual and the chosen branch dry-run
+    async fn dry_run(&self, params: Value, context: &ActionContext) -> Result<ActionResult> {
+        self.execute(params, context).await
+    }
+
+    async fn execute(&self, params: Value, context: &ActionContext) -> Result<ActionResult> {
+        let parsed: ConditionalParams = serde_json::from_value(params)
+            .map_err(|e| ActionError::InvalidParameters(e.to_string()))?;
+        let child_context = context.nested()?;
+
+        let matched = parsed.condition.evaluate(&child_context)?;
+        let branch = if matched {
+            Some(&parsed.then)
+        } else {
+            parsed.otherwise.as_ref()
+        };
+
+        let Some(branch) = branch else {
+            return Ok(ActionResult::success_with_data(
+                "Condition not met, no else branch".to_string(),
+                json!({ "condition": false, "branch": null }),
+            ));
+        };
+
+        let result = branch.run(&child_context).await?;
+        Ok(ActionResult {
+            success: result.success,
+            message: format!(
+                "Condition {}, ran {}: {}",
+                matched, branch.action, result.message
+            ),
+            data: Some(json!({
+                "condition": matched,
+                "branch": if matched { "then" } else { "else" },
+                "result": result,
+            })),
+            execution_time_ms: 0,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RetryParams {
+    action: ChildAction,
+    #[serde(default)]
+    policy: RetryPolicy,
+}
+
+/// Re-runs a child action with backoff until it succeeds or attempts run out
+pub struct RetryAction;
+
+impl RetryAction {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RetryAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Action for RetryAction {
+    fn name(&self) -> &str {
+        "retry"
+    }
+
+    fn description(&self) -> &str {
+        "Retry a child action with exponential backoff until it succeeds"
+    }
+
+    fn parameter_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "required": ["action"],
+            "properties": {
+                "action": ChildAction::schema(),
+                "policy": {
+                    "type": "object",
+                    "properties": {
+                        "max_attempts": { "type": "integer", "minimum": 1, "default": 3 },
+                        "initial_backoff_ms": { "type": "integer", "default": 250 },
+                        "multiplier": { "type": "number", "default": 2.0 },
+                        "max_backoff_ms": { "type": "integer", "default": 5000 }
+                    }
+                }
+            }
+        })
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<()> {
+        let parsed: RetryParams = serde_json::from_value(params.clone())
+            .map_err(|e| ActionError::InvalidParameters(e.to_string()))?;
+        if parsed.policy.max_attempts == 0 {
+            return Err(ActionError::InvalidParameters(
+                "max_attempts must be at least 1".to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// A dry run of the child gives the same answer every time, so it runs once
+    async fn dry_run(&self, params: Value, context: &ActionContext) -> Result<ActionResult> {
+        let parsed: RetryParams = serde_json::from_value(params)
+            .map_err(|e| ActionError::InvalidParameters(e.to_string()))?;
+        let result = parsed.action.run(&context.nested()?).await?;
+        Ok(ActionResult {
+            success: result.success,
+            message: format!(
+                "Would retry {} up to {} times: {}",
+                parsed.action.action, parsed.policy.max_attempts, result.message
+            ),
+            data: Some(
+                json!({ "attempts": [{ "attempt": 1, "success": result.success, "result": result }] }),
+            ),
+            execution_time_ms: 0,
+        })
+    }
+
+    async fn execute(&self, params: Value, context: &ActionContext) -> Result<ActionResult> {
+        let parsed: RetryParams = serde_json::from_value(params)
+            .map_err(|e| ActionError::InvalidParameters(e.to_string()))?;
+        let child_context = context.nested()?;
+
+        let mut attempts = Vec::new();
+        for attempt in 1..=parsed.policy.max_attempts.max(1) {
+            let outcome = parsed.action.run(&child_context).await;
+            let succeeded = matches!(&outcome, Ok(result) if result.success);
+
+            attempts.push(match &outcome {
+                Ok(result) => {
+                    json!({ "attempt": attempt, "success": result.success, "result": result })
+                }
+                Err(e) => json!({ "attempt": attempt, "success": false, "error": e.to_string() }),
+            });
+
+            if succeeded {
+                return Ok(ActionResult::success_with_data(
+                    format!("{} succeeded on attempt {}", parsed.action.action, attempt),
+                    json!({ "attempts": attempts }),
+                ));
+            }
+
+            if attempt < parsed.policy.max_attempts {
+                let delay = parsed.policy.delay_for(attempt);
+                println!(
+                    "🔄 {} failed (attempt {}/{}), retrying in {}ms",
+                    parsed.action.action,
+                    attempt,
+                    parsed.policy.max_attempts,
+                    delay.as_millis()
+                );
+                run_cancellable(&child_context.cancel_token, "retry", async {
+                    child_context.clock.sleep(delay).await;
+                    Ok(())
+                })
+                .await?;
+            }
+        }
+
+        Ok(ActionResult {
+            success: false,
+            message: format!(
+                "{} failed after {} attempts",
+                parsed.action.action, parsed.policy.max_attempts
+            ),
+            data: Some(json!({ "attempts": attempts })),
+            execution_time_ms: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::ActionRegistry;
+    use crate::dom::{DomElement, DomState};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    fn state() -> DomState {
+        let mut state = DomState::new(
+            "https://shop.example.com/cart?id=7".to_string(),
+            "Your Cart".to_string(),
+        );
+        let mut checkout = DomElement::new("button".to_string(), "elem_1".to_string())
+            .with_text_content("Checkout".to_string());
+        checkout.is_clickable = true;
+        let mut hidden = DomElement::new("button".to_string(), "elem_2".to_string())
+            .with_text_content("Remove".to_string());
+        hidden.is_visible = false;
+        state.add_element(checkout);
+        state.add_element(hidden);
+        state.add_element(DomElement::new("input".to_string(), "elem_3".to_string()));
+        state
+    }
+
+    fn context() -> ActionContext {
+        ActionContext::new("s1".to_string())
+            .with_browser_state(state())
+            .with_variable("step".to_string(), json!("payment"))
+            .with_variable("cleared".to_string(), Value::Null)
+    }
+
+    fn holds(condition: Value) -> bool {
+        serde_json::from_value::<Condition>(condition)
+            .unwrap()
+            .evaluate(&context())
+            .unwrap()
+    }
+
+    #[test]
+    fn element_conditions_count_matches() {
+        assert!(holds(json!({ "elements": { "filter": {
+            "tag_names": ["button"], "has_text": null, "is_visible": null,
+            "is_interactive": null, "has_attribute": null
+        } } })));
+        assert!(holds(json!({ "elements": { "min_matches": 2, "filter": {
+            "tag_names": ["button"], "has_text": null, "is_visible": null,
+            "is_interactive": null, "has_attribute": null
+        } } })));
+        assert!(!holds(json!({ "elements": { "min_matches": 2, "filter": {
+            "tag_names": ["button"], "has_text": null, "is_visible": true,
+            "is_interactive": null, "has_attribute": null
+        } } })));
+        assert!(holds(json!({ "elements": { "filter": {
+            "tag_names": null, "has_text": "checkout", "is_visible": null,
+            "is_interactive": null, "has_attribute": null
+        } } })));
+        assert!(!holds(json!({ "elements": { "filter": {
+            "tag_names": ["a"], "has_text": null, "is_visible": null,
+            "is_interactive": null, "has_attribute": null
+        } } })));
+    }
+
+    #[test]
+    fn page_conditions_read_url_and_title() {
+        assert!(holds(json!({ "url_matches": "/cart\\?id=\\d+$" })));
+        assert!(!holds(json!({ "url_matches": "^https://other\\." })));
+        assert!(holds(json!({ "title_contains": "your CART" })));
+        assert!(!holds(json!({ "title_contains": "Checkout" })));
+    }
+
+    #[test]
+    fn variable_conditions_need_a_set_value() {
+        assert!(holds(json!({ "variable": { "name": "step" } })));
+        assert!(holds(
+            json!({ "variable": { "name": "step", "equals": "payment" } })
+        ));
+        assert!(!holds(
+            json!({ "variable": { "name": "step", "equals": "shipping" } })
+        ));
+        assert!(!holds(json!({ "variable": { "name": "cleared" } })));
+        assert!(!holds(json!({ "variable": { "name": "missing" } })));
+    }
+
+    #[test]
+    fn combinators_compose() {
+        let yes = json!({ "title_contains": "cart" });
+        let no = json!({ "title_contains": "login" });
+        assert!(holds(json!({ "not": no })));
+        assert!(holds(json!({ "all": [yes, { "not": no }] })));
+        assert!(!holds(json!({ "all": [yes, no] })));
+        assert!(holds(json!({ "any": [no, yes] })));
+        assert!(!holds(json!({ "any": [no, no] })));
+        assert!(holds(json!({ "all": [] })));
+        assert!(!holds(json!({ "any": [] })));
+    }
+
+    #[test]
+    fn page_conditions_need_a_state() {
+        let condition = Condition::TitleContains("cart".to_string());
+        assert!(condition
+            .evaluate(&ActionContext::new("s1".to_string()))
+            .is_err());
+        // Variables alone need no page
+        let condition = Condition::Variable {
+            name: "step".to_string(),
+            equals: None,
+        };
+        assert!(!condition
+            .evaluate(&ActionContext::new("s1".to_string()))
+            .unwrap());
+        assert!(Condition::UrlMatches("(".to_string())
+            .evaluate(&context())
+            .is_err());
+    }
+
+    /// Fails until it has been called `succeed_on` times
+    struct Flaky {
+        calls: Arc<AtomicU32>,
+        succeed_on: u32,
+    }
+
+    #[async_trait]
+    impl Action for Flaky {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn description(&self) -> &str {
+            "Fail a few times"
+        }
+
+        fn parameter_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+
+        async fn execute(&self, _params: Value, _context: &ActionContext) -> Result<ActionResult> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(if call >= self.succeed_on {
+                ActionResult::success(format!("call {}", call))
+            } else {
+                ActionResult::failure(format!("call {}", call))
+            })
+        }
+    }
+
+    fn registry_context(succeed_on: u32) -> (ActionContext, Arc<AtomicU32>) {
+        let calls = Arc::new(AtomicU32::new(0));
+        let mut registry = ActionRegistry::new();
+        registry.register(ConditionalAction::new());
+        registry.register(RetryAction::new());
+        registry.register(Flaky {
+            calls: calls.clone(),
+            succeed_on,
+        });
+        let context = context().with_registry(Arc::new(registry));
+        (context, calls)
+    }
+
+    #[tokio::test]
+    async fn conditional_runs_the_matching_branch() {
+        let (context, calls) = registry_context(1);
+        let registry = context.require_registry().unwrap();
+        let params = |title: &str| {
+            json!({
+                "condition": { "title_contains": title },
+                "then": { "action": "flaky" }
+            })
+        };
+
+        let result = registry
+            .execute_action("if", params("cart"), &context)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.data.as_ref().unwrap()["branch"], "then");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let result = registry
+            .execute_action("if", params("login"), &context)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.data.as_ref().unwrap()["branch"], Value::Null);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_stops_at_the_first_success() {
+        let clock = Arc::new(crate::utils::VirtualClock::start());
+        let started = std::time::Instant::now();
+        let (context, calls) = registry_context(3);
+        let context = context.with_clock(clock.clone());
+        let registry = context.require_registry().unwrap();
+        let params = |max_attempts: u32| {
+            json!({
+                "action": { "action": "flaky" },
+                "policy": {
+                    "max_attempts": max_attempts,
+                    "initial_backoff_ms": 30_000,
+                    "multiplier": 2.0,
+                    "max_backoff_ms": 45_000
+                }
+            })
+        };
+
+        let result = registry
+            .execute_action("retry", params(5), &context)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        // Two backoffs, the second capped, on virtual time only; tokio rounds
+        // each sleep up to the millisecond
+        assert_eq!(clock.elapsed().as_secs(), 75);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        calls.store(0, Ordering::SeqCst);
+        let result = registry
+            .execute_action("retry", params(2), &context)
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.data.as_ref().unwrap()["attempts"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn nesting_is_limited() {
+        let (mut context, _) = registry_context(1);
+        context.depth = crate::actions::base::MAX_ACTION_DEPTH;
+        let result = ConditionalAction::new()
+            .execute(
+                json!({ "condition": { "all": [] }, "then": { "action": "flaky" } }),
+                &context,
+            )
+            .await;
+        assert!(result.is_err());
+    }
+}
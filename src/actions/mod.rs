@@ -1,5 +1,17 @@
+pub mod audit;
 pub mod base;
+pub mod control;
 pub mod registry;
+pub mod schema;
+pub mod sequence;
+pub mod variables;
 
-pub use base::{Action, ActionError, ActionResult};
-pub use registry::ActionRegistry;
+pub use audit::{
+    ActionAuditLog, AuditEntry, AuditQuery, AuditSink, JsonlAuditSink, MemoryAuditSink,
+};
+pub use base::{Action, ActionError, ActionResult, DRY_RUN_KEY};
+pub use control::{ChildAction, Condition, ConditionalAction, RetryAction};
+pub use registry::{ActionEntry, ActionRegistry, ConflictPolicy};
+pub use schema::validate_against_schema;
+pub use sequence::{FailurePolicy, SequenceAction, SequenceStep};
+pub use variables::substitute_variables;
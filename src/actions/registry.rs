@@ -1,35 +1,223 @@
+use crate::actions::audit::{redact_params, ActionAuditLog, AuditRecord, AuditSink};
 use crate::actions::base::ActionContext;
+use crate::actions::schema::validate_against_schema;
 use crate::actions::{Action, ActionError, ActionResult};
-use crate::errors::Result;
+use crate::errors::{BrowserAgentError, Result};
+use crate::utils::run_cancellable;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-/// Registry for browser actions
+/// Registry for browser actions.
+///
+/// Actions are stored under a qualified name: `namespace.name` when registered with
+/// `register_in`, or the bare action name otherwise. Lookups resolve, in order, an
+/// exact qualified name, an alias, and finally a bare name that matches exactly one
+/// namespaced action.
 pub struct ActionRegistry {
-    actions: HashMap<String, Arc<dyn Action>>,
+    actions: HashMap<String, RegisteredAction>,
+    aliases: HashMap<String, String>,
+    audit: Option<ActionAuditLog>,
+}
+
+#[derive(Clone)]
+struct RegisteredAction {
+    namespace: Option<String>,
+    action: Arc<dyn Action>,
+}
+
+/// How `merge` handles names present in both registries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the action already registered
+    KeepExisting,
+    /// Replace it with the incoming action
+    Replace,
+    /// Fail the merge without changing the registry
+    Error,
 }
 
 impl ActionRegistry {
     pub fn new() -> Self {
         Self {
             actions: HashMap::new(),
+            aliases: HashMap::new(),
+            audit: None,
         }
     }
 
-    /// Register a new action
+    /// Record every `execute_action` call to `sink`
+    pub fn with_audit<S: AuditSink + 'static>(mut self, sink: S) -> Result<Self> {
+        self.audit = Some(ActionAuditLog::new(sink)?);
+        Ok(self)
+    }
+
+    pub fn audit_log(&self) -> Option<&ActionAuditLog> {
+        self.audit.as_ref()
+    }
+
+    fn qualified_name(namespace: Option<&str>, name: &str) -> String {
+        match namespace {
+            Some(ns) => format!("{}.{}", ns, name),
+            None => name.to_string(),
+        }
+    }
+
+    fn conflict(name: &str) -> BrowserAgentError {
+        BrowserAgentError::ActionError(ActionError::Conflict(name.to_string()))
+    }
+
+    /// Register a new action, replacing any action with the same name
     pub fn register<A: Action + 'static>(&mut self, action: A) {
-        let name = action.name().to_string();
-        self.actions.insert(name, Arc::new(action));
+        self.insert(None, Arc::new(action));
+    }
+
+    /// Register an action as `namespace.name`, replacing any action with the same name
+    pub fn register_in<A: Action + 'static>(&mut self, namespace: &str, action: A) {
+        self.insert(Some(namespace), Arc::new(action));
+    }
+
+    /// Register a new action, failing if the name is already taken
+    pub fn try_register<A: Action + 'static>(&mut self, action: A) -> Result<()> {
+        self.try_insert(None, Arc::new(action))
     }
 
-    /// Get an action by name
+    /// Register an action as `namespace.name`, failing if the name is already taken
+    pub fn try_register_in<A: Action + 'static>(
+        &mut self,
+        namespace: &str,
+        action: A,
+    ) -> Result<()> {
+        self.try_insert(Some(namespace), Arc::new(action))
+    }
+
+    fn insert(&mut self, namespace: Option<&str>, action: Arc<dyn Action>) {
+        let name = Self::qualified_name(namespace, action.name());
+        if self.actions.contains_key(&name) {
+            println!("⚠️ Replacing registered action: {}", name);
+        }
+        // A real action always wins over an alias of the same name
+        self.aliases.remove(&name);
+        self.actions.insert(
+            name,
+            RegisteredAction {
+                namespace: namespace.map(|ns| ns.to_string()),
+                action,
+            },
+        );
+    }
+
+    fn try_insert(&mut self, namespace: Option<&str>, action: Arc<dyn Action>) -> Result<()> {
+        let name = Self::qualified_name(namespace, action.name());
+        if self.actions.contains_key(&name) || self.aliases.contains_key(&name) {
+            return Err(Self::conflict(&name));
+        }
+        self.insert(namespace, action);
+        Ok(())
+    }
+
+    /// Make `alias` resolve to the action registered as `target`
+    pub fn alias(&mut self, alias: &str, target: &str) -> Result<()> {
+        if self.actions.contains_key(alias) || self.aliases.contains_key(alias) {
+            return Err(Self::conflict(alias));
+        }
+        let target = self.resolve_name(target).ok_or_else(|| {
+            BrowserAgentError::ActionError(ActionError::ActionNotFound(target.to_string()))
+        })?;
+        self.aliases.insert(alias.to_string(), target);
+        Ok(())
+    }
+
+    /// Qualified name that `name` resolves to
+    pub fn resolve_name(&self, name: &str) -> Option<String> {
+        if self.actions.contains_key(name) {
+            return Some(name.to_string());
+        }
+        if let Some(target) = self.aliases.get(name) {
+            return Some(target.clone());
+        }
+        if name.contains('.') {
+            return None;
+        }
+
+        // Bare name: only resolve when exactly one namespace provides it
+        let mut matches = self
+            .actions
+            .iter()
+            .filter(|(_, entry)| entry.namespace.is_some() && entry.action.name() == name)
+            .map(|(qualified, _)| qualified);
+        match (matches.next(), matches.next()) {
+            (Some(qualified), None) => Some(qualified.clone()),
+            _ => None,
+        }
+    }
+
+    /// Get an action by qualified name, alias, or unambiguous bare name
     pub fn get_action(&self, name: &str) -> Option<Arc<dyn Action>> {
-        self.actions.get(name).cloned()
+        self.resolve_name(name)
+            .and_then(|qualified| self.actions.get(&qualified))
+            .map(|entry| entry.action.clone())
+    }
+
+    /// Move every action and alias from `other` into this registry
+    pub fn merge(&mut self, other: ActionRegistry, policy: ConflictPolicy) -> Result<()> {
+        if policy == ConflictPolicy::Error {
+            let taken =
+                |name: &String| self.actions.contains_key(name) || self.aliases.contains_key(name);
+            if let Some(name) = other
+                .actions
+                .keys()
+                .chain(other.aliases.keys())
+                .find(|name| taken(name))
+            {
+                return Err(Self::conflict(name));
+            }
+        }
+
+        for (name, entry) in other.actions {
+            if self.actions.contains_key(&name) && policy == ConflictPolicy::KeepExisting {
+                continue;
+            }
+            self.aliases.remove(&name);
+            self.actions.insert(name, entry);
+        }
+
+        for (alias, target) in other.aliases {
+            let taken = self.actions.contains_key(&alias) || self.aliases.contains_key(&alias);
+            if taken && policy == ConflictPolicy::KeepExisting {
+                continue;
+            }
+            if !self.actions.contains_key(&alias) {
+                self.aliases.insert(alias, target);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List all registered actions, sorted by qualified name
+    pub fn list_actions(&self) -> Vec<ActionEntry> {
+        let mut entries: Vec<ActionEntry> = self
+            .actions
+            .values()
+            .map(|entry| ActionEntry {
+                namespace: entry.namespace.clone(),
+                name: entry.action.name().to_string(),
+                description: entry.action.description().to_string(),
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.qualified_name());
+        entries
     }
 
-    /// List all registered actions
-    pub fn list_actions(&self) -> Vec<String> {
-        self.actions.keys().cloned().collect()
+    /// List all aliases as `(alias, qualified target)` pairs
+    pub fn list_aliases(&self) -> Vec<(String, String)> {
+        let mut aliases: Vec<(String, String)> = self
+            .aliases
+            .iter()
+            .map(|(alias, target)| (alias.clone(), target.clone()))
+            .collect();
+        aliases.sort();
+        aliases
     }
 
     /// Execute an action by name
@@ -38,6 +226,111 @@ impl ActionRegistry {
         name: &str,
         params: serde_json::Value,
         context: &ActionContext,
+    ) -> Result<ActionResult> {
+        let start_time = std::time::Instant::now();
+        let outcome = self.execute_audited(name, params, context).await;
+
+        // Unregistered names are lumped together to keep the label bounded
+        let action = self
+            .resolve_name(name)
+            .filter(|resolved| self.actions.contains_key(resolved))
+            .unwrap_or_else(|| "unknown".to_string());
+        let result = match &outcome {
+            Ok(result) if result.success => "success",
+            Ok(_) => "failure",
+            Err(_) => "error",
+        };
+        crate::utils::metrics::action(&context.session_id, &action, result, start_time.elapsed());
+        outcome
+    }
+
+    async fn execute_audited(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+        context: &ActionContext,
+    ) -> Result<ActionResult> {
+        let Some(audit) = &self.audit else {
+            return self.run_action(name, params, context).await;
+        };
+
+        let logged_params = match self.get_action(name) {
+            Some(action) => self.redacted_params(action.as_ref(), &params),
+            None => params.clone(),
+        };
+        let start_time = std::time::Instant::now();
+        let outcome = self.run_action(name, params, context).await;
+
+        let (success, message, post_url) = match &outcome {
+            Ok(result) => (
+                result.success,
+                result.message.clone(),
+                result
+                    .data
+                    .as_ref()
+                    .and_then(|d| d.get("url"))
+                    .and_then(|u| u.as_str())
+                    .map(|u| u.to_string()),
+            ),
+            Err(e) => (false, e.to_string(), None),
+        };
+        let pre_url = context.browser_state.as_ref().map(|s| s.url.clone());
+
+        audit.record(AuditRecord {
+            session_id: &context.session_id,
+            action: &self.resolve_name(name).unwrap_or_else(|| name.to_string()),
+            params: logged_params,
+            success,
+            duration_ms: start_time.elapsed().as_millis() as u64,
+            message,
+            post_url: post_url.or_else(|| pre_url.clone()),
+            pre_url,
+        })?;
+
+        outcome
+    }
+
+    /// `params` as the audit log records them. Properties the action's schema marks
+    /// secret are masked, and so are those of nested `{ action, params }` children
+    /// such as sequence steps, by the child action's own schema.
+    fn redacted_params(
+        &self,
+        action: &dyn Action,
+        params: &serde_json::Value,
+    ) -> serde_json::Value {
+        let mut redacted = redact_params(params, &action.parameter_schema());
+        self.redact_children(&mut redacted);
+        redacted
+    }
+
+    fn redact_children(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                let child = map
+                    .get("action")
+                    .and_then(|name| name.as_str())
+                    .and_then(|name| self.get_action(name));
+                for (key, nested) in map.iter_mut() {
+                    match &child {
+                        Some(child) if key == "params" => {
+                            *nested = self.redacted_params(child.as_ref(), nested)
+                        }
+                        _ => self.redact_children(nested),
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                items.iter_mut().for_each(|item| self.redact_children(item))
+            }
+            _ => {}
+        }
+    }
+
+    async fn run_action(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+        context: &ActionContext,
     ) -> Result<ActionResult> {
         let action = self.get_action(name).ok_or_else(|| {
             crate::errors::BrowserAgentError::ActionError(ActionError::ActionNotFound(
@@ -45,7 +338,16 @@ impl ActionRegistry {
             ))
         })?;
 
-        // Validate parameters
+        // Fill in variable references, then validate against the declared schema and
+        // any custom checks
+        let params = if action.templates_params() {
+            context.render(&params)
+        } else {
+            params
+        };
+        validate_against_schema(&params, &action.parameter_schema()).map_err(|e| {
+            crate::errors::BrowserAgentError::ActionError(ActionError::InvalidParameters(e))
+        })?;
         action.validate_params(&params).map_err(|e| {
             crate::errors::BrowserAgentError::ActionError(ActionError::InvalidParameters(
                 e.to_string(),
@@ -54,7 +356,22 @@ impl ActionRegistry {
 
         // Execute action with timing
         let start_time = std::time::Instant::now();
-        let result = action.execute(params, context).await?;
+        let result = if context.dry_run {
+            run_cancellable(
+                &context.cancel_token,
+                action.name(),
+                action.dry_run(params, context),
+            )
+            .await?
+            .into_dry_run()
+        } else {
+            run_cancellable(
+                &context.cancel_token,
+                action.name(),
+                action.execute(params, context),
+            )
+            .await?
+        };
         let execution_time = start_time.elapsed().as_millis() as u64;
 
         Ok(result.with_execution_time(execution_time))
@@ -62,36 +379,257 @@ impl ActionRegistry {
 
     /// Get action metadata
     pub fn get_action_metadata(&self, name: &str) -> Option<ActionMetadata> {
-        self.get_action(name).map(|action| ActionMetadata {
-            name: action.name().to_string(),
-            description: action.description().to_string(),
-            parameter_schema: action.parameter_schema(),
+        let qualified = self.resolve_name(name)?;
+        self.actions.get(&qualified).map(|entry| ActionMetadata {
+            name: qualified.clone(),
+            description: entry.action.description().to_string(),
+            parameter_schema: entry.action.parameter_schema(),
+            output_schema: entry.action.output_schema(),
         })
     }
 
     /// Get metadata for all actions
     pub fn get_all_metadata(&self) -> Vec<ActionMetadata> {
         self.actions
-            .values()
-            .map(|action| ActionMetadata {
-                name: action.name().to_string(),
-                description: action.description().to_string(),
-                parameter_schema: action.parameter_schema(),
+            .iter()
+            .map(|(qualified, entry)| ActionMetadata {
+                name: qualified.clone(),
+                description: entry.action.description().to_string(),
+                parameter_schema: entry.action.parameter_schema(),
+                output_schema: entry.action.output_schema(),
             })
             .collect()
     }
 }
 
+impl std::fmt::Debug for ActionRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<&String> = self.actions.keys().collect();
+        names.sort();
+        f.debug_struct("ActionRegistry")
+            .field("actions", &names)
+            .field("aliases", &self.list_aliases())
+            .finish()
+    }
+}
+
 impl Default for ActionRegistry {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// A registered action as reported by `list_actions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionEntry {
+    pub namespace: Option<String>,
+    pub name: String,
+    pub description: String,
+}
+
+impl ActionEntry {
+    /// `namespace.name`, or the bare name for root-level actions
+    pub fn qualified_name(&self) -> String {
+        ActionRegistry::qualified_name(self.namespace.as_deref(), &self.name)
+    }
+}
+
 /// Metadata about an action
 #[derive(Debug, Clone)]
 pub struct ActionMetadata {
     pub name: String,
     pub description: String,
     pub parameter_schema: serde_json::Value,
+    pub output_schema: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Action whose description tells registrations of the same name apart
+    struct Stub {
+        name: &'static str,
+        description: &'static str,
+    }
+
+    #[async_trait]
+    impl Action for Stub {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            self.description
+        }
+
+        fn parameter_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(
+            &self,
+            _params: serde_json::Value,
+            _context: &ActionContext,
+        ) -> Result<ActionResult> {
+            Ok(ActionResult::success(self.description.to_string()))
+        }
+    }
+
+    fn stub(name: &'static str, description: &'static str) -> Stub {
+        Stub { name, description }
+    }
+
+    fn description(registry: &ActionRegistry, name: &str) -> Option<String> {
+        registry
+            .get_action(name)
+            .map(|action| action.description().to_string())
+    }
+
+    fn is_conflict(result: Result<()>) -> bool {
+        matches!(
+            result,
+            Err(BrowserAgentError::ActionError(ActionError::Conflict(_)))
+        )
+    }
+
+    #[test]
+    fn register_replaces_and_try_register_refuses() {
+        let mut registry = ActionRegistry::new();
+        registry.register(stub("click", "first"));
+        registry.register(stub("click", "second"));
+        assert_eq!(description(&registry, "click").as_deref(), Some("second"));
+
+        assert!(is_conflict(registry.try_register(stub("click", "third"))));
+        assert_eq!(description(&registry, "click").as_deref(), Some("second"));
+        assert!(registry
+            .try_register_in("dom", stub("click", "dom"))
+            .is_ok());
+        assert!(is_conflict(
+            registry.try_register_in("dom", stub("click", "again"))
+        ));
+    }
+
+    #[test]
+    fn bare_names_resolve_only_when_unambiguous() {
+        let mut registry = ActionRegistry::new();
+        registry.register_in("dom", stub("click", "dom"));
+        assert_eq!(registry.resolve_name("click").as_deref(), Some("dom.click"));
+        assert_eq!(description(&registry, "dom.click").as_deref(), Some("dom"));
+
+        registry.register_in("canvas", stub("click", "canvas"));
+        assert_eq!(registry.resolve_name("click"), None);
+        assert_eq!(
+            description(&registry, "canvas.click").as_deref(),
+            Some("canvas")
+        );
+
+        // A root-level action shadows the namespaced ones for the bare name
+        registry.register(stub("click", "root"));
+        assert_eq!(description(&registry, "click").as_deref(), Some("root"));
+        assert_eq!(registry.resolve_name("dom.missing"), None);
+    }
+
+    #[test]
+    fn aliases_resolve_and_yield_to_actions() {
+        let mut registry = ActionRegistry::new();
+        registry.register_in("dom", stub("click", "dom"));
+        registry.alias("press", "dom.click").unwrap();
+        assert_eq!(registry.resolve_name("press").as_deref(), Some("dom.click"));
+        assert_eq!(description(&registry, "press").as_deref(), Some("dom"));
+
+        assert!(is_conflict(registry.alias("press", "dom.click")));
+        assert!(is_conflict(registry.alias("dom.click", "press")));
+        assert!(matches!(
+            registry.alias("tap", "missing"),
+            Err(BrowserAgentError::ActionError(ActionError::ActionNotFound(
+                _
+            )))
+        ));
+        // An alias blocks try_register but not register, which takes its name over
+        assert!(is_conflict(registry.try_register(stub("press", "real"))));
+        registry.register(stub("press", "real"));
+        assert_eq!(description(&registry, "press").as_deref(), Some("real"));
+        assert!(registry.list_aliases().is_empty());
+    }
+
+    fn pair() -> (ActionRegistry, ActionRegistry) {
+        let mut ours = ActionRegistry::new();
+        ours.register_in("dom", stub("click", "ours"));
+        ours.register(stub("wait", "ours"));
+        let mut theirs = ActionRegistry::new();
+        theirs.register_in("dom", stub("click", "theirs"));
+        theirs.register(stub("scroll", "theirs"));
+        theirs.alias("wait", "scroll").unwrap();
+        (ours, theirs)
+    }
+
+    #[test]
+    fn merge_keeping_existing_adds_only_new_names() {
+        let (mut ours, theirs) = pair();
+        ours.merge(theirs, ConflictPolicy::KeepExisting).unwrap();
+        assert_eq!(description(&ours, "dom.click").as_deref(), Some("ours"));
+        assert_eq!(description(&ours, "wait").as_deref(), Some("ours"));
+        assert_eq!(description(&ours, "scroll").as_deref(), Some("theirs"));
+        assert!(ours.list_aliases().is_empty());
+    }
+
+    #[test]
+    fn merge_replacing_takes_incoming_actions() {
+        let (mut ours, theirs) = pair();
+        ours.merge(theirs, ConflictPolicy::Replace).unwrap();
+        assert_eq!(description(&ours, "dom.click").as_deref(), Some("theirs"));
+        // An incoming alias never hides an action of the same name
+        assert_eq!(description(&ours, "wait").as_deref(), Some("ours"));
+        assert_eq!(ours.list_actions().len(), 3);
+    }
+
+    #[test]
+    fn merge_with_error_policy_changes_nothing_on_conflict() {
+        let (mut ours, theirs) = pair();
+        assert!(is_conflict(ours.merge(theirs, ConflictPolicy::Error)));
+        assert_eq!(description(&ours, "dom.click").as_deref(), Some("ours"));
+        assert_eq!(description(&ours, "scroll"), None);
+
+        let mut other = ActionRegistry::new();
+        other.register_in("net", stub("fetch", "net"));
+        ours.merge(other, ConflictPolicy::Error).unwrap();
+        assert_eq!(ours.resolve_name("fetch").as_deref(), Some("net.fetch"));
+    }
+
+    #[test]
+    fn list_actions_is_structured_and_sorted() {
+        let mut registry = ActionRegistry::new();
+        registry.register(stub("wait", "Wait"));
+        registry.register_in("dom", stub("click", "Click"));
+        let entries = registry.list_actions();
+        assert_eq!(
+            entries[0],
+            ActionEntry {
+                namespace: Some("dom".to_string()),
+                name: "click".to_string(),
+                description: "Click".to_string(),
+            }
+        );
+        let names: Vec<_> = entries.iter().map(ActionEntry::qualified_name).collect();
+        assert_eq!(names, ["dom.click", "wait"]);
+    }
+
+    #[tokio::test]
+    async fn execute_action_runs_the_resolved_action() {
+        let mut registry = ActionRegistry::new();
+        registry.register_in("dom", stub("click", "clicked"));
+        registry.alias("press", "click").unwrap();
+        let context = ActionContext::new("s1".to_string());
+        let result = registry
+            .execute_action("press", serde_json::json!({}), &context)
+            .await
+            .unwrap();
+        assert_eq!(result.message, "clicked");
+        assert!(registry
+            .execute_action("missing", serde_json::json!({}), &context)
+            .await
+            .is_err());
+    }
 }
@@ -0,0 +1,219 @@
+use serde_json::Value;
+
+/// Check `params` against an action's JSON Schema.
+///
+/// Supports the subset used by action schemas: `type` (a name or a list of names),
+/// `required`, `properties`, `additionalProperties: false`, `items`, `enum`,
+/// `minimum`/`maximum`, `minLength`/`maxLength` and `minItems`/`maxItems`. Other
+/// keywords are ignored. The error names the offending path, e.g.
+/// `params.selector: expected string, got number`.
+pub fn validate_against_schema(params: &Value, schema: &Value) -> Result<(), String> {
+    validate_at("params", params, schema)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "number" => value.is_number(),
+        "integer" => match value {
+            Value::Number(n) => {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            _ => false,
+        },
+        other => type_name(value) == other,
+    }
+}
+
+fn validate_at(path: &str, value: &Value, schema: &Value) -> Result<(), String> {
+    let fail = |message: String| Err(format!("{}: {}", path, message));
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(|n| n.as_str()).collect(),
+            _ => vec![],
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| type_matches(t, value)) {
+            return fail(format!(
+                "expected {}, got {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(|v| v.as_array()) {
+        if !options.contains(value) {
+            let listed: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+            return fail(format!(
+                "expected one of [{}], got {}",
+                listed.join(", "),
+                value
+            ));
+        }
+    }
+
+    match value {
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or(0.0);
+            if let Some(min) = schema.get("minimum").and_then(|v| v.as_f64()) {
+                if n < min {
+                    return fail(format!("must be at least {}, got {}", min, n));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(|v| v.as_f64()) {
+                if n > max {
+                    return fail(format!("must be at most {}, got {}", max, n));
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64()) {
+                if len < min {
+                    return fail(format!("must be at least {} characters", min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(|v| v.as_u64()) {
+                if len > max {
+                    return fail(format!("must be at most {} characters", max));
+                }
+            }
+        }
+        Value::Array(items) => {
+            let len = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64()) {
+                if len < min {
+                    return fail(format!("must have at least {} items", min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64()) {
+                if len > max {
+                    return fail(format!("must have at most {} items", max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(&format!("{}[{}]", path, index), item, item_schema)?;
+                }
+            }
+        }
+        Value::Object(map) => {
+            if let Some(required) = schema.get("required").and_then(|v| v.as_array()) {
+                for key in required.iter().filter_map(|k| k.as_str()) {
+                    if !map.contains_key(key) {
+                        return Err(format!("{}.{}: required property missing", path, key));
+                    }
+                }
+            }
+
+            let properties = schema.get("properties").and_then(|v| v.as_object());
+            for (key, item) in map {
+                let child_path = format!("{}.{}", path, key);
+                match properties.and_then(|p| p.get(key)) {
+                    Some(property) => validate_at(&child_path, item, property)?,
+                    None => {
+                        if schema.get("additionalProperties") == Some(&Value::Bool(false)) {
+                            return Err(format!("{}: unknown property", child_path));
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn click_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["selector"],
+            "additionalProperties": false,
+            "properties": {
+                "selector": { "type": "string", "minLength": 1 },
+                "button": { "enum": ["left", "right", "middle"] },
+                "count": { "type": "integer", "minimum": 1, "maximum": 3 },
+                "offset": { "type": ["array", "null"], "items": { "type": "number" }, "maxItems": 2 }
+            }
+        })
+    }
+
+    #[test]
+    fn valid_params_pass() {
+        let params = json!({ "selector": "#go", "button": "left", "count": 2, "offset": [1.5, 2] });
+        assert_eq!(validate_against_schema(&params, &click_schema()), Ok(()));
+        let params = json!({ "selector": "#go", "offset": null, "count": 2.0 });
+        assert_eq!(validate_against_schema(&params, &click_schema()), Ok(()));
+    }
+
+    #[test]
+    fn errors_name_the_offending_path() {
+        let schema = click_schema();
+        let check = |params: Value| validate_against_schema(&params, &schema).unwrap_err();
+
+        assert_eq!(
+            check(json!({})),
+            "params.selector: required property missing"
+        );
+        assert_eq!(
+            check(json!({ "selector": 5 })),
+            "params.selector: expected string, got number"
+        );
+        assert_eq!(
+            check(json!({ "selector": "" })),
+            "params.selector: must be at least 1 characters"
+        );
+        assert_eq!(
+            check(json!({ "selector": "a", "count": 1.5 })),
+            "params.count: expected integer, got number"
+        );
+        assert_eq!(
+            check(json!({ "selector": "a", "count": 4 })),
+            "params.count: must be at most 3, got 4"
+        );
+        assert_eq!(
+            check(json!({ "selector": "a", "button": "back" })),
+            "params.button: expected one of [\"left\", \"right\", \"middle\"], got \"back\""
+        );
+        assert_eq!(
+            check(json!({ "selector": "a", "offset": [1, "2"] })),
+            "params.offset[1]: expected number, got string"
+        );
+        assert_eq!(
+            check(json!({ "selector": "a", "offset": [1, 2, 3] })),
+            "params.offset: must have at most 2 items"
+        );
+        assert_eq!(
+            check(json!({ "selector": "a", "force": true })),
+            "params.force: unknown property"
+        );
+        assert_eq!(check(json!([])), "params: expected object, got array");
+    }
+
+    #[test]
+    fn unknown_keywords_and_extra_properties_are_allowed_by_default() {
+        let schema = json!({ "type": "object", "properties": {}, "pattern": "^x$" });
+        assert_eq!(
+            validate_against_schema(&json!({ "extra": 1 }), &schema),
+            Ok(())
+        );
+    }
+}
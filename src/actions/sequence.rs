@@ -0,0 +1,460 @@
+use crate::actions::base::ActionContext;
+use crate::actions::{Action, ActionError, ActionResult};
+use crate::errors::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// What a sequence does when a step fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// Stop at the first failing step
+    #[default]
+    Abort,
+    /// Record the failure and run the remaining steps
+    Continue,
+}
+
+/// One step of a `SequenceAction`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceStep {
+    /// Registered action name (qualified name, alias, or bare name)
+    pub action: String,
+    /// Left out, the step runs with empty params
+    #[serde(default = "crate::actions::control::empty_params")]
+    pub params: Value,
+    /// Step name; the step's output is stored under it unless `store_as` is set
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Store the step's `ActionResult.data` under this variable name
+    #[serde(default)]
+    pub store_as: Option<String>,
+    /// Overrides the sequence-wide failure policy for this step
+    #[serde(default)]
+    pub on_failure: Option<FailurePolicy>,
+}
+
+impl SequenceStep {
+    /// Variable the step's output is stored under
+    pub fn output_name(&self) -> Option<&String> {
+        self.store_as.as_ref().or(self.name.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SequenceParams {
+    steps: Vec<SequenceStep>,
+    #[serde(default)]
+    on_failure: FailurePolicy,
+}
+
+/// Runs a list of registered actions in order as a single action.
+///
+/// Each successful step's `ActionResult.data` becomes a variable named by the step's
+/// `store_as` or `name`, so later steps can reference it as `{{name}}` or `${name}`;
+/// dotted paths such as `{{order.id}}` index into JSON values. The registry fills in
+/// those references as each step runs. Steps are resolved through
+/// `ActionContext.registry`.
+pub struct SequenceAction;
+
+impl SequenceAction {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SequenceAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Action for SequenceAction {
+    fn name(&self) -> &str {
+        "sequence"
+    }
+
+    fn description(&self) -> &str {
+        "Run a list of actions in order, passing variables between steps"
+    }
+
+    fn parameter_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "required": ["steps"],
+            "properties": {
+                "steps": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["action"],
+                        "properties": {
+                            "action": { "type": "string" },
+                            "params": { "type": "object" },
+                            "name": { "type": "string" },
+                            "store_as": { "type": "string" },
+                            "on_failure": { "type": "string", "enum": ["abort", "continue"] }
+                        }
+                    }
+                },
+                "on_failure": { "type": "string", "enum": ["abort", "continue"], "default": "abort" }
+            }
+        })
+    }
+
+    /// Step params are filled in step by step, once earlier steps have stored their
+    /// outputs
+    fn templates_params(&self) -> bool {
+        false
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<()> {
+        let parsed: SequenceParams = serde_json::from_value(params.clone())
+            .map_err(|e| ActionError::InvalidParameters(e.to_string()))?;
+        if parsed.steps.is_empty() {
+            return Err(ActionError::InvalidParameters("sequence has no steps".to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Steps run as dry runs in turn, each seeing the outputs earlier ones stored
+    async fn dry_run(&self, params: Value, context: &ActionContext) -> Result<ActionResult> {
+        self.execute(params, context).await
+    }
+
+    async fn execute(&self, params: Value, context: &ActionContext) -> Result<ActionResult> {
+        let parsed: SequenceParams = serde_json::from_value(params)
+            .map_err(|e| ActionError::InvalidParameters(e.to_string()))?;
+        let registry = context.require_registry()?;
+
+        let start_time = std::time::Instant::now();
+        let mut step_context = context.nested()?;
+        let mut steps = Vec::with_capacity(parsed.steps.len());
+        let mut failed = 0;
+        let mut aborted = false;
+
+        for (index, step) in parsed.steps.iter().enumerate() {
+            if step_context.cancel_token.is_cancelled() {
+                return Err(crate::errors::BrowserAgentError::Cancelled(format!(
+                    "sequence before step {}",
+                    index
+                )));
+            }
+            let outcome = registry
+                .execute_action(&step.action, step.params.clone(), &step_context)
+                .await;
+
+            let (result, error) = match outcome {
+                Ok(result) => (Some(result), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            let succeeded = result.as_ref().map(|r| r.success).unwrap_or(false);
+
+            if let (Some(name), Some(result)) = (step.output_name(), &result) {
+                step_context.set_var(name, result.data.clone().unwrap_or(Value::Null));
+            }
+
+            steps.push(json!({
+                "index": index,
+                "action": step.action,
+                "success": succeeded,
+                "result": result,
+                "error": error,
+            }));
+
+            if !succeeded {
+                failed += 1;
+                if step.on_failure.unwrap_or(parsed.on_failure) == FailurePolicy::Abort {
+                    aborted = true;
+                    break;
+                }
+            }
+        }
+
+        let total_time_ms = start_time.elapsed().as_millis() as u64;
+        let stored: HashMap<&String, &Value> = parsed
+            .steps
+            .iter()
+            .filter_map(|s| s.output_name())
+            .filter_map(|name| step_context.variables.get(name).map(|v| (name, v)))
+            .collect();
+        let data = json!({
+            "steps": steps,
+            "completed": steps.len(),
+            "failed": failed,
+            "aborted": aborted,
+            "total_time_ms": total_time_ms,
+            "variables": stored,
+        });
+
+        let result = if failed == 0 {
+            ActionResult::success_with_data(
+                format!("Sequence completed {} steps", steps.len()),
+                data,
+            )
+        } else {
+            ActionResult {
+                success: false,
+                message: format!(
+                    "Sequence had {} failed step(s){}",
+                    failed,
+                    if aborted { ", aborted" } else { "" }
+                ),
+                data: Some(data),
+                execution_time_ms: 0,
+            }
+        };
+
+        Ok(result.with_execution_time(total_time_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::ActionRegistry;
+    use std::sync::Arc;
+
+    /// Returns its params as data, failing when they say `"fail": true`
+    struct Echo;
+
+    #[async_trait]
+    impl Action for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Return the params"
+        }
+
+        fn parameter_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+
+        async fn execute(&self, params: Value, _context: &ActionContext) -> Result<ActionResult> {
+            if params.get("fail") == Some(&Value::Bool(true)) {
+                return Ok(ActionResult::failure("asked to fail".to_string()));
+            }
+            Ok(ActionResult::success_with_data(
+                "echoed".to_string(),
+                params,
+            ))
+        }
+    }
+
+    fn context() -> ActionContext {
+        let mut registry = ActionRegistry::new();
+        registry.register(SequenceAction::new());
+        registry.register(Echo);
+        ActionContext::new("s1".to_string()).with_registry(Arc::new(registry))
+    }
+
+    async fn run(params: Value) -> ActionResult {
+        let context = context();
+        context
+            .require_registry()
+            .unwrap()
+            .execute_action("sequence", params, &context)
+            .await
+            .unwrap()
+    }
+
+    fn data(result: &ActionResult) -> &Value {
+        result.data.as_ref().unwrap()
+    }
+
+    #[tokio::test]
+    async fn steps_see_earlier_outputs() {
+        let result = run(json!({
+            "steps": [
+                { "action": "echo", "params": { "id": "A1" }, "store_as": "order" },
+                { "action": "echo", "params": { "ref": "{{order.id}}" }, "name": "confirm" }
+            ]
+        }))
+        .await;
+        assert!(result.success);
+        assert_eq!(data(&result)["completed"], 2);
+        assert_eq!(data(&result)["variables"]["confirm"]["ref"], "A1");
+        assert_eq!(data(&result)["steps"][1]["result"]["data"]["ref"], "A1");
+    }
+
+    #[tokio::test]
+    async fn nested_sequences_share_variables_both_ways() {
+        let result = run(json!({
+            "steps": [
+                { "action": "echo", "params": { "id": "A1" }, "store_as": "order" },
+                {
+                    "action": "sequence",
+                    "name": "inner",
+                    "params": {
+                        "steps": [
+                            { "action": "echo", "params": { "ref": "${order.id}" }, "name": "lookup" }
+                        ]
+                    }
+                },
+                { "action": "echo", "params": { "found": "{{inner.variables.lookup.ref}}" }, "name": "last" }
+            ]
+        }))
+        .await;
+        assert!(result.success, "{}", result.message);
+        let inner = &data(&result)["steps"][1]["result"]["data"];
+        assert_eq!(inner["completed"], 1);
+        assert_eq!(data(&result)["variables"]["last"]["found"], "A1");
+    }
+
+    #[tokio::test]
+    async fn failing_nested_sequence_fails_its_step() {
+        let result = run(json!({
+            "steps": [
+                { "action": "sequence", "params": { "steps": [
+                    { "action": "echo", "params": { "fail": true } }
+                ] } },
+                { "action": "echo" }
+            ]
+        }))
+        .await;
+        assert!(!result.success);
+        assert_eq!(data(&result)["completed"], 1);
+        assert_eq!(data(&result)["aborted"], true);
+    }
+
+    fn failing_middle(on_failure: &str, middle_override: Option<&str>) -> Value {
+        let mut middle = json!({ "action": "echo", "params": { "fail": true } });
+        if let Some(policy) = middle_override {
+            middle["on_failure"] = json!(policy);
+        }
+        json!({
+            "on_failure": on_failure,
+            "steps": [
+                { "action": "echo", "params": { "n": 1 } },
+                middle,
+                { "action": "echo", "params": { "n": 3 } }
+            ]
+        })
+    }
+
+    #[tokio::test]
+    async fn failing_middle_step_aborts_by_default() {
+        let result = run(failing_middle("abort", None)).await;
+        assert!(!result.success);
+        assert_eq!(data(&result)["completed"], 2);
+        assert_eq!(data(&result)["failed"], 1);
+        assert_eq!(data(&result)["aborted"], true);
+        assert_eq!(data(&result)["steps"][1]["success"], false);
+    }
+
+    #[tokio::test]
+    async fn failing_middle_step_continues_when_asked() {
+        for params in [
+            failing_middle("continue", None),
+            failing_middle("abort", Some("continue")),
+        ] {
+            let result = run(params).await;
+            assert!(!result.success);
+            assert_eq!(data(&result)["completed"], 3);
+            assert_eq!(data(&result)["failed"], 1);
+            assert_eq!(data(&result)["aborted"], false);
+            assert_eq!(data(&result)["steps"][2]["result"]["data"]["n"], 3);
+        }
+
+        let result = run(failing_middle("continue", Some("abort"))).await;
+        assert_eq!(data(&result)["completed"], 2);
+    }
+
+    #[tokio::test]
+    async fn step_without_params_gets_empty_ones() {
+        let result = run(json!({ "steps": [{ "action": "echo" }] })).await;
+        assert!(result.success, "{}", result.message);
+        assert_eq!(data(&result)["steps"][0]["result"]["data"], json!({}));
+    }
+
+    #[tokio::test]
+    async fn unknown_step_is_a_failed_step() {
+        let result = run(json!({ "steps": [{ "action": "missing" }] })).await;
+        assert!(!result.success);
+        assert!(data(&result)["steps"][0]["error"]
+            .as_str()
+            .unwrap()
+            .contains("missing"));
+    }
+
+    /// Takes a password its schema marks secret
+    struct Login;
+
+    #[async_trait]
+    impl Action for Login {
+        fn name(&self) -> &str {
+            "login"
+        }
+
+        fn description(&self) -> &str {
+            "Sign in"
+        }
+
+        fn parameter_schema(&self) -> Value {
+            json!({
+                "type": "object",
+                "properties": {
+                    "user": { "type": "string" },
+                    "password": { "type": "string", "format": "password" }
+                }
+            })
+        }
+
+        async fn execute(&self, _params: Value, _context: &ActionContext) -> Result<ActionResult> {
+            Ok(ActionResult::success("signed in".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn audit_masks_secret_step_params_by_the_step_schema() {
+        let mut registry = ActionRegistry::new()
+            .with_audit(crate::actions::audit::MemoryAuditSink::new(10))
+            .unwrap();
+        registry.register(SequenceAction::new());
+        registry.register(Login);
+        let registry = Arc::new(registry);
+        let context = ActionContext::new("s1".to_string()).with_registry(registry.clone());
+
+        let login =
+            json!({ "action": "login", "params": { "user": "ada", "password": "hunter2" } });
+        let result = registry
+            .execute_action(
+                "sequence",
+                json!({ "steps": [login, { "action": "sequence", "params": { "steps": [login] } }] }),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(result.success, "{}", result.message);
+
+        let entries = registry
+            .audit_log()
+            .unwrap()
+            .query(&Default::default())
+            .unwrap();
+        assert_eq!(entries.len(), 4);
+        for entry in &entries {
+            let logged = serde_json::to_string(entry).unwrap();
+            assert!(!logged.contains("hunter2"), "{}", logged);
+        }
+        let outer = &entries.last().unwrap().params;
+        assert_eq!(outer["steps"][0]["params"]["user"], "ada");
+        assert_eq!(outer["steps"][0]["params"]["password"], "[REDACTED]");
+        assert_eq!(
+            outer["steps"][1]["params"]["steps"][0]["params"]["password"],
+            "[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn empty_sequence_is_rejected() {
+        assert!(SequenceAction::new()
+            .validate_params(&json!({ "steps": [] }))
+            .is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Replace `{{name}}` and `${name}` references in every string of `value`.
+///
+/// A string that is exactly one reference takes the variable's JSON value as-is;
+/// references embedded in longer strings are interpolated as text. Dotted paths
+/// such as `{{order.id}}` or `{{items.0}}` index into JSON values. Unknown
+/// references are left untouched.
+pub fn substitute_variables(value: &Value, variables: &HashMap<String, Value>) -> Value {
+    match value {
+        Value::String(text) => substitute_string(text, variables),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| substitute_variables(item, variables))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), substitute_variables(item, variables)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// `{{name}}` or `${name}`, capturing the name in group 1 or 2
+fn reference_pattern() -> &'static regex::Regex {
+    static PATTERN: OnceLock<regex::Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        regex::Regex::new(r"\{\{\s*([A-Za-z0-9_.\-]+)\s*\}\}|\$\{([A-Za-z0-9_.\-]+)\}").unwrap()
+    })
+}
+
+fn substitute_string(text: &str, variables: &HashMap<String, Value>) -> Value {
+    let pattern = reference_pattern();
+    let name = |captures: &regex::Captures| {
+        captures
+            .get(1)
+            .or_else(|| captures.get(2))
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default()
+    };
+
+    if let Some(captures) = pattern.captures(text) {
+        if captures.get(0).map(|m| m.as_str()) == Some(text) {
+            if let Some(found) = lookup_variable(&name(&captures), variables) {
+                return found.clone();
+            }
+        }
+    }
+
+    let replaced = pattern.replace_all(text, |captures: &regex::Captures| {
+        match lookup_variable(&name(captures), variables) {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => captures[0].to_string(),
+        }
+    });
+    Value::String(replaced.into_owned())
+}
+
+/// Value at a dotted `path` such as `order.items.0.id`
+pub(crate) fn lookup_variable<'a>(
+    path: &str,
+    variables: &'a HashMap<String, Value>,
+) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut current = variables.get(parts.next()?)?;
+    for part in parts {
+        current = match current {
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            other => other.get(part)?,
+        };
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::base::ActionContext;
+    use crate::actions::{Action, ActionRegistry, ActionResult, SequenceAction};
+    use crate::errors::Result;
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    fn variables() -> HashMap<String, Value> {
+        HashMap::from([
+            ("user".to_string(), json!("ada")),
+            ("count".to_string(), json!(3)),
+            (
+                "order".to_string(),
+                json!({ "id": "A-17", "items": [{ "sku": "x1" }, { "sku": "y2" }] }),
+            ),
+        ])
+    }
+
+    #[test]
+    fn whole_reference_keeps_the_json_type() {
+        let vars = variables();
+        assert_eq!(substitute_variables(&json!("{{count}}"), &vars), json!(3));
+        assert_eq!(substitute_variables(&json!("${count}"), &vars), json!(3));
+        assert_eq!(
+            substitute_variables(&json!("{{ order.items }}"), &vars),
+            json!([{ "sku": "x1" }, { "sku": "y2" }])
+        );
+    }
+
+    #[test]
+    fn embedded_references_are_interpolated_as_text() {
+        let vars = variables();
+        assert_eq!(
+            substitute_variables(&json!("hi {{user}}, {{count}} items in ${order.id}"), &vars),
+            json!("hi ada, 3 items in A-17")
+        );
+    }
+
+    #[test]
+    fn dotted_paths_index_objects_and_arrays() {
+        let vars = variables();
+        assert_eq!(
+            substitute_variables(&json!("{{order.items.1.sku}}"), &vars),
+            json!("y2")
+        );
+        assert_eq!(lookup_variable("order.items.5", &vars), None);
+        assert_eq!(lookup_variable("order.items.first", &vars), None);
+    }
+
+    #[test]
+    fn unknown_references_are_left_untouched() {
+        let vars = variables();
+        assert_eq!(
+            substitute_variables(&json!("{{missing}}"), &vars),
+            json!("{{missing}}")
+        );
+        assert_eq!(
+            substitute_variables(&json!("a {{missing}} b ${user}"), &vars),
+            json!("a {{missing}} b ada")
+        );
+    }
+
+    /// Stands in for a text extraction: returns `{ "text": <params.text> }`
+    struct ExtractText;
+
+    /// Stands in for typing: records the text it was asked to type
+    struct Type(Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl Action for ExtractText {
+        fn name(&self) -> &str {
+            "extract_text"
+        }
+
+        fn description(&self) -> &str {
+            "Extract text"
+        }
+
+        fn parameter_schema(&self) -> Value {
+            json!({ "type": "object", "required": ["selector"] })
+        }
+
+        async fn execute(&self, params: Value, _context: &ActionContext) -> Result<ActionResult> {
+            Ok(ActionResult::success_with_data(
+                "extracted".to_string(),
+                json!({ "text": params["text"] }),
+            ))
+        }
+    }
+
+    #[async_trait]
+    impl Action for Type {
+        fn name(&self) -> &str {
+            "type"
+        }
+
+        fn description(&self) -> &str {
+            "Type text"
+        }
+
+        fn parameter_schema(&self) -> Value {
+            json!({
+                "type": "object",
+                "required": ["selector", "text"],
+                "properties": { "text": { "type": "string" } }
+            })
+        }
+
+        async fn execute(&self, params: Value, _context: &ActionContext) -> Result<ActionResult> {
+            let text = params["text"].as_str().unwrap_or_default().to_string();
+            self.0.lock().unwrap().push(text);
+            Ok(ActionResult::success("typed".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn extracted_text_feeds_a_later_type_step() {
+        let typed = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = ActionRegistry::new();
+        registry.register(SequenceAction::new());
+        registry.register(ExtractText);
+        registry.register(Type(typed.clone()));
+        let registry = Arc::new(registry);
+        let context = ActionContext::new("s1".to_string()).with_registry(registry.clone());
+
+        let result = registry
+            .execute_action(
+                "sequence",
+                json!({
+                    "steps": [
+                        { "action": "extract_text", "params": { "selector": "h1", "text": "Order A-17" }, "name": "heading" },
+                        { "action": "type", "params": { "selector": "#search", "text": "{{heading.text}}" } },
+                        { "action": "type", "params": { "selector": "#note", "text": "re: ${heading.text}!" } }
+                    ]
+                }),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(*typed.lock().unwrap(), ["Order A-17", "re: Order A-17!"]);
+
+        // Outside a sequence the step reads what the caller set on the context
+        let mut context = context;
+        context.set_var("heading", json!({ "text": "Direct" }));
+        registry
+            .execute_action(
+                "type",
+                json!({ "selector": "#q", "text": "{{heading.text}}" }),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            typed.lock().unwrap().last().map(String::as_str),
+            Some("Direct")
+        );
+    }
+
+    #[test]
+    fn nested_values_are_substituted() {
+        let vars = variables();
+        assert_eq!(
+            substitute_variables(
+                &json!({ "selector": "#{{user}}", "values": ["{{count}}", true, null] }),
+                &vars
+            ),
+            json!({ "selector": "#ada", "values": [3, true, null] })
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A precondition an element must meet before it is clicked or typed into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionabilityCheck {
+    /// Present and connected to the document
+    Attached,
+    /// Has a box and is not hidden by `display`, `visibility` or `opacity`
+    Visible,
+    /// Not `disabled`, inside a disabled fieldset, or `aria-disabled`
+    Enabled,
+    /// Not `readonly`; only checked before typing
+    Editable,
+    /// Same bounding box across two animation frames
+    Stable,
+    /// Would be the target of a click at one of its points
+    ReceivesEvents,
+}
+
+impl std::fmt::Display for ActionabilityCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ActionabilityCheck::Attached => "attached",
+            ActionabilityCheck::Visible => "visible",
+            ActionabilityCheck::Enabled => "enabled",
+            ActionabilityCheck::Editable => "editable",
+            ActionabilityCheck::Stable => "stable",
+            ActionabilityCheck::ReceivesEvents => "receives events",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckOutcome {
+    pub check: ActionabilityCheck,
+    pub passed: bool,
+    /// Why the check failed
+    pub detail: Option<String>,
+}
+
+/// Result of `BrowserSession::check_actionable`. Checks run in order and stop at
+/// the first failure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionabilityReport {
+    pub selector: String,
+    pub checks: Vec<CheckOutcome>,
+    /// Selector of the element that would receive the click instead, when the
+    /// `ReceivesEvents` check failed
+    pub covered_by: Option<String>,
+}
+
+impl ActionabilityReport {
+    pub fn is_actionable(&self) -> bool {
+        self.checks.iter().all(|outcome| outcome.passed)
+    }
+
+    pub fn failure(&self) -> Option<&CheckOutcome> {
+        self.checks.iter().find(|outcome| !outcome.passed)
+    }
+
+    pub(crate) fn from_value(selector: &str, value: &Value) -> Self {
+        Self {
+            selector: selector.to_string(),
+            checks: value
+                .get("checks")
+                .cloned()
+                .and_then(|checks| serde_json::from_value(checks).ok())
+                .unwrap_or_default(),
+            covered_by: value
+                .get("covered_by")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Script evaluating to `{ checks, covered_by }` for the element `element_expression`
+/// yields. Scrolls the element into view before the hit test; without `scroll`,
+/// an element out of view skips the hit test instead.
+pub(crate) fn actionability_script(
+    element_expression: &str,
+    editable: bool,
+    scroll: bool,
+) -> String {
+    format!(
+        r#"
+        (async function() {{
+{describe}
+            const checks = [];
+            const pass = (check) => {{ checks.push({{ check, passed: true, detail: null }}); return true; }};
+            const fail = (check, detail, extra) => {{
+                checks.push({{ check, passed: false, detail }});
+                return Object.assign({{ checks }}, extra || {{}});
+            }};
+            const frame = () => new Promise(resolve => requestAnimationFrame(() => resolve()));
+
+            const element = {element};
+            if (!element || !element.isConnected) return fail('attached', 'no element matches');
+            pass('attached');
+
+            const style = window.getComputedStyle(element);
+            const box = element.getBoundingClientRect();
+            if (style.display === 'none') return fail('visible', 'display is none');
+            if (style.visibility !== 'visible') return fail('visible', 'visibility is ' + style.visibility);
+            if (parseFloat(style.opacity) === 0) return fail('visible', 'opacity is 0');
+            if (box.width === 0 || box.height === 0) return fail('visible', 'element has an empty box');
+            pass('visible');
+
+            const disabledControl = 'disabled' in element && element.disabled;
+            const fieldset = element.closest('fieldset[disabled]');
+            const inLegend = fieldset && fieldset.querySelector(':scope > legend')?.contains(element);
+            const ariaDisabled = element.closest('[aria-disabled="true"]');
+            if (disabledControl) return fail('enabled', 'element is disabled');
+            if (fieldset && !inLegend) return fail('enabled', 'inside a disabled fieldset');
+            if (ariaDisabled) return fail('enabled', 'aria-disabled is set' + (ariaDisabled === element ? '' : ' on an ancestor'));
+            pass('enabled');
+
+            if ({editable}) {{
+                const editableField = element.isContentEditable
+                    || ['INPUT', 'TEXTAREA', 'SELECT'].includes(element.tagName);
+                if (!editableField) return fail('editable', element.tagName.toLowerCase() + ' is not a text field');
+                if (element.readOnly) return fail('editable', 'element is readonly');
+                pass('editable');
+            }}
+
+            if ({scroll}) {{
+                element.scrollIntoView({{ behavior: 'instant', block: 'center', inline: 'center' }});
+            }}
+            await frame();
+            const first = element.getBoundingClientRect();
+            await frame();
+            const second = element.getBoundingClientRect();
+            if (first.x !== second.x || first.y !== second.y
+                || first.width !== second.width || first.height !== second.height) {{
+                return fail('stable', 'bounding box moved between animation frames');
+            }}
+            pass('stable');
+            const inView = second.bottom > 0 && second.right > 0
+                && second.top < window.innerHeight && second.left < window.innerWidth;
+            if (!{scroll} && !inView) return {{ checks }};
+
+            const samples = [[0.5, 0.5], [0.25, 0.25], [0.75, 0.25], [0.25, 0.75], [0.75, 0.75]];
+            let covering = null;
+            for (const [fx, fy] of samples) {{
+                const x = second.left + second.width * fx;
+                const y = second.top + second.height * fy;
+                if (x < 0 || y < 0 || x >= window.innerWidth || y >= window.innerHeight) continue;
+                const top = document.elementFromPoint(x, y);
+                if (!top || top === element || element.contains(top) || top.contains(element)) {{
+                    pass('receives_events');
+                    return {{ checks }};
+                }}
+                if (!covering) covering = top;
+            }}
+            if (covering) {{
+                return fail('receives_events', 'covered by ' + cssPath(covering), {{ covered_by: cssPath(covering) }});
+            }}
+            return fail('receives_events', 'element is outside the viewport');
+        }})()
+        "#,
+        describe = crate::dom::xpath::DESCRIBE_ELEMENT_JS,
+        element = element_expression,
+        editable = editable,
+        scroll = scroll
+    )
+}
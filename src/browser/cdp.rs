@@ -0,0 +1,287 @@
+//! Raw Chrome DevTools Protocol access behind `ChromeBrowser::call_method_raw`.
+//!
+//! **Unstable.** Methods and events are passed through as JSON without any checks,
+//! so code built on this breaks whenever Chrome changes the protocol. Use it to
+//! prototype what the crate does not wrap yet.
+//!
+//! Calls go over a websocket connection of their own rather than the one
+//! `headless_chrome` drives the tabs through, so responses and events it does not
+//! know about never reach it. Domains enabled here stay enabled for this
+//! connection only.
+
+use crate::errors::{BrowserAgentError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc as async_mpsc, oneshot};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+/// How long a raw call may take before it fails with `TimeoutError`
+const CALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often the connection thread stops reading to send queued calls
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A protocol event received by a `RawEventSubscription`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawCdpEvent {
+    /// e.g. `Network.requestWillBeSent`
+    pub method: String,
+    pub params: Value,
+    /// Session of the tab the event belongs to; `None` for browser-wide events
+    pub session_id: Option<String>,
+}
+
+/// Events whose method starts with a prefix, from `ChromeBrowser::subscribe_raw`.
+/// Unsubscribes when dropped.
+#[derive(Debug)]
+pub struct RawEventSubscription {
+    events: async_mpsc::UnboundedReceiver<RawCdpEvent>,
+}
+
+impl RawEventSubscription {
+    /// The next matching event; `None` once the connection closed
+    pub async fn next(&mut self) -> Option<RawCdpEvent> {
+        self.events.recv().await
+    }
+
+    /// The next matching event if one arrived within `timeout`
+    pub async fn next_timeout(&mut self, timeout: Duration) -> Option<RawCdpEvent> {
+        tokio::time::timeout(timeout, self.events.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+}
+
+struct Subscriber {
+    prefix: String,
+    session_id: Option<String>,
+    events: async_mpsc::UnboundedSender<RawCdpEvent>,
+}
+
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value>>>>>;
+type Subscribers = Arc<Mutex<Vec<Subscriber>>>;
+
+/// Websocket connection to the browser's debugging endpoint, read and written by
+/// a thread of its own
+pub(crate) struct RawCdpConnection {
+    next_id: AtomicU64,
+    outgoing: Mutex<mpsc::Sender<String>>,
+    pending: Pending,
+    subscribers: Subscribers,
+    /// Session ids from `Target.attachToTarget`, by target id
+    sessions: tokio::sync::Mutex<HashMap<String, String>>,
+}
+
+impl RawCdpConnection {
+    pub(crate) fn connect(ws_url: &str) -> Result<Self> {
+        let (mut socket, _) = tungstenite::connect(ws_url).map_err(|e| {
+            BrowserAgentError::ChromeError(format!("raw protocol connection failed: {}", e))
+        })?;
+        if let MaybeTlsStream::Plain(stream) = socket.get_mut() {
+            stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        }
+
+        let (outgoing, queued) = mpsc::channel::<String>();
+        let pending: Pending = Arc::default();
+        let subscribers: Subscribers = Arc::default();
+        let (thread_pending, thread_subscribers) = (pending.clone(), subscribers.clone());
+        std::thread::Builder::new()
+            .name("surfai-raw-cdp".to_string())
+            .spawn(move || run(socket, queued, thread_pending, thread_subscribers))?;
+
+        Ok(Self {
+            next_id: AtomicU64::new(1),
+            outgoing: Mutex::new(outgoing),
+            pending,
+            subscribers,
+            sessions: tokio::sync::Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Send `method` to the browser, or to the tab `session_id` is attached to,
+    /// and wait for its result
+    pub(crate) async fn call(
+        &self,
+        method: &str,
+        params: Value,
+        session_id: Option<&str>,
+    ) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut message = json!({ "id": id, "method": method, "params": params });
+        if let Some(session_id) = session_id {
+            message["sessionId"] = Value::String(session_id.to_string());
+        }
+
+        let (respond, response) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, respond);
+        let sent = self
+            .outgoing
+            .lock()
+            .unwrap()
+            .send(message.to_string())
+            .is_ok();
+        if !sent {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(closed());
+        }
+
+        match tokio::time::timeout(CALL_TIMEOUT, response).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(closed()),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err(BrowserAgentError::TimeoutError(format!(
+                    "{} got no response within {:?}",
+                    method, CALL_TIMEOUT
+                )))
+            }
+        }
+    }
+
+    /// Session for `target_id` on this connection, attaching on first use
+    pub(crate) async fn session_for(&self, target_id: &str) -> Result<String> {
+        let mut sessions = self.sessions.lock().await;
+        if let Some(session_id) = sessions.get(target_id) {
+            return Ok(session_id.clone());
+        }
+        let attached = self
+            .call(
+                "Target.attachToTarget",
+                json!({ "targetId": target_id, "flatten": true }),
+                None,
+            )
+            .await?;
+        let session_id = attached["sessionId"]
+            .as_str()
+            .ok_or_else(|| {
+                BrowserAgentError::ChromeError(format!("could not attach to target {}", target_id))
+            })?
+            .to_string();
+        sessions.insert(target_id.to_string(), session_id.clone());
+        Ok(session_id)
+    }
+
+    pub(crate) fn subscribe(
+        &self,
+        prefix: &str,
+        session_id: Option<String>,
+    ) -> RawEventSubscription {
+        let (events, receiver) = async_mpsc::unbounded_channel();
+        self.subscribers.lock().unwrap().push(Subscriber {
+            prefix: prefix.to_string(),
+            session_id,
+            events,
+        });
+        RawEventSubscription { events: receiver }
+    }
+}
+
+fn closed() -> BrowserAgentError {
+    BrowserAgentError::ChromeError("raw protocol connection closed".to_string())
+}
+
+/// Shape of every message Chrome sends: a response when `id` is set, an event
+/// otherwise
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Incoming {
+    id: Option<u64>,
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+    result: Option<Value>,
+    error: Option<Value>,
+    session_id: Option<String>,
+}
+
+fn run(
+    mut socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    queued: mpsc::Receiver<String>,
+    pending: Pending,
+    subscribers: Subscribers,
+) {
+    loop {
+        loop {
+            match queued.try_recv() {
+                Ok(message) => {
+                    if socket.send(Message::Text(message.into())).is_err() {
+                        return shut_down(&pending);
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                // The browser and every clone of the sender are gone
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    return shut_down(&pending);
+                }
+            }
+        }
+
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => return shut_down(&pending),
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "raw protocol connection ended");
+                return shut_down(&pending);
+            }
+        };
+        let Ok(incoming) = serde_json::from_str::<Incoming>(text.as_str()) else {
+            continue;
+        };
+
+        match (incoming.id, incoming.method) {
+            (Some(id), _) => {
+                let Some(respond) = pending.lock().unwrap().remove(&id) else {
+                    continue;
+                };
+                let result = match incoming.error {
+                    Some(error) => Err(BrowserAgentError::ChromeError(
+                        error["message"]
+                            .as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| error.to_string()),
+                    )),
+                    None => Ok(incoming.result.unwrap_or(Value::Null)),
+                };
+                let _ = respond.send(result);
+            }
+            (None, Some(method)) => {
+                let event = RawCdpEvent {
+                    method,
+                    params: incoming.params,
+                    session_id: incoming.session_id,
+                };
+                subscribers.lock().unwrap().retain(|subscriber| {
+                    let matches = event.method.starts_with(&subscriber.prefix)
+                        && (subscriber.session_id.is_none()
+                            || subscriber.session_id == event.session_id);
+                    !matches || subscriber.events.send(event.clone()).is_ok()
+                });
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Fail every call still waiting
+fn shut_down(pending: &Pending) {
+    for (_, respond) in pending.lock().unwrap().drain() {
+        let _ = respond.send(Err(closed()));
+    }
+}
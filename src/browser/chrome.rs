@@ -1,15 +1,277 @@
-use crate::core::{BrowserCapabilities, BrowserTrait, Config};
+use super::cdp::{RawCdpConnection, RawEventSubscription};
+use super::network::{graphql_operation_name, ws_event, NetworkTracker};
+use crate::core::config::{BrowserConfig, ChromeChannel, HeadlessMode};
+use crate::core::{
+    BrowserCapabilities, BrowserTrait, CapturedResponse, Config, ConsoleLevel, ConsoleMessage,
+    FrameInfo, FrameTarget, IndexedDbDatabase, IndexedDbStore, NetworkConditions, NetworkIdleStats,
+    NodeDescription, NodeRef, ServiceWorkerInfo, SiteDataKind, UserAgentOverride,
+    WsConnectionStats, WsDirection, WsEvent,
+};
 use crate::errors::{BrowserAgentError, Result};
+use crate::utils::Clock;
 use async_trait::async_trait;
 use headless_chrome::{Browser, LaunchOptions, Tab};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
 
 /// Chrome browser implementation
 pub struct ChromeBrowser {
     browser: Option<Browser>,
     capabilities: BrowserCapabilities,
+    network_trackers: Mutex<HashMap<String, NetworkTracker>>,
+    console_logs: Mutex<HashMap<String, ConsoleLog>>,
+    response_captures: Mutex<HashMap<String, ResponseCapture>>,
+    websocket_observers: Mutex<HashMap<String, WebSocketObserver>>,
+    service_workers: Mutex<HashMap<String, ServiceWorkerRegistry>>,
+    /// Set when the tab's renderer crashes, for tabs passed to `watch_for_crash`
+    crash_flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    /// Connection behind `call_method_raw`, opened on first use
+    raw_cdp: Mutex<Option<Arc<RawCdpConnection>>>,
+    /// Browser contexts of tabs from `new_isolated_tab`, by target id, disposed
+    /// when the tab is closed
+    isolated_contexts: Mutex<HashMap<String, String>>,
+}
+
+/// Registrations keyed by registration id, kept current from protocol events
+type ServiceWorkerRegistry = Arc<Mutex<HashMap<String, ServiceWorkerInfo>>>;
+
+/// A browser binary found by `ChromeBrowser::detect_installations`
+#[derive(Debug, Clone)]
+pub struct ChromeInstallation {
+    pub channel: ChromeChannel,
+    pub path: PathBuf,
+    /// Output of `--version`, e.g. `Google Chrome 124.0.6367.91`; `None` if it could not be read
+    pub version: Option<String>,
+}
+
+impl ChromeInstallation {
+    pub fn major_version(&self) -> Option<u32> {
+        self.version.as_deref().and_then(major_version)
+    }
+}
+
+/// Well-known install locations for each channel on the current platform
+fn candidate_paths(channel: ChromeChannel) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    if cfg!(target_os = "macos") {
+        let app = match channel {
+            ChromeChannel::Stable => "Google Chrome.app/Contents/MacOS/Google Chrome",
+            ChromeChannel::Beta => "Google Chrome Beta.app/Contents/MacOS/Google Chrome Beta",
+            ChromeChannel::Chromium => "Chromium.app/Contents/MacOS/Chromium",
+        };
+        paths.push(Path::new("/Applications").join(app));
+        if let Some(home) = std::env::var_os("HOME") {
+            paths.push(Path::new(&home).join("Applications").join(app));
+        }
+    } else if cfg!(windows) {
+        let relative = match channel {
+            ChromeChannel::Stable => r"Google\Chrome\Application\chrome.exe",
+            ChromeChannel::Beta => r"Google\Chrome Beta\Application\chrome.exe",
+            ChromeChannel::Chromium => r"Chromium\Application\chrome.exe",
+        };
+        for var in ["PROGRAMFILES", "PROGRAMFILES(X86)", "LOCALAPPDATA"] {
+            if let Some(root) = std::env::var_os(var) {
+                paths.push(Path::new(&root).join(relative));
+            }
+        }
+    } else {
+        let names: &[&str] = match channel {
+            ChromeChannel::Stable => &["google-chrome-stable", "google-chrome"],
+            ChromeChannel::Beta => &["google-chrome-beta"],
+            ChromeChannel::Chromium => &["chromium", "chromium-browser"],
+        };
+        let dirs = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for name in names {
+            for dir in dirs
+                .iter()
+                .map(PathBuf::as_path)
+                .chain([Path::new("/usr/bin"), Path::new("/snap/bin")])
+            {
+                paths.push(dir.join(name));
+            }
+        }
+    }
+    paths
+}
+
+/// Binary inside a chrome-for-testing download, given either the unpacked
+/// archive directory (`chrome-linux64`) or the directory containing it
+fn chrome_for_testing_binary(dir: &Path) -> Option<PathBuf> {
+    let platform = if cfg!(target_os = "macos") {
+        if cfg!(target_arch = "aarch64") {
+            "chrome-mac-arm64"
+        } else {
+            "chrome-mac-x64"
+        }
+    } else if cfg!(windows) {
+        if cfg!(target_pointer_width = "64") {
+            "chrome-win64"
+        } else {
+            "chrome-win32"
+        }
+    } else {
+        "chrome-linux64"
+    };
+    let binary = if cfg!(target_os = "macos") {
+        "Google Chrome for Testing.app/Contents/MacOS/Google Chrome for Testing"
+    } else if cfg!(windows) {
+        "chrome.exe"
+    } else {
+        "chrome"
+    };
+    [dir.join(platform).join(binary), dir.join(binary)]
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+/// Version reported by `binary --version`. Chrome on Windows does not print
+/// one, so fall back to the versioned directory installed next to the binary.
+fn binary_version(binary: &Path) -> Option<String> {
+    if !cfg!(windows) {
+        let output = std::process::Command::new(binary)
+            .arg("--version")
+            .output()
+            .ok()?;
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        return (!version.is_empty()).then_some(version);
+    }
+    std::fs::read_dir(binary.parent()?)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .find(|name| major_version(name).is_some() && name.contains('.'))
+}
+
+/// Major version from strings like `Google Chrome 124.0.6367.91` or `HeadlessChrome/124.0.6367.91`
+pub(crate) fn major_version(version: &str) -> Option<u32> {
+    version
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .find(|part| part.contains('.'))
+        .and_then(|part| part.split('.').next())
+        .and_then(|major| major.parse().ok())
+}
+
+/// Flags that switch the same feature on and off
+const OPPOSING_FLAGS: &[(&str, &str)] = &[
+    ("--disable-gpu", "--enable-gpu"),
+    ("--disable-javascript", "--enable-javascript"),
+];
+
+/// Command line generated from `config` followed by the user's `args`, plus a warning
+/// for each user arg that repeats or contradicts a generated one. A contradicting
+/// user arg replaces the generated flag; an exact repeat is dropped.
+pub(crate) fn launch_args(config: &BrowserConfig) -> (Vec<String>, Vec<String>) {
+    let mut generated = vec![
+        "--no-sandbox".to_string(),
+        "--disable-dev-shm-usage".to_string(),
+        format!(
+            "--window-size={},{}",
+            config.viewport.width, config.viewport.height
+        ),
+    ];
+    match config.headless {
+        HeadlessMode::Off => {}
+        HeadlessMode::Legacy => generated.push("--headless".to_string()),
+        HeadlessMode::New => generated.push("--headless=new".to_string()),
+    }
+    if config.disable_gpu {
+        generated.push("--disable-gpu".to_string());
+    }
+    if config.no_first_run {
+        generated.push("--no-first-run".to_string());
+    }
+    if let Some(lang) = &config.lang {
+        generated.push(format!("--lang={}", lang));
+    }
+    if let Some(position) = config.window_position {
+        generated.push(format!("--window-position={},{}", position.x, position.y));
+    }
+    if let Some(user_agent) = &config.user_agent {
+        generated.push(format!("--user-agent={}", user_agent));
+    }
+    if config.disable_images {
+        generated.push("--blink-settings=imagesEnabled=false".to_string());
+    }
+
+    let flag_name = |arg: &str| arg.split('=').next().unwrap_or(arg).to_string();
+    let mut warnings = Vec::new();
+    let mut user_args = Vec::new();
+    for arg in &config.args {
+        let name = flag_name(arg);
+        if generated.contains(arg) {
+            warnings.push(format!(
+                "ignoring '{}': already generated from the config",
+                arg
+            ));
+            continue;
+        }
+        let opposite = OPPOSING_FLAGS.iter().find_map(|(a, b)| {
+            if name == *a {
+                Some(*b)
+            } else if name == *b {
+                Some(*a)
+            } else {
+                None
+            }
+        });
+        let before = generated.len();
+        generated.retain(|existing| {
+            let existing = flag_name(existing);
+            existing != name && Some(existing.as_str()) != opposite
+        });
+        if generated.len() != before {
+            warnings.push(format!(
+                "'{}' overrides a flag generated from the config",
+                arg
+            ));
+        }
+        user_args.push(arg.clone());
+    }
+    generated.extend(user_args);
+    (generated, warnings)
+}
+
+/// Console messages kept per tab; the oldest are dropped first
+const CONSOLE_LOG_LIMIT: usize = 1000;
+
+/// Captured responses or frames buffered per subscriber before it starts lagging
+const RESPONSE_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Clone)]
+struct ConsoleLog {
+    messages: Arc<Mutex<Vec<ConsoleMessage>>>,
+    sender: broadcast::Sender<ConsoleMessage>,
+}
+
+#[derive(Clone)]
+struct ResponseCapture {
+    sender: broadcast::Sender<CapturedResponse>,
+    max_body_bytes: Arc<AtomicUsize>,
+}
+
+#[derive(Clone)]
+struct WebSocketObserver {
+    sender: broadcast::Sender<WsEvent>,
+    max_payload_bytes: Arc<AtomicUsize>,
+    /// Keyed by protocol request id
+    connections: Arc<Mutex<HashMap<String, WsConnectionStats>>>,
+}
+
+/// Request details held until its response body can be read
+struct PendingResponse {
+    url: String,
+    method: String,
+    request_body: Option<String>,
+    status: u16,
+    mime_type: String,
+    encoded_length: usize,
 }
 
 impl ChromeBrowser {
@@ -21,52 +283,700 @@ impl ChromeBrowser {
                 supports_screenshots: true,
                 supports_network_interception: true,
                 supports_mobile_emulation: true,
+                supports_native_input: true,
+                supports_cdp_cookies: true,
+                supports_pdf: true,
+                supports_screencast: true,
+                supports_isolated_worlds: true,
+                supports_overlay_highlight: true,
+                supports_node_resolution: true,
+                supports_user_agent_override: true,
+                supports_throttling: true,
+                supports_js_coverage: true,
+                browser_version: None,
             },
+            network_trackers: Mutex::new(HashMap::new()),
+            console_logs: Mutex::new(HashMap::new()),
+            response_captures: Mutex::new(HashMap::new()),
+            websocket_observers: Mutex::new(HashMap::new()),
+            service_workers: Mutex::new(HashMap::new()),
+            crash_flags: Mutex::new(HashMap::new()),
+            raw_cdp: Mutex::new(None),
+            isolated_contexts: Mutex::new(HashMap::new()),
         }
     }
 
-    pub fn capabilities(&self) -> &BrowserCapabilities {
-        &self.capabilities
+    /// Browsers installed in the usual locations for this platform, with versions
+    pub fn detect_installations() -> Vec<ChromeInstallation> {
+        let mut found: Vec<ChromeInstallation> = Vec::new();
+        for channel in [
+            ChromeChannel::Stable,
+            ChromeChannel::Beta,
+            ChromeChannel::Chromium,
+        ] {
+            for path in candidate_paths(channel) {
+                if !path.is_file() {
+                    continue;
+                }
+                let path = std::fs::canonicalize(&path).unwrap_or(path);
+                if found.iter().any(|installation| installation.path == path) {
+                    continue;
+                }
+                found.push(ChromeInstallation {
+                    channel,
+                    version: binary_version(&path),
+                    path,
+                });
+            }
+        }
+        found
     }
-}
 
-#[async_trait]
-impl BrowserTrait for ChromeBrowser {
-    type TabHandle = Arc<Tab>;
+    /// Binary to launch for `config`, or `None` to let headless_chrome search
+    fn resolve_executable(config: &Config) -> Result<Option<PathBuf>> {
+        if let Some(path) = &config.browser.executable_path {
+            if path.is_dir() {
+                return chrome_for_testing_binary(path).map(Some).ok_or_else(|| {
+                    BrowserAgentError::ConfigurationError(format!(
+                        "no chrome-for-testing binary for this platform under {}",
+                        path.display()
+                    ))
+                });
+            }
+            return Ok(Some(path.clone()));
+        }
+        match config.browser.channel {
+            Some(channel) => Self::detect_installations()
+                .into_iter()
+                .find(|installation| installation.channel == channel)
+                .map(|installation| Some(installation.path))
+                .ok_or_else(|| {
+                    BrowserAgentError::ConfigurationError(format!(
+                        "no {:?} browser installation found",
+                        channel
+                    ))
+                }),
+            None => Ok(None),
+        }
+    }
 
-    async fn launch(&mut self, config: &Config) -> Result<()> {
-        let window_size_arg = format!(
-            "--window-size={},{}",
-            config.browser.viewport.width, config.browser.viewport.height
-        );
+    /// Call a typed CDP method whose parameters are given as JSON
+    fn call_cdp<M>(tab: &Tab, params: Value) -> Result<M::ReturnObject>
+    where
+        M: headless_chrome::protocol::cdp::types::Method
+            + serde::Serialize
+            + serde::de::DeserializeOwned,
+    {
+        let method: M = serde_json::from_value(params)?;
+        tab.call_method(method)
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))
+    }
 
-        let user_agent_arg = config
-            .browser
-            .user_agent
-            .as_ref()
-            .map(|ua| format!("--user-agent={}", ua));
+    /// Network tracker for `tab`, subscribing to request events on first use
+    fn network_tracker(&self, tab: &Tab) -> Result<NetworkTracker> {
+        use headless_chrome::protocol::cdp::types::Event;
+        use headless_chrome::protocol::cdp::Network;
 
-        let mut args = vec![
-            OsStr::new("--no-sandbox"),
-            OsStr::new("--disable-dev-shm-usage"),
-            OsStr::new(&window_size_arg),
-        ];
+        let target_id = tab.get_target_id().clone();
+        if let Some(tracker) = self.network_trackers.lock().unwrap().get(&target_id) {
+            return Ok(tracker.clone());
+        }
+
+        tab.call_method(Network::Enable {
+            max_total_buffer_size: None,
+            max_resource_buffer_size: None,
+            max_post_data_size: None,
+        })
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        let tracker = NetworkTracker::default();
+        let listener = tracker.clone();
+        tab.add_event_listener(Arc::new(move |event: &Event| match event {
+            Event::NetworkRequestWillBeSent(e) => {
+                // Websockets and event streams stay open by design
+                let streaming = matches!(
+                    e.params.Type,
+                    Some(Network::ResourceType::WebSocket)
+                        | Some(Network::ResourceType::EventSource)
+                );
+                if !streaming {
+                    listener.request_started(&e.params.request_id, &e.params.request.url);
+                }
+            }
+            Event::NetworkLoadingFinished(e) => listener.request_finished(&e.params.request_id),
+            Event::NetworkLoadingFailed(e) => listener.request_finished(&e.params.request_id),
+            _ => {}
+        }))
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        self.network_trackers
+            .lock()
+            .unwrap()
+            .insert(target_id, tracker.clone());
+        Ok(tracker)
+    }
 
-        if let Some(ref ua_arg) = user_agent_arg {
-            args.push(OsStr::new(ua_arg));
+    /// Crash flag for `tab`, subscribing to inspector events on first use
+    fn crash_flag(&self, tab: &Tab) -> Result<Arc<AtomicBool>> {
+        use headless_chrome::protocol::cdp::types::Event;
+        use headless_chrome::protocol::cdp::Inspector;
+
+        let target_id = tab.get_target_id().clone();
+        if let Some(flag) = self.crash_flags.lock().unwrap().get(&target_id) {
+            return Ok(flag.clone());
         }
 
-        if config.browser.disable_images {
-            args.push(OsStr::new("--blink-settings=imagesEnabled=false"));
+        tab.call_method(Inspector::Enable(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        let flag = Arc::new(AtomicBool::new(false));
+        let listener = flag.clone();
+        tab.add_event_listener(Arc::new(move |event: &Event| match event {
+            Event::InspectorTargetCrashed(_) => listener.store(true, Ordering::SeqCst),
+            Event::InspectorTargetReloadedAfterCrash(_) => listener.store(false, Ordering::SeqCst),
+            _ => {}
+        }))
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        self.crash_flags
+            .lock()
+            .unwrap()
+            .insert(target_id, flag.clone());
+        Ok(flag)
+    }
+
+    /// URL of `tab` when its crash flag is set
+    fn crash_url(&self, tab: &Tab) -> Option<String> {
+        let crashed = self
+            .crash_flags
+            .lock()
+            .unwrap()
+            .get(tab.get_target_id())
+            .is_some_and(|flag| flag.load(Ordering::SeqCst));
+        crashed.then(|| tab.get_url())
+    }
+
+    /// `TabCrashed` once `tab`'s renderer is gone, instead of the protocol error
+    /// every call on it would fail with
+    fn ensure_alive(&self, tab: &Tab) -> Result<()> {
+        match self.crash_url(tab) {
+            Some(url) => Err(BrowserAgentError::TabCrashed(url)),
+            None => Ok(()),
         }
+    }
 
-        // Add custom args
-        for arg in &config.browser.args {
-            args.push(OsStr::new(arg));
+    /// Console log for `tab`, subscribing to console and exception events on first use
+    fn console_log(&self, tab: &Tab) -> Result<ConsoleLog> {
+        use headless_chrome::protocol::cdp::types::Event;
+        use headless_chrome::protocol::cdp::{Log, Runtime};
+
+        let target_id = tab.get_target_id().clone();
+        if let Some(log) = self.console_logs.lock().unwrap().get(&target_id) {
+            return Ok(log.clone());
         }
 
+        tab.call_method(Runtime::Enable(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        tab.call_method(Log::Enable(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        let (sender, _) = broadcast::channel(RESPONSE_CHANNEL_CAPACITY);
+        let log = ConsoleLog {
+            messages: Arc::new(Mutex::new(Vec::new())),
+            sender,
+        };
+        let listener = log.clone();
+        tab.add_event_listener(Arc::new(move |event: &Event| {
+            let message = match event {
+                Event::RuntimeConsoleAPICalled(e) => {
+                    use Runtime::ConsoleAPICalledEventTypeOption as Kind;
+                    let level = match e.params.Type {
+                        Kind::Error | Kind::Assert => ConsoleLevel::Error,
+                        Kind::Warning => ConsoleLevel::Warning,
+                        Kind::Debug | Kind::Trace => ConsoleLevel::Debug,
+                        _ => ConsoleLevel::Info,
+                    };
+                    let text = e
+                        .params
+                        .args
+                        .iter()
+                        .map(|arg| match (&arg.value, &arg.description) {
+                            (Some(Value::String(s)), _) => s.clone(),
+                            (Some(value), _) => value.to_string(),
+                            (None, Some(description)) => description.clone(),
+                            (None, None) => String::new(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let url = e
+                        .params
+                        .stack_trace
+                        .as_ref()
+                        .and_then(|trace| trace.call_frames.first())
+                        .map(|frame| frame.url.clone());
+                    Some((level, text, url))
+                }
+                Event::RuntimeExceptionThrown(e) => {
+                    let details = &e.params.exception_details;
+                    let text = details
+                        .exception
+                        .as_ref()
+                        .and_then(|exception| exception.description.clone())
+                        .unwrap_or_else(|| details.text.clone());
+                    Some((ConsoleLevel::Error, text, details.url.clone()))
+                }
+                Event::LogEntryAdded(e) => {
+                    let level = match e.params.entry.level {
+                        Log::LogEntryLevel::Error => ConsoleLevel::Error,
+                        Log::LogEntryLevel::Warning => ConsoleLevel::Warning,
+                        Log::LogEntryLevel::Info => ConsoleLevel::Info,
+                        Log::LogEntryLevel::Verbose => ConsoleLevel::Debug,
+                    };
+                    Some((
+                        level,
+                        e.params.entry.text.clone(),
+                        e.params.entry.url.clone(),
+                    ))
+                }
+                _ => None,
+            };
+
+            if let Some((level, text, url)) = message {
+                let message = ConsoleMessage {
+                    level,
+                    text,
+                    url,
+                    timestamp: chrono::Utc::now(),
+                };
+                {
+                    let mut messages = listener.messages.lock().unwrap();
+                    if messages.len() == CONSOLE_LOG_LIMIT {
+                        messages.remove(0);
+                    }
+                    messages.push(message.clone());
+                }
+                // No subscribers is fine
+                let _ = listener.sender.send(message);
+            }
+        }))
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        self.console_logs
+            .lock()
+            .unwrap()
+            .insert(target_id, log.clone());
+        Ok(log)
+    }
+
+    /// Response capture for `tab`, subscribing to request events on first use.
+    ///
+    /// Bodies are read on a worker thread once loading finishes, so the event
+    /// listener never blocks on a protocol call.
+    fn response_capture(&self, tab: &Arc<Tab>) -> Result<ResponseCapture> {
+        use headless_chrome::protocol::cdp::types::Event;
+        use headless_chrome::protocol::cdp::Network;
+
+        let target_id = tab.get_target_id().clone();
+        if let Some(capture) = self.response_captures.lock().unwrap().get(&target_id) {
+            return Ok(capture.clone());
+        }
+
+        tab.call_method(Network::Enable {
+            max_total_buffer_size: None,
+            max_resource_buffer_size: None,
+            max_post_data_size: None,
+        })
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        let (sender, _) = broadcast::channel(RESPONSE_CHANNEL_CAPACITY);
+        let capture = ResponseCapture {
+            sender,
+            max_body_bytes: Arc::new(AtomicUsize::new(0)),
+        };
+
+        let pending: Arc<Mutex<HashMap<String, PendingResponse>>> = Arc::default();
+        let (finished_tx, finished_rx) = std::sync::mpsc::channel::<(String, PendingResponse)>();
+
+        let listener = pending.clone();
+        tab.add_event_listener(Arc::new(move |event: &Event| match event {
+            Event::NetworkRequestWillBeSent(e) => {
+                let data_request = matches!(
+                    e.params.Type,
+                    Some(Network::ResourceType::Xhr) | Some(Network::ResourceType::Fetch)
+                );
+                if data_request {
+                    listener.lock().unwrap().insert(
+                        e.params.request_id.clone(),
+                        PendingResponse {
+                            url: e.params.request.url.clone(),
+                            method: e.params.request.method.clone(),
+                            request_body: e.params.request.post_data.clone(),
+                            status: 0,
+                            mime_type: String::new(),
+                            encoded_length: 0,
+                        },
+                    );
+                }
+            }
+            Event::NetworkResponseReceived(e) => {
+                if let Some(entry) = listener.lock().unwrap().get_mut(&e.params.request_id) {
+                    entry.status = e.params.response.status as u16;
+                    entry.mime_type = e.params.response.mime_type.clone();
+                }
+            }
+            Event::NetworkLoadingFinished(e) => {
+                let entry = listener.lock().unwrap().remove(&e.params.request_id);
+                if let Some(mut entry) = entry {
+                    entry.encoded_length = e.params.encoded_data_length as usize;
+                    let _ = finished_tx.send((e.params.request_id.clone(), entry));
+                }
+            }
+            Event::NetworkLoadingFailed(e) => {
+                listener.lock().unwrap().remove(&e.params.request_id);
+            }
+            _ => {}
+        }))
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        let worker_tab = Arc::downgrade(tab);
+        let worker = capture.clone();
+        std::thread::spawn(move || {
+            while let Ok((request_id, entry)) = finished_rx.recv() {
+                let Some(tab) = worker_tab.upgrade() else {
+                    break;
+                };
+                if worker.sender.receiver_count() == 0 {
+                    continue;
+                }
+
+                let limit = worker.max_body_bytes.load(Ordering::Relaxed);
+                let mut body = Value::Null;
+                let mut body_truncated = entry.encoded_length > limit;
+                if !body_truncated {
+                    if let Ok(response) = tab.call_method(Network::GetResponseBody { request_id }) {
+                        let bytes = if response.base_64_encoded {
+                            use base64::Engine;
+                            base64::engine::general_purpose::STANDARD
+                                .decode(&response.body)
+                                .unwrap_or_default()
+                        } else {
+                            response.body.into_bytes()
+                        };
+                        if bytes.len() > limit {
+                            body_truncated = true;
+                        } else {
+                            body = serde_json::from_slice(&bytes).unwrap_or_else(|_| {
+                                Value::String(String::from_utf8_lossy(&bytes).into_owned())
+                            });
+                        }
+                    }
+                }
+
+                let _ = worker.sender.send(CapturedResponse {
+                    operation_name: graphql_operation_name(
+                        &entry.url,
+                        entry.request_body.as_deref(),
+                    ),
+                    url: entry.url,
+                    method: entry.method,
+                    status: entry.status,
+                    mime_type: entry.mime_type,
+                    request_body: entry.request_body,
+                    body,
+                    body_truncated,
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+        });
+
+        self.response_captures
+            .lock()
+            .unwrap()
+            .insert(target_id, capture.clone());
+        Ok(capture)
+    }
+
+    /// WebSocket observer for `tab`, subscribing to frame events on first use
+    fn websocket_observer(&self, tab: &Tab) -> Result<WebSocketObserver> {
+        use headless_chrome::protocol::cdp::types::Event;
+        use headless_chrome::protocol::cdp::Network;
+
+        let target_id = tab.get_target_id().clone();
+        if let Some(observer) = self.websocket_observers.lock().unwrap().get(&target_id) {
+            return Ok(observer.clone());
+        }
+
+        tab.call_method(Network::Enable {
+            max_total_buffer_size: None,
+            max_resource_buffer_size: None,
+            max_post_data_size: None,
+        })
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        let (sender, _) = broadcast::channel(RESPONSE_CHANNEL_CAPACITY);
+        let observer = WebSocketObserver {
+            sender,
+            max_payload_bytes: Arc::new(AtomicUsize::new(0)),
+            connections: Arc::default(),
+        };
+
+        let listener = observer.clone();
+        tab.add_event_listener(Arc::new(move |event: &Event| {
+            let (request_id, direction, frame) = match event {
+                Event::NetworkWebSocketCreated(e) => {
+                    listener.connections.lock().unwrap().insert(
+                        e.params.request_id.clone(),
+                        WsConnectionStats {
+                            url: e.params.url.clone(),
+                            open: true,
+                            ..WsConnectionStats::default()
+                        },
+                    );
+                    return;
+                }
+                Event::NetworkWebSocketClosed(e) => {
+                    if let Some(stats) = listener
+                        .connections
+                        .lock()
+                        .unwrap()
+                        .get_mut(&e.params.request_id)
+                    {
+                        stats.open = false;
+                    }
+                    return;
+                }
+                Event::NetworkWebSocketFrameSent(e) => {
+                    (&e.params.request_id, WsDirection::Sent, &e.params.response)
+                }
+                Event::NetworkWebSocketFrameReceived(e) => (
+                    &e.params.request_id,
+                    WsDirection::Received,
+                    &e.params.response,
+                ),
+                _ => return,
+            };
+
+            let event = {
+                let mut connections = listener.connections.lock().unwrap();
+                // Sockets opened before observation started have no created event
+                let stats =
+                    connections
+                        .entry(request_id.clone())
+                        .or_insert_with(|| WsConnectionStats {
+                            open: true,
+                            ..WsConnectionStats::default()
+                        });
+                let event = ws_event(
+                    &stats.url,
+                    direction,
+                    frame.opcode as u8,
+                    &frame.payload_data,
+                    listener.max_payload_bytes.load(Ordering::Relaxed),
+                );
+                match direction {
+                    WsDirection::Sent => {
+                        stats.frames_sent += 1;
+                        stats.bytes_sent += event.payload_size;
+                    }
+                    WsDirection::Received => {
+                        stats.frames_received += 1;
+                        stats.bytes_received += event.payload_size;
+                    }
+                }
+                event
+            };
+            let _ = listener.sender.send(event);
+        }))
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        self.websocket_observers
+            .lock()
+            .unwrap()
+            .insert(target_id, observer.clone());
+        Ok(observer)
+    }
+
+    /// Service worker registry for `tab`, subscribing to worker events on first use.
+    ///
+    /// The second value is `true` when the subscription was just made; the browser
+    /// reports existing registrations shortly after.
+    fn service_worker_registry(&self, tab: &Tab) -> Result<(ServiceWorkerRegistry, bool)> {
+        use headless_chrome::protocol::cdp::types::Event;
+        use headless_chrome::protocol::cdp::ServiceWorker;
+
+        let target_id = tab.get_target_id().clone();
+        if let Some(registry) = self.service_workers.lock().unwrap().get(&target_id) {
+            return Ok((registry.clone(), false));
+        }
+
+        let registry: ServiceWorkerRegistry = Arc::default();
+        let listener = registry.clone();
+        tab.add_event_listener(Arc::new(move |event: &Event| match event {
+            Event::ServiceWorkerWorkerRegistrationUpdated(e) => {
+                let mut registrations = listener.lock().unwrap();
+                for registration in &e.params.registrations {
+                    if registration.is_deleted {
+                        registrations.remove(&registration.registration_id);
+                        continue;
+                    }
+                    registrations
+                        .entry(registration.registration_id.clone())
+                        .or_insert_with(|| ServiceWorkerInfo {
+                            registration_id: registration.registration_id.clone(),
+                            scope_url: String::new(),
+                            script_url: None,
+                            status: None,
+                            running_status: None,
+                        })
+                        .scope_url = registration.scope_url.clone();
+                }
+            }
+            Event::ServiceWorkerWorkerVersionUpdated(e) => {
+                let mut registrations = listener.lock().unwrap();
+                for version in &e.params.versions {
+                    let status = serde_json::to_value(&version.status)
+                        .ok()
+                        .and_then(|v| v.as_str().map(String::from));
+                    // A redundant version has been replaced or removed
+                    if status.as_deref() == Some("redundant") {
+                        continue;
+                    }
+                    if let Some(info) = registrations.get_mut(&version.registration_id) {
+                        info.script_url = Some(version.script_url.clone());
+                        info.status = status;
+                        info.running_status = serde_json::to_value(&version.running_status)
+                            .ok()
+                            .and_then(|v| v.as_str().map(String::from));
+                    }
+                }
+            }
+            _ => {}
+        }))
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        tab.call_method(ServiceWorker::Enable(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        self.service_workers
+            .lock()
+            .unwrap()
+            .insert(target_id, registry.clone());
+        Ok((registry, true))
+    }
+
+    /// Resolve a frame target to a CDP frame id
+    fn resolve_frame_id(
+        &self,
+        tab: &Tab,
+        frames: &[FrameInfo],
+        frame: &FrameTarget,
+    ) -> Result<String> {
+        use headless_chrome::protocol::cdp::DOM;
+
+        match frame {
+            FrameTarget::Id(id) => frames
+                .iter()
+                .find(|f| &f.id == id)
+                .map(|f| f.id.clone())
+                .ok_or_else(|| BrowserAgentError::FrameNotFound(frame.to_string())),
+            FrameTarget::UrlPattern(_) => frames
+                .iter()
+                .find(|f| !f.is_main_frame() && frame.matches_url(&f.url))
+                .map(|f| f.id.clone())
+                .ok_or_else(|| BrowserAgentError::FrameNotFound(frame.to_string())),
+            FrameTarget::Selector(selector) => {
+                let document =
+                    Self::call_cdp::<DOM::GetDocument>(tab, serde_json::json!({ "depth": 0 }))?;
+                let found = Self::call_cdp::<DOM::QuerySelector>(
+                    tab,
+                    serde_json::json!({ "nodeId": document.root.node_id, "selector": selector }),
+                )?;
+                if found.node_id == 0 {
+                    return Err(BrowserAgentError::FrameNotFound(frame.to_string()));
+                }
+                let described = Self::call_cdp::<DOM::DescribeNode>(
+                    tab,
+                    serde_json::json!({ "nodeId": found.node_id }),
+                )?;
+                described
+                    .node
+                    .frame_id
+                    .ok_or_else(|| BrowserAgentError::FrameNotFound(frame.to_string()))
+            }
+        }
+    }
+
+    /// **Unstable.** Send any protocol method to `tab` and return its raw result,
+    /// e.g. `Emulation.setIdleOverride` before the crate wraps it. Protocol
+    /// errors come back as `ChromeError`. See [`crate::browser::cdp`].
+    pub async fn call_method_raw(&self, tab: &Tab, method: &str, params: Value) -> Result<Value> {
+        let connection = self.raw_connection().await?;
+        let session_id = connection.session_for(tab.get_target_id()).await?;
+        connection.call(method, params, Some(&session_id)).await
+    }
+
+    /// **Unstable.** `call_method_raw` for browser-wide methods such as
+    /// `Browser.getVersion` or the `Target` domain
+    pub async fn call_browser_method_raw(&self, method: &str, params: Value) -> Result<Value> {
+        self.raw_connection()
+            .await?
+            .call(method, params, None)
+            .await
+    }
+
+    /// **Unstable.** Events from `tab` whose method starts with `prefix`, e.g.
+    /// `Network.` for the whole domain. Enable the domain through
+    /// `call_method_raw` first; it is enabled for these raw calls only.
+    pub async fn subscribe_raw(&self, tab: &Tab, prefix: &str) -> Result<RawEventSubscription> {
+        let connection = self.raw_connection().await?;
+        let session_id = connection.session_for(tab.get_target_id()).await?;
+        Ok(connection.subscribe(prefix, Some(session_id)))
+    }
+
+    async fn raw_connection(&self) -> Result<Arc<RawCdpConnection>> {
+        if let Some(connection) = self.raw_cdp.lock().unwrap().as_ref() {
+            return Ok(connection.clone());
+        }
+        let ws_url = self
+            .browser
+            .as_ref()
+            .ok_or(BrowserAgentError::BrowserNotLaunched)?
+            .get_ws_url();
+        let connection = tokio::task::spawn_blocking(move || RawCdpConnection::connect(&ws_url))
+            .await
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))??;
+        let connection = Arc::new(connection);
+        // A connection opened concurrently wins; this one closes when dropped
+        Ok(self
+            .raw_cdp
+            .lock()
+            .unwrap()
+            .get_or_insert(connection)
+            .clone())
+    }
+}
+
+#[async_trait]
+impl BrowserTrait for ChromeBrowser {
+    type TabHandle = Arc<Tab>;
+
+    async fn launch(&mut self, config: &Config) -> Result<()> {
+        let (args, warnings) = launch_args(&config.browser);
+        for warning in &warnings {
+            println!("⚠️ {}", warning);
+        }
+        let args: Vec<&OsStr> = args.iter().map(OsStr::new).collect();
+
+        let executable = Self::resolve_executable(config)?;
+        if let Some(ref path) = executable {
+            println!("🧭 Using browser at {}", path.display());
+        }
+
+        // Headless and GPU flags come from `launch_args`, so headless_chrome must not add its own
         let launch_options = LaunchOptions::default_builder()
-            .headless(config.browser.headless)
+            .headless(false)
+            .enable_gpu(true)
+            .path(executable)
             .args(args)
             .build()
             .map_err(|e| BrowserAgentError::LaunchFailed(e.to_string()))?;
@@ -74,6 +984,19 @@ impl BrowserTrait for ChromeBrowser {
         let browser = Browser::new(launch_options)
             .map_err(|e| BrowserAgentError::LaunchFailed(e.to_string()))?;
 
+        let product = browser.get_version().map(|version| version.product).ok();
+        if let Some(minimum) = config.browser.min_version {
+            let found = product.clone().unwrap_or_else(|| "unknown".to_string());
+            if product
+                .as_deref()
+                .and_then(major_version)
+                .is_none_or(|major| major < minimum)
+            {
+                return Err(BrowserAgentError::UnsupportedBrowserVersion { found, minimum });
+            }
+        }
+        self.capabilities.browser_version = product;
+
         self.browser = Some(browser);
         Ok(())
     }
@@ -91,17 +1014,98 @@ impl BrowserTrait for ChromeBrowser {
         Ok(tab)
     }
 
-    async fn navigate(&self, tab: &Self::TabHandle, url: &str) -> Result<()> {
-        tab.navigate_to(url)
-            .map_err(|e| BrowserAgentError::NavigationFailed(e.to_string()))?;
+    async fn new_background_tab(&self) -> Result<Self::TabHandle> {
+        use headless_chrome::protocol::cdp::Target::CreateTarget;
+
+        let browser = self
+            .browser
+            .as_ref()
+            .ok_or(BrowserAgentError::BrowserNotLaunched)?;
+
+        browser
+            .new_tab_with_options(CreateTarget {
+                url: "about:blank".to_string(),
+                width: None,
+                height: None,
+                browser_context_id: None,
+                enable_begin_frame_control: None,
+                new_window: None,
+                background: Some(true),
+                for_tab: None,
+            })
+            .map_err(|e| BrowserAgentError::TabCreationFailed(e.to_string()))
+    }
+
+    async fn new_isolated_tab(&self) -> Result<Self::TabHandle> {
+        let browser = self
+            .browser
+            .as_ref()
+            .ok_or(BrowserAgentError::BrowserNotLaunched)?;
+
+        let context = browser
+            .new_context()
+            .map_err(|e| BrowserAgentError::TabCreationFailed(e.to_string()))?;
+        let tab = context
+            .new_tab()
+            .map_err(|e| BrowserAgentError::TabCreationFailed(e.to_string()))?;
+        self.isolated_contexts
+            .lock()
+            .unwrap()
+            .insert(tab.get_target_id().clone(), context.get_id().to_string());
+        Ok(tab)
+    }
+
+    async fn activate_tab(&self, tab: &Self::TabHandle) -> Result<()> {
+        tab.activate()
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn close_tab(&self, tab: &Self::TabHandle) -> Result<()> {
+        tab.close(false)
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        // Per-tab capture state is keyed by target id
+        let target_id = tab.get_target_id().clone();
+        self.network_trackers.lock().unwrap().remove(&target_id);
+        self.console_logs.lock().unwrap().remove(&target_id);
+        self.response_captures.lock().unwrap().remove(&target_id);
+        self.websocket_observers.lock().unwrap().remove(&target_id);
+        self.service_workers.lock().unwrap().remove(&target_id);
+        self.crash_flags.lock().unwrap().remove(&target_id);
 
-        tab.wait_until_navigated()
-            .map_err(|e| BrowserAgentError::NavigationFailed(e.to_string()))?;
+        // headless_chrome has no browser-level call for this, so it goes over the
+        // raw connection
+        let context = self.isolated_contexts.lock().unwrap().remove(&target_id);
+        if let Some(context) = context {
+            self.raw_connection()
+                .await?
+                .call(
+                    "Target.disposeBrowserContext",
+                    serde_json::json!({ "browserContextId": context }),
+                    None,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn navigate(&self, tab: &Self::TabHandle, url: &str) -> Result<()> {
+        // Both calls block until the page answers, so they run off the executor
+        // where a cancelled caller can stop waiting for them
+        let (tab, url) = (tab.clone(), url.to_string());
+        tokio::task::spawn_blocking(move || {
+            tab.navigate_to(&url)?.wait_until_navigated()?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .await
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?
+        .map_err(|e| BrowserAgentError::NavigationFailed(e.to_string()))?;
 
         Ok(())
     }
 
     async fn execute_script(&self, tab: &Self::TabHandle, script: &str) -> Result<Value> {
+        self.ensure_alive(tab)?;
         let result = tab
             .evaluate(script, false)
             .map_err(|e| BrowserAgentError::JavaScriptFailed(e.to_string()))?;
@@ -110,6 +1114,7 @@ impl BrowserTrait for ChromeBrowser {
     }
 
     async fn take_screenshot(&self, tab: &Self::TabHandle) -> Result<Vec<u8>> {
+        self.ensure_alive(tab)?;
         let screenshot = tab
             .capture_screenshot(
                 headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption::Png,
@@ -136,6 +1141,669 @@ impl BrowserTrait for ChromeBrowser {
         Ok(())
     }
 
+    async fn set_request_filter(
+        &self,
+        tab: &Self::TabHandle,
+        policy: crate::core::DomainPolicy,
+    ) -> Result<()> {
+        use headless_chrome::browser::tab::RequestPausedDecision;
+        use headless_chrome::protocol::cdp::{Fetch, Network};
+
+        tab.enable_fetch(None, None)
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        tab.enable_request_interception(Arc::new(
+            move |_transport, _session_id, event: Fetch::events::RequestPausedEvent| {
+                let url = &event.params.request.url;
+                if let Some(rule) = policy.blocking_rule(url) {
+                    println!("🚫 Blocked request to {} ({})", url, rule);
+                    RequestPausedDecision::Fail(Fetch::FailRequest {
+                        request_id: event.params.request_id,
+                        error_reason: Network::ErrorReason::BlockedByClient,
+                    })
+                } else {
+                    RequestPausedDecision::Continue(None)
+                }
+            },
+        ))
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn start_network_tracking(&self, tab: &Self::TabHandle) -> Result<bool> {
+        self.network_tracker(tab)?.reset_stats();
+        Ok(true)
+    }
+
+    async fn wait_for_network_idle(
+        &self,
+        tab: &Self::TabHandle,
+        clock: &dyn Clock,
+        idle_ms: u64,
+        timeout_ms: u64,
+    ) -> Result<NetworkIdleStats> {
+        self.network_tracker(tab)?
+            .wait_for_idle(clock, idle_ms, timeout_ms)
+            .await
+    }
+
+    async fn click_at(&self, tab: &Self::TabHandle, x: f64, y: f64) -> Result<()> {
+        tab.click_point(headless_chrome::browser::tab::point::Point { x, y })
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn move_mouse(&self, tab: &Self::TabHandle, x: f64, y: f64) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Input;
+
+        Self::call_cdp::<Input::DispatchMouseEvent>(
+            tab,
+            serde_json::json!({ "type": "mouseMoved", "x": x, "y": y }),
+        )?;
+        Ok(true)
+    }
+
+    async fn press_key(&self, tab: &Self::TabHandle, key: &str) -> Result<()> {
+        tab.press_key(key)
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn highlight_nodes(
+        &self,
+        tab: &Self::TabHandle,
+        selectors: &[String],
+    ) -> Result<Option<usize>> {
+        use headless_chrome::protocol::cdp::{Overlay, DOM};
+
+        tab.call_method(DOM::Enable {
+            include_whitespace: None,
+        })
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        tab.call_method(Overlay::Enable(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        let document = Self::call_cdp::<DOM::GetDocument>(tab, serde_json::json!({ "depth": 0 }))?;
+        let mut first_node = None;
+        let mut matched = Vec::new();
+        for selector in selectors {
+            let found = Self::call_cdp::<DOM::QuerySelector>(
+                tab,
+                serde_json::json!({ "nodeId": document.root.node_id, "selector": selector }),
+            );
+            match found {
+                Ok(found) if found.node_id != 0 => {
+                    first_node.get_or_insert(found.node_id);
+                    matched.push(selector.as_str());
+                }
+                _ => {}
+            }
+        }
+        let Some(node_id) = first_node else {
+            self.hide_highlight(tab).await?;
+            return Ok(Some(0));
+        };
+        // With a selector list Chrome outlines every match in the node's document,
+        // not just the node itself
+        Self::call_cdp::<Overlay::HighlightNode>(
+            tab,
+            serde_json::json!({
+                "highlightConfig": {
+                    "showInfo": false,
+                    "contentColor": { "r": 255, "g": 64, "b": 0, "a": 0.1 },
+                    "borderColor": { "r": 255, "g": 64, "b": 0, "a": 0.9 }
+                },
+                "nodeId": node_id,
+                "selector": matched.join(", ")
+            }),
+        )?;
+        Ok(Some(matched.len()))
+    }
+
+    async fn hide_highlight(&self, tab: &Self::TabHandle) -> Result<()> {
+        use headless_chrome::protocol::cdp::Overlay;
+
+        tab.call_method(Overlay::HideHighlight(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn resolve_node(&self, tab: &Self::TabHandle, selector: &str) -> Result<Option<NodeRef>> {
+        use headless_chrome::protocol::cdp::DOM;
+
+        tab.call_method(DOM::Enable {
+            include_whitespace: None,
+        })
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        let document = Self::call_cdp::<DOM::GetDocument>(tab, serde_json::json!({ "depth": 0 }))?;
+        let found = Self::call_cdp::<DOM::QuerySelector>(
+            tab,
+            serde_json::json!({ "nodeId": document.root.node_id, "selector": selector }),
+        )?;
+        if found.node_id == 0 {
+            return Ok(None);
+        }
+        let described = Self::call_cdp::<DOM::DescribeNode>(
+            tab,
+            serde_json::json!({ "nodeId": found.node_id }),
+        )?;
+        Ok(Some(NodeRef {
+            backend_node_id: described.node.backend_node_id,
+            document_node_id: document.root.backend_node_id,
+            document_url: document.root.document_url.unwrap_or_default(),
+            selector: selector.to_string(),
+        }))
+    }
+
+    async fn describe_node(
+        &self,
+        tab: &Self::TabHandle,
+        node: &NodeRef,
+    ) -> Result<NodeDescription> {
+        use headless_chrome::protocol::cdp::{Runtime, DOM};
+
+        let stale = |reason: &str| {
+            BrowserAgentError::ElementDetached(format!("{} ({})", node.selector, reason))
+        };
+
+        // Backend ids are not reused within a session, but a new document means the
+        // node belongs to a page that is gone
+        let document = Self::call_cdp::<DOM::GetDocument>(tab, serde_json::json!({ "depth": 0 }))?;
+        if document.root.backend_node_id != node.document_node_id {
+            return Err(stale("the document has changed"));
+        }
+        let described = Self::call_cdp::<DOM::DescribeNode>(
+            tab,
+            serde_json::json!({ "backendNodeId": node.backend_node_id }),
+        )
+        .map_err(|_| stale("the node no longer exists"))?;
+
+        // A node removed from the page can still be described while something holds it
+        let object = Self::call_cdp::<DOM::ResolveNode>(
+            tab,
+            serde_json::json!({ "backendNodeId": node.backend_node_id }),
+        )
+        .map_err(|_| stale("the node no longer exists"))?;
+        if let Some(object_id) = object.object.object_id {
+            let connected = Self::call_cdp::<Runtime::CallFunctionOn>(
+                tab,
+                serde_json::json!({
+                    "objectId": object_id,
+                    "functionDeclaration": "function() { return this.isConnected; }",
+                    "returnByValue": true
+                }),
+            )?;
+            let _ = Self::call_cdp::<Runtime::ReleaseObject>(
+                tab,
+                serde_json::json!({ "objectId": object_id }),
+            );
+            if connected.result.value == Some(Value::Bool(false)) {
+                return Err(stale("the node was removed from the document"));
+            }
+        }
+
+        let node = described.node;
+        let attributes = node
+            .attributes
+            .unwrap_or_default()
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair.get(1).cloned().unwrap_or_default()))
+            .collect();
+        Ok(NodeDescription {
+            backend_node_id: node.backend_node_id,
+            local_name: node.local_name,
+            node_name: node.node_name,
+            attributes,
+            child_count: node.child_node_count.unwrap_or(0) as usize,
+            frame_id: node.frame_id,
+        })
+    }
+
+    async fn add_init_script(&self, tab: &Self::TabHandle, source: &str) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Page;
+
+        Self::call_cdp::<Page::AddScriptToEvaluateOnNewDocument>(
+            tab,
+            serde_json::json!({ "source": source }),
+        )?;
+        Ok(true)
+    }
+
+    async fn watch_for_crash(&self, tab: &Self::TabHandle) -> Result<bool> {
+        self.crash_flag(tab)?;
+        Ok(true)
+    }
+
+    fn crashed_at(&self, tab: &Self::TabHandle) -> Option<String> {
+        self.crash_url(tab)
+    }
+
+    async fn set_page_active(&self, tab: &Self::TabHandle) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Page;
+
+        Self::call_cdp::<Page::SetWebLifecycleState>(
+            tab,
+            serde_json::json!({ "state": "active" }),
+        )?;
+        Ok(true)
+    }
+
+    async fn emulate_viewport(
+        &self,
+        tab: &Self::TabHandle,
+        viewport: &crate::core::config::Viewport,
+    ) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Emulation;
+
+        Self::call_cdp::<Emulation::SetDeviceMetricsOverride>(
+            tab,
+            serde_json::json!({
+                "width": viewport.width,
+                "height": viewport.height,
+                "deviceScaleFactor": viewport.device_scale_factor,
+                "mobile": viewport.mobile
+            }),
+        )?;
+        Ok(true)
+    }
+
+    async fn clear_viewport_emulation(&self, tab: &Self::TabHandle) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Emulation;
+
+        tab.call_method(Emulation::ClearDeviceMetricsOverride(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        Ok(true)
+    }
+
+    async fn set_user_agent(
+        &self,
+        tab: &Self::TabHandle,
+        user_agent: Option<&UserAgentOverride>,
+    ) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Emulation;
+
+        // An empty user agent drops the override
+        let params = match user_agent {
+            Some(user_agent) => serde_json::json!({
+                "userAgent": user_agent.user_agent,
+                "acceptLanguage": user_agent.accept_language,
+                "platform": user_agent.platform,
+                "userAgentMetadata": user_agent.metadata,
+            }),
+            None => serde_json::json!({ "userAgent": "" }),
+        };
+        Self::call_cdp::<Emulation::SetUserAgentOverride>(tab, params)?;
+        Ok(true)
+    }
+
+    async fn set_extra_headers(
+        &self,
+        tab: &Self::TabHandle,
+        headers: &HashMap<String, String>,
+    ) -> Result<bool> {
+        tab.set_extra_http_headers(
+            headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect(),
+        )
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        Ok(true)
+    }
+
+    async fn emulate_network(
+        &self,
+        tab: &Self::TabHandle,
+        conditions: Option<&NetworkConditions>,
+    ) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Network;
+
+        // Conditions only apply once the network domain is enabled
+        tab.call_method(Network::Enable {
+            max_total_buffer_size: None,
+            max_resource_buffer_size: None,
+            max_post_data_size: None,
+        })
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        // -1 throughput disables throttling
+        let conditions = conditions.copied().unwrap_or(NetworkConditions {
+            offline: false,
+            latency_ms: 0.0,
+            download_bytes_per_sec: -1.0,
+            upload_bytes_per_sec: -1.0,
+        });
+        let throughput = |bytes_per_sec: f64| {
+            if bytes_per_sec > 0.0 {
+                bytes_per_sec
+            } else {
+                -1.0
+            }
+        };
+        Self::call_cdp::<Network::EmulateNetworkConditions>(
+            tab,
+            serde_json::json!({
+                "offline": conditions.offline,
+                "latency": conditions.latency_ms,
+                "downloadThroughput": throughput(conditions.download_bytes_per_sec),
+                "uploadThroughput": throughput(conditions.upload_bytes_per_sec),
+            }),
+        )?;
+        Ok(true)
+    }
+
+    async fn emulate_cpu(&self, tab: &Self::TabHandle, slowdown: f64) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Emulation;
+
+        Self::call_cdp::<Emulation::SetCPUThrottlingRate>(
+            tab,
+            serde_json::json!({ "rate": slowdown.max(1.0) }),
+        )?;
+        Ok(true)
+    }
+
+    async fn start_js_coverage(&self, tab: &Self::TabHandle) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Profiler;
+
+        Self::call_cdp::<Profiler::Enable>(tab, serde_json::json!(null))?;
+        // Block-level counts, so unused branches inside functions that ran show up
+        Self::call_cdp::<Profiler::StartPreciseCoverage>(
+            tab,
+            serde_json::json!({ "callCount": true, "detailed": true }),
+        )?;
+        Ok(true)
+    }
+
+    async fn stop_js_coverage(
+        &self,
+        tab: &Self::TabHandle,
+    ) -> Result<Option<Vec<crate::core::ScriptCoverage>>> {
+        use crate::core::{CountedRange, ScriptCoverage};
+        use headless_chrome::protocol::cdp::Profiler;
+
+        let taken = Self::call_cdp::<Profiler::TakePreciseCoverage>(tab, serde_json::json!(null))?;
+        Self::call_cdp::<Profiler::StopPreciseCoverage>(tab, serde_json::json!(null))?;
+        Self::call_cdp::<Profiler::Disable>(tab, serde_json::json!(null))?;
+        let scripts = taken
+            .result
+            .iter()
+            .map(|script| {
+                let ranges: Vec<CountedRange> = script
+                    .functions
+                    .iter()
+                    .flat_map(|function| &function.ranges)
+                    .map(|range| CountedRange {
+                        start: range.start_offset as usize,
+                        end: range.end_offset as usize,
+                        count: range.count as u64,
+                    })
+                    .collect();
+                ScriptCoverage::from_ranges(script.url.clone(), &ranges)
+            })
+            .collect();
+        Ok(Some(scripts))
+    }
+
+    async fn start_console_capture(&self, tab: &Self::TabHandle) -> Result<bool> {
+        self.console_log(tab)?;
+        Ok(true)
+    }
+
+    async fn console_messages(&self, tab: &Self::TabHandle) -> Result<Vec<ConsoleMessage>> {
+        Ok(self.console_log(tab)?.messages.lock().unwrap().clone())
+    }
+
+    async fn subscribe_console(
+        &self,
+        tab: &Self::TabHandle,
+    ) -> Result<Option<broadcast::Receiver<ConsoleMessage>>> {
+        Ok(Some(self.console_log(tab)?.sender.subscribe()))
+    }
+
+    async fn subscribe_responses(
+        &self,
+        tab: &Self::TabHandle,
+        max_body_bytes: usize,
+    ) -> Result<Option<broadcast::Receiver<CapturedResponse>>> {
+        let capture = self.response_capture(tab)?;
+        capture
+            .max_body_bytes
+            .store(max_body_bytes, Ordering::Relaxed);
+        Ok(Some(capture.sender.subscribe()))
+    }
+
+    async fn subscribe_websockets(
+        &self,
+        tab: &Self::TabHandle,
+        max_payload_bytes: usize,
+    ) -> Result<Option<broadcast::Receiver<WsEvent>>> {
+        let observer = self.websocket_observer(tab)?;
+        observer
+            .max_payload_bytes
+            .store(max_payload_bytes, Ordering::Relaxed);
+        Ok(Some(observer.sender.subscribe()))
+    }
+
+    async fn list_service_workers(&self, tab: &Self::TabHandle) -> Result<Vec<ServiceWorkerInfo>> {
+        let (registry, new) = self.service_worker_registry(tab)?;
+        if new {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        }
+        let registrations = registry.lock().unwrap();
+        Ok(registrations.values().cloned().collect())
+    }
+
+    async fn unregister_service_workers(&self, tab: &Self::TabHandle) -> Result<usize> {
+        use headless_chrome::protocol::cdp::ServiceWorker;
+
+        let registrations = self.list_service_workers(tab).await?;
+        for registration in &registrations {
+            tab.call_method(ServiceWorker::Unregister {
+                scope_url: registration.scope_url.clone(),
+            })
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        }
+        Ok(registrations.len())
+    }
+
+    async fn clear_site_data(
+        &self,
+        tab: &Self::TabHandle,
+        origin: &str,
+        kinds: &[SiteDataKind],
+    ) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Storage;
+
+        let storage_types = if kinds.is_empty() {
+            SiteDataKind::All.storage_type().to_string()
+        } else {
+            kinds
+                .iter()
+                .map(|kind| kind.storage_type())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        tab.call_method(Storage::ClearDataForOrigin {
+            origin: origin.to_string(),
+            storage_Types: storage_types,
+        })
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        Ok(true)
+    }
+
+    async fn set_bypass_service_worker(&self, tab: &Self::TabHandle, bypass: bool) -> Result<bool> {
+        use headless_chrome::protocol::cdp::Network;
+
+        tab.call_method(Network::Enable {
+            max_total_buffer_size: None,
+            max_resource_buffer_size: None,
+            max_post_data_size: None,
+        })
+        .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        tab.call_method(Network::SetBypassServiceWorker { bypass })
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        Ok(true)
+    }
+
+    async fn indexed_db_summary(&self, tab: &Self::TabHandle) -> Result<Vec<IndexedDbDatabase>> {
+        use headless_chrome::protocol::cdp::IndexedDB;
+        use serde_json::json;
+
+        let origin = match url::Url::parse(&tab.get_url()) {
+            Ok(url) if url.origin().is_tuple() => url.origin().ascii_serialization(),
+            _ => return Ok(Vec::new()),
+        };
+
+        tab.call_method(IndexedDB::Enable(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+        let names = Self::call_cdp::<IndexedDB::RequestDatabaseNames>(
+            tab,
+            json!({ "securityOrigin": origin }),
+        )?
+        .database_names;
+
+        let mut databases = Vec::with_capacity(names.len());
+        for name in names {
+            let database = Self::call_cdp::<IndexedDB::RequestDatabase>(
+                tab,
+                json!({ "securityOrigin": origin, "databaseName": name }),
+            )?
+            .database_with_object_stores;
+
+            let mut object_stores = Vec::with_capacity(database.object_stores.len());
+            for store in database.object_stores {
+                let metadata = Self::call_cdp::<IndexedDB::GetMetadata>(
+                    tab,
+                    json!({
+                        "securityOrigin": origin,
+                        "databaseName": database.name,
+                        "objectStoreName": store.name
+                    }),
+                )?;
+                object_stores.push(IndexedDbStore {
+                    name: store.name,
+                    record_count: metadata.entries_count as u64,
+                });
+            }
+
+            databases.push(IndexedDbDatabase {
+                origin: origin.clone(),
+                name: database.name,
+                version: database.version,
+                object_stores,
+            });
+        }
+        Ok(databases)
+    }
+
+    async fn websocket_stats(&self, tab: &Self::TabHandle) -> Result<Vec<WsConnectionStats>> {
+        let observer = self.websocket_observer(tab)?;
+        let connections = observer.connections.lock().unwrap();
+        Ok(connections.values().cloned().collect())
+    }
+
+    async fn list_frames(&self, tab: &Self::TabHandle) -> Result<Vec<FrameInfo>> {
+        use headless_chrome::protocol::cdp::Page;
+
+        let tree = tab
+            .call_method(Page::GetFrameTree(None))
+            .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))?;
+
+        fn walk(tree: &Page::FrameTree, frames: &mut Vec<FrameInfo>) {
+            frames.push(FrameInfo {
+                id: tree.frame.id.clone(),
+                parent_id: tree.frame.parent_id.clone(),
+                url: tree.frame.url.clone(),
+                origin: tree.frame.security_origin.clone(),
+                name: tree.frame.name.clone(),
+            });
+            for child in tree.child_frames.iter().flatten() {
+                walk(child, frames);
+            }
+        }
+
+        let mut frames = Vec::new();
+        walk(&tree.frame_tree, &mut frames);
+        Ok(frames)
+    }
+
+    async fn execute_script_in_frame(
+        &self,
+        tab: &Self::TabHandle,
+        frame: &FrameTarget,
+        script: &str,
+    ) -> Result<Value> {
+        use headless_chrome::protocol::cdp::{Page, Runtime};
+
+        let frames = self.list_frames(tab).await?;
+        let frame_id = self.resolve_frame_id(tab, &frames, frame)?;
+
+        // An isolated world gives us an execution context bound to that frame,
+        // including cross-origin frames page JavaScript cannot reach.
+        let world = Self::call_cdp::<Page::CreateIsolatedWorld>(
+            tab,
+            serde_json::json!({
+                "frameId": frame_id,
+                "worldName": "surfai",
+                "grantUniveralAccess": true
+            }),
+        )?;
+
+        let evaluated = Self::call_cdp::<Runtime::Evaluate>(
+            tab,
+            serde_json::json!({
+                "expression": script,
+                "contextId": world.execution_context_id,
+                "returnByValue": true,
+                "awaitPromise": true
+            }),
+        )?;
+
+        if let Some(exception) = evaluated.exception_details {
+            return Err(BrowserAgentError::JavaScriptFailed(exception.text));
+        }
+
+        Ok(evaluated.result.value.unwrap_or(Value::Null))
+    }
+
+    async fn evaluate_async(
+        &self,
+        tab: &Self::TabHandle,
+        script: &str,
+        timeout_ms: u64,
+    ) -> Result<Value> {
+        use headless_chrome::protocol::cdp::Runtime;
+
+        let evaluated = Self::call_cdp::<Runtime::Evaluate>(
+            tab,
+            serde_json::json!({
+                "expression": script,
+                "returnByValue": true,
+                "awaitPromise": true,
+                "timeout": timeout_ms
+            }),
+        )?;
+
+        if let Some(details) = evaluated.exception_details {
+            return Err(BrowserAgentError::JavaScriptFailed(
+                details
+                    .exception
+                    .as_ref()
+                    .and_then(|e| e.description.clone())
+                    .unwrap_or_else(|| details.text.clone()),
+            ));
+        }
+
+        Ok(evaluated.result.value.unwrap_or(Value::Null))
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "chrome"
+    }
+
+    fn capabilities(&self) -> &BrowserCapabilities {
+        &self.capabilities
+    }
+
     fn is_running(&self) -> bool {
         self.browser.is_some()
     }
@@ -145,3 +1813,140 @@ impl BrowserTrait for ChromeBrowser {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::WindowPosition;
+
+    fn config(args: &[&str]) -> BrowserConfig {
+        BrowserConfig {
+            args: args.iter().map(|a| a.to_string()).collect(),
+            ..BrowserConfig::default()
+        }
+    }
+
+    #[test]
+    fn generated_args_follow_the_config() {
+        let mut config = config(&[]);
+        config.lang = Some("de-DE".to_string());
+        config.window_position = Some(WindowPosition { x: 10, y: -20 });
+        config.disable_images = true;
+        let (args, warnings) = launch_args(&config);
+        assert!(warnings.is_empty());
+        assert_eq!(
+            args,
+            [
+                "--no-sandbox".to_string(),
+                "--disable-dev-shm-usage".to_string(),
+                format!(
+                    "--window-size={},{}",
+                    config.viewport.width, config.viewport.height
+                ),
+                "--headless=new".to_string(),
+                "--disable-gpu".to_string(),
+                "--no-first-run".to_string(),
+                "--lang=de-DE".to_string(),
+                "--window-position=10,-20".to_string(),
+                "--blink-settings=imagesEnabled=false".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn default_config_args() {
+        let (args, warnings) = launch_args(&BrowserConfig::default());
+        assert!(warnings.is_empty());
+        assert_eq!(
+            args,
+            [
+                "--no-sandbox",
+                "--disable-dev-shm-usage",
+                "--window-size=1280,720",
+                "--headless=new",
+                "--disable-gpu",
+                "--no-first-run",
+            ]
+        );
+    }
+
+    #[test]
+    fn headless_modes_map_to_flags() {
+        let mut config = config(&[]);
+        config.headless = HeadlessMode::Legacy;
+        config.disable_gpu = false;
+        config.no_first_run = false;
+        config.user_agent = Some("surfai-test".to_string());
+        assert_eq!(
+            launch_args(&config).0,
+            [
+                "--no-sandbox",
+                "--disable-dev-shm-usage",
+                "--window-size=1280,720",
+                "--headless",
+                "--user-agent=surfai-test",
+            ]
+        );
+
+        config.headless = HeadlessMode::Off;
+        assert_eq!(
+            launch_args(&config).0,
+            [
+                "--no-sandbox",
+                "--disable-dev-shm-usage",
+                "--window-size=1280,720",
+                "--user-agent=surfai-test",
+            ]
+        );
+    }
+
+    #[test]
+    fn user_headless_flag_replaces_the_generated_mode() {
+        let (args, warnings) = launch_args(&config(&["--headless"]));
+        assert!(!args.iter().any(|arg| arg == "--headless=new"));
+        assert_eq!(args.last().map(String::as_str), Some("--headless"));
+        assert_eq!(
+            warnings,
+            ["'--headless' overrides a flag generated from the config"]
+        );
+    }
+
+    #[test]
+    fn repeated_user_arg_is_dropped_with_a_warning() {
+        let (args, warnings) = launch_args(&config(&["--no-first-run", "--mute-audio"]));
+        assert_eq!(
+            args.iter().filter(|arg| *arg == "--no-first-run").count(),
+            1
+        );
+        assert_eq!(args.last().map(String::as_str), Some("--mute-audio"));
+        assert_eq!(
+            warnings,
+            ["ignoring '--no-first-run': already generated from the config"]
+        );
+    }
+
+    #[test]
+    fn user_arg_replaces_same_and_opposing_flags() {
+        let (args, warnings) = launch_args(&config(&["--window-size=800,600", "--enable-gpu"]));
+        assert!(!args.iter().any(|arg| arg == "--disable-gpu"));
+        assert_eq!(
+            args.iter()
+                .filter(|arg| arg.starts_with("--window-size"))
+                .collect::<Vec<_>>(),
+            ["--window-size=800,600"]
+        );
+        assert_eq!(
+            &args[args.len() - 2..],
+            ["--window-size=800,600", "--enable-gpu"]
+        );
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[1].contains("'--enable-gpu' overrides"));
+    }
+
+    #[test]
+    fn major_version_reads_both_formats() {
+        assert_eq!(major_version("Google Chrome 124.0.6367.91"), Some(124));
+        assert_eq!(major_version("HeadlessChrome/99.0.4844.51"), Some(99));
+        assert_eq!(major_version("Chromium"), None);
+    }
+}
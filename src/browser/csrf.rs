@@ -0,0 +1,305 @@
+use crate::core::config::{CsrfOptions, CsrfSource};
+use crate::errors::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+
+/// Header a token goes in when its name suggests no other
+pub const DEFAULT_CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Anti-forgery field and cookie names of common frameworks that say neither
+/// `csrf` nor `xsrf`
+pub const ANTI_FORGERY_NAMES: &[&str] =
+    &["authenticity_token", "__RequestVerificationToken", "_token"];
+
+/// CSRF token read from the page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsrfToken {
+    /// Cookie, meta, field or storage key it was read from
+    pub name: String,
+    pub value: String,
+    pub source: CsrfSource,
+    /// Header the token is sent in
+    pub header: String,
+}
+
+#[derive(Deserialize)]
+struct Candidate {
+    name: String,
+    value: String,
+    source: CsrfSource,
+}
+
+/// Keeps track of the page's CSRF token.
+///
+/// `BrowserSession` reads the configured sources after each navigation and again
+/// before every use, so a token the site rotated since is picked up. `fetch`
+/// sends it in `current_csrf_header`'s header and `submit_and_wait` copies it
+/// into the anti-forgery fields of the form it submits.
+pub struct CsrfManager {
+    options: CsrfOptions,
+    current: Mutex<Option<CsrfToken>>,
+}
+
+impl CsrfManager {
+    pub fn new(options: CsrfOptions) -> Self {
+        Self {
+            options,
+            current: Mutex::new(None),
+        }
+    }
+
+    pub fn options(&self) -> &CsrfOptions {
+        &self.options
+    }
+
+    /// Whether any source is configured
+    pub fn enabled(&self) -> bool {
+        !self.options.sources.is_empty()
+    }
+
+    /// Token found by the last read, if any
+    pub fn current(&self) -> Option<CsrfToken> {
+        self.current.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        *self.current.lock().unwrap() = None;
+    }
+
+    /// Header for a token named `name`: the configured header, else
+    /// `X-XSRF-TOKEN` for XSRF names, `X-CSRFToken` for Django's,
+    /// `RequestVerificationToken` for ASP.NET's and `X-CSRF-Token` otherwise
+    pub fn header_for(&self, name: &str) -> String {
+        if let Some(header) = &self.options.header {
+            return header.clone();
+        }
+        let lower = name.to_lowercase();
+        if lower.contains("xsrf") {
+            "X-XSRF-TOKEN".to_string()
+        } else if lower == "csrftoken" || lower == "csrfmiddlewaretoken" {
+            "X-CSRFToken".to_string()
+        } else if name == "__RequestVerificationToken" {
+            "RequestVerificationToken".to_string()
+        } else {
+            DEFAULT_CSRF_HEADER.to_string()
+        }
+    }
+
+    fn names(&self) -> Vec<String> {
+        ANTI_FORGERY_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(self.options.extra_names.iter().cloned())
+            .collect()
+    }
+
+    /// Script listing `{ name, value, source }` for every token in the
+    /// configured sources
+    pub(crate) fn read_script(&self) -> String {
+        format!(
+            r#"
+            (function() {{
+                const sources = {sources};
+                const names = {names};
+                const matches = name => /csrf|xsrf/i.test(name) && !/[-_]param$/i.test(name)
+                    || names.includes(name);
+                const found = [];
+                const add = (name, value, source) => {{
+                    if (name && value && matches(name)) found.push({{ name, value, source }});
+                }};
+
+                if (sources.includes('cookie')) {{
+                    for (const pair of document.cookie.split(';')) {{
+                        const index = pair.indexOf('=');
+                        if (index < 0) continue;
+                        let value = pair.slice(index + 1).trim();
+                        try {{ value = decodeURIComponent(value); }} catch (e) {{}}
+                        add(pair.slice(0, index).trim(), value, 'cookie');
+                    }}
+                }}
+                if (sources.includes('meta')) {{
+                    document.querySelectorAll('meta[name]').forEach(meta =>
+                        add(meta.getAttribute('name'), meta.getAttribute('content'), 'meta'));
+                }}
+                if (sources.includes('hidden_input')) {{
+                    document.querySelectorAll('input[type="hidden"][name]').forEach(input =>
+                        add(input.name, input.value, 'hidden_input'));
+                }}
+                if (sources.includes('storage')) {{
+                    for (const storage of [window.localStorage, window.sessionStorage]) {{
+                        try {{
+                            for (let i = 0; i < storage.length; i++) {{
+                                const key = storage.key(i);
+                                add(key, storage.getItem(key), 'storage');
+                            }}
+                        }} catch (e) {{}}
+                    }}
+                }}
+                return found;
+            }})()
+            "#,
+            sources = serde_json::to_string(&self.options.sources).unwrap_or_default(),
+            names = serde_json::to_string(&self.names()).unwrap_or_default(),
+        )
+    }
+
+    /// Keep the preferred token of what `read_script` returned, or none
+    pub(crate) fn update(&self, found: Value) -> Result<Option<CsrfToken>> {
+        let mut candidates: Vec<Candidate> = serde_json::from_value(found)?;
+        candidates.sort_by_key(|candidate| {
+            self.options
+                .sources
+                .iter()
+                .position(|source| *source == candidate.source)
+                .unwrap_or(usize::MAX)
+        });
+        let token = candidates.into_iter().next().map(|candidate| CsrfToken {
+            header: self.header_for(&candidate.name),
+            name: candidate.name,
+            value: candidate.value,
+            source: candidate.source,
+        });
+        *self.current.lock().unwrap() = token.clone();
+        Ok(token)
+    }
+
+    /// Script setting the anti-forgery fields of the form `element` is, or is in,
+    /// to `value`; evaluates to the number of fields changed
+    pub(crate) fn form_field_script(&self, element: &str, value: &str) -> String {
+        format!(
+            r#"
+            (function() {{
+                const names = {names};
+                const el = {element};
+                if (!el) return 0;
+                const form = el.tagName === 'FORM' ? el : (el.form || el.closest('form'));
+                if (!form) return 0;
+                let changed = 0;
+                form.querySelectorAll('input[type="hidden"][name]').forEach(input => {{
+                    const anti = /csrf|xsrf/i.test(input.name) || names.includes(input.name);
+                    if (anti && input.value !== {value}) {{
+                        input.value = {value};
+                        changed++;
+                    }}
+                }});
+                return changed;
+            }})()
+            "#,
+            names = serde_json::to_string(&self.names()).unwrap_or_default(),
+            element = element,
+            value = serde_json::to_string(value).unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn manager() -> CsrfManager {
+        CsrfManager::new(CsrfOptions::default())
+    }
+
+    #[test]
+    fn headers_follow_the_token_name() {
+        let manager = manager();
+        for (name, header) in [
+            ("XSRF-TOKEN", "X-XSRF-TOKEN"),
+            ("_xsrf", "X-XSRF-TOKEN"),
+            ("csrftoken", "X-CSRFToken"),
+            ("csrfmiddlewaretoken", "X-CSRFToken"),
+            ("__RequestVerificationToken", "RequestVerificationToken"),
+            ("csrf-token", DEFAULT_CSRF_HEADER),
+            ("authenticity_token", DEFAULT_CSRF_HEADER),
+            ("_token", DEFAULT_CSRF_HEADER),
+        ] {
+            assert_eq!(manager.header_for(name), header, "{}", name);
+        }
+    }
+
+    #[test]
+    fn a_configured_header_wins() {
+        let manager = CsrfManager::new(CsrfOptions {
+            header: Some("X-App-Csrf".to_string()),
+            ..CsrfOptions::default()
+        });
+        assert_eq!(manager.header_for("XSRF-TOKEN"), "X-App-Csrf");
+        assert_eq!(manager.header_for("csrftoken"), "X-App-Csrf");
+    }
+
+    #[test]
+    fn the_most_preferred_source_wins() {
+        let manager = manager();
+        let token = manager
+            .update(json!([
+                { "name": "csrf-token", "value": "from-meta", "source": "meta" },
+                { "name": "_token", "value": "from-field", "source": "hidden_input" },
+                { "name": "XSRF-TOKEN", "value": "from-cookie", "source": "cookie" }
+            ]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.value, "from-cookie");
+        assert_eq!(token.source, CsrfSource::Cookie);
+        assert_eq!(token.header, "X-XSRF-TOKEN");
+        assert_eq!(manager.current(), Some(token));
+    }
+
+    #[test]
+    fn source_order_comes_from_the_options() {
+        let manager = CsrfManager::new(CsrfOptions {
+            sources: vec![CsrfSource::HiddenInput, CsrfSource::Cookie],
+            ..CsrfOptions::default()
+        });
+        let token = manager
+            .update(json!([
+                { "name": "XSRF-TOKEN", "value": "from-cookie", "source": "cookie" },
+                { "name": "csrfmiddlewaretoken", "value": "from-field", "source": "hidden_input" }
+            ]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.value, "from-field");
+        assert_eq!(token.header, "X-CSRFToken");
+    }
+
+    #[test]
+    fn reading_nothing_clears_the_token() {
+        let manager = manager();
+        manager
+            .update(json!([{ "name": "_token", "value": "a", "source": "meta" }]))
+            .unwrap();
+        assert!(manager.current().is_some());
+        assert_eq!(manager.update(json!([])).unwrap(), None);
+        assert_eq!(manager.current(), None);
+        assert!(manager.update(json!({ "name": "_token" })).is_err());
+    }
+
+    #[test]
+    fn scripts_carry_the_sources_and_names() {
+        let manager = CsrfManager::new(CsrfOptions {
+            sources: vec![CsrfSource::Meta],
+            extra_names: vec!["my_guard".to_string()],
+            header: None,
+        });
+        assert!(manager.enabled());
+        let script = manager.read_script();
+        assert!(
+            script.contains(r#"const sources = ["meta"];"#),
+            "{}",
+            script
+        );
+        assert!(script.contains(r#""authenticity_token""#));
+        assert!(script.contains(r#""my_guard""#));
+
+        let script = manager.form_field_script("document.forms[0]", "t\"ok");
+        assert!(script.contains("const el = document.forms[0];"));
+        assert!(script.contains(r#"input.value = "t\"ok";"#), "{}", script);
+
+        let off = CsrfManager::new(CsrfOptions {
+            sources: Vec::new(),
+            ..CsrfOptions::default()
+        });
+        assert!(!off.enabled());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::browser::actionability::ActionabilityReport;
+use serde::{Deserialize, Serialize};
+
+/// What an interaction would have done, from `BrowserSession::dry_run_click`,
+/// `dry_run_type` or `dry_run_navigate`. Nothing was dispatched to the page and
+/// nothing navigated; what the page would change in response is not predicted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunReport {
+    /// `click`, `type` or `navigate`
+    pub action: String,
+    /// Selector or URL as given
+    pub target: String,
+    /// Elements the selector matched; 0 for navigations
+    pub matches: usize,
+    /// Tag, id and text of the element that would be acted on
+    pub element: Option<String>,
+    /// Checks run without scrolling; an element out of view skips the hit test
+    pub actionability: Option<ActionabilityReport>,
+    /// Absolute URL the navigation, or the link or submit button clicked, would load
+    pub resolved_url: Option<String>,
+    /// Domain policy rule that would refuse `resolved_url`
+    pub blocked_by: Option<String>,
+    /// Why the action would fail; empty when it would go ahead
+    pub problems: Vec<String>,
+}
+
+impl DryRunReport {
+    pub(crate) fn new(action: &str, target: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            target: target.to_string(),
+            matches: 0,
+            element: None,
+            actionability: None,
+            resolved_url: None,
+            blocked_by: None,
+            problems: Vec::new(),
+        }
+    }
+
+    pub fn would_succeed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Script evaluating to `{ count, element, href }` for the elements
+/// `elements_expression` yields: how many there are, a description of the first,
+/// and the URL clicking it would load, through a link or a form's submit button
+pub(crate) fn inspect_script(elements_expression: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const elements = {elements};
+            const element = elements[0] || null;
+            if (!element) return {{ count: 0, element: null, href: null }};
+            const text = (element.innerText || element.value || '').replace(/\s+/g, ' ').trim();
+            const id = element.id ? '#' + element.id : '';
+            const link = element.closest('a[href], area[href]');
+            const submits = element.form && element.type === 'submit';
+            return {{
+                count: elements.length,
+                element: element.tagName.toLowerCase() + id + (text ? ' "' + text.slice(0, 80) + '"' : ''),
+                href: link ? link.href : submits ? element.form.action : null
+            }};
+        }})()
+        "#,
+        elements = elements_expression
+    )
+}
@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+
+/// Content of a rich text editor, from `BrowserSession::get_editor_content`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditorContent {
+    /// Plain text in reading order, blocks separated by newlines
+    pub text: String,
+    /// Headings, bold, italic, inline code, links and lists as markdown; the plain
+    /// text when no formatting could be derived
+    pub markdown: String,
+    pub source: EditorTextSource,
+}
+
+/// Where `EditorContent::text` came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditorTextSource {
+    /// The editor's own copy handler, as for ProseMirror and Slate, which
+    /// serialize their document model rather than the rendered DOM
+    Clipboard,
+    /// The text of a selection spanning the whole editor
+    Selection,
+}
+
+/// `toMarkdown(root)`: markdown for the children of `root`, from their tags and
+/// computed styles
+const MARKDOWN_JS: &str = r#"
+            const toMarkdown = (root) => {
+                const styled = (node, property, test) => {
+                    try { return test(getComputedStyle(node)[property]); } catch (e) { return false; }
+                };
+                const inline = (node) => {
+                    if (node.nodeType === Node.TEXT_NODE) return node.textContent.replace(/\s+/g, ' ');
+                    if (node.nodeType !== Node.ELEMENT_NODE) return '';
+                    const tag = node.tagName.toLowerCase();
+                    if (tag === 'br') return '\n';
+                    let text = Array.from(node.childNodes).map(inline).join('');
+                    if (!text.trim()) return text;
+                    if (tag === 'code') return '`' + text + '`';
+                    if (tag === 'a' && node.getAttribute('href')) {
+                        return '[' + text + '](' + node.getAttribute('href') + ')';
+                    }
+                    const bold = tag === 'b' || tag === 'strong'
+                        || styled(node, 'fontWeight', w => parseInt(w, 10) >= 600);
+                    const italic = tag === 'i' || tag === 'em'
+                        || styled(node, 'fontStyle', s => s === 'italic');
+                    const parentBold = node.parentElement && node.parentElement !== root
+                        && styled(node.parentElement, 'fontWeight', w => parseInt(w, 10) >= 600);
+                    if (bold && !parentBold && !/^h[1-6]$/.test(tag)) text = '**' + text.trim() + '**';
+                    if (italic && !['i', 'em'].includes(node.parentElement && node.parentElement.tagName.toLowerCase())) {
+                        text = '*' + text.trim() + '*';
+                    }
+                    return text;
+                };
+                const blocks = [];
+                const block = (node, depth) => {
+                    if (node.nodeType !== Node.ELEMENT_NODE) {
+                        const text = inline(node).trim();
+                        if (text) blocks.push(text);
+                        return;
+                    }
+                    const tag = node.tagName.toLowerCase();
+                    if (tag === 'ul' || tag === 'ol') {
+                        let number = 0;
+                        for (const item of node.children) {
+                            number++;
+                            const marker = tag === 'ol' ? number + '. ' : '- ';
+                            const nested = Array.from(item.children).filter(c => ['ul', 'ol'].includes(c.tagName.toLowerCase()));
+                            const own = Array.from(item.childNodes).filter(c => !nested.includes(c));
+                            blocks.push('  '.repeat(depth) + marker + own.map(inline).join('').trim());
+                            nested.forEach(list => block(list, depth + 1));
+                        }
+                        return;
+                    }
+                    const heading = /^h([1-6])$/.exec(tag);
+                    if (heading) {
+                        blocks.push('#'.repeat(Number(heading[1])) + ' ' + inline(node).trim());
+                        return;
+                    }
+                    const hasBlocks = Array.from(node.children).some(child =>
+                        /^(p|div|h[1-6]|ul|ol|blockquote|pre)$/.test(child.tagName.toLowerCase()));
+                    if (hasBlocks) {
+                        node.childNodes.forEach(child => block(child, depth));
+                        return;
+                    }
+                    const text = inline(node).trim();
+                    if (!text) return;
+                    if (tag === 'blockquote') blocks.push('> ' + text);
+                    else if (tag === 'pre') blocks.push('```\n' + node.textContent + '\n```');
+                    else blocks.push(text);
+                };
+                root.childNodes.forEach(child => block(child, 0));
+                return blocks.join('\n\n');
+            };
+"#;
+
+/// Script selecting everything in the editor `element_expression` yields and
+/// reading its text, first through a copy event the editor can serialize its own
+/// model into, then through the selection; markdown comes from the rendered
+/// markup. The previous selection is restored after.
+/// Evaluates to `{ success, text, markdown, source, error }`.
+pub(crate) fn read_editor_script(element_expression: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+{markdown}
+            const element = {element};
+            if (!element) return {{ success: false, error: 'Element not found' }};
+            if (!element.isContentEditable) return {{ success: false, error: 'Element is not editable' }};
+
+            const selection = window.getSelection();
+            const saved = [];
+            for (let i = 0; i < selection.rangeCount; i++) saved.push(selection.getRangeAt(i));
+            element.focus();
+            const range = document.createRange();
+            range.selectNodeContents(element);
+            selection.removeAllRanges();
+            selection.addRange(range);
+
+            let text = null, source = 'selection';
+            try {{
+                const data = new DataTransfer();
+                const copy = new ClipboardEvent('copy', {{ clipboardData: data, bubbles: true, cancelable: true }});
+                element.dispatchEvent(copy);
+                if (copy.defaultPrevented && data.getData('text/plain')) {{
+                    text = data.getData('text/plain');
+                    source = 'clipboard';
+                }}
+            }} catch (e) {{}}
+            if (text === null) text = selection.toString();
+
+            const markdown = toMarkdown(element);
+
+            selection.removeAllRanges();
+            saved.forEach(r => selection.addRange(r));
+            return {{
+                success: true,
+                text: text.replace(/\u00a0/g, ' ').replace(/\n{{3,}}/g, '\n\n').trim(),
+                markdown: markdown || text.trim(),
+                source: source
+            }};
+        }})()
+        "#,
+        markdown = MARKDOWN_JS,
+        element = element_expression
+    )
+}
+
+/// Script replacing everything in the editor `element_expression` yields with
+/// `text`, a line at a time.
+///
+/// Each insertion is offered to the editor as a cancelable `beforeinput` event
+/// first, the way ProseMirror and Slate expect typing to arrive; if the editor
+/// does not take it, the editing commands insert it, which fire the trusted
+/// events those editors also observe. Evaluates to `{ success, before, after,
+/// elementType, error }`, the shape of `InsertOutcome`.
+pub(crate) fn write_editor_script(element_expression: &str, text: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const element = {element};
+            if (!element) return {{ success: false, error: 'Element not found' }};
+            const elementType = element.tagName.toLowerCase();
+            if (!element.isContentEditable) {{
+                return {{ success: false, error: 'Element is not editable', elementType: elementType }};
+            }}
+            const before = element.innerText;
+            const lines = {text}.split('\n');
+
+            const offer = (inputType, data) => {{
+                const event = new InputEvent('beforeinput', {{
+                    inputType: inputType,
+                    data: data,
+                    bubbles: true,
+                    cancelable: true
+                }});
+                return !element.dispatchEvent(event);
+            }};
+            const perform = (inputType, data, command) => {{
+                if (offer(inputType, data)) return;
+                if (!document.execCommand(command, false, data)) {{
+                    element.dispatchEvent(new InputEvent('input', {{ inputType: inputType, data: data, bubbles: true }}));
+                }}
+            }};
+
+            try {{
+                element.focus();
+                const selection = window.getSelection();
+                const range = document.createRange();
+                range.selectNodeContents(element);
+                selection.removeAllRanges();
+                selection.addRange(range);
+                if (!selection.isCollapsed) perform('deleteContentBackward', null, 'delete');
+                lines.forEach((line, index) => {{
+                    if (index > 0) perform('insertParagraph', null, 'insertParagraph');
+                    if (line) perform('insertText', line, 'insertText');
+                }});
+                return {{ success: true, before: before, after: element.innerText, elementType: elementType }};
+            }} catch (error) {{
+                return {{ success: false, error: error.message, elementType: elementType }};
+            }}
+        }})()
+        "#,
+        element = element_expression,
+        text = serde_json::to_string(text).unwrap_or_else(|_| "''".to_string())
+    )
+}
+
+/// Shape of `read_editor_script`'s result
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct EditorReadOutcome {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub markdown: String,
+    #[serde(default)]
+    pub source: Option<EditorTextSource>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
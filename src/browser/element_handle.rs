@@ -0,0 +1,231 @@
+use crate::browser::typing::{TypeMode, TypeResult};
+use crate::browser::BrowserSession;
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::dom::ElementRect;
+use crate::errors::{BrowserAgentError, Result};
+use serde_json::Value;
+
+/// Attribute used to pin a specific DOM node across calls
+pub const HANDLE_ATTRIBUTE: &str = "data-surfai-id";
+
+/// A handle pinned to one DOM node.
+///
+/// The node is stamped with a unique `data-surfai-id` attribute when the handle is
+/// created, so every operation targets that exact node instead of re-resolving the
+/// original selector. If the node is removed or replaced, operations fail with
+/// `ElementDetached` rather than acting on a different element.
+pub struct ElementHandle<'a, B: BrowserTrait> {
+    session: &'a BrowserSession<B>,
+    handle_id: String,
+    selector: String,
+}
+
+impl<'a, B: BrowserTrait> ElementHandle<'a, B> {
+    /// Stamp the first element matching `selector` and return a handle to it
+    pub(crate) async fn attach(session: &'a BrowserSession<B>, selector: &str) -> Result<Self> {
+        let handle_id = session.next_id();
+        let script = format!(
+            r#"
+            (function() {{
+                const element = document.querySelector({});
+                if (!element) return {{ success: false }};
+                if (!element.hasAttribute('{attr}')) {{
+                    element.setAttribute('{attr}', {});
+                }}
+                return {{ success: true, id: element.getAttribute('{attr}') }};
+            }})()
+            "#,
+            serde_json::to_string(selector)?,
+            serde_json::to_string(&handle_id)?,
+            attr = HANDLE_ATTRIBUTE
+        );
+
+        let result = session.execute_script(&script).await?;
+        if !result
+            .get("success")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            return Err(BrowserAgentError::ElementNotFound(selector.to_string()));
+        }
+
+        // The node may already carry an id from an earlier handle; reuse it
+        let handle_id = result
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or(handle_id);
+
+        Ok(Self {
+            session,
+            handle_id,
+            selector: selector.to_string(),
+        })
+    }
+
+    /// The unique id stamped on the node
+    pub fn id(&self) -> &str {
+        &self.handle_id
+    }
+
+    /// The selector the handle was created from
+    pub fn selector(&self) -> &str {
+        &self.selector
+    }
+
+    /// CSS selector that matches only the pinned node
+    pub fn pinned_selector(&self) -> String {
+        format!("[{}=\"{}\"]", HANDLE_ATTRIBUTE, self.handle_id)
+    }
+
+    fn element_expression(&self) -> String {
+        format!(
+            "document.querySelector('[{}=\"{}\"]')",
+            HANDLE_ATTRIBUTE, self.handle_id
+        )
+    }
+
+    /// Evaluate `body` with `element` bound to the pinned node
+    async fn evaluate(&self, body: &str) -> Result<Value> {
+        let script = format!(
+            r#"
+            (function() {{
+                const element = {};
+                if (!element || !element.isConnected) return {{ detached: true }};
+                return {{ detached: false, value: (function() {{ {} }})() }};
+            }})()
+            "#,
+            self.element_expression(),
+            body
+        );
+
+        let result = self.session.execute_script(&script).await?;
+        if result
+            .get("detached")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true)
+        {
+            return Err(BrowserAgentError::ElementDetached(self.selector.clone()));
+        }
+        Ok(result.get("value").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Whether the node is still in the document
+    pub async fn is_attached(&self) -> Result<bool> {
+        match self.evaluate("return true;").await {
+            Ok(_) => Ok(true),
+            Err(BrowserAgentError::ElementDetached(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn is_visible(&self) -> Result<bool> {
+        let value = self
+            .evaluate(
+                r#"
+                const rect = element.getBoundingClientRect();
+                const style = window.getComputedStyle(element);
+                return rect.width > 0 && rect.height > 0
+                    && style.visibility !== 'hidden'
+                    && style.display !== 'none'
+                    && parseFloat(style.opacity || '1') > 0;
+                "#,
+            )
+            .await?;
+        Ok(value.as_bool().unwrap_or(false))
+    }
+
+    pub async fn is_enabled(&self) -> Result<bool> {
+        let value = self
+            .evaluate(
+                r#"
+                if (element.disabled) return false;
+                if (element.getAttribute('aria-disabled') === 'true') return false;
+                const fieldset = element.closest('fieldset[disabled]');
+                return !fieldset;
+                "#,
+            )
+            .await?;
+        Ok(value.as_bool().unwrap_or(false))
+    }
+
+    pub async fn text(&self) -> Result<String> {
+        let value = self
+            .evaluate("return (element.innerText || element.textContent || '').trim();")
+            .await?;
+        Ok(value.as_str().unwrap_or("").to_string())
+    }
+
+    pub async fn attribute(&self, name: &str) -> Result<Option<String>> {
+        let value = self
+            .evaluate(&format!(
+                "return element.getAttribute({});",
+                serde_json::to_string(name)?
+            ))
+            .await?;
+        Ok(value.as_str().map(|s| s.to_string()))
+    }
+
+    /// Bounding box in CSS pixels relative to the viewport
+    pub async fn bounding_box(&self) -> Result<ElementRect> {
+        let value = self
+            .evaluate(
+                r#"
+                const rect = element.getBoundingClientRect();
+                return { x: rect.x, y: rect.y, width: rect.width, height: rect.height };
+                "#,
+            )
+            .await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub async fn click(&self) -> Result<()> {
+        self.ensure_attached().await?;
+        self.session
+            .click_expression(&self.element_expression(), &self.selector)
+            .await
+    }
+
+    pub async fn type_text(&self, text: &str) -> Result<()> {
+        self.type_text_with_mode(text, TypeMode::Replace).await?;
+        Ok(())
+    }
+
+    /// Type `text` where `mode` says, returning the content before and after
+    pub async fn type_text_with_mode(&self, text: &str, mode: TypeMode) -> Result<TypeResult> {
+        self.ensure_attached().await?;
+        self.session
+            .type_into_expression(
+                &self.element_expression(),
+                &self.selector,
+                text,
+                mode,
+                false,
+            )
+            .await
+    }
+
+    /// Wait until the node leaves the document
+    pub async fn wait_for_detached(&self, timeout_ms: u64) -> Result<()> {
+        let clock = self.session.clock();
+        let start = clock.now();
+        while clock.elapsed_since(start).as_millis() < timeout_ms as u128 {
+            if !self.is_attached().await? {
+                return Ok(());
+            }
+            clock.sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+        Err(BrowserAgentError::TimeoutError(format!(
+            "Element {} still attached after {}ms",
+            self.selector, timeout_ms
+        )))
+    }
+
+    async fn ensure_attached(&self) -> Result<()> {
+        if self.is_attached().await? {
+            Ok(())
+        } else {
+            Err(BrowserAgentError::ElementDetached(self.selector.clone()))
+        }
+    }
+}
@@ -1,29 +1,153 @@
+use crate::browser::navigation::LIFECYCLE_JS;
 use crate::core::BrowserTrait;
 use crate::errors::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// Change events kept by `change_history` unless set with `with_history_len`
+pub const DEFAULT_HISTORY_LEN: usize = 100;
+
+/// Span `BrowserSession` summarizes recent DOM activity over
+pub const DEFAULT_ACTIVITY_WINDOW: Duration = Duration::from_secs(5);
+
+/// Mutations per second over the activity window above which a page counts as
+/// mutating heavily
+const HEAVY_MUTATIONS_PER_SEC: f64 = 10.0;
+
+/// JavaScript evaluating to the observer's running counters, or `{}` without one
+const COUNTERS_JS: &str = r#"(() => {
+                    const changes = window.browserAgentChanges;
+                    if (!changes) return {};
+                    return {
+                        startedAt: changes.startedAt,
+                        lastChangeTime: changes.lastChangeTime,
+                        totalMutations: changes.totalMutations,
+                        interactiveChanges: changes.interactiveChanges,
+                        visibilityChanges: changes.visibilityChanges
+                    };
+                })()"#;
+
 pub struct ElementMonitor {
     is_monitoring: Arc<RwLock<bool>>,
     observer_active: Arc<RwLock<bool>>,
+    history: Arc<std::sync::Mutex<ChangeHistory>>,
 }
 
 impl ElementMonitor {
     pub fn new() -> Self {
+        Self::with_history_len(DEFAULT_HISTORY_LEN)
+    }
+
+    /// Keep the last `len` change events instead of `DEFAULT_HISTORY_LEN`
+    pub fn with_history_len(len: usize) -> Self {
         Self {
             is_monitoring: Arc::new(RwLock::new(false)),
             observer_active: Arc::new(RwLock::new(false)),
+            history: Arc::new(std::sync::Mutex::new(ChangeHistory::new(len))),
+        }
+    }
+
+    /// The most recent change events, oldest first. Every check for changes adds
+    /// one when the page mutated since the previous check, across navigations.
+    pub fn change_history(&self) -> Vec<DomChangeEvent> {
+        self.history
+            .lock()
+            .unwrap()
+            .events
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Number of change events kept, at most the history length
+    pub fn history_len(&self) -> usize {
+        self.history.lock().unwrap().events.len()
+    }
+
+    /// Counters accumulated since the monitor was created or `reset_stats`
+    pub fn stats(&self) -> MonitorStats {
+        self.history.lock().unwrap().stats.clone()
+    }
+
+    /// Zero the counters and forget the change history
+    pub fn reset_stats(&self) {
+        let mut history = self.history.lock().unwrap();
+        history.events.clear();
+        history.stats = MonitorStats::default();
+    }
+
+    /// How much the page changed within the last `window`, as of the last check
+    pub fn activity(&self, window: Duration) -> MutationActivity {
+        let history = self.history.lock().unwrap();
+        let now = chrono::Utc::now();
+        let cutoff =
+            now - chrono::Duration::from_std(window).unwrap_or_else(|_| chrono::Duration::zero());
+        let mut activity = MutationActivity {
+            window_ms: window.as_millis() as u64,
+            last_change_at: history.events.back().map(|event| event.timestamp),
+            ..MutationActivity::default()
+        };
+        for event in history
+            .events
+            .iter()
+            .filter(|event| event.timestamp >= cutoff)
+        {
+            activity.change_events += 1;
+            activity.mutations += event.mutations;
+            activity.interactive_changes += event.interactive_changes;
+            activity.visibility_changes += event.visibility_changes;
         }
+        activity.heavy =
+            activity.mutations as f64 >= HEAVY_MUTATIONS_PER_SEC * window.as_secs_f64().max(1.0);
+        activity
+    }
+
+    /// Bring the page's running counters into the history without consuming its
+    /// pending changes, so a later `check_for_changes` still reports them
+    pub async fn sample<B: BrowserTrait>(&self, browser: &B, tab: &B::TabHandle) -> Result<()> {
+        let counters = browser.execute_script(tab, COUNTERS_JS).await?;
+        let result: DOMChangeResult = serde_json::from_value(counters)?;
+        self.record(&result);
+        Ok(())
     }
 
-    /// Start monitoring DOM changes with mutation observer
+    /// Take over `other`'s tab, e.g. when a prefetched tab with a monitor of its
+    /// own becomes the session's tab: its history and counters join this one's,
+    /// and its observer state replaces this one's
+    pub(crate) async fn adopt(&self, other: &ElementMonitor) {
+        if Arc::ptr_eq(&self.history, &other.history) {
+            return;
+        }
+        let theirs = std::mem::replace(&mut *other.history.lock().unwrap(), ChangeHistory::new(0));
+        {
+            let mut history = self.history.lock().unwrap();
+            history.stats.absorb(&theirs.stats);
+            for event in theirs.events {
+                history.push(event);
+            }
+            history.seen = theirs.seen;
+        }
+        *self.is_monitoring.write().await = *other.is_monitoring.read().await;
+        *self.observer_active.write().await = *other.observer_active.read().await;
+    }
+
+    fn record(&self, result: &DOMChangeResult) {
+        self.history.lock().unwrap().record(result);
+    }
+
+    /// Start monitoring DOM changes with mutation observer. When already
+    /// monitoring, the observer is reinstalled if the current document lacks it,
+    /// as after navigating to a new page.
     pub async fn start_monitoring<B: BrowserTrait>(
         &self,
         browser: &B,
         tab: &B::TabHandle,
     ) -> Result<()> {
         let mut monitoring = self.is_monitoring.write().await;
-        if *monitoring {
+        if *monitoring && self.observer_attached(browser, tab).await? {
             return Ok(());
         }
 
@@ -39,14 +163,32 @@ impl ElementMonitor {
                     hasChanges: false,
                     changeCount: 0,
                     lastChangeTime: Date.now(),
-                    changeTypes: []
+                    changeTypes: [],
+                    // Running counters, read into the session's change history
+                    startedAt: Date.now(),
+                    totalMutations: 0,
+                    interactiveChanges: 0,
+                    visibilityChanges: 0
                 };
 
+                // Highlights, labels and styles inserted by the agent itself
+                const isOwnNode = (node) => node.nodeType === 1 && !!node.closest('[data-surfai-internal]');
+
                 // Create mutation observer
-                window.browserAgentObserver = new MutationObserver((mutations) => {
+                window.browserAgentObserver = new MutationObserver((records) => {
+                    if (window.browserAgentChanges.paused) return;
                     let significantChange = false;
                     let changeTypes = [];
 
+                    const mutations = records
+                        .filter(mutation => !isOwnNode(mutation.target))
+                        .map(mutation => mutation.type !== 'childList' ? mutation : {
+                            type: mutation.type,
+                            addedNodes: Array.from(mutation.addedNodes).filter(node => !isOwnNode(node)),
+                            removedNodes: Array.from(mutation.removedNodes).filter(node => !isOwnNode(node))
+                        });
+                    window.browserAgentChanges.totalMutations += mutations.length;
+
                     mutations.forEach((mutation) => {
                         // Track different types of changes
                         if (mutation.type === 'childList') {
@@ -63,6 +205,7 @@ impl ElementMonitor {
                                 });
 
                                 if (hasInteractiveNodes) {
+                                    window.browserAgentChanges.interactiveChanges++;
                                     significantChange = true;
                                     changeTypes.push('interactive_elements');
                                 }
@@ -89,6 +232,7 @@ impl ElementMonitor {
                             // Track attribute changes that might affect interactivity
                             const attributeName = mutation.attributeName;
                             if (['class', 'style', 'disabled', 'hidden', 'aria-expanded', 'aria-hidden'].includes(attributeName)) {
+                                window.browserAgentChanges.visibilityChanges++;
                                 significantChange = true;
                                 changeTypes.push('visibility_changes');
                             }
@@ -111,7 +255,23 @@ impl ElementMonitor {
                     }
                 });
 
-                // Start observing
+                // A page restored from the back-forward cache kept this observer,
+                // but the session's view of it is stale
+{lifecycle}
+                if (!window.browserAgentRestoreListener) {
+                    window.browserAgentRestoreListener = (event) => {
+                        if (!event.persisted || !window.browserAgentChanges) return;
+                        const changes = window.browserAgentChanges;
+                        changes.hasChanges = true;
+                        changes.changeCount++;
+                        changes.lastChangeTime = Date.now();
+                        changes.changeTypes = [...new Set([...changes.changeTypes, 'bfcache_restore'])];
+                    };
+                    window.addEventListener('pageshow', window.browserAgentRestoreListener);
+                }
+
+                // Start observing. Element stamps (data-surfai-id) stay out of the
+                // filter so extraction never reads as a page change.
                 window.browserAgentObserver.observe(document.body, {
                     childList: true,
                     subtree: true,
@@ -123,7 +283,8 @@ impl ElementMonitor {
             })()
         "#;
 
-        browser.execute_script(tab, observer_script).await?;
+        let observer_script = observer_script.replace("{lifecycle}", LIFECYCLE_JS);
+        browser.execute_script(tab, &observer_script).await?;
         *monitoring = true;
         *self.observer_active.write().await = true;
 
@@ -131,6 +292,53 @@ impl ElementMonitor {
         Ok(())
     }
 
+    /// Whether the current document still has the observer `start_monitoring`
+    /// installed
+    pub async fn observer_attached<B: BrowserTrait>(
+        &self,
+        browser: &B,
+        tab: &B::TabHandle,
+    ) -> Result<bool> {
+        let check_script = r#"
+            (function() {
+                return !!(window.browserAgentObserver && window.browserAgentChanges);
+            })()
+        "#;
+        let attached = browser.execute_script(tab, check_script).await?;
+        Ok(attached.as_bool().unwrap_or(false))
+    }
+
+    /// Ignore DOM changes until `resume`, e.g. while the session draws highlights
+    pub async fn pause<B: BrowserTrait>(&self, browser: &B, tab: &B::TabHandle) -> Result<()> {
+        if !*self.observer_active.read().await {
+            return Ok(());
+        }
+        let pause_script = r#"
+            (function() {
+                if (window.browserAgentChanges) window.browserAgentChanges.paused = true;
+                return true;
+            })()
+        "#;
+        browser.execute_script(tab, pause_script).await?;
+        Ok(())
+    }
+
+    /// Report DOM changes again, dropping any made while paused
+    pub async fn resume<B: BrowserTrait>(&self, browser: &B, tab: &B::TabHandle) -> Result<()> {
+        if !*self.observer_active.read().await {
+            return Ok(());
+        }
+        let resume_script = r#"
+            (function() {
+                if (window.browserAgentObserver) window.browserAgentObserver.takeRecords();
+                if (window.browserAgentChanges) window.browserAgentChanges.paused = false;
+                return true;
+            })()
+        "#;
+        browser.execute_script(tab, resume_script).await?;
+        Ok(())
+    }
+
     /// Check if DOM has changed since last check
     pub async fn check_for_changes<B: BrowserTrait>(
         &self,
@@ -145,6 +353,7 @@ impl ElementMonitor {
 
                 const changes = window.browserAgentChanges;
                 const result = {
+                    ...{counters},
                     hasChanges: changes.hasChanges,
                     changeCount: changes.changeCount,
                     lastChangeTime: changes.lastChangeTime,
@@ -160,8 +369,11 @@ impl ElementMonitor {
             })()
         "#;
 
-        let result = browser.execute_script(tab, check_script).await?;
-        Ok(serde_json::from_value(result)?)
+        let check_script = check_script.replace("{counters}", COUNTERS_JS);
+        let result = browser.execute_script(tab, &check_script).await?;
+        let result: DOMChangeResult = serde_json::from_value(result)?;
+        self.record(&result);
+        Ok(result)
     }
 
     /// Wait for DOM changes with timeout
@@ -180,7 +392,7 @@ impl ElementMonitor {
                     const resolveOnce = (result) => {{
                         if (!resolved) {{
                             resolved = true;
-                            resolve(result);
+                            resolve({{ ...{}, ...result }});
                         }}
                     }};
 
@@ -219,11 +431,13 @@ impl ElementMonitor {
                 }});
             }})()
         "#,
-            timeout_ms
+            COUNTERS_JS, timeout_ms
         );
 
         let result = browser.execute_script(tab, &wait_script).await?;
-        Ok(serde_json::from_value(result)?)
+        let result: DOMChangeResult = serde_json::from_value(result)?;
+        self.record(&result);
+        Ok(result)
     }
 
     pub async fn stop_monitoring<B: BrowserTrait>(
@@ -237,6 +451,10 @@ impl ElementMonitor {
                     window.browserAgentObserver.disconnect();
                     delete window.browserAgentObserver;
                 }
+                if (window.browserAgentRestoreListener) {
+                    window.removeEventListener('pageshow', window.browserAgentRestoreListener);
+                    delete window.browserAgentRestoreListener;
+                }
                 if (window.browserAgentChanges) {
                     delete window.browserAgentChanges;
                 }
@@ -254,11 +472,300 @@ impl ElementMonitor {
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DOMChangeResult {
+    #[serde(default)]
     pub has_changes: bool,
     pub change_count: Option<u32>,
     pub change_types: Option<Vec<String>>,
     pub last_change_time: Option<u64>,
     pub time_since_last_change: Option<u64>,
     pub reason: Option<String>,
+    /// When the observer reporting the counters below was installed
+    #[serde(default)]
+    pub started_at: Option<u64>,
+    /// Mutations the observer has seen in this document, whether significant or not
+    #[serde(default)]
+    pub total_mutations: Option<u64>,
+    #[serde(default)]
+    pub interactive_changes: Option<u64>,
+    #[serde(default)]
+    pub visibility_changes: Option<u64>,
+}
+
+/// Mutations between two checks for changes, from `ElementMonitor::change_history`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomChangeEvent {
+    /// Time of the latest mutation among them
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// e.g. `interactive_elements`, `dropdown_suggestions`, `visibility_changes`
+    pub change_types: Vec<String>,
+    pub mutations: u64,
+    /// Mutations adding or removing inputs, buttons, links or forms
+    pub interactive_changes: u64,
+    /// Class, style, hidden, disabled and ARIA visibility attribute changes
+    pub visibility_changes: u64,
+}
+
+/// Cumulative counters of an `ElementMonitor`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorStats {
+    pub total_mutations: u64,
+    pub interactive_changes: u64,
+    pub visibility_changes: u64,
+    /// Change events recorded, including those since dropped from the history
+    pub change_events: u64,
+    pub since: chrono::DateTime<chrono::Utc>,
+}
+
+impl Default for MonitorStats {
+    fn default() -> Self {
+        Self {
+            total_mutations: 0,
+            interactive_changes: 0,
+            visibility_changes: 0,
+            change_events: 0,
+            since: chrono::Utc::now(),
+        }
+    }
+}
+
+impl MonitorStats {
+    fn absorb(&mut self, other: &MonitorStats) {
+        self.total_mutations += other.total_mutations;
+        self.interactive_changes += other.interactive_changes;
+        self.visibility_changes += other.visibility_changes;
+        self.change_events += other.change_events;
+    }
+}
+
+/// DOM activity over a recent window, from `ElementMonitor::activity`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MutationActivity {
+    pub window_ms: u64,
+    pub mutations: u64,
+    pub interactive_changes: u64,
+    pub visibility_changes: u64,
+    pub change_events: usize,
+    /// Latest recorded change, which may be older than the window
+    pub last_change_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// More than ten mutations a second over the window
+    pub heavy: bool,
+}
+
+impl MutationActivity {
+    /// Advice for an agent deciding whether to act now, when the page is busy
+    pub fn hint(&self) -> Option<String> {
+        self.heavy.then(|| {
+            format!(
+                "the page has been mutating heavily for the last {} seconds ({} mutations, {} to \
+                 interactive elements); consider waiting before acting",
+                self.window_ms / 1000,
+                self.mutations,
+                self.interactive_changes
+            )
+        })
+    }
+}
+
+/// Counters last read from the page, to turn its running totals into deltas
+#[derive(Debug, Clone)]
+struct SeenCounters {
+    started_at: u64,
+    last_change_time: Option<u64>,
+    total_mutations: u64,
+    interactive_changes: u64,
+    visibility_changes: u64,
+}
+
+struct ChangeHistory {
+    events: VecDeque<DomChangeEvent>,
+    capacity: usize,
+    stats: MonitorStats,
+    seen: Option<SeenCounters>,
+}
+
+impl ChangeHistory {
+    fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity.min(DEFAULT_HISTORY_LEN)),
+            capacity,
+            stats: MonitorStats::default(),
+            seen: None,
+        }
+    }
+
+    fn push(&mut self, event: DomChangeEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.events.len() >= self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    fn record(&mut self, result: &DOMChangeResult) {
+        let (Some(started_at), Some(total_mutations)) = (result.started_at, result.total_mutations)
+        else {
+            return;
+        };
+        let current = SeenCounters {
+            started_at,
+            last_change_time: result.last_change_time,
+            total_mutations,
+            interactive_changes: result.interactive_changes.unwrap_or(0),
+            visibility_changes: result.visibility_changes.unwrap_or(0),
+        };
+        // A reinstalled observer, as on a new document, counts from zero again
+        let previous = self
+            .seen
+            .replace(current.clone())
+            .filter(|seen| seen.started_at == started_at);
+        let (mutations, interactive_changes, visibility_changes, new_change) = match &previous {
+            Some(seen) => (
+                current.total_mutations.saturating_sub(seen.total_mutations),
+                current
+                    .interactive_changes
+                    .saturating_sub(seen.interactive_changes),
+                current
+                    .visibility_changes
+                    .saturating_sub(seen.visibility_changes),
+                current.last_change_time != seen.last_change_time,
+            ),
+            None => (
+                current.total_mutations,
+                current.interactive_changes,
+                current.visibility_changes,
+                result.has_changes,
+            ),
+        };
+        if mutations == 0 && !(result.has_changes && new_change) {
+            return;
+        }
+
+        self.stats.total_mutations += mutations;
+        self.stats.interactive_changes += interactive_changes;
+        self.stats.visibility_changes += visibility_changes;
+        self.stats.change_events += 1;
+        let timestamp = current
+            .last_change_time
+            .and_then(|millis| chrono::DateTime::from_timestamp_millis(millis as i64))
+            .unwrap_or_else(chrono::Utc::now);
+        self.push(DomChangeEvent {
+            timestamp,
+            change_types: result.change_types.clone().unwrap_or_default(),
+            mutations,
+            interactive_changes,
+            visibility_changes,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check(value: serde_json::Value) -> DOMChangeResult {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn bfcache_restores_record_an_event_without_mutations() {
+        let mut history = ChangeHistory::new(10);
+        history.record(&check(json!({
+            "hasChanges": true,
+            "changeTypes": ["interactive_elements"],
+            "lastChangeTime": 1_000,
+            "startedAt": 500,
+            "totalMutations": 7,
+            "interactiveChanges": 2
+        })));
+        history.record(&check(json!({
+            "hasChanges": true,
+            "changeTypes": ["bfcache_restore"],
+            "lastChangeTime": 2_000,
+            "startedAt": 500,
+            "totalMutations": 7,
+            "interactiveChanges": 2
+        })));
+
+        assert_eq!(history.events.len(), 2);
+        let restore = &history.events[1];
+        assert_eq!(restore.change_types, ["bfcache_restore"]);
+        assert_eq!(restore.mutations, 0);
+        assert_eq!(restore.interactive_changes, 0);
+        assert_eq!(history.stats.total_mutations, 7);
+        assert_eq!(history.stats.change_events, 2);
+    }
+
+    #[test]
+    fn repeated_checks_without_changes_record_nothing() {
+        let mut history = ChangeHistory::new(10);
+        let result = check(json!({
+            "hasChanges": true,
+            "lastChangeTime": 1_000,
+            "startedAt": 500,
+            "totalMutations": 3
+        }));
+        history.record(&result);
+        history.record(&result);
+        assert_eq!(history.events.len(), 1);
+        assert_eq!(history.stats.total_mutations, 3);
+    }
+
+    #[test]
+    fn a_reinstalled_observer_counts_from_zero() {
+        let mut history = ChangeHistory::new(10);
+        history.record(&check(json!({
+            "hasChanges": true,
+            "lastChangeTime": 1_000,
+            "startedAt": 500,
+            "totalMutations": 40
+        })));
+        history.record(&check(json!({
+            "hasChanges": true,
+            "lastChangeTime": 3_000,
+            "startedAt": 2_500,
+            "totalMutations": 5
+        })));
+        assert_eq!(history.events[1].mutations, 5);
+        assert_eq!(history.stats.total_mutations, 45);
+    }
+
+    #[test]
+    fn history_keeps_the_latest_events_up_to_its_length() {
+        let mut history = ChangeHistory::new(3);
+        for n in 1..=5u64 {
+            history.record(&check(json!({
+                "hasChanges": true,
+                "lastChangeTime": n * 1_000,
+                "startedAt": 500,
+                "totalMutations": n
+            })));
+        }
+        assert_eq!(history.events.len(), 3);
+        let times: Vec<_> = history
+            .events
+            .iter()
+            .map(|event| event.timestamp.timestamp_millis())
+            .collect();
+        assert_eq!(times, [3_000, 4_000, 5_000]);
+        // Dropped events still count
+        assert_eq!(history.stats.change_events, 5);
+        assert_eq!(history.stats.total_mutations, 5);
+
+        let mut none = ChangeHistory::new(0);
+        none.record(&check(json!({ "startedAt": 1, "totalMutations": 2 })));
+        assert!(none.events.is_empty());
+        assert_eq!(none.stats.total_mutations, 2);
+    }
+
+    #[test]
+    fn results_without_counters_are_ignored() {
+        let mut history = ChangeHistory::new(10);
+        history.record(&check(json!({ "hasChanges": true, "changeCount": 3 })));
+        assert!(history.events.is_empty());
+    }
 }
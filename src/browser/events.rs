@@ -0,0 +1,150 @@
+use crate::core::ConsoleMessage;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::Stream;
+
+/// Something that happened to the session outside the call that is running,
+/// published on `BrowserSession::events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// `navigate_smart` finished loading a page
+    Navigated {
+        url: String,
+        success: bool,
+        duration_ms: u64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// The element monitor saw the page change, e.g. `added`, `removed` or `modified`
+    DomChanged {
+        change_types: Vec<String>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// A console call, uncaught exception or browser log entry
+    ConsoleMessage { message: ConsoleMessage },
+    /// Interactive elements were highlighted again after the page changed
+    HighlightRefreshed {
+        count: usize,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// The page was replaced without the session asking for it: the browser
+    /// discarded and restored the tab, or something reloaded it. Page state such as
+    /// injected storage or highlights is gone.
+    PageReplaced {
+        url: String,
+        /// The browser reported the document as restored after a discard
+        discarded: bool,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// The keepalive activity failed; the keepalive keeps running
+    KeepaliveFailed {
+        error: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// The logout watchdog found one of its indicators on the page
+    LoggedOut {
+        url: String,
+        /// The URL pattern or selector that matched
+        indicator: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// The re-login flow logged the session back in
+    Relogged {
+        url: String,
+        /// 1 for the first re-login of a `run_with_relogin` call
+        attempt: u32,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// The re-login flow failed; the interrupted operation is not retried
+    ReloginFailed {
+        error: String,
+        attempt: u32,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// The tab's renderer crashed, e.g. out of memory; every call on the tab
+    /// fails with `TabCrashed` until `BrowserSession::reload_after_crash`
+    TabCrashed {
+        /// Page the tab showed when it crashed
+        url: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// This stream fell behind and `missed` older events were dropped; never
+    /// published by the session itself
+    Lagged {
+        missed: u64,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+}
+
+/// Events published after `BrowserSession::events` was called.
+///
+/// Events are buffered up to `session.event_buffer`; a consumer falling further
+/// behind gets a `Lagged` event in place of the ones it missed. The stream ends when
+/// the session is dropped.
+pub struct EventStream {
+    inner: BroadcastStream<SessionEvent>,
+}
+
+impl EventStream {
+    pub(crate) fn new(receiver: tokio::sync::broadcast::Receiver<SessionEvent>) -> Self {
+        Self {
+            inner: BroadcastStream::new(receiver),
+        }
+    }
+}
+
+impl Stream for EventStream {
+    type Item = SessionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SessionEvent>> {
+        Pin::new(&mut self.inner).poll_next(cx).map(|item| {
+            item.map(|event| match event {
+                Ok(event) => event,
+                Err(BroadcastStreamRecvError::Lagged(missed)) => SessionEvent::Lagged {
+                    missed,
+                    timestamp: chrono::Utc::now(),
+                },
+            })
+        })
+    }
+}
+
+impl std::fmt::Debug for EventStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventStream").finish_non_exhaustive()
+    }
+}
+
+/// Task copying console messages into the session's events; stops when dropped
+#[derive(Debug)]
+pub(crate) struct ConsoleForwarder(tokio::task::JoinHandle<()>);
+
+impl ConsoleForwarder {
+    pub fn spawn(
+        mut console: tokio::sync::broadcast::Receiver<ConsoleMessage>,
+        events: tokio::sync::broadcast::Sender<SessionEvent>,
+    ) -> Self {
+        use tokio::sync::broadcast::error::RecvError;
+        Self(tokio::spawn(async move {
+            loop {
+                match console.recv().await {
+                    Ok(message) => {
+                        let _ = events.send(SessionEvent::ConsoleMessage { message });
+                    }
+                    // Subscribers see the gap as their own lag
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+}
+
+impl Drop for ConsoleForwarder {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
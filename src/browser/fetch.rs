@@ -0,0 +1,322 @@
+use crate::errors::{BrowserAgentError, Result};
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Largest body `BrowserSession::fetch` reads unless told otherwise
+pub const DEFAULT_FETCH_MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+
+/// When `BrowserSession::fetch` sends the page's CSRF token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsrfMode {
+    /// With requests other than GET, HEAD and OPTIONS to the page's own origin,
+    /// when the page has a token
+    #[default]
+    Auto,
+    /// With every request, failing when the page has no token
+    Always,
+    Never,
+}
+
+/// Request made by `BrowserSession::fetch`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchOptions {
+    pub method: String,
+    pub headers: Vec<(String, String)>,
+    /// Sent as is; `post_json` also sets the content type
+    pub body: Option<String>,
+    /// Bytes of the response body kept; the rest is cut off and the response
+    /// marked truncated
+    pub max_body_bytes: usize,
+    /// Aborts the request; the session's navigation timeout when unset
+    pub timeout_ms: Option<u64>,
+    pub csrf: CsrfMode,
+    /// Header for the CSRF token instead of the one `CsrfManager` picks
+    pub csrf_header: Option<String>,
+    /// `fetch` credentials mode: `same-origin`, `include` or `omit`
+    pub credentials: String,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            method: "GET".to_string(),
+            headers: Vec::new(),
+            body: None,
+            max_body_bytes: DEFAULT_FETCH_MAX_BODY_BYTES,
+            timeout_ms: None,
+            csrf: CsrfMode::default(),
+            csrf_header: None,
+            credentials: "same-origin".to_string(),
+        }
+    }
+}
+
+impl FetchOptions {
+    pub fn get() -> Self {
+        Self::default()
+    }
+
+    /// POST `body` serialized as JSON
+    pub fn post_json(body: &Value) -> Self {
+        Self {
+            method: "POST".to_string(),
+            body: Some(body.to_string()),
+            ..Self::default()
+        }
+        .header("Content-Type", "application/json")
+    }
+
+    pub fn method(mut self, method: &str) -> Self {
+        self.method = method.to_uppercase();
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Send the page's CSRF token whatever the method and origin, and fail when
+    /// there is none
+    pub fn with_csrf(mut self) -> Self {
+        self.csrf = CsrfMode::Always;
+        self
+    }
+
+    /// `with_csrf`, sending the token in `header`
+    pub fn with_csrf_header(mut self, header: &str) -> Self {
+        self.csrf = CsrfMode::Always;
+        self.csrf_header = Some(header.to_string());
+        self
+    }
+
+    pub fn without_csrf(mut self) -> Self {
+        self.csrf = CsrfMode::Never;
+        self
+    }
+
+    /// Method that changes state on the server, which `CsrfMode::Auto` sends the
+    /// token with
+    pub fn is_unsafe(&self) -> bool {
+        !matches!(self.method.as_str(), "GET" | "HEAD" | "OPTIONS")
+    }
+
+    /// Send cookies to other origins too, as `credentials: 'include'` does
+    pub fn with_cross_origin_credentials(mut self) -> Self {
+        self.credentials = "include".to_string();
+        self
+    }
+}
+
+/// Response to `BrowserSession::fetch`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchResponse {
+    /// Final URL, after redirects
+    pub url: String,
+    pub status: u16,
+    pub status_text: String,
+    pub redirected: bool,
+    /// Lowercase names, as the page sees them; cross-origin responses only expose
+    /// the headers CORS allows
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// The body was longer than `FetchOptions::max_body_bytes`
+    pub truncated: bool,
+}
+
+impl FetchResponse {
+    /// Status in the 200 range
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// First value of header `name`, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Body as UTF-8, with invalid sequences replaced
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Body parsed as JSON; fails on a truncated body
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        if self.truncated {
+            return Err(BrowserAgentError::ConfigurationError(format!(
+                "response from {} was truncated at {} bytes; raise max_body_bytes to parse it",
+                self.url,
+                self.body.len()
+            )));
+        }
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// Script running the request with `fetch` and reading at most
+/// `max_body_bytes` of the body. Evaluates to a promise of
+/// `{ ok: true, url, status, status_text, redirected, headers, body, truncated }`
+/// with the body in base64, or `{ ok: false, error, timed_out }`.
+pub(crate) fn fetch_script(
+    url: &str,
+    options: &FetchOptions,
+    headers: &[(String, String)],
+    timeout_ms: u64,
+) -> String {
+    let request = serde_json::json!({
+        "url": url,
+        "method": options.method,
+        "headers": headers,
+        "body": options.body,
+        "credentials": options.credentials,
+        "maxBytes": options.max_body_bytes,
+        "timeoutMs": timeout_ms,
+    });
+    format!(
+        r#"
+        (async function() {{
+            const request = {request};
+            const controller = new AbortController();
+            const timer = setTimeout(() => controller.abort(), request.timeoutMs);
+            try {{
+                const response = await fetch(request.url, {{
+                    method: request.method,
+                    headers: request.headers,
+                    body: request.body === null ? undefined : request.body,
+                    credentials: request.credentials,
+                    signal: controller.signal
+                }});
+                const headers = [];
+                response.headers.forEach((value, name) => headers.push([name, value]));
+
+                const chunks = [];
+                let size = 0;
+                let truncated = false;
+                if (response.body) {{
+                    const reader = response.body.getReader();
+                    while (true) {{
+                        const {{ done, value }} = await reader.read();
+                        if (done) break;
+                        if (size + value.length > request.maxBytes) {{
+                            chunks.push(value.subarray(0, request.maxBytes - size));
+                            truncated = true;
+                            await reader.cancel();
+                            break;
+                        }}
+                        chunks.push(value);
+                        size += value.length;
+                    }}
+                }}
+                let binary = '';
+                for (const chunk of chunks) {{
+                    for (let i = 0; i < chunk.length; i += 0x8000) {{
+                        binary += String.fromCharCode.apply(null, chunk.subarray(i, i + 0x8000));
+                    }}
+                }}
+                return {{
+                    ok: true,
+                    url: response.url,
+                    status: response.status,
+                    status_text: response.statusText,
+                    redirected: response.redirected,
+                    headers: headers,
+                    body: btoa(binary),
+                    truncated: truncated
+                }};
+            }} catch (e) {{
+                return {{
+                    ok: false,
+                    error: String((e && e.message) || e),
+                    timed_out: controller.signal.aborted
+                }};
+            }} finally {{
+                clearTimeout(timer);
+            }}
+        }})()
+        "#,
+        request = request
+    )
+}
+
+#[derive(Deserialize)]
+struct RawResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    timed_out: bool,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    status: u16,
+    #[serde(default)]
+    status_text: String,
+    #[serde(default)]
+    redirected: bool,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    truncated: bool,
+}
+
+/// `FetchResponse` from what `fetch_script` evaluated to
+pub(crate) fn parse_fetch_result(
+    url: &str,
+    timeout_ms: u64,
+    value: Value,
+) -> Result<FetchResponse> {
+    let raw: RawResponse = serde_json::from_value(value)?;
+    if !raw.ok {
+        let error = raw.error.unwrap_or_default();
+        return Err(if raw.timed_out {
+            BrowserAgentError::TimeoutError(format!(
+                "fetch {} did not finish within {}ms",
+                url, timeout_ms
+            ))
+        } else {
+            BrowserAgentError::JavaScriptFailed(format!("fetch {} failed: {}", url, error))
+        });
+    }
+    let body = base64::engine::general_purpose::STANDARD
+        .decode(raw.body)
+        .map_err(|e| BrowserAgentError::JavaScriptFailed(format!("fetch {}: {}", url, e)))?;
+    Ok(FetchResponse {
+        url: raw.url,
+        status: raw.status,
+        status_text: raw.status_text,
+        redirected: raw.redirected,
+        headers: raw.headers,
+        body,
+        truncated: raw.truncated,
+    })
+}
@@ -0,0 +1,105 @@
+use crate::browser::keepalive::KeepaliveTask;
+use crate::browser::refresh::{RefreshTask, DEFAULT_REFRESH_DEBOUNCE};
+use crate::browser::BrowserSession;
+use crate::core::config::KeepaliveOptions;
+use crate::core::BrowserTrait;
+use crate::errors::Result;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Cloneable, shareable handle to a `BrowserSession`.
+///
+/// Clones refer to the same tab, so one task can stream page state while another
+/// clicks. The full session API is reachable through `Deref`.
+pub struct SessionHandle<B: BrowserTrait> {
+    inner: Arc<BrowserSession<B>>,
+}
+
+impl<B: BrowserTrait> SessionHandle<B> {
+    pub fn new(session: BrowserSession<B>) -> Self {
+        Self {
+            inner: Arc::new(session),
+        }
+    }
+
+    /// Number of handles sharing the session
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Take the session back when this is the last handle
+    pub fn try_unwrap(self) -> std::result::Result<BrowserSession<B>, Self> {
+        Arc::try_unwrap(self.inner).map_err(|inner| Self { inner })
+    }
+}
+
+impl<B> SessionHandle<B>
+where
+    B: BrowserTrait + 'static,
+    B::TabHandle: 'static,
+{
+    /// Run `config.session.keepalive` in the background until `stop_keepalive`, the
+    /// session closing, or the last handle dropping. Replaces a running keepalive.
+    pub async fn start_keepalive(&self) -> Result<()> {
+        let options = self.inner.keepalive_options();
+        self.start_keepalive_with(options).await
+    }
+
+    /// `start_keepalive` with explicit options
+    pub async fn start_keepalive_with(&self, options: KeepaliveOptions) -> Result<()> {
+        self.inner.prepare_keepalive().await?;
+        println!(
+            "💓 Keepalive every {}ms ({:?})",
+            options.interval_ms, options.activity
+        );
+        let task = KeepaliveTask::spawn(Arc::downgrade(&self.inner), options);
+        self.inner.set_keepalive(Some(task));
+        Ok(())
+    }
+
+    pub fn stop_keepalive(&self) {
+        self.inner.set_keepalive(None);
+    }
+
+    /// Move the highlight refresh after `*_with_refresh` interactions onto a
+    /// background task, so they return once the click or typing is done. Use
+    /// `wait_for_refresh` before relying on element numbers.
+    pub fn start_background_refresh(&self) {
+        self.start_background_refresh_with(DEFAULT_REFRESH_DEBOUNCE);
+    }
+
+    /// `start_background_refresh` waiting `debounce` after the last interaction
+    pub fn start_background_refresh_with(&self, debounce: Duration) {
+        println!("🔄 Background highlight refresh, debounced {:?}", debounce);
+        let task = RefreshTask::spawn(
+            Arc::downgrade(&self.inner),
+            self.inner.clock().clone(),
+            debounce,
+        );
+        self.inner.set_refresh_task(Some(task));
+    }
+
+    /// Go back to refreshing inline, after any refresh already asked for
+    pub async fn stop_background_refresh(&self) -> Result<()> {
+        self.inner.wait_for_refresh().await?;
+        self.inner.set_refresh_task(None);
+        Ok(())
+    }
+}
+
+impl<B: BrowserTrait> Clone for SessionHandle<B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<B: BrowserTrait> Deref for SessionHandle<B> {
+    type Target = BrowserSession<B>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
@@ -0,0 +1,430 @@
+use crate::core::config::HighlightStyle;
+use crate::dom::DomElement;
+use serde::Serialize;
+
+/// Class of the outlines, number labels and leader lines of
+/// `highlight_interactive_elements`, and of text match marks
+pub(crate) const HIGHLIGHT_CLASS: &str = "browser-automation-highlight";
+
+/// Class of the labels of `add_dynamic_labels`, cleared separately from highlights
+pub(crate) const LABEL_CLASS: &str = "browser-agent-label";
+
+/// Outline and label color for an element, by tag
+pub(crate) fn highlight_color(tag_name: &str) -> &'static str {
+    match tag_name {
+        "button" => "#0000FF",
+        "input" => "#00FF00",
+        "select" => "#FF6600",
+        "textarea" => "#9900FF",
+        "a" => "#00FFFF",
+        _ => "#FF0000",
+    }
+}
+
+/// One element to outline and number
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HighlightTarget {
+    pub selector: String,
+    pub number: usize,
+    pub color: String,
+}
+
+impl HighlightTarget {
+    pub fn for_element(element: &DomElement, number: usize) -> Self {
+        Self {
+            selector: element.css_selector.clone(),
+            number,
+            color: highlight_color(&element.tag_name).to_string(),
+        }
+    }
+}
+
+/// Script outlining every target and placing its number label.
+///
+/// Outlines are clipped to the viewport. Each label tries the four outside corners
+/// of its element, then its sides, then spots further out, taking the first that
+/// overlaps no placed label and as few highlighted elements as possible; labels set
+/// apart from their element get a leader line. Elements under 24x16 CSS pixels get
+/// a smaller font. Evaluates to `[{ number, label: { x, y, width, height } }]`.
+pub(crate) fn highlight_script(targets: &[HighlightTarget], style: HighlightStyle) -> String {
+    format!(
+        r#"
+        (function() {{
+            const targets = {targets};
+            const compact = {compact};
+
+            const style = document.createElement('style');
+            style.className = '{class}';
+            style.setAttribute('data-surfai-internal', '');
+            style.textContent = `
+                .{class} {{
+                    position: fixed !important;
+                    pointer-events: none !important;
+                    z-index: 999999 !important;
+                    box-sizing: border-box !important;
+                    font-family: Arial, sans-serif !important;
+                }}
+                .{class}-label {{
+                    z-index: 1000000 !important;
+                    color: white !important;
+                    padding: 2px 6px;
+                    font-size: 12px;
+                    line-height: 14px;
+                    font-weight: bold !important;
+                    border-radius: 3px;
+                    white-space: nowrap !important;
+                }}
+                .{class}-label.tiny {{
+                    padding: 1px 3px;
+                    font-size: 9px;
+                    line-height: 10px;
+                }}
+                .{class}-label.compact {{
+                    width: 14px;
+                    height: 14px;
+                    padding: 0;
+                    border-radius: 50%;
+                    font-size: 9px;
+                    line-height: 14px;
+                    text-align: center;
+                }}
+                .{class}-leader {{
+                    height: 1px !important;
+                    transform-origin: 0 50% !important;
+                }}
+            `;
+            document.head.appendChild(style);
+
+            const vw = window.innerWidth;
+            const vh = window.innerHeight;
+            const intersects = (a, b) =>
+                a.left < b.right && b.left < a.right && a.top < b.bottom && b.top < a.bottom;
+
+            const boxes = [];
+            for (const target of targets) {{
+                let element = null;
+                try {{
+                    element = document.querySelector(target.selector);
+                }} catch (e) {{
+                    console.error('Highlight error for element ' + target.number + ':', e);
+                }}
+                if (!element) continue;
+                const rect = element.getBoundingClientRect();
+                if (rect.width <= 0 || rect.height <= 0) continue;
+                const box = {{
+                    left: Math.max(0, rect.left),
+                    top: Math.max(0, rect.top),
+                    right: Math.min(vw, rect.right),
+                    bottom: Math.min(vh, rect.bottom)
+                }};
+                if (box.right <= box.left || box.bottom <= box.top) continue;
+                boxes.push({{ target, box, tiny: rect.width < 24 || rect.height < 16 }});
+            }}
+
+            const placed = [];
+            const results = [];
+            for (const {{ target, box, tiny }} of boxes) {{
+                const overlay = document.createElement('div');
+                overlay.className = '{class} {class}-' + target.number;
+                overlay.style.left = box.left + 'px';
+                overlay.style.top = box.top + 'px';
+                overlay.style.width = (box.right - box.left) + 'px';
+                overlay.style.height = (box.bottom - box.top) + 'px';
+                overlay.style.border = (tiny ? 2 : 3) + 'px solid ' + target.color;
+                overlay.style.backgroundColor = 'rgba(255,255,255,0.1)';
+                overlay.setAttribute('data-surfai-internal', '');
+                document.body.appendChild(overlay);
+
+                const label = document.createElement('div');
+                label.className = '{class} {class}-label'
+                    + (compact ? ' compact' : tiny ? ' tiny' : '');
+                label.style.backgroundColor = target.color;
+                label.textContent = String(target.number);
+                label.style.left = '0px';
+                label.style.top = '0px';
+                label.setAttribute('data-surfai-internal', '');
+                document.body.appendChild(label);
+                const size = label.getBoundingClientRect();
+                const w = size.width;
+                const h = size.height;
+
+                const fit = (left, top, distance) => {{
+                    left = Math.min(Math.max(0, left), vw - w);
+                    top = Math.min(Math.max(0, top), vh - h);
+                    return {{ left, top, right: left + w, bottom: top + h, distance }};
+                }};
+                const candidates = [
+                    fit(box.left, box.top - h, 0),
+                    fit(box.right - w, box.top - h, 0),
+                    fit(box.left, box.bottom, 0),
+                    fit(box.right - w, box.bottom, 0),
+                    fit(box.left - w, box.top, 0),
+                    fit(box.right, box.top, 0)
+                ];
+                for (let ring = 1; ring <= 3; ring++) {{
+                    const gap = ring * (h + 2);
+                    candidates.push(
+                        fit(box.left, box.top - h - gap, ring),
+                        fit(box.left, box.bottom + gap, ring),
+                        fit(box.left - w - gap, box.top, ring),
+                        fit(box.right + gap, box.top, ring)
+                    );
+                }}
+                const cost = (spot) => {{
+                    let value = spot.distance * 0.5;
+                    for (const other of placed) if (intersects(spot, other)) value += 1000;
+                    if (intersects(spot, box)) value += 100;
+                    for (const other of boxes) {{
+                        if (other.box !== box && intersects(spot, other.box)) value += 1;
+                    }}
+                    return value;
+                }};
+                let best = candidates[0];
+                let bestCost = cost(best);
+                for (const spot of candidates.slice(1)) {{
+                    const value = cost(spot);
+                    if (value < bestCost) {{
+                        best = spot;
+                        bestCost = value;
+                    }}
+                }}
+                const free = (spot) => !placed.some(other => intersects(spot, other));
+                for (let step = 4; !free(best) && step * (h + 2) < vh; step++) {{
+                    const above = fit(box.left, box.top - h - step * (h + 2), step);
+                    const below = fit(box.left, box.bottom + step * (h + 2), step);
+                    if (free(above)) best = above;
+                    else if (free(below)) best = below;
+                }}
+
+                label.style.left = best.left + 'px';
+                label.style.top = best.top + 'px';
+                placed.push(best);
+
+                const gapX = Math.max(box.left - best.right, best.left - box.right, 0);
+                const gapY = Math.max(box.top - best.bottom, best.top - box.bottom, 0);
+                if (gapX > 2 || gapY > 2) {{
+                    const fromX = best.left + w / 2;
+                    const fromY = best.top + h / 2;
+                    const toX = Math.min(Math.max(fromX, box.left), box.right);
+                    const toY = Math.min(Math.max(fromY, box.top), box.bottom);
+                    const leader = document.createElement('div');
+                    leader.className = '{class} {class}-leader';
+                    leader.style.left = fromX + 'px';
+                    leader.style.top = fromY + 'px';
+                    leader.style.width = Math.hypot(toX - fromX, toY - fromY) + 'px';
+                    leader.style.transform = 'rotate(' + Math.atan2(toY - fromY, toX - fromX) + 'rad)';
+                    leader.style.backgroundColor = target.color;
+                    leader.setAttribute('data-surfai-internal', '');
+                    document.body.appendChild(leader);
+                }}
+
+                results.push({{
+                    number: target.number,
+                    label: {{ x: best.left, y: best.top, width: w, height: h }}
+                }});
+            }}
+            return results;
+        }})()
+        "#,
+        targets = serde_json::to_string(targets).unwrap_or_else(|_| "[]".to_string()),
+        compact = style == HighlightStyle::Compact,
+        class = HIGHLIGHT_CLASS
+    )
+}
+
+/// One element to give a dynamic label
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LabelTarget {
+    pub selector: String,
+    pub number: usize,
+    pub text: String,
+    /// `search`, `button`, `link` or anything else for the default color
+    pub kind: String,
+}
+
+/// Script placing a text label above each target, in page coordinates so labels
+/// scroll with the page. Evaluates to `[{ number, selector, text, type }]` for the
+/// targets found with a box.
+pub(crate) fn label_script(targets: &[LabelTarget]) -> String {
+    format!(
+        r#"
+        (function() {{
+            const targets = {targets};
+
+            const style = document.createElement('style');
+            style.className = '{class}';
+            style.setAttribute('data-surfai-internal', '');
+            style.textContent = `
+                .{class} {{
+                    position: absolute !important;
+                    z-index: 999999 !important;
+                    background: #FF4444 !important;
+                    color: white !important;
+                    font-family: Arial, sans-serif !important;
+                    font-size: 14px !important;
+                    font-weight: bold !important;
+                    padding: 4px 8px !important;
+                    border-radius: 4px !important;
+                    border: 2px solid #CC0000 !important;
+                    box-shadow: 0 2px 8px rgba(0,0,0,0.3) !important;
+                    pointer-events: none !important;
+                    white-space: nowrap !important;
+                    line-height: 1 !important;
+                    min-width: 20px !important;
+                    text-align: center !important;
+                }}
+                .{class}-search {{
+                    background: #0066CC !important;
+                    border-color: #004499 !important;
+                }}
+                .{class}-button {{
+                    background: #00AA00 !important;
+                    border-color: #007700 !important;
+                }}
+                .{class}-link {{
+                    background: #AA00AA !important;
+                    border-color: #770077 !important;
+                }}
+            `;
+            document.head.appendChild(style);
+
+            const results = [];
+            for (const target of targets) {{
+                try {{
+                    const element = document.querySelector(target.selector);
+                    if (!element) continue;
+                    const rect = element.getBoundingClientRect();
+                    if (rect.width <= 0 || rect.height <= 0) continue;
+                    const label = document.createElement('div');
+                    label.className = '{class} {class}-' + target.kind;
+                    label.id = '{class}-' + target.number;
+                    label.setAttribute('data-surfai-internal', '');
+                    label.textContent = target.text;
+                    label.style.left = (rect.left + window.scrollX - 5) + 'px';
+                    label.style.top = (rect.top + window.scrollY - 25) + 'px';
+                    document.body.appendChild(label);
+                    results.push({{
+                        number: target.number,
+                        selector: target.selector,
+                        text: target.text,
+                        type: target.kind
+                    }});
+                }} catch (e) {{
+                    console.error('Label error for element ' + target.number + ':', e);
+                }}
+            }}
+            return results;
+        }})()
+        "#,
+        targets = serde_json::to_string(targets).unwrap_or_else(|_| "[]".to_string()),
+        class = LABEL_CLASS
+    )
+}
+
+/// Script removing everything carrying `class`, `HIGHLIGHT_CLASS` or
+/// `LABEL_CLASS`, style sheets included. Evaluates to how many elements were
+/// removed.
+pub(crate) fn clear_script(class: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const drawn = document.querySelectorAll('.{class}');
+            drawn.forEach(element => element.remove());
+            return drawn.length;
+        }})()
+        "#,
+        class = class
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// The `targets` array a script embeds, parsed back
+    fn embedded_targets(script: &str) -> Value {
+        let start = script.find("const targets = ").unwrap() + "const targets = ".len();
+        let end = start + script[start..].find(";\n").unwrap();
+        serde_json::from_str(&script[start..end]).unwrap()
+    }
+
+    #[test]
+    fn highlight_selectors_are_embedded_as_json() {
+        let selector = r#"input[name="o'neil\"]`${x}`"#;
+        let script = highlight_script(
+            &[HighlightTarget {
+                selector: selector.to_string(),
+                number: 3,
+                color: highlight_color("input").to_string(),
+            }],
+            HighlightStyle::Full,
+        );
+        let targets = embedded_targets(&script);
+        assert_eq!(targets[0]["selector"], selector);
+        assert_eq!(targets[0]["number"], 3);
+        assert_eq!(targets[0]["color"], "#00FF00");
+        assert!(script.contains(r#""input[name=\"o'neil\\\"]`${x}`""#));
+    }
+
+    #[test]
+    fn highlight_script_uses_the_highlight_classes() {
+        let script = highlight_script(&[], HighlightStyle::Full);
+        assert_eq!(embedded_targets(&script), serde_json::json!([]));
+        assert!(script.contains("const compact = false;"));
+        for class in [
+            "browser-automation-highlight-label",
+            "browser-automation-highlight-leader",
+            "browser-automation-highlight-label.tiny",
+            "browser-automation-highlight-label.compact",
+        ] {
+            assert!(script.contains(&format!(".{} {{", class)), "{}", class);
+        }
+        assert!(script.contains("setAttribute('data-surfai-internal', '')"));
+        assert!(script.contains(
+            "'browser-automation-highlight browser-automation-highlight-' + target.number"
+        ));
+        assert!(highlight_script(&[], HighlightStyle::Compact).contains("const compact = true;"));
+    }
+
+    #[test]
+    fn label_text_is_embedded_as_json() {
+        let text = "Say \"hi\"\n</div><script>alert(1)</script>";
+        let script = label_script(&[LabelTarget {
+            selector: "#search".to_string(),
+            number: 1,
+            text: text.to_string(),
+            kind: "search".to_string(),
+        }]);
+        let targets = embedded_targets(&script);
+        assert_eq!(targets[0]["text"], text);
+        assert_eq!(targets[0]["kind"], "search");
+        assert!(!script.contains(text));
+        assert!(script.contains(".browser-agent-label-search {"));
+        assert!(script.contains("label.id = 'browser-agent-label-' + target.number"));
+        assert!(script.contains("setAttribute('data-surfai-internal', '')"));
+    }
+
+    #[test]
+    fn clear_script_removes_the_class() {
+        assert!(
+            clear_script(LABEL_CLASS).contains("document.querySelectorAll('.browser-agent-label')")
+        );
+    }
+
+    #[test]
+    fn targets_are_colored_by_tag() {
+        let mut element = DomElement::new("button".to_string(), "e1".to_string());
+        element.css_selector = "#save".to_string();
+        let target = HighlightTarget::for_element(&element, 4);
+        assert_eq!(
+            (
+                target.selector.as_str(),
+                target.number,
+                target.color.as_str()
+            ),
+            ("#save", 4, "#0000FF")
+        );
+        assert_eq!(highlight_color("div"), "#FF0000");
+    }
+}
@@ -0,0 +1,121 @@
+use crate::browser::session::CookieData;
+use serde::{Deserialize, Serialize};
+
+/// How `inject_session` waits for the page after reloading it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitStrategy {
+    /// Return once the reload is requested
+    Immediate,
+    /// Wait until the new document's `readyState` is `complete`
+    DocumentLoad,
+    /// Wait for the new document to load, then for content and a quiet network the
+    /// way `navigate_smart` does
+    #[default]
+    NavigationComplete,
+}
+
+/// What `inject_session_with_options` does once the session data is written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InjectOptions {
+    /// Reload the page so it picks up the injected state. Turn it off when the next
+    /// step navigates anyway.
+    pub reload: bool,
+    pub wait: WaitStrategy,
+    /// Bound for the reload wait; `SessionConfig::navigation_timeout_ms` when unset
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+impl Default for InjectOptions {
+    fn default() -> Self {
+        Self {
+            reload: true,
+            wait: WaitStrategy::default(),
+            timeout_ms: None,
+        }
+    }
+}
+
+impl InjectOptions {
+    /// Write the session data and leave the page as it is
+    pub fn no_reload() -> Self {
+        Self {
+            reload: false,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_wait(mut self, wait: WaitStrategy) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+}
+
+/// A cookie that was injected but could not be read back
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingCookie {
+    pub name: String,
+    /// Likely cause, e.g. a secure cookie on an http page
+    pub reason: String,
+}
+
+impl MissingCookie {
+    /// Cookie `cookie` was not found on a page at `url`; `found_value` is the value
+    /// read back under the same name, if any
+    pub(crate) fn diagnose(cookie: &CookieData, url: &url::Url, found_value: Option<&str>) -> Self {
+        let host = url.host_str().unwrap_or_default();
+        let domain = cookie.domain.trim_start_matches('.');
+        let reason = if cookie.http_only {
+            "HttpOnly cookies cannot be written or read by page scripts".to_string()
+        } else if cookie.secure && url.scheme() != "https" {
+            format!("secure cookie on a {} page", url.scheme())
+        } else if cookie
+            .expires
+            .is_some_and(|expires| expires > 0 && expires < chrono::Utc::now().timestamp())
+        {
+            "expired".to_string()
+        } else if !domain.is_empty() && host != domain && !host.ends_with(&format!(".{}", domain)) {
+            format!("domain {} does not match host {}", cookie.domain, host)
+        } else if let Some(value) = found_value {
+            format!("value read back differs ('{}')", value)
+        } else {
+            "not present after injection".to_string()
+        };
+        Self {
+            name: cookie.name.clone(),
+            reason,
+        }
+    }
+}
+
+/// What `inject_session` wrote and what could not be read back afterwards
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InjectReport {
+    pub cookies_injected: usize,
+    pub local_storage_injected: usize,
+    pub session_storage_injected: usize,
+    pub auth_tokens_injected: usize,
+    /// Storage values left out because they were truncated at extraction
+    pub truncated_skipped: usize,
+    /// Whether the page was reloaded, and whether it finished loading in time
+    pub reloaded: bool,
+    pub reload_completed: bool,
+    pub missing_cookies: Vec<MissingCookie>,
+    pub missing_local_storage: Vec<String>,
+    pub missing_session_storage: Vec<String>,
+}
+
+impl InjectReport {
+    /// Whether everything written could be read back
+    pub fn is_complete(&self) -> bool {
+        self.missing_cookies.is_empty()
+            && self.missing_local_storage.is_empty()
+            && self.missing_session_storage.is_empty()
+    }
+}
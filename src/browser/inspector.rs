@@ -0,0 +1,547 @@
+use crate::browser::events::SessionEvent;
+use crate::browser::session::{AIElement, ElementHighlight};
+use crate::dom::ElementRect;
+use crate::errors::Result;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// Events kept for `/api/events`; older ones are only seen by stream subscribers
+pub const INSPECTOR_EVENT_HISTORY: usize = 200;
+
+/// Longest request or header line read; longer ones get a 431 response
+const MAX_LINE_BYTES: usize = 8 * 1024;
+
+/// Most header lines read before answering 431
+const MAX_HEADERS: usize = 64;
+
+const PAGE: &str = include_str!("inspector.html");
+
+/// Read-only view of a session on a localhost port, from
+/// `BrowserSession::enable_inspector`, for dashboards that would otherwise scrape
+/// logs.
+///
+/// | Path                 | Serves                                               |
+/// |----------------------|------------------------------------------------------|
+/// | `/`                  | page drawing the numbered boxes over the screenshot  |
+/// | `/api/observation`   | URL, title and elements from `get_ai_elements`       |
+/// | `/api/highlights`    | highlighted elements with their rects                |
+/// | `/api/screenshot`    | last screenshot, base64 PNG                          |
+/// | `/api/events`        | the last `INSPECTOR_EVENT_HISTORY` session events    |
+/// | `/api/events/stream` | session events as server-sent events                 |
+///
+/// Every request needs the endpoint's token, as `?token=` or an
+/// `Authorization: Bearer` header. The view is what the session last produced;
+/// serving it never touches the browser.
+pub(crate) struct Inspector {
+    endpoint: InspectorEndpoint,
+    view: Arc<RwLock<InspectorView>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+/// Where `BrowserSession::enable_inspector` serves, and the token it wants
+#[derive(Debug, Clone)]
+pub struct InspectorEndpoint {
+    pub addr: SocketAddr,
+    pub token: String,
+}
+
+impl InspectorEndpoint {
+    /// URL of the inspector page, token included
+    pub fn page_url(&self) -> String {
+        format!("http://{}/?token={}", self.addr, self.token)
+    }
+
+    /// Absolute URL for `path`, e.g. `/api/highlights`, token included
+    pub fn url(&self, path: &str) -> String {
+        format!(
+            "http://{}/{}?token={}",
+            self.addr,
+            path.trim_start_matches('/'),
+            self.token
+        )
+    }
+}
+
+/// What `get_ai_elements` last saw
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Observation {
+    pub url: String,
+    pub title: String,
+    pub observed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub elements: Vec<AIElement>,
+}
+
+/// A highlighted element as the inspector serves it
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectedHighlight {
+    pub element_number: usize,
+    pub element_id: String,
+    pub element_type: String,
+    pub selector: String,
+    pub color: String,
+    /// Viewport rect of the element when it was highlighted
+    pub rect: Option<ElementRect>,
+    /// Viewport rect of the number label, when drawn into the page
+    pub label_rect: Option<ElementRect>,
+}
+
+impl InspectedHighlight {
+    pub(crate) fn new(highlight: &ElementHighlight, rect: Option<ElementRect>) -> Self {
+        Self {
+            element_number: highlight.element_number,
+            element_id: highlight.element_id.clone(),
+            element_type: highlight.element_type.clone(),
+            selector: highlight.css_selector.clone(),
+            color: highlight.color.clone(),
+            rect,
+            label_rect: highlight.label_rect.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectedScreenshot {
+    pub taken_at: chrono::DateTime<chrono::Utc>,
+    /// Device pixels per CSS pixel, to place element rects on the image
+    pub device_scale_factor: f64,
+    /// PNG, base64
+    pub png: String,
+}
+
+/// Everything the inspector serves, updated by the session
+#[derive(Debug, Default)]
+pub(crate) struct InspectorView {
+    pub observation: Observation,
+    pub highlights: Vec<InspectedHighlight>,
+    pub screenshot: Option<InspectedScreenshot>,
+    pub events: VecDeque<SessionEvent>,
+}
+
+impl InspectorView {
+    /// A new page replaced the elements and highlights of the last one
+    pub fn page_changed(&mut self, url: &str) {
+        self.observation.url = url.to_string();
+        self.observation.elements.clear();
+        self.highlights.clear();
+    }
+
+    pub fn set_screenshot(&mut self, png: &[u8], device_scale_factor: f64) {
+        self.screenshot = Some(InspectedScreenshot {
+            taken_at: chrono::Utc::now(),
+            device_scale_factor,
+            png: base64::Engine::encode(&base64::engine::general_purpose::STANDARD, png),
+        });
+    }
+
+    fn record(&mut self, event: SessionEvent) {
+        if self.events.len() == INSPECTOR_EVENT_HISTORY {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
+
+impl Inspector {
+    /// Serve `view` on `127.0.0.1:port`, 0 picking a free port, recording what
+    /// `events` publishes from now on
+    pub async fn start(
+        port: u16,
+        view: InspectorView,
+        events: &broadcast::Sender<SessionEvent>,
+    ) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        let endpoint = InspectorEndpoint {
+            addr: listener.local_addr()?,
+            token: uuid::Uuid::new_v4().simple().to_string(),
+        };
+        let view = Arc::new(RwLock::new(view));
+
+        let mut recorded = events.subscribe();
+        let recorder_view = view.clone();
+        let recorder = tokio::spawn(async move {
+            loop {
+                match recorded.recv().await {
+                    Ok(event) => recorder_view.write().unwrap().record(event),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let server_view = view.clone();
+        let token = endpoint.token.clone();
+        let events = events.clone();
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (view, token, events) = (server_view.clone(), token.clone(), events.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &view, &token, &events).await {
+                        tracing::debug!(error = %e, "inspector connection error");
+                    }
+                });
+            }
+        });
+
+        println!("🔭 Inspector listening on http://{}", endpoint.addr);
+        Ok(Self {
+            endpoint,
+            view,
+            tasks: vec![recorder, server],
+        })
+    }
+
+    pub fn endpoint(&self) -> &InspectorEndpoint {
+        &self.endpoint
+    }
+
+    pub fn update(&self, update: impl FnOnce(&mut InspectorView)) {
+        update(&mut self.view.write().unwrap());
+    }
+}
+
+impl Drop for Inspector {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    view: &RwLock<InspectorView>,
+    token: &str,
+    events: &broadcast::Sender<SessionEvent>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    match read_line_capped(&mut reader, &mut request_line).await? {
+        Some(0) => return Ok(()),
+        Some(_) => {}
+        None => return too_large(reader.into_inner()).await,
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = parts.next().unwrap_or("/").to_string();
+
+    let mut bearer = None;
+    let mut headers = 0;
+    loop {
+        let mut header = String::new();
+        match read_line_capped(&mut reader, &mut header).await? {
+            Some(0) => break,
+            Some(_) if header.trim().is_empty() => break,
+            Some(_) if headers < MAX_HEADERS => headers += 1,
+            _ => return too_large(reader.into_inner()).await,
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("authorization") {
+                bearer = value.trim().strip_prefix("Bearer ").map(str::to_string);
+            }
+        }
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query_token = url::form_urlencoded::parse(query.as_bytes())
+        .find(|(name, _)| name == "token")
+        .map(|(_, value)| value.into_owned());
+    let mut stream = reader.into_inner();
+
+    if method != "GET" {
+        return respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "read-only",
+        )
+        .await;
+    }
+    let authorized = [bearer, query_token]
+        .iter()
+        .flatten()
+        .any(|given| tokens_match(given, token));
+    if !authorized {
+        return respond(
+            &mut stream,
+            "401 Unauthorized",
+            "text/plain",
+            "token required",
+        )
+        .await;
+    }
+
+    let json = match path.trim_end_matches('/') {
+        "" => return respond(&mut stream, "200 OK", "text/html; charset=utf-8", PAGE).await,
+        "/api/events/stream" => return stream_events(stream, events.subscribe()).await,
+        path => {
+            let view = view.read().unwrap();
+            match path {
+                "/api/observation" => Some(serde_json::to_string(&view.observation)),
+                "/api/highlights" => Some(serde_json::to_string(&view.highlights)),
+                "/api/screenshot" => Some(serde_json::to_string(&view.screenshot)),
+                "/api/events" => Some(serde_json::to_string(&view.events)),
+                _ => None,
+            }
+        }
+    };
+    match json {
+        None => respond(&mut stream, "404 Not Found", "text/plain", "not found").await,
+        Some(Ok(json)) => respond(&mut stream, "200 OK", "application/json", &json).await,
+        Some(Err(e)) => {
+            let error = e.to_string();
+            respond(
+                &mut stream,
+                "500 Internal Server Error",
+                "text/plain",
+                &error,
+            )
+            .await
+        }
+    }
+}
+
+/// Read one line of at most `MAX_LINE_BYTES`; `None` when it is longer
+async fn read_line_capped(
+    reader: &mut BufReader<TcpStream>,
+    line: &mut String,
+) -> std::io::Result<Option<usize>> {
+    let read = (&mut *reader)
+        .take(MAX_LINE_BYTES as u64 + 1)
+        .read_line(line)
+        .await?;
+    Ok((read <= MAX_LINE_BYTES).then_some(read))
+}
+
+async fn too_large(mut stream: TcpStream) -> std::io::Result<()> {
+    respond(
+        &mut stream,
+        "431 Request Header Fields Too Large",
+        "text/plain",
+        "request too large",
+    )
+    .await
+}
+
+/// Compare a presented token with the endpoint's in time that does not depend on
+/// where they differ
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.shutdown().await
+}
+
+/// Write each event as a server-sent event until the client goes away
+async fn stream_events(
+    mut stream: TcpStream,
+    mut events: broadcast::Receiver<SessionEvent>,
+) -> std::io::Result<()> {
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(missed)) => SessionEvent::Lagged {
+                missed,
+                timestamp: chrono::Utc::now(),
+            },
+            Err(broadcast::error::RecvError::Closed) => return stream.shutdown().await,
+        };
+        let json = serde_json::to_string(&event).map_err(std::io::Error::other)?;
+        stream
+            .write_all(format!("data: {}\n\n", json).as_bytes())
+            .await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn inspector() -> (Inspector, broadcast::Sender<SessionEvent>) {
+        let (events, _) = broadcast::channel(16);
+        let inspector = Inspector::start(0, InspectorView::default(), &events)
+            .await
+            .unwrap();
+        inspector.update(|view| {
+            view.highlights = vec![InspectedHighlight {
+                element_number: 1,
+                element_id: "el-1".to_string(),
+                element_type: "button".to_string(),
+                selector: "#login-button".to_string(),
+                color: "#ff0000".to_string(),
+                rect: Some(ElementRect {
+                    x: 10.0,
+                    y: 20.0,
+                    width: 80.0,
+                    height: 24.0,
+                }),
+                label_rect: None,
+            }]
+        });
+        (inspector, events)
+    }
+
+    /// Send `request` as is; the status code and body of the response
+    async fn send(addr: SocketAddr, request: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .unwrap_or(0);
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    fn get(path: &str, headers: &str) -> String {
+        format!(
+            "GET {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n",
+            path, headers
+        )
+    }
+
+    #[tokio::test]
+    async fn requests_without_the_token_are_refused() {
+        let (inspector, _events) = inspector().await;
+        let endpoint = inspector.endpoint().clone();
+        let wrong = "0".repeat(endpoint.token.len());
+
+        for request in [
+            get("/api/highlights", ""),
+            get(&format!("/api/highlights?token={}", wrong), ""),
+            get("/api/highlights?token=short", ""),
+            get(
+                "/api/highlights",
+                &format!("Authorization: Bearer {}\r\n", wrong),
+            ),
+            get(
+                "/api/highlights",
+                &format!("Authorization: Basic {}\r\n", endpoint.token),
+            ),
+        ] {
+            assert_eq!(
+                send(endpoint.addr, &request).await,
+                (401, "token required".to_string()),
+                "{}",
+                request
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn only_get_is_allowed() {
+        let (inspector, _events) = inspector().await;
+        let endpoint = inspector.endpoint().clone();
+        let request = format!(
+            "POST /api/highlights?token={} HTTP/1.1\r\nContent-Length: 2\r\n\r\n{{}}",
+            endpoint.token
+        );
+        assert_eq!(
+            send(endpoint.addr, &request).await,
+            (405, "read-only".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn highlights_are_served_as_json() {
+        let (inspector, _events) = inspector().await;
+        let endpoint = inspector.endpoint().clone();
+        let expected = serde_json::json!([{
+            "element_number": 1,
+            "element_id": "el-1",
+            "element_type": "button",
+            "selector": "#login-button",
+            "color": "#ff0000",
+            "rect": { "x": 10.0, "y": 20.0, "width": 80.0, "height": 24.0 },
+            "label_rect": null,
+        }]);
+
+        let query = format!("/api/highlights?token={}", endpoint.token);
+        let bearer = format!("Authorization: Bearer {}\r\n", endpoint.token);
+        for request in [get(&query, ""), get("/api/highlights/", &bearer)] {
+            let (status, body) = send(endpoint.addr, &request).await;
+            assert_eq!(status, 200, "{}", body);
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+                expected
+            );
+        }
+
+        let (status, _) = send(endpoint.addr, &get("/api/nothing", &bearer)).await;
+        assert_eq!(status, 404);
+    }
+
+    #[tokio::test]
+    async fn oversized_header_lines_are_refused() {
+        let (inspector, _events) = inspector().await;
+        let endpoint = inspector.endpoint().clone();
+        let long = format!("X-Padding: {}\r\n", "a".repeat(MAX_LINE_BYTES));
+        assert_eq!(
+            send(
+                endpoint.addr,
+                &get(&format!("/?token={}", endpoint.token), &long)
+            )
+            .await
+            .0,
+            431
+        );
+        let many = "X-Padding: a\r\n".repeat(MAX_HEADERS + 1);
+        assert_eq!(
+            send(
+                endpoint.addr,
+                &get(&format!("/?token={}", endpoint.token), &many)
+            )
+            .await
+            .0,
+            431
+        );
+    }
+
+    #[test]
+    fn token_comparison_needs_an_exact_match() {
+        assert!(tokens_match("4f2a", "4f2a"));
+        assert!(!tokens_match("4f2b", "4f2a"));
+        assert!(!tokens_match("4f2", "4f2a"));
+        assert!(!tokens_match("", "4f2a"));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::browser::{BrowserSession, SessionEvent};
+use crate::core::config::{KeepaliveActivity, KeepaliveOptions};
+use crate::core::BrowserTrait;
+use crate::utils::CancellationToken;
+use serde::Deserialize;
+use std::sync::Weak;
+use tokio::task::JoinHandle;
+
+/// Background keepalive started by `SessionHandle::start_keepalive`. Stops when
+/// stopped, dropped, or when the session is dropped.
+#[derive(Debug)]
+pub struct KeepaliveTask {
+    token: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+impl KeepaliveTask {
+    pub(crate) fn spawn<B>(session: Weak<BrowserSession<B>>, options: KeepaliveOptions) -> Self
+    where
+        B: BrowserTrait + 'static,
+        B::TabHandle: 'static,
+    {
+        let token = CancellationToken::new();
+        let cancelled = token.clone();
+        let interval = std::time::Duration::from_millis(options.interval_ms.max(1000));
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = cancelled.cancelled() => break,
+                    _ = tokio::time::sleep(interval) => {}
+                }
+                // Holding only a weak reference lets the session drop while this runs
+                let Some(session) = session.upgrade() else {
+                    break;
+                };
+                if let Err(e) = session.keepalive_tick(&options).await {
+                    if session.is_closed() {
+                        break;
+                    }
+                    session.publish_event(SessionEvent::KeepaliveFailed {
+                        error: e.to_string(),
+                        timestamp: chrono::Utc::now(),
+                    });
+                }
+            }
+        });
+        Self { token, task }
+    }
+
+    pub fn stop(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for KeepaliveTask {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+/// Script stamping every new document with an id that starts with `nonce`, so the
+/// session can tell when the page was replaced underneath it
+pub(crate) fn init_script(nonce: &str) -> String {
+    format!(
+        "window.__surfaiDocumentId = {} + '-' + Math.random().toString(36).slice(2);",
+        serde_json::to_string(nonce).unwrap_or_else(|_| "''".to_string())
+    )
+}
+
+/// Script performing `activity` and reporting the document's identity. Documents
+/// the init script did not reach are stamped on the spot. Evaluates to
+/// `{ id, stamped, discarded, navigation_type, url }`.
+pub(crate) fn tick_script(activity: &KeepaliveActivity, nonce: &str) -> String {
+    let activity = match activity {
+        KeepaliveActivity::MouseMove => r#"
+            const x = Math.floor(window.innerWidth / 2);
+            const y = Math.floor(window.innerHeight / 2);
+            document.dispatchEvent(new MouseEvent('mousemove', { clientX: x, clientY: y, bubbles: true }));
+        "#
+        .to_string(),
+        KeepaliveActivity::TitlePing => "void document.title;".to_string(),
+        KeepaliveActivity::Script(script) => format!("(function() {{ {} }})();", script),
+    };
+    format!(
+        r#"
+        (function() {{
+            {activity}
+            let stamped = false;
+            if (!window.__surfaiDocumentId) {{
+                {stamp}
+                stamped = true;
+            }}
+            const navigation = performance.getEntriesByType('navigation')[0];
+            return {{
+                id: window.__surfaiDocumentId,
+                stamped: stamped,
+                discarded: !!document.wasDiscarded,
+                navigation_type: navigation ? navigation.type : null,
+                url: location.href
+            }};
+        }})()
+        "#,
+        activity = activity,
+        stamp = init_script(nonce)
+    )
+}
+
+/// Shape of `tick_script`'s result
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct TickReport {
+    #[serde(default)]
+    pub id: String,
+    /// The document had no id yet, so it is new unless this is the first tick
+    #[serde(default)]
+    pub stamped: bool,
+    #[serde(default)]
+    pub discarded: bool,
+    #[serde(default)]
+    pub navigation_type: Option<String>,
+    #[serde(default)]
+    pub url: String,
+}
@@ -1,9 +1,74 @@
+pub mod actionability;
+pub mod cdp;
 pub mod chrome;
+pub mod csrf;
+pub mod dry_run;
+pub mod editor;
+pub mod element_handle;
 pub mod element_monitor;
+pub mod events;
+pub mod fetch;
+pub mod handle;
+pub(crate) mod highlight;
+pub mod inject;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+pub mod keepalive;
 pub mod navigation;
+pub mod network;
+pub mod overlay;
+pub(crate) mod pacing;
+pub(crate) mod prefetch;
+pub mod refresh;
 pub mod session;
+pub mod session_diff;
+pub mod state;
+pub mod submit;
+pub mod targeting;
+pub mod typing;
+pub mod watchdog;
+#[cfg(feature = "webdriver")]
+pub mod webdriver;
+pub mod wizard;
 
-pub use chrome::ChromeBrowser;
-pub use element_monitor::{DOMChangeResult, ElementMonitor};
+pub use actionability::{ActionabilityCheck, ActionabilityReport, CheckOutcome};
+pub use cdp::{RawCdpEvent, RawEventSubscription};
+pub use chrome::{ChromeBrowser, ChromeInstallation};
+pub use csrf::{CsrfManager, CsrfToken, DEFAULT_CSRF_HEADER};
+pub use dry_run::DryRunReport;
+pub use editor::{EditorContent, EditorTextSource};
+pub use element_handle::ElementHandle;
+pub use element_monitor::{
+    DOMChangeResult, DomChangeEvent, ElementMonitor, MonitorStats, MutationActivity,
+};
+pub use events::{EventStream, SessionEvent};
+pub use fetch::{CsrfMode, FetchOptions, FetchResponse, DEFAULT_FETCH_MAX_BODY_BYTES};
+pub use handle::SessionHandle;
+pub use inject::{InjectOptions, InjectReport, MissingCookie, WaitStrategy};
+#[cfg(feature = "inspector")]
+pub use inspector::{
+    InspectedHighlight, InspectedScreenshot, InspectorEndpoint, Observation,
+    INSPECTOR_EVENT_HISTORY,
+};
+pub use keepalive::KeepaliveTask;
 pub use navigation::{NavigationManager, NavigationResult};
-pub use session::{AIElement, BrowserSession, LoginConfig, SessionData};
+pub use network::{ResponseSubscription, WebSocketSubscription, LONG_POLL_THRESHOLD_MS};
+pub use overlay::{
+    DismissMethod, DismissOutcome, DismissStrategy, Occlusion, Overlay, OverlayKind,
+};
+pub use refresh::RefreshTask;
+pub use session::{
+    AIElement, BrowserSession, ElementState, LoginConfig, MemoryStats, ResponsiveScreenshot,
+    SessionData, TokenEntry, TokenSource,
+};
+pub use session_diff::{EntryChange, EntryChanges, SessionDataDiff};
+pub use state::{SessionState, StateTransition};
+pub use submit::{SubmitOutcome, SubmitResult};
+pub use targeting::ClickRectOptions;
+pub use typing::{TypeMode, TypeResult};
+pub use watchdog::ReloginFlow;
+#[cfg(feature = "webdriver")]
+pub use webdriver::{WebDriverBrowser, WebDriverTab};
+pub use wizard::{
+    StepMatcher, WizardConfig, WizardOutcome, WizardReport, WizardStep, WizardStepReport,
+};
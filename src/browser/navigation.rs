@@ -1,19 +1,37 @@
-use crate::core::BrowserTrait;
+use crate::core::{BrowserTrait, NetworkIdleStats, Throttling};
+use crate::dom::DocumentKind;
 use crate::errors::Result;
-use std::time::Instant;
+use crate::utils::Clock;
 
 pub struct NavigationManager;
 
-impl NavigationManager {
-    pub async fn wait_for_navigation_complete<B: BrowserTrait>(
-        browser: &B,
-        tab: &B::TabHandle,
-        timeout_ms: u64,
-    ) -> Result<NavigationResult> {
-        let start_time = Instant::now();
+/// Longest the load detection script waits, whatever the navigation timeout
+const ABSOLUTE_FALLBACK_MS: u64 = 8000;
+
+/// JavaScript giving the document a `window.__surfaiLifecycle` record, once:
+/// `restores` counts `pageshow` events for back-forward cache restores, and
+/// `hidden`/`persisted` follow the last `pagehide`. The record lives on in the
+/// cached page, so a restore is visible to the first script run after it.
+pub(crate) const LIFECYCLE_JS: &str = r#"
+                    if (!window.__surfaiLifecycle) {
+                        const record = { restores: 0, reported: 0, hidden: false, persisted: false };
+                        window.__surfaiLifecycle = record;
+                        window.addEventListener('pagehide', (event) => {
+                            record.hidden = true;
+                            record.persisted = event.persisted;
+                        });
+                        window.addEventListener('pageshow', (event) => {
+                            record.hidden = false;
+                            if (event.persisted) record.restores++;
+                        }, true);
+                    }
+"#;
 
-        // Dynamic, event-driven navigation detection
-        let navigation_script = r#"
+/// Event-driven load detection, resolving with the reason the page counts as
+/// loaded: a load milestone, a back-forward cache restore, or the fallback timer
+/// after `timeout_ms`, capped at `ABSOLUTE_FALLBACK_MS`
+fn navigation_script(timeout_ms: u64) -> String {
+    let script = r#"
             (function() {
                 return new Promise((resolve) => {
                     let resolved = false;
@@ -27,6 +45,7 @@ impl NavigationManager {
                                 reason: reason,
                                 readyState: document.readyState,
                                 url: window.location.href,
+                                contentType: document.contentType,
                                 timestamp: Date.now(),
                                 loadTime: Date.now() - startTime,
                                 ...additionalData
@@ -34,6 +53,28 @@ impl NavigationManager {
                         }
                     };
 
+                    // Pages restored from the back-forward cache fire pageshow
+                    // instead of any load event; each restore is reported once
+{lifecycle}
+                    const lifecycle = window.__surfaiLifecycle;
+                    const resolveRestore = () => {
+                        lifecycle.reported = lifecycle.restores;
+                        resolveOnce('bfcache_restore', {
+                            trigger: 'pageshow',
+                            hasContent: !!(document.body && document.body.children.length > 0),
+                            networkQuiet: true,
+                            imagesLoaded: true,
+                            bfcacheRestore: true
+                        });
+                    };
+                    if (lifecycle.restores > lifecycle.reported) {
+                        resolveRestore();
+                        return;
+                    }
+                    window.addEventListener('pageshow', (event) => {
+                        if (event.persisted) resolveRestore();
+                    });
+
                     // Immediate check - if page is already complete
                     if (document.readyState === 'complete') {
                         // Double-check that resources are actually loaded
@@ -170,7 +211,7 @@ impl NavigationManager {
                     // Check images after a short delay to let them start loading
                     setTimeout(checkImagesLoaded, 200);
 
-                    // Absolute fallback - never wait more than reasonable time
+                    // Absolute fallback - never wait past the navigation timeout
                     setTimeout(() => {
                         if (!resolved) {
                             resolveOnce('absolute_fallback', {
@@ -181,79 +222,64 @@ impl NavigationManager {
                                 finalReadyState: document.readyState
                             });
                         }
-                    }, 8000);
+                    }, {fallback_ms});
                 });
             })()
         "#;
+    script.replace("{lifecycle}", LIFECYCLE_JS).replace(
+        "{fallback_ms}",
+        &timeout_ms.min(ABSOLUTE_FALLBACK_MS).to_string(),
+    )
+}
+
+impl NavigationManager {
+    pub async fn wait_for_navigation_complete<B: BrowserTrait>(
+        browser: &B,
+        tab: &B::TabHandle,
+        clock: &dyn Clock,
+        timeout_ms: u64,
+    ) -> Result<NavigationResult> {
+        let start_time = clock.now();
 
         // Execute the dynamic navigation detection
-        let result = browser.execute_script(tab, navigation_script).await?;
-
-        if let Some(obj) = result.as_object() {
-            if obj
-                .get("success")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false)
-            {
-                let reason = obj
-                    .get("reason")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-                let url = obj
-                    .get("url")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let ready_state = obj
-                    .get("readyState")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let load_time = obj.get("loadTime").and_then(|v| v.as_u64()).unwrap_or(0);
-
-                return Ok(NavigationResult {
-                    success: true,
-                    reason,
-                    url,
-                    ready_state,
-                    duration_ms: start_time.elapsed().as_millis() as u64,
-                    actual_load_time: load_time,
-                    network_quiet: obj
-                        .get("networkQuiet")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false),
-                    has_content: obj
-                        .get("hasContent")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false),
-                });
-            }
+        let result = browser
+            .execute_script(tab, &navigation_script(timeout_ms))
+            .await?;
+
+        if let Some(navigation) = NavigationResult::from_script(
+            &result,
+            clock.elapsed_since(start_time).as_millis() as u64,
+        ) {
+            return Ok(navigation);
         }
 
         // If script execution failed, use minimal fallback
-        Self::minimal_fallback(browser, tab).await
+        Self::minimal_fallback(browser, tab, clock).await
     }
 
     async fn minimal_fallback<B: BrowserTrait>(
         browser: &B,
         tab: &B::TabHandle,
+        clock: &dyn Clock,
     ) -> Result<NavigationResult> {
-        let start_time = Instant::now();
+        let start_time = clock.now();
 
         // Just check if we can get URL and basic page info
         let url = browser.get_url(tab).await.unwrap_or_default();
 
         if !url.is_empty() && !url.starts_with("about:") {
             Ok(NavigationResult {
+                document_kind: DocumentKind::from_url(&url),
                 success: true,
                 reason: "fallback_url_available".to_string(),
                 url,
                 ready_state: "unknown".to_string(),
-                duration_ms: start_time.elapsed().as_millis() as u64,
+                duration_ms: clock.elapsed_since(start_time).as_millis() as u64,
                 actual_load_time: 0,
                 network_quiet: false,
                 has_content: false,
+                network_idle: None,
+                throttling: None,
             })
         } else {
             Err(crate::errors::BrowserAgentError::NavigationFailed(
@@ -273,9 +299,62 @@ pub struct NavigationResult {
     pub actual_load_time: u64,
     pub network_quiet: bool,
     pub has_content: bool,
+    /// Request statistics when the session also waited for network idle
+    pub network_idle: Option<NetworkIdleStats>,
+    /// Network and CPU throttling in effect during the load, if any
+    pub throttling: Option<Throttling>,
+    /// What the page turned out to be, from its content type or else its URL
+    pub document_kind: DocumentKind,
 }
 
 impl NavigationResult {
+    /// Result of the navigation script, taking `duration_ms` as the time waited;
+    /// `None` unless the script reported success
+    pub(crate) fn from_script(result: &serde_json::Value, duration_ms: u64) -> Option<Self> {
+        let obj = result.as_object()?;
+        if !obj
+            .get("success")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        let text = |key: &str, default: &str| {
+            obj.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or(default)
+                .to_string()
+        };
+        let flag = |key: &str| obj.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+        let url = text("url", "");
+        Some(NavigationResult {
+            success: true,
+            reason: text("reason", "unknown"),
+            document_kind: DocumentKind::detect(
+                obj.get("contentType").and_then(|v| v.as_str()),
+                &url,
+            ),
+            url,
+            ready_state: text("readyState", ""),
+            duration_ms,
+            actual_load_time: obj.get("loadTime").and_then(|v| v.as_u64()).unwrap_or(0),
+            network_quiet: flag("networkQuiet"),
+            has_content: flag("hasContent"),
+            network_idle: None,
+            throttling: None,
+        })
+    }
+
+    /// The page came back from the back-forward cache rather than loading
+    pub fn is_bfcache_restore(&self) -> bool {
+        self.reason == "bfcache_restore"
+    }
+
+    /// The page loaded under emulated network or CPU throttling
+    pub fn was_throttled(&self) -> bool {
+        self.throttling.is_some()
+    }
+
     pub fn is_fast_load(&self) -> bool {
         self.actual_load_time < 1000
     }
@@ -297,3 +376,83 @@ impl NavigationResult {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bfcache_restores_are_loaded_pages() {
+        let result = NavigationResult::from_script(
+            &json!({
+                "success": true,
+                "reason": "bfcache_restore",
+                "url": "https://example.com/list",
+                "readyState": "complete",
+                "loadTime": 4,
+                "networkQuiet": true,
+                "hasContent": true,
+                "contentType": "text/html"
+            }),
+            12,
+        )
+        .unwrap();
+        assert!(result.is_bfcache_restore());
+        assert!(result.network_quiet && result.has_content);
+        assert_eq!(result.duration_ms, 12);
+        assert_eq!(result.actual_load_time, 4);
+        assert_eq!(result.document_kind, DocumentKind::Html);
+    }
+
+    #[test]
+    fn missing_fields_take_defaults() {
+        let result =
+            NavigationResult::from_script(&json!({ "success": true, "url": "about:blank" }), 0)
+                .unwrap();
+        assert_eq!(result.reason, "unknown");
+        assert!(!result.is_bfcache_restore());
+        assert!(!result.network_quiet && !result.has_content);
+        assert_eq!(result.ready_state, "");
+
+        let loaded = NavigationResult::from_script(
+            &json!({ "success": true, "reason": "already_complete" }),
+            0,
+        )
+        .unwrap();
+        assert!(!loaded.is_bfcache_restore());
+    }
+
+    #[test]
+    fn failed_or_malformed_results_are_rejected() {
+        assert!(NavigationResult::from_script(&json!({ "success": false }), 0).is_none());
+        assert!(NavigationResult::from_script(&json!({ "reason": "load" }), 0).is_none());
+        assert!(NavigationResult::from_script(&json!(null), 0).is_none());
+        assert!(NavigationResult::from_script(&json!("loaded"), 0).is_none());
+    }
+
+    #[test]
+    fn content_type_sets_the_document_kind() {
+        let result = NavigationResult::from_script(
+            &json!({
+                "success": true,
+                "url": "https://example.com/report",
+                "contentType": "application/pdf"
+            }),
+            0,
+        )
+        .unwrap();
+        assert_eq!(result.document_kind, DocumentKind::Pdf);
+    }
+
+    #[test]
+    fn script_embeds_the_lifecycle_and_caps_the_fallback() {
+        let script = navigation_script(30_000);
+        assert!(!script.contains("{lifecycle}"));
+        assert!(!script.contains("{fallback_ms}"));
+        assert!(script.contains("if (!window.__surfaiLifecycle)"));
+        assert!(script.contains(&format!("}}, {});", ABSOLUTE_FALLBACK_MS)));
+
+        assert!(navigation_script(2_000).contains("}, 2000);"));
+    }
+}
@@ -0,0 +1,399 @@
+use crate::core::{CapturedResponse, NetworkIdleStats, ResponseMatcher, WsDirection, WsEvent};
+use crate::errors::{BrowserAgentError, Result};
+use crate::utils::Clock;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Requests in flight longer than this are treated as long-polls and stop
+/// holding the network busy
+pub const LONG_POLL_THRESHOLD_MS: u64 = 5000;
+
+/// In-flight request bookkeeping fed from protocol network events
+#[derive(Clone, Default)]
+pub(crate) struct NetworkTracker {
+    state: Arc<Mutex<TrackerState>>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    /// Request id to URL, real start time and sequence number
+    in_flight: HashMap<String, (String, std::time::Instant, u64)>,
+    requests_seen: usize,
+    longest_request_ms: u64,
+    longest_request_url: Option<String>,
+    /// Bumped on every request start or finish
+    activity: u64,
+}
+
+impl NetworkTracker {
+    pub fn request_started(&self, request_id: &str, url: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.activity += 1;
+        // Redirects reuse the request id; count them once
+        if !state.in_flight.contains_key(request_id) {
+            state.requests_seen += 1;
+            let seq = state.activity;
+            state.in_flight.insert(
+                request_id.to_string(),
+                (url.to_string(), std::time::Instant::now(), seq),
+            );
+        }
+    }
+
+    pub fn request_finished(&self, request_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.activity += 1;
+        if let Some((url, started, _)) = state.in_flight.remove(request_id) {
+            let elapsed = started.elapsed().as_millis() as u64;
+            if elapsed >= state.longest_request_ms {
+                state.longest_request_ms = elapsed;
+                state.longest_request_url = Some(url);
+            }
+        }
+    }
+
+    /// Reset the counters reported in `NetworkIdleStats`, keeping in-flight requests
+    pub fn reset_stats(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.requests_seen = state.in_flight.len();
+        state.longest_request_ms = 0;
+        state.longest_request_url = None;
+    }
+
+    /// Sequence numbers of the requests in flight and the activity counter
+    fn snapshot(&self) -> (Vec<u64>, u64) {
+        let state = self.state.lock().unwrap();
+        let in_flight = state.in_flight.values().map(|(_, _, seq)| *seq).collect();
+        (in_flight, state.activity)
+    }
+
+    fn stats(&self, waited_ms: u64) -> NetworkIdleStats {
+        let state = self.state.lock().unwrap();
+        NetworkIdleStats {
+            requests_seen: state.requests_seen,
+            long_polls_ignored: state.in_flight.len(),
+            longest_request_ms: state.longest_request_ms,
+            longest_request_url: state.longest_request_url.clone(),
+            waited_ms,
+        }
+    }
+
+    /// Resolve once no request has been pending for `idle_ms`, timing the wait
+    /// on `clock`.
+    ///
+    /// Events arrive on the protocol thread in real time, so the quiet period and
+    /// the long-poll cutoff are both measured on `clock`, from when this wait
+    /// first saw the network go quiet or a request in flight.
+    pub async fn wait_for_idle(
+        &self,
+        clock: &dyn Clock,
+        idle_ms: u64,
+        timeout_ms: u64,
+    ) -> Result<NetworkIdleStats> {
+        let start = clock.now();
+        let mut first_seen: HashMap<u64, Instant> = HashMap::new();
+        let mut quiet_since: Option<(Instant, u64)> = None;
+
+        loop {
+            let now = clock.now();
+            let (in_flight, activity) = self.snapshot();
+            first_seen.retain(|seq, _| in_flight.contains(seq));
+            let pending = in_flight
+                .iter()
+                .filter(|seq| {
+                    let seen = *first_seen.entry(**seq).or_insert(now);
+                    now.saturating_duration_since(seen)
+                        < Duration::from_millis(LONG_POLL_THRESHOLD_MS)
+                })
+                .count();
+            if pending == 0 {
+                // A request may have started and finished between polls
+                let since = match quiet_since {
+                    Some((since, seen_activity)) if seen_activity == activity => since,
+                    _ => now,
+                };
+                quiet_since = Some((since, activity));
+                if now.saturating_duration_since(since) >= Duration::from_millis(idle_ms) {
+                    return Ok(self.stats(clock.elapsed_since(start).as_millis() as u64));
+                }
+            } else {
+                quiet_since = None;
+            }
+
+            if clock.elapsed_since(start) >= Duration::from_millis(timeout_ms) {
+                return Err(BrowserAgentError::TimeoutError(format!(
+                    "Network not idle for {}ms within {}ms ({} requests pending)",
+                    idle_ms, timeout_ms, pending
+                )));
+            }
+
+            clock.sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
+
+/// Stream of captured responses accepted by a `ResponseMatcher`
+pub struct ResponseSubscription {
+    receiver: tokio::sync::broadcast::Receiver<CapturedResponse>,
+    matcher: ResponseMatcher,
+}
+
+impl ResponseSubscription {
+    pub(crate) fn new(
+        receiver: tokio::sync::broadcast::Receiver<CapturedResponse>,
+        matcher: ResponseMatcher,
+    ) -> Self {
+        Self { receiver, matcher }
+    }
+
+    pub fn matcher(&self) -> &ResponseMatcher {
+        &self.matcher
+    }
+
+    /// Next matching response, or `None` once capture has stopped.
+    ///
+    /// Responses missed because this subscriber fell behind are skipped.
+    pub async fn next(&mut self) -> Option<CapturedResponse> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        loop {
+            match self.receiver.recv().await {
+                Ok(response) if self.matcher.matches(&response) => return Some(response),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    println!("⚠️ Response subscription lagged, {} skipped", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Bytes of a binary WebSocket frame shown in its hex preview
+const WS_HEX_PREVIEW_BYTES: usize = 32;
+
+/// Build a `WsEvent` from a protocol frame. Binary payloads arrive base64-encoded
+/// and are reported by size and hex preview only.
+pub(crate) fn ws_event(
+    url: &str,
+    direction: WsDirection,
+    opcode: u8,
+    payload_data: &str,
+    max_payload_bytes: usize,
+) -> WsEvent {
+    let binary = opcode == 2;
+    let (payload, payload_size, truncated) = if binary {
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(payload_data)
+            .unwrap_or_default();
+        let preview = bytes
+            .iter()
+            .take(WS_HEX_PREVIEW_BYTES)
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        (preview, bytes.len(), bytes.len() > WS_HEX_PREVIEW_BYTES)
+    } else if payload_data.len() > max_payload_bytes {
+        let mut end = max_payload_bytes;
+        while !payload_data.is_char_boundary(end) {
+            end -= 1;
+        }
+        (payload_data[..end].to_string(), payload_data.len(), true)
+    } else {
+        (payload_data.to_string(), payload_data.len(), false)
+    };
+
+    WsEvent {
+        url: url.to_string(),
+        direction,
+        opcode,
+        payload,
+        payload_size,
+        binary,
+        truncated,
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+/// Stream of WebSocket frames, optionally limited to URLs matching a glob
+pub struct WebSocketSubscription {
+    receiver: tokio::sync::broadcast::Receiver<WsEvent>,
+    url_pattern: Option<regex::Regex>,
+}
+
+impl WebSocketSubscription {
+    pub(crate) fn new(
+        receiver: tokio::sync::broadcast::Receiver<WsEvent>,
+        url_pattern: Option<regex::Regex>,
+    ) -> Self {
+        Self {
+            receiver,
+            url_pattern,
+        }
+    }
+
+    /// Next frame on a matching socket, or `None` once observation has stopped
+    pub async fn next(&mut self) -> Option<WsEvent> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        loop {
+            match self.receiver.recv().await {
+                Ok(event)
+                    if self
+                        .url_pattern
+                        .as_ref()
+                        .is_none_or(|re| re.is_match(&event.url)) =>
+                {
+                    return Some(event)
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    println!(
+                        "⚠️ WebSocket subscription lagged, {} frame(s) skipped",
+                        skipped
+                    );
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// GraphQL operation name of a request, from an `operationName` field in the JSON
+/// body (the first operation of a batch), the query string, or the document itself
+pub(crate) fn graphql_operation_name(url: &str, body: Option<&str>) -> Option<String> {
+    if let Some(body) = body {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
+            let operation = match &value {
+                serde_json::Value::Array(batch) => batch.first().cloned(),
+                _ => Some(value),
+            }?;
+            if let Some(name) = operation.get("operationName").and_then(|v| v.as_str()) {
+                return Some(name.to_string());
+            }
+            if let Some(query) = operation.get("query").and_then(|v| v.as_str()) {
+                return operation_from_document(query);
+            }
+            return None;
+        }
+    }
+
+    let parsed = url::Url::parse(url).ok()?;
+    let mut query_document = None;
+    for (key, value) in parsed.query_pairs() {
+        match key.as_ref() {
+            "operationName" if !value.is_empty() => return Some(value.into_owned()),
+            "query" => query_document = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+    query_document.and_then(|query| operation_from_document(&query))
+}
+
+fn operation_from_document(query: &str) -> Option<String> {
+    let re =
+        regex::Regex::new(r"\b(?:query|mutation|subscription)\s+([_A-Za-z][_0-9A-Za-z]*)").ok()?;
+    re.captures(query).map(|c| c[1].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::VirtualClock;
+
+    /// Feed the tracker from another thread, as the protocol listener does
+    fn on_listener_thread(tracker: &NetworkTracker, events: impl FnOnce(&NetworkTracker) + Send) {
+        std::thread::scope(|scope| {
+            scope.spawn(|| events(tracker));
+        });
+    }
+
+    #[tokio::test]
+    async fn idle_comes_after_the_quiet_period() {
+        let clock = VirtualClock::start();
+        let real = std::time::Instant::now();
+        let tracker = NetworkTracker::default();
+        on_listener_thread(&tracker, |tracker| {
+            tracker.request_started("1", "https://example.com/app.js");
+            tracker.request_finished("1");
+        });
+
+        let stats = tracker.wait_for_idle(&clock, 500, 10_000).await.unwrap();
+        assert!((500..600).contains(&stats.waited_ms), "{}", stats.waited_ms);
+        assert_eq!(stats.requests_seen, 1);
+        assert_eq!(stats.long_polls_ignored, 0);
+        assert_eq!(
+            stats.longest_request_url.as_deref(),
+            Some("https://example.com/app.js")
+        );
+        assert!(real.elapsed() < Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn activity_during_the_wait_restarts_the_quiet_period() {
+        let clock = VirtualClock::start();
+        let tracker = NetworkTracker::default();
+        let wait = tracker.wait_for_idle(&clock, 500, 10_000);
+        let traffic = async {
+            clock.sleep(Duration::from_millis(300)).await;
+            on_listener_thread(&tracker, |tracker| {
+                tracker.request_started("1", "https://example.com/api");
+                tracker.request_finished("1");
+            });
+        };
+        let (stats, ()) = tokio::join!(wait, traffic);
+        let stats = stats.unwrap();
+        assert!(stats.waited_ms >= 800, "{}", stats.waited_ms);
+        assert_eq!(stats.requests_seen, 1);
+    }
+
+    #[tokio::test]
+    async fn pending_requests_run_into_the_timeout() {
+        let clock = VirtualClock::start();
+        let tracker = NetworkTracker::default();
+        on_listener_thread(&tracker, |tracker| {
+            tracker.request_started("1", "https://example.com/slow")
+        });
+
+        let err = tracker.wait_for_idle(&clock, 500, 2_000).await.unwrap_err();
+        assert!(err.to_string().contains("1 requests pending"), "{}", err);
+        assert!(clock.elapsed() >= Duration::from_millis(2_000));
+    }
+
+    #[tokio::test]
+    async fn long_polls_stop_holding_the_network_busy() {
+        let clock = VirtualClock::start();
+        let real = std::time::Instant::now();
+        let tracker = NetworkTracker::default();
+        on_listener_thread(&tracker, |tracker| {
+            tracker.request_started("1", "https://example.com/events")
+        });
+
+        let stats = tracker.wait_for_idle(&clock, 500, 10_000).await.unwrap();
+        assert!(
+            stats.waited_ms >= LONG_POLL_THRESHOLD_MS + 500,
+            "{}",
+            stats.waited_ms
+        );
+        assert_eq!(stats.long_polls_ignored, 1);
+        assert!(real.elapsed() < Duration::from_millis(LONG_POLL_THRESHOLD_MS));
+    }
+
+    #[test]
+    fn redirects_count_once() {
+        let tracker = NetworkTracker::default();
+        tracker.request_started("1", "https://example.com/");
+        tracker.request_started("1", "https://example.com/home");
+        tracker.request_started("2", "https://example.com/style.css");
+        assert_eq!(tracker.stats(0).requests_seen, 2);
+
+        tracker.request_finished("2");
+        tracker.reset_stats();
+        let stats = tracker.stats(0);
+        assert_eq!(stats.requests_seen, 1);
+        assert_eq!(stats.longest_request_url, None);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::core::LanguagePack;
+use crate::dom::ElementRect;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayKind {
+    /// Dialog or interstitial blocking the page, usually with a backdrop
+    Modal,
+    /// Consent or cookie notice pinned to an edge of the viewport
+    CookieBanner,
+    /// Small transient notification
+    Toast,
+    Other,
+}
+
+/// A positioned element covering part of the viewport
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Overlay {
+    /// Unique CSS selector for the overlay root
+    pub selector: String,
+    pub kind: OverlayKind,
+    /// Fraction of the viewport covered (0.0-1.0)
+    pub coverage: f64,
+    pub z_index: i64,
+    pub rect: ElementRect,
+    /// Start of the overlay's text, for logging
+    pub text: String,
+    /// Selector of the control that looks like a close button, if any
+    pub close_selector: Option<String>,
+}
+
+/// How `dismiss_overlay` gets rid of an overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum DismissStrategy {
+    /// Close button, then Escape, then hiding
+    #[default]
+    Auto,
+    CloseButton,
+    EscapeKey,
+    /// Hide the overlay and restore page scrolling, without involving the page's own code
+    Hide,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DismissMethod {
+    CloseButton(String),
+    EscapeKey,
+    Hidden,
+}
+
+/// What `dismiss_overlay` did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DismissOutcome {
+    pub overlay: Overlay,
+    /// Method that removed the overlay; `None` when every attempt left it in place
+    pub method: Option<DismissMethod>,
+    pub attempts: Vec<String>,
+}
+
+/// An element whose center is covered by something else
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Occlusion {
+    pub selector: String,
+    /// Selector of the element found at the center point
+    pub covered_by: String,
+}
+
+/// Shared helper: a reasonably unique selector for an element
+pub(crate) const UNIQUE_SELECTOR_JS: &str = r#"
+    const uniqueSelector = (el) => {
+        if (el.id) return '#' + CSS.escape(el.id);
+        const parts = [];
+        while (el && el.nodeType === 1 && el !== document.documentElement) {
+            let part = el.tagName.toLowerCase();
+            if (el.id) {
+                parts.unshift('#' + CSS.escape(el.id));
+                break;
+            }
+            const parent = el.parentElement;
+            if (parent) {
+                const same = Array.from(parent.children).filter(c => c.tagName === el.tagName);
+                if (same.length > 1) part += ':nth-of-type(' + (same.indexOf(el) + 1) + ')';
+            }
+            parts.unshift(part);
+            el = parent;
+        }
+        return parts.join(' > ');
+    };
+"#;
+
+/// Script listing overlays; close controls and consent notices are recognized by
+/// the `dismiss` and `consent` words of `keywords`
+pub(crate) fn detect_script(min_coverage: f64, keywords: &LanguagePack) -> String {
+    format!(
+        r#"
+        (function() {{
+            {unique}
+            const viewportArea = window.innerWidth * window.innerHeight;
+            const escape = (word) => word.replace(/[.*+?^${{}}()|[\]\\]/g, '\\$&');
+            const alternatives = (words) => words.map(escape).join('|');
+            const closePattern = new RegExp('^(' + alternatives({dismiss}.concat(['×', '✕', 'x'])) + ')$', 'i');
+            const consentPattern = new RegExp('(' + alternatives({consent}) + ')', 'i');
+
+            const findClose = (root) => {{
+                const candidates = root.querySelectorAll(
+                    'button, [role="button"], a, [aria-label], [class*="close"], [data-dismiss]'
+                );
+                for (const el of candidates) {{
+                    const label = (el.getAttribute('aria-label') || el.title || el.textContent || '').trim();
+                    if (closePattern.test(label) || /close|dismiss/i.test(el.className || '')
+                        || el.hasAttribute('data-dismiss')) {{
+                        return uniqueSelector(el);
+                    }}
+                }}
+                return null;
+            }};
+
+            const overlays = [];
+            const seen = new Set();
+            for (const el of document.body.querySelectorAll('*')) {{
+                const style = window.getComputedStyle(el);
+                if (!['fixed', 'sticky', 'absolute'].includes(style.position)) continue;
+                if (style.display === 'none' || style.visibility === 'hidden' || style.opacity === '0') continue;
+                if (el.hasAttribute('data-surfai-internal')) continue;
+
+                const zIndex = parseInt(style.zIndex, 10);
+                if (style.position === 'absolute' && !(zIndex > 0)) continue;
+
+                const rect = el.getBoundingClientRect();
+                const width = Math.max(0, Math.min(rect.right, window.innerWidth) - Math.max(rect.left, 0));
+                const height = Math.max(0, Math.min(rect.bottom, window.innerHeight) - Math.max(rect.top, 0));
+                const coverage = viewportArea > 0 ? (width * height) / viewportArea : 0;
+                if (coverage < {min_coverage}) continue;
+
+                // Report only the outermost overlay of a nested group
+                let ancestor = el.parentElement;
+                let nested = false;
+                while (ancestor) {{
+                    if (seen.has(ancestor)) {{ nested = true; break; }}
+                    ancestor = ancestor.parentElement;
+                }}
+                if (nested) continue;
+                seen.add(el);
+
+                const text = (el.innerText || '').trim().replace(/\s+/g, ' ');
+                const role = el.getAttribute('role') || '';
+                let kind = 'other';
+                if (consentPattern.test(text.slice(0, 500)) || consentPattern.test(el.id + ' ' + el.className)) {{
+                    kind = 'cookie_banner';
+                }} else if (role === 'dialog' || role === 'alertdialog' || el.getAttribute('aria-modal') === 'true'
+                    || el.tagName === 'DIALOG' || coverage > 0.5) {{
+                    kind = 'modal';
+                }} else if (role === 'status' || role === 'alert' || coverage < 0.1) {{
+                    kind = 'toast';
+                }}
+
+                overlays.push({{
+                    selector: uniqueSelector(el),
+                    kind: kind,
+                    coverage: coverage,
+                    z_index: isNaN(zIndex) ? 0 : zIndex,
+                    rect: {{ x: rect.x, y: rect.y, width: rect.width, height: rect.height }},
+                    text: text.slice(0, 120),
+                    close_selector: findClose(el)
+                }});
+            }}
+
+            overlays.sort((a, b) => b.z_index - a.z_index || b.coverage - a.coverage);
+            return overlays;
+        }})()
+        "#,
+        unique = UNIQUE_SELECTOR_JS,
+        min_coverage = min_coverage,
+        dismiss = serde_json::to_string(&keywords.dismiss).unwrap_or_else(|_| "[]".to_string()),
+        consent = serde_json::to_string(&keywords.consent).unwrap_or_else(|_| "[]".to_string())
+    )
+}
+
+/// Script reporting whether the overlay at `selector` is still showing
+pub(crate) fn visible_script(selector: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const el = document.querySelector({selector});
+            if (!el || !el.isConnected) return false;
+            const style = window.getComputedStyle(el);
+            const rect = el.getBoundingClientRect();
+            return style.display !== 'none' && style.visibility !== 'hidden'
+                && style.opacity !== '0' && rect.width > 0 && rect.height > 0;
+        }})()
+        "#,
+        selector = serde_json::to_string(selector).unwrap_or_default()
+    )
+}
+
+/// Script hiding the overlay and undoing the usual scroll lock on the page
+pub(crate) fn hide_script(selector: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const el = document.querySelector({selector});
+            if (!el) return false;
+            el.style.setProperty('display', 'none', 'important');
+            for (const root of [document.documentElement, document.body]) {{
+                if (window.getComputedStyle(root).overflow === 'hidden') {{
+                    root.style.setProperty('overflow', 'auto', 'important');
+                }}
+            }}
+            return true;
+        }})()
+        "#,
+        selector = serde_json::to_string(selector).unwrap_or_default()
+    )
+}
+
+/// Script returning the selectors whose center point is covered by another element
+pub(crate) fn occlusion_script(selectors: &[String]) -> String {
+    format!(
+        r#"
+        (function() {{
+            {unique}
+            const selectors = {selectors};
+            const occluded = [];
+            for (const selector of selectors) {{
+                let el;
+                try {{ el = document.querySelector(selector); }} catch (e) {{ continue; }}
+                if (!el) continue;
+                const rect = el.getBoundingClientRect();
+                if (rect.width === 0 || rect.height === 0) continue;
+                const x = rect.left + rect.width / 2;
+                const y = rect.top + rect.height / 2;
+                if (x < 0 || y < 0 || x >= window.innerWidth || y >= window.innerHeight) continue;
+                const top = document.elementFromPoint(x, y);
+                if (top && top !== el && !el.contains(top) && !top.contains(el)) {{
+                    occluded.push({{ selector: selector, covered_by: uniqueSelector(top) }});
+                }}
+            }}
+            return occluded;
+        }})()
+        "#,
+        unique = UNIQUE_SELECTOR_JS,
+        selectors = serde_json::to_string(selectors).unwrap_or_default()
+    )
+}
+
+pub(crate) fn overlays_from_value(value: Value) -> Vec<Overlay> {
+    serde_json::from_value(value).unwrap_or_default()
+}
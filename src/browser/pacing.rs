@@ -0,0 +1,213 @@
+use crate::core::config::{DelayRange, PacingOptions};
+use crate::utils::SeededRng;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Draws the delays, pointer paths and scroll steps of a session's
+/// `InteractionProfile`
+#[derive(Debug)]
+pub(crate) struct Pacer {
+    options: PacingOptions,
+    rng: SeededRng,
+    /// Where the last native pointer movement ended
+    pointer: Mutex<Option<(f64, f64)>>,
+}
+
+impl Pacer {
+    pub fn new(options: PacingOptions, seed: Option<u64>) -> Self {
+        Self {
+            options,
+            rng: SeededRng::new(seed),
+            pointer: Mutex::new(None),
+        }
+    }
+
+    fn delay(&self, range: DelayRange) -> Duration {
+        Duration::from_millis(self.rng.range(range.min, range.max))
+    }
+
+    /// Wait before the next click, typed text or scroll
+    pub fn action_delay(&self) -> Duration {
+        self.delay(self.options.action_delay_ms)
+    }
+
+    /// Whether text is typed one character at a time
+    pub fn types_per_character(&self) -> bool {
+        !self.options.keystroke_delay_ms.is_zero()
+    }
+
+    /// Wait before the next character, now and then with a longer hesitation
+    pub fn keystroke_delay(&self) -> Duration {
+        let delay = self.delay(self.options.keystroke_delay_ms);
+        if self.rng.chance(self.options.pause_probability) {
+            delay + self.delay(self.options.pause_ms)
+        } else {
+            delay
+        }
+    }
+
+    /// Points from the last pointer position to `target` along a cubic Bézier
+    /// curve with randomly bent control points, ending on `target`. Empty when the
+    /// profile jumps straight there.
+    pub fn pointer_path(&self, target: (f64, f64)) -> Vec<(f64, f64)> {
+        let steps = self.options.mouse_steps;
+        let start = self.pointer.lock().unwrap().replace(target);
+        if steps == 0 {
+            return Vec::new();
+        }
+        // Without a known position, approach from a short distance away
+        let start = start.unwrap_or((
+            target.0 - 40.0 - self.rng.next_f64() * 120.0,
+            target.1 - 20.0 - self.rng.next_f64() * 80.0,
+        ));
+        let (dx, dy) = (target.0 - start.0, target.1 - start.1);
+        let distance = (dx * dx + dy * dy).sqrt();
+        // Perpendicular offsets up to a third of the distance bend the path
+        let (nx, ny) = if distance > 0.0 {
+            (-dy / distance, dx / distance)
+        } else {
+            (0.0, 0.0)
+        };
+        let bend = |fraction: f64| {
+            let offset = (self.rng.next_f64() - 0.5) * distance * 2.0 / 3.0;
+            (
+                start.0 + dx * fraction + nx * offset,
+                start.1 + dy * fraction + ny * offset,
+            )
+        };
+        let (c1, c2) = (bend(0.3), bend(0.7));
+
+        (1..=steps)
+            .map(|step| {
+                // Ease in and out, so the pointer accelerates and then settles
+                let t = ease_in_out(step as f64 / steps as f64);
+                let u = 1.0 - t;
+                (
+                    u * u * u * start.0
+                        + 3.0 * u * u * t * c1.0
+                        + 3.0 * u * t * t * c2.0
+                        + t * t * t * target.0,
+                    u * u * u * start.1
+                        + 3.0 * u * u * t * c1.1
+                        + 3.0 * u * t * t * c2.1
+                        + t * t * t * target.1,
+                )
+            })
+            .collect()
+    }
+
+    /// Wait between points of a pointer path
+    pub fn pointer_step_delay(&self) -> Duration {
+        self.delay(self.options.mouse_step_delay_ms)
+    }
+
+    /// Whether page scrolls are split into eased increments
+    pub fn eases_scrolling(&self) -> bool {
+        self.options.scroll_steps > 0
+    }
+
+    /// Increments adding up to a (`left`, `top`) scroll, small at both ends. Empty
+    /// when the profile scrolls at once.
+    pub fn scroll_steps(&self, left: f64, top: f64) -> Vec<(f64, f64)> {
+        let steps = self.options.scroll_steps;
+        if steps == 0 || (left == 0.0 && top == 0.0) {
+            return Vec::new();
+        }
+        let mut previous = 0.0;
+        (1..=steps)
+            .map(|step| {
+                let progress = ease_in_out(step as f64 / steps as f64);
+                let fraction = progress - previous;
+                previous = progress;
+                (left * fraction, top * fraction)
+            })
+            .collect()
+    }
+
+    /// Wait between scroll increments
+    pub fn scroll_step_delay(&self) -> Duration {
+        self.delay(self.options.scroll_step_delay_ms)
+    }
+}
+
+fn ease_in_out(t: f64) -> f64 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::InteractionProfile;
+
+    fn within(delay: Duration, min: u64, max: u64) -> bool {
+        (min..=max).contains(&(delay.as_millis() as u64))
+    }
+
+    #[test]
+    fn delays_stay_within_the_profile_bounds() {
+        for profile in [InteractionProfile::Fast, InteractionProfile::Human] {
+            let options = profile.pacing();
+            let pacer = Pacer::new(options.clone(), Some(7));
+            let (action, keystroke, pause) = (
+                options.action_delay_ms,
+                options.keystroke_delay_ms,
+                options.pause_ms,
+            );
+            for _ in 0..1_000 {
+                assert!(within(pacer.action_delay(), action.min, action.max));
+                assert!(within(
+                    pacer.keystroke_delay(),
+                    keystroke.min,
+                    keystroke.max + pause.max
+                ));
+                let step = options.mouse_step_delay_ms;
+                assert!(within(pacer.pointer_step_delay(), step.min, step.max));
+                let step = options.scroll_step_delay_ms;
+                assert!(within(pacer.scroll_step_delay(), step.min, step.max));
+            }
+        }
+
+        let instant = Pacer::new(InteractionProfile::Instant.pacing(), Some(7));
+        assert_eq!(instant.action_delay(), Duration::ZERO);
+        assert!(!instant.types_per_character());
+        assert!(instant.pointer_path((10.0, 10.0)).is_empty());
+        assert!(instant.scroll_steps(0.0, 500.0).is_empty());
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_sequence() {
+        let run = |seed| {
+            let pacer = Pacer::new(InteractionProfile::Human.pacing(), Some(seed));
+            let delays: Vec<_> = (0..50)
+                .map(|_| (pacer.action_delay(), pacer.keystroke_delay()))
+                .collect();
+            let paths = [
+                pacer.pointer_path((300.0, 200.0)),
+                pacer.pointer_path((20.0, 40.0)),
+            ];
+            (delays, paths)
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42).0, run(43).0);
+    }
+
+    #[test]
+    fn paths_end_on_the_target_and_scroll_steps_add_up() {
+        let pacer = Pacer::new(InteractionProfile::Human.pacing(), Some(1));
+        let path = pacer.pointer_path((300.0, 200.0));
+        assert_eq!(path.len(), 20);
+        let last = *path.last().unwrap();
+        assert!((last.0 - 300.0).abs() < 1e-9 && (last.1 - 200.0).abs() < 1e-9);
+
+        let steps = pacer.scroll_steps(0.0, 1200.0);
+        assert_eq!(steps.len(), 12);
+        let total: f64 = steps.iter().map(|(_, top)| top).sum();
+        assert!((total - 1200.0).abs() < 1e-6);
+        // Eased: the middle increment is larger than the first and the last
+        assert!(steps[6].1 > steps[0].1 && steps[6].1 > steps[11].1);
+    }
+}
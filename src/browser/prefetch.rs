@@ -0,0 +1,199 @@
+use crate::browser::ElementMonitor;
+use crate::dom::DomState;
+use std::collections::VecDeque;
+
+/// A page loaded and extracted in a background tab by `BrowserSession::prefetch`
+pub(crate) struct PrefetchedPage<T> {
+    /// URL that was asked for
+    pub url: String,
+    /// URL the tab ended up on, after redirects
+    pub final_url: String,
+    pub tab: T,
+    pub dom_state: DomState,
+    /// Watches the tab, so changes after extraction invalidate `dom_state`
+    pub monitor: ElementMonitor,
+    /// When extraction finished, on the session's clock
+    pub fetched_at: tokio::time::Instant,
+    /// Approximate size of `dom_state`, counted against the cache's byte budget
+    pub bytes: usize,
+}
+
+impl<T> PrefetchedPage<T> {
+    fn matches(&self, url: &str) -> bool {
+        self.url == url || self.final_url == url
+    }
+}
+
+/// Outcome of `PrefetchCache::insert`. The tabs of every page in it are the
+/// caller's to close.
+pub(crate) struct Inserted<T> {
+    /// Pages replaced or pushed out to make room
+    pub evicted: Vec<PrefetchedPage<T>>,
+    /// The inserted page itself, when it alone takes more than the byte budget;
+    /// it is not kept
+    pub too_large: Option<PrefetchedPage<T>>,
+}
+
+/// Prefetched pages, least recently prefetched first
+pub(crate) struct PrefetchCache<T> {
+    pages: VecDeque<PrefetchedPage<T>>,
+}
+
+impl<T> PrefetchCache<T> {
+    pub fn new() -> Self {
+        Self {
+            pages: VecDeque::new(),
+        }
+    }
+
+    /// Add `page`, replacing the page prefetched for its URL and pushing out the
+    /// oldest pages beyond `limit` pages or `max_bytes` in total. A page larger
+    /// than `max_bytes` on its own is handed back instead, leaving the others.
+    pub fn insert(
+        &mut self,
+        page: PrefetchedPage<T>,
+        limit: usize,
+        max_bytes: usize,
+    ) -> Inserted<T> {
+        let mut evicted = self.remove_matching(&page.url);
+        if page.bytes > max_bytes {
+            return Inserted {
+                evicted,
+                too_large: Some(page),
+            };
+        }
+        self.pages.push_back(page);
+        while self.pages.len() > limit || self.bytes() > max_bytes {
+            evicted.extend(self.pages.pop_front());
+        }
+        Inserted {
+            evicted,
+            too_large: None,
+        }
+    }
+
+    /// Take out the page prefetched for `url`, matching either the requested or
+    /// the final URL
+    pub fn take(&mut self, url: &str) -> Option<PrefetchedPage<T>> {
+        let index = self.pages.iter().position(|page| page.matches(url))?;
+        self.pages.remove(index)
+    }
+
+    fn remove_matching(&mut self, url: &str) -> Vec<PrefetchedPage<T>> {
+        let (matching, rest): (VecDeque<_>, VecDeque<_>) =
+            self.pages.drain(..).partition(|page| page.matches(url));
+        self.pages = rest;
+        matching.into()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Approximate size of the kept pages
+    pub fn bytes(&self) -> usize {
+        self.pages.iter().map(|page| page.bytes).sum()
+    }
+
+    pub fn urls(&self) -> Vec<String> {
+        self.pages.iter().map(|page| page.url.clone()).collect()
+    }
+
+    pub fn drain(&mut self) -> Vec<PrefetchedPage<T>> {
+        self.pages.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, bytes: usize) -> PrefetchedPage<()> {
+        PrefetchedPage {
+            url: url.to_string(),
+            final_url: format!("{}/landed", url),
+            tab: (),
+            dom_state: DomState::new(url.to_string(), String::new()),
+            monitor: ElementMonitor::new(),
+            fetched_at: tokio::time::Instant::now(),
+            bytes,
+        }
+    }
+
+    fn urls(pages: &[PrefetchedPage<()>]) -> Vec<&str> {
+        pages.iter().map(|page| page.url.as_str()).collect()
+    }
+
+    #[test]
+    fn the_page_limit_evicts_the_oldest_first() {
+        let mut cache = PrefetchCache::new();
+        assert!(cache
+            .insert(page("/a", 10), 2, usize::MAX)
+            .evicted
+            .is_empty());
+        assert!(cache
+            .insert(page("/b", 10), 2, usize::MAX)
+            .evicted
+            .is_empty());
+        let evicted = cache.insert(page("/c", 10), 2, usize::MAX).evicted;
+        assert_eq!(urls(&evicted), ["/a"]);
+        assert_eq!(cache.urls(), ["/b", "/c"]);
+    }
+
+    #[test]
+    fn the_byte_budget_evicts_until_it_fits() {
+        let mut cache = PrefetchCache::new();
+        cache.insert(page("/a", 40), 10, 100);
+        cache.insert(page("/b", 40), 10, 100);
+        let inserted = cache.insert(page("/c", 50), 10, 100);
+        assert!(inserted.too_large.is_none());
+        let evicted = inserted.evicted;
+        assert_eq!(urls(&evicted), ["/a"]);
+        assert_eq!(cache.bytes(), 90);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn a_page_over_the_budget_is_handed_back() {
+        let mut cache = PrefetchCache::new();
+        cache.insert(page("/a", 10), 10, 100);
+        let inserted = cache.insert(page("/huge", 500), 10, 100);
+        assert!(inserted.evicted.is_empty());
+        assert_eq!(inserted.too_large.unwrap().url, "/huge");
+        assert_eq!(cache.urls(), ["/a"]);
+        assert_eq!(cache.bytes(), 10);
+    }
+
+    #[test]
+    fn a_page_filling_the_budget_is_kept() {
+        let mut cache = PrefetchCache::new();
+        cache.insert(page("/a", 10), 10, 100);
+        let inserted = cache.insert(page("/b", 100), 10, 100);
+        assert!(inserted.too_large.is_none());
+        assert_eq!(urls(&inserted.evicted), ["/a"]);
+        assert_eq!(cache.urls(), ["/b"]);
+    }
+
+    #[test]
+    fn prefetching_again_replaces_the_page() {
+        let mut cache = PrefetchCache::new();
+        cache.insert(page("/a", 10), 10, 100);
+        cache.insert(page("/b", 10), 10, 100);
+        let evicted = cache.insert(page("/a", 20), 10, 100).evicted;
+        assert_eq!(urls(&evicted), ["/a"]);
+        assert_eq!(evicted[0].bytes, 10);
+        assert_eq!(cache.urls(), ["/b", "/a"]);
+        assert_eq!(cache.bytes(), 30);
+    }
+
+    #[test]
+    fn pages_are_taken_by_requested_or_final_url() {
+        let mut cache = PrefetchCache::new();
+        cache.insert(page("/a", 10), 10, 100);
+        cache.insert(page("/b", 10), 10, 100);
+        assert_eq!(cache.take("/b/landed").unwrap().url, "/b");
+        assert_eq!(cache.take("/a").unwrap().url, "/a");
+        assert!(cache.take("/a").is_none());
+        assert_eq!(cache.bytes(), 0);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::browser::BrowserSession;
+use crate::core::BrowserTrait;
+use crate::utils::{CancellationToken, SharedClock};
+use std::future::Future;
+use std::sync::Weak;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Quiet time after the last request before the refresh runs
+pub const DEFAULT_REFRESH_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// A steady stream of requests delays the refresh by at most this many debounce
+/// periods
+const MAX_DEBOUNCE_PERIODS: u32 = 5;
+
+/// Background highlight refresh started by `SessionHandle::start_background_refresh`.
+///
+/// Interactions only ask for a refresh; bursts of requests are collapsed into one
+/// run of change detection, re-extraction and redrawing. Stops when stopped,
+/// dropped, or when the session is dropped.
+#[derive(Debug)]
+pub struct RefreshTask {
+    token: CancellationToken,
+    task: JoinHandle<()>,
+    /// Number of refreshes asked for so far
+    requested: watch::Sender<u64>,
+    /// The `requested` count the last finished refresh covered
+    completed: watch::Receiver<u64>,
+}
+
+impl RefreshTask {
+    pub(crate) fn spawn<B>(
+        session: Weak<BrowserSession<B>>,
+        clock: SharedClock,
+        debounce: Duration,
+    ) -> Self
+    where
+        B: BrowserTrait + 'static,
+        B::TabHandle: 'static,
+    {
+        Self::spawn_with(clock, debounce, move || {
+            let session = session.clone();
+            async move {
+                // Holding only a weak reference lets the session drop while this waits
+                let Some(session) = session.upgrade() else {
+                    return false;
+                };
+                if let Err(e) = session.refresh_if_changed().await {
+                    if session.is_closed() {
+                        return false;
+                    }
+                    tracing::warn!(error = %e, "background highlight refresh failed");
+                }
+                true
+            }
+        })
+    }
+
+    /// Run `refresh` once per debounced burst of requests, timed on `clock`,
+    /// until it returns false
+    fn spawn_with<F, Fut>(clock: SharedClock, debounce: Duration, mut refresh: F) -> Self
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = bool> + Send,
+    {
+        let token = CancellationToken::new();
+        let cancelled = token.clone();
+        let (requested, mut pending) = watch::channel(0u64);
+        let (done, completed) = watch::channel(0u64);
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = cancelled.cancelled() => break,
+                    changed = pending.changed() => if changed.is_err() { break },
+                }
+                let started = clock.now();
+                let limit = debounce * MAX_DEBOUNCE_PERIODS;
+                loop {
+                    tokio::select! {
+                        _ = cancelled.cancelled() => return,
+                        _ = clock.sleep(debounce) => break,
+                        changed = pending.changed() => {
+                            if changed.is_err() {
+                                return;
+                            }
+                            if clock.elapsed_since(started) >= limit {
+                                break;
+                            }
+                        }
+                    }
+                }
+                let generation = *pending.borrow_and_update();
+                if !refresh().await {
+                    break;
+                }
+                let _ = done.send(generation);
+            }
+        });
+        Self {
+            token,
+            task,
+            requested,
+            completed,
+        }
+    }
+
+    /// Ask for a refresh without waiting for it
+    pub(crate) fn request(&self) {
+        self.requested.send_modify(|count| *count += 1);
+    }
+
+    /// Future resolving once every refresh requested so far has run, or the task
+    /// has stopped
+    pub(crate) fn settled(&self) -> impl Future<Output = ()> + Send + 'static {
+        let target = *self.requested.borrow();
+        let mut completed = self.completed.clone();
+        async move {
+            let _ = completed.wait_for(|done| *done >= target).await;
+        }
+    }
+
+    pub fn stop(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for RefreshTask {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::VirtualClock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tokio::time::Instant;
+
+    const DEBOUNCE: Duration = Duration::from_millis(30);
+
+    /// Task on virtual time counting its refreshes, and the times they ran
+    fn counting() -> (RefreshTask, Arc<Mutex<Vec<Instant>>>) {
+        let clock = VirtualClock::start().shared();
+        let runs = Arc::new(Mutex::new(Vec::new()));
+        let recorded = runs.clone();
+        let task = RefreshTask::spawn_with(clock.clone(), DEBOUNCE, move || {
+            recorded.lock().unwrap().push(clock.now());
+            async { true }
+        });
+        (task, runs)
+    }
+
+    async fn settle(task: &RefreshTask) {
+        tokio::time::timeout(Duration::from_secs(5), task.settled())
+            .await
+            .expect("refresh never settled");
+    }
+
+    #[tokio::test]
+    async fn a_burst_of_requests_refreshes_once() {
+        let (task, runs) = counting();
+        let started = Instant::now();
+        for _ in 0..10 {
+            task.request();
+        }
+        settle(&task).await;
+
+        let runs = runs.lock().unwrap().clone();
+        assert_eq!(runs.len(), 1);
+        // Only after the quiet period
+        assert!(runs[0] - started >= DEBOUNCE);
+    }
+
+    #[tokio::test]
+    async fn steady_requests_still_refresh_within_the_limit() {
+        let (task, runs) = counting();
+        let started = Instant::now();
+        let limit = DEBOUNCE * MAX_DEBOUNCE_PERIODS;
+        // Requests closer together than the debounce for three limit periods
+        while started.elapsed() < limit * 3 {
+            task.request();
+            tokio::time::sleep(DEBOUNCE / 3).await;
+        }
+        settle(&task).await;
+
+        let runs = runs.lock().unwrap().clone();
+        assert!(runs.len() >= 2, "{} refreshes", runs.len());
+        assert!(
+            runs[0] - started < limit * 2,
+            "first refresh after {:?}",
+            runs[0] - started
+        );
+    }
+
+    #[tokio::test]
+    async fn settled_waits_only_for_earlier_requests() {
+        let (task, runs) = counting();
+        // Nothing requested yet
+        settle(&task).await;
+        assert!(runs.lock().unwrap().is_empty());
+
+        task.request();
+        settle(&task).await;
+        task.request();
+        settle(&task).await;
+        assert_eq!(runs.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stop_ends_the_task_before_a_pending_refresh() {
+        let (task, runs) = counting();
+        task.request();
+        task.stop();
+        tokio::time::sleep(DEBOUNCE * 3).await;
+        assert!(!task.is_running());
+        assert!(runs.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_refresh_returning_false_ends_the_task() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        let task = RefreshTask::spawn_with(VirtualClock::start().shared(), DEBOUNCE, move || {
+            counted.fetch_add(1, Ordering::SeqCst);
+            async { false }
+        });
+        task.request();
+        // The session is gone, so the refresh never completes but settled resolves
+        settle(&task).await;
+        task.request();
+        tokio::time::sleep(DEBOUNCE * 3).await;
+        assert!(!task.is_running());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
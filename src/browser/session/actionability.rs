@@ -0,0 +1,225 @@
+use crate::browser::actionability::{
+    actionability_script, ActionabilityCheck, ActionabilityReport,
+};
+use crate::browser::state::SessionState;
+use crate::core::BrowserTrait;
+use crate::errors::Result;
+
+use super::{poll_until, BrowserSession};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Click the element produced by a JavaScript expression
+    pub(crate) async fn click_expression(
+        &self,
+        element_expression: &str,
+        target: &str,
+    ) -> Result<()> {
+        let _state = self.enter_state(SessionState::Interacting, "click").await?;
+        let tab = &*self.current_tab()?;
+
+        if self.domain_policy.is_active() {
+            self.check_link_target(element_expression).await?;
+        }
+        self.wait_until_actionable(element_expression, target, false)
+            .await?;
+        self.pace().await;
+
+        let click_script = format!(
+            r#"
+                                                                   (function() {{
+                                                                       const element = {};
+                                                                       if (!element) return {{ success: false, error: 'Element not found' }};
+{}
+                                                                       try {{
+                                                                           element.scrollIntoView({{ behavior: 'instant', block: 'center' }});
+
+                                                                           // Click the first uncovered point, trying the center before the edges
+                                                                           const rect = element.getBoundingClientRect();
+                                                                           const samples = [[0.5, 0.5], [0.25, 0.25], [0.75, 0.25], [0.25, 0.75], [0.75, 0.75],
+                                                                                            [0.5, 0.15], [0.5, 0.85], [0.15, 0.5], [0.85, 0.5]];
+                                                                           let point = null;
+                                                                           let covering = null;
+                                                                           for (const [fx, fy] of samples) {{
+                                                                               const x = rect.left + rect.width * fx;
+                                                                               const y = rect.top + rect.height * fy;
+                                                                               if (x < 0 || y < 0 || x >= window.innerWidth || y >= window.innerHeight) continue;
+                                                                               const top = document.elementFromPoint(x, y);
+                                                                               if (!top || top === element || element.contains(top) || top.contains(element)) {{
+                                                                                   point = {{ x: x, y: y }};
+                                                                                   break;
+                                                                               }}
+                                                                               if (!covering) covering = top;
+                                                                           }}
+                                                                           if (!point && covering) {{
+                                                                               return {{ success: false, occluded: true, covering: cssPath(covering) }};
+                                                                           }}
+                                                                           if (!point) {{
+                                                                               point = {{ x: rect.left + rect.width / 2, y: rect.top + rect.height / 2 }};
+                                                                           }}
+
+                                                                           setTimeout(() => {{
+                                                                               element.focus();
+                                                                               element.click();
+
+                                                                               ['mousedown', 'mouseup', 'click'].forEach(eventType => {{
+                                                                                   const event = new MouseEvent(eventType, {{
+                                                                                       bubbles: true,
+                                                                                       cancelable: true,
+                                                                                       clientX: point.x,
+                                                                                       clientY: point.y
+                                                                                   }});
+                                                                                   element.dispatchEvent(event);
+                                                                               }});
+                                                                           }}, 100);
+
+                                                                           return {{ success: true, elementType: element.tagName.toLowerCase() }};
+                                                                       }} catch (e) {{
+                                                                           return {{ success: false, error: e.message }};
+                                                                       }}
+                                                                   }})()
+                                                                   "#,
+            element_expression,
+            crate::dom::xpath::DESCRIBE_ELEMENT_JS
+        );
+
+        let result = self.browser.execute_script(tab, &click_script).await?;
+
+        if result
+            .get("success")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            println!("✅ Successfully clicked element: {}", target);
+            Ok(())
+        } else if let Some(covering) = result.get("covering").and_then(|v| v.as_str()) {
+            println!("❌ Click failed: {} is covered by {}", target, covering);
+            Err(crate::errors::BrowserAgentError::ElementOccluded {
+                selector: target.to_string(),
+                covering_selector: covering.to_string(),
+            })
+        } else {
+            let error_msg = result
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown error");
+            println!("❌ Click failed: {}", error_msg);
+            Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "Failed to click element {}: {}",
+                target, error_msg
+            )))
+        }
+    }
+
+    /// Run the actionability checks once against the first element matching
+    /// `selector` and report which pass, without waiting or acting
+    pub async fn check_actionable(&self, selector: &str) -> Result<ActionabilityReport> {
+        let expression = format!(
+            "document.querySelector({})",
+            serde_json::to_string(selector)?
+        );
+        self.actionability(&expression, selector, false, true).await
+    }
+
+    pub(super) async fn actionability(
+        &self,
+        element_expression: &str,
+        target: &str,
+        editable: bool,
+        scroll: bool,
+    ) -> Result<ActionabilityReport> {
+        let tab = &*self.current_tab()?;
+        let value = self
+            .browser
+            .execute_script(
+                tab,
+                &actionability_script(element_expression, editable, scroll),
+            )
+            .await?;
+        Ok(ActionabilityReport::from_value(target, &value))
+    }
+
+    /// Wait up to `element_timeout_ms` for the element to pass every actionability
+    /// check, failing with the check that still fails after that. A disabled or
+    /// readonly element fails at once: the page keeps it that way until something
+    /// else is done first.
+    pub(super) async fn wait_until_actionable(
+        &self,
+        element_expression: &str,
+        target: &str,
+        editable: bool,
+    ) -> Result<()> {
+        let last = std::sync::Mutex::new(None);
+        poll_until(self.clock.as_ref(), &self.call_options(), || async {
+            let report = self
+                .actionability(element_expression, target, editable, true)
+                .await?;
+            let settled = report.is_actionable()
+                || report.failure().is_some_and(|failure| {
+                    matches!(
+                        failure.check,
+                        ActionabilityCheck::Enabled | ActionabilityCheck::Editable
+                    )
+                });
+            *last.lock().unwrap() = Some(report);
+            Ok(settled)
+        })
+        .await?;
+
+        let report = last
+            .into_inner()
+            .unwrap()
+            .unwrap_or_else(|| ActionabilityReport {
+                selector: target.to_string(),
+                checks: Vec::new(),
+                covered_by: None,
+            });
+        let Some(failure) = report.failure() else {
+            return Ok(());
+        };
+        println!(
+            "❌ {} is not actionable: {} ({})",
+            target,
+            failure.check,
+            failure.detail.as_deref().unwrap_or("no detail")
+        );
+        match (failure.check, &report.covered_by) {
+            (ActionabilityCheck::ReceivesEvents, Some(covering)) => {
+                Err(crate::errors::BrowserAgentError::ElementOccluded {
+                    selector: target.to_string(),
+                    covering_selector: covering.clone(),
+                })
+            }
+            (ActionabilityCheck::Attached, _) => Err(
+                crate::errors::BrowserAgentError::ElementNotFound(target.to_string()),
+            ),
+            (check, _) => Err(crate::errors::BrowserAgentError::NotActionable {
+                selector: target.to_string(),
+                check: check.to_string(),
+                detail: failure.detail.clone().unwrap_or_default(),
+            }),
+        }
+    }
+
+    /// Refuse to click a link whose resolved href leaves the domain policy
+    async fn check_link_target(&self, element_expression: &str) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        let href_script = format!(
+            r#"
+            (function() {{
+                const element = {};
+                if (!element) return null;
+                const link = element.closest('a[href], area[href]');
+                return link ? link.href : null;
+            }})()
+            "#,
+            element_expression
+        );
+
+        let href = self.browser.execute_script(tab, &href_script).await?;
+        if let Some(href) = href.as_str() {
+            self.domain_policy.check(href)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use crate::browser::csrf::{CsrfManager, CsrfToken};
+use crate::core::{BrowserTrait, Selector, SessionTrait};
+use crate::errors::Result;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Keeps the page's CSRF token; see `current_csrf_header`
+    pub fn csrf(&self) -> &CsrfManager {
+        &self.csrf
+    }
+
+    /// Read the page's CSRF token from the sources in `SessionConfig::csrf`
+    pub async fn refresh_csrf(&self) -> Result<Option<CsrfToken>> {
+        if !self.csrf.enabled() {
+            return Ok(None);
+        }
+        let found = self.execute_script(&self.csrf.read_script()).await?;
+        self.csrf.update(found)
+    }
+
+    /// Header name and value carrying the page's CSRF token, read again so a
+    /// rotated token is picked up; the token from the last read when the page
+    /// cannot be read right now, and `None` when it has none
+    pub async fn current_csrf_header(&self) -> Result<Option<(String, String)>> {
+        let token = match self.refresh_csrf().await {
+            Ok(token) => token,
+            Err(e) => {
+                tracing::debug!(session_id = %self.session_id, %e, "could not read the CSRF token");
+                self.csrf.current()
+            }
+        };
+        Ok(token.map(|token| (token.header, token.value)))
+    }
+
+    /// Put the current CSRF token into the anti-forgery fields of the form
+    /// `selector` is or is in, replacing a value rotated since the page loaded.
+    /// Returns the number of fields changed.
+    pub(super) async fn sync_csrf_fields(&self, selector: &Selector) -> Result<usize> {
+        let Some((_, value)) = self.current_csrf_header().await? else {
+            return Ok(0);
+        };
+        let changed = self
+            .execute_script(
+                &self
+                    .csrf
+                    .form_field_script(&selector.element_expression(), &value),
+            )
+            .await?;
+        Ok(changed.as_u64().unwrap_or(0) as usize)
+    }
+}
@@ -0,0 +1,1251 @@
+use crate::browser::inject::{InjectOptions, InjectReport, MissingCookie, WaitStrategy};
+use crate::browser::navigation::NavigationManager;
+use crate::core::config::TokenProbeOptions;
+use crate::core::{
+    BrowserFeature, BrowserTrait, CallOptions, FrameInfo, FrameTarget, ServiceWorkerInfo,
+    SessionTrait, SiteDataKind,
+};
+use crate::errors::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use tracing::Instrument;
+
+use super::{
+    poll_until, BrowserSession, CookieData, SessionData, SessionMetadata, TokenEntry, TokenSource,
+    ViewportData,
+};
+
+/// Copy of `storage` without `keys`
+fn without_keys(storage: &HashMap<String, String>, keys: &[String]) -> HashMap<String, String> {
+    storage
+        .iter()
+        .filter(|(key, _)| !keys.contains(key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Script collecting token-like storage entries, cookies and meta tags of the frame
+/// it runs in, as `options` describe them. Invalid patterns are ignored. Evaluates to
+/// `{ origin, tokens: [{ name, value, source }], invalid_patterns }`.
+fn auth_tokens_script(options: &TokenProbeOptions) -> Result<String> {
+    Ok(format!(
+        r#"
+        (function() {{
+            const options = {options};
+            const tokens = [];
+            const invalidPatterns = [];
+
+            const tokenKeys = [
+                'access_token', 'accessToken', 'authToken', 'auth_token',
+                'bearer_token', 'bearerToken', 'jwt', 'JWT', 'token',
+                'refresh_token', 'refreshToken', 'id_token', 'idToken',
+                'session_token', 'sessionToken', 'api_key', 'apiKey',
+                'authorization', 'Authorization', 'x-auth-token'
+            ];
+            const exactKeys = new Set(tokenKeys.concat(options.extra_keys));
+            const compile = (patterns) => patterns.flatMap(pattern => {{
+                try {{
+                    return [new RegExp(pattern, 'i')];
+                }} catch (e) {{
+                    invalidPatterns.push(pattern);
+                    return [];
+                }}
+            }});
+            const keyPatterns = compile(options.key_patterns);
+            const cookiePatterns = compile(options.cookie_patterns);
+
+            const jwt = /^eyJ[A-Za-z0-9_-]*\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*$/;
+            const opaque = /^[A-Za-z0-9_\-+/=.~]{{32,}}$/;
+            const looksLikeToken = (value) => options.detect_token_values
+                && (jwt.test(value) || (opaque.test(value) && /[0-9]/.test(value) && /[A-Za-z]/.test(value)));
+            const full = () => tokens.length >= options.max_candidates;
+            const push = (name, value, source) => {{
+                if (!full()) tokens.push({{ name: name, value: value, source: source }});
+            }};
+
+            const probe = (storage, source) => {{
+                for (let i = 0; i < storage.length && !full(); i++) {{
+                    const key = storage.key(i);
+                    const value = storage.getItem(key);
+                    if (!key || !value) continue;
+                    if (exactKeys.has(key) || keyPatterns.some(re => re.test(key)) || looksLikeToken(value)) {{
+                        push(key, value, source);
+                    }}
+                }}
+            }};
+            try {{
+                probe(localStorage, 'local_storage');
+                probe(sessionStorage, 'session_storage');
+            }} catch (e) {{
+                // Storage is unavailable in sandboxed and opaque-origin frames
+            }}
+
+            document.cookie.split(';').forEach(cookie => {{
+                const separator = cookie.indexOf('=');
+                if (separator < 0) return;
+                const name = cookie.slice(0, separator).trim();
+                const value = cookie.slice(separator + 1).trim();
+                if (!name || !value) return;
+                const lower = name.toLowerCase();
+                if (tokenKeys.some(key => lower.includes(key.toLowerCase()))
+                    || cookiePatterns.some(re => re.test(name))
+                    || looksLikeToken(value)) {{
+                    push(name, value, 'cookie');
+                }}
+            }});
+
+            document.querySelectorAll('meta[name*="token"], meta[name*="csrf"]').forEach(meta => {{
+                const name = meta.getAttribute('name');
+                const content = meta.getAttribute('content');
+                if (name && content) push(name, content, 'meta');
+            }});
+
+            return {{ origin: location.origin, tokens: tokens, invalid_patterns: invalidPatterns }};
+        }})()
+        "#,
+        options = serde_json::to_string(options)?
+    ))
+}
+
+/// `TokenEntry`s from an `auth_tokens_script` result
+fn token_entries_from(result: serde_json::Value, frame_path: Vec<String>) -> Vec<TokenEntry> {
+    #[derive(Deserialize)]
+    struct Found {
+        name: String,
+        value: String,
+        source: TokenSource,
+    }
+
+    let origin = result
+        .get("origin")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let found: Vec<Found> = result
+        .get("tokens")
+        .cloned()
+        .and_then(|tokens| serde_json::from_value(tokens).ok())
+        .unwrap_or_default();
+    found
+        .into_iter()
+        .map(|token| TokenEntry {
+            name: token.name,
+            value: token.value,
+            source: token.source,
+            origin: origin.clone(),
+            frame_path: frame_path.clone(),
+        })
+        .collect()
+}
+
+/// URLs of the frames from below the top frame down to `frame`
+fn frame_path(frames: &[FrameInfo], frame: &FrameInfo) -> Vec<String> {
+    let mut path = vec![frame.url.clone()];
+    let mut parent = frame.parent_id.as_deref();
+    while let Some(parent_frame) = parent.and_then(|id| frames.iter().find(|f| f.id == id)) {
+        if parent_frame.is_main_frame() {
+            break;
+        }
+        path.push(parent_frame.url.clone());
+        parent = parent_frame.parent_id.as_deref();
+    }
+    path.reverse();
+    path
+}
+
+/// Script writing storage `entries` into the frame it runs in, provided that frame's
+/// origin is `origin`. Evaluates to `{ success, count }`.
+fn inject_tokens_script(origin: &str, entries: &[&TokenEntry]) -> Result<String> {
+    Ok(format!(
+        r#"
+        (function() {{
+            const origin = {};
+            const entries = {};
+            if (location.origin !== origin) return {{ success: false, count: 0 }};
+            let count = 0;
+            try {{
+                for (const entry of entries) {{
+                    const storage = entry.source === 'session_storage' ? sessionStorage : localStorage;
+                    storage.setItem(entry.name, entry.value);
+                    count++;
+                }}
+                return {{ success: true, count: count }};
+            }} catch (error) {{
+                return {{ success: false, error: error.message, count: count }};
+            }}
+        }})()
+        "#,
+        serde_json::to_string(origin)?,
+        serde_json::to_string(entries)?
+    ))
+}
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    pub async fn extract_session(&self, domain: &str) -> Result<SessionData> {
+        self.extract_session_inner(domain)
+            .instrument(self.span())
+            .await
+    }
+
+    async fn extract_session_inner(&self, domain: &str) -> Result<SessionData> {
+        let tab = &*self.current_tab()?;
+
+        let current_url = self.browser.get_url(tab).await?;
+
+        println!("🔍 Extracting session data for domain: {}", domain);
+
+        let cookies = self.extract_cookies(domain).await?;
+        println!("   Extracted {} cookies", cookies.len());
+
+        let (local_storage, truncated_local_storage) = self.extract_local_storage().await?;
+        println!(
+            "   Extracted {} localStorage items ({} truncated)",
+            local_storage.len(),
+            truncated_local_storage.len()
+        );
+
+        let (session_storage, truncated_session_storage) = self.extract_session_storage().await?;
+        println!(
+            "   Extracted {} sessionStorage items ({} truncated)",
+            session_storage.len(),
+            truncated_session_storage.len()
+        );
+
+        let indexed_db = if self.config.session.storage_extraction.indexed_db_metadata {
+            let databases = self.browser.indexed_db_summary(tab).await?;
+            println!(
+                "   Extracted metadata of {} IndexedDB databases",
+                databases.len()
+            );
+            databases
+        } else {
+            Vec::new()
+        };
+
+        let auth_tokens = self.extract_auth_tokens().await?;
+        println!("   Extracted {} auth tokens", auth_tokens.len());
+
+        let frame_local_storage = if self.config.session.aggregate_frame_storage {
+            let frame_storage = self.extract_frame_local_storage().await?;
+            println!(
+                "   Extracted localStorage from {} frame origins",
+                frame_storage.len()
+            );
+            frame_storage
+        } else {
+            HashMap::new()
+        };
+
+        let csrf_tokens = self.extract_csrf_tokens().await?;
+        println!("   Extracted {} CSRF tokens", csrf_tokens.len());
+
+        let viewport = self.get_viewport_info().await?;
+
+        let user_agent = self.get_user_agent().await?;
+
+        let session_data = SessionData {
+            session_id: self.session_id.clone(),
+            domain: domain.to_string(),
+            url: current_url,
+            cookies,
+            local_storage,
+            session_storage,
+            user_agent: Some(user_agent),
+            viewport: Some(viewport),
+            custom_headers: self.custom_headers.lock().unwrap().clone(),
+            auth_tokens,
+            frame_local_storage,
+            truncated_local_storage,
+            truncated_session_storage,
+            indexed_db,
+            timestamp: chrono::Utc::now(),
+            metadata: SessionMetadata {
+                login_selectors: vec![],
+                success_indicators: vec![],
+                failure_indicators: vec![],
+                csrf_tokens,
+                form_data: HashMap::new(),
+            },
+        };
+
+        let previous = self
+            .current_session_data
+            .write()
+            .await
+            .replace(session_data.clone());
+        if let Some(previous) = previous {
+            let diff = previous.diff(&session_data);
+            tracing::debug!(
+                session_id = %self.session_id,
+                summary = %diff.summary(),
+                "session data changed since the last extraction:\n{}",
+                diff
+            );
+        }
+        println!("✅ Session extraction completed");
+
+        Ok(session_data)
+    }
+
+    /// Write `session_data` into the browser, reload the page and report what could
+    /// not be read back
+    pub async fn inject_session(&self, session_data: SessionData) -> Result<InjectReport> {
+        self.inject_session_with_options(session_data, &InjectOptions::default())
+            .await
+    }
+
+    /// `inject_session` reloading and waiting as `options` say
+    pub async fn inject_session_with_options(
+        &self,
+        session_data: SessionData,
+        options: &InjectOptions,
+    ) -> Result<InjectReport> {
+        self.inject_session_inner(session_data, options)
+            .instrument(self.span())
+            .await
+    }
+
+    async fn inject_session_inner(
+        &self,
+        session_data: SessionData,
+        options: &InjectOptions,
+    ) -> Result<InjectReport> {
+        println!(
+            "💉 Injecting session data for domain: {}",
+            session_data.domain
+        );
+
+        let current_url = {
+            let tab = &*self.current_tab()?;
+            self.browser.get_url(tab).await?
+        };
+
+        if !current_url.contains(&session_data.domain) {
+            let domain_url = if session_data.domain.starts_with("http") {
+                session_data.domain.clone()
+            } else {
+                format!("https://{}", session_data.domain)
+            };
+            self.navigate_and_wait_reactive(&domain_url).await?;
+        }
+
+        let mut report = InjectReport::default();
+
+        self.inject_cookies(&session_data.cookies).await?;
+        report.cookies_injected = session_data.cookies.len();
+        println!("   Injected {} cookies", session_data.cookies.len());
+
+        // Truncated values would overwrite good data with a cut-off copy
+        let local_storage = without_keys(
+            &session_data.local_storage,
+            &session_data.truncated_local_storage,
+        );
+        self.inject_local_storage(&local_storage).await?;
+        report.local_storage_injected = local_storage.len();
+        println!(
+            "   Injected {} localStorage items ({} truncated skipped)",
+            local_storage.len(),
+            session_data.local_storage.len() - local_storage.len()
+        );
+
+        let session_storage = without_keys(
+            &session_data.session_storage,
+            &session_data.truncated_session_storage,
+        );
+        self.inject_session_storage(&session_storage).await?;
+        report.session_storage_injected = session_storage.len();
+        println!(
+            "   Injected {} sessionStorage items ({} truncated skipped)",
+            session_storage.len(),
+            session_data.session_storage.len() - session_storage.len()
+        );
+
+        if !session_data.custom_headers.is_empty() {
+            self.set_custom_headers(&session_data.custom_headers)
+                .await?;
+            println!(
+                "   Set {} custom headers",
+                session_data.custom_headers.len()
+            );
+        }
+
+        let fallback_origin = url::Url::parse(&session_data.url)
+            .map(|url| url.origin().ascii_serialization())
+            .unwrap_or_default();
+        report.auth_tokens_injected = self
+            .inject_auth_tokens(&session_data.auth_tokens, &fallback_origin)
+            .await?;
+        println!(
+            "   Injected {} of {} auth tokens",
+            report.auth_tokens_injected,
+            session_data.auth_tokens.len()
+        );
+        report.truncated_skipped = session_data.local_storage.len() - local_storage.len()
+            + session_data.session_storage.len()
+            - session_storage.len();
+
+        if let Some(viewport) = &session_data.viewport {
+            self.set_viewport(viewport).await?;
+        }
+
+        if options.reload {
+            let call_options = match options.timeout_ms {
+                Some(timeout_ms) => self.load_options().with_timeout_ms(timeout_ms),
+                None => self.load_options(),
+            };
+            report.reloaded = true;
+            report.reload_completed = self.reload_and_wait(options.wait, &call_options).await?;
+            if !report.reload_completed {
+                println!(
+                    "⚠️ Page did not finish reloading within {}ms",
+                    call_options.timeout_ms
+                );
+            }
+        }
+
+        // Only wait strategies that saw the new document can read its state reliably
+        if !options.reload || options.wait != WaitStrategy::Immediate {
+            self.verify_injection(&session_data, &local_storage, &session_storage, &mut report)
+                .await?;
+        }
+
+        *self.current_session_data.write().await = Some(session_data);
+        if report.is_complete() {
+            println!("✅ Session injection completed");
+        } else {
+            println!(
+                "⚠️ Session injection incomplete: {} cookies, {} localStorage and {} sessionStorage items did not stick",
+                report.missing_cookies.len(),
+                report.missing_local_storage.len(),
+                report.missing_session_storage.len()
+            );
+            for cookie in &report.missing_cookies {
+                println!("   Cookie {}: {}", cookie.name, cookie.reason);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Read cookies and web storage back and record in `report` what is missing
+    async fn verify_injection(
+        &self,
+        session_data: &SessionData,
+        local_storage: &HashMap<String, String>,
+        session_storage: &HashMap<String, String>,
+        report: &mut InjectReport,
+    ) -> Result<()> {
+        let url = url::Url::parse(&self.current_url().await?)
+            .map_err(|e| crate::errors::BrowserAgentError::NavigationFailed(e.to_string()))?;
+
+        let present = self
+            .extract_cookies(url.host_str().unwrap_or_default())
+            .await?;
+        report.missing_cookies = session_data
+            .cookies
+            .iter()
+            .filter_map(|cookie| {
+                let found = present.iter().find(|other| other.name == cookie.name);
+                match found {
+                    Some(other) if other.value == cookie.value => None,
+                    _ => Some(MissingCookie::diagnose(
+                        cookie,
+                        &url,
+                        found.map(|other| other.value.as_str()),
+                    )),
+                }
+            })
+            .collect();
+
+        let missing = |written: &HashMap<String, String>, read: &HashMap<String, String>| {
+            let mut keys: Vec<String> = written
+                .iter()
+                .filter(|(key, value)| read.get(*key) != Some(*value))
+                .map(|(key, _)| key.clone())
+                .collect();
+            keys.sort();
+            keys
+        };
+        // Values read back may be cut at the configured length; those count as present
+        let (read_local, truncated_local) = self.extract_local_storage().await?;
+        report.missing_local_storage = missing(local_storage, &read_local)
+            .into_iter()
+            .filter(|key| !truncated_local.contains(key))
+            .collect();
+        let (read_session, truncated_session) = self.extract_session_storage().await?;
+        report.missing_session_storage = missing(session_storage, &read_session)
+            .into_iter()
+            .filter(|key| !truncated_session.contains(key))
+            .collect();
+        Ok(())
+    }
+
+    /// Reload the page and wait for it as `wait` says. A marker left on the old
+    /// document tells the two apart. Returns whether the wait finished in time.
+    async fn reload_and_wait(&self, wait: WaitStrategy, options: &CallOptions) -> Result<bool> {
+        let tab = &*self.current_tab()?;
+        self.browser
+            .execute_script(
+                tab,
+                "window.__surfaiBeforeReload = true; window.location.reload(); true",
+            )
+            .await?;
+        self.reloads.fetch_add(1, Ordering::Relaxed);
+        if wait == WaitStrategy::Immediate {
+            return Ok(true);
+        }
+
+        let started = self.clock.now();
+        let loaded = poll_until(self.clock.as_ref(), options, || async {
+            // Evaluation can fail while the documents are swapped; that means "not yet"
+            Ok(self
+                .browser
+                .execute_script(
+                    tab,
+                    "!window.__surfaiBeforeReload && document.readyState === 'complete'",
+                )
+                .await
+                .ok()
+                .and_then(|loaded| loaded.as_bool())
+                .unwrap_or(false))
+        })
+        .await?;
+        if !loaded || wait == WaitStrategy::DocumentLoad {
+            return Ok(loaded);
+        }
+
+        let remaining = options
+            .timeout_ms
+            .saturating_sub(self.clock.now().duration_since(started).as_millis() as u64);
+        let navigation = NavigationManager::wait_for_navigation_complete(
+            self.browser.as_ref(),
+            tab,
+            self.clock.as_ref(),
+            remaining,
+        )
+        .await?;
+        Ok(navigation.success)
+    }
+
+    pub async fn delete_session(&self) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        println!("🗑️ Deleting session data");
+
+        self.clear_all_cookies().await?;
+
+        // sessionStorage is per tab and not covered by site data clearing
+        let clear_storage_script = r#"
+            (async function() {
+                try {
+                    localStorage.clear();
+                    sessionStorage.clear();
+                } catch (error) {
+                    return { success: false, error: error.message };
+                }
+                return { success: true };
+            })()
+        "#;
+        let result = self
+            .browser
+            .execute_script(tab, clear_storage_script)
+            .await?;
+        println!("   Web storage clear result: {:?}", result);
+
+        let origin = url::Url::parse(&self.browser.get_url(tab).await?)
+            .ok()
+            .filter(|url| url.origin().is_tuple())
+            .map(|url| url.origin().ascii_serialization());
+        let cleared = match &origin {
+            Some(origin) if self.supports(BrowserFeature::CdpCookies, "page script deletion") => {
+                self.browser
+                    .clear_site_data(tab, origin, &[SiteDataKind::All])
+                    .await?
+            }
+            _ => false,
+        };
+
+        if cleared {
+            println!(
+                "   Cleared site data for {}",
+                origin.as_deref().unwrap_or_default()
+            );
+        } else {
+            // No protocol support: wait for the page's own deletion promises instead
+            let fallback_script = r#"
+                (async function() {
+                    const cleared = { databases: 0, caches: 0, serviceWorkers: 0 };
+                    if (window.indexedDB && indexedDB.databases) {
+                        for (const db of await indexedDB.databases()) {
+                            await new Promise(resolve => {
+                                const request = indexedDB.deleteDatabase(db.name);
+                                request.onsuccess = request.onerror = request.onblocked = resolve;
+                            });
+                            cleared.databases++;
+                        }
+                    }
+                    if ('caches' in window) {
+                        for (const name of await caches.keys()) {
+                            if (await caches.delete(name)) cleared.caches++;
+                        }
+                    }
+                    if (navigator.serviceWorker) {
+                        for (const registration of await navigator.serviceWorker.getRegistrations()) {
+                            if (await registration.unregister()) cleared.serviceWorkers++;
+                        }
+                    }
+                    return cleared;
+                })()
+            "#;
+            let result = self.browser.execute_script(tab, fallback_script).await?;
+            println!("   Storage clear result: {:?}", result);
+        }
+
+        *self.current_session_data.write().await = None;
+        println!("✅ Session deletion completed");
+
+        Ok(())
+    }
+
+    /// Service worker registrations known to the browser for this tab
+    pub async fn list_service_workers(&self) -> Result<Vec<ServiceWorkerInfo>> {
+        let tab = &*self.current_tab()?;
+        self.browser.list_service_workers(tab).await
+    }
+
+    /// Unregister all service workers, returning how many were removed
+    pub async fn unregister_service_workers(&self) -> Result<usize> {
+        let tab = &*self.current_tab()?;
+        let count = self.browser.unregister_service_workers(tab).await?;
+        println!("🧹 Unregistered {} service worker(s)", count);
+        Ok(count)
+    }
+
+    /// Clear the given kinds of stored data for `origin` (e.g.
+    /// `https://example.com`), waiting for the browser to finish
+    pub async fn clear_site_data(&self, origin: &str, kinds: &[SiteDataKind]) -> Result<()> {
+        let tab = &*self.current_tab()?;
+        self.require(BrowserFeature::CdpCookies)?;
+        if !self.browser.clear_site_data(tab, origin, kinds).await? {
+            return Err(self.unavailable(BrowserFeature::CdpCookies));
+        }
+        println!("🧹 Cleared {:?} for {}", kinds, origin);
+        Ok(())
+    }
+
+    pub async fn validate_session(&self, success_indicators: &[String]) -> Result<bool> {
+        let tab = &*self.current_tab()?;
+
+        if success_indicators.is_empty() {
+            return Ok(true);
+        }
+
+        let validation_script = format!(
+            r#"
+            (function() {{
+                const indicators = {};
+                let validCount = 0;
+
+                for (const indicator of indicators) {{
+                    try {{
+                        if (document.querySelector(indicator)) {{
+                            validCount++;
+                            continue;
+                        }}
+                    }} catch (e) {{}}
+
+                    if (document.body.textContent.includes(indicator)) {{
+                        validCount++;
+                        continue;
+                    }}
+
+                    if (localStorage.getItem(indicator)) {{
+                        validCount++;
+                        continue;
+                    }}
+
+                    if (document.cookie.includes(indicator)) {{
+                        validCount++;
+                        continue;
+                    }}
+                }}
+
+                return {{
+                    valid: validCount > 0,
+                    validCount: validCount,
+                    totalIndicators: indicators.length
+                }};
+            }})()
+        "#,
+            serde_json::to_string(success_indicators)?
+        );
+
+        let result = self.browser.execute_script(tab, &validation_script).await?;
+        let is_valid = result
+            .get("valid")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        Ok(is_valid)
+    }
+
+    async fn extract_cookies(&self, domain: &str) -> Result<Vec<CookieData>> {
+        let tab = &*self.current_tab()?;
+
+        let cookie_script = r#"
+            (function() {
+                const cookies = [];
+                document.cookie.split(';').forEach(cookie => {
+                    const [name, value] = cookie.trim().split('=');
+                    if (name && value) {
+                        cookies.push({
+                            name: name.trim(),
+                            value: value.trim(),
+                            domain: window.location.hostname,
+                            path: '/',
+                            httpOnly: false,
+                            secure: window.location.protocol === 'https:',
+                            sameSite: null
+                        });
+                    }
+                });
+                return cookies;
+            })()
+        "#;
+
+        let result = self.browser.execute_script(tab, cookie_script).await?;
+        let cookies: Vec<CookieData> = serde_json::from_value(result)?;
+        Ok(cookies)
+    }
+
+    async fn extract_local_storage(&self) -> Result<(HashMap<String, String>, Vec<String>)> {
+        let tab = &*self.current_tab()?;
+
+        let script = self.storage_script("localStorage");
+        let result = self.browser.execute_script(tab, &script).await?;
+        self.filter_storage(result)
+    }
+
+    /// Script reading `storage`, cutting values at the configured length so large
+    /// blobs never cross the script bridge
+    fn storage_script(&self, storage: &str) -> String {
+        format!(
+            r#"
+            (function() {{
+                const max = {max};
+                const items = {{}};
+                const truncated = [];
+                try {{
+                    for (let i = 0; i < {storage}.length; i++) {{
+                        const key = {storage}.key(i);
+                        if (!key) continue;
+                        const value = {storage}.getItem(key) || '';
+                        if (value.length > max) {{
+                            items[key] = value.slice(0, max) + '…[truncated ' + value.length + ' chars]';
+                            truncated.push(key);
+                        }} else {{
+                            items[key] = value;
+                        }}
+                    }}
+                }} catch (e) {{}}
+                return {{ items: items, truncated: truncated }};
+            }})()
+            "#,
+            storage = storage,
+            max = self.config.session.storage_extraction.max_value_length
+        )
+    }
+
+    /// Apply the key include/exclude globs to the result of `storage_script`
+    fn filter_storage(
+        &self,
+        result: serde_json::Value,
+    ) -> Result<(HashMap<String, String>, Vec<String>)> {
+        let options = &self.config.session.storage_extraction;
+        let items: HashMap<String, String> = result
+            .get("items")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default();
+        let truncated: Vec<String> = result
+            .get("truncated")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default();
+
+        let items: HashMap<String, String> = items
+            .into_iter()
+            .filter(|(key, _)| options.keeps_key(key))
+            .collect();
+        let truncated = truncated
+            .into_iter()
+            .filter(|key| items.contains_key(key))
+            .collect();
+        Ok((items, truncated))
+    }
+
+    /// Collect localStorage of every child frame whose origin differs from the top frame
+    async fn extract_frame_local_storage(
+        &self,
+    ) -> Result<HashMap<String, HashMap<String, String>>> {
+        let frames = self.list_frames().await?;
+        let top_origin = frames
+            .iter()
+            .find(|f| f.is_main_frame())
+            .map(|f| f.origin.clone())
+            .unwrap_or_default();
+
+        let script = self.storage_script("localStorage");
+
+        let mut by_origin = HashMap::new();
+        for frame in frames {
+            if frame.is_main_frame()
+                || frame.origin == top_origin
+                || frame.origin.is_empty()
+                || frame.origin == "null"
+                || by_origin.contains_key(&frame.origin)
+            {
+                continue;
+            }
+
+            match self
+                .execute_script_in_frame(FrameTarget::Id(frame.id.clone()), &script)
+                .await
+            {
+                Ok(result) => {
+                    let (storage, _) = self.filter_storage(result).unwrap_or_default();
+                    if !storage.is_empty() {
+                        by_origin.insert(frame.origin.clone(), storage);
+                    }
+                }
+                Err(e) => println!("⚠️ Could not read storage of frame {}: {}", frame.url, e),
+            }
+        }
+
+        Ok(by_origin)
+    }
+
+    async fn extract_session_storage(&self) -> Result<(HashMap<String, String>, Vec<String>)> {
+        let tab = &*self.current_tab()?;
+
+        let script = self.storage_script("sessionStorage");
+        let result = self.browser.execute_script(tab, &script).await?;
+        self.filter_storage(result)
+    }
+
+    /// Token-like values of the top frame and, with `aggregate_frame_storage`, of
+    /// every child frame, each tagged with its frame's origin
+    async fn extract_auth_tokens(&self) -> Result<Vec<TokenEntry>> {
+        let tab = &*self.current_tab()?;
+
+        let script = auth_tokens_script(&self.config.session.token_probe)?;
+        let result = self.browser.execute_script(tab, &script).await?;
+        if let Some(invalid) = result
+            .get("invalid_patterns")
+            .and_then(|v| v.as_array())
+            .filter(|invalid| !invalid.is_empty())
+        {
+            println!("⚠️ Ignored invalid token patterns: {:?}", invalid);
+        }
+        let mut entries = token_entries_from(result, Vec::new());
+
+        if self.config.session.aggregate_frame_storage {
+            let frames = self.list_frames().await?;
+            let top_origin = frames
+                .iter()
+                .find(|frame| frame.is_main_frame())
+                .map(|frame| frame.origin.clone())
+                .unwrap_or_default();
+            // Same-origin frames share the top frame's storage
+            for frame in frames
+                .iter()
+                .filter(|frame| !frame.is_main_frame() && frame.origin != top_origin)
+            {
+                match self
+                    .execute_script_in_frame(FrameTarget::Id(frame.id.clone()), &script)
+                    .await
+                {
+                    Ok(result) => {
+                        entries.extend(token_entries_from(result, frame_path(&frames, frame)))
+                    }
+                    Err(e) => println!("⚠️ Could not read tokens of frame {}: {}", frame.url, e),
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    async fn extract_csrf_tokens(&self) -> Result<HashMap<String, String>> {
+        let tab = &*self.current_tab()?;
+
+        let script = r#"
+            (function() {
+                const tokens = {};
+
+                const metaTags = document.querySelectorAll('meta[name*="csrf"], meta[name*="token"]');
+                metaTags.forEach(meta => {
+                    const name = meta.getAttribute('name');
+                    const content = meta.getAttribute('content');
+                    if (name && content) {
+                        tokens[name] = content;
+                    }
+                });
+
+                const hiddenInputs = document.querySelectorAll('input[type="hidden"][name*="csrf"], input[type="hidden"][name*="token"]');
+                hiddenInputs.forEach(input => {
+                    const name = input.getAttribute('name');
+                    const value = input.getAttribute('value');
+                    if (name && value) {
+                        tokens[name] = value;
+                    }
+                });
+
+                const csrfKeys = ['csrf_token', 'csrfToken', '_token', 'authenticity_token'];
+                csrfKeys.forEach(key => {
+                    const value = localStorage.getItem(key);
+                    if (value) tokens[key] = value;
+                });
+
+                return tokens;
+            })()
+        "#;
+
+        let result = self.browser.execute_script(tab, script).await?;
+        let tokens: HashMap<String, String> = serde_json::from_value(result)?;
+        Ok(tokens)
+    }
+
+    async fn get_viewport_info(&self) -> Result<ViewportData> {
+        let tab = &*self.current_tab()?;
+
+        let script = r#"
+            (function() {
+                return {
+                    width: window.innerWidth,
+                    height: window.innerHeight,
+                    deviceScaleFactor: window.devicePixelRatio || 1
+                };
+            })()
+        "#;
+
+        let result = self.browser.execute_script(tab, script).await?;
+        let viewport: ViewportData = serde_json::from_value(result)?;
+        Ok(viewport)
+    }
+
+    async fn get_user_agent(&self) -> Result<String> {
+        let tab = &*self.current_tab()?;
+
+        let result = self
+            .browser
+            .execute_script(tab, "navigator.userAgent")
+            .await?;
+        Ok(result.as_str().unwrap_or("").to_string())
+    }
+
+    async fn inject_cookies(&self, cookies: &[CookieData]) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        for cookie in cookies {
+            let cookie_script = format!(
+                r#"
+                (function() {{
+                    let cookieString = '{}={}; path={}';
+
+                    if ('{}' !== 'null') {{
+                        const expires = new Date({} * 1000);
+                        cookieString += '; expires=' + expires.toUTCString();
+                    }}
+
+                    if ({}) {{
+                        cookieString += '; secure';
+                    }}
+
+                    if ('{}' !== 'null') {{
+                        cookieString += '; samesite={}';
+                    }}
+
+                    document.cookie = cookieString;
+                    return {{ success: true, cookie: cookieString }};
+                }})()
+            "#,
+                cookie.name,
+                cookie.value,
+                cookie.path,
+                cookie
+                    .expires
+                    .map(|e| e.to_string())
+                    .unwrap_or_else(|| "null".to_string()),
+                cookie.expires.unwrap_or(0),
+                cookie.secure,
+                cookie.same_site.as_ref().unwrap_or(&"null".to_string()),
+                cookie.same_site.as_ref().unwrap_or(&"".to_string())
+            );
+
+            self.browser.execute_script(tab, &cookie_script).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn inject_local_storage(&self, storage: &HashMap<String, String>) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        let script = format!(
+            r#"
+            (function() {{
+                const storage = {};
+                let count = 0;
+                try {{
+                    for (const [key, value] of Object.entries(storage)) {{
+                        localStorage.setItem(key, value);
+                        count++;
+                    }}
+                    return {{ success: true, count: count }};
+                }} catch (error) {{
+                    return {{ success: false, error: error.message, count: count }};
+                }}
+            }})()
+        "#,
+            serde_json::to_string(storage)?
+        );
+
+        self.browser.execute_script(tab, &script).await?;
+        Ok(())
+    }
+
+    async fn inject_session_storage(&self, storage: &HashMap<String, String>) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        let script = format!(
+            r#"
+            (function() {{
+                const storage = {};
+                let count = 0;
+                try {{
+                    for (const [key, value] of Object.entries(storage)) {{
+                        sessionStorage.setItem(key, value);
+                        count++;
+                    }}
+                    return {{ success: true, count: count }};
+                }} catch (error) {{
+                    return {{ success: false, error: error.message, count: count }};
+                }}
+            }})()
+        "#,
+            serde_json::to_string(storage)?
+        );
+
+        self.browser.execute_script(tab, &script).await?;
+        Ok(())
+    }
+
+    /// Write storage tokens back into the frames of their origin.
+    ///
+    /// Entries without a recorded origin are taken to belong to `fallback_origin`.
+    /// Entries whose origin has no frame on the current page are skipped and
+    /// summarized; cookie and meta entries are never written. Returns how many
+    /// entries were written.
+    async fn inject_auth_tokens(
+        &self,
+        tokens: &[TokenEntry],
+        fallback_origin: &str,
+    ) -> Result<usize> {
+        let tab = &*self.current_tab()?;
+
+        let storable: Vec<&TokenEntry> = tokens
+            .iter()
+            .filter(|entry| {
+                matches!(
+                    entry.source,
+                    TokenSource::LocalStorage | TokenSource::SessionStorage
+                )
+            })
+            .collect();
+        if storable.is_empty() {
+            return Ok(0);
+        }
+
+        let frames = self.list_frames().await?;
+        let top_origin = frames
+            .iter()
+            .find(|frame| frame.is_main_frame())
+            .map(|frame| frame.origin.clone())
+            .unwrap_or_default();
+
+        let mut by_origin: HashMap<&str, Vec<&TokenEntry>> = HashMap::new();
+        for entry in storable {
+            let origin = if entry.origin.is_empty() {
+                fallback_origin
+            } else {
+                entry.origin.as_str()
+            };
+            by_origin.entry(origin).or_default().push(entry);
+        }
+
+        let mut written = 0;
+        let mut skipped: Vec<String> = Vec::new();
+        for (origin, entries) in by_origin {
+            let script = inject_tokens_script(origin, &entries)?;
+            let result = if origin == top_origin {
+                self.browser.execute_script(tab, &script).await
+            } else if let Some(frame) = frames
+                .iter()
+                .find(|frame| !frame.is_main_frame() && frame.origin == origin)
+            {
+                self.execute_script_in_frame(FrameTarget::Id(frame.id.clone()), &script)
+                    .await
+            } else {
+                skipped.push(format!("{} from {}", entries.len(), origin));
+                continue;
+            };
+
+            match result {
+                Ok(value) => {
+                    written += value.get("count").and_then(|v| v.as_u64()).unwrap_or(0) as usize
+                }
+                Err(e) => skipped.push(format!("{} from {} ({})", entries.len(), origin, e)),
+            }
+        }
+
+        if !skipped.is_empty() {
+            println!(
+                "⚠️ Skipped auth tokens with no frame of their origin on {}: {}",
+                top_origin,
+                skipped.join(", ")
+            );
+        }
+        Ok(written)
+    }
+
+    async fn clear_all_cookies(&self) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        let script = r#"
+            (function() {
+                const cookies = document.cookie.split(';');
+                let clearedCount = 0;
+
+                cookies.forEach(cookie => {
+                    const name = cookie.split('=')[0].trim();
+                    if (name) {
+                        document.cookie = name + '=; expires=Thu, 01 Jan 1970 00:00:00 UTC; path=/;';
+                        document.cookie = name + '=; expires=Thu, 01 Jan 1970 00:00:00 UTC; path=/; domain=' + window.location.hostname + ';';
+                        document.cookie = name + '=; expires=Thu, 01 Jan 1970 00:00:00 UTC; path=/; domain=.' + window.location.hostname + ';';
+                        clearedCount++;
+                    }
+                });
+
+                return { success: true, clearedCount: clearedCount };
+            })()
+        "#;
+
+        self.browser.execute_script(tab, script).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(id: &str, parent_id: Option<&str>, url: &str) -> FrameInfo {
+        FrameInfo {
+            id: id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            url: url.to_string(),
+            origin: url::Url::parse(url).unwrap().origin().ascii_serialization(),
+            name: None,
+        }
+    }
+
+    #[test]
+    fn probe_results_are_tagged_with_their_frame_origin() {
+        let result = serde_json::json!({
+            "origin": "http://localhost:8080",
+            "tokens": [
+                { "name": "access_token", "value": "frame-access", "source": "local_storage" },
+                { "name": "id_token", "value": "frame-id", "source": "session_storage" },
+            ],
+            "invalid_patterns": [],
+        });
+        let path = vec!["http://localhost:8080/auth-tokens/frame".to_string()];
+        let entries = token_entries_from(result, path.clone());
+        assert_eq!(
+            entries,
+            [
+                TokenEntry {
+                    name: "access_token".to_string(),
+                    value: "frame-access".to_string(),
+                    source: TokenSource::LocalStorage,
+                    origin: "http://localhost:8080".to_string(),
+                    frame_path: path.clone(),
+                },
+                TokenEntry {
+                    name: "id_token".to_string(),
+                    value: "frame-id".to_string(),
+                    source: TokenSource::SessionStorage,
+                    origin: "http://localhost:8080".to_string(),
+                    frame_path: path,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn frame_paths_stop_below_the_top_frame() {
+        let frames = [
+            frame("top", None, "http://127.0.0.1:8080/auth-tokens"),
+            frame("outer", Some("top"), "http://localhost:8080/outer"),
+            frame("inner", Some("outer"), "http://localhost:8080/inner"),
+        ];
+        assert_eq!(
+            frame_path(&frames, &frames[2]),
+            ["http://localhost:8080/outer", "http://localhost:8080/inner"]
+        );
+        assert_eq!(
+            frame_path(&frames, &frames[1]),
+            ["http://localhost:8080/outer"]
+        );
+    }
+
+    #[test]
+    fn probe_script_carries_the_options() {
+        let options = TokenProbeOptions {
+            extra_keys: vec!["app.session.v2".to_string()],
+            key_patterns: vec!["^app\\.".to_string(), "(".to_string()],
+            cookie_patterns: vec!["^sid$".to_string()],
+            detect_token_values: true,
+            max_candidates: 3,
+        };
+        let script = auth_tokens_script(&options).unwrap();
+        assert!(script.contains(&format!(
+            "const options = {};",
+            serde_json::to_string(&options).unwrap()
+        )));
+        // Patterns are compiled in the page, so an invalid one is reported there
+        assert!(script.contains("invalidPatterns.push(pattern)"));
+        assert!(script.contains("invalid_patterns: invalidPatterns"));
+    }
+
+    #[test]
+    fn injected_tokens_only_land_in_their_origin() {
+        let entry = TokenEntry {
+            name: "access_token".to_string(),
+            value: "it's".to_string(),
+            source: TokenSource::LocalStorage,
+            origin: "http://localhost:8080".to_string(),
+            frame_path: Vec::new(),
+        };
+        let script = inject_tokens_script("http://localhost:8080", &[&entry]).unwrap();
+        assert!(
+            script.contains("if (location.origin !== origin) return { success: false, count: 0 };")
+        );
+        assert!(script.contains(r#"const origin = "http://localhost:8080";"#));
+        assert!(script.contains(&serde_json::to_string(&[&entry]).unwrap()));
+    }
+}
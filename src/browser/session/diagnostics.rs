@@ -0,0 +1,207 @@
+use crate::browser::element_monitor::{
+    DomChangeEvent, MonitorStats, MutationActivity, DEFAULT_ACTIVITY_WINDOW,
+};
+use crate::core::{
+    BrowserFeature, BrowserTrait, ConsoleLevel, ConsoleMessage, JsCoverage, JsCoverageOptions,
+};
+use crate::dom::report::{PageReport, PageStats};
+use crate::errors::Result;
+
+use super::{
+    approx_bytes, page_language, BrowserSession, ElementHighlight, MemoryStats, PAGE_LANGUAGE_JS,
+};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Counts, metadata, timings and content measures for the current page.
+    ///
+    /// Extracts the page state for the counts; forms and iframes are counted over
+    /// the whole document.
+    pub async fn analyze_page(&self) -> Result<PageReport> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        let mut report = self.analyze_tab(tab, self.console_capture).await?;
+        report.dom_activity = Some(self.dom_activity().await?);
+        Ok(report)
+    }
+
+    /// DOM mutations in the session's tab over the last five seconds, with a hint
+    /// to wait when the page is changing heavily
+    pub async fn dom_activity(&self) -> Result<MutationActivity> {
+        let tab = &*self.current_tab()?;
+        self.element_monitor
+            .sample(self.browser.as_ref(), tab)
+            .await?;
+        Ok(self.element_monitor.activity(DEFAULT_ACTIVITY_WINDOW))
+    }
+
+    /// Mutations recorded in the session's tabs, oldest first; kept across
+    /// navigations and highlight refreshes
+    pub fn dom_change_history(&self) -> Vec<DomChangeEvent> {
+        self.element_monitor.change_history()
+    }
+
+    /// Counters behind `dom_change_history`
+    pub fn dom_change_stats(&self) -> MonitorStats {
+        self.element_monitor.stats()
+    }
+
+    /// Zero the DOM change counters and forget the history
+    pub fn reset_dom_change_stats(&self) {
+        self.element_monitor.reset_stats();
+    }
+
+    /// `analyze_page` for any tab of the session's browser
+    pub(super) async fn analyze_tab(
+        &self,
+        tab: &B::TabHandle,
+        console_capture: bool,
+    ) -> Result<PageReport> {
+        let state = self
+            .dom_processor
+            .extract_dom_state(self.browser.as_ref(), tab, false)
+            .await?;
+        let details = self
+            .browser
+            .execute_script(tab, crate::dom::report::page_report_script())
+            .await?;
+
+        let mut stats = PageStats::from_state(&state);
+        let count = |key: &str| details.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        stats.forms = count("forms");
+        stats.iframes = count("iframes");
+        let field = |key: &str| details.get(key).cloned().unwrap_or_default();
+
+        let console_errors = if console_capture {
+            Some(
+                self.browser
+                    .console_messages(tab)
+                    .await?
+                    .iter()
+                    .filter(|message| message.level == ConsoleLevel::Error)
+                    .count(),
+            )
+        } else {
+            None
+        };
+
+        let report = PageReport {
+            stats,
+            metadata: serde_json::from_value(field("metadata")).unwrap_or_default(),
+            performance: serde_json::from_value(field("performance")).unwrap_or_default(),
+            console_errors,
+            largest_image: serde_json::from_value(field("largest_image")).unwrap_or(None),
+            word_count: count("word_count"),
+            language: page_language(&self.browser.execute_script(tab, PAGE_LANGUAGE_JS).await?),
+            extraction: state.stats.clone(),
+            dom_activity: None,
+            generated_at: chrono::Utc::now(),
+        };
+        println!(
+            "📊 {}: {} elements, {} words, {} forms",
+            report.metadata.url, report.stats.total_elements, report.word_count, report.stats.forms
+        );
+        Ok(report)
+    }
+
+    /// Console messages and uncaught exceptions since the session was created
+    pub async fn console_messages(&self) -> Result<Vec<ConsoleMessage>> {
+        if !self.console_capture {
+            return Err(crate::errors::BrowserAgentError::ConfigurationError(
+                "console capture is not supported by this browser".to_string(),
+            ));
+        }
+        let tab = &*self.current_tab()?;
+        self.browser.console_messages(tab).await
+    }
+
+    /// Entry counts and approximate size of what the session keeps in memory, to
+    /// check long runs against `session.change_history_len`,
+    /// `session.prefetch_limit` and `session.prefetch_max_bytes`
+    pub async fn memory_stats(&self) -> MemoryStats {
+        let highlights = self.element_highlights.read().await;
+        let ai_elements = self.ai_elements.read().await;
+        let history = self.element_monitor.change_history();
+        let prefetched = self.prefetched.lock().await;
+        let console_messages = match self.current_tab() {
+            Ok(tab) => self
+                .browser
+                .console_messages(&tab)
+                .await
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+
+        let highlight_bytes: usize = highlights
+            .iter()
+            .map(|h| {
+                std::mem::size_of::<ElementHighlight>()
+                    + h.element_id.len()
+                    + h.color.len()
+                    + h.element_type.len()
+                    + h.css_selector.len()
+            })
+            .sum();
+        MemoryStats {
+            highlights: highlights.len(),
+            ai_elements: ai_elements.len(),
+            change_events: history.len(),
+            prefetched_pages: prefetched.len(),
+            prefetched_bytes: prefetched.bytes(),
+            console_messages: console_messages.len(),
+            approx_bytes: highlight_bytes
+                + approx_bytes(&*ai_elements)
+                + approx_bytes(&history)
+                + prefetched.bytes()
+                + approx_bytes(&console_messages),
+        }
+    }
+
+    /// Start recording which parts of the page's scripts run. Start before
+    /// navigating to see a page's own scripts from their first statement.
+    pub async fn start_js_coverage(&self) -> Result<()> {
+        self.start_js_coverage_with_options(JsCoverageOptions::default())
+            .await
+    }
+
+    /// `start_js_coverage`, also keeping unused spans or scripts without a URL
+    /// as `options` says
+    pub async fn start_js_coverage_with_options(&self, options: JsCoverageOptions) -> Result<()> {
+        self.ensure_open()?;
+        self.require(BrowserFeature::JsCoverage)?;
+        let tab = &*self.current_tab()?;
+        if !self.browser.start_js_coverage(tab).await? {
+            return Err(self.unavailable(BrowserFeature::JsCoverage));
+        }
+        *self.js_coverage.lock().unwrap() = Some(options);
+        Ok(())
+    }
+
+    /// Stop the recording `start_js_coverage` began and report how much of each
+    /// script still loaded ran
+    pub async fn stop_js_coverage(&self) -> Result<JsCoverage> {
+        let options = self.js_coverage.lock().unwrap().take().ok_or_else(|| {
+            crate::errors::BrowserAgentError::ConfigurationError(
+                "JavaScript coverage is not being recorded; call start_js_coverage first"
+                    .to_string(),
+            )
+        })?;
+        let tab = &*self.current_tab()?;
+        let scripts = self
+            .browser
+            .stop_js_coverage(tab)
+            .await?
+            .ok_or_else(|| self.unavailable(BrowserFeature::JsCoverage))?
+            .into_iter()
+            .filter(|script| options.include_anonymous || !script.url.is_empty())
+            .map(|mut script| {
+                if !options.unused_ranges {
+                    script.unused_ranges.clear();
+                }
+                script
+            })
+            .collect();
+        let coverage = JsCoverage::from_scripts(scripts);
+        println!("📊 JavaScript coverage: {}", coverage);
+        Ok(coverage)
+    }
+}
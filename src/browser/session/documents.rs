@@ -0,0 +1,85 @@
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::dom::DocumentKind;
+use crate::errors::Result;
+use crate::utils::artifacts::{ArtifactRef, ArtifactSink};
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// What the tab is showing now, from the page's content type or else its URL
+    pub async fn document_kind(&self) -> Result<DocumentKind> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        Ok(self.document_kind_at(tab).await?.0)
+    }
+
+    /// Document kind and URL of `tab`
+    pub(super) async fn document_kind_at(
+        &self,
+        tab: &B::TabHandle,
+    ) -> Result<(DocumentKind, String)> {
+        let url = self.browser.get_url(tab).await?;
+        // Some viewers run no script; the URL is the only hint then
+        let content_type = self
+            .browser
+            .execute_script(tab, crate::dom::document::CONTENT_TYPE_JS)
+            .await
+            .ok();
+        let kind = DocumentKind::detect(content_type.as_ref().and_then(|v| v.as_str()), &url);
+        Ok((kind, url))
+    }
+
+    /// Fail with `UnsupportedDocument` unless the tab shows an HTML page
+    pub(super) async fn require_html(&self, operation: &str) -> Result<()> {
+        let tab = &*self.current_tab()?;
+        let (kind, url) = self.document_kind_at(tab).await?;
+        if kind.is_html() {
+            Ok(())
+        } else {
+            Err(crate::errors::BrowserAgentError::UnsupportedDocument {
+                operation: operation.to_string(),
+                kind,
+                url,
+            })
+        }
+    }
+
+    /// The document in the tab as the server sends it, such as a PDF the
+    /// browser shows in its viewer, requested again from inside the page with
+    /// its cookies. The body is cut at `max_bytes`; check `truncated`.
+    pub async fn download_document(
+        &self,
+        max_bytes: usize,
+    ) -> Result<crate::browser::fetch::FetchResponse> {
+        let url = self.current_url().await?;
+        self.fetch(
+            &url,
+            crate::browser::fetch::FetchOptions::get().with_max_body_bytes(max_bytes),
+        )
+        .await
+    }
+
+    /// Download the document in the tab into the artifact sink under `name`;
+    /// fails with `IoError` when it is larger than `max_bytes`
+    pub async fn save_document(&self, name: &str, max_bytes: usize) -> Result<ArtifactRef> {
+        let response = self.download_document(max_bytes).await?;
+        if response.truncated {
+            return Err(crate::errors::BrowserAgentError::IoError(
+                std::io::Error::other(format!(
+                    "{} is larger than {} bytes",
+                    response.url, max_bytes
+                )),
+            ));
+        }
+        let content_type = response
+            .content_type()
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        let stored = self
+            .artifact_sink
+            .store(name, response.bytes(), &content_type)
+            .await?;
+        println!("📄 Document stored at {}", stored.location);
+        Ok(stored)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::browser::dry_run::{inspect_script, DryRunReport};
+use crate::core::{BrowserTrait, Selector};
+use crate::errors::Result;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Resolve `selector` and run the checks a click would, without scrolling,
+    /// dispatching events or following the link it would click. Problems are
+    /// reported when the selector does not match exactly one element, the element
+    /// is not actionable, or the URL a link or submit button leads to is outside
+    /// the domain policy.
+    pub async fn dry_run_click(&self, selector: impl Into<Selector>) -> Result<DryRunReport> {
+        self.dry_run_interaction("click", &selector.into(), false)
+            .await
+    }
+
+    /// `dry_run_click` for typing; the element must also be editable
+    pub async fn dry_run_type(&self, selector: impl Into<Selector>) -> Result<DryRunReport> {
+        self.dry_run_interaction("type", &selector.into(), true)
+            .await
+    }
+
+    /// Resolve `url` against the current page and check it against the domain
+    /// policy, without navigating
+    pub async fn dry_run_navigate(&self, url: &str) -> Result<DryRunReport> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+
+        let mut report = DryRunReport::new("navigate", url);
+        let page = url::Url::parse(&self.browser.get_url(tab).await?).ok();
+        match page
+            .as_ref()
+            .map_or_else(|| url::Url::parse(url), |base| base.join(url))
+        {
+            Ok(resolved) => {
+                if let Some(rule) = self.domain_policy.blocking_rule(resolved.as_str()) {
+                    report
+                        .problems
+                        .push(format!("{} is refused by {}", resolved, rule));
+                    report.blocked_by = Some(rule);
+                }
+                report.resolved_url = Some(resolved.to_string());
+            }
+            Err(e) => report.problems.push(format!("invalid URL {}: {}", url, e)),
+        }
+        Ok(report)
+    }
+
+    async fn dry_run_interaction(
+        &self,
+        action: &str,
+        selector: &Selector,
+        editable: bool,
+    ) -> Result<DryRunReport> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+
+        let target = selector.to_string();
+        let mut report = DryRunReport::new(action, &target);
+        let found = self
+            .browser
+            .execute_script(tab, &inspect_script(&selector.elements_expression()))
+            .await?;
+        report.matches = found.get("count").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        report.element = found
+            .get("element")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        match report.matches {
+            0 => {
+                report
+                    .problems
+                    .push(format!("{} matches no element", target));
+                return Ok(report);
+            }
+            1 => {}
+            count => report.problems.push(format!(
+                "{} matches {} elements; the first would be used",
+                target, count
+            )),
+        }
+
+        let actionability = self
+            .actionability(&selector.element_expression(), &target, editable, false)
+            .await?;
+        if let Some(failure) = actionability.failure() {
+            report.problems.push(format!(
+                "not {}: {}",
+                failure.check,
+                failure.detail.as_deref().unwrap_or("no detail")
+            ));
+        }
+        report.actionability = Some(actionability);
+
+        if let Some(href) = found.get("href").and_then(|v| v.as_str()) {
+            if let Some(rule) = self.domain_policy.blocking_rule(href) {
+                report
+                    .problems
+                    .push(format!("{} is refused by {}", href, rule));
+                report.blocked_by = Some(rule);
+            }
+            report.resolved_url = Some(href.to_string());
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::browser::editor::{self, EditorContent, EditorTextSource};
+use crate::browser::state::SessionState;
+use crate::browser::typing::{InsertOutcome, TypeMode, TypeResult};
+use crate::core::BrowserTrait;
+use crate::errors::Result;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Everything in the rich text editor `selector`, as plain text and markdown.
+    ///
+    /// Selects the whole editor and lets it serialize the selection through a copy
+    /// event, so model-based editors like ProseMirror and Slate report their text in
+    /// reading order; other editable elements are read through the selection. The
+    /// page's selection is restored afterwards.
+    pub async fn get_editor_content(&self, selector: &str) -> Result<EditorContent> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        let element_expression = format!(
+            "document.querySelector({})",
+            serde_json::to_string(selector)?
+        );
+        let result = self
+            .browser
+            .execute_script(tab, &editor::read_editor_script(&element_expression))
+            .await?;
+        let outcome: editor::EditorReadOutcome = serde_json::from_value(result).unwrap_or_default();
+        if !outcome.success {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "Failed to read editor: {} ({})",
+                selector,
+                outcome.error.unwrap_or_default()
+            )));
+        }
+        Ok(EditorContent {
+            text: outcome.text,
+            markdown: outcome.markdown,
+            source: outcome.source.unwrap_or(EditorTextSource::Selection),
+        })
+    }
+
+    /// Replace everything in the rich text editor `selector` with `text`, each line
+    /// a paragraph, through the input events editors like ProseMirror and Slate
+    /// handle instead of direct DOM changes they would undo
+    pub async fn set_editor_content(&self, selector: &str, text: &str) -> Result<TypeResult> {
+        let _state = self.enter_state(SessionState::Interacting, "type").await?;
+        let element_expression = format!(
+            "document.querySelector({})",
+            serde_json::to_string(selector)?
+        );
+        self.wait_until_actionable(&element_expression, selector, true)
+            .await?;
+        self.pace().await;
+        let tab = &*self.current_tab()?;
+        let result = self
+            .browser
+            .execute_script(tab, &editor::write_editor_script(&element_expression, text))
+            .await?;
+        let outcome: InsertOutcome = serde_json::from_value(result).unwrap_or_default();
+        if !outcome.success {
+            let error = outcome.error.unwrap_or_default();
+            println!("❌ Editor update failed: {}", error);
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "Failed to type in editor: {} ({})",
+                selector, error
+            )));
+        }
+        println!("✅ Replaced the content of editor: {}", selector);
+        Ok(TypeResult {
+            mode: TypeMode::Replace,
+            before: outcome.before,
+            after: outcome.after,
+            element_type: outcome.element_type,
+        })
+    }
+}
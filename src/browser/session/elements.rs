@@ -0,0 +1,375 @@
+use crate::core::{BrowserTrait, CallOptions, Selector, SessionTrait};
+use crate::dom::labeler::{self, PageContext};
+use crate::dom::{FieldValue, Viewport};
+use crate::errors::Result;
+use crate::utils::{run_cancellable, CancellationToken};
+
+use super::{AIElement, BrowserSession, ElementState};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    pub async fn get_ai_elements(&self) -> Result<Vec<AIElement>> {
+        let dom_state = self.get_page_state(false).await?;
+        if !dom_state.document_kind.is_html() {
+            return Err(crate::errors::BrowserAgentError::UnsupportedDocument {
+                operation: "get_ai_elements".to_string(),
+                kind: dom_state.document_kind,
+                url: dom_state.url,
+            });
+        }
+        let elements: Vec<crate::dom::DomElement> = dom_state
+            .elements
+            .iter()
+            .filter(|element| {
+                element.is_clickable
+                    || element.is_interactable
+                    || element.text_content.is_some()
+                    || element.graphic_label.is_some()
+                    || element.tag_name == "canvas"
+            })
+            .cloned()
+            .collect();
+        let annotations = self
+            .labeler
+            .label_all(&elements, &PageContext::from(&dom_state))
+            .await?;
+        if annotations.len() != elements.len() {
+            return Err(crate::errors::BrowserAgentError::DomExtractionFailed(
+                format!(
+                    "labeler returned {} annotations for {} elements",
+                    annotations.len(),
+                    elements.len()
+                ),
+            ));
+        }
+
+        let ai_elements: Vec<AIElement> = elements
+            .iter()
+            .zip(annotations)
+            .enumerate()
+            .map(|(index, (element, annotation))| AIElement {
+                id: element.id.clone(),
+                element_number: index + 1,
+                tag_name: element.tag_name.clone(),
+                element_type: labeler::element_type(element),
+                selector: element.css_selector.clone(),
+                xpath: element.xpath.clone(),
+                text_content: element.text_content.clone(),
+                placeholder: element.attributes.get("placeholder").cloned(),
+                label: annotation.label,
+                description: annotation.description,
+                capabilities: labeler::element_capabilities(element),
+                attributes: element.attributes.clone(),
+                is_visible: element.is_visible,
+                ai_instructions: annotation.instructions,
+                importance: annotation.importance,
+                rect: element.rect.clone(),
+                options: element.options.clone(),
+                resolved_href: element.resolved_href.clone(),
+                link_kind: element.link_kind,
+                is_disabled: element.is_disabled,
+                is_readonly: element.is_readonly,
+                is_aria_disabled: element.is_aria_disabled,
+                state: None,
+            })
+            .collect();
+
+        *self.ai_elements.write().await = ai_elements.clone();
+        #[cfg(feature = "inspector")]
+        self.inspect(|view| {
+            view.observation = crate::browser::inspector::Observation {
+                url: dom_state.url.clone(),
+                title: dom_state.title.clone(),
+                observed_at: Some(chrono::Utc::now()),
+                elements: ai_elements.clone(),
+            }
+        });
+        Ok(ai_elements)
+    }
+
+    /// The most important elements from `get_ai_elements` that fit in `max_tokens`.
+    ///
+    /// Elements are ordered by importance, ties keeping page order, and added until
+    /// the next would exceed the budget, estimated at four bytes of JSON per token.
+    /// Element numbers stay those of the full list.
+    pub async fn get_ai_elements_within_budget(&self, max_tokens: usize) -> Result<Vec<AIElement>> {
+        let mut elements = self.get_ai_elements().await?;
+        elements.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+
+        let mut used = 0;
+        let mut kept = Vec::new();
+        for element in elements {
+            let tokens = serde_json::to_string(&element)
+                .map(|json| json.len().div_ceil(4))
+                .unwrap_or(0);
+            if used + tokens > max_tokens {
+                break;
+            }
+            used += tokens;
+            kept.push(element);
+        }
+        Ok(kept)
+    }
+
+    /// Elements numbered by the last `get_ai_elements`, each with its live state.
+    ///
+    /// Numbers come from the cached list, extracting it only if there is none yet,
+    /// and the state of every requested element is read in a single script.
+    /// Unknown numbers give `None`.
+    pub async fn get_elements_info(&self, numbers: &[usize]) -> Result<Vec<Option<AIElement>>> {
+        if self.ai_elements.read().await.is_empty() {
+            self.get_ai_elements().await?;
+        }
+        let mut elements: Vec<Option<AIElement>> = {
+            let cached = self.ai_elements.read().await;
+            numbers
+                .iter()
+                .map(|number| number.checked_sub(1).and_then(|i| cached.get(i)).cloned())
+                .collect()
+        };
+
+        let selectors: Vec<String> = elements
+            .iter()
+            .flatten()
+            .map(|element| element.selector.clone())
+            .collect();
+        let states = self.element_states(&selectors).await?;
+        for (element, state) in elements.iter_mut().flatten().zip(states) {
+            element.is_visible = state.as_ref().is_some_and(|s| s.is_visible);
+            element.state = state;
+        }
+        Ok(elements)
+    }
+
+    /// Current value of element `number`, for form controls
+    pub async fn get_element_value(&self, number: usize) -> Result<Option<String>> {
+        Ok(self
+            .get_element_state(number)
+            .await?
+            .and_then(|state| state.value))
+    }
+
+    /// Current viewport size, scroll position and page size
+    pub async fn viewport(&self) -> Result<Viewport> {
+        Ok(serde_json::from_value(
+            self.execute_script(Viewport::CAPTURE_JS).await?,
+        )?)
+    }
+
+    /// What the first element matching `selector` contains right now: value,
+    /// checked state and selected options
+    pub async fn get_field_value(&self, selector: &str) -> Result<FieldValue> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        let result = self
+            .browser
+            .execute_script(tab, &crate::dom::field_value::field_value_script(selector))
+            .await?;
+        if result.is_null() {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(
+                selector.to_string(),
+            ));
+        }
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Live state of element `number`, or `None` when it is unknown or gone
+    pub async fn get_element_state(&self, number: usize) -> Result<Option<ElementState>> {
+        Ok(self
+            .get_elements_info(&[number])
+            .await?
+            .pop()
+            .flatten()
+            .and_then(|element| element.state))
+    }
+
+    /// Live state of the first element matching each selector, in one script
+    async fn element_states(&self, selectors: &[String]) -> Result<Vec<Option<ElementState>>> {
+        if selectors.is_empty() {
+            return Ok(Vec::new());
+        }
+        let tab = &*self.current_tab()?;
+        let script = format!(
+            r#"
+            {}.map(selector => {{
+                let element = null;
+                try {{ element = document.querySelector(selector); }} catch (e) {{}}
+                if (!element) return null;
+                const rect = element.getBoundingClientRect();
+                const style = window.getComputedStyle(element);
+                return {{
+                    value: 'value' in element && element.value !== undefined ? String(element.value) : null,
+                    checked: 'checked' in element ? !!element.checked : null,
+                    rect: {{ x: rect.x, y: rect.y, width: rect.width, height: rect.height }},
+                    is_visible: rect.width > 0 && rect.height > 0
+                        && style.visibility !== 'hidden' && style.display !== 'none',
+                    is_enabled: !element.disabled && element.getAttribute('aria-disabled') !== 'true'
+                }};
+            }})
+            "#,
+            serde_json::to_string(selectors)?
+        );
+        let result = self.browser.execute_script(tab, &script).await?;
+        let mut states: Vec<Option<ElementState>> =
+            serde_json::from_value(result).unwrap_or_default();
+        states.resize(selectors.len(), None);
+        Ok(states)
+    }
+
+    pub async fn wait_for_elements(
+        &self,
+        selector: impl Into<Selector>,
+        timeout_ms: u64,
+    ) -> Result<bool> {
+        let token = self.cancellation_token();
+        self.wait_for_elements_with_cancel(selector, timeout_ms, &token)
+            .await
+    }
+
+    /// `wait_for_elements` bounded by `options.timeout_ms`
+    pub async fn wait_for_elements_with_options(
+        &self,
+        selector: impl Into<Selector>,
+        options: &CallOptions,
+    ) -> Result<bool> {
+        self.wait_for_elements(selector, options.timeout_ms).await
+    }
+
+    /// Click `selector` once it appears. A failed click is retried up to
+    /// `options.retries` times, `poll_interval_ms` apart, while within `timeout_ms`.
+    pub async fn click_with_options(
+        &self,
+        selector: impl Into<Selector>,
+        options: &CallOptions,
+    ) -> Result<()> {
+        let selector = selector.into();
+        self.interact_with_options("click", &selector, options, || self.click(&selector))
+            .await
+    }
+
+    /// Type into `selector` once it appears, retrying like `click_with_options`
+    pub async fn type_text_with_options(
+        &self,
+        selector: impl Into<Selector>,
+        text: &str,
+        options: &CallOptions,
+    ) -> Result<()> {
+        let selector = selector.into();
+        self.interact_with_options("type", &selector, options, || {
+            self.type_text_enhanced(&selector, text)
+        })
+        .await
+    }
+
+    async fn interact_with_options<F, Fut>(
+        &self,
+        action: &str,
+        selector: &Selector,
+        options: &CallOptions,
+        mut attempt: F,
+    ) -> Result<()>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
+        let deadline = self.clock.now() + std::time::Duration::from_millis(options.timeout_ms);
+        if !self
+            .wait_for_elements_with_options(selector, options)
+            .await?
+        {
+            return Err(crate::errors::BrowserAgentError::TimeoutError(format!(
+                "{} target {} did not appear within {}ms",
+                action, selector, options.timeout_ms
+            )));
+        }
+
+        let mut retries_left = options.retries;
+        loop {
+            match attempt().await {
+                Ok(()) => return Ok(()),
+                Err(
+                    e @ (crate::errors::BrowserAgentError::Cancelled(_)
+                    | crate::errors::BrowserAgentError::SessionClosed),
+                ) => return Err(e),
+                Err(e) if retries_left == 0 || self.clock.now() >= deadline => return Err(e),
+                Err(e) => {
+                    retries_left -= 1;
+                    println!("🔁 {} on {} failed ({}), retrying", action, selector, e);
+                    self.clock.sleep(options.poll_interval()).await;
+                }
+            }
+        }
+    }
+
+    /// `wait_for_elements` that gives up with `Cancelled` once `token` trips
+    pub async fn wait_for_elements_with_cancel(
+        &self,
+        selector: impl Into<Selector>,
+        timeout_ms: u64,
+        token: &CancellationToken,
+    ) -> Result<bool> {
+        let selector = selector.into();
+        run_cancellable(
+            token,
+            "wait_for_elements",
+            self.wait_for_elements_inner(&selector, timeout_ms),
+        )
+        .await
+    }
+
+    async fn wait_for_elements_inner(&self, selector: &Selector, timeout_ms: u64) -> Result<bool> {
+        let tab = &*self.current_tab()?;
+
+        let wait_script = format!(
+            r#"
+                                                                   (function() {{
+                                                                       return new Promise((resolve) => {{
+                                                                           const checkElement = () => {{
+                                                                               const elements = {};
+                                                                               if (elements.length > 0) {{
+                                                                                   resolve({{ found: true, count: elements.length }});
+                                                                                   return true;
+                                                                               }}
+                                                                               return false;
+                                                                           }};
+
+                                                                           if (checkElement()) return;
+
+                                                                           const observer = new MutationObserver(() => {{
+                                                                               if (checkElement()) {{
+                                                                                   observer.disconnect();
+                                                                               }}
+                                                                           }});
+
+                                                                           observer.observe(document.body, {{
+                                                                               childList: true,
+                                                                               subtree: true
+                                                                           }});
+
+                                                                           setTimeout(() => {{
+                                                                               observer.disconnect();
+                                                                               resolve({{ found: false, timeout: true }});
+                                                                           }}, {});
+                                                                       }});
+                                                                   }})()
+                                                               "#,
+            selector.elements_expression(),
+            timeout_ms
+        );
+
+        let result = self.browser.execute_script(tab, &wait_script).await?;
+        let found = result
+            .get("found")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if found {
+            self.refresh_elements_after_change().await?;
+        }
+
+        Ok(found)
+    }
+
+    pub async fn get_current_interactive_elements(&self) -> Result<Vec<AIElement>> {
+        self.get_ai_elements().await
+    }
+}
@@ -0,0 +1,226 @@
+use crate::core::user_agent::split_headers;
+use crate::core::{
+    BrowserFeature, BrowserTrait, NetworkProfile, Throttling, UserAgentMetadata, UserAgentOverride,
+    UserAgentProfile, UserAgentRun,
+};
+use crate::errors::Result;
+use std::collections::HashMap;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Load `url` once per profile, each in a browser context of its own with the
+    /// profile's user agent, and report on every load for diffing.
+    ///
+    /// Runs send the session's custom headers but none of its cookies or storage,
+    /// and leave its tab alone.
+    pub async fn compare_as(
+        &self,
+        profiles: &[UserAgentProfile],
+        url: &str,
+    ) -> Result<Vec<UserAgentRun>> {
+        self.ensure_open()?;
+        self.domain_policy.check(url)?;
+        if profiles.iter().any(|profile| profile.user_agent.is_some()) {
+            self.require(BrowserFeature::UserAgentOverride)?;
+        }
+        let headers = self.custom_headers.lock().unwrap().clone();
+
+        let mut runs = Vec::new();
+        for profile in profiles {
+            let tab = self.browser.new_isolated_tab().await?;
+            let run = self.run_as(&tab, profile, &headers, url).await;
+            if let Err(e) = self.browser.close_tab(&tab).await {
+                println!("⚠️ Could not close the tab of {}: {}", profile.name, e);
+            }
+            runs.push(run?);
+        }
+        Ok(runs)
+    }
+
+    async fn run_as(
+        &self,
+        tab: &B::TabHandle,
+        profile: &UserAgentProfile,
+        headers: &HashMap<String, String>,
+        url: &str,
+    ) -> Result<UserAgentRun> {
+        self.override_requests(tab, profile.user_agent.as_ref(), headers)
+            .await?;
+        let console_capture =
+            self.console_capture && self.browser.start_console_capture(tab).await?;
+        let nav_result = self.load_in_tab(tab, url).await?;
+        let report = self.analyze_tab(tab, console_capture).await?;
+        let user_agent = self
+            .browser
+            .execute_script(tab, "navigator.userAgent")
+            .await?;
+        println!("🕵️ {} loaded {}", profile.name, nav_result.url);
+        Ok(UserAgentRun {
+            profile: profile.name.clone(),
+            user_agent: user_agent.as_str().unwrap_or_default().to_string(),
+            final_url: nav_result.url,
+            report,
+        })
+    }
+
+    /// Send `headers` with every request from the session's tab, replacing the
+    /// previous set.
+    ///
+    /// A `User-Agent` header becomes a user agent override with matching client
+    /// hints, so pages see what the server sees; one set by `set_user_agent` takes
+    /// precedence over it.
+    pub async fn set_custom_headers(&self, headers: &HashMap<String, String>) -> Result<()> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        *self.custom_headers.lock().unwrap() = headers.clone();
+        self.apply_request_overrides(tab).await
+    }
+
+    /// Report `user_agent` from the next request on, in the session's tab only.
+    ///
+    /// `metadata` holds the client hints sent with it; `None` derives them from a
+    /// Chrome user agent string and sends none for other browsers.
+    pub async fn set_user_agent(
+        &self,
+        user_agent: &str,
+        metadata: Option<UserAgentMetadata>,
+    ) -> Result<()> {
+        let mut user_agent = UserAgentOverride::from_user_agent(user_agent);
+        if metadata.is_some() {
+            user_agent.metadata = metadata;
+        }
+        self.set_user_agent_override(user_agent).await
+    }
+
+    /// `set_user_agent` with the accept language and platform given as well
+    pub async fn set_user_agent_override(&self, user_agent: UserAgentOverride) -> Result<()> {
+        self.ensure_open()?;
+        self.require(BrowserFeature::UserAgentOverride)?;
+        let tab = &*self.current_tab()?;
+        println!("🕵️ User agent: {}", user_agent.user_agent);
+        *self.user_agent.lock().unwrap() = Some(user_agent);
+        self.apply_request_overrides(tab).await
+    }
+
+    /// Go back to the browser's own user agent, or the one in a `User-Agent` custom
+    /// header
+    pub async fn clear_user_agent(&self) -> Result<()> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        *self.user_agent.lock().unwrap() = None;
+        self.apply_request_overrides(tab).await
+    }
+
+    /// Override set with `set_user_agent`, if any
+    pub fn user_agent_override(&self) -> Option<UserAgentOverride> {
+        self.user_agent.lock().unwrap().clone()
+    }
+
+    /// Apply the session's user agent override and custom headers to `tab`
+    pub(super) async fn apply_request_overrides(&self, tab: &B::TabHandle) -> Result<()> {
+        let user_agent = self.user_agent_override();
+        let headers = self.custom_headers.lock().unwrap().clone();
+        self.override_requests(tab, user_agent.as_ref(), &headers)
+            .await
+    }
+
+    async fn override_requests(
+        &self,
+        tab: &B::TabHandle,
+        user_agent: Option<&UserAgentOverride>,
+        headers: &HashMap<String, String>,
+    ) -> Result<()> {
+        let (user_agent, extra) = split_headers(headers, user_agent);
+        if self.browser.capabilities().supports_user_agent_override {
+            self.browser
+                .set_user_agent(tab, user_agent.as_ref())
+                .await?;
+        } else if user_agent.is_some() {
+            return Err(self.unavailable(BrowserFeature::UserAgentOverride));
+        }
+        if !self.browser.set_extra_headers(tab, &extra).await? && !extra.is_empty() {
+            println!(
+                "⚠️ Custom headers are not supported by the {} backend",
+                self.browser.backend_name()
+            );
+        }
+        Ok(())
+    }
+
+    /// Throttle the session's tab to `profile`, and the tabs it prefetches from then
+    /// on. Navigation results report the throttling in effect.
+    pub async fn emulate_network(&self, profile: NetworkProfile) -> Result<()> {
+        self.ensure_open()?;
+        self.require(BrowserFeature::Throttling)?;
+        let tab = &*self.current_tab()?;
+        if !self
+            .browser
+            .emulate_network(tab, Some(&profile.conditions()))
+            .await?
+        {
+            return Err(self.unavailable(BrowserFeature::Throttling));
+        }
+        println!("🐢 Network: {}", profile);
+        self.throttling.lock().unwrap().network = Some(profile);
+        Ok(())
+    }
+
+    /// Lift the throttling set by `emulate_network`
+    pub async fn clear_network_emulation(&self) -> Result<()> {
+        let tab = &*self.current_tab()?;
+        if self.throttling.lock().unwrap().network.take().is_some() {
+            self.browser.emulate_network(tab, None).await?;
+        }
+        Ok(())
+    }
+
+    /// Run the page `slowdown_factor` times slower, e.g. 4.0 for a mid-range phone;
+    /// 1.0 returns to full speed
+    pub async fn emulate_cpu(&self, slowdown_factor: f64) -> Result<()> {
+        self.ensure_open()?;
+        self.require(BrowserFeature::Throttling)?;
+        if !slowdown_factor.is_finite() || slowdown_factor < 1.0 {
+            return Err(crate::errors::BrowserAgentError::ConfigurationError(
+                format!(
+                    "CPU slowdown factor must be at least 1.0, got {}",
+                    slowdown_factor
+                ),
+            ));
+        }
+        let tab = &*self.current_tab()?;
+        if !self.browser.emulate_cpu(tab, slowdown_factor).await? {
+            return Err(self.unavailable(BrowserFeature::Throttling));
+        }
+        println!("🐢 CPU: {}x slowdown", slowdown_factor);
+        self.throttling.lock().unwrap().cpu_slowdown =
+            (slowdown_factor > 1.0).then_some(slowdown_factor);
+        Ok(())
+    }
+
+    /// Network and CPU throttling currently applied to the session's tab
+    pub fn throttling(&self) -> Throttling {
+        *self.throttling.lock().unwrap()
+    }
+
+    pub(super) fn active_throttling(&self) -> Option<Throttling> {
+        Some(self.throttling()).filter(Throttling::is_active)
+    }
+
+    /// Apply `throttling` to a tab other than the session's
+    pub(super) async fn apply_throttling(
+        &self,
+        tab: &B::TabHandle,
+        throttling: &Throttling,
+    ) -> Result<()> {
+        if let Some(network) = &throttling.network {
+            self.browser
+                .emulate_network(tab, Some(&network.conditions()))
+                .await?;
+        }
+        if let Some(slowdown) = throttling.cpu_slowdown {
+            self.browser.emulate_cpu(tab, slowdown).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use crate::core::BrowserTrait;
+use crate::errors::Result;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Request `url` with `fetch` from inside the page, so it carries the page's
+    /// cookies and origin, and return the response with its body cut at
+    /// `options.max_body_bytes`.
+    ///
+    /// Relative URLs resolve against the current page. The page's CSRF token, from
+    /// `current_csrf_header`, goes with the requests `options.csrf` says; with
+    /// `CsrfMode::Always` a page without one fails with `ConfigurationError`.
+    /// Network failures are `JavaScriptFailed`, and exceeding the timeout, the
+    /// navigation timeout when unset, is `TimeoutError`.
+    pub async fn fetch(
+        &self,
+        url: &str,
+        options: crate::browser::fetch::FetchOptions,
+    ) -> Result<crate::browser::fetch::FetchResponse> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+
+        let page = url::Url::parse(&self.browser.get_url(tab).await?).ok();
+        let resolved = page
+            .as_ref()
+            .map_or_else(|| url::Url::parse(url), |base| base.join(url))
+            .map_err(|e| {
+                crate::errors::BrowserAgentError::ConfigurationError(format!(
+                    "invalid fetch URL {}: {}",
+                    url, e
+                ))
+            })?;
+        self.domain_policy.check(resolved.as_str())?;
+
+        let mut headers = options.headers.clone();
+        let same_origin = page.is_some_and(|page| page.origin() == resolved.origin());
+        let send_csrf = match options.csrf {
+            crate::browser::fetch::CsrfMode::Auto => same_origin && options.is_unsafe(),
+            crate::browser::fetch::CsrfMode::Always => true,
+            crate::browser::fetch::CsrfMode::Never => false,
+        };
+        if send_csrf {
+            match self.current_csrf_header().await? {
+                Some((header, value)) => {
+                    let header = options.csrf_header.clone().unwrap_or(header);
+                    if !headers
+                        .iter()
+                        .any(|(name, _)| name.eq_ignore_ascii_case(&header))
+                    {
+                        headers.push((header, value));
+                    }
+                }
+                None if options.csrf == crate::browser::fetch::CsrfMode::Always => {
+                    return Err(crate::errors::BrowserAgentError::ConfigurationError(
+                        "no CSRF token found on the page".to_string(),
+                    ));
+                }
+                None => {}
+            }
+        }
+        let resolved = resolved.to_string();
+
+        let timeout_ms = options
+            .timeout_ms
+            .unwrap_or(self.config.session.navigation_timeout_ms);
+        let script = crate::browser::fetch::fetch_script(&resolved, &options, &headers, timeout_ms);
+        // Leave the page's own timer a head start before giving up on the script
+        let value = self
+            .browser
+            .evaluate_async(tab, &script, timeout_ms + 1000)
+            .await?;
+        crate::browser::fetch::parse_fetch_result(&resolved, timeout_ms, value)
+    }
+}
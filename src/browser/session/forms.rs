@@ -0,0 +1,89 @@
+use crate::browser::typing::TypeMode;
+use crate::core::{BrowserTrait, Selector, SessionTrait};
+use crate::dom::{LabeledField, OptionInfo};
+use crate::errors::Result;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// The visible field labeled `label`.
+    ///
+    /// Looks at `<label for>`, wrapping labels, `aria-label`, `aria-labelledby` and
+    /// placeholders, then at text right before or above fields with none of those.
+    /// Matching ignores case, diacritics and required markers, and prefers exact
+    /// matches over prefixes over substrings. Several fields at the best level give
+    /// `AmbiguousLabel` listing them.
+    pub async fn find_field_by_label(&self, label: &str) -> Result<LabeledField> {
+        let result = self
+            .execute_script(&crate::dom::field_label::field_by_label_script(label))
+            .await?;
+        let mut matches = crate::dom::field_label::best_matches(result);
+        match matches.len() {
+            0 => Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "field labeled '{}'",
+                label
+            ))),
+            1 => Ok(matches.remove(0)),
+            _ => Err(crate::errors::BrowserAgentError::AmbiguousLabel {
+                label: label.to_string(),
+                candidates: matches.iter().map(LabeledField::describe).collect(),
+            }),
+        }
+    }
+
+    /// Type `value` into the field `find_field_by_label` resolves, or choose the
+    /// option with that text or value when the field is a `<select>`
+    pub async fn fill_field_by_label(&self, label: &str, value: &str) -> Result<()> {
+        let field = self.find_field_by_label(label).await?;
+        println!(
+            "🏷️ '{}' resolved to {} by {:?}",
+            label, field.selector, field.source
+        );
+
+        if field.tag_name == "select" {
+            self.select_option(Selector::css(&field.selector), value)
+                .await?;
+            return Ok(());
+        }
+
+        let element_expression = format!(
+            "document.querySelector({})",
+            serde_json::to_string(&field.selector)?
+        );
+        self.type_into_expression(
+            &element_expression,
+            &field.selector,
+            value,
+            TypeMode::Replace,
+            false,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Choose the option of the select at `selector` whose value or text is
+    /// `value`, ignoring case, diacritics and spacing, the way `DomElement::options`
+    /// lists them. Returns the option chosen.
+    pub async fn select_option<S: Into<Selector>>(
+        &self,
+        selector: S,
+        value: &str,
+    ) -> Result<OptionInfo> {
+        let selector = selector.into();
+        let chosen = self
+            .execute_script(&crate::dom::field_value::select_option_script(
+                &selector.element_expression(),
+                value,
+            ))
+            .await?;
+        if chosen.is_null() {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "option '{}' in {}",
+                value, selector
+            )));
+        }
+        let option: OptionInfo = serde_json::from_value(chosen)?;
+        println!("🔽 Selected '{}' in {}", option.label, selector);
+        Ok(option)
+    }
+}
@@ -0,0 +1,94 @@
+use crate::core::{BrowserTrait, CallOptions, FrameInfo, FrameTarget};
+use crate::errors::Result;
+
+use super::{poll_until, BrowserSession};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// List the page's frames, top frame first
+    pub async fn list_frames(&self) -> Result<Vec<FrameInfo>> {
+        let tab = &*self.current_tab()?;
+        self.browser.list_frames(tab).await
+    }
+
+    /// Evaluate `script` inside a frame located by selector, URL pattern or id
+    pub async fn execute_script_in_frame(
+        &self,
+        frame: FrameTarget,
+        script: &str,
+    ) -> Result<serde_json::Value> {
+        let tab = &*self.current_tab()?;
+        self.browser
+            .execute_script_in_frame(tab, &frame, script)
+            .await
+    }
+
+    /// Click an element inside a frame
+    pub async fn click_in_frame(&self, frame: FrameTarget, selector: &str) -> Result<()> {
+        let script = format!(
+            r#"
+            (function() {{
+                const element = document.querySelector({});
+                if (!element) return {{ success: false, error: 'Element not found' }};
+                element.scrollIntoView({{ block: 'center' }});
+                element.focus();
+                element.click();
+                return {{ success: true }};
+            }})()
+            "#,
+            serde_json::to_string(selector)?
+        );
+
+        let result = self.execute_script_in_frame(frame.clone(), &script).await?;
+        if result
+            .get("success")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            println!("✅ Clicked {} in frame {}", selector, frame);
+            Ok(())
+        } else {
+            Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "{} in frame {}",
+                selector, frame
+            )))
+        }
+    }
+
+    /// Wait for an element to appear inside a frame
+    pub async fn wait_for_element_in_frame(
+        &self,
+        frame: FrameTarget,
+        selector: &str,
+        timeout_ms: u64,
+    ) -> Result<bool> {
+        self.wait_for_element_in_frame_with_options(
+            frame,
+            selector,
+            &self.call_options().with_timeout_ms(timeout_ms),
+        )
+        .await
+    }
+
+    /// `wait_for_element_in_frame` polling every `options.poll_interval_ms`
+    pub async fn wait_for_element_in_frame_with_options(
+        &self,
+        frame: FrameTarget,
+        selector: &str,
+        options: &CallOptions,
+    ) -> Result<bool> {
+        let script = format!(
+            "document.querySelector({}) !== null",
+            serde_json::to_string(selector)?
+        );
+        poll_until(self.clock.as_ref(), options, || async {
+            // The frame itself may not exist yet, so treat lookup errors as "not yet"
+            Ok(self
+                .execute_script_in_frame(frame.clone(), &script)
+                .await
+                .ok()
+                .and_then(|found| found.as_bool())
+                .unwrap_or(false))
+        })
+        .await
+    }
+}
@@ -0,0 +1,229 @@
+use crate::browser::highlight::{self, highlight_script, HighlightTarget};
+use crate::core::config::HighlightBackend;
+use crate::core::{BrowserFeature, BrowserTrait, SessionTrait};
+use crate::dom::{CoordinateSpace, ElementRect};
+use crate::errors::Result;
+
+use super::{BrowserSession, ElementHighlight};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    pub async fn highlight_interactive_elements(&self) -> Result<Vec<ElementHighlight>> {
+        let tab = &*self.current_tab()?;
+
+        self.clear_element_highlights().await?;
+        self.require_html("highlight_interactive_elements").await?;
+
+        if self.config.session.check_overlays_before_highlight {
+            for overlay in self.detect_overlays().await? {
+                println!(
+                    "⚠️ {:?} overlay {} covers {:.0}% of the viewport",
+                    overlay.kind,
+                    overlay.selector,
+                    overlay.coverage * 100.0
+                );
+            }
+        }
+
+        let dom_state = self.get_page_state(false).await?;
+
+        let mut highlights = Vec::new();
+
+        let mut targets = Vec::new();
+        for (index, element) in dom_state.clickable_elements.iter().enumerate() {
+            let target = HighlightTarget::for_element(element, index + 1);
+            highlights.push(ElementHighlight {
+                element_id: element.id.clone(),
+                element_number: index + 1,
+                color: target.color.clone(),
+                element_type: element.tag_name.clone(),
+                css_selector: element.css_selector.clone(),
+                label_rect: None,
+            });
+            targets.push(target);
+        }
+
+        if self.uses_overlay_highlight() {
+            let selectors: Vec<String> = targets.into_iter().map(|t| t.selector).collect();
+            let matched = self
+                .browser
+                .highlight_nodes(tab, &selectors)
+                .await?
+                .unwrap_or(0);
+            println!(
+                "✅ Highlighted {} elements in the browser overlay; numbers appear in screenshot_highlighted",
+                matched
+            );
+            *self.element_highlights.write().await = highlights.clone();
+            #[cfg(feature = "inspector")]
+            self.inspect_highlights(&highlights, &dom_state);
+            return Ok(highlights);
+        }
+
+        let script = highlight_script(&targets, self.config.dom.highlight_style);
+        let result = self
+            .without_monitoring(tab, self.browser.execute_script(tab, &script))
+            .await?;
+        let drawn = result.as_array().cloned().unwrap_or_default();
+        for entry in &drawn {
+            let number = entry.get("number").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let Some(highlight) = highlights.iter_mut().find(|h| h.element_number == number) else {
+                continue;
+            };
+            highlight.label_rect = entry
+                .get("label")
+                .and_then(|label| serde_json::from_value(label.clone()).ok());
+        }
+        println!("✅ Highlighted {} elements", drawn.len());
+
+        if self.config.session.check_overlays_before_highlight {
+            let selectors: Vec<String> =
+                highlights.iter().map(|h| h.css_selector.clone()).collect();
+            for occlusion in self.check_occlusion(&selectors).await? {
+                println!(
+                    "⚠️ {} is covered by {}",
+                    occlusion.selector, occlusion.covered_by
+                );
+            }
+        }
+
+        *self.element_highlights.write().await = highlights.clone();
+        #[cfg(feature = "inspector")]
+        self.inspect_highlights(&highlights, &dom_state);
+        Ok(highlights)
+    }
+
+    /// Remove the `data-surfai-id` stamps `DomConfig::stamp_elements` left on the
+    /// page and restart their numbering. Navigation does this itself, so pages
+    /// that keep their document across routes do not carry stale stamps.
+    pub async fn clear_element_stamps(&self) -> Result<usize> {
+        let tab = &*self.current_tab()?;
+        let removed = self
+            .without_monitoring(
+                tab,
+                self.browser
+                    .execute_script(tab, &crate::dom::processor::unstamp_script()),
+            )
+            .await?;
+        Ok(removed.as_u64().unwrap_or_default() as usize)
+    }
+
+    pub async fn clear_element_highlights(&self) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        if self.uses_overlay_highlight() {
+            self.browser.hide_highlight(tab).await?;
+        }
+
+        let clear_script = highlight::clear_script(highlight::HIGHLIGHT_CLASS);
+
+        self.without_monitoring(tab, self.browser.execute_script(tab, &clear_script))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn click_element_by_number(&self, element_number: usize) -> Result<()> {
+        self.wait_for_refresh().await?;
+        let highlight = self
+            .element_highlights
+            .read()
+            .await
+            .iter()
+            .find(|h| h.element_number == element_number)
+            .cloned();
+        if let Some(highlight) = highlight {
+            self.click(&highlight.css_selector).await
+        } else {
+            Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "Element number {} not found",
+                element_number
+            )))
+        }
+    }
+
+    pub async fn type_in_element_by_number(&self, element_number: usize, text: &str) -> Result<()> {
+        let highlight = self
+            .element_highlights
+            .read()
+            .await
+            .iter()
+            .find(|h| h.element_number == element_number)
+            .cloned();
+        if let Some(highlight) = highlight {
+            self.type_text_enhanced(&highlight.css_selector, text).await
+        } else {
+            Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "Element number {} not found",
+                element_number
+            )))
+        }
+    }
+
+    pub async fn get_highlighted_elements(&self) -> Vec<ElementHighlight> {
+        self.element_highlights.read().await.clone()
+    }
+
+    /// Run `operation` with the element monitor ignoring the DOM changes it makes
+    pub(super) async fn without_monitoring<T>(
+        &self,
+        tab: &B::TabHandle,
+        operation: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        self.element_monitor
+            .pause(self.browser.as_ref(), tab)
+            .await?;
+        let result = operation.await;
+        self.element_monitor
+            .resume(self.browser.as_ref(), tab)
+            .await?;
+        result
+    }
+
+    /// Whether highlights go to the browser's overlay layer instead of the DOM
+    fn uses_overlay_highlight(&self) -> bool {
+        self.config.dom.highlight_backend == HighlightBackend::CdpOverlay
+            && self.supports(BrowserFeature::OverlayHighlight, "DOM overlay highlights")
+    }
+
+    /// Screenshot with every highlighted element outlined and numbered.
+    ///
+    /// The numbers are drawn into the image, not the page, which makes this the way to
+    /// see them with `HighlightBackend::CdpOverlay`.
+    pub async fn screenshot_highlighted(&self) -> Result<(Vec<u8>, CoordinateSpace)> {
+        let tab = &*self.current_tab()?;
+        let highlights = self.get_highlighted_elements().await;
+        let selectors: Vec<&str> = highlights.iter().map(|h| h.css_selector.as_str()).collect();
+        let measure_script = format!(
+            r#"
+            {}.map(selector => {{
+                let element = null;
+                try {{ element = document.querySelector(selector); }} catch (e) {{}}
+                if (!element) return null;
+                const rect = element.getBoundingClientRect();
+                if (rect.width <= 0 || rect.height <= 0) return null;
+                return {{ x: rect.x, y: rect.y, width: rect.width, height: rect.height }};
+            }})
+            "#,
+            serde_json::to_string(&selectors)?
+        );
+        let measured = self.browser.execute_script(tab, &measure_script).await?;
+        let rects: Vec<Option<ElementRect>> = serde_json::from_value(measured).unwrap_or_default();
+
+        let space = self.coordinate_space().await?;
+        let screenshot = SessionTrait::screenshot(self).await?;
+        let numbered: Vec<(ElementRect, usize)> = highlights
+            .iter()
+            .zip(rects)
+            .filter_map(|(highlight, rect)| {
+                rect.map(|rect| (rect.in_screenshot(&space, &space), highlight.element_number))
+            })
+            .collect();
+        let scale = (space.device_scale_factor * 2.0).round() as u32;
+        let annotated = crate::utils::image::draw_numbered_rects(
+            &screenshot,
+            &numbered,
+            [255, 64, 0, 255],
+            scale,
+        )?;
+        Ok((annotated, space))
+    }
+}
@@ -0,0 +1,68 @@
+use crate::core::BrowserTrait;
+use crate::dom::DomState;
+use crate::errors::Result;
+
+use super::{BrowserSession, ElementHighlight};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Serve a read-only JSON view of the session on `127.0.0.1:port`, 0 picking
+    /// a free port: the last `get_ai_elements`, the highlighted elements with
+    /// their rects, the last screenshot and the event stream, plus a page drawing
+    /// the numbered boxes over the screenshot. See `InspectorEndpoint` for the
+    /// URLs and the token every request needs.
+    ///
+    /// Replaces a running inspector, with a new token.
+    pub async fn enable_inspector(
+        &self,
+        port: u16,
+    ) -> Result<crate::browser::inspector::InspectorEndpoint> {
+        self.ensure_open()?;
+        let mut view = crate::browser::inspector::InspectorView::default();
+        if let Ok(tab) = self.current_tab() {
+            view.observation.url = self.browser.get_url(&tab).await?;
+        }
+        view.observation.elements = self.ai_elements.read().await.clone();
+        view.highlights = self
+            .element_highlights
+            .read()
+            .await
+            .iter()
+            .map(|highlight| crate::browser::inspector::InspectedHighlight::new(highlight, None))
+            .collect();
+
+        let inspector =
+            crate::browser::inspector::Inspector::start(port, view, &self.events).await?;
+        let endpoint = inspector.endpoint().clone();
+        *self.inspector.lock().unwrap() = Some(inspector);
+        Ok(endpoint)
+    }
+
+    /// Stop serving the inspector, if one runs
+    pub fn disable_inspector(&self) {
+        self.inspector.lock().unwrap().take();
+    }
+
+    pub(super) fn inspect(
+        &self,
+        update: impl FnOnce(&mut crate::browser::inspector::InspectorView),
+    ) {
+        if let Some(inspector) = self.inspector.lock().unwrap().as_ref() {
+            inspector.update(update);
+        }
+    }
+
+    /// Serve `highlights` with the rects `dom_state` measured for their elements
+    pub(super) fn inspect_highlights(&self, highlights: &[ElementHighlight], dom_state: &DomState) {
+        let highlights = highlights
+            .iter()
+            .map(|highlight| {
+                let rect = dom_state
+                    .clickable_elements
+                    .get(highlight.element_number - 1)
+                    .and_then(|element| element.rect.clone());
+                crate::browser::inspector::InspectedHighlight::new(highlight, rect)
+            })
+            .collect();
+        self.inspect(|view| view.highlights = highlights);
+    }
+}
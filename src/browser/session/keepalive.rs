@@ -0,0 +1,238 @@
+use crate::browser::events::SessionEvent;
+use crate::browser::keepalive::{self, KeepaliveTask};
+use crate::browser::refresh::RefreshTask;
+use crate::browser::watchdog::{self, ReloginFlow};
+use crate::core::config::KeepaliveOptions;
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::errors::Result;
+use std::sync::atomic::Ordering;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Install the document stamp the keepalive uses to notice replaced pages
+    pub(crate) async fn prepare_keepalive(&self) -> Result<()> {
+        let tab = &*self.current_tab()?;
+        if !self
+            .browser
+            .add_init_script(tab, &keepalive::init_script(&self.session_id))
+            .await?
+        {
+            println!("⚠️ Init scripts are not supported; reloads are detected by a stamp on the current page");
+        }
+        Ok(())
+    }
+
+    pub(crate) fn keepalive_options(&self) -> KeepaliveOptions {
+        self.config.session.keepalive.clone()
+    }
+
+    pub(crate) fn set_keepalive(&self, task: Option<KeepaliveTask>) {
+        let previous = std::mem::replace(&mut *self.keepalive.lock().unwrap(), task);
+        if let Some(previous) = previous {
+            previous.stop();
+        }
+    }
+
+    pub(crate) fn set_refresh_task(&self, task: Option<RefreshTask>) {
+        let previous = std::mem::replace(&mut *self.refresh.lock().unwrap(), task);
+        if let Some(previous) = previous {
+            previous.stop();
+        }
+    }
+
+    /// Wait until the highlight refreshes that interactions asked for have run,
+    /// so element numbers match the page. Returns at once unless
+    /// `SessionHandle::start_background_refresh` is running.
+    pub async fn wait_for_refresh(&self) -> Result<()> {
+        let settled = self
+            .refresh
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(RefreshTask::settled);
+        if let Some(settled) = settled {
+            settled.await;
+        }
+        Ok(())
+    }
+
+    /// Perform one keepalive round: keep the page active, run the configured
+    /// activity, publish `PageReplaced` when the document changed without the
+    /// session navigating or reloading and run the logout watchdog
+    pub async fn keepalive_tick(&self, options: &KeepaliveOptions) -> Result<()> {
+        self.ensure_open()?;
+        self.ensure_alive()?;
+        let tab = &*self.current_tab()?;
+
+        if options.prevent_freezing {
+            self.browser.set_page_active(tab).await?;
+        }
+        let result = self
+            .browser
+            .execute_script(
+                tab,
+                &keepalive::tick_script(&options.activity, &self.session_id),
+            )
+            .await?;
+        let report: keepalive::TickReport = serde_json::from_value(result).unwrap_or_default();
+        let reloads = self.reloads.load(Ordering::Relaxed);
+
+        let previous = self
+            .keepalive_seen
+            .lock()
+            .unwrap()
+            .replace((report.id.clone(), reloads));
+        if let Some((previous_id, previous_reloads)) = previous {
+            let replaced = report.stamped || report.id != previous_id;
+            let unrequested_reload =
+                report.navigation_type.as_deref() == Some("reload") && reloads == previous_reloads;
+            if replaced && (report.discarded || unrequested_reload) {
+                self.publish_event(SessionEvent::PageReplaced {
+                    url: report.url,
+                    discarded: report.discarded,
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+        }
+
+        if self.config.session.watchdog.check_on_keepalive {
+            self.check_logged_out().await?;
+        }
+
+        self.touch();
+        Ok(())
+    }
+
+    /// Credentials `run_with_relogin` logs back in with; `None` makes it fail
+    /// with `LoggedOut` instead
+    pub fn set_relogin_flow(&self, flow: Option<ReloginFlow>) {
+        *self.relogin_flow.lock().unwrap() = flow;
+    }
+
+    /// Whether the last logout watchdog check found the session logged out
+    pub fn is_logged_out(&self) -> bool {
+        self.logged_out.load(Ordering::Relaxed)
+    }
+
+    /// Check the current page against `SessionConfig::watchdog`, returning the URL
+    /// pattern or selector that matched. Publishes `SessionEvent::LoggedOut` when
+    /// the session was not already known to be logged out.
+    pub async fn check_logged_out(&self) -> Result<Option<String>> {
+        let config = &self.config.session.watchdog;
+        if !config.is_enabled() || self.watchdog_paused.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let tab = &*self.current_tab()?;
+
+        let url = self.browser.get_url(tab).await?;
+        let mut indicator = watchdog::matching_url_pattern(config, &url)?;
+        if indicator.is_none() && !config.dom_indicators.is_empty() {
+            indicator = self
+                .browser
+                .execute_script(tab, &watchdog::indicator_script(&config.dom_indicators))
+                .await?
+                .as_str()
+                .map(str::to_string);
+        }
+
+        let was_logged_out = self.logged_out.swap(indicator.is_some(), Ordering::Relaxed);
+        if let Some(indicator) = &indicator {
+            if !was_logged_out {
+                self.publish_event(SessionEvent::LoggedOut {
+                    url,
+                    indicator: indicator.clone(),
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+        }
+        Ok(indicator)
+    }
+
+    /// Run `operation`, and when the logout watchdog finds the session logged out
+    /// before or after it, log back in with the flow from `set_relogin_flow` and
+    /// run it again.
+    ///
+    /// Retries are bounded by `WatchdogConfig::max_relogin_attempts`: a failed
+    /// re-login ends the call with its error, and still being logged out after the
+    /// last attempt ends it with `LoggedOut`. The operation runs again from the page
+    /// the login left the tab on, so it should navigate first if it depends on one.
+    ///
+    /// ```ignore
+    /// session.set_relogin_flow(Some(ReloginFlow::new(login_url, "alice", password)));
+    /// let orders = session
+    ///     .run_with_relogin(|| async {
+    ///         session.navigate_smart(&orders_url).await?;
+    ///         session.execute_script("document.querySelector('#orders').innerText").await
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn run_with_relogin<T, F, Fut>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let max_attempts = self.config.session.watchdog.max_relogin_attempts;
+        let mut attempt = 0;
+        loop {
+            // Logged out since the last operation, e.g. noticed by the keepalive
+            let logged_out = self.is_logged_out() && self.check_logged_out().await?.is_some();
+            if !logged_out {
+                let result = operation().await;
+                if self.check_logged_out().await?.is_none() {
+                    return result;
+                }
+            }
+
+            if attempt >= max_attempts {
+                return Err(crate::errors::BrowserAgentError::LoggedOut(format!(
+                    "still logged out after {} re-login attempt(s)",
+                    attempt
+                )));
+            }
+            attempt += 1;
+            self.relogin(attempt).await?;
+        }
+    }
+
+    async fn relogin(&self, attempt: u32) -> Result<()> {
+        let flow = self.relogin_flow.lock().unwrap().clone().ok_or_else(|| {
+            crate::errors::BrowserAgentError::LoggedOut(
+                "no re-login flow set with set_relogin_flow".to_string(),
+            )
+        })?;
+        println!("🔐 Session logged out, re-login attempt {}", attempt);
+
+        let login = if flow.from_provider {
+            self.auto_login_with_credentials(&flow.login_url, flow.login_config)
+                .await
+        } else {
+            self.auto_login_and_extract_session(
+                &flow.login_url,
+                &flow.username,
+                &flow.password,
+                flow.login_config,
+            )
+            .await
+        };
+        match login {
+            Ok(_) => {
+                let url = self.current_url().await.unwrap_or_default();
+                self.publish_event(SessionEvent::Relogged {
+                    url,
+                    attempt,
+                    timestamp: chrono::Utc::now(),
+                });
+                Ok(())
+            }
+            Err(e) => {
+                self.publish_event(SessionEvent::ReloginFailed {
+                    error: e.to_string(),
+                    attempt,
+                    timestamp: chrono::Utc::now(),
+                });
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::browser::highlight::{self, LabelTarget};
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::dom::labeler::PageContext;
+use crate::errors::Result;
+
+use super::{BrowserSession, DynamicLabel, LabelAction};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    pub async fn add_dynamic_labels(&self) -> Result<Vec<DynamicLabel>> {
+        let tab = &*self.current_tab()?;
+        self.clear_dynamic_labels().await?;
+
+        let dom_state = self.get_page_state(false).await?;
+        let page = PageContext::from(&dom_state);
+        let mut labels = Vec::new();
+        let mut label_counter = 1;
+
+        let mut targets = Vec::new();
+        for element in &dom_state.clickable_elements {
+            let label_type = self.classify_element_for_labeling(element);
+            let label_text = self.generate_dynamic_label_text(element, label_counter);
+
+            targets.push(LabelTarget {
+                selector: element.css_selector.clone(),
+                number: label_counter,
+                text: label_text.clone(),
+                kind: label_type.clone(),
+            });
+            labels.push(DynamicLabel {
+                number: label_counter,
+                element_id: element.id.clone(),
+                selector: element.css_selector.clone(),
+                label_text: label_text.clone(),
+                label_type: label_type.clone(),
+                element_description: self.labeler.label(element, &page).description,
+            });
+
+            label_counter += 1;
+        }
+
+        let script = highlight::label_script(&targets);
+        let result = self
+            .without_monitoring(tab, self.browser.execute_script(tab, &script))
+            .await?;
+        println!(
+            "✅ Added {} dynamic labels",
+            result.as_array().map(|a| a.len()).unwrap_or(0)
+        );
+
+        Ok(labels)
+    }
+
+    pub async fn clear_dynamic_labels(&self) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        let clear_script = highlight::clear_script(highlight::LABEL_CLASS);
+
+        self.without_monitoring(tab, self.browser.execute_script(tab, &clear_script))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn interact_with_labeled_element(
+        &self,
+        label_number: usize,
+        action: LabelAction,
+    ) -> Result<()> {
+        match action {
+            LabelAction::Click => {
+                self.click_element_by_number_with_refresh(label_number)
+                    .await
+            }
+            LabelAction::Type(text) => self.type_in_element_by_number(label_number, &text).await,
+        }
+    }
+
+    pub async fn get_labeled_element_info(&self, label_number: usize) -> Option<String> {
+        None
+    }
+
+    fn classify_element_for_labeling(&self, element: &crate::dom::DomElement) -> String {
+        if let Some(name) = element.attributes.get("name") {
+            if name == "q" || element.attributes.get("role") == Some(&"searchbox".to_string()) {
+                return "search".to_string();
+            }
+        }
+
+        match element.tag_name.as_str() {
+            "input" => {
+                let default_type = "text".to_string();
+                let input_type = element.attributes.get("type").unwrap_or(&default_type);
+                match input_type.as_str() {
+                    "search" => "search".to_string(),
+                    "submit" | "button" => "button".to_string(),
+                    _ => "input".to_string(),
+                }
+            }
+            "button" => "button".to_string(),
+            "a" => "link".to_string(),
+            _ => "interactive".to_string(),
+        }
+    }
+
+    fn generate_dynamic_label_text(
+        &self,
+        element: &crate::dom::DomElement,
+        number: usize,
+    ) -> String {
+        // Create concise labels for the webpage overlay
+        if let Some(name) = element.attributes.get("name") {
+            if name == "q" {
+                return format!("{} 🔍", number);
+            }
+        }
+
+        if let Some(aria_label) = element.attributes.get("aria-label") {
+            if aria_label.to_lowercase().contains("search") {
+                return format!("{} 🔍", number);
+            }
+        }
+
+        match element.tag_name.as_str() {
+            "input" => {
+                let default_type = "text".to_string();
+                let input_type = element.attributes.get("type").unwrap_or(&default_type);
+                match input_type.as_str() {
+                    "search" => format!("{} 🔍", number),
+                    "submit" | "button" => format!("{} ▶", number),
+                    "email" => format!("{} ✉", number),
+                    "password" => format!("{} 🔒", number),
+                    _ => format!("{} ✏", number),
+                }
+            }
+            "button" => format!("{} ▶", number),
+            "a" => format!("{} 🔗", number),
+            "select" => format!("{} ▼", number),
+            _ => number.to_string(),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use crate::browser::submit::{SubmitOutcome, SubmitResult};
+use crate::browser::typing::TypeMode;
+use crate::core::{BrowserTrait, CallOptions, SessionTrait};
+use crate::errors::Result;
+use crate::utils::{CredentialPurpose, SecretString};
+use std::sync::atomic::Ordering;
+use tracing::Instrument;
+
+use super::{poll_until, BrowserSession, LoginConfig, SessionData};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    pub async fn auto_login_and_extract_session(
+        &self,
+        login_url: &str,
+        username: &str,
+        password: &SecretString,
+        login_config: LoginConfig,
+    ) -> Result<SessionData> {
+        self.auto_login_and_extract_session_with_options(
+            login_url,
+            username,
+            password,
+            login_config,
+            &self.load_options(),
+        )
+        .await
+    }
+
+    /// `auto_login_and_extract_session` with the `CredentialPurpose::Login`
+    /// credential the providers from `with_credential_provider` hold for the
+    /// login URL's host or its closest parent domain. Fails with
+    /// `ConfigurationError` when none has one.
+    pub async fn auto_login_with_credentials(
+        &self,
+        login_url: &str,
+        login_config: LoginConfig,
+    ) -> Result<SessionData> {
+        let host = url::Url::parse(login_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .ok_or_else(|| {
+                crate::errors::BrowserAgentError::ConfigurationError(format!(
+                    "login URL {} has no host",
+                    login_url
+                ))
+            })?;
+        let credential = self
+            .credentials
+            .get(&host, &CredentialPurpose::Login)
+            .await
+            .ok_or_else(|| {
+                crate::errors::BrowserAgentError::ConfigurationError(format!(
+                    "no login credential for {} from providers {:?}",
+                    host,
+                    self.credentials.names()
+                ))
+            })?;
+        self.auto_login_and_extract_session(
+            login_url,
+            &credential.username,
+            &credential.password,
+            login_config,
+        )
+        .await
+    }
+
+    /// `auto_login_and_extract_session` giving the login form and the post-submit
+    /// page `options.timeout_ms` each to appear
+    pub async fn auto_login_and_extract_session_with_options(
+        &self,
+        login_url: &str,
+        username: &str,
+        password: &SecretString,
+        login_config: LoginConfig,
+        options: &CallOptions,
+    ) -> Result<SessionData> {
+        // The login page is expected here, not a sign of being logged out
+        let paused = self.watchdog_paused.swap(true, Ordering::Relaxed);
+        let result = self
+            .auto_login_inner(login_url, username, password, login_config, options)
+            .instrument(self.span())
+            .await;
+        self.watchdog_paused.store(paused, Ordering::Relaxed);
+        if result.is_ok() {
+            self.logged_out.store(false, Ordering::Relaxed);
+        }
+        result
+    }
+
+    async fn auto_login_inner(
+        &self,
+        login_url: &str,
+        username: &str,
+        password: &SecretString,
+        login_config: LoginConfig,
+        options: &CallOptions,
+    ) -> Result<SessionData> {
+        println!("🔐 Starting auto-login process for: {}", login_url);
+
+        self.navigate_and_wait_reactive(login_url).await?;
+
+        let keywords = self.language_pack().await?;
+        println!("🌐 Using {} login keywords", keywords.language);
+        let login_config = login_config.localized(&keywords);
+
+        // A missing field is reported by the fill below
+        let form_script = format!(
+            "{}.some(selector => document.querySelector(selector) !== null)",
+            serde_json::to_string(&login_config.username_selectors)?
+        );
+        poll_until(self.clock.as_ref(), options, || async {
+            Ok(self
+                .execute_script(&form_script)
+                .await?
+                .as_bool()
+                .unwrap_or(false))
+        })
+        .await?;
+
+        self.highlight_interactive_elements().await?;
+
+        let username_filled = self
+            .try_fill_field(&login_config.username_selectors, username, false)
+            .await?;
+        if !username_filled {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(
+                "Username field not found".to_string(),
+            ));
+        }
+
+        let password_filled = self
+            .try_fill_field(
+                &login_config.password_selectors,
+                password.expose_secret(),
+                true,
+            )
+            .await?;
+        if !password_filled {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(
+                "Password field not found".to_string(),
+            ));
+        }
+
+        let submit = match self.first_present(&login_config.submit_selectors).await? {
+            Some(selector) => selector,
+            None => self.find_by_text(&keywords.login).await?.ok_or_else(|| {
+                crate::errors::BrowserAgentError::ElementNotFound(
+                    "Submit button not found".to_string(),
+                )
+            })?,
+        };
+
+        // The submission gets the whole budget, then the success indicators get
+        // whatever is left of it to show up
+        let deadline = self.clock.now() + std::time::Duration::from_millis(options.timeout_ms);
+        let submitted = self.submit_and_wait_with_options(&submit, options).await?;
+        if matches!(
+            submitted.outcome,
+            SubmitOutcome::ValidationError { .. } | SubmitOutcome::NoChange
+        ) {
+            return Err(self
+                .login_failure(&submitted, &login_config.failure_indicators)
+                .await);
+        }
+
+        if let Err(e) = self
+            .wait_for_network_idle(self.config.session.network_idle_ms, options.timeout_ms)
+            .await
+        {
+            println!("⚠️ Network did not settle after submitting login: {}", e);
+        }
+        let remaining = options.clone().with_timeout_ms(
+            deadline
+                .saturating_duration_since(self.clock.now())
+                .as_millis() as u64,
+        );
+        let login_successful = poll_until(self.clock.as_ref(), &remaining, || {
+            self.validate_session(&login_config.success_indicators)
+        })
+        .await?;
+        if !login_successful {
+            return Err(self
+                .login_failure(&submitted, &login_config.failure_indicators)
+                .await);
+        }
+
+        println!("✅ Login successful! Extracting session...");
+
+        let domain = url::Url::parse(login_url)
+            .map_err(|e| crate::errors::BrowserAgentError::ConfigurationError(e.to_string()))?
+            .host_str()
+            .unwrap_or("unknown")
+            .to_string();
+
+        let mut session_data = self.extract_session(&domain).await?;
+
+        session_data.metadata.login_selectors = login_config.username_selectors.clone();
+        session_data.metadata.success_indicators = login_config.success_indicators.clone();
+        session_data.metadata.failure_indicators = login_config.failure_indicators.clone();
+
+        Ok(session_data)
+    }
+
+    /// `LoginFailed` describing the submission, the page it ended on and the
+    /// failure indicators showing there
+    async fn login_failure(
+        &self,
+        submitted: &SubmitResult,
+        failure_indicators: &[String],
+    ) -> crate::errors::BrowserAgentError {
+        let mut reason = format!(
+            "{} after submitting; now on {}",
+            submitted.outcome.describe(),
+            self.current_url()
+                .await
+                .unwrap_or_else(|_| submitted.url.clone())
+        );
+        let showing = match serde_json::to_string(failure_indicators) {
+            Ok(indicators) => self
+                .execute_script(&format!(
+                    "{}.filter(indicator => document.body && document.body.innerText.includes(indicator))",
+                    indicators
+                ))
+                .await
+                .ok()
+                .and_then(|value| serde_json::from_value::<Vec<String>>(value).ok())
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        if !showing.is_empty() {
+            reason.push_str(&format!("; page shows '{}'", showing.join("', '")));
+        }
+        crate::errors::BrowserAgentError::LoginFailed(reason)
+    }
+
+    /// First of `selectors` matching an element on the page
+    async fn first_present(&self, selectors: &[String]) -> Result<Option<String>> {
+        let script = format!(
+            r#"{}.find(selector => {{
+                try {{ return document.querySelector(selector) !== null; }} catch (e) {{ return false; }}
+            }}) || null"#,
+            serde_json::to_string(selectors)?
+        );
+        Ok(self
+            .execute_script(&script)
+            .await?
+            .as_str()
+            .map(str::to_string))
+    }
+
+    /// Selector of the first button, submit input or link whose text or value is one
+    /// of `texts`, ignoring case, preferring controls in the form holding a password field
+    async fn find_by_text(&self, texts: &[String]) -> Result<Option<String>> {
+        let script = format!(
+            r#"
+            (function() {{
+                {unique}
+                const texts = {texts}.map(text => text.toLowerCase());
+                const password = document.querySelector('input[type="password"]');
+                const scopes = [password && password.form, document].filter(Boolean);
+                for (const scope of scopes) {{
+                    const controls = scope.querySelectorAll(
+                        'button, input[type="submit"], input[type="button"], [role="button"], a'
+                    );
+                    for (const el of controls) {{
+                        const text = (el.value || el.textContent || '').trim().replace(/\s+/g, ' ');
+                        if (texts.includes(text.toLowerCase())) return uniqueSelector(el);
+                    }}
+                }}
+                return null;
+            }})()
+            "#,
+            unique = crate::browser::overlay::UNIQUE_SELECTOR_JS,
+            texts = serde_json::to_string(texts)?
+        );
+        Ok(self
+            .execute_script(&script)
+            .await?
+            .as_str()
+            .map(str::to_string))
+    }
+
+    async fn try_fill_field(
+        &self,
+        selectors: &[String],
+        value: &str,
+        secret: bool,
+    ) -> Result<bool> {
+        for selector in selectors {
+            let typed = self
+                .type_text_internal(selector, value, TypeMode::Replace, secret)
+                .await;
+            if typed.is_ok() && self.check_and_refresh_if_needed().await.is_ok() {
+                println!("✅ Filled field with selector: {}", selector);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
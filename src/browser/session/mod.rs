@@ -0,0 +1,1248 @@
+use crate::browser::events::{ConsoleForwarder, EventStream, SessionEvent};
+use crate::browser::keepalive::KeepaliveTask;
+use crate::browser::refresh::RefreshTask;
+use crate::browser::watchdog::ReloginFlow;
+use crate::core::config::HeadlessMode;
+use crate::core::dom::SessionDomProcessor;
+use crate::core::{
+    BrowserCapabilities, BrowserFeature, BrowserTrait, CallOptions, Config, DomProcessorTrait,
+    DomainPolicy, IndexedDbDatabase, JsCoverageOptions, LanguagePack, Selector, SessionTrait,
+    Throttling, UserAgentOverride,
+};
+use crate::dom::labeler::{DefaultLabeler, ElementLabeler};
+use crate::dom::{DomElement, DomProcessor, DomState, ElementRect, LinkKind, OptionInfo};
+use crate::errors::Result;
+use crate::utils::artifacts::{ArtifactSink, LocalDirSink};
+use crate::utils::{
+    default_clock, CancellationToken, Clock, CredentialChain, CredentialProvider, IdGenerator,
+    SharedClock,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::csrf::CsrfManager;
+use super::element_monitor::ElementMonitor;
+use super::handle::SessionHandle;
+use super::pacing::Pacer;
+use super::prefetch::PrefetchCache;
+use super::session_diff::SessionDataDiff;
+use super::state::{SessionState, StateGuard, StateTracker, StateTransition};
+
+// The `BrowserSession` methods of each feature, beside the core kept here
+mod actionability;
+mod csrf;
+mod data;
+mod diagnostics;
+mod documents;
+mod dry_run;
+mod editor;
+mod elements;
+mod emulation;
+mod fetch;
+mod forms;
+mod frames;
+mod highlight;
+#[cfg(feature = "inspector")]
+mod inspector;
+mod keepalive;
+mod labels;
+mod login;
+mod navigation;
+mod network;
+mod overlay;
+mod page;
+mod prefetch;
+mod refresh;
+mod screenshots;
+mod selectors;
+mod submit;
+mod targeting;
+mod typing;
+mod wizard;
+
+#[derive(Debug, Clone)]
+pub struct DynamicLabel {
+    pub number: usize,
+    pub element_id: String,
+    pub selector: String,
+    pub label_text: String,
+    pub label_type: String,
+    pub element_description: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum LabelAction {
+    Click,
+    Type(String),
+}
+
+/// A browser tab plus the state built up while driving it.
+///
+/// Every method takes `&self`, so a session can be shared between tasks through
+/// `SessionHandle`. Mutable state sits behind individual locks: the tab, the
+/// highlight list and the current `SessionData` each have their own `RwLock`, and
+/// the auto-refresh flag is atomic. Locks are only held while reading or replacing
+/// a value, never across a browser round trip, so concurrent calls interleave at
+/// the browser instead of blocking each other.
+pub struct BrowserSession<B: BrowserTrait> {
+    browser: Arc<B>,
+    /// Replaced by `activate_prefetched` and `reload_after_crash`; read it with
+    /// `current_tab`
+    tab: std::sync::RwLock<Option<Arc<B::TabHandle>>>,
+    /// Extraction behind `get_page_state`, `DomProcessor` unless replaced with
+    /// `with_dom_processor`
+    dom_processor: Box<dyn SessionDomProcessor<B>>,
+    /// Annotates `get_ai_elements` results, `DefaultLabeler` unless replaced with
+    /// `with_labeler`
+    labeler: Arc<dyn ElementLabeler>,
+    /// Destination of screenshots and other produced files, `LocalDirSink` unless
+    /// replaced with `with_artifact_sink`
+    artifact_sink: Arc<dyn ArtifactSink>,
+    /// Registered with `with_credential_provider`
+    credentials: CredentialChain,
+    config: Config,
+    element_highlights: RwLock<Vec<ElementHighlight>>,
+    /// Elements from the last `get_ai_elements`, by `element_number - 1`
+    ai_elements: RwLock<Vec<AIElement>>,
+    element_monitor: ElementMonitor,
+    auto_refresh_enabled: AtomicBool,
+    session_id: String,
+    current_session_data: RwLock<Option<SessionData>>,
+    domain_policy: DomainPolicy,
+    cancel_token: std::sync::Mutex<CancellationToken>,
+    state: StateTracker,
+    console_capture: bool,
+    /// The domain policy's `window.open` guard is an init script on the session's
+    /// tabs; otherwise it is run on each document after navigating
+    window_open_guard_init: bool,
+    clock: SharedClock,
+    ids: IdGenerator,
+    /// Children created by `fork`, numbering their ids
+    forks: AtomicUsize,
+    csrf: CsrfManager,
+    last_activity_at: std::sync::Mutex<chrono::DateTime<chrono::Utc>>,
+    keepalive: std::sync::Mutex<Option<KeepaliveTask>>,
+    /// Runs highlight refreshes off the interaction path; `None` refreshes inline
+    refresh: std::sync::Mutex<Option<RefreshTask>>,
+    events: tokio::sync::broadcast::Sender<SessionEvent>,
+    /// Copies console messages into `events`; `None` without console capture
+    console_forwarder: std::sync::Mutex<Option<ConsoleForwarder>>,
+    /// Pages loaded in background tabs by `prefetch`
+    prefetched: tokio::sync::Mutex<PrefetchCache<B::TabHandle>>,
+    /// Set by `set_user_agent`, for the session's tab and the tabs it prefetches
+    user_agent: std::sync::Mutex<Option<UserAgentOverride>>,
+    /// Set by `set_custom_headers`, for the same tabs
+    custom_headers: std::sync::Mutex<HashMap<String, String>>,
+    /// Set by `emulate_network` and `emulate_cpu`, for the same tabs
+    throttling: std::sync::Mutex<Throttling>,
+    /// Options of the coverage recording `start_js_coverage` began, while it runs
+    js_coverage: std::sync::Mutex<Option<JsCoverageOptions>>,
+    /// Document id and reload count at the last keepalive tick
+    keepalive_seen: std::sync::Mutex<Option<(String, usize)>>,
+    /// Reloads the session asked for, so the keepalive does not report them
+    reloads: AtomicUsize,
+    relogin_flow: std::sync::Mutex<Option<ReloginFlow>>,
+    /// Result of the last logout watchdog check
+    logged_out: AtomicBool,
+    /// Set while logging in, when landing on the login page is expected
+    watchdog_paused: AtomicBool,
+    /// Set once `TabCrashed` was published for the tab's current crash
+    crash_reported: AtomicBool,
+    pacer: Pacer,
+    /// Started by `enable_inspector`
+    #[cfg(feature = "inspector")]
+    inspector: std::sync::Mutex<Option<super::inspector::Inspector>>,
+    /// Counts the session in `surfai_active_sessions` until closed
+    active: crate::utils::metrics::ActiveSession,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElementHighlight {
+    pub element_id: String,
+    pub element_number: usize,
+    pub color: String,
+    pub element_type: String,
+    pub css_selector: String,
+    /// Viewport rect of the number label, when the element was drawn
+    pub label_rect: Option<ElementRect>,
+}
+
+/// What `BrowserSession::memory_stats` found the session holding
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    /// Highlights drawn on the current page
+    pub highlights: usize,
+    /// Elements of the last `get_ai_elements` on the current page
+    pub ai_elements: usize,
+    /// DOM change events in the element monitor's history
+    pub change_events: usize,
+    pub prefetched_pages: usize,
+    pub prefetched_bytes: usize,
+    /// Console messages the backend keeps for the tab
+    pub console_messages: usize,
+    /// Approximate size of all of the above, measured as JSON
+    pub approx_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionData {
+    pub session_id: String,
+    pub domain: String,
+    pub url: String,
+    pub cookies: Vec<CookieData>,
+    pub local_storage: HashMap<String, String>,
+    pub session_storage: HashMap<String, String>,
+    pub user_agent: Option<String>,
+    pub viewport: Option<ViewportData>,
+    pub custom_headers: HashMap<String, String>,
+    /// Token-like values with the origin they were read from; injection only
+    /// writes each one back into a frame of the same origin. The older map form
+    /// is still accepted and scoped to `url`.
+    #[serde(default, deserialize_with = "token_entries")]
+    pub auth_tokens: Vec<TokenEntry>,
+    /// localStorage of child frames keyed by frame origin
+    #[serde(default)]
+    pub frame_local_storage: HashMap<String, HashMap<String, String>>,
+    /// localStorage keys whose values were cut at the extraction size limit
+    #[serde(default)]
+    pub truncated_local_storage: Vec<String>,
+    /// sessionStorage keys whose values were cut at the extraction size limit
+    #[serde(default)]
+    pub truncated_session_storage: Vec<String>,
+    /// IndexedDB metadata of the page origin, when enabled in `StorageExtractionOptions`
+    #[serde(default)]
+    pub indexed_db: Vec<IndexedDbDatabase>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub metadata: SessionMetadata,
+}
+
+impl SessionData {
+    /// What changed from this extraction to `other`, with values redacted
+    pub fn diff(&self, other: &SessionData) -> SessionDataDiff {
+        SessionDataDiff::between(self, other, false)
+    }
+
+    /// `diff` showing the values, for local debugging only
+    pub fn diff_revealed(&self, other: &SessionData) -> SessionDataDiff {
+        SessionDataDiff::between(self, other, true)
+    }
+}
+
+/// Where an extracted token was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenSource {
+    LocalStorage,
+    SessionStorage,
+    Cookie,
+    Meta,
+}
+
+/// A token-like value and the origin and frame it was read from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenEntry {
+    /// Storage key, cookie name or meta tag name
+    pub name: String,
+    pub value: String,
+    pub source: TokenSource,
+    /// Origin of the frame holding the token; empty for entries saved before
+    /// origins were recorded
+    pub origin: String,
+    /// URLs of the frames from the top frame down to the holding frame, empty for
+    /// the top frame
+    #[serde(default)]
+    pub frame_path: Vec<String>,
+}
+
+/// `auth_tokens` as a list of entries, or as the older map with `session_`,
+/// `cookie_` and `meta_` key prefixes
+fn token_entries<'de, D>(deserializer: D) -> std::result::Result<Vec<TokenEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tokens {
+        Entries(Vec<TokenEntry>),
+        Legacy(HashMap<String, String>),
+    }
+
+    Ok(match Tokens::deserialize(deserializer)? {
+        Tokens::Entries(entries) => entries,
+        Tokens::Legacy(map) => map
+            .into_iter()
+            .map(|(key, value)| {
+                let (source, name) = [
+                    ("session_", TokenSource::SessionStorage),
+                    ("cookie_", TokenSource::Cookie),
+                    ("meta_", TokenSource::Meta),
+                ]
+                .into_iter()
+                .find_map(|(prefix, source)| {
+                    key.strip_prefix(prefix)
+                        .map(|name| (source, name.to_string()))
+                })
+                .unwrap_or((TokenSource::LocalStorage, key.clone()));
+                TokenEntry {
+                    name,
+                    value,
+                    source,
+                    origin: String::new(),
+                    frame_path: Vec::new(),
+                }
+            })
+            .collect(),
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookieData {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    pub expires: Option<i64>,
+    pub http_only: bool,
+    pub secure: bool,
+    pub same_site: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewportData {
+    pub width: u32,
+    pub height: u32,
+    pub device_scale_factor: f64,
+}
+
+/// Screenshot from `capture_responsive_screenshots`
+#[derive(Debug, Clone)]
+pub struct ResponsiveScreenshot {
+    /// `Viewport::label` of the size it was taken at
+    pub label: String,
+    pub viewport: crate::core::config::Viewport,
+    pub png: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMetadata {
+    pub login_selectors: Vec<String>,
+    pub success_indicators: Vec<String>,
+    pub failure_indicators: Vec<String>,
+    pub csrf_tokens: HashMap<String, String>,
+    pub form_data: HashMap<String, String>,
+}
+
+/// Check `condition` every `poll_interval_ms` until it holds or `timeout_ms` has
+/// passed; the result says which
+async fn poll_until<F, Fut>(
+    clock: &dyn Clock,
+    options: &CallOptions,
+    mut condition: F,
+) -> Result<bool>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<bool>>,
+{
+    let deadline = clock.now() + std::time::Duration::from_millis(options.timeout_ms);
+    loop {
+        if condition().await? {
+            return Ok(true);
+        }
+        let remaining = deadline.saturating_duration_since(clock.now());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+        clock.sleep(options.poll_interval().min(remaining)).await;
+    }
+}
+
+/// Elements from a `{ success, elements, error }` script result, with ids `<prefix>_<n>`
+fn described_elements(
+    result: &serde_json::Value,
+    target: &str,
+    prefix: &str,
+) -> Result<Vec<DomElement>> {
+    if !result
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        let error = result
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error");
+        return Err(crate::errors::BrowserAgentError::InvalidSelector(format!(
+            "{}: {}",
+            target, error
+        )));
+    }
+
+    Ok(result
+        .get("elements")
+        .and_then(|v| v.as_array())
+        .map(|elements| {
+            elements
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    crate::dom::xpath::element_from_value(value, format!("{}_{}", prefix, i + 1))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Evaluates to the `lang` attribute or `Content-Language` meta tag, or `''`
+const PAGE_LANGUAGE_JS: &str = r#"(document.documentElement.lang
+    || (document.querySelector('meta[http-equiv="content-language" i]') || {}).content
+    || '').trim()"#;
+
+fn page_language(result: &serde_json::Value) -> Option<String> {
+    result
+        .as_str()
+        .filter(|language| !language.is_empty())
+        .map(str::to_string)
+}
+
+/// Size of `value` serialized as JSON, as a stand-in for the memory it takes
+fn approx_bytes<T: Serialize + ?Sized>(value: &T) -> usize {
+    serde_json::to_vec(value).map_or(0, |bytes| bytes.len())
+}
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    pub async fn new(mut browser: B, config: Config) -> Result<Self> {
+        browser.launch(&config).await?;
+        let tab = browser.new_tab().await?;
+        let browser = Arc::new(browser);
+        let dom_processor = DomProcessor::new(config.dom.clone());
+        let element_monitor = ElementMonitor::with_history_len(config.session.change_history_len);
+        let ids = IdGenerator::new(config.session.seed);
+        let pacer = Pacer::new(
+            config.session.interaction_profile.pacing(),
+            config.session.seed,
+        );
+        let session_id = config
+            .session
+            .session_id
+            .clone()
+            .unwrap_or_else(|| ids.next_id());
+        let domain_policy = DomainPolicy::from_config(&config.session)?;
+
+        if config.session.restrict_subresources && domain_policy.is_active() {
+            if browser.capabilities().supports_network_interception {
+                browser
+                    .set_request_filter(&tab, domain_policy.clone())
+                    .await?;
+            } else {
+                tracing::warn!(
+                    session_id = %session_id,
+                    backend = browser.backend_name(),
+                    "request interception unavailable; only navigations are checked against the domain policy"
+                );
+            }
+        }
+
+        // Wrapped before page scripts run, so none can keep the original window.open
+        let window_open_guard_init = domain_policy.is_active()
+            && browser
+                .add_init_script(&tab, &domain_policy.window_open_guard_script())
+                .await?;
+
+        let console_capture = browser.start_console_capture(&tab).await?;
+        browser.watch_for_crash(&tab).await?;
+        let events = tokio::sync::broadcast::channel(config.session.event_buffer.max(1)).0;
+        let console_forwarder = browser
+            .subscribe_console(&tab)
+            .await?
+            .map(|console| ConsoleForwarder::spawn(console, events.clone()));
+
+        let csrf = CsrfManager::new(config.session.csrf.clone());
+        Ok(Self {
+            browser,
+            tab: std::sync::RwLock::new(Some(Arc::new(tab))),
+            dom_processor: Box::new(dom_processor),
+            labeler: Arc::new(DefaultLabeler),
+            artifact_sink: Arc::new(LocalDirSink::default()),
+            credentials: CredentialChain::new(),
+            config,
+            element_highlights: RwLock::new(Vec::new()),
+            ai_elements: RwLock::new(Vec::new()),
+            element_monitor,
+            auto_refresh_enabled: AtomicBool::new(true),
+            session_id,
+            current_session_data: RwLock::new(None),
+            domain_policy,
+            cancel_token: std::sync::Mutex::new(CancellationToken::new()),
+            state: StateTracker::new(),
+            console_capture,
+            window_open_guard_init,
+            clock: default_clock(),
+            ids,
+            forks: AtomicUsize::new(0),
+            csrf,
+            last_activity_at: std::sync::Mutex::new(chrono::Utc::now()),
+            keepalive: std::sync::Mutex::new(None),
+            refresh: std::sync::Mutex::new(None),
+            events,
+            console_forwarder: std::sync::Mutex::new(console_forwarder),
+            prefetched: tokio::sync::Mutex::new(PrefetchCache::new()),
+            user_agent: std::sync::Mutex::new(None),
+            custom_headers: std::sync::Mutex::new(HashMap::new()),
+            throttling: std::sync::Mutex::new(Throttling::default()),
+            js_coverage: std::sync::Mutex::new(None),
+            keepalive_seen: std::sync::Mutex::new(None),
+            reloads: AtomicUsize::new(0),
+            relogin_flow: std::sync::Mutex::new(None),
+            logged_out: AtomicBool::new(false),
+            watchdog_paused: AtomicBool::new(false),
+            crash_reported: AtomicBool::new(false),
+            pacer,
+            #[cfg(feature = "inspector")]
+            inspector: std::sync::Mutex::new(None),
+            active: crate::utils::metrics::ActiveSession::new(),
+        })
+    }
+
+    /// Start a session and inject `session_data`. The session keeps the data's
+    /// `session_id` unless `config.session.session_id` names one.
+    pub async fn new_with_session(
+        mut browser: B,
+        mut config: Config,
+        session_data: SessionData,
+    ) -> Result<Self> {
+        if config.session.session_id.is_none() && !session_data.session_id.is_empty() {
+            config.session.session_id = Some(session_data.session_id.clone());
+        }
+        let session = Self::new(browser, config).await?;
+        session.inject_session(session_data).await?;
+        Ok(session)
+    }
+
+    /// Start a second session on `browser` with this session's config and state.
+    ///
+    /// The child gets the id `<parent id>.<n>` and is given the last extracted or
+    /// injected `SessionData`, or a fresh extraction for the current host when there
+    /// is none, so both can explore independently from the same starting point.
+    pub async fn fork_with(&self, browser: B) -> Result<Self> {
+        let child_id = format!(
+            "{}.{}",
+            self.session_id,
+            self.forks.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        let session_data = match self.current_session_data.read().await.clone() {
+            Some(data) => data,
+            None => {
+                let url = url::Url::parse(&self.current_url().await?).map_err(|e| {
+                    crate::errors::BrowserAgentError::NavigationFailed(e.to_string())
+                })?;
+                self.extract_session(url.host_str().unwrap_or_default())
+                    .await?
+            }
+        };
+
+        let mut config = self.config.clone();
+        config.session.session_id = Some(child_id.clone());
+        let mut child = Self::new(browser, config).await?;
+        child.labeler = self.labeler.clone();
+        child.artifact_sink = self.artifact_sink.clone();
+        child.credentials = self.credentials.clone();
+        if let Some(user_agent) = self.user_agent_override() {
+            child.set_user_agent_override(user_agent).await?;
+        }
+        let throttling = self.throttling();
+        if let Some(network) = throttling.network {
+            child.emulate_network(network).await?;
+        }
+        if let Some(slowdown) = throttling.cpu_slowdown {
+            child.emulate_cpu(slowdown).await?;
+        }
+        child
+            .inject_session(SessionData {
+                session_id: child_id,
+                ..session_data
+            })
+            .await?;
+        tracing::info!(
+            session_id = %self.session_id,
+            child_id = %child.session_id,
+            "forked session"
+        );
+        println!(
+            "🍴 Forked session {} into {}",
+            self.session_id, child.session_id
+        );
+        Ok(child)
+    }
+
+    /// Current lifecycle state
+    pub fn state(&self) -> SessionState {
+        self.state.state()
+    }
+
+    /// Subscribe to lifecycle transitions from now on
+    pub fn state_events(&self) -> tokio::sync::broadcast::Receiver<StateTransition> {
+        self.state.subscribe()
+    }
+
+    /// Enter `target` for the duration of an operation, per the configured busy policy
+    pub(crate) async fn enter_state(
+        &self,
+        target: SessionState,
+        reason: &str,
+    ) -> Result<StateGuard<'_>> {
+        self.touch();
+        if target == SessionState::Interacting {
+            self.ensure_alive()?;
+        }
+        self.state
+            .enter(
+                target,
+                reason,
+                self.config.session.busy_policy,
+                self.config.session.navigation_timeout_ms,
+            )
+            .await
+    }
+
+    /// When the session last navigated, interacted or ran a keepalive tick
+    pub fn last_activity_at(&self) -> chrono::DateTime<chrono::Utc> {
+        *self.last_activity_at.lock().unwrap()
+    }
+
+    fn touch(&self) {
+        *self.last_activity_at.lock().unwrap() = chrono::Utc::now();
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.state.state() == SessionState::Closed
+    }
+
+    /// Stream of what happens to the session from now on: navigations, DOM
+    /// changes, console output, highlight refreshes, and events from the keepalive
+    /// and the logout watchdog.
+    ///
+    /// ```no_run
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # async fn run(session: &surfai::DefaultSession) {
+    /// let mut events = session.events();
+    /// while let Some(event) = events.next().await {
+    ///     println!("{:?}", event);
+    /// }
+    /// # }
+    /// ```
+    pub fn events(&self) -> EventStream {
+        EventStream::new(self.events.subscribe())
+    }
+
+    pub(crate) fn publish_event(&self, event: SessionEvent) {
+        match &event {
+            SessionEvent::Navigated { url, success, .. } => {
+                tracing::debug!(session_id = %self.session_id, %url, success, "navigated");
+            }
+            SessionEvent::DomChanged { change_types, .. } => {
+                tracing::debug!(session_id = %self.session_id, ?change_types, "DOM changed");
+            }
+            SessionEvent::HighlightRefreshed { count, .. } => {
+                tracing::debug!(session_id = %self.session_id, count, "highlights refreshed");
+            }
+            // Forwarded without passing through here; lag is per subscriber
+            SessionEvent::ConsoleMessage { .. } | SessionEvent::Lagged { .. } => {}
+            SessionEvent::PageReplaced { url, discarded, .. } => {
+                crate::utils::metrics::page_replaced(&self.session_id, *discarded);
+                tracing::warn!(session_id = %self.session_id, %url, discarded, "page replaced underneath the session");
+                println!(
+                    "⚠️ Page {} was {} underneath the session; page state is lost",
+                    url,
+                    if *discarded {
+                        "discarded and restored"
+                    } else {
+                        "reloaded"
+                    }
+                );
+            }
+            SessionEvent::KeepaliveFailed { error, .. } => {
+                tracing::warn!(session_id = %self.session_id, %error, "keepalive activity failed");
+                println!("⚠️ Keepalive activity failed: {}", error);
+            }
+            SessionEvent::LoggedOut { url, indicator, .. } => {
+                tracing::warn!(session_id = %self.session_id, %url, %indicator, "session logged out");
+                println!("⚠️ Logged out: {} matched '{}'", url, indicator);
+            }
+            SessionEvent::Relogged { url, attempt, .. } => {
+                crate::utils::metrics::relogin(&self.session_id, true);
+                tracing::info!(session_id = %self.session_id, %url, attempt, "logged back in");
+                println!("🔐 Logged back in (attempt {}), now at {}", attempt, url);
+            }
+            SessionEvent::ReloginFailed { error, attempt, .. } => {
+                crate::utils::metrics::relogin(&self.session_id, false);
+                tracing::warn!(session_id = %self.session_id, %error, attempt, "re-login failed");
+                println!("❌ Re-login attempt {} failed: {}", attempt, error);
+            }
+            SessionEvent::TabCrashed { url, .. } => {
+                crate::utils::metrics::tab_crashed(&self.session_id);
+                tracing::warn!(session_id = %self.session_id, %url, "tab crashed");
+                println!("💥 Tab crashed at {}; reload_after_crash to continue", url);
+            }
+        }
+        // Nobody listening is fine
+        let _ = self.events.send(event);
+    }
+
+    /// Native features of the underlying browser backend
+    pub fn capabilities(&self) -> &BrowserCapabilities {
+        self.browser.capabilities()
+    }
+
+    /// Whether the backend implements `feature`, tracing the `fallback` used when not
+    fn supports(&self, feature: BrowserFeature, fallback: &str) -> bool {
+        let supported = self.browser.capabilities().supports(feature);
+        if !supported {
+            tracing::info!(
+                session_id = %self.session_id,
+                %feature,
+                backend = self.browser.backend_name(),
+                fallback,
+                "feature unavailable, using fallback"
+            );
+        }
+        supported
+    }
+
+    /// Fail with `FeatureUnavailable` unless the backend implements `feature`
+    fn require(&self, feature: BrowserFeature) -> Result<()> {
+        if self.browser.capabilities().supports(feature) {
+            Ok(())
+        } else {
+            Err(self.unavailable(feature))
+        }
+    }
+
+    fn unavailable(&self, feature: BrowserFeature) -> crate::errors::BrowserAgentError {
+        tracing::warn!(
+            session_id = %self.session_id,
+            %feature,
+            backend = self.browser.backend_name(),
+            "feature unavailable and no fallback exists"
+        );
+        crate::errors::BrowserAgentError::FeatureUnavailable {
+            feature: feature.to_string(),
+            backend: self.browser.backend_name().to_string(),
+        }
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Span carrying the session id, entered by the session's long-running operations
+    pub fn span(&self) -> tracing::Span {
+        tracing::info_span!("session", session_id = %self.session_id)
+    }
+
+    /// Options for `*_with_options` calls, filled from this session's config
+    pub fn call_options(&self) -> CallOptions {
+        CallOptions::from(&self.config.session)
+    }
+
+    /// Options for calls waiting on a page load, bounded by the navigation timeout
+    fn load_options(&self) -> CallOptions {
+        self.call_options()
+            .with_timeout_ms(self.config.session.navigation_timeout_ms)
+    }
+
+    /// The session's tab. The handle stays usable after the session moves to
+    /// another tab, so a call that started on the old one fails at the browser
+    /// rather than mid-way on the new one.
+    fn current_tab(&self) -> Result<Arc<B::TabHandle>> {
+        self.tab
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or(crate::errors::BrowserAgentError::NoActiveTab)
+    }
+
+    fn ensure_open(&self) -> Result<()> {
+        if self.state.state() == SessionState::Closed {
+            Err(crate::errors::BrowserAgentError::SessionClosed)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// `TabCrashed` once the tab's renderer is gone, publishing
+    /// `SessionEvent::TabCrashed` the first time
+    fn ensure_alive(&self) -> Result<()> {
+        let crashed = self
+            .current_tab()
+            .ok()
+            .and_then(|tab| self.browser.crashed_at(&tab));
+        let Some(url) = crashed else {
+            // The browser may have brought the same tab back, e.g. by navigating it
+            self.crash_reported.store(false, Ordering::Relaxed);
+            return Ok(());
+        };
+        if !self.crash_reported.swap(true, Ordering::Relaxed) {
+            self.publish_event(SessionEvent::TabCrashed {
+                url: url.clone(),
+                timestamp: chrono::Utc::now(),
+            });
+        }
+        Err(crate::errors::BrowserAgentError::TabCrashed(url))
+    }
+
+    /// Token that trips when `cancel_all` is called.
+    ///
+    /// Cancelling the returned token only aborts the operations it was passed to.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel_token.lock().unwrap().child_token()
+    }
+
+    /// Abort every in-flight cancellable operation of this session.
+    ///
+    /// Operations started afterwards are unaffected.
+    pub fn cancel_all(&self) {
+        let mut token = self.cancel_token.lock().unwrap();
+        token.cancel();
+        *token = CancellationToken::new();
+        println!("🛑 Cancelled all in-flight operations");
+    }
+
+    pub fn domain_policy(&self) -> &DomainPolicy {
+        &self.domain_policy
+    }
+
+    pub async fn get_session_data(&self) -> Option<SessionData> {
+        self.current_session_data.read().await.clone()
+    }
+
+    pub fn set_auto_refresh(&self, enabled: bool) {
+        self.auto_refresh_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Replace the time source used for polling, settle delays and timeouts
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Next generated id; reproducible when `session.seed` is set
+    pub(crate) fn next_id(&self) -> String {
+        self.ids.next_id()
+    }
+
+    /// Extract page state with `processor` instead of the built-in `DomProcessor`.
+    ///
+    /// Every caller of `get_page_state` uses it, including highlighting, labels and
+    /// `get_ai_elements`.
+    pub fn with_dom_processor<D: DomProcessorTrait + 'static>(mut self, processor: D) -> Self {
+        self.dom_processor = Box::new(processor);
+        self
+    }
+
+    /// Annotate `get_ai_elements` results with `labeler` instead of `DefaultLabeler`.
+    ///
+    /// Forks keep the labeler.
+    pub fn with_labeler<L: ElementLabeler + 'static>(mut self, labeler: L) -> Self {
+        self.labeler = Arc::new(labeler);
+        self
+    }
+
+    /// Write screenshots and other produced files through `sink` instead of to the
+    /// working directory.
+    ///
+    /// Forks keep the sink.
+    pub fn with_artifact_sink<S: ArtifactSink + 'static>(mut self, sink: S) -> Self {
+        self.artifact_sink = Arc::new(sink);
+        self
+    }
+
+    /// Add a source of login credentials, asked after the ones added before it.
+    ///
+    /// Forks keep the providers.
+    pub fn with_credential_provider<P: CredentialProvider + 'static>(
+        mut self,
+        provider: P,
+    ) -> Self {
+        self.credentials.push(provider);
+        self
+    }
+
+    /// Wrap the session in a cloneable handle that can be shared across tasks
+    pub fn into_handle(self) -> SessionHandle<B> {
+        SessionHandle::new(self)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AIElement {
+    pub id: String,
+    pub element_number: usize,
+    pub tag_name: String,
+    pub element_type: String,
+    pub selector: String,
+    pub xpath: String,
+    pub text_content: Option<String>,
+    pub placeholder: Option<String>,
+    pub label: Option<String>,
+    pub description: String,
+    pub capabilities: Vec<String>,
+    pub attributes: std::collections::HashMap<String, String>,
+    pub is_visible: bool,
+    pub ai_instructions: String,
+    /// From the session's `ElementLabeler`, 0.0 to 1.0
+    #[serde(default)]
+    pub importance: f64,
+    /// Viewport box measured at extraction, for canvases; aim `click_rect` inside it
+    #[serde(default)]
+    pub rect: Option<ElementRect>,
+    /// Options of a select
+    #[serde(default)]
+    pub options: Vec<OptionInfo>,
+    /// A link's `href` resolved against the document's base URI
+    #[serde(default)]
+    pub resolved_href: Option<String>,
+    #[serde(default)]
+    pub link_kind: Option<LinkKind>,
+    #[serde(default)]
+    pub is_disabled: bool,
+    #[serde(default)]
+    pub is_readonly: bool,
+    #[serde(default)]
+    pub is_aria_disabled: bool,
+    /// Live state, filled by `get_elements_info`
+    #[serde(default)]
+    pub state: Option<ElementState>,
+}
+
+/// Live state of an element, read when requested
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ElementState {
+    /// `value` of inputs, selects and textareas
+    pub value: Option<String>,
+    /// Checkbox and radio state
+    pub checked: Option<bool>,
+    pub rect: ElementRect,
+    pub is_visible: bool,
+    /// Neither `disabled` nor `aria-disabled`
+    pub is_enabled: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoginConfig {
+    pub username_selectors: Vec<String>,
+    pub password_selectors: Vec<String>,
+    pub submit_selectors: Vec<String>,
+    pub success_indicators: Vec<String>,
+    pub failure_indicators: Vec<String>,
+}
+
+impl LoginConfig {
+    /// Add field selectors built from the `username` and `password` words of
+    /// `keywords`, and its `success` and `failure` words as indicators.
+    ///
+    /// Submit buttons are found by the `login` words separately, when none of
+    /// `submit_selectors` matches.
+    pub fn localized(mut self, keywords: &LanguagePack) -> Self {
+        let hints = |words: &[String]| {
+            words
+                .iter()
+                .flat_map(|word| {
+                    let word = word.replace('\'', "\\'");
+                    ["name", "id", "placeholder", "aria-label"]
+                        .map(|attribute| format!("input[{}*='{}' i]", attribute, word))
+                })
+                .collect::<Vec<_>>()
+        };
+        let append = |list: &mut Vec<String>, extra: Vec<String>| {
+            for item in extra {
+                if !list.contains(&item) {
+                    list.push(item);
+                }
+            }
+        };
+
+        append(&mut self.username_selectors, hints(&keywords.username));
+        append(&mut self.password_selectors, hints(&keywords.password));
+        append(&mut self.success_indicators, keywords.success.clone());
+        append(&mut self.failure_indicators, keywords.failure.clone());
+        self
+    }
+}
+
+impl Default for LoginConfig {
+    fn default() -> Self {
+        Self {
+            username_selectors: vec![
+                "input[name='username']".to_string(),
+                "input[name='email']".to_string(),
+                "input[type='email']".to_string(),
+                "input[id*='username']".to_string(),
+                "input[id*='email']".to_string(),
+                "input[placeholder*='username']".to_string(),
+                "input[placeholder*='email']".to_string(),
+            ],
+            password_selectors: vec![
+                "input[type='password']".to_string(),
+                "input[name='password']".to_string(),
+                "input[id*='password']".to_string(),
+            ],
+            submit_selectors: vec![
+                "button[type='submit']".to_string(),
+                "input[type='submit']".to_string(),
+                ".login-button".to_string(),
+                ".signin-button".to_string(),
+            ],
+            success_indicators: vec![
+                "dashboard".to_string(),
+                "profile".to_string(),
+                "logout".to_string(),
+                "welcome".to_string(),
+            ],
+            failure_indicators: vec![
+                "error".to_string(),
+                "invalid".to_string(),
+                "incorrect".to_string(),
+                "failed".to_string(),
+            ],
+        }
+    }
+}
+
+#[async_trait]
+impl<B: BrowserTrait> SessionTrait<B> for BrowserSession<B> {
+    async fn new(browser: B, config: Config) -> Result<Self> {
+        Self::new(browser, config).await
+    }
+
+    async fn navigate_and_wait(&mut self, url: &str) -> Result<()> {
+        self.navigate_and_wait_reactive(url).await?;
+        Ok(())
+    }
+
+    async fn get_page_state(&self, include_screenshot: bool) -> Result<DomState> {
+        self.ensure_open()?;
+        self.ensure_alive()?;
+        let tab = &*self.current_tab()?;
+        // PDF and image viewers have no DOM of the document to extract
+        let (kind, url) = self.document_kind_at(tab).await?;
+        if !kind.is_html() {
+            let title = self.browser.get_title(tab).await.unwrap_or_default();
+            let mut state = DomState::new(url, title);
+            state.document_kind = kind;
+            if include_screenshot {
+                let png = self.browser.take_screenshot(tab).await?;
+                state.set_screenshot(base64::Engine::encode(
+                    &base64::engine::general_purpose::STANDARD,
+                    png,
+                ));
+            }
+            return Ok(state);
+        }
+
+        let started = std::time::Instant::now();
+        let state = self
+            .dom_processor
+            .extract_dom_state(self.browser.as_ref(), tab, include_screenshot)
+            .await?;
+        crate::utils::metrics::dom_extraction(&self.session_id, started.elapsed());
+        Ok(state)
+    }
+
+    async fn click<S: Into<Selector> + Send>(&self, selector: S) -> Result<()> {
+        let selector = selector.into();
+        self.click_expression(&selector.element_expression(), &selector.to_string())
+            .await
+    }
+
+    async fn type_text<S: Into<Selector> + Send>(&self, selector: S, text: &str) -> Result<()> {
+        self.type_text_enhanced(selector, text).await
+    }
+
+    async fn execute_script(&self, script: &str) -> Result<serde_json::Value> {
+        self.ensure_open()?;
+        self.ensure_alive()?;
+        let tab = &*self.current_tab()?;
+        self.browser.execute_script(tab, script).await
+    }
+
+    async fn screenshot(&self) -> Result<Vec<u8>> {
+        self.ensure_open()?;
+        self.ensure_alive()?;
+        let tab = &*self.current_tab()?;
+        self.require(BrowserFeature::Screenshots)?;
+        let png = self.browser.take_screenshot(tab).await?;
+        #[cfg(feature = "inspector")]
+        self.inspect(|view| {
+            view.set_screenshot(&png, self.config.browser.viewport.device_scale_factor)
+        });
+        Ok(png)
+    }
+
+    async fn current_url(&self) -> Result<String> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        self.browser.get_url(tab).await
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.ensure_open()?;
+        self.cancel_all();
+        self.set_keepalive(None);
+        self.set_refresh_task(None);
+        self.console_forwarder.lock().unwrap().take();
+        #[cfg(feature = "inspector")]
+        self.disable_inspector();
+        for page in self.prefetched.lock().await.drain() {
+            let _ = self.browser.close_tab(&page.tab).await;
+        }
+        self.state.close("close");
+        self.active.release();
+        self.clear_element_highlights().await?;
+        self.element_monitor
+            .stop_monitoring(self.browser.as_ref(), &*self.current_tab()?)
+            .await?;
+        Ok(())
+    }
+}
+impl BrowserSession<crate::browser::ChromeBrowser> {
+    /// Quick builder for common use cases
+    pub async fn quick_start() -> Result<Self> {
+        let config = Config::default();
+        let browser = crate::browser::ChromeBrowser::new();
+        Self::new(browser, config).await
+    }
+
+    /// Quick builder for demos with visible browser
+    pub async fn demo_mode() -> Result<Self> {
+        let mut config = Config::default();
+        config.browser.headless = HeadlessMode::Off;
+        config.browser.viewport.width = 1920;
+        config.browser.viewport.height = 1080;
+        config.dom.enable_ai_labels = true;
+        config.dom.extract_all_elements = true;
+        config.features.enable_highlighting = true;
+        config.features.enable_state_tracking = true;
+
+        let browser = crate::browser::ChromeBrowser::new();
+        let session = Self::new(browser, config).await?;
+        session.set_auto_refresh(true);
+        Ok(session)
+    }
+
+    /// Quick builder with custom config
+    pub async fn with_config(config: Config) -> Result<Self> {
+        let browser = crate::browser::ChromeBrowser::new();
+        Self::new(browser, config).await
+    }
+
+    /// `fork_with` on a newly launched Chrome
+    pub async fn fork(&self) -> Result<Self> {
+        self.fork_with(crate::browser::ChromeBrowser::new()).await
+    }
+
+    /// **Unstable.** Send any protocol method to the session's tab, as
+    /// `ChromeBrowser::call_method_raw`
+    pub async fn call_method_raw(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let tab = &*self.current_tab()?;
+        self.browser.call_method_raw(tab, method, params).await
+    }
+
+    /// **Unstable.** Browser-wide protocol method, as
+    /// `ChromeBrowser::call_browser_method_raw`
+    pub async fn call_browser_method_raw(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.browser.call_browser_method_raw(method, params).await
+    }
+
+    /// **Unstable.** Protocol events from the session's tab whose method starts
+    /// with `prefix`, as `ChromeBrowser::subscribe_raw`
+    pub async fn subscribe_raw(
+        &self,
+        prefix: &str,
+    ) -> Result<crate::browser::RawEventSubscription> {
+        let tab = &*self.current_tab()?;
+        self.browser.subscribe_raw(tab, prefix).await
+    }
+}
+
+#[cfg(feature = "webdriver")]
+impl BrowserSession<crate::browser::WebDriverBrowser> {
+    /// Session on the WebDriver remote end at `browser.webdriver_url`
+    pub async fn with_webdriver(config: Config) -> Result<Self> {
+        Self::new(crate::browser::WebDriverBrowser::new(), config).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_token_maps_load_without_an_origin() {
+        let tokens: Vec<TokenEntry> = token_entries(serde_json::json!({
+            "access_token": "a",
+            "session_refresh_token": "r",
+            "cookie_auth": "c",
+            "meta_csrf-token": "m",
+        }))
+        .unwrap();
+        let mut found: Vec<(&str, TokenSource, &str)> = tokens
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.source, entry.origin.as_str()))
+            .collect();
+        found.sort_by_key(|(name, _, _)| *name);
+        assert_eq!(
+            found,
+            [
+                ("access_token", TokenSource::LocalStorage, ""),
+                ("auth", TokenSource::Cookie, ""),
+                ("csrf-token", TokenSource::Meta, ""),
+                ("refresh_token", TokenSource::SessionStorage, ""),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn poll_until_runs_on_the_clock() {
+        let clock = crate::utils::VirtualClock::start();
+        let started = std::time::Instant::now();
+        let options = CallOptions::default()
+            .with_timeout_ms(60_000)
+            .with_poll_interval_ms(1_000);
+
+        let mut checks = 0;
+        let held = poll_until(&clock, &options, || {
+            checks += 1;
+            let done = checks == 4;
+            async move { Ok(done) }
+        })
+        .await
+        .unwrap();
+        assert!(held);
+        assert_eq!(clock.elapsed().as_secs(), 3);
+
+        // The last sleep is cut short at the deadline
+        let options = options.with_poll_interval_ms(7_000);
+        let held = poll_until(&clock, &options, || async { Ok(false) })
+            .await
+            .unwrap();
+        assert!(!held);
+        assert_eq!(clock.elapsed().as_secs(), 63);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+}
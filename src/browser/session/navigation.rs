@@ -0,0 +1,370 @@
+use crate::browser::events::{ConsoleForwarder, SessionEvent};
+use crate::browser::inject::InjectOptions;
+use crate::browser::keepalive::{self};
+use crate::browser::navigation::{NavigationManager, NavigationResult};
+use crate::browser::state::SessionState;
+use crate::browser::watchdog::{self};
+use crate::core::BrowserTrait;
+use crate::errors::Result;
+use crate::utils::{run_cancellable, CancellationToken};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tracing::Instrument;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    pub async fn navigate_and_wait_reactive(&self, url: &str) -> Result<NavigationResult> {
+        self.navigate_smart(url).await
+    }
+
+    pub async fn navigate_smart(&self, url: &str) -> Result<NavigationResult> {
+        let token = self.cancellation_token();
+        self.navigate_smart_with_cancel(url, &token).await
+    }
+
+    /// `navigate_smart` that gives up with `Cancelled` once `token` trips
+    pub async fn navigate_smart_with_cancel(
+        &self,
+        url: &str,
+        token: &CancellationToken,
+    ) -> Result<NavigationResult> {
+        let started = std::time::Instant::now();
+        let result = run_cancellable(
+            token,
+            "navigate_smart",
+            self.navigate_smart_inner(url).instrument(self.span()),
+        )
+        .await;
+
+        let outcome = match &result {
+            Ok(nav_result) if nav_result.success => "success",
+            Ok(_) => "no_content",
+            Err(crate::errors::BrowserAgentError::TimeoutError(_)) => "timeout",
+            Err(crate::errors::BrowserAgentError::Cancelled(_)) => "cancelled",
+            Err(_) => "error",
+        };
+        crate::utils::metrics::navigation(&self.session_id, outcome, started.elapsed());
+
+        if outcome == "success" && self.csrf.enabled() {
+            if let Err(e) = self.refresh_csrf().await {
+                tracing::debug!(session_id = %self.session_id, %e, "could not read the CSRF token");
+                self.csrf.clear();
+            }
+        }
+
+        if let Err(crate::errors::BrowserAgentError::Cancelled(_)) = &result {
+            // Stop whatever the page was still loading
+            if let Ok(tab) = self.current_tab() {
+                let _ = self.browser.execute_script(&tab, "window.stop()").await;
+            }
+        }
+
+        result
+    }
+
+    async fn navigate_smart_inner(&self, url: &str) -> Result<NavigationResult> {
+        let _state = self
+            .enter_state(SessionState::Navigating, "navigate")
+            .await?;
+        let tab = &*self.current_tab()?;
+
+        self.domain_policy.check(url)?;
+
+        println!("🚀 Smart navigating to: {}", url);
+        if self.config.dom.stamp_elements {
+            let _ = self.clear_element_stamps().await;
+        }
+
+        let track_network = self.config.session.wait_for_network_idle
+            && self.browser.start_network_tracking(tab).await?;
+
+        if self.config.session.bypass_service_worker
+            && !self.browser.set_bypass_service_worker(tab, true).await?
+        {
+            println!("⚠️ Service worker bypass is not supported by this browser");
+        }
+
+        // Start navigation
+        self.browser.navigate(tab, url).await?;
+
+        // Use dynamic navigation detection
+        let mut nav_result = NavigationManager::wait_for_navigation_complete(
+            self.browser.as_ref(),
+            tab,
+            self.clock.as_ref(),
+            self.config.session.navigation_timeout_ms,
+        )
+        .await?;
+
+        if track_network {
+            match self
+                .browser
+                .wait_for_network_idle(
+                    tab,
+                    self.clock.as_ref(),
+                    self.config.session.network_idle_ms,
+                    self.config.session.navigation_timeout_ms,
+                )
+                .await
+            {
+                Ok(stats) => {
+                    println!(
+                        "🌐 Network idle after {}ms ({} requests, longest {}ms)",
+                        stats.waited_ms, stats.requests_seen, stats.longest_request_ms
+                    );
+                    nav_result.network_quiet = true;
+                    nav_result.network_idle = Some(stats);
+                }
+                Err(crate::errors::BrowserAgentError::TimeoutError(msg)) => {
+                    println!("⚠️ {}", msg);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        println!(
+            "✅ Navigation completed: {} | Quality: {} | Load time: {}ms | Reason: {}",
+            nav_result.url,
+            nav_result.load_quality(),
+            nav_result.actual_load_time,
+            nav_result.reason
+        );
+
+        nav_result.throttling = self.active_throttling();
+        self.finish_navigation(tab, &nav_result, url).await?;
+        Ok(nav_result)
+    }
+
+    /// What follows every navigation: the domain policy, DOM monitoring and
+    /// highlights, the `Navigated` event and the logout watchdog
+    async fn finish_navigation(
+        &self,
+        tab: &B::TabHandle,
+        nav_result: &NavigationResult,
+        url: &str,
+    ) -> Result<()> {
+        // Highlights and numbered elements point into the previous document
+        self.element_highlights.write().await.clear();
+        self.ai_elements.write().await.clear();
+        #[cfg(feature = "inspector")]
+        self.inspect(|view| view.page_changed(&nav_result.url));
+
+        // Redirects may have landed somewhere the policy forbids
+        self.domain_policy.check(&nav_result.url)?;
+
+        self.run_window_open_guard(tab).await?;
+
+        // Only start monitoring if navigation was successful, on a page with a DOM
+        if nav_result.has_content && nav_result.document_kind.is_html() {
+            self.element_monitor
+                .start_monitoring(self.browser.as_ref(), tab)
+                .await?;
+
+            if self.auto_refresh_enabled.load(Ordering::Relaxed) {
+                let _ = self.refresh_elements_after_change().await;
+            }
+        }
+
+        self.publish_event(SessionEvent::Navigated {
+            url: nav_result.url.clone(),
+            success: nav_result.success,
+            duration_ms: nav_result.duration_ms,
+            timestamp: chrono::Utc::now(),
+        });
+        self.watch_after_navigation(url).await?;
+
+        Ok(())
+    }
+
+    /// Go back one entry in the tab's history. A page restored from the
+    /// back-forward cache fires no load events; it is reported with reason
+    /// `bfcache_restore`, and DOM monitoring is checked to still be in place.
+    pub async fn back(&self) -> Result<NavigationResult> {
+        self.traverse_history(-1).await
+    }
+
+    /// Go forward one entry in the tab's history, like `back`
+    pub async fn forward(&self) -> Result<NavigationResult> {
+        self.traverse_history(1).await
+    }
+
+    async fn traverse_history(&self, delta: i32) -> Result<NavigationResult> {
+        let direction = if delta < 0 { "back" } else { "forward" };
+        let _state = self
+            .enter_state(SessionState::Navigating, direction)
+            .await?;
+        let tab = &*self.current_tab()?;
+
+        let before = self.browser.get_url(tab).await?;
+        println!("🔙 Going {} from {}", direction, before);
+        if self.config.dom.stamp_elements {
+            let _ = self.clear_element_stamps().await;
+        }
+        self.browser
+            .execute_script(tab, &format!("history.go({}); true", delta))
+            .await?;
+
+        // The script returns before the traversal commits; wait for the tab to
+        // show the other entry so detection runs in the right document
+        let deadline = self.clock.now()
+            + std::time::Duration::from_millis(self.config.session.navigation_timeout_ms);
+        while self.browser.get_url(tab).await? == before {
+            if self.clock.now() >= deadline {
+                return Err(crate::errors::BrowserAgentError::NavigationFailed(format!(
+                    "no history entry to go {} to from {}",
+                    direction, before
+                )));
+            }
+            self.clock.sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        let mut nav_result = NavigationManager::wait_for_navigation_complete(
+            self.browser.as_ref(),
+            tab,
+            self.clock.as_ref(),
+            self.config.session.navigation_timeout_ms,
+        )
+        .await?;
+        nav_result.throttling = self.active_throttling();
+        println!(
+            "✅ Navigation completed: {} | Quality: {} | Load time: {}ms | Reason: {}",
+            nav_result.url,
+            nav_result.load_quality(),
+            nav_result.actual_load_time,
+            nav_result.reason
+        );
+
+        let url = nav_result.url.clone();
+        self.finish_navigation(tab, &nav_result, &url).await?;
+        Ok(nav_result)
+    }
+
+    /// Run the logout watchdog after navigating to `url`, if configured to
+    pub(super) async fn watch_after_navigation(&self, url: &str) -> Result<()> {
+        let watchdog_config = &self.config.session.watchdog;
+        // Asking for the login page is not being logged out
+        if watchdog_config.check_after_navigation
+            && watchdog::matching_url_pattern(watchdog_config, url)?.is_none()
+        {
+            if let Err(e) = self.check_logged_out().await {
+                println!("⚠️ Logout check failed: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the session to a new tab after its renderer crashed and load the page
+    /// the crashed tab showed.
+    ///
+    /// The new tab gets the request filter, overrides, throttling and keepalive
+    /// stamp of the old one, and the session data last extracted or injected is
+    /// injected again before navigating. Anything else the page held, such as form
+    /// input, is lost. On a tab that did not crash this reloads its page in a
+    /// fresh tab.
+    ///
+    /// Other calls on the session wait, or fail, per the busy policy while the tab
+    /// is replaced; a background keepalive carries on with the new tab.
+    pub async fn reload_after_crash(&self) -> Result<NavigationResult> {
+        self.ensure_open()?;
+        let url = {
+            let _state = self
+                .enter_state(SessionState::Recovering, "reload_after_crash")
+                .await?;
+            let previous = self.current_tab()?;
+            let url = match self.browser.crashed_at(&previous) {
+                Some(url) => url,
+                None => self.browser.get_url(&previous).await?,
+            };
+            println!("🩹 Reloading {} in a new tab", url);
+
+            let tab = self.open_replacement_tab().await?;
+            let forwarder = self
+                .browser
+                .subscribe_console(&tab)
+                .await?
+                .map(|console| ConsoleForwarder::spawn(console, self.events.clone()));
+            *self.console_forwarder.lock().unwrap() = forwarder;
+            self.tab.write().unwrap().replace(Arc::new(tab));
+            self.element_highlights.write().await.clear();
+            self.ai_elements.write().await.clear();
+            *self.keepalive_seen.lock().unwrap() = None;
+            self.crash_reported.store(false, Ordering::Relaxed);
+            if let Err(e) = self.browser.close_tab(&previous).await {
+                println!("⚠️ Could not close the crashed tab: {}", e);
+            }
+            url
+        };
+
+        let session_data = self.current_session_data.read().await.clone();
+        if let Some(session_data) = session_data {
+            let options = InjectOptions {
+                reload: false,
+                ..InjectOptions::default()
+            };
+            self.inject_session_with_options(session_data, &options)
+                .await?;
+        }
+        self.navigate_smart(&url).await
+    }
+
+    /// New tab set up the way `new` and the setters set up the session's tab
+    async fn open_replacement_tab(&self) -> Result<B::TabHandle> {
+        let tab = self.browser.new_tab().await?;
+        if let Err(e) = self.prepare_replacement_tab(&tab).await {
+            let _ = self.browser.close_tab(&tab).await;
+            return Err(e);
+        }
+        Ok(tab)
+    }
+
+    pub(super) async fn prepare_replacement_tab(&self, tab: &B::TabHandle) -> Result<()> {
+        if self.config.session.restrict_subresources
+            && self.domain_policy.is_active()
+            && self.browser.capabilities().supports_network_interception
+        {
+            self.browser
+                .set_request_filter(tab, self.domain_policy.clone())
+                .await?;
+        }
+        self.register_window_open_guard(tab).await?;
+        if self.console_capture {
+            self.browser.start_console_capture(tab).await?;
+        }
+        if self.user_agent_override().is_some() || !self.custom_headers.lock().unwrap().is_empty() {
+            self.apply_request_overrides(tab).await?;
+        }
+        let throttling = self.throttling();
+        if throttling.is_active() {
+            self.apply_throttling(tab, &throttling).await?;
+        }
+        let keepalive_running = self.keepalive.lock().unwrap().is_some();
+        if keepalive_running {
+            self.browser
+                .add_init_script(tab, &keepalive::init_script(&self.session_id))
+                .await?;
+        }
+        self.browser.watch_for_crash(tab).await?;
+        Ok(())
+    }
+    /// Register the domain policy's `window.open` guard for every document `tab`
+    /// loads from now on
+    pub(super) async fn register_window_open_guard(&self, tab: &B::TabHandle) -> Result<()> {
+        if self.domain_policy.is_active() && self.window_open_guard_init {
+            self.browser
+                .add_init_script(tab, &self.domain_policy.window_open_guard_script())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Guard the document just loaded in `tab`, on backends without init scripts
+    pub(super) async fn run_window_open_guard(&self, tab: &B::TabHandle) -> Result<()> {
+        if self.domain_policy.is_active() && !self.window_open_guard_init {
+            self.browser
+                .execute_script(tab, &self.domain_policy.window_open_guard_script())
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use crate::browser::network::{ResponseSubscription, WebSocketSubscription};
+use crate::core::{
+    BrowserFeature, BrowserTrait, CallOptions, CapturedResponse, FrameTarget, NetworkIdleStats,
+    ResponseMatcher, WsConnectionStats,
+};
+use crate::errors::Result;
+use crate::utils::run_cancellable;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Wait until no request has been in flight for `idle_ms`.
+    ///
+    /// Only requests started after this call are counted in the returned stats.
+    pub async fn wait_for_network_idle(
+        &self,
+        idle_ms: u64,
+        timeout_ms: u64,
+    ) -> Result<NetworkIdleStats> {
+        let tab = &*self.current_tab()?;
+
+        self.browser.start_network_tracking(tab).await?;
+        let stats = run_cancellable(
+            &self.cancellation_token(),
+            "wait_for_network_idle",
+            self.browser
+                .wait_for_network_idle(tab, self.clock.as_ref(), idle_ms, timeout_ms),
+        )
+        .await?;
+
+        println!(
+            "🌐 Network idle after {}ms | Requests: {} | Longest: {}ms {}",
+            stats.waited_ms,
+            stats.requests_seen,
+            stats.longest_request_ms,
+            stats.longest_request_url.as_deref().unwrap_or("")
+        );
+
+        Ok(stats)
+    }
+
+    /// Subscribe to XHR and fetch responses accepted by `matcher`, with bodies parsed
+    /// as JSON where possible.
+    ///
+    /// Subscribe before triggering the request; responses that completed earlier are
+    /// not replayed.
+    pub async fn subscribe_responses(
+        &self,
+        matcher: ResponseMatcher,
+    ) -> Result<ResponseSubscription> {
+        let tab = &*self.current_tab()?;
+        self.require(BrowserFeature::RequestInterception)?;
+        let receiver = self
+            .browser
+            .subscribe_responses(tab, self.config.session.max_response_body_bytes)
+            .await?
+            .ok_or_else(|| self.unavailable(BrowserFeature::RequestInterception))?;
+        Ok(ResponseSubscription::new(receiver, matcher))
+    }
+
+    /// Wait up to `timeout_ms` for the next response accepted by `matcher`
+    pub async fn wait_for_response(
+        &self,
+        matcher: ResponseMatcher,
+        timeout_ms: u64,
+    ) -> Result<CapturedResponse> {
+        self.wait_for_response_with_options(
+            matcher,
+            &self.call_options().with_timeout_ms(timeout_ms),
+        )
+        .await
+    }
+
+    /// `wait_for_response` bounded by `options.timeout_ms`
+    pub async fn wait_for_response_with_options(
+        &self,
+        matcher: ResponseMatcher,
+        options: &CallOptions,
+    ) -> Result<CapturedResponse> {
+        let timeout_ms = options.timeout_ms;
+        let mut subscription = self.subscribe_responses(matcher).await?;
+        let description = subscription.matcher().to_string();
+
+        match tokio::time::timeout(
+            std::time::Duration::from_millis(timeout_ms),
+            subscription.next(),
+        )
+        .await
+        {
+            Ok(Some(response)) => {
+                println!(
+                    "📥 Captured {} {} ({})",
+                    response.method, response.url, response.status
+                );
+                Ok(response)
+            }
+            Ok(None) => Err(crate::errors::BrowserAgentError::ChromeError(
+                "response capture stopped".to_string(),
+            )),
+            Err(_) => Err(crate::errors::BrowserAgentError::TimeoutError(format!(
+                "no response matching {} within {}ms",
+                description, timeout_ms
+            ))),
+        }
+    }
+
+    /// Observe WebSocket frames sent and received by the page, optionally only on
+    /// sockets whose URL matches a glob (`*` wildcard).
+    ///
+    /// Observation is read-only; frames cannot be altered or injected.
+    pub async fn observe_websockets(
+        &self,
+        url_pattern: Option<&str>,
+    ) -> Result<WebSocketSubscription> {
+        let tab = &*self.current_tab()?;
+        let url_pattern = url_pattern
+            .map(|pattern| regex::Regex::new(&FrameTarget::glob_to_regex(pattern)))
+            .transpose()
+            .map_err(|e| crate::errors::BrowserAgentError::ConfigurationError(e.to_string()))?;
+        self.require(BrowserFeature::RequestInterception)?;
+        let receiver = self
+            .browser
+            .subscribe_websockets(tab, self.config.session.max_websocket_payload_bytes)
+            .await?
+            .ok_or_else(|| self.unavailable(BrowserFeature::RequestInterception))?;
+        Ok(WebSocketSubscription::new(receiver, url_pattern))
+    }
+
+    /// Frame and byte counts per WebSocket connection seen by the page
+    pub async fn websocket_stats(&self) -> Result<Vec<WsConnectionStats>> {
+        let tab = &*self.current_tab()?;
+        self.browser.websocket_stats(tab).await
+    }
+}
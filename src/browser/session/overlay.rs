@@ -0,0 +1,212 @@
+use crate::browser::overlay::{
+    DismissMethod, DismissOutcome, DismissStrategy, Occlusion, Overlay, OverlayKind,
+};
+use crate::core::{
+    BrowserFeature, BrowserTrait, ConsentChoice, LanguagePack, SessionTrait, SiteProfile,
+};
+use crate::errors::Result;
+
+use super::{page_language, BrowserSession, PAGE_LANGUAGE_JS};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Language the page declares in its `lang` attribute or a `Content-Language`
+    /// meta tag, if any
+    pub async fn page_language(&self) -> Result<Option<String>> {
+        Ok(page_language(&self.execute_script(PAGE_LANGUAGE_JS).await?))
+    }
+
+    /// Keywords for text-based detection on the current page: those of
+    /// `session.language`, or of `page_language` when unset, followed by English
+    pub async fn language_pack(&self) -> Result<LanguagePack> {
+        let language = match &self.config.session.language {
+            Some(language) => Some(language.clone()),
+            None => self.page_language().await?,
+        };
+        Ok(LanguagePack::resolve(
+            language.as_deref(),
+            &self.config.session.language_packs,
+        ))
+    }
+
+    /// Positioned elements covering at least `overlay_min_coverage` of the viewport,
+    /// topmost first
+    pub async fn detect_overlays(&self) -> Result<Vec<Overlay>> {
+        let tab = &*self.current_tab()?;
+        let keywords = self.language_pack().await?;
+        let script = crate::browser::overlay::detect_script(
+            self.config.session.overlay_min_coverage,
+            &keywords,
+        );
+        let result = self.browser.execute_script(tab, &script).await?;
+        Ok(crate::browser::overlay::overlays_from_value(result))
+    }
+
+    /// Try to remove `overlay`, checking after each attempt whether it is gone
+    pub async fn dismiss_overlay(
+        &self,
+        overlay: &Overlay,
+        strategy: DismissStrategy,
+    ) -> Result<DismissOutcome> {
+        let tab = &*self.current_tab()?;
+
+        let mut plan = Vec::new();
+        if matches!(
+            strategy,
+            DismissStrategy::Auto | DismissStrategy::CloseButton
+        ) {
+            if let Some(close) = &overlay.close_selector {
+                plan.push(DismissMethod::CloseButton(close.clone()));
+            }
+        }
+        if matches!(strategy, DismissStrategy::Auto | DismissStrategy::EscapeKey) {
+            plan.push(DismissMethod::EscapeKey);
+        }
+        if matches!(strategy, DismissStrategy::Auto | DismissStrategy::Hide) {
+            plan.push(DismissMethod::Hidden);
+        }
+
+        let visible_script = crate::browser::overlay::visible_script(&overlay.selector);
+        let mut attempts = Vec::new();
+        for method in plan {
+            let attempt = match &method {
+                DismissMethod::CloseButton(selector) => {
+                    attempts.push(format!("clicked close button {}", selector));
+                    let expression = format!(
+                        "document.querySelector({})",
+                        serde_json::to_string(selector)?
+                    );
+                    self.click_expression(&expression, selector).await
+                }
+                DismissMethod::EscapeKey => {
+                    attempts.push("pressed Escape".to_string());
+                    if self.supports(BrowserFeature::NativeInput, "synthetic key events") {
+                        self.browser.press_key(tab, "Escape").await
+                    } else {
+                        match crate::core::browser::press_key_script("Escape") {
+                            Ok(script) => {
+                                self.browser.execute_script(tab, &script).await.map(|_| ())
+                            }
+                            Err(e) => Err(e),
+                        }
+                    }
+                }
+                DismissMethod::Hidden => {
+                    attempts.push("hid the overlay".to_string());
+                    self.browser
+                        .execute_script(
+                            tab,
+                            &crate::browser::overlay::hide_script(&overlay.selector),
+                        )
+                        .await
+                        .map(|_| ())
+                }
+            };
+            if let Err(e) = attempt {
+                attempts.push(format!("failed: {}", e));
+                continue;
+            }
+
+            // Give close animations a moment to finish
+            self.clock
+                .sleep(tokio::time::Duration::from_millis(300))
+                .await;
+            let still_visible = self
+                .browser
+                .execute_script(tab, &visible_script)
+                .await?
+                .as_bool()
+                .unwrap_or(false);
+            if !still_visible {
+                println!(
+                    "🧹 Dismissed {:?} overlay {}: {}",
+                    overlay.kind,
+                    overlay.selector,
+                    attempts.join(", ")
+                );
+                return Ok(DismissOutcome {
+                    overlay: overlay.clone(),
+                    method: Some(method),
+                    attempts,
+                });
+            }
+        }
+
+        println!(
+            "⚠️ Could not dismiss overlay {} ({})",
+            overlay.selector,
+            attempts.join(", ")
+        );
+        Ok(DismissOutcome {
+            overlay: overlay.clone(),
+            method: None,
+            attempts,
+        })
+    }
+
+    /// Detect and dismiss every overlay currently on the page
+    pub async fn dismiss_overlays(&self, strategy: DismissStrategy) -> Result<Vec<DismissOutcome>> {
+        let mut outcomes = Vec::new();
+        for overlay in self.detect_overlays().await? {
+            outcomes.push(self.dismiss_overlay(&overlay, strategy).await?);
+        }
+        Ok(outcomes)
+    }
+
+    /// Site profiles from `dom.site_profiles` matching the current page, merged
+    pub async fn site_profile(&self) -> Result<Option<SiteProfile>> {
+        let url = self.current_url().await?;
+        Ok(SiteProfile::resolve(&self.config.dom.site_profiles, &url))
+    }
+
+    /// Handle consent and cookie notices as the site profile of the current page
+    /// chooses, dismissing them when it does not. Returns whether a notice was
+    /// clicked away, closed or hidden.
+    pub async fn handle_consent(&self) -> Result<bool> {
+        let choice = self
+            .site_profile()
+            .await?
+            .and_then(|profile| profile.consent)
+            .unwrap_or_default();
+        let strategy = match choice {
+            ConsentChoice::Ignore => return Ok(false),
+            ConsentChoice::Dismiss => DismissStrategy::Auto,
+            ConsentChoice::Hide => DismissStrategy::Hide,
+            ConsentChoice::Click(selector) => {
+                let present = self
+                    .execute_script(&format!(
+                        "!!document.querySelector({})",
+                        serde_json::to_string(&selector)?
+                    ))
+                    .await?;
+                if !present.as_bool().unwrap_or(false) {
+                    return Ok(false);
+                }
+                SessionTrait::click(self, &selector).await?;
+                println!("🍪 Clicked consent control {}", selector);
+                return Ok(true);
+            }
+        };
+
+        let mut handled = false;
+        for overlay in self.detect_overlays().await? {
+            if overlay.kind == OverlayKind::CookieBanner {
+                handled |= self
+                    .dismiss_overlay(&overlay, strategy)
+                    .await?
+                    .method
+                    .is_some();
+            }
+        }
+        Ok(handled)
+    }
+
+    /// Elements among `selectors` whose center point is covered by another element
+    pub async fn check_occlusion(&self, selectors: &[String]) -> Result<Vec<Occlusion>> {
+        let tab = &*self.current_tab()?;
+        let result = self
+            .browser
+            .execute_script(tab, &crate::browser::overlay::occlusion_script(selectors))
+            .await?;
+        Ok(serde_json::from_value(result).unwrap_or_default())
+    }
+}
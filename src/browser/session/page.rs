@@ -0,0 +1,230 @@
+use crate::core::BrowserTrait;
+use crate::dom::{DomElement, ScrollAlignment, TextMatch, TextSearchOptions};
+use crate::errors::Result;
+
+use super::{described_elements, poll_until, BrowserSession};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Bring the first element matching `selector` into the viewport, scrolling each
+    /// scrollable ancestor from the nearest outwards and then the page
+    pub async fn scroll_to_element(
+        &self,
+        selector: &str,
+        alignment: ScrollAlignment,
+    ) -> Result<()> {
+        let tab = &*self.current_tab()?;
+        let expression = format!(
+            "document.querySelector({})",
+            serde_json::to_string(selector)?
+        );
+        self.pace().await;
+        self.scroll_window_eased(&expression, alignment).await?;
+        let result = self
+            .browser
+            .execute_script(
+                tab,
+                &crate::dom::scroll::scroll_into_view_script(&expression, alignment),
+            )
+            .await?;
+        if !result
+            .get("found")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(
+                selector.to_string(),
+            ));
+        }
+        let containers = result
+            .get("containers")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        if !result
+            .get("inViewport")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            println!(
+                "⚠️ {} is still outside the viewport after scrolling {} container(s)",
+                selector, containers
+            );
+        }
+        Ok(())
+    }
+
+    /// Scroll the window towards the element in the profile's eased steps, leaving
+    /// the exact placement to `scroll_into_view_script`
+    async fn scroll_window_eased(
+        &self,
+        element_expression: &str,
+        alignment: ScrollAlignment,
+    ) -> Result<()> {
+        if !self.pacer.eases_scrolling() {
+            return Ok(());
+        }
+        let tab = &*self.current_tab()?;
+        let offset = self
+            .browser
+            .execute_script(
+                tab,
+                &crate::dom::scroll::window_scroll_offset_script(element_expression, alignment),
+            )
+            .await?;
+        let top = offset.get("top").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let left = offset.get("left").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        self.scroll_window_by(left, top).await
+    }
+
+    /// Scroll the window by (`left`, `top`) CSS pixels, in the profile's eased steps
+    /// when it has them
+    pub(super) async fn scroll_window_by(&self, left: f64, top: f64) -> Result<()> {
+        let tab = &*self.current_tab()?;
+        let steps = if self.pacer.eases_scrolling() {
+            self.pacer.scroll_steps(left, top)
+        } else {
+            vec![(left, top)]
+        };
+        for (index, (dx, dy)) in steps.into_iter().enumerate() {
+            if index > 0 {
+                self.clock.sleep(self.pacer.scroll_step_delay()).await;
+            }
+            self.browser
+                .execute_script(
+                    tab,
+                    &format!(
+                        "window.scrollBy({{ left: {}, top: {}, behavior: 'instant' }})",
+                        dx, dy
+                    ),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Collect items from an infinitely scrolling list.
+    ///
+    /// Scrolls `container` (the page when `None`) to its end until `max_items` elements
+    /// match `item_selector` inside it, or no new ones load within `element_timeout_ms`,
+    /// then returns the first `max_items` of them.
+    pub async fn collect_items(
+        &self,
+        item_selector: &str,
+        container: Option<&str>,
+        max_items: usize,
+    ) -> Result<Vec<DomElement>> {
+        let tab = &*self.current_tab()?;
+        let options = self.call_options();
+        loop {
+            let count = self
+                .browser
+                .execute_script(
+                    tab,
+                    &crate::dom::scroll::scroll_to_end_script(item_selector, container),
+                )
+                .await?
+                .as_i64()
+                .unwrap_or(0);
+            if count < 0 {
+                return Err(crate::errors::BrowserAgentError::ElementNotFound(
+                    container.unwrap_or_default().to_string(),
+                ));
+            }
+            if count as usize >= max_items {
+                break;
+            }
+            let loaded_more = poll_until(self.clock.as_ref(), &options, || async {
+                let current = self
+                    .browser
+                    .execute_script(
+                        tab,
+                        &crate::dom::scroll::count_items_script(item_selector, container),
+                    )
+                    .await?
+                    .as_i64()
+                    .unwrap_or(0);
+                Ok(current > count)
+            })
+            .await?;
+            if !loaded_more {
+                break;
+            }
+        }
+
+        let result = self
+            .browser
+            .execute_script(
+                tab,
+                &crate::dom::scroll::describe_items_script(item_selector, container, max_items),
+            )
+            .await?;
+        let items = described_elements(&result, item_selector, "item")?;
+        println!(
+            "📜 Collected {} items matching {}",
+            items.len(),
+            item_selector
+        );
+        Ok(items)
+    }
+
+    /// Search the rendered text of the page for `query`.
+    ///
+    /// Matches may cross inline elements (`foo <b>bar</b>`) but not block boundaries.
+    /// Each match carries the innermost element containing it and the viewport rect
+    /// of the matched range. With `options.highlight_match` set, that match is
+    /// scrolled into view and highlighted until `clear_element_highlights`.
+    pub async fn find_text(
+        &self,
+        query: &str,
+        options: &TextSearchOptions,
+    ) -> Result<Vec<TextMatch>> {
+        let tab = &*self.current_tab()?;
+
+        let script = crate::dom::text_search::find_text_script(query, options)?;
+        let result = self.browser.execute_script(tab, &script).await?;
+        let matches = crate::dom::text_search::matches_from_value(&result)?;
+
+        println!("🔎 Found {} match(es) for '{}'", matches.len(), query);
+        Ok(matches)
+    }
+
+    /// Absolute http(s) URLs of every link on the page, without fragments or duplicates
+    pub async fn extract_links(&self) -> Result<Vec<String>> {
+        let tab = &*self.current_tab()?;
+
+        let script = r#"
+            (function() {
+                const seen = new Set();
+                for (const link of document.querySelectorAll('a[href], area[href]')) {
+                    let url;
+                    try { url = new URL(link.href, document.baseURI); } catch (e) { continue; }
+                    if (url.protocol !== 'http:' && url.protocol !== 'https:') continue;
+                    url.hash = '';
+                    seen.add(url.href);
+                }
+                return Array.from(seen);
+            })()
+        "#;
+
+        let result = self.browser.execute_script(tab, script).await?;
+        let links: Vec<String> = serde_json::from_value(result)?;
+        Ok(links)
+    }
+
+    /// Audit the current page for common accessibility problems: images without alt
+    /// text, unnamed buttons and links, unlabelled form fields, low text contrast,
+    /// duplicate ids and a missing document language
+    pub async fn accessibility_audit(&self) -> Result<crate::dom::AuditReport> {
+        let tab = &*self.current_tab()?;
+
+        let script = crate::dom::accessibility::audit_script();
+        let result = self.browser.execute_script(tab, &script).await?;
+        let report = crate::dom::accessibility::report_from_value(&result);
+
+        println!(
+            "♿ Accessibility audit: {} issue(s) across {} element(s)",
+            report.issues.len(),
+            report.elements_checked
+        );
+        Ok(report)
+    }
+}
@@ -0,0 +1,237 @@
+use crate::browser::element_monitor::ElementMonitor;
+use crate::browser::events::{ConsoleForwarder, SessionEvent};
+use crate::browser::navigation::{NavigationManager, NavigationResult};
+use crate::browser::prefetch::PrefetchedPage;
+use crate::browser::state::SessionState;
+use crate::core::BrowserTrait;
+use crate::dom::DomState;
+use crate::errors::Result;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use super::{approx_bytes, BrowserSession};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Load `url` in a background tab and extract its `DomState`, so that
+    /// `activate_prefetched(url)` can switch to it without waiting for the
+    /// navigation or the extraction. Run it alongside other work, e.g. with
+    /// `tokio::join!` while deciding the next step.
+    ///
+    /// Up to `session.prefetch_limit` pages, and `session.prefetch_max_bytes` of
+    /// their states, are kept, dropping the least recently prefetched first;
+    /// prefetching a URL again replaces its page. A page whose state alone is
+    /// larger than `session.prefetch_max_bytes` is not kept.
+    pub async fn prefetch(&self, url: &str) -> Result<()> {
+        self.ensure_open()?;
+        let limit = self.config.session.prefetch_limit;
+        if limit == 0 {
+            return Err(crate::errors::BrowserAgentError::ConfigurationError(
+                "prefetching is disabled; set session.prefetch_limit".to_string(),
+            ));
+        }
+        self.domain_policy.check(url)?;
+
+        let started = self.clock.now();
+        let tab = self.browser.new_background_tab().await?;
+        let (final_url, dom_state, monitor) = match self.load_in_background(&tab, url).await {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                let _ = self.browser.close_tab(&tab).await;
+                return Err(e);
+            }
+        };
+        println!(
+            "⏩ Prefetched {} in {}ms ({} elements)",
+            final_url,
+            self.clock.elapsed_since(started).as_millis(),
+            dom_state.elements.len()
+        );
+
+        let bytes = approx_bytes(&dom_state);
+        let inserted = self.prefetched.lock().await.insert(
+            PrefetchedPage {
+                url: url.to_string(),
+                final_url,
+                tab,
+                dom_state,
+                monitor,
+                fetched_at: self.clock.now(),
+                bytes,
+            },
+            limit,
+            self.config.session.prefetch_max_bytes,
+        );
+        if let Some(page) = &inserted.too_large {
+            println!(
+                "⚠️ Prefetched {} takes ~{} bytes, more than session.prefetch_max_bytes",
+                url, page.bytes
+            );
+        }
+        for page in inserted.evicted.iter().chain(&inserted.too_large) {
+            self.browser.close_tab(&page.tab).await?;
+        }
+        Ok(())
+    }
+
+    /// Navigate `tab` to `url`, extract it and start watching it for changes
+    async fn load_in_background(
+        &self,
+        tab: &B::TabHandle,
+        url: &str,
+    ) -> Result<(String, DomState, ElementMonitor)> {
+        if self.user_agent_override().is_some() || !self.custom_headers.lock().unwrap().is_empty() {
+            self.apply_request_overrides(tab).await?;
+        }
+        let throttling = self.throttling();
+        if throttling.is_active() {
+            self.apply_throttling(tab, &throttling).await?;
+        }
+        let nav_result = self.load_in_tab(tab, url).await?;
+
+        let dom_state = self
+            .dom_processor
+            .extract_dom_state(self.browser.as_ref(), tab, false)
+            .await?;
+        let monitor = ElementMonitor::with_history_len(self.config.session.change_history_len);
+        monitor.start_monitoring(self.browser.as_ref(), tab).await?;
+        Ok((nav_result.url, dom_state, monitor))
+    }
+
+    /// Navigate a tab other than the session's to `url` under the domain policy
+    pub(super) async fn load_in_tab(
+        &self,
+        tab: &B::TabHandle,
+        url: &str,
+    ) -> Result<NavigationResult> {
+        if self.config.session.restrict_subresources
+            && self.domain_policy.is_active()
+            && self.browser.capabilities().supports_network_interception
+        {
+            self.browser
+                .set_request_filter(tab, self.domain_policy.clone())
+                .await?;
+        }
+        self.register_window_open_guard(tab).await?;
+
+        self.browser.navigate(tab, url).await?;
+        let nav_result = NavigationManager::wait_for_navigation_complete(
+            self.browser.as_ref(),
+            tab,
+            self.clock.as_ref(),
+            self.config.session.navigation_timeout_ms,
+        )
+        .await?;
+        self.domain_policy.check(&nav_result.url)?;
+        self.run_window_open_guard(tab).await?;
+        Ok(nav_result)
+    }
+
+    /// URLs with a prefetched page, least recently prefetched first
+    pub async fn prefetched_urls(&self) -> Vec<String> {
+        self.prefetched.lock().await.urls()
+    }
+
+    /// Make the page prefetched for `url` the session's tab, closing the current
+    /// one, and return its `DomState`. The state extracted in the background is
+    /// reused unless the page changed since; then it is extracted again.
+    ///
+    /// `None`, with the session untouched, when `url` was not prefetched.
+    pub async fn activate_prefetched(&self, url: &str) -> Result<Option<DomState>> {
+        self.ensure_open()?;
+        let _state = self
+            .enter_state(SessionState::Navigating, "activate_prefetched")
+            .await?;
+        let Some(page) = self.prefetched.lock().await.take(url) else {
+            return Ok(None);
+        };
+        let PrefetchedPage {
+            final_url,
+            tab,
+            dom_state,
+            monitor,
+            fetched_at,
+            ..
+        } = page;
+
+        // Whatever changed on the session since prefetching, such as a keepalive
+        // started since, applies to the tab it switches to
+        let prepared = match self.prepare_replacement_tab(&tab).await {
+            Ok(()) => {
+                self.prepare_prefetched(&tab, &monitor, &final_url, dom_state)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        let dom_state = match prepared {
+            Ok(dom_state) => dom_state,
+            Err(e) => {
+                let _ = self.browser.close_tab(&tab).await;
+                return Err(e);
+            }
+        };
+
+        if let Ok(previous) = self.current_tab() {
+            let _ = self.clear_element_highlights().await;
+            let _ = self
+                .element_monitor
+                .stop_monitoring(self.browser.as_ref(), &previous)
+                .await;
+        }
+        let forwarder = self
+            .browser
+            .subscribe_console(&tab)
+            .await?
+            .map(|console| ConsoleForwarder::spawn(console, self.events.clone()));
+        *self.console_forwarder.lock().unwrap() = forwarder;
+        let previous = self.tab.write().unwrap().replace(Arc::new(tab));
+        self.crash_reported.store(false, Ordering::Relaxed);
+        self.element_monitor.adopt(&monitor).await;
+        self.element_highlights.write().await.clear();
+        self.ai_elements.write().await.clear();
+        #[cfg(feature = "inspector")]
+        self.inspect(|view| view.page_changed(&final_url));
+        *self.keepalive_seen.lock().unwrap() = None;
+        if let Some(previous) = previous {
+            if let Err(e) = self.browser.close_tab(&previous).await {
+                println!("⚠️ Could not close the previous tab: {}", e);
+            }
+        }
+
+        println!(
+            "⏩ Activated prefetched {} ({}ms after prefetching)",
+            final_url,
+            self.clock.elapsed_since(fetched_at).as_millis()
+        );
+        self.publish_event(SessionEvent::Navigated {
+            url: final_url.clone(),
+            success: true,
+            duration_ms: 0,
+            timestamp: chrono::Utc::now(),
+        });
+        self.watch_after_navigation(&final_url).await?;
+        Ok(Some(dom_state))
+    }
+
+    /// Bring a prefetched tab to the front, extracting it again if it changed
+    /// after `dom_state` was taken
+    async fn prepare_prefetched(
+        &self,
+        tab: &B::TabHandle,
+        monitor: &ElementMonitor,
+        final_url: &str,
+        dom_state: DomState,
+    ) -> Result<DomState> {
+        let changes = monitor
+            .check_for_changes(self.browser.as_ref(), tab)
+            .await?;
+        let moved = self.browser.get_url(tab).await? != final_url;
+        self.browser.activate_tab(tab).await?;
+        if !changes.has_changes && !moved {
+            return Ok(dom_state);
+        }
+        println!("🔄 Prefetched page changed since prefetching; extracting again");
+        self.dom_processor
+            .extract_dom_state(self.browser.as_ref(), tab, false)
+            .await
+    }
+}
@@ -0,0 +1,111 @@
+use crate::browser::events::SessionEvent;
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::errors::Result;
+use crate::utils::SecretString;
+use std::sync::atomic::Ordering;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Refresh highlights if the DOM changed, in the background when a refresh
+    /// task is running
+    pub(super) async fn check_and_refresh_if_needed(&self) -> Result<()> {
+        if !self.auto_refresh_enabled.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        if let Some(task) = self.refresh.lock().unwrap().as_ref() {
+            task.request();
+            return Ok(());
+        }
+        self.refresh_if_changed().await
+    }
+
+    /// Wait briefly for DOM changes and redraw the highlights if there were any
+    pub(crate) async fn refresh_if_changed(&self) -> Result<()> {
+        if !self.auto_refresh_enabled.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let tab = &*self.current_tab()?;
+
+        let change_result = self
+            .element_monitor
+            .wait_for_changes(self.browser.as_ref(), tab, 1000)
+            .await?;
+
+        if change_result.has_changes {
+            println!("🔄 DOM changes detected: {:?}", change_result.change_types);
+            self.publish_dom_changed(change_result.change_types);
+            self.refresh_elements_after_change().await?;
+        } else {
+            self.clock
+                .sleep(tokio::time::Duration::from_millis(100))
+                .await;
+            let quick_check = self
+                .element_monitor
+                .check_for_changes(self.browser.as_ref(), tab)
+                .await?;
+
+            if quick_check.has_changes {
+                self.publish_dom_changed(quick_check.change_types);
+                self.refresh_elements_after_change().await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn publish_dom_changed(&self, change_types: Option<Vec<String>>) {
+        self.publish_event(SessionEvent::DomChanged {
+            change_types: change_types.unwrap_or_default(),
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
+    pub(super) async fn refresh_elements_after_change(&self) -> Result<()> {
+        println!("🔄 Refreshing elements due to DOM changes...");
+
+        self.clear_element_highlights().await?;
+
+        self.clock
+            .sleep(tokio::time::Duration::from_millis(200))
+            .await;
+
+        let highlights = self.highlight_interactive_elements().await?;
+
+        println!("✅ Refreshed {} interactive elements", highlights.len());
+        self.publish_event(SessionEvent::HighlightRefreshed {
+            count: highlights.len(),
+            timestamp: chrono::Utc::now(),
+        });
+        Ok(())
+    }
+
+    pub async fn type_secret_with_refresh(
+        &self,
+        selector: &str,
+        secret: &SecretString,
+    ) -> Result<()> {
+        self.type_secret(selector, secret).await?;
+        self.check_and_refresh_if_needed().await?;
+        Ok(())
+    }
+
+    pub async fn click_with_refresh(&self, selector: &str) -> Result<()> {
+        self.click(selector).await?;
+        self.check_and_refresh_if_needed().await?;
+        Ok(())
+    }
+
+    pub async fn type_with_refresh(&self, selector: &str, text: &str) -> Result<()> {
+        self.type_text_enhanced(selector, text).await?;
+        self.check_and_refresh_if_needed().await?;
+        Ok(())
+    }
+
+    pub async fn click_element_by_number_with_refresh(&self, element_number: usize) -> Result<()> {
+        self.click_element_by_number(element_number).await?;
+        self.check_and_refresh_if_needed().await?;
+        Ok(())
+    }
+}
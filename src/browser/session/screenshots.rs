@@ -0,0 +1,291 @@
+use crate::core::{BrowserFeature, BrowserTrait, SessionTrait};
+use crate::dom::{CoordinateSpace, DomElement, ElementRect};
+use crate::errors::Result;
+use crate::utils::artifacts::{ArtifactRef, ArtifactSink};
+use crate::utils::image::{compare_screenshots, CompareOptions, DiffResult};
+use crate::utils::ScreenshotManager;
+
+use super::{BrowserSession, ResponsiveScreenshot, ViewportData};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Screenshot taken at the configured viewport and device scale factor, with
+    /// animations, transitions and the text caret disabled so repeated captures of
+    /// an unchanged page are identical
+    pub async fn stable_screenshot(&self) -> Result<Vec<u8>> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        self.require(BrowserFeature::Screenshots)?;
+
+        if !self.supports(BrowserFeature::MobileEmulation, "current window size")
+            || !self
+                .browser
+                .emulate_viewport(tab, &self.config.browser.viewport)
+                .await?
+        {
+            println!("⚠️ Viewport emulation unsupported; screenshot size follows the window");
+        }
+
+        let freeze = r#"
+            (function() {
+                if (document.getElementById('surfai-visual-freeze')) return true;
+                const style = document.createElement('style');
+                style.id = 'surfai-visual-freeze';
+                style.setAttribute('data-surfai-internal', '');
+                style.textContent = '*, *::before, *::after { animation: none !important; '
+                    + 'transition: none !important; caret-color: transparent !important; }';
+                document.head.appendChild(style);
+                return true;
+            })()
+        "#;
+        self.browser.execute_script(tab, freeze).await?;
+        // Let the style change reach a painted frame
+        self.clock
+            .sleep(tokio::time::Duration::from_millis(100))
+            .await;
+
+        let screenshot = self.browser.take_screenshot(tab).await;
+        self.browser
+            .execute_script(
+                tab,
+                "document.getElementById('surfai-visual-freeze')?.remove(); true",
+            )
+            .await?;
+        screenshot
+    }
+
+    /// Resize the page's viewport to `viewport` without relaunching the browser,
+    /// so media queries and layout follow the new size.
+    ///
+    /// Highlights and numbered elements from before are dropped, since which
+    /// elements show and where they sit can change with the size.
+    pub async fn emulate_viewport(&self, viewport: &crate::core::config::Viewport) -> Result<()> {
+        self.ensure_open()?;
+        let tab = &*self.current_tab()?;
+        self.require(BrowserFeature::MobileEmulation)?;
+        if !self.browser.emulate_viewport(tab, viewport).await? {
+            return Err(self.unavailable(BrowserFeature::MobileEmulation));
+        }
+        self.invalidate_layout().await;
+        println!("📐 Viewport set to {}", viewport.label());
+        Ok(())
+    }
+
+    /// Undo `emulate_viewport`, returning to the window's own size
+    pub async fn clear_viewport_emulation(&self) -> Result<()> {
+        let tab = &*self.current_tab()?;
+        if self.browser.clear_viewport_emulation(tab).await? {
+            self.invalidate_layout().await;
+        }
+        Ok(())
+    }
+
+    /// Drop what depends on the layout and let the page repaint at its new size
+    async fn invalidate_layout(&self) {
+        let _ = self.clear_element_highlights().await;
+        self.ai_elements.write().await.clear();
+        self.clock
+            .sleep(tokio::time::Duration::from_millis(100))
+            .await;
+    }
+
+    /// Run `run` once per viewport, resizing the page in between without
+    /// relaunching, and collect the results in order. The window's own size is
+    /// restored afterwards, also when a run fails.
+    ///
+    /// ```ignore
+    /// let viewports = [Viewport::mobile(390, 844), Viewport::new(1440, 900)];
+    /// let counts = session
+    ///     .with_viewports(&viewports, |_| async {
+    ///         Ok(session.get_page_state(false).await?.clickable_elements.len())
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn with_viewports<T, F, Fut>(
+        &self,
+        viewports: &[crate::core::config::Viewport],
+        mut run: F,
+    ) -> Result<Vec<T>>
+    where
+        F: FnMut(crate::core::config::Viewport) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut results = Vec::with_capacity(viewports.len());
+        let mut outcome = Ok(());
+        for viewport in viewports {
+            let result = match self.emulate_viewport(viewport).await {
+                Ok(()) => run(viewport.clone()).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    outcome = Err(e);
+                    break;
+                }
+            }
+        }
+        let restored = self.clear_viewport_emulation().await;
+        outcome?;
+        restored?;
+        Ok(results)
+    }
+
+    /// Screenshot of the page at each of `viewports`, labelled with the size
+    pub async fn capture_responsive_screenshots(
+        &self,
+        viewports: &[crate::core::config::Viewport],
+    ) -> Result<Vec<ResponsiveScreenshot>> {
+        self.require(BrowserFeature::Screenshots)?;
+        self.with_viewports(viewports, |viewport| async move {
+            Ok(ResponsiveScreenshot {
+                label: viewport.label(),
+                viewport,
+                png: SessionTrait::screenshot(self).await?,
+            })
+        })
+        .await
+    }
+
+    /// Current device scale factor and scroll offset
+    pub async fn coordinate_space(&self) -> Result<CoordinateSpace> {
+        let tab = &*self.current_tab()?;
+        let value = self
+            .browser
+            .execute_script(tab, CoordinateSpace::CAPTURE_JS)
+            .await?;
+        Ok(CoordinateSpace::from_value(&value))
+    }
+
+    /// Screenshot with the rects of `elements` outlined.
+    ///
+    /// Rects are taken to be measured at the current scroll position, as returned by
+    /// `query_xpath` or `find_text` without scrolling since.
+    pub async fn screenshot_annotated(
+        &self,
+        elements: &[DomElement],
+    ) -> Result<(Vec<u8>, CoordinateSpace)> {
+        let space = self.coordinate_space().await?;
+        let screenshot = SessionTrait::screenshot(self).await?;
+        let rects: Vec<ElementRect> = elements
+            .iter()
+            .filter_map(|element| element.rect.as_ref())
+            .map(|rect| rect.in_screenshot(&space, &space))
+            .collect();
+        let annotated = crate::utils::image::draw_rects(&screenshot, &rects, [255, 64, 0, 255])?;
+        Ok((annotated, space))
+    }
+
+    /// Compare the page against the PNG at `baseline_path`.
+    ///
+    /// Fails with `AssertionFailed` when more than `threshold` (0.0-1.0) of the pixels
+    /// differ, storing `<baseline>.actual.png` and `<baseline>.diff.png` next to the
+    /// baseline. A missing baseline is created from the current page and passes.
+    /// Baselines are read from and written to the session's artifact sink, with the
+    /// path as the artifact name.
+    pub async fn assert_visual_match<P: AsRef<std::path::Path>>(
+        &self,
+        baseline_path: P,
+        threshold: f64,
+    ) -> Result<DiffResult> {
+        self.assert_visual_match_with(baseline_path, threshold, CompareOptions::default())
+            .await
+    }
+
+    /// `assert_visual_match` with ignored regions and tolerance.
+    ///
+    /// `options.device_scale_factor` is replaced by the configured one, so ignored
+    /// regions are always given in CSS pixels.
+    pub async fn assert_visual_match_with<P: AsRef<std::path::Path>>(
+        &self,
+        baseline_path: P,
+        threshold: f64,
+        mut options: CompareOptions,
+    ) -> Result<DiffResult> {
+        let baseline_path = baseline_path.as_ref();
+        let baseline_name = baseline_path.to_string_lossy();
+        let actual = self.stable_screenshot().await?;
+
+        let Some(baseline) = self.artifact_sink.load(&baseline_name).await? else {
+            let stored = self
+                .artifact_sink
+                .store(&baseline_name, &actual, "image/png")
+                .await?;
+            println!("📸 Baseline created at {}", stored.location);
+            return compare_screenshots(&actual, &actual, &options);
+        };
+
+        options.device_scale_factor = self.config.browser.viewport.device_scale_factor;
+        let diff = compare_screenshots(&baseline, &actual, &options)?;
+
+        if diff.size_mismatch || diff.mismatch_ratio > threshold {
+            let actual_name = baseline_path.with_extension("actual.png");
+            let diff_name = baseline_path.with_extension("diff.png");
+            self.artifact_sink
+                .store(&actual_name.to_string_lossy(), &actual, "image/png")
+                .await?;
+            let stored_diff = self
+                .artifact_sink
+                .store(&diff_name.to_string_lossy(), &diff.diff_image, "image/png")
+                .await?;
+            return Err(crate::errors::BrowserAgentError::AssertionFailed(format!(
+                "visual mismatch against {}: {:.2}% of pixels differ (threshold {:.2}%){}; diff written to {}",
+                baseline_path.display(),
+                diff.mismatch_ratio * 100.0,
+                threshold * 100.0,
+                if diff.size_mismatch { ", image sizes differ" } else { "" },
+                stored_diff.location
+            )));
+        }
+
+        println!(
+            "✅ Visual match against {} ({:.2}% differ)",
+            baseline_path.display(),
+            diff.mismatch_ratio * 100.0
+        );
+        Ok(diff)
+    }
+
+    /// Screenshot the page into the artifact sink under `name`
+    pub async fn save_screenshot(&self, name: &str) -> Result<ArtifactRef> {
+        let tab = &*self.current_tab()?;
+        let stored = ScreenshotManager::save_to_sink(
+            self.browser.as_ref(),
+            tab,
+            self.artifact_sink.as_ref(),
+            name,
+        )
+        .await?;
+        println!("📸 Screenshot stored at {}", stored.location);
+        Ok(stored)
+    }
+
+    /// Render `report` into the artifact sink under `name`
+    pub async fn save_report(
+        &self,
+        report: &crate::replay::ReportBuilder,
+        name: &str,
+    ) -> Result<ArtifactRef> {
+        report.write(self.artifact_sink.as_ref(), name).await
+    }
+
+    pub(super) async fn set_viewport(&self, viewport: &ViewportData) -> Result<()> {
+        let tab = &*self.current_tab()?;
+
+        let script = format!(
+            r#"
+            (function() {{
+                window.resizeTo({}, {});
+                return {{
+                    success: true,
+                    width: window.innerWidth,
+                    height: window.innerHeight
+                }};
+            }})()
+        "#,
+            viewport.width, viewport.height
+        );
+
+        self.browser.execute_script(tab, &script).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::browser::element_handle::ElementHandle;
+use crate::browser::typing::TypeMode;
+use crate::core::{
+    BrowserFeature, BrowserTrait, NodeDescription, NodeRef, Selector, SelectorType, SessionTrait,
+};
+use crate::dom::DomElement;
+use crate::errors::Result;
+
+use super::{described_elements, BrowserSession};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Resolve the first element matching `selector` to a protocol node, for
+    /// backends with `BrowserFeature::NodeResolution`
+    pub async fn resolve_node(&self, selector: &str) -> Result<Option<NodeRef>> {
+        self.require(BrowserFeature::NodeResolution)?;
+        let tab = &*self.current_tab()?;
+        self.browser.resolve_node(tab, selector).await
+    }
+
+    /// Current state of `node`; fails with `ElementDetached` once it is stale
+    pub async fn describe_node(&self, node: &NodeRef) -> Result<NodeDescription> {
+        self.require(BrowserFeature::NodeResolution)?;
+        let tab = &*self.current_tab()?;
+        self.browser.describe_node(tab, node).await
+    }
+
+    /// Pin the first element matching `selector` and return a handle to it
+    pub async fn element_handle(&self, selector: &str) -> Result<ElementHandle<'_, B>> {
+        ElementHandle::attach(self, selector).await
+    }
+
+    /// Click the first element matching an XPath expression
+    pub async fn click_xpath(&self, xpath: &str) -> Result<()> {
+        self.click_expression(&crate::dom::xpath::first_node_expression(xpath), xpath)
+            .await
+    }
+
+    /// Type into the first element matching an XPath expression
+    pub async fn type_text_xpath(&self, xpath: &str, text: &str) -> Result<()> {
+        self.type_into_expression(
+            &crate::dom::xpath::first_node_expression(xpath),
+            xpath,
+            text,
+            TypeMode::Replace,
+            false,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Return every element matching an XPath expression, in document order
+    pub async fn query_xpath(&self, xpath: &str) -> Result<Vec<DomElement>> {
+        let tab = &*self.current_tab()?;
+
+        let result = self
+            .browser
+            .execute_script(tab, &crate::dom::xpath::query_script(xpath))
+            .await?;
+
+        described_elements(&result, xpath, "xpath")
+    }
+
+    /// Click using an explicit selector type
+    pub async fn click_by(&self, selector: &str, selector_type: SelectorType) -> Result<()> {
+        self.click(Selector::from_type(selector, &selector_type))
+            .await
+    }
+
+    /// Type using an explicit selector type
+    pub async fn type_text_by(
+        &self,
+        selector: &str,
+        selector_type: SelectorType,
+        text: &str,
+    ) -> Result<()> {
+        self.type_text_enhanced(Selector::from_type(selector, &selector_type), text)
+            .await
+    }
+}
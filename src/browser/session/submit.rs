@@ -0,0 +1,145 @@
+use crate::browser::navigation::NavigationManager;
+use crate::browser::submit::{SubmitOutcome, SubmitResult, SubmitStatus};
+use crate::core::{BrowserTrait, CallOptions, Selector, SessionTrait};
+use crate::errors::Result;
+
+use super::{poll_until, BrowserSession};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Submit a form and wait to see what the page does.
+    ///
+    /// `selector` is either the form, submitted with `requestSubmit` so constraint
+    /// validation runs, or a control to click. The result is the first of: a new
+    /// document, a URL change within the document, error messages or fields
+    /// marked invalid near the form, or a new status message or toast anywhere on
+    /// the page. `NoChange` when none shows up within the navigation timeout.
+    pub async fn submit_and_wait(&self, selector: impl Into<Selector>) -> Result<SubmitResult> {
+        self.submit_and_wait_with_options(selector, &self.load_options())
+            .await
+    }
+
+    /// `submit_and_wait` giving the outcome `options.timeout_ms` to show
+    pub async fn submit_and_wait_with_options(
+        &self,
+        selector: impl Into<Selector>,
+        options: &CallOptions,
+    ) -> Result<SubmitResult> {
+        let selector = selector.into();
+        let tab = &*self.current_tab()?;
+        let started = self.clock.now();
+        let token = self.next_id();
+
+        let watch = self
+            .execute_script(&crate::browser::submit::watch_script(
+                &selector.element_expression(),
+                &token,
+            ))
+            .await?;
+        if watch.get("found").and_then(|v| v.as_bool()) != Some(true) {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(
+                selector.to_string(),
+            ));
+        }
+        let start_url = watch
+            .get("url")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        if self.csrf.enabled() {
+            match self.sync_csrf_fields(&selector).await {
+                Ok(0) => {}
+                Ok(changed) => {
+                    tracing::debug!(session_id = %self.session_id, changed, "refreshed CSRF form fields")
+                }
+                Err(e) => {
+                    tracing::warn!(session_id = %self.session_id, %e, "could not refresh CSRF form fields")
+                }
+            }
+        }
+
+        if watch.get("isForm").and_then(|v| v.as_bool()) == Some(true) {
+            self.execute_script(&crate::browser::submit::request_submit_script(
+                &selector.element_expression(),
+            ))
+            .await?;
+        } else {
+            self.click(&selector).await?;
+        }
+
+        let status_script = crate::browser::submit::status_script(&token);
+        let last = std::sync::Mutex::new(SubmitStatus::default());
+        let settled = poll_until(self.clock.as_ref(), options, || async {
+            // Reads fail or come back empty while the old document unloads
+            let Ok(value) = self.execute_script(&status_script).await else {
+                return Ok(false);
+            };
+            let Ok(status) = serde_json::from_value::<SubmitStatus>(value) else {
+                return Ok(false);
+            };
+            let done = !status.same_document
+                || status.url != start_url
+                || !status.errors.is_empty()
+                || !status.invalid.is_empty()
+                || !status.success.is_empty();
+            *last.lock().unwrap_or_else(|e| e.into_inner()) = status;
+            Ok(done)
+        })
+        .await?;
+        let status = last.into_inner().unwrap_or_else(|e| e.into_inner());
+
+        let outcome = if !settled {
+            SubmitOutcome::NoChange
+        } else if !status.same_document {
+            let navigation = NavigationManager::wait_for_navigation_complete(
+                self.browser.as_ref(),
+                tab,
+                self.clock.as_ref(),
+                self.config.session.navigation_timeout_ms,
+            )
+            .await?;
+            SubmitOutcome::Navigated { navigation }
+        } else if status.url != start_url {
+            SubmitOutcome::RouteChanged {
+                from: start_url.clone(),
+                to: status.url.clone(),
+            }
+        } else if !status.errors.is_empty() || !status.invalid.is_empty() {
+            SubmitOutcome::ValidationError {
+                messages: status.errors.clone(),
+                invalid_fields: status.invalid.clone(),
+            }
+        } else {
+            SubmitOutcome::Success {
+                message: status.success.first().cloned().unwrap_or_default(),
+            }
+        };
+        if status.same_document {
+            let _ = self
+                .execute_script(&crate::browser::submit::stop_script(&token))
+                .await;
+        }
+
+        let url = match &outcome {
+            SubmitOutcome::Navigated { navigation } => navigation.url.clone(),
+            _ => status.url.clone(),
+        };
+        let result = SubmitResult {
+            outcome,
+            diff: status.diff,
+            url,
+            duration_ms: self
+                .clock
+                .now()
+                .saturating_duration_since(started)
+                .as_millis() as u64,
+        };
+        println!(
+            "📨 Submitted {}: {} ({})",
+            selector,
+            result.outcome.describe(),
+            result.diff.summary()
+        );
+        Ok(result)
+    }
+}
@@ -0,0 +1,152 @@
+use crate::browser::state::SessionState;
+use crate::browser::targeting::{self, ClickRectOptions, PointHit};
+use crate::core::{BrowserFeature, BrowserTrait, SessionTrait};
+use crate::dom::{CoordinateSpace, DomElement, ElementRect};
+use crate::errors::Result;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Click at a viewport point in CSS pixels
+    pub async fn click_at(&self, x: f64, y: f64) -> Result<()> {
+        let tab = &*self.current_tab()?;
+        let _state = self
+            .enter_state(SessionState::Interacting, "click_at")
+            .await?;
+        self.pace().await;
+        if self.supports(BrowserFeature::NativeInput, "synthetic click") {
+            for (px, py) in self.pacer.pointer_path((x, y)) {
+                if !self.browser.move_mouse(tab, px, py).await? {
+                    break;
+                }
+                self.clock.sleep(self.pacer.pointer_step_delay()).await;
+            }
+            self.browser.click_at(tab, x, y).await?;
+        } else {
+            let clicked = self
+                .browser
+                .execute_script(tab, &crate::core::browser::click_at_script(x, y))
+                .await?;
+            if !clicked.as_bool().unwrap_or(false) {
+                return Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                    "nothing at ({}, {})",
+                    x, y
+                )));
+            }
+        }
+        println!("🖱️ Clicked at ({:.0}, {:.0})", x, y);
+        Ok(())
+    }
+
+    /// Topmost element at viewport point (`x`, `y`) in CSS pixels; `None` when the
+    /// point lies outside the viewport or on no element
+    pub async fn element_at(&self, x: f64, y: f64) -> Result<Option<DomElement>> {
+        let hit = self.hit_test(x, y).await?;
+        Ok(hit
+            .element
+            .filter(|_| hit.in_viewport)
+            .map(|value| crate::dom::xpath::element_from_value(&value, "point_1".to_string())))
+    }
+
+    async fn hit_test(&self, x: f64, y: f64) -> Result<PointHit> {
+        let value = self
+            .execute_script(&targeting::hit_test_script(x, y))
+            .await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Click the element under the center of `rect`, e.g. a box a vision model
+    /// drew, and return the element that was hit.
+    ///
+    /// The box is viewport CSS pixels, or device pixels of a screenshot with
+    /// `options.screenshot_space`, in which case scrolling since the capture is
+    /// compensated for. Fails when the center lies outside the viewport, unless
+    /// `options.scroll_into_view` scrolls it in first, and when it lands on a
+    /// highlight or other element the crate injected.
+    pub async fn click_rect(
+        &self,
+        rect: &ElementRect,
+        options: &ClickRectOptions,
+    ) -> Result<DomElement> {
+        let rect = match &options.screenshot_space {
+            Some(captured) => {
+                let current = self.coordinate_space().await?;
+                rect.from_device_pixels(
+                    captured.device_scale_factor,
+                    (
+                        captured.scroll_x - current.scroll_x,
+                        captured.scroll_y - current.scroll_y,
+                    ),
+                )
+            }
+            None => rect.clone(),
+        };
+        let (mut x, mut y) = rect.center();
+        let mut hit = self.hit_test(x, y).await?;
+
+        if !hit.in_viewport && options.scroll_into_view {
+            // Bring the center to the middle of the viewport
+            let before = self.coordinate_space().await?;
+            self.scroll_window_by(x - hit.width / 2.0, y - hit.height / 2.0)
+                .await?;
+            let after = self.coordinate_space().await?;
+            x -= after.scroll_x - before.scroll_x;
+            y -= after.scroll_y - before.scroll_y;
+            hit = self.hit_test(x, y).await?;
+        }
+
+        if !hit.in_viewport {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "box center ({:.0}, {:.0}) is outside the {:.0}x{:.0} viewport",
+                x, y, hit.width, hit.height
+            )));
+        }
+        if hit.internal {
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "box center ({:.0}, {:.0}) lands on a highlight or overlay added by the session; clear highlights first",
+                x, y
+            )));
+        }
+        let element = hit
+            .element
+            .map(|value| crate::dom::xpath::element_from_value(&value, "point_1".to_string()))
+            .ok_or_else(|| {
+                crate::errors::BrowserAgentError::ElementNotFound(format!(
+                    "nothing at ({:.0}, {:.0})",
+                    x, y
+                ))
+            })?;
+
+        println!(
+            "🎯 Box center ({:.0}, {:.0}) hits <{}> {}",
+            x, y, element.tag_name, element.css_selector
+        );
+        self.click_at(x, y).await?;
+        Ok(element)
+    }
+
+    /// Click the page point shown at device pixel (`x`, `y`) of a screenshot captured
+    /// in `captured`, compensating for any scrolling since the capture
+    pub async fn click_at_screenshot_pixel(
+        &self,
+        x: f64,
+        y: f64,
+        captured: &CoordinateSpace,
+    ) -> Result<()> {
+        let current = self.coordinate_space().await?;
+        let point = ElementRect {
+            x,
+            y,
+            width: 0.0,
+            height: 0.0,
+        }
+        .from_device_pixels(
+            captured.device_scale_factor,
+            (
+                captured.scroll_x - current.scroll_x,
+                captured.scroll_y - current.scroll_y,
+            ),
+        );
+        self.click_at(point.x, point.y).await
+    }
+}
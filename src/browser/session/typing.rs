@@ -0,0 +1,193 @@
+use crate::browser::state::SessionState;
+use crate::browser::typing::{self, InsertOutcome, TypeMode, TypeResult};
+use crate::core::{BrowserTrait, Selector};
+use crate::errors::Result;
+use crate::utils::SecretString;
+
+use super::BrowserSession;
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Replace the content of `selector` with `text`
+    pub async fn type_text_enhanced(
+        &self,
+        selector: impl Into<Selector>,
+        text: &str,
+    ) -> Result<()> {
+        let selector = selector.into();
+        self.type_into_expression(
+            &selector.element_expression(),
+            &selector.to_string(),
+            text,
+            TypeMode::Replace,
+            false,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Type `text` into `selector` where `mode` says, returning the content before
+    /// and after. Editable elements keep the formatting around the insertion.
+    pub async fn type_text_with_mode(
+        &self,
+        selector: &str,
+        text: &str,
+        mode: TypeMode,
+    ) -> Result<TypeResult> {
+        self.type_text_internal(selector, text, mode, false).await
+    }
+
+    /// Type a secret value; the value never appears in logs or results
+    pub async fn type_secret(&self, selector: &str, secret: &SecretString) -> Result<()> {
+        self.type_text_internal(selector, secret.expose_secret(), TypeMode::Replace, true)
+            .await?;
+        Ok(())
+    }
+
+    pub(super) async fn type_text_internal(
+        &self,
+        selector: &str,
+        text: &str,
+        mode: TypeMode,
+        redact: bool,
+    ) -> Result<TypeResult> {
+        self.type_into_expression(
+            &Selector::css(selector).element_expression(),
+            selector,
+            text,
+            mode,
+            redact,
+        )
+        .await
+    }
+
+    /// Type into the element produced by a JavaScript expression
+    pub(crate) async fn type_into_expression(
+        &self,
+        element_expression: &str,
+        target: &str,
+        text: &str,
+        mode: TypeMode,
+        redact: bool,
+    ) -> Result<TypeResult> {
+        let _state = self.enter_state(SessionState::Interacting, "type").await?;
+        self.wait_until_actionable(element_expression, target, true)
+            .await?;
+        if redact {
+            // Marked before the first keystroke, so no extraction in between
+            // reads the secret back
+            let tab = &*self.current_tab()?;
+            self.browser
+                .execute_script(
+                    tab,
+                    &crate::dom::field_value::mark_secret_script(element_expression),
+                )
+                .await?;
+        }
+        self.pace().await;
+
+        let outcome = if self.pacer.types_per_character() {
+            self.type_paced(element_expression, text, mode).await?
+        } else {
+            self.insert_text(element_expression, text, mode, false, true)
+                .await?
+        };
+        if !outcome.success {
+            let error = outcome.error.unwrap_or_default();
+            println!("❌ Typing failed: {}", error);
+            return Err(crate::errors::BrowserAgentError::ElementNotFound(format!(
+                "Failed to type in element: {} ({})",
+                target, error
+            )));
+        }
+
+        println!("✅ Successfully typed in element: {}", target);
+        // Password fields are redacted however they were typed into
+        let redact = redact || outcome.secret;
+        let redacted = |value: String| {
+            if redact {
+                "[REDACTED]".to_string()
+            } else {
+                value
+            }
+        };
+        let result = TypeResult {
+            mode,
+            before: redacted(outcome.before),
+            after: redacted(outcome.after),
+            element_type: outcome.element_type,
+        };
+        println!("   Final value: {}", result.after);
+        Ok(result)
+    }
+
+    async fn insert_text(
+        &self,
+        element_expression: &str,
+        text: &str,
+        mode: TypeMode,
+        keystroke: bool,
+        commit: bool,
+    ) -> Result<InsertOutcome> {
+        let tab = &*self.current_tab()?;
+        let result = self
+            .browser
+            .execute_script(
+                tab,
+                &typing::insert_text_script(element_expression, text, mode, keystroke, commit),
+            )
+            .await?;
+        Ok(serde_json::from_value(result).unwrap_or_default())
+    }
+
+    /// Type `text` a character at a time with the profile's keystroke cadence; the
+    /// first character goes where `mode` says and the rest follow it
+    async fn type_paced(
+        &self,
+        element_expression: &str,
+        text: &str,
+        mode: TypeMode,
+    ) -> Result<InsertOutcome> {
+        let characters: Vec<String> = text.chars().map(String::from).collect();
+        if characters.is_empty() {
+            return self
+                .insert_text(element_expression, "", mode, false, true)
+                .await;
+        }
+
+        let mut before = None;
+        let mut outcome = InsertOutcome::default();
+        for (index, character) in characters.iter().enumerate() {
+            if index > 0 {
+                self.clock.sleep(self.pacer.keystroke_delay()).await;
+            }
+            let character_mode = if index == 0 {
+                mode
+            } else {
+                TypeMode::InsertAtCursor
+            };
+            outcome = self
+                .insert_text(
+                    element_expression,
+                    character,
+                    character_mode,
+                    true,
+                    index + 1 == characters.len(),
+                )
+                .await?;
+            if !outcome.success {
+                return Ok(outcome);
+            }
+            before.get_or_insert_with(|| outcome.before.clone());
+        }
+        outcome.before = before.unwrap_or_default();
+        Ok(outcome)
+    }
+
+    /// Wait the profile's delay before an interaction
+    pub(super) async fn pace(&self) {
+        let delay = self.pacer.action_delay();
+        if !delay.is_zero() {
+            self.clock.sleep(delay).await;
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::browser::navigation::NavigationManager;
+use crate::browser::submit::SubmitStatus;
+use crate::browser::wizard::{
+    StepMatcher, WizardConfig, WizardOutcome, WizardProbe, WizardReport, WizardStepReport,
+};
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::errors::Result;
+
+use super::{poll_until, BrowserSession};
+
+impl<B: BrowserTrait> BrowserSession<B> {
+    /// Walk a multi-step form to the end.
+    ///
+    /// Each step is recognised by its matcher, filled in label by label and left
+    /// with its next control, then the page is watched until it shows another
+    /// step, the done page, a new document, or error messages and invalid fields.
+    /// The report keeps what every step changed and, unless turned off, a
+    /// screenshot of each step once filled. A step that fails to fill or comes
+    /// back right after its next control was used ends the run as `Stuck` with
+    /// the messages the page showed.
+    pub async fn run_wizard(&self, wizard: &WizardConfig) -> Result<WizardReport> {
+        let problems = wizard.validate();
+        if !problems.is_empty() {
+            return Err(crate::errors::BrowserAgentError::ConfigurationError(
+                problems.join("; "),
+            ));
+        }
+        let tab = &*self.current_tab()?;
+        let started = self.clock.now();
+        let mut matchers: Vec<&StepMatcher> = vec![&wizard.done];
+        matchers.extend(wizard.steps.iter().map(|step| &step.matcher));
+        let probe_script = crate::browser::wizard::probe_script(&matchers);
+        let options = self.load_options();
+
+        let mut steps: Vec<WizardStepReport> = Vec::new();
+        let mut previous: Option<usize> = None;
+        let outcome = loop {
+            let probe: WizardProbe =
+                serde_json::from_value(self.execute_script(&probe_script).await?)
+                    .unwrap_or_default();
+            if probe.matches.first() == Some(&true) {
+                break WizardOutcome::Completed;
+            }
+            let Some(current) = probe.matches.iter().skip(1).position(|matched| *matched) else {
+                break WizardOutcome::UnknownStep {
+                    url: probe.url,
+                    headings: probe.headings,
+                };
+            };
+            let step = &wizard.steps[current];
+            if previous == Some(current) {
+                let last = steps.last();
+                let mut diagnostics: Vec<String> = last
+                    .map(|report| report.messages.clone())
+                    .unwrap_or_default();
+                if let Some(report) = last.filter(|report| !report.invalid_fields.is_empty()) {
+                    diagnostics.push(format!(
+                        "invalid fields: {}",
+                        report.invalid_fields.join(", ")
+                    ));
+                }
+                break WizardOutcome::Stuck {
+                    step: step.name.clone(),
+                    reason: if diagnostics.is_empty() {
+                        "the step did not advance".to_string()
+                    } else {
+                        diagnostics.join("; ")
+                    },
+                };
+            }
+            if steps.len() >= wizard.max_steps {
+                break WizardOutcome::MaxStepsReached;
+            }
+
+            let step_started = self.clock.now();
+            let mut filled = Vec::new();
+            let mut fill_error = None;
+            for (label, value) in &step.fill {
+                if let Err(e) = self.fill_field_by_label(label, value).await {
+                    fill_error = Some(format!("filling '{}' failed: {}", label, e));
+                    break;
+                }
+                filled.push(label.clone());
+            }
+            let screenshot = if wizard.screenshots {
+                let name = crate::browser::wizard::screenshot_name(steps.len() + 1, &step.name);
+                match self.save_screenshot(&name).await {
+                    Ok(stored) => Some(stored),
+                    Err(e) => {
+                        tracing::warn!(session_id = %self.session_id, step = %step.name, error = %e, "wizard screenshot failed");
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            if let Some(reason) = fill_error {
+                break WizardOutcome::Stuck {
+                    step: step.name.clone(),
+                    reason,
+                };
+            }
+
+            let next = step.next.as_ref().unwrap_or(&wizard.next);
+            let token = self.next_id();
+            let watch = self
+                .execute_script(&crate::browser::submit::watch_script(
+                    &next.element_expression(),
+                    &token,
+                ))
+                .await?;
+            if watch.get("found").and_then(|v| v.as_bool()) != Some(true) {
+                break WizardOutcome::Stuck {
+                    step: step.name.clone(),
+                    reason: format!("no next control {}", next),
+                };
+            }
+            self.click(next).await?;
+
+            let status_script = crate::browser::submit::status_script(&token);
+            let last = std::sync::Mutex::new(SubmitStatus::default());
+            poll_until(self.clock.as_ref(), &options, || async {
+                // Reads fail or come back empty while the old document unloads
+                let Ok(value) = self.execute_script(&status_script).await else {
+                    return Ok(false);
+                };
+                let Ok(status) = serde_json::from_value::<SubmitStatus>(value) else {
+                    return Ok(false);
+                };
+                let moved = !status.same_document
+                    || !status.errors.is_empty()
+                    || !status.invalid.is_empty()
+                    || match self.execute_script(&probe_script).await {
+                        Ok(value) => serde_json::from_value::<WizardProbe>(value)
+                            .map(|probe| {
+                                probe.matches.first() == Some(&true)
+                                    || probe.matches.get(current + 1) != Some(&true)
+                            })
+                            .unwrap_or(false),
+                        Err(_) => false,
+                    };
+                *last.lock().unwrap_or_else(|e| e.into_inner()) = status;
+                Ok(moved)
+            })
+            .await?;
+            let status = last.into_inner().unwrap_or_else(|e| e.into_inner());
+            if status.same_document {
+                let _ = self
+                    .execute_script(&crate::browser::submit::stop_script(&token))
+                    .await;
+            } else {
+                NavigationManager::wait_for_navigation_complete(
+                    self.browser.as_ref(),
+                    tab,
+                    self.clock.as_ref(),
+                    self.config.session.navigation_timeout_ms,
+                )
+                .await?;
+            }
+
+            println!(
+                "🧭 Wizard step {} '{}': {}",
+                steps.len() + 1,
+                step.name,
+                status.diff.summary()
+            );
+            steps.push(WizardStepReport {
+                index: steps.len() + 1,
+                step: step.name.clone(),
+                url: probe.url,
+                filled,
+                diff: status.diff,
+                messages: status.errors,
+                invalid_fields: status.invalid,
+                screenshot,
+                duration_ms: self
+                    .clock
+                    .now()
+                    .saturating_duration_since(step_started)
+                    .as_millis() as u64,
+            });
+            previous = Some(current);
+        };
+
+        let report = WizardReport {
+            outcome,
+            steps,
+            final_url: self.current_url().await.unwrap_or_default(),
+            duration_ms: self
+                .clock
+                .now()
+                .saturating_duration_since(started)
+                .as_millis() as u64,
+        };
+        println!(
+            "🧭 Wizard {} after {} steps",
+            report.outcome.describe(),
+            report.steps.len()
+        );
+        Ok(report)
+    }
+}
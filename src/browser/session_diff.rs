@@ -0,0 +1,401 @@
+use super::session::{SessionData, TokenSource};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// What changed between two extractions of a session, from `SessionData::diff`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionDataDiff {
+    /// Keyed by `name (domain path)`
+    pub cookies: EntryChanges,
+    pub local_storage: EntryChanges,
+    pub session_storage: EntryChanges,
+    /// Keyed by `name (source, origin)`
+    pub auth_tokens: EntryChanges,
+    /// localStorage of child frames, keyed by `key (origin)`
+    #[serde(default)]
+    pub frame_local_storage: EntryChanges,
+    /// Whether values are shown; otherwise each is `[REDACTED]`
+    pub revealed: bool,
+}
+
+/// Entries of one kind that appeared, disappeared or got a new value
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryChanges {
+    #[serde(default)]
+    pub added: Vec<EntryChange>,
+    #[serde(default)]
+    pub removed: Vec<EntryChange>,
+    #[serde(default)]
+    pub changed: Vec<EntryChange>,
+}
+
+/// One cookie, storage item or token, with its value before and after
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryChange {
+    pub key: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl EntryChanges {
+    /// Compare two key-value listings; keys appearing twice keep their last value
+    fn between(
+        before: impl IntoIterator<Item = (String, String)>,
+        after: impl IntoIterator<Item = (String, String)>,
+        reveal: bool,
+    ) -> Self {
+        let before: BTreeMap<_, _> = before.into_iter().collect();
+        let after: BTreeMap<_, _> = after.into_iter().collect();
+        let shown = |value: &String| {
+            Some(if reveal {
+                value.clone()
+            } else {
+                REDACTED.to_string()
+            })
+        };
+        let mut changes = Self::default();
+        for (key, old_value) in &before {
+            match after.get(key) {
+                None => changes.removed.push(EntryChange {
+                    key: key.clone(),
+                    old_value: shown(old_value),
+                    new_value: None,
+                }),
+                Some(new_value) if new_value != old_value => changes.changed.push(EntryChange {
+                    key: key.clone(),
+                    old_value: shown(old_value),
+                    new_value: shown(new_value),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (key, new_value) in &after {
+            if !before.contains_key(key) {
+                changes.added.push(EntryChange {
+                    key: key.clone(),
+                    old_value: None,
+                    new_value: shown(new_value),
+                });
+            }
+        }
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl SessionDataDiff {
+    pub(crate) fn between(before: &SessionData, after: &SessionData, reveal: bool) -> Self {
+        let cookies = |data: &SessionData| {
+            data.cookies
+                .iter()
+                .map(|cookie| {
+                    (
+                        format!("{} ({} {})", cookie.name, cookie.domain, cookie.path),
+                        cookie.value.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let tokens = |data: &SessionData| {
+            data.auth_tokens
+                .iter()
+                .map(|token| {
+                    let source = match token.source {
+                        TokenSource::LocalStorage => "local_storage",
+                        TokenSource::SessionStorage => "session_storage",
+                        TokenSource::Cookie => "cookie",
+                        TokenSource::Meta => "meta",
+                    };
+                    (
+                        format!("{} ({}, {})", token.name, source, token.origin),
+                        token.value.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let frame_storage = |data: &SessionData| {
+            data.frame_local_storage
+                .iter()
+                .flat_map(|(origin, storage)| {
+                    storage
+                        .iter()
+                        .map(move |(key, value)| (format!("{} ({})", key, origin), value.clone()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Self {
+            cookies: EntryChanges::between(cookies(before), cookies(after), reveal),
+            local_storage: EntryChanges::between(
+                before.local_storage.clone(),
+                after.local_storage.clone(),
+                reveal,
+            ),
+            session_storage: EntryChanges::between(
+                before.session_storage.clone(),
+                after.session_storage.clone(),
+                reveal,
+            ),
+            auth_tokens: EntryChanges::between(tokens(before), tokens(after), reveal),
+            frame_local_storage: EntryChanges::between(
+                frame_storage(before),
+                frame_storage(after),
+                reveal,
+            ),
+            revealed: reveal,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections()
+            .iter()
+            .all(|(_, changes)| changes.is_empty())
+    }
+
+    /// One line counting each kind of change, for logs
+    pub fn summary(&self) -> String {
+        self.sections()
+            .iter()
+            .map(|(name, changes)| {
+                format!(
+                    "{}: +{} -{} ~{}",
+                    name,
+                    changes.added.len(),
+                    changes.removed.len(),
+                    changes.changed.len()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn sections(&self) -> [(&'static str, &EntryChanges); 5] {
+        [
+            ("cookies", &self.cookies),
+            ("localStorage", &self.local_storage),
+            ("sessionStorage", &self.session_storage),
+            ("auth tokens", &self.auth_tokens),
+            ("frame localStorage", &self.frame_local_storage),
+        ]
+    }
+}
+
+/// One line per change under a heading per kind, e.g. `~ sid (.example.com /):
+/// [REDACTED] → [REDACTED]` for a rotated cookie
+impl fmt::Display for SessionDataDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("no session changes");
+        }
+        let value = |value: &Option<String>| value.as_deref().unwrap_or_default().to_string();
+        for (name, changes) in self.sections() {
+            if changes.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", name)?;
+            for change in &changes.added {
+                writeln!(f, "  + {} = {}", change.key, value(&change.new_value))?;
+            }
+            for change in &changes.removed {
+                writeln!(f, "  - {} (was {})", change.key, value(&change.old_value))?;
+            }
+            for change in &changes.changed {
+                writeln!(
+                    f,
+                    "  ~ {}: {} → {}",
+                    change.key,
+                    value(&change.old_value),
+                    value(&change.new_value)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::session::{CookieData, SessionMetadata, TokenEntry};
+    use std::collections::HashMap;
+
+    fn data() -> SessionData {
+        SessionData {
+            session_id: "s1".to_string(),
+            domain: "example.com".to_string(),
+            url: "https://example.com/".to_string(),
+            cookies: Vec::new(),
+            local_storage: HashMap::new(),
+            session_storage: HashMap::new(),
+            user_agent: None,
+            viewport: None,
+            custom_headers: HashMap::new(),
+            auth_tokens: Vec::new(),
+            frame_local_storage: HashMap::new(),
+            truncated_local_storage: Vec::new(),
+            truncated_session_storage: Vec::new(),
+            indexed_db: Vec::new(),
+            timestamp: chrono::Utc::now(),
+            metadata: SessionMetadata {
+                login_selectors: Vec::new(),
+                success_indicators: Vec::new(),
+                failure_indicators: Vec::new(),
+                csrf_tokens: HashMap::new(),
+                form_data: HashMap::new(),
+            },
+        }
+    }
+
+    fn storage(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn change(key: &str, old_value: Option<&str>, new_value: Option<&str>) -> EntryChange {
+        EntryChange {
+            key: key.to_string(),
+            old_value: old_value.map(str::to_string),
+            new_value: new_value.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn frame_storage_is_diffed_per_origin() {
+        let mut before = data();
+        before.frame_local_storage = HashMap::from([
+            (
+                "https://auth.example".to_string(),
+                storage(&[("token", "a1"), ("theme", "dark")]),
+            ),
+            (
+                "https://ads.example".to_string(),
+                storage(&[("token", "same")]),
+            ),
+        ]);
+        let mut after = data();
+        after.frame_local_storage = HashMap::from([
+            (
+                "https://auth.example".to_string(),
+                storage(&[("token", "a2"), ("theme", "dark")]),
+            ),
+            (
+                "https://ads.example".to_string(),
+                storage(&[("token", "same"), ("seen", "1")]),
+            ),
+            (
+                "https://chat.example".to_string(),
+                storage(&[("token", "a1")]),
+            ),
+        ]);
+        before.frame_local_storage.insert(
+            "https://gone.example".to_string(),
+            storage(&[("visits", "3")]),
+        );
+
+        let diff = before.diff_revealed(&after);
+        assert_eq!(
+            diff.frame_local_storage,
+            EntryChanges {
+                added: vec![
+                    change("seen (https://ads.example)", None, Some("1")),
+                    change("token (https://chat.example)", None, Some("a1")),
+                ],
+                removed: vec![change("visits (https://gone.example)", Some("3"), None)],
+                changed: vec![change(
+                    "token (https://auth.example)",
+                    Some("a1"),
+                    Some("a2")
+                )],
+            }
+        );
+        assert!(diff.local_storage.is_empty());
+        assert_eq!(
+            diff.summary(),
+            "cookies: +0 -0 ~0, localStorage: +0 -0 ~0, sessionStorage: +0 -0 ~0, \
+             auth tokens: +0 -0 ~0, frame localStorage: +2 -1 ~1"
+        );
+    }
+
+    #[test]
+    fn values_are_redacted_unless_revealed() {
+        let mut before = data();
+        before.cookies.push(CookieData {
+            name: "sid".to_string(),
+            value: "old-sid".to_string(),
+            domain: ".example.com".to_string(),
+            path: "/".to_string(),
+            expires: None,
+            http_only: true,
+            secure: true,
+            same_site: None,
+        });
+        before.frame_local_storage.insert(
+            "https://auth.example".to_string(),
+            storage(&[("token", "frame-old")]),
+        );
+        let mut after = before.clone();
+        after.cookies[0].value = "new-sid".to_string();
+        after.frame_local_storage.insert(
+            "https://auth.example".to_string(),
+            storage(&[("token", "frame-new")]),
+        );
+        after.auth_tokens.push(TokenEntry {
+            name: "access_token".to_string(),
+            value: "jwt".to_string(),
+            source: TokenSource::LocalStorage,
+            origin: "https://example.com".to_string(),
+            frame_path: Vec::new(),
+        });
+
+        let diff = before.diff(&after);
+        assert!(!diff.revealed);
+        assert_eq!(
+            diff.to_string(),
+            "cookies:\n  ~ sid (.example.com /): [REDACTED] → [REDACTED]\n\
+             auth tokens:\n  + access_token (local_storage, https://example.com) = [REDACTED]\n\
+             frame localStorage:\n  ~ token (https://auth.example): [REDACTED] → [REDACTED]\n"
+        );
+        for value in ["old-sid", "new-sid", "jwt", "frame-old", "frame-new"] {
+            assert!(!serde_json::to_string(&diff).unwrap().contains(value));
+        }
+
+        let revealed = before.diff_revealed(&after);
+        assert_eq!(
+            revealed.frame_local_storage.changed,
+            [change(
+                "token (https://auth.example)",
+                Some("frame-old"),
+                Some("frame-new")
+            )]
+        );
+        assert_eq!(
+            revealed.cookies.changed,
+            [change(
+                "sid (.example.com /)",
+                Some("old-sid"),
+                Some("new-sid")
+            )]
+        );
+    }
+
+    #[test]
+    fn identical_extractions_have_no_changes() {
+        let mut before = data();
+        before.local_storage = storage(&[("theme", "dark")]);
+        before.frame_local_storage.insert(
+            "https://auth.example".to_string(),
+            storage(&[("token", "a1")]),
+        );
+        let diff = before.diff(&before.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "no session changes");
+    }
+}
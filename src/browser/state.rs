@@ -0,0 +1,331 @@
+use crate::core::config::BusyPolicy;
+use crate::errors::{BrowserAgentError, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{broadcast, Notify};
+
+/// Lifecycle state of a `BrowserSession`.
+///
+/// ```text
+///                 navigate_smart            done
+///        ┌──────────────────────────► Navigating ──────┐
+///        │                                             │
+///  ──► Idle ◄──────────────────────────────────────────┘
+///        │  ▲
+///        │  │ last interaction done
+///        ▼  │
+///     Interacting  (click / type, may overlap)
+///        │
+///  Idle ─┴─► Recovering ──► Idle
+///
+///  any state ── close ──► Closed   (terminal)
+/// ```
+///
+/// `Navigating` and `Recovering` are exclusive; several interactions may run at
+/// once. An operation that arrives while the session is busy waits or fails
+/// according to `SessionConfig::busy_policy`. Everything fails with `SessionClosed`
+/// once the session is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionState {
+    Idle,
+    Navigating,
+    Interacting,
+    Recovering,
+    Closed,
+}
+
+/// A state change published on `BrowserSession::state_events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransition {
+    pub from: SessionState,
+    pub to: SessionState,
+    /// Operation that caused the change
+    pub reason: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+struct Inner {
+    state: SessionState,
+    interactions: usize,
+}
+
+pub(crate) struct StateTracker {
+    inner: Mutex<Inner>,
+    events: broadcast::Sender<StateTransition>,
+    changed: Notify,
+}
+
+impl StateTracker {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            inner: Mutex::new(Inner {
+                state: SessionState::Idle,
+                interactions: 0,
+            }),
+            events,
+            changed: Notify::new(),
+        }
+    }
+
+    pub fn state(&self) -> SessionState {
+        self.inner.lock().unwrap().state
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StateTransition> {
+        self.events.subscribe()
+    }
+
+    fn transition(&self, inner: &mut Inner, to: SessionState, reason: &str) {
+        if inner.state == to {
+            return;
+        }
+        let transition = StateTransition {
+            from: inner.state,
+            to,
+            reason: reason.to_string(),
+            timestamp: chrono::Utc::now(),
+        };
+        inner.state = to;
+        // Nobody listening is fine
+        let _ = self.events.send(transition);
+        self.changed.notify_waiters();
+    }
+
+    /// Enter `target` for the lifetime of the returned guard
+    pub async fn enter(
+        &self,
+        target: SessionState,
+        reason: &str,
+        policy: BusyPolicy,
+        wait_timeout_ms: u64,
+    ) -> Result<StateGuard<'_>> {
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(wait_timeout_ms);
+
+        loop {
+            // Register for wakeups before checking so a release is not missed
+            let notified = self.changed.notified();
+            {
+                let mut inner = self.inner.lock().unwrap();
+                let admitted = match (inner.state, target) {
+                    (SessionState::Closed, _) => return Err(BrowserAgentError::SessionClosed),
+                    (SessionState::Idle, _) => true,
+                    (SessionState::Interacting, SessionState::Interacting) => true,
+                    _ => false,
+                };
+
+                if admitted {
+                    if target == SessionState::Interacting {
+                        inner.interactions += 1;
+                    }
+                    self.transition(&mut inner, target, reason);
+                    return Ok(StateGuard {
+                        tracker: self,
+                        state: target,
+                        reason: reason.to_string(),
+                    });
+                }
+
+                if policy == BusyPolicy::Error {
+                    return Err(BrowserAgentError::SessionBusy(format!(
+                        "{} rejected while {:?}",
+                        reason, inner.state
+                    )));
+                }
+            }
+
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return Err(BrowserAgentError::TimeoutError(format!(
+                    "{} waited {}ms for the session to become available",
+                    reason, wait_timeout_ms
+                )));
+            }
+        }
+    }
+
+    /// Move to `Closed`; every later `enter` fails
+    pub fn close(&self, reason: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.interactions = 0;
+        self.transition(&mut inner, SessionState::Closed, reason);
+    }
+
+    fn release(&self, state: SessionState, reason: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == SessionState::Closed {
+            return;
+        }
+        if state == SessionState::Interacting {
+            inner.interactions = inner.interactions.saturating_sub(1);
+            if inner.interactions > 0 {
+                return;
+            }
+        }
+        self.transition(&mut inner, SessionState::Idle, reason);
+    }
+}
+
+/// Returns the session to `Idle` when dropped
+pub(crate) struct StateGuard<'a> {
+    tracker: &'a StateTracker,
+    state: SessionState,
+    reason: String,
+}
+
+impl Drop for StateGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.release(self.state, &self.reason);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(
+        events: &mut broadcast::Receiver<StateTransition>,
+    ) -> Vec<(SessionState, SessionState)> {
+        let mut seen = Vec::new();
+        while let Ok(transition) = events.try_recv() {
+            seen.push((transition.from, transition.to));
+        }
+        seen
+    }
+
+    async fn enter<'a>(
+        tracker: &'a StateTracker,
+        target: SessionState,
+        policy: BusyPolicy,
+    ) -> Result<StateGuard<'a>> {
+        tracker.enter(target, "test", policy, 1_000).await
+    }
+
+    #[tokio::test]
+    async fn navigate_interact_close_in_order() {
+        use SessionState::*;
+        let tracker = StateTracker::new();
+        let mut events = tracker.subscribe();
+
+        drop(
+            enter(&tracker, Navigating, BusyPolicy::Error)
+                .await
+                .unwrap(),
+        );
+        let first = enter(&tracker, Interacting, BusyPolicy::Error)
+            .await
+            .unwrap();
+        let second = enter(&tracker, Interacting, BusyPolicy::Error)
+            .await
+            .unwrap();
+        drop(first);
+        assert_eq!(tracker.state(), Interacting);
+        drop(second);
+        tracker.close("close");
+
+        assert_eq!(
+            drain(&mut events),
+            [
+                (Idle, Navigating),
+                (Navigating, Idle),
+                (Idle, Interacting),
+                (Interacting, Idle),
+                (Idle, Closed)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn busy_policy_error_rejects_overlap() {
+        let tracker = StateTracker::new();
+        let _navigating = enter(&tracker, SessionState::Navigating, BusyPolicy::Error)
+            .await
+            .unwrap();
+        for target in [SessionState::Interacting, SessionState::Recovering] {
+            assert!(matches!(
+                enter(&tracker, target, BusyPolicy::Error).await,
+                Err(BrowserAgentError::SessionBusy(_))
+            ));
+        }
+
+        // Interactions overlap each other, but nothing else
+        let tracker = StateTracker::new();
+        let _interacting = enter(&tracker, SessionState::Interacting, BusyPolicy::Error)
+            .await
+            .unwrap();
+        assert!(
+            enter(&tracker, SessionState::Interacting, BusyPolicy::Error)
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            enter(&tracker, SessionState::Navigating, BusyPolicy::Error).await,
+            Err(BrowserAgentError::SessionBusy(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn busy_policy_wait_admits_after_release() {
+        use SessionState::*;
+        let tracker = StateTracker::new();
+        let mut events = tracker.subscribe();
+        let navigating = enter(&tracker, Navigating, BusyPolicy::Wait).await.unwrap();
+
+        let waiting = async {
+            let _guard = enter(&tracker, Interacting, BusyPolicy::Wait)
+                .await
+                .unwrap();
+            tracker.state()
+        };
+        let releasing = async {
+            tokio::task::yield_now().await;
+            drop(navigating);
+        };
+        let (state, ()) = tokio::join!(waiting, releasing);
+        assert_eq!(state, Interacting);
+        assert_eq!(
+            drain(&mut events),
+            [
+                (Idle, Navigating),
+                (Navigating, Idle),
+                (Idle, Interacting),
+                (Interacting, Idle)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn busy_policy_wait_times_out() {
+        let clock = crate::utils::VirtualClock::start();
+        let started = std::time::Instant::now();
+        let tracker = StateTracker::new();
+        let _recovering = enter(&tracker, SessionState::Recovering, BusyPolicy::Wait)
+            .await
+            .unwrap();
+        assert!(matches!(
+            tracker
+                .enter(SessionState::Navigating, "test", BusyPolicy::Wait, 60_000)
+                .await,
+            Err(BrowserAgentError::TimeoutError(_))
+        ));
+        // The whole minute passes on virtual time
+        assert_eq!(clock.elapsed().as_secs(), 60);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn closed_is_terminal() {
+        let tracker = StateTracker::new();
+        let guard = enter(&tracker, SessionState::Interacting, BusyPolicy::Wait)
+            .await
+            .unwrap();
+        tracker.close("close");
+        // Releasing an operation that outlived the session leaves it closed
+        drop(guard);
+        assert_eq!(tracker.state(), SessionState::Closed);
+        assert!(matches!(
+            enter(&tracker, SessionState::Interacting, BusyPolicy::Wait).await,
+            Err(BrowserAgentError::SessionClosed)
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::browser::navigation::NavigationResult;
+use crate::dom::DomDiff;
+use serde::Deserialize;
+
+/// What happened after `submit_and_wait` submitted a form
+#[derive(Debug, Clone)]
+pub enum SubmitOutcome {
+    /// A new document loaded
+    Navigated { navigation: NavigationResult },
+    /// Same document, new URL through the history API
+    RouteChanged { from: String, to: String },
+    /// Error messages appeared near the form, fields were marked invalid, or the
+    /// browser's own constraint validation blocked the submission
+    ValidationError {
+        messages: Vec<String>,
+        invalid_fields: Vec<String>,
+    },
+    /// A status message or toast appeared
+    Success { message: String },
+    /// None of the above before the timeout
+    NoChange,
+}
+
+impl SubmitOutcome {
+    /// Short description, for logs and error messages
+    pub fn describe(&self) -> String {
+        match self {
+            SubmitOutcome::Navigated { navigation } => format!("navigated to {}", navigation.url),
+            SubmitOutcome::RouteChanged { from, to } => {
+                format!("route changed from {} to {}", from, to)
+            }
+            SubmitOutcome::ValidationError {
+                messages,
+                invalid_fields,
+            } => {
+                let mut parts = Vec::new();
+                if !messages.is_empty() {
+                    parts.push(format!("'{}'", messages.join("', '")));
+                }
+                if !invalid_fields.is_empty() {
+                    parts.push(format!("invalid fields {}", invalid_fields.join(", ")));
+                }
+                format!("validation failed: {}", parts.join("; "))
+            }
+            SubmitOutcome::Success { message } => format!("success message '{}'", message),
+            SubmitOutcome::NoChange => "no visible change".to_string(),
+        }
+    }
+}
+
+/// `SubmitOutcome` with the page changes seen while waiting for it
+#[derive(Debug, Clone)]
+pub struct SubmitResult {
+    pub outcome: SubmitOutcome,
+    /// Changes to the document the form was on; empty after a navigation
+    pub diff: DomDiff,
+    /// URL once the outcome was known
+    pub url: String,
+    pub duration_ms: u64,
+}
+
+/// Shape of `status_script`'s result
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct SubmitStatus {
+    /// `false` once the watched document has been replaced
+    #[serde(default)]
+    pub same_document: bool,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub errors: Vec<String>,
+    #[serde(default)]
+    pub invalid: Vec<String>,
+    #[serde(default)]
+    pub success: Vec<String>,
+    #[serde(default)]
+    pub diff: DomDiff,
+}
+
+/// Script recording the page around the form that the element `target` evaluates
+/// to is or belongs to:
+/// the error and status messages already showing, the document's mutations from
+/// now on and the fields the browser reports invalid. Stored under `token`.
+/// Evaluates to `{ found, url, isForm }`.
+pub(crate) fn watch_script(target: &str, token: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+{describe}
+            const target = {target};
+            if (!target) return {{ found: false }};
+            if (window.__surfaiSubmit) window.__surfaiSubmit.stop();
+
+            const form = target.tagName === 'FORM' ? target : (target.form || target.closest('form'));
+            const scope = form ? (form.parentElement || document.body) : document.body;
+            const limit = 50;
+            const text = (node) => (node.innerText || node.textContent || '').trim().replace(/\s+/g, ' ').slice(0, 200);
+            const visible = (node) => {{
+                const rect = node.getBoundingClientRect();
+                const style = window.getComputedStyle(node);
+                return rect.width > 0 && rect.height > 0 && style.visibility !== 'hidden' && style.display !== 'none';
+            }};
+            const internal = (node) => node.closest && node.closest('[data-surfai-internal]');
+            const errorSelector = '[role="alert"], [aria-live="assertive"], .error, .errors, .invalid-feedback, '
+                + '.field-error, .form-error, [class*="error"]';
+            const successSelector = '[role="status"], [aria-live="polite"], .toast, .alert-success, .success, '
+                + '.notification, [class*="toast"], [class*="success"]';
+            const messages = (selector, root) => Array.from(root.querySelectorAll(selector))
+                .filter(node => !internal(node) && visible(node)).map(text).filter(Boolean);
+
+            const state = {{
+                token: {token},
+                added: [],
+                removed: [],
+                changed: [],
+                invalid: [],
+                errorsBefore: new Set(messages(errorSelector, scope)),
+                successBefore: new Set(messages(successSelector, document))
+            }};
+            state.observer = new MutationObserver(records => {{
+                for (const record of records) {{
+                    if (record.type === 'childList') {{
+                        for (const node of record.addedNodes) {{
+                            if (node.nodeType !== 1 || internal(node) || state.added.length >= limit) continue;
+                            state.added.push({{ selector: cssPath(node), tag_name: node.tagName.toLowerCase(), text: text(node) }});
+                        }}
+                        for (const node of record.removedNodes) {{
+                            if (node.nodeType !== 1 || internal(node) || state.removed.length >= limit) continue;
+                            state.removed.push({{
+                                selector: record.target.nodeType === 1 ? cssPath(record.target) : '',
+                                tag_name: node.tagName.toLowerCase(),
+                                text: text(node)
+                            }});
+                        }}
+                    }} else if (record.type === 'attributes' && !internal(record.target) && state.changed.length < limit) {{
+                        state.changed.push({{
+                            selector: cssPath(record.target),
+                            attribute: record.attributeName,
+                            old_value: record.oldValue,
+                            new_value: record.target.getAttribute(record.attributeName)
+                        }});
+                    }}
+                }}
+            }});
+            state.observer.observe(document.documentElement, {{
+                childList: true,
+                subtree: true,
+                attributes: true,
+                attributeOldValue: true,
+                attributeFilter: ['aria-invalid', 'class', 'hidden', 'disabled']
+            }});
+            const onInvalid = (event) => state.invalid.push(cssPath(event.target));
+            if (form) form.addEventListener('invalid', onInvalid, true);
+
+            state.stop = () => {{
+                state.observer.disconnect();
+                if (form) form.removeEventListener('invalid', onInvalid, true);
+                if (window.__surfaiSubmit === state) delete window.__surfaiSubmit;
+            }};
+            state.read = () => {{
+                const markedInvalid = state.changed
+                    .filter(change => change.attribute === 'aria-invalid' && change.new_value === 'true')
+                    .map(change => change.selector);
+                return {{
+                    same_document: true,
+                    url: location.href,
+                    errors: messages(errorSelector, scope).filter(message => !state.errorsBefore.has(message)),
+                    invalid: Array.from(new Set(state.invalid.concat(markedInvalid))),
+                    success: messages(successSelector, document).filter(message => !state.successBefore.has(message)),
+                    diff: {{ added: state.added, removed: state.removed, changed: state.changed }}
+                }};
+            }};
+            window.__surfaiSubmit = state;
+            return {{ found: true, url: location.href, isForm: target.tagName === 'FORM' }};
+        }})()
+        "#,
+        describe = crate::dom::xpath::DESCRIBE_ELEMENT_JS,
+        token = serde_json::to_string(token).unwrap_or_else(|_| "''".to_string())
+    )
+}
+
+/// Script submitting the form `target` evaluates to the way a submit button
+/// would, running constraint validation first
+pub(crate) fn request_submit_script(target: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const form = {target};
+            if (!form) return false;
+            if (form.requestSubmit) form.requestSubmit(); else form.submit();
+            return true;
+        }})()
+        "#
+    )
+}
+
+/// Script reading what the watcher stored under `token` has seen, in the shape of
+/// `SubmitStatus`
+pub(crate) fn status_script(token: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const state = window.__surfaiSubmit;
+            if (!state || state.token !== {token}) return {{ same_document: false, url: location.href }};
+            return state.read();
+        }})()
+        "#,
+        token = serde_json::to_string(token).unwrap_or_else(|_| "''".to_string())
+    )
+}
+
+/// Script removing the watcher stored under `token`
+pub(crate) fn stop_script(token: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const state = window.__surfaiSubmit;
+            if (state && state.token === {token}) state.stop();
+            return true;
+        }})()
+        "#,
+        token = serde_json::to_string(token).unwrap_or_else(|_| "''".to_string())
+    )
+}
@@ -0,0 +1,77 @@
+use crate::dom::xpath::DESCRIBE_ELEMENT_JS;
+use crate::dom::CoordinateSpace;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// How `BrowserSession::click_rect` reads and reaches the box it is given
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ClickRectOptions {
+    /// Space of the screenshot the box was drawn on, making the box device pixels
+    /// of that screenshot. `None` reads it as viewport CSS pixels.
+    pub screenshot_space: Option<CoordinateSpace>,
+    /// Scroll the box into view when its center lies outside the viewport, rather
+    /// than failing
+    pub scroll_into_view: bool,
+}
+
+impl ClickRectOptions {
+    /// Box in device pixels of a screenshot captured in `space`
+    pub fn from_screenshot(space: CoordinateSpace) -> Self {
+        Self {
+            screenshot_space: Some(space),
+            ..Self::default()
+        }
+    }
+
+    pub fn scroll_into_view(mut self) -> Self {
+        self.scroll_into_view = true;
+        self
+    }
+}
+
+/// Script describing the topmost element at viewport point (`x`, `y`). Evaluates to
+/// `{ inViewport, width, height, internal, element }`, where `internal` says the
+/// element is something the crate injected, such as a highlight.
+pub(crate) fn hit_test_script(x: f64, y: f64) -> String {
+    format!(
+        r#"
+        (function() {{
+{describe}
+            const x = {x};
+            const y = {y};
+            const width = window.innerWidth;
+            const height = window.innerHeight;
+            if (!(x >= 0 && y >= 0 && x < width && y < height)) {{
+                return {{ inViewport: false, width: width, height: height }};
+            }}
+            const hit = document.elementFromPoint(x, y);
+            return {{
+                inViewport: true,
+                width: width,
+                height: height,
+                internal: !!(hit && hit.closest('[data-surfai-internal]')),
+                element: hit ? describeElement(hit) : null
+            }};
+        }})()
+        "#,
+        describe = DESCRIBE_ELEMENT_JS,
+        x = x,
+        y = y
+    )
+}
+
+/// Shape of `hit_test_script`'s result
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PointHit {
+    #[serde(default)]
+    pub in_viewport: bool,
+    #[serde(default)]
+    pub width: f64,
+    #[serde(default)]
+    pub height: f64,
+    #[serde(default)]
+    pub internal: bool,
+    #[serde(default)]
+    pub element: Option<Value>,
+}
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+
+/// Where typed text goes relative to what the field already holds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeMode {
+    /// Replace the whole content
+    #[default]
+    Replace,
+    /// Add after the existing content
+    Append,
+    /// Add before the existing content
+    Prepend,
+    /// Replace the current selection, or insert at the caret; at the end when the
+    /// field has no caret
+    InsertAtCursor,
+}
+
+impl TypeMode {
+    fn as_js(&self) -> &'static str {
+        match self {
+            TypeMode::Replace => "replace",
+            TypeMode::Append => "append",
+            TypeMode::Prepend => "prepend",
+            TypeMode::InsertAtCursor => "cursor",
+        }
+    }
+}
+
+/// Field content around a `type_text_with_mode` call, for verifying the result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeResult {
+    pub mode: TypeMode,
+    /// `value` of inputs and textareas, text content of editable elements;
+    /// `[REDACTED]` for password fields and values typed with `type_secret`
+    pub before: String,
+    pub after: String,
+    pub element_type: String,
+}
+
+/// Script inserting `text` into the element `element_expression` yields as `mode`
+/// says.
+///
+/// Inputs and textareas get their value through the native setter, so frameworks
+/// tracking it see the change. Editable elements get the text through the editing
+/// commands, falling back to inserting a text node at the range, so the markup
+/// around it keeps its formatting. With `keystroke` the insertion is wrapped in key
+/// events for a single typed character; with `commit` a `change` event follows.
+/// Evaluates to `{ success, before, after, elementType, secret, error }`, where
+/// `secret` says the element is a password field or was marked by `type_secret`.
+pub(crate) fn insert_text_script(
+    element_expression: &str,
+    text: &str,
+    mode: TypeMode,
+    keystroke: bool,
+    commit: bool,
+) -> String {
+    format!(
+        r#"
+        (function() {{
+            const element = {element};
+            if (!element) return {{ success: false, error: 'Element not found' }};
+            const text = {text};
+            const mode = '{mode}';
+            const keyInit = {{ key: text, bubbles: true, cancelable: true }};
+            const elementType = element.tagName.toLowerCase();
+            const secret = element.__surfaiSecret === true
+                || (elementType === 'input' && (element.type || '').toLowerCase() === 'password');
+
+            try {{
+                element.focus();
+                if ({keystroke} && !element.dispatchEvent(new KeyboardEvent('keydown', keyInit))) {{
+                    const value = 'value' in element ? element.value : element.textContent;
+                    return {{ success: true, before: value, after: value, elementType: elementType, secret: secret }};
+                }}
+                if ({keystroke}) element.dispatchEvent(new KeyboardEvent('keypress', keyInit));
+
+                let before, after;
+                if (elementType === 'input' || elementType === 'textarea') {{
+                    before = element.value;
+                    let start = before.length;
+                    let end = before.length;
+                    let caret = null;
+                    try {{
+                        caret = [element.selectionStart, element.selectionEnd];
+                    }} catch (e) {{}}
+                    // Inputs such as email and number have no selection API
+                    if (caret === null || caret[0] === null) {{
+                        const stored = element.__surfaiCaret;
+                        caret = typeof stored === 'number' && stored <= before.length ? [stored, stored] : null;
+                    }}
+                    if (mode === 'replace') {{
+                        start = 0;
+                    }} else if (mode === 'prepend') {{
+                        start = 0;
+                        end = 0;
+                    }} else if (mode === 'cursor' && caret) {{
+                        [start, end] = caret;
+                    }}
+                    const value = before.slice(0, start) + text + before.slice(end);
+                    const setter = Object.getOwnPropertyDescriptor(Object.getPrototypeOf(element), 'value');
+                    if (setter && setter.set) setter.set.call(element, value); else element.value = value;
+                    const position = start + text.length;
+                    try {{
+                        element.setSelectionRange(position, position);
+                        delete element.__surfaiCaret;
+                    }} catch (e) {{
+                        element.__surfaiCaret = position;
+                    }}
+                    element.dispatchEvent(new InputEvent('input', {{
+                        bubbles: true,
+                        inputType: text ? 'insertText' : 'deleteContentBackward',
+                        data: text || null
+                    }}));
+                    after = element.value;
+                }} else if (element.isContentEditable) {{
+                    before = element.textContent;
+                    const selection = window.getSelection();
+                    let range = null;
+                    if (mode === 'cursor' && selection.rangeCount > 0
+                        && element.contains(selection.getRangeAt(0).commonAncestorContainer)) {{
+                        range = selection.getRangeAt(0);
+                    }} else {{
+                        range = document.createRange();
+                        range.selectNodeContents(element);
+                        if (mode === 'append' || mode === 'cursor') range.collapse(false);
+                        if (mode === 'prepend') range.collapse(true);
+                    }}
+                    selection.removeAllRanges();
+                    selection.addRange(range);
+                    // Editors listen for the input events the editing commands fire
+                    const inserted = text
+                        ? document.execCommand('insertText', false, text)
+                        : document.execCommand('delete', false);
+                    if (!inserted) {{
+                        range.deleteContents();
+                        if (text) {{
+                            const node = document.createTextNode(text);
+                            range.insertNode(node);
+                            range.setStartAfter(node);
+                            range.collapse(true);
+                            selection.removeAllRanges();
+                            selection.addRange(range);
+                        }}
+                        element.dispatchEvent(new InputEvent('input', {{
+                            bubbles: true,
+                            inputType: 'insertText',
+                            data: text || null
+                        }}));
+                    }}
+                    after = element.textContent;
+                }} else {{
+                    return {{ success: false, error: 'Element is not editable', elementType: elementType, secret: secret }};
+                }}
+
+                if ({keystroke}) element.dispatchEvent(new KeyboardEvent('keyup', keyInit));
+                if ({commit}) element.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                return {{ success: true, before: before, after: after, elementType: elementType, secret: secret }};
+            }} catch (error) {{
+                return {{ success: false, error: error.message, elementType: elementType, secret: secret }};
+            }}
+        }})()
+        "#,
+        element = element_expression,
+        text = serde_json::to_string(text).unwrap_or_else(|_| "''".to_string()),
+        mode = mode.as_js(),
+        keystroke = keystroke,
+        commit = commit
+    )
+}
+
+/// Shape of `insert_text_script`'s result
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InsertOutcome {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(default)]
+    pub before: String,
+    #[serde(default)]
+    pub after: String,
+    #[serde(default)]
+    pub element_type: String,
+    /// Password field, or marked secret; its content must not be logged
+    #[serde(default)]
+    pub secret: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
@@ -0,0 +1,82 @@
+use crate::browser::LoginConfig;
+use crate::core::config::WatchdogConfig;
+use crate::core::FrameTarget;
+use crate::errors::Result;
+use crate::utils::SecretString;
+
+/// How `BrowserSession::run_with_relogin` logs the session back in once the
+/// logout watchdog trips; set with `BrowserSession::set_relogin_flow`
+#[derive(Debug, Clone)]
+pub struct ReloginFlow {
+    pub login_url: String,
+    pub username: String,
+    pub password: SecretString,
+    pub login_config: LoginConfig,
+    /// Ask the session's credential providers at each re-login instead of using
+    /// `username` and `password`
+    pub from_provider: bool,
+}
+
+impl ReloginFlow {
+    pub fn new(
+        login_url: impl Into<String>,
+        username: impl Into<String>,
+        password: SecretString,
+    ) -> Self {
+        Self {
+            login_url: login_url.into(),
+            username: username.into(),
+            password,
+            login_config: LoginConfig::default(),
+            from_provider: false,
+        }
+    }
+
+    /// Log back in with the credential the session's providers hold for the
+    /// login URL's host, looked up again at every re-login
+    pub fn from_provider(login_url: impl Into<String>) -> Self {
+        Self {
+            from_provider: true,
+            ..Self::new(login_url, String::new(), SecretString::default())
+        }
+    }
+
+    pub fn with_login_config(mut self, login_config: LoginConfig) -> Self {
+        self.login_config = login_config;
+        self
+    }
+}
+
+/// The first of the configured URL globs matching `url`
+pub(crate) fn matching_url_pattern(config: &WatchdogConfig, url: &str) -> Result<Option<String>> {
+    for pattern in &config.url_patterns {
+        let regex = regex::Regex::new(&FrameTarget::glob_to_regex(pattern)).map_err(|e| {
+            crate::errors::BrowserAgentError::ConfigurationError(format!(
+                "Invalid watchdog URL pattern '{}': {}",
+                pattern, e
+            ))
+        })?;
+        if regex.is_match(url) {
+            return Ok(Some(pattern.clone()));
+        }
+    }
+    Ok(None)
+}
+
+/// Script evaluating to the first of `selectors` present in the document, or
+/// `null`. Selectors the browser rejects are skipped.
+pub(crate) fn indicator_script(selectors: &[String]) -> String {
+    format!(
+        r#"
+        (function() {{
+            for (const selector of {selectors}) {{
+                try {{
+                    if (document.querySelector(selector)) return selector;
+                }} catch (e) {{}}
+            }}
+            return null;
+        }})()
+        "#,
+        selectors = serde_json::to_string(selectors).unwrap_or_else(|_| "[]".to_string())
+    )
+}
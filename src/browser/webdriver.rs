@@ -0,0 +1,452 @@
+//! W3C WebDriver backend, for chromedriver, Selenium Grid and other remote ends.
+//!
+//! Enabled by the `webdriver` feature. Every command is a blocking HTTP request run
+//! on tokio's blocking pool. WebDriver only talks to one window at a time, so tab
+//! commands switch to their window first and are serialized with each other.
+
+use crate::browser::chrome::{launch_args, major_version};
+use crate::core::config::{BrowserConfig, BrowserType, HeadlessMode};
+use crate::core::{BrowserCapabilities, BrowserTrait, Config};
+use crate::errors::{BrowserAgentError, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// How long to wait for the remote end to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Added to the script and page load timeouts so the remote end reports them first
+const RESPONSE_MARGIN: Duration = Duration::from_secs(30);
+
+/// Runs the script in the page's global scope like `Runtime.evaluate`, so statements
+/// and expressions work alike. A returned promise is awaited by the remote end.
+/// Pages whose CSP forbids `eval` get the script as a returned expression instead.
+const EVALUATE_JS: &str = "return (0, eval)(arguments[0]);";
+
+/// A top-level browsing context, addressed by its window handle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WebDriverTab {
+    handle: String,
+}
+
+impl WebDriverTab {
+    /// Window handle the remote end knows the tab by
+    pub fn handle(&self) -> &str {
+        &self.handle
+    }
+}
+
+/// Browser driven through a WebDriver remote end at `browser.webdriver_url`, or
+/// the endpoint given to `with_endpoint`
+pub struct WebDriverBrowser {
+    endpoint: Option<String>,
+    agent: ureq::Agent,
+    /// Session URL, `<endpoint>/session/<id>`, while launched
+    session: Option<String>,
+    capabilities: BrowserCapabilities,
+    /// Window commands currently go to; held while switching and sending
+    current_window: tokio::sync::Mutex<Option<String>>,
+    /// Window the session started with, handed out by the first `new_tab`
+    initial_window: std::sync::Mutex<Option<String>>,
+}
+
+impl Default for WebDriverBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebDriverBrowser {
+    pub fn new() -> Self {
+        Self {
+            endpoint: None,
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(CONNECT_TIMEOUT)
+                .build(),
+            session: None,
+            capabilities: BrowserCapabilities {
+                supports_screenshots: true,
+                ..BrowserCapabilities::javascript_only()
+            },
+            current_window: tokio::sync::Mutex::new(None),
+            initial_window: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Use `endpoint` instead of `browser.webdriver_url`
+    pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+        let mut browser = Self::new();
+        browser.endpoint = Some(endpoint.into());
+        browser
+    }
+
+    /// Id the remote end gave the session, once launched
+    pub fn session_id(&self) -> Option<&str> {
+        self.session
+            .as_deref()
+            .and_then(|session| session.rsplit('/').next())
+    }
+
+    /// Run `script` through `/execute/async`. The script gets `args` followed by a
+    /// callback, and its result is the value passed to that callback.
+    pub async fn execute_async_script(
+        &self,
+        tab: &WebDriverTab,
+        script: &str,
+        args: Vec<Value>,
+    ) -> Result<Value> {
+        self.tab_command(
+            tab,
+            "POST",
+            "/execute/async",
+            Some(json!({ "script": script, "args": args })),
+        )
+        .await
+    }
+
+    /// Send a command to the session
+    async fn command(
+        &self,
+        method: &'static str,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let session = self
+            .session
+            .as_ref()
+            .ok_or(BrowserAgentError::BrowserNotLaunched)?;
+        let url = format!("{}{}", session, path);
+        let agent = self.agent.clone();
+        tokio::task::spawn_blocking(move || send(&agent, method, &url, body.as_ref()))
+            .await
+            .map_err(|e| BrowserAgentError::WebDriverError(e.to_string()))?
+    }
+
+    /// Send a command to the session with `tab` as the current window
+    async fn tab_command(
+        &self,
+        tab: &WebDriverTab,
+        method: &'static str,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let mut current = self.current_window.lock().await;
+        self.switch_to(&mut current, tab).await?;
+        self.command(method, path, body).await
+    }
+
+    async fn switch_to(&self, current: &mut Option<String>, tab: &WebDriverTab) -> Result<()> {
+        if current.as_deref() != Some(tab.handle.as_str()) {
+            self.command("POST", "/window", Some(json!({ "handle": tab.handle })))
+                .await?;
+            *current = Some(tab.handle.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Capabilities asked for when creating the session
+fn requested_capabilities(config: &BrowserConfig) -> Value {
+    let timeout_ms = config.timeout_ms;
+    let mut capabilities = json!({
+        "browserName": match config.browser_type {
+            BrowserType::Chrome => "chrome",
+            BrowserType::Firefox => "firefox",
+            BrowserType::Safari => "safari",
+            BrowserType::Edge => "MicrosoftEdge",
+        },
+        "pageLoadStrategy": "normal",
+        "timeouts": { "script": timeout_ms, "pageLoad": timeout_ms },
+    });
+
+    match config.browser_type {
+        BrowserType::Chrome | BrowserType::Edge => {
+            let (args, warnings) = launch_args(config);
+            for warning in &warnings {
+                println!("⚠️ {}", warning);
+            }
+            let mut options = json!({ "args": args });
+            if let Some(path) = &config.executable_path {
+                options["binary"] = json!(path);
+            }
+            let key = match config.browser_type {
+                BrowserType::Edge => "ms:edgeOptions",
+                _ => "goog:chromeOptions",
+            };
+            capabilities[key] = options;
+        }
+        BrowserType::Firefox => {
+            let mut args = Vec::new();
+            if config.headless != HeadlessMode::Off {
+                args.push("-headless".to_string());
+            }
+            args.extend(config.args.iter().cloned());
+            capabilities["moz:firefoxOptions"] = json!({ "args": args });
+        }
+        BrowserType::Safari => {}
+    }
+
+    json!({ "capabilities": { "alwaysMatch": capabilities } })
+}
+
+/// Blocking request to the remote end, returning the `value` of its response
+fn send(agent: &ureq::Agent, method: &str, url: &str, body: Option<&Value>) -> Result<Value> {
+    let request = agent.request(method, url);
+    let response = match body {
+        Some(body) => request
+            .set("Content-Type", "application/json; charset=utf-8")
+            .send_string(&body.to_string()),
+        None => request.call(),
+    };
+    let (failed, response) = match response {
+        Ok(response) => (false, response),
+        Err(ureq::Error::Status(_, response)) => (true, response),
+        Err(e) => {
+            return Err(BrowserAgentError::WebDriverError(format!(
+                "{} {}: {}",
+                method, url, e
+            )))
+        }
+    };
+
+    let text = response.into_string()?;
+    let value = serde_json::from_str::<Value>(&text)
+        .map(|mut parsed| parsed["value"].take())
+        .map_err(|_| {
+            BrowserAgentError::WebDriverError(format!(
+                "{} {}: unexpected response {}",
+                method, url, text
+            ))
+        })?;
+    if failed {
+        return Err(command_error(&value));
+    }
+    Ok(value)
+}
+
+/// Error for a failed command's `{ error, message }`
+fn command_error(value: &Value) -> BrowserAgentError {
+    let message = value["message"].as_str().unwrap_or_default().to_string();
+    match value["error"].as_str().unwrap_or("unknown error") {
+        "invalid session id" | "no such window" => BrowserAgentError::SessionClosed,
+        "javascript error" => BrowserAgentError::JavaScriptFailed(message),
+        "script timeout" => BrowserAgentError::JavaScriptTimeout,
+        "timeout" => BrowserAgentError::TimeoutError(message),
+        "no such element" => BrowserAgentError::ElementNotFound(message),
+        "no such frame" => BrowserAgentError::FrameNotFound(message),
+        "session not created" => BrowserAgentError::LaunchFailed(message),
+        error => BrowserAgentError::WebDriverError(format!("{}: {}", error, message)),
+    }
+}
+
+#[async_trait]
+impl BrowserTrait for WebDriverBrowser {
+    type TabHandle = WebDriverTab;
+
+    async fn launch(&mut self, config: &Config) -> Result<()> {
+        let endpoint = self
+            .endpoint
+            .clone()
+            .or_else(|| config.browser.webdriver_url.clone())
+            .ok_or_else(|| {
+                BrowserAgentError::ConfigurationError(
+                    "browser.webdriver_url is required for the WebDriver backend".to_string(),
+                )
+            })?;
+        let endpoint = endpoint.trim_end_matches('/').to_string();
+
+        self.agent = ureq::AgentBuilder::new()
+            .timeout_connect(CONNECT_TIMEOUT)
+            .timeout_read(Duration::from_millis(config.browser.timeout_ms) + RESPONSE_MARGIN)
+            .build();
+        let created = {
+            let agent = self.agent.clone();
+            let url = format!("{}/session", endpoint);
+            let body = requested_capabilities(&config.browser);
+            tokio::task::spawn_blocking(move || send(&agent, "POST", &url, Some(&body)))
+                .await
+                .map_err(|e| BrowserAgentError::LaunchFailed(e.to_string()))?
+                .map_err(|e| match e {
+                    BrowserAgentError::LaunchFailed(_) => e,
+                    e => BrowserAgentError::LaunchFailed(e.to_string()),
+                })?
+        };
+        let session_id = created["sessionId"].as_str().ok_or_else(|| {
+            BrowserAgentError::LaunchFailed(format!("no session id in {}", created))
+        })?;
+        self.session = Some(format!("{}/session/{}", endpoint, session_id));
+
+        let granted = &created["capabilities"];
+        let product = match (
+            granted["browserName"].as_str(),
+            granted["browserVersion"].as_str(),
+        ) {
+            (Some(name), Some(version)) => Some(format!("{}/{}", name, version)),
+            _ => None,
+        };
+        println!(
+            "🌐 WebDriver session {} on {}",
+            session_id,
+            product.as_deref().unwrap_or("an unknown browser")
+        );
+        if let Some(minimum) = config.browser.min_version {
+            let found = product.clone().unwrap_or_else(|| "unknown".to_string());
+            if product
+                .as_deref()
+                .and_then(major_version)
+                .is_none_or(|major| major < minimum)
+            {
+                let _ = self.close().await;
+                return Err(BrowserAgentError::UnsupportedBrowserVersion { found, minimum });
+            }
+        }
+        self.capabilities.browser_version = product;
+
+        // Window size stands in for the viewport, which WebDriver cannot set
+        let viewport = &config.browser.viewport;
+        if let Err(e) = self
+            .command(
+                "POST",
+                "/window/rect",
+                Some(json!({ "width": viewport.width, "height": viewport.height })),
+            )
+            .await
+        {
+            tracing::debug!(error = %e, "remote end did not resize the window");
+        }
+
+        let window = self.command("GET", "/window", None).await?;
+        let window = window.as_str().map(str::to_string);
+        *self.current_window.get_mut() = window.clone();
+        *self.initial_window.lock().unwrap() = window;
+        Ok(())
+    }
+
+    async fn new_tab(&self) -> Result<Self::TabHandle> {
+        if let Some(handle) = self.initial_window.lock().unwrap().take() {
+            return Ok(WebDriverTab { handle });
+        }
+        let created = self
+            .command("POST", "/window/new", Some(json!({ "type": "tab" })))
+            .await
+            .map_err(|e| BrowserAgentError::TabCreationFailed(e.to_string()))?;
+        let handle = created["handle"].as_str().ok_or_else(|| {
+            BrowserAgentError::TabCreationFailed(format!("no window handle in {}", created))
+        })?;
+        Ok(WebDriverTab {
+            handle: handle.to_string(),
+        })
+    }
+
+    async fn activate_tab(&self, tab: &Self::TabHandle) -> Result<()> {
+        let mut current = self.current_window.lock().await;
+        self.switch_to(&mut current, tab).await
+    }
+
+    async fn close_tab(&self, tab: &Self::TabHandle) -> Result<()> {
+        let mut current = self.current_window.lock().await;
+        self.switch_to(&mut current, tab).await?;
+        self.command("DELETE", "/window", None).await?;
+        *current = None;
+        Ok(())
+    }
+
+    async fn navigate(&self, tab: &Self::TabHandle, url: &str) -> Result<()> {
+        self.tab_command(tab, "POST", "/url", Some(json!({ "url": url })))
+            .await
+            .map_err(|e| BrowserAgentError::NavigationFailed(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn execute_script(&self, tab: &Self::TabHandle, script: &str) -> Result<Value> {
+        let evaluated = self
+            .tab_command(
+                tab,
+                "POST",
+                "/execute/sync",
+                Some(json!({ "script": EVALUATE_JS, "args": [script] })),
+            )
+            .await;
+        match evaluated {
+            Err(BrowserAgentError::JavaScriptFailed(message)) if message.contains("EvalError") => {
+                self.tab_command(
+                    tab,
+                    "POST",
+                    "/execute/sync",
+                    Some(json!({ "script": format!("return (\n{}\n);", script), "args": [] })),
+                )
+                .await
+            }
+            evaluated => evaluated,
+        }
+    }
+
+    async fn take_screenshot(&self, tab: &Self::TabHandle) -> Result<Vec<u8>> {
+        let encoded = self
+            .tab_command(tab, "GET", "/screenshot", None)
+            .await
+            .map_err(|e| BrowserAgentError::ScreenshotFailed(e.to_string()))?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded.as_str().unwrap_or_default())
+            .map_err(|e| BrowserAgentError::ScreenshotFailed(e.to_string()))
+    }
+
+    async fn get_url(&self, tab: &Self::TabHandle) -> Result<String> {
+        let url = self.tab_command(tab, "GET", "/url", None).await?;
+        Ok(url.as_str().unwrap_or_default().to_string())
+    }
+
+    async fn get_title(&self, tab: &Self::TabHandle) -> Result<String> {
+        let title = self.tab_command(tab, "GET", "/title", None).await?;
+        Ok(title.as_str().unwrap_or_default().to_string())
+    }
+
+    async fn wait_for_navigation(&self, tab: &Self::TabHandle, timeout_ms: u64) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(timeout_ms);
+        while tokio::time::Instant::now() < deadline {
+            let state = self.execute_script(tab, "document.readyState").await?;
+            if state.as_str() == Some("complete") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Ok(())
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "webdriver"
+    }
+
+    fn capabilities(&self) -> &BrowserCapabilities {
+        &self.capabilities
+    }
+
+    fn is_running(&self) -> bool {
+        self.session.is_some()
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        if self.session.is_some() {
+            let closed = self.command("DELETE", "", None).await;
+            self.session = None;
+            closed?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for WebDriverBrowser {
+    /// Ends a session that was not closed, so it does not hold a grid slot until
+    /// the remote end times it out
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            let agent = self.agent.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = send(&agent, "DELETE", &session, None) {
+                    tracing::debug!(error = %e, "could not end WebDriver session");
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use crate::core::Selector;
+use crate::dom::DomDiff;
+use crate::utils::ArtifactRef;
+use serde::{Deserialize, Serialize};
+
+/// Steps `run_wizard` takes unless set with `WizardConfig::with_max_steps`
+pub const DEFAULT_MAX_WIZARD_STEPS: usize = 10;
+
+/// How a wizard step, or the page after the last one, is recognised. Every
+/// condition that is set must hold.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StepMatcher {
+    /// Part of the URL, e.g. `#shipping` or `/checkout/payment`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_contains: Option<String>,
+    /// Text of a visible heading or legend, matched case-insensitively
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    /// CSS selector of a visible element marking the step, e.g. the active item
+    /// of a progress indicator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indicator: Option<String>,
+}
+
+impl StepMatcher {
+    pub fn url_contains(fragment: impl Into<String>) -> Self {
+        Self::default().and_url_contains(fragment)
+    }
+
+    pub fn heading(text: impl Into<String>) -> Self {
+        Self::default().and_heading(text)
+    }
+
+    pub fn indicator(selector: impl Into<String>) -> Self {
+        Self::default().and_indicator(selector)
+    }
+
+    pub fn and_url_contains(mut self, fragment: impl Into<String>) -> Self {
+        self.url_contains = Some(fragment.into());
+        self
+    }
+
+    pub fn and_heading(mut self, text: impl Into<String>) -> Self {
+        self.heading = Some(text.into());
+        self
+    }
+
+    pub fn and_indicator(mut self, selector: impl Into<String>) -> Self {
+        self.indicator = Some(selector.into());
+        self
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.url_contains.is_none() && self.heading.is_none() && self.indicator.is_none()
+    }
+}
+
+impl std::fmt::Display for StepMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(fragment) = &self.url_contains {
+            parts.push(format!("url containing '{}'", fragment));
+        }
+        if let Some(heading) = &self.heading {
+            parts.push(format!("heading '{}'", heading));
+        }
+        if let Some(indicator) = &self.indicator {
+            parts.push(format!("indicator {}", indicator));
+        }
+        f.write_str(&parts.join(" and "))
+    }
+}
+
+/// One step of a wizard: how to recognise it, what to fill in and how to move on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WizardStep {
+    pub name: String,
+    pub matcher: StepMatcher,
+    /// Field label and value pairs, filled in order with `fill_field_by_label`
+    #[serde(default)]
+    pub fill: Vec<(String, String)>,
+    /// Control moving to the next step; `WizardConfig::next` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<Selector>,
+}
+
+impl WizardStep {
+    pub fn new(name: impl Into<String>, matcher: StepMatcher) -> Self {
+        Self {
+            name: name.into(),
+            matcher,
+            fill: Vec::new(),
+            next: None,
+        }
+    }
+
+    pub fn fill(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fill.push((label.into(), value.into()));
+        self
+    }
+
+    pub fn next(mut self, selector: impl Into<Selector>) -> Self {
+        self.next = Some(selector.into());
+        self
+    }
+}
+
+/// What `BrowserSession::run_wizard` walks through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WizardConfig {
+    pub steps: Vec<WizardStep>,
+    /// Recognises the page after the last step
+    pub done: StepMatcher,
+    /// Control moving to the next step, for steps without their own
+    pub next: Selector,
+    /// Steps taken before giving up
+    pub max_steps: usize,
+    /// Screenshot each step once filled in, under `wizard/`
+    pub screenshots: bool,
+}
+
+impl WizardConfig {
+    /// A wizard that is finished once `done` matches, moving on with a button
+    /// labelled "Next"
+    pub fn new(done: StepMatcher) -> Self {
+        Self {
+            steps: Vec::new(),
+            done,
+            next: Selector::role_named("button", "Next"),
+            max_steps: DEFAULT_MAX_WIZARD_STEPS,
+            screenshots: true,
+        }
+    }
+
+    pub fn step(mut self, step: WizardStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    pub fn with_next(mut self, selector: impl Into<Selector>) -> Self {
+        self.next = selector.into();
+        self
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn without_screenshots(mut self) -> Self {
+        self.screenshots = false;
+        self
+    }
+
+    /// Problems that would make the wizard unrunnable
+    pub(crate) fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.steps.is_empty() {
+            problems.push("a wizard needs at least one step".to_string());
+        }
+        if self.done.is_empty() {
+            problems.push("the done matcher sets no condition".to_string());
+        }
+        for step in &self.steps {
+            if step.matcher.is_empty() {
+                problems.push(format!("step '{}' sets no condition", step.name));
+            }
+        }
+        if self.max_steps == 0 {
+            problems.push("max_steps must be positive".to_string());
+        }
+        problems
+    }
+}
+
+/// What one pass through a step did
+#[derive(Debug, Clone)]
+pub struct WizardStepReport {
+    /// 1-based position in the run
+    pub index: usize,
+    pub step: String,
+    /// URL the step was recognised at
+    pub url: String,
+    /// Labels of the fields filled in
+    pub filled: Vec<String>,
+    /// Changes to the document between clicking next and the outcome
+    pub diff: DomDiff,
+    /// Error messages that appeared and fields marked invalid after clicking next
+    pub messages: Vec<String>,
+    pub invalid_fields: Vec<String>,
+    pub screenshot: Option<ArtifactRef>,
+    pub duration_ms: u64,
+}
+
+/// How a wizard run ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum WizardOutcome {
+    /// The done matcher matched
+    Completed,
+    /// A step could not be completed, or came up again without progress
+    Stuck { step: String, reason: String },
+    /// The page matched no step and was not done
+    UnknownStep { url: String, headings: Vec<String> },
+    /// `max_steps` steps were taken without finishing
+    MaxStepsReached,
+}
+
+impl WizardOutcome {
+    pub fn describe(&self) -> String {
+        match self {
+            WizardOutcome::Completed => "completed".to_string(),
+            WizardOutcome::Stuck { step, reason } => format!("stuck on '{}': {}", step, reason),
+            WizardOutcome::UnknownStep { url, headings } => format!(
+                "no step matches {} (headings: {})",
+                url,
+                if headings.is_empty() {
+                    "none".to_string()
+                } else {
+                    format!("'{}'", headings.join("', '"))
+                }
+            ),
+            WizardOutcome::MaxStepsReached => "gave up after the maximum number of steps".into(),
+        }
+    }
+}
+
+/// Result of `BrowserSession::run_wizard`
+#[derive(Debug, Clone)]
+pub struct WizardReport {
+    pub outcome: WizardOutcome,
+    pub steps: Vec<WizardStepReport>,
+    pub final_url: String,
+    pub duration_ms: u64,
+}
+
+impl WizardReport {
+    pub fn completed(&self) -> bool {
+        self.outcome == WizardOutcome::Completed
+    }
+}
+
+/// Artifact name for the screenshot of the `index`th step taken
+pub(crate) fn screenshot_name(index: usize, step: &str) -> String {
+    let slug: String = step
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("wizard/{:02}-{}.png", index, slug)
+}
+
+/// Where the page is, as `probe_script` sees it
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct WizardProbe {
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub headings: Vec<String>,
+    /// One entry per matcher given to `probe_script`, in order
+    #[serde(default)]
+    pub matches: Vec<bool>,
+}
+
+/// Script checking every matcher against the page. Evaluates to
+/// `{ url, headings, matches }`.
+pub(crate) fn probe_script(matchers: &[&StepMatcher]) -> String {
+    format!(
+        r#"
+        (function() {{
+            const matchers = {matchers};
+            const normalize = (value) => (value || '').replace(/\s+/g, ' ').trim().toLowerCase();
+            const shown = (el) => typeof el.checkVisibility === 'function'
+                ? el.checkVisibility()
+                : !!(el.offsetWidth || el.offsetHeight || el.getClientRects().length);
+            const headings = Array.from(document.querySelectorAll('h1, h2, h3, h4, h5, h6, [role="heading"], legend'))
+                .filter(el => shown(el) && !el.closest('[data-surfai-internal]'))
+                .map(el => (el.innerText || el.textContent || '').replace(/\s+/g, ' ').trim())
+                .filter(Boolean);
+            const indicator = (selector) => {{
+                try {{
+                    return Array.from(document.querySelectorAll(selector)).some(shown);
+                }} catch (e) {{
+                    return false;
+                }}
+            }};
+            const matches = matchers.map(matcher =>
+                (!matcher.url_contains || location.href.includes(matcher.url_contains))
+                && (!matcher.heading || headings.some(text => normalize(text).includes(normalize(matcher.heading))))
+                && (!matcher.indicator || indicator(matcher.indicator)));
+            return {{ url: location.href, headings: headings.slice(0, 10), matches }};
+        }})()
+        "#,
+        matchers = serde_json::to_string(matchers).unwrap_or_else(|_| "[]".to_string())
+    )
+}
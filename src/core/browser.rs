@@ -1,5 +1,7 @@
-use crate::errors::Result;
+use crate::errors::{BrowserAgentError, Result};
+use crate::utils::Clock;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 #[async_trait]
@@ -12,6 +14,29 @@ pub trait BrowserTrait: Send + Sync {
     /// Create a new tab/page
     async fn new_tab(&self) -> Result<Self::TabHandle>;
 
+    /// Create a tab that loads without being shown, e.g. for prefetching. Backends
+    /// without background tabs create an ordinary one.
+    async fn new_background_tab(&self) -> Result<Self::TabHandle> {
+        self.new_tab().await
+    }
+
+    /// Create a tab in a browser context of its own, sharing no cookies, storage or
+    /// cache with other tabs. Backends without separate contexts create an ordinary
+    /// one.
+    async fn new_isolated_tab(&self) -> Result<Self::TabHandle> {
+        self.new_tab().await
+    }
+
+    /// Bring `tab` to the front
+    async fn activate_tab(&self, _tab: &Self::TabHandle) -> Result<()> {
+        Ok(())
+    }
+
+    /// Close `tab`; it must not be used afterwards
+    async fn close_tab(&self, _tab: &Self::TabHandle) -> Result<()> {
+        Ok(())
+    }
+
     /// Navigate to a URL
     async fn navigate(&self, tab: &Self::TabHandle, url: &str) -> Result<()>;
 
@@ -30,6 +55,610 @@ pub trait BrowserTrait: Send + Sync {
     /// Wait for navigation to complete
     async fn wait_for_navigation(&self, tab: &Self::TabHandle, timeout_ms: u64) -> Result<()>;
 
+    /// Fail subresource requests that `policy` does not allow.
+    ///
+    /// Backends without request interception keep the default no-op.
+    async fn set_request_filter(
+        &self,
+        _tab: &Self::TabHandle,
+        _policy: crate::core::DomainPolicy,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// List the frames of the page, top frame first.
+    ///
+    /// The default implementation walks `<iframe>` elements from page JavaScript and
+    /// can only see same-origin frames; backends with protocol access should override it.
+    async fn list_frames(&self, tab: &Self::TabHandle) -> Result<Vec<FrameInfo>> {
+        let script = r#"
+            (function() {
+                const frames = [{
+                    id: 'main',
+                    parentId: null,
+                    url: window.location.href,
+                    origin: window.location.origin,
+                    name: window.name || null
+                }];
+                document.querySelectorAll('iframe, frame').forEach((frame, index) => {
+                    let url = frame.src || '';
+                    let origin = '';
+                    try {
+                        url = frame.contentWindow.location.href;
+                        origin = frame.contentWindow.location.origin;
+                    } catch (e) {
+                        try { origin = new URL(url, window.location.href).origin; } catch (_) {}
+                    }
+                    frames.push({
+                        id: 'frame-' + index,
+                        parentId: 'main',
+                        url: url,
+                        origin: origin,
+                        name: frame.name || null
+                    });
+                });
+                return frames;
+            })()
+        "#;
+
+        let result = self.execute_script(tab, script).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Evaluate `script` inside the frame identified by `frame`.
+    ///
+    /// The default implementation calls `eval` on the frame's `contentWindow`, which
+    /// only works for same-origin frames.
+    async fn execute_script_in_frame(
+        &self,
+        tab: &Self::TabHandle,
+        frame: &FrameTarget,
+        script: &str,
+    ) -> Result<Value> {
+        let locate = match frame {
+            FrameTarget::Selector(selector) => format!(
+                "document.querySelector({})",
+                serde_json::to_string(selector)?
+            ),
+            FrameTarget::Id(id) => {
+                let index = id
+                    .strip_prefix("frame-")
+                    .and_then(|i| i.parse::<usize>().ok())
+                    .ok_or_else(|| BrowserAgentError::FrameNotFound(id.clone()))?;
+                format!("document.querySelectorAll('iframe, frame')[{}]", index)
+            }
+            FrameTarget::UrlPattern(pattern) => format!(
+                r#"Array.from(document.querySelectorAll('iframe, frame')).find(f => {{
+                    let url = f.src || '';
+                    try {{ url = f.contentWindow.location.href; }} catch (e) {{}}
+                    return new RegExp({}).test(url);
+                }})"#,
+                serde_json::to_string(&FrameTarget::glob_to_regex(pattern))?
+            ),
+        };
+
+        let wrapped = format!(
+            r#"
+            (function() {{
+                const frame = {};
+                if (!frame) return {{ __surfaiFrameError: 'not_found' }};
+                try {{
+                    return {{ __surfaiFrameValue: frame.contentWindow.eval({}) }};
+                }} catch (e) {{
+                    return {{ __surfaiFrameError: e.message }};
+                }}
+            }})()
+            "#,
+            locate,
+            serde_json::to_string(script)?
+        );
+
+        let result = self.execute_script(tab, &wrapped).await?;
+        match result.get("__surfaiFrameError").and_then(|v| v.as_str()) {
+            Some("not_found") => Err(BrowserAgentError::FrameNotFound(frame.to_string())),
+            Some(error) => Err(BrowserAgentError::JavaScriptFailed(error.to_string())),
+            None => Ok(result
+                .get("__surfaiFrameValue")
+                .cloned()
+                .unwrap_or(Value::Null)),
+        }
+    }
+
+    /// Evaluate `script` and wait for the promise it returns, giving the resolved
+    /// value as JSON; a rejection becomes `JavaScriptFailed`.
+    ///
+    /// The default implementation parks the outcome on `window` and polls for it,
+    /// so the value must survive `JSON.stringify`; backends that can await promises
+    /// natively should override it.
+    async fn evaluate_async(
+        &self,
+        tab: &Self::TabHandle,
+        script: &str,
+        timeout_ms: u64,
+    ) -> Result<Value> {
+        let slot = format!("__surfaiAsync{}", uuid::Uuid::new_v4().simple());
+        let start_script = format!(
+            r#"
+            (function() {{
+                const slot = {slot};
+                window[slot] = {{ done: false }};
+                Promise.resolve().then(() => {script}).then(
+                    value => {{ window[slot] = {{ done: true, value: JSON.stringify(value === undefined ? null : value) }}; }},
+                    error => {{ window[slot] = {{ done: true, error: String((error && error.message) || error) }}; }}
+                );
+                return true;
+            }})()
+            "#,
+            slot = serde_json::to_string(&slot)?,
+            script = script
+        );
+        self.execute_script(tab, &start_script).await?;
+
+        let poll_script = format!(
+            r#"
+            (function() {{
+                const slot = {slot};
+                const outcome = window[slot];
+                if (!outcome) return JSON.stringify({{ done: true, error: 'page navigated away' }});
+                if (outcome.done) delete window[slot];
+                return JSON.stringify(outcome);
+            }})()
+            "#,
+            slot = serde_json::to_string(&slot)?
+        );
+        let start = std::time::Instant::now();
+        loop {
+            let polled = self.execute_script(tab, &poll_script).await?;
+            let outcome: Value = serde_json::from_str(polled.as_str().unwrap_or("{}"))?;
+            if outcome.get("done").and_then(|v| v.as_bool()) == Some(true) {
+                if let Some(error) = outcome.get("error").and_then(|v| v.as_str()) {
+                    return Err(BrowserAgentError::JavaScriptFailed(error.to_string()));
+                }
+                return match outcome.get("value").and_then(|v| v.as_str()) {
+                    Some(value) => Ok(serde_json::from_str(value)?),
+                    None => Ok(Value::Null),
+                };
+            }
+
+            if start.elapsed().as_millis() as u64 >= timeout_ms {
+                let _ = self
+                    .execute_script(
+                        tab,
+                        &format!("delete window[{}]", serde_json::to_string(&slot)?),
+                    )
+                    .await;
+                return Err(BrowserAgentError::TimeoutError(format!(
+                    "Script did not settle within {}ms",
+                    timeout_ms
+                )));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Start recording network requests on `tab` and reset the reported counters.
+    ///
+    /// Returns `false` when the backend cannot observe network events.
+    async fn start_network_tracking(&self, _tab: &Self::TabHandle) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Wait until no request has been in flight for `idle_ms`, timing the wait on
+    /// `clock`.
+    ///
+    /// The default implementation only sees completed requests through the resource
+    /// timing buffer and treats the network as idle once no new entry has appeared for
+    /// `idle_ms`; backends with protocol access should override it.
+    async fn wait_for_network_idle(
+        &self,
+        tab: &Self::TabHandle,
+        clock: &dyn Clock,
+        idle_ms: u64,
+        timeout_ms: u64,
+    ) -> Result<NetworkIdleStats> {
+        let script = r#"
+            (function() {
+                const entries = performance.getEntriesByType('resource');
+                let longest = null;
+                for (const entry of entries) {
+                    if (!longest || entry.duration > longest.duration) longest = entry;
+                }
+                return {
+                    count: entries.length,
+                    longestMs: longest ? Math.round(longest.duration) : 0,
+                    longestUrl: longest ? longest.name : null
+                };
+            })()
+        "#;
+
+        let start = clock.now();
+        let initial = self.execute_script(tab, script).await?;
+        let initial_count = initial.get("count").and_then(|v| v.as_u64()).unwrap_or(0);
+        let mut last_count = initial_count;
+        let mut quiet_since = clock.now();
+
+        loop {
+            let snapshot = self.execute_script(tab, script).await?;
+            let count = snapshot.get("count").and_then(|v| v.as_u64()).unwrap_or(0);
+            if count != last_count {
+                last_count = count;
+                quiet_since = clock.now();
+            } else if clock.elapsed_since(quiet_since).as_millis() as u64 >= idle_ms {
+                return Ok(NetworkIdleStats {
+                    requests_seen: count.saturating_sub(initial_count) as usize,
+                    long_polls_ignored: 0,
+                    longest_request_ms: snapshot
+                        .get("longestMs")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0),
+                    longest_request_url: snapshot
+                        .get("longestUrl")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    waited_ms: clock.elapsed_since(start).as_millis() as u64,
+                });
+            }
+
+            if clock.elapsed_since(start).as_millis() as u64 >= timeout_ms {
+                return Err(BrowserAgentError::TimeoutError(format!(
+                    "Network not idle for {}ms within {}ms",
+                    idle_ms, timeout_ms
+                )));
+            }
+
+            clock.sleep(std::time::Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Click at a viewport point in CSS pixels.
+    ///
+    /// The default dispatches a synthetic click on the element at that point; backends
+    /// with input emulation should send real mouse events instead.
+    async fn click_at(&self, tab: &Self::TabHandle, x: f64, y: f64) -> Result<()> {
+        let clicked = self.execute_script(tab, &click_at_script(x, y)).await?;
+        if clicked.as_bool().unwrap_or(false) {
+            Ok(())
+        } else {
+            Err(BrowserAgentError::ElementNotFound(format!(
+                "nothing at ({}, {})",
+                x, y
+            )))
+        }
+    }
+
+    /// Move the pointer to a viewport point in CSS pixels without clicking.
+    ///
+    /// Returns `false` when the backend has no input emulation to move a pointer with.
+    async fn move_mouse(&self, _tab: &Self::TabHandle, _x: f64, _y: f64) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Press and release a key (`"Escape"`, `"Enter"`, ...) on the focused element.
+    ///
+    /// The default dispatches synthetic keyboard events, which pages can tell apart
+    /// from real input.
+    async fn press_key(&self, tab: &Self::TabHandle, key: &str) -> Result<()> {
+        self.execute_script(tab, &press_key_script(key)?).await?;
+        Ok(())
+    }
+
+    /// Outline every element matching one of `selectors` in the browser's own overlay
+    /// layer, leaving the DOM untouched. Replaces any earlier overlay highlight.
+    ///
+    /// Returns how many selectors matched a node, or `None` when the backend has no
+    /// overlay layer.
+    async fn highlight_nodes(
+        &self,
+        _tab: &Self::TabHandle,
+        _selectors: &[String],
+    ) -> Result<Option<usize>> {
+        Ok(None)
+    }
+
+    /// Remove the overlay highlight drawn by `highlight_nodes`
+    async fn hide_highlight(&self, _tab: &Self::TabHandle) -> Result<()> {
+        Ok(())
+    }
+
+    /// Evaluate `source` at the start of every new document in `tab`, before page
+    /// scripts run.
+    ///
+    /// Returns `false` when the backend cannot, in which case callers fall back to
+    /// running the script on the current document only.
+    async fn add_init_script(&self, _tab: &Self::TabHandle, _source: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Start noticing when `tab`'s renderer crashes, e.g. running out of memory,
+    /// for `crashed_at`.
+    ///
+    /// Returns `false` when the backend cannot observe crashes.
+    async fn watch_for_crash(&self, _tab: &Self::TabHandle) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// URL `tab` showed when its renderer crashed, once `watch_for_crash` saw it
+    /// crash; `None` while it is alive
+    fn crashed_at(&self, _tab: &Self::TabHandle) -> Option<String> {
+        None
+    }
+
+    /// Mark the page's lifecycle state active, undoing or preventing the browser
+    /// freezing a background tab.
+    ///
+    /// Returns `false` when the backend cannot control page lifecycle.
+    async fn set_page_active(&self, _tab: &Self::TabHandle) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Pin the viewport size and device scale factor so screenshots are reproducible.
+    ///
+    /// Returns `false` when the backend cannot override them.
+    async fn emulate_viewport(
+        &self,
+        _tab: &Self::TabHandle,
+        _viewport: &crate::core::config::Viewport,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Drop the override set by `emulate_viewport`, returning to the window's size.
+    ///
+    /// Returns `false` when the backend cannot override the viewport.
+    async fn clear_viewport_emulation(&self, _tab: &Self::TabHandle) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Report `user_agent` from the next request in `tab` on, or the browser's own
+    /// with `None`.
+    ///
+    /// Returns `false` when the backend cannot override the user agent.
+    async fn set_user_agent(
+        &self,
+        _tab: &Self::TabHandle,
+        _user_agent: Option<&crate::core::UserAgentOverride>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Send `headers` with every request from `tab`, replacing the previous set.
+    ///
+    /// Returns `false` when the backend cannot add request headers.
+    async fn set_extra_headers(
+        &self,
+        _tab: &Self::TabHandle,
+        _headers: &std::collections::HashMap<String, String>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Apply `conditions` to every request from `tab`, or lift them with `None`.
+    ///
+    /// Returns `false` when the backend cannot throttle the network.
+    async fn emulate_network(
+        &self,
+        _tab: &Self::TabHandle,
+        _conditions: Option<&crate::core::NetworkConditions>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Run `tab`'s scripts and rendering `slowdown` times slower; 1.0 is full speed.
+    ///
+    /// Returns `false` when the backend cannot throttle the CPU.
+    async fn emulate_cpu(&self, _tab: &Self::TabHandle, _slowdown: f64) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Start recording which parts of `tab`'s scripts run.
+    ///
+    /// Returns `false` when the backend cannot record coverage.
+    async fn start_js_coverage(&self, _tab: &Self::TabHandle) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Stop the recording `start_js_coverage` began and return one entry per
+    /// script still loaded, or `None` when the backend cannot record coverage
+    async fn stop_js_coverage(
+        &self,
+        _tab: &Self::TabHandle,
+    ) -> Result<Option<Vec<crate::core::ScriptCoverage>>> {
+        Ok(None)
+    }
+
+    /// Start collecting console output and uncaught exceptions from `tab`.
+    ///
+    /// Returns `false` when the backend cannot observe the console.
+    async fn start_console_capture(&self, _tab: &Self::TabHandle) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Console messages collected since `start_console_capture`, oldest first
+    async fn console_messages(&self, _tab: &Self::TabHandle) -> Result<Vec<ConsoleMessage>> {
+        Ok(Vec::new())
+    }
+
+    /// Subscribe to console messages on `tab` as they arrive, starting console
+    /// capture if needed.
+    ///
+    /// Returns `None` when the backend cannot observe the console.
+    async fn subscribe_console(
+        &self,
+        _tab: &Self::TabHandle,
+    ) -> Result<Option<tokio::sync::broadcast::Receiver<ConsoleMessage>>> {
+        Ok(None)
+    }
+
+    /// Subscribe to XHR and fetch responses on `tab`, with bodies up to
+    /// `max_body_bytes`.
+    ///
+    /// Returns `None` when the backend cannot read response bodies.
+    async fn subscribe_responses(
+        &self,
+        _tab: &Self::TabHandle,
+        _max_body_bytes: usize,
+    ) -> Result<Option<tokio::sync::broadcast::Receiver<CapturedResponse>>> {
+        Ok(None)
+    }
+
+    /// Subscribe to WebSocket frames on `tab`, keeping text payloads up to
+    /// `max_payload_bytes`.
+    ///
+    /// Returns `None` when the backend cannot observe WebSocket traffic.
+    async fn subscribe_websockets(
+        &self,
+        _tab: &Self::TabHandle,
+        _max_payload_bytes: usize,
+    ) -> Result<Option<tokio::sync::broadcast::Receiver<WsEvent>>> {
+        Ok(None)
+    }
+
+    /// Frame counts per WebSocket seen since `subscribe_websockets` was first called
+    async fn websocket_stats(&self, _tab: &Self::TabHandle) -> Result<Vec<WsConnectionStats>> {
+        Ok(Vec::new())
+    }
+
+    /// Names, versions and per-store record counts of the IndexedDB databases of
+    /// the page's origin, without reading any records
+    async fn indexed_db_summary(&self, tab: &Self::TabHandle) -> Result<Vec<IndexedDbDatabase>> {
+        let script = r#"
+            (async function() {
+                if (!window.indexedDB || !indexedDB.databases) return [];
+                const databases = [];
+                for (const info of await indexedDB.databases()) {
+                    if (!info.name) continue;
+                    const db = await new Promise((resolve, reject) => {
+                        const request = indexedDB.open(info.name);
+                        // Opening without a version never upgrades an existing database
+                        request.onupgradeneeded = () => request.transaction.abort();
+                        request.onsuccess = () => resolve(request.result);
+                        request.onerror = () => reject(request.error);
+                    }).catch(() => null);
+                    if (!db) continue;
+                    const stores = [];
+                    for (const name of Array.from(db.objectStoreNames)) {
+                        const count = await new Promise(resolve => {
+                            const request = db.transaction(name, 'readonly').objectStore(name).count();
+                            request.onsuccess = () => resolve(request.result);
+                            request.onerror = () => resolve(0);
+                        });
+                        stores.push({ name: name, record_count: count });
+                    }
+                    databases.push({
+                        origin: location.origin,
+                        name: db.name,
+                        version: db.version,
+                        object_stores: stores
+                    });
+                    db.close();
+                }
+                return databases;
+            })()
+        "#;
+        let result = self.execute_script(tab, script).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Service worker registrations visible to `tab`
+    async fn list_service_workers(&self, tab: &Self::TabHandle) -> Result<Vec<ServiceWorkerInfo>> {
+        let script = r#"
+            (async function() {
+                if (!navigator.serviceWorker) return [];
+                const registrations = await navigator.serviceWorker.getRegistrations();
+                return registrations.map((registration, index) => {
+                    const worker = registration.active || registration.waiting || registration.installing;
+                    return {
+                        registration_id: String(index),
+                        scope_url: registration.scope,
+                        script_url: worker ? worker.scriptURL : null,
+                        status: worker ? worker.state : null,
+                        running_status: null
+                    };
+                });
+            })()
+        "#;
+        let result = self.execute_script(tab, script).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Unregister every service worker visible to `tab` and return how many were removed
+    async fn unregister_service_workers(&self, tab: &Self::TabHandle) -> Result<usize> {
+        let script = r#"
+            (async function() {
+                if (!navigator.serviceWorker) return 0;
+                const registrations = await navigator.serviceWorker.getRegistrations();
+                const results = await Promise.all(registrations.map(r => r.unregister()));
+                return results.filter(Boolean).length;
+            })()
+        "#;
+        let result = self.execute_script(tab, script).await?;
+        Ok(result.as_u64().unwrap_or(0) as usize)
+    }
+
+    /// Clear stored data of `origin`, returning once the browser reports completion.
+    ///
+    /// Returns `false` when the backend cannot clear site data.
+    async fn clear_site_data(
+        &self,
+        _tab: &Self::TabHandle,
+        _origin: &str,
+        _kinds: &[SiteDataKind],
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Make requests from `tab` skip service workers and go to the network.
+    ///
+    /// Returns `false` when the backend cannot bypass service workers.
+    async fn set_bypass_service_worker(
+        &self,
+        _tab: &Self::TabHandle,
+        _bypass: bool,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Resolve the first element matching `selector` to a protocol node, for
+    /// operations that need a node rather than a selector. `None` when nothing matches.
+    ///
+    /// Backends without `BrowserFeature::NodeResolution` fail with `FeatureUnavailable`.
+    async fn resolve_node(
+        &self,
+        _tab: &Self::TabHandle,
+        _selector: &str,
+    ) -> Result<Option<NodeRef>> {
+        Err(self.node_resolution_unavailable())
+    }
+
+    /// Current state of a node from `resolve_node`. Fails with `ElementDetached` once
+    /// the node's document has been replaced or the node is gone.
+    async fn describe_node(
+        &self,
+        _tab: &Self::TabHandle,
+        _node: &NodeRef,
+    ) -> Result<NodeDescription> {
+        Err(self.node_resolution_unavailable())
+    }
+
+    #[doc(hidden)]
+    fn node_resolution_unavailable(&self) -> crate::errors::BrowserAgentError {
+        crate::errors::BrowserAgentError::FeatureUnavailable {
+            feature: BrowserFeature::NodeResolution.to_string(),
+            backend: self.backend_name().to_string(),
+        }
+    }
+
+    /// Short name of the backend used in logs and `FeatureUnavailable` errors
+    fn backend_name(&self) -> &'static str {
+        "unknown"
+    }
+
+    /// Features the backend implements natively. The default claims only
+    /// JavaScript, so sessions fall back to script-based implementations.
+    fn capabilities(&self) -> &BrowserCapabilities {
+        &JAVASCRIPT_ONLY
+    }
+
     /// Check if browser is still running
     fn is_running(&self) -> bool;
 
@@ -37,11 +666,460 @@ pub trait BrowserTrait: Send + Sync {
     async fn close(&mut self) -> Result<()>;
 }
 
+/// Synthetic click on the element at a viewport point; evaluates to whether one was found
+pub(crate) fn click_at_script(x: f64, y: f64) -> String {
+    format!(
+        r#"
+        (function() {{
+            const element = document.elementFromPoint({x}, {y});
+            if (!element) return false;
+            element.click();
+            return true;
+        }})()
+        "#,
+        x = x,
+        y = y
+    )
+}
+
+/// Synthetic keydown/keyup pair on the focused element
+pub(crate) fn press_key_script(key: &str) -> Result<String> {
+    Ok(format!(
+        r#"
+        (function() {{
+            const target = document.activeElement || document.body;
+            for (const type of ['keydown', 'keyup']) {{
+                target.dispatchEvent(new KeyboardEvent(type, {{ key: {key}, bubbles: true, cancelable: true }}));
+            }}
+            return true;
+        }})()
+        "#,
+        key = serde_json::to_string(key)?
+    ))
+}
+
+static JAVASCRIPT_ONLY: BrowserCapabilities = BrowserCapabilities::javascript_only();
+
+/// Feature that a backend may or may not implement natively
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrowserFeature {
+    JavaScript,
+    Screenshots,
+    /// Intercepting requests and observing responses and WebSocket frames
+    RequestInterception,
+    MobileEmulation,
+    /// Trusted mouse and keyboard events instead of synthetic DOM events
+    NativeInput,
+    /// Cookie and site data access through the protocol, including httpOnly cookies
+    CdpCookies,
+    Pdf,
+    Screencast,
+    /// Evaluating scripts in isolated worlds, which reaches cross-origin frames
+    IsolatedWorlds,
+    /// Highlighting nodes in the browser's overlay layer instead of the DOM
+    OverlayHighlight,
+    /// Resolving elements to protocol nodes with `resolve_node`
+    NodeResolution,
+    /// Reporting another user agent and its client hints per tab
+    UserAgentOverride,
+    /// Emulating slow networks and CPUs
+    Throttling,
+    /// Recording which parts of the page's scripts run
+    JsCoverage,
+}
+
+impl std::fmt::Display for BrowserFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BrowserFeature::JavaScript => "javascript",
+            BrowserFeature::Screenshots => "screenshots",
+            BrowserFeature::RequestInterception => "request interception",
+            BrowserFeature::MobileEmulation => "mobile emulation",
+            BrowserFeature::NativeInput => "native input",
+            BrowserFeature::CdpCookies => "protocol cookie access",
+            BrowserFeature::Pdf => "PDF printing",
+            BrowserFeature::Screencast => "screencast",
+            BrowserFeature::IsolatedWorlds => "isolated worlds",
+            BrowserFeature::OverlayHighlight => "overlay highlighting",
+            BrowserFeature::NodeResolution => "node resolution",
+            BrowserFeature::UserAgentOverride => "user agent override",
+            BrowserFeature::Throttling => "network and CPU throttling",
+            BrowserFeature::JsCoverage => "JavaScript coverage",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Browser capabilities that can be queried
 #[derive(Debug, Clone)]
 pub struct BrowserCapabilities {
     pub supports_javascript: bool,
     pub supports_screenshots: bool,
+    /// Request interception plus response and WebSocket observation
     pub supports_network_interception: bool,
     pub supports_mobile_emulation: bool,
+    pub supports_native_input: bool,
+    pub supports_cdp_cookies: bool,
+    pub supports_pdf: bool,
+    pub supports_screencast: bool,
+    pub supports_isolated_worlds: bool,
+    pub supports_overlay_highlight: bool,
+    pub supports_node_resolution: bool,
+    pub supports_user_agent_override: bool,
+    pub supports_throttling: bool,
+    pub supports_js_coverage: bool,
+    /// Product string reported by the launched browser, e.g. `Chrome/124.0.6367.91`
+    pub browser_version: Option<String>,
+}
+
+impl BrowserCapabilities {
+    /// A backend that can only run page JavaScript
+    pub const fn javascript_only() -> Self {
+        Self {
+            supports_javascript: true,
+            supports_screenshots: false,
+            supports_network_interception: false,
+            supports_mobile_emulation: false,
+            supports_native_input: false,
+            supports_cdp_cookies: false,
+            supports_pdf: false,
+            supports_screencast: false,
+            supports_isolated_worlds: false,
+            supports_overlay_highlight: false,
+            supports_node_resolution: false,
+            supports_user_agent_override: false,
+            supports_throttling: false,
+            supports_js_coverage: false,
+            browser_version: None,
+        }
+    }
+
+    pub fn supports(&self, feature: BrowserFeature) -> bool {
+        match feature {
+            BrowserFeature::JavaScript => self.supports_javascript,
+            BrowserFeature::Screenshots => self.supports_screenshots,
+            BrowserFeature::RequestInterception => self.supports_network_interception,
+            BrowserFeature::MobileEmulation => self.supports_mobile_emulation,
+            BrowserFeature::NativeInput => self.supports_native_input,
+            BrowserFeature::CdpCookies => self.supports_cdp_cookies,
+            BrowserFeature::Pdf => self.supports_pdf,
+            BrowserFeature::Screencast => self.supports_screencast,
+            BrowserFeature::IsolatedWorlds => self.supports_isolated_worlds,
+            BrowserFeature::OverlayHighlight => self.supports_overlay_highlight,
+            BrowserFeature::NodeResolution => self.supports_node_resolution,
+            BrowserFeature::UserAgentOverride => self.supports_user_agent_override,
+            BrowserFeature::Throttling => self.supports_throttling,
+            BrowserFeature::JsCoverage => self.supports_js_coverage,
+        }
+    }
+}
+
+/// Outcome of waiting for the network to go idle
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkIdleStats {
+    /// Requests started while tracking was active
+    pub requests_seen: usize,
+    /// Requests still open past the long-poll threshold, excluded from the idle check
+    pub long_polls_ignored: usize,
+    pub longest_request_ms: u64,
+    pub longest_request_url: Option<String>,
+    /// Time spent waiting before the network went idle
+    pub waited_ms: u64,
+}
+
+/// Severity of a console message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+/// A console call, uncaught exception or browser log entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleMessage {
+    pub level: ConsoleLevel,
+    pub text: String,
+    /// Script or resource the message refers to, when known
+    pub url: Option<String>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// An XHR or fetch response recorded by response capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedResponse {
+    pub url: String,
+    pub method: String,
+    pub status: u16,
+    pub mime_type: String,
+    pub request_body: Option<String>,
+    /// GraphQL `operationName` from the request body or query string
+    pub operation_name: Option<String>,
+    /// Parsed JSON, the raw text for other content, or null when unavailable
+    pub body: Value,
+    /// The body exceeded the size limit and was dropped
+    pub body_truncated: bool,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Selects captured responses by URL glob, method and GraphQL operation
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMatcher {
+    url_pattern: Option<String>,
+    method: Option<String>,
+    operation_name: Option<String>,
+}
+
+impl ResponseMatcher {
+    /// Every captured response
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Responses whose URL matches a glob (`*` wildcard)
+    pub fn url(pattern: &str) -> Self {
+        Self::default().with_url(pattern)
+    }
+
+    /// GraphQL responses for the named operation
+    pub fn graphql(operation_name: &str) -> Self {
+        Self {
+            operation_name: Some(operation_name.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_url(mut self, pattern: &str) -> Self {
+        self.url_pattern = Some(pattern.to_string());
+        self
+    }
+
+    pub fn with_method(mut self, method: &str) -> Self {
+        self.method = Some(method.to_uppercase());
+        self
+    }
+
+    pub fn matches(&self, response: &CapturedResponse) -> bool {
+        if let Some(pattern) = &self.url_pattern {
+            let matched = regex::Regex::new(&FrameTarget::glob_to_regex(pattern))
+                .map(|re| re.is_match(&response.url))
+                .unwrap_or(false);
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(method) = &self.method {
+            if !response.method.eq_ignore_ascii_case(method) {
+                return false;
+            }
+        }
+        if let Some(name) = &self.operation_name {
+            if response.operation_name.as_deref() != Some(name.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl std::fmt::Display for ResponseMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(method) = &self.method {
+            parts.push(method.clone());
+        }
+        if let Some(pattern) = &self.url_pattern {
+            parts.push(format!("url '{}'", pattern));
+        }
+        if let Some(name) = &self.operation_name {
+            parts.push(format!("operation '{}'", name));
+        }
+        if parts.is_empty() {
+            write!(f, "any response")
+        } else {
+            write!(f, "{}", parts.join(" "))
+        }
+    }
+}
+
+/// A service worker registration and its current worker version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceWorkerInfo {
+    pub registration_id: String,
+    pub scope_url: String,
+    pub script_url: Option<String>,
+    /// Lifecycle state, e.g. `installing` or `activated`
+    pub status: Option<String>,
+    /// `running` or `stopped`, when the backend reports it
+    pub running_status: Option<String>,
+}
+
+/// Category of site data for `clear_site_data`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiteDataKind {
+    Cookies,
+    LocalStorage,
+    IndexedDb,
+    CacheStorage,
+    ServiceWorkers,
+    FileSystems,
+    All,
+}
+
+impl SiteDataKind {
+    /// Storage type name used by the DevTools protocol
+    pub fn storage_type(&self) -> &'static str {
+        match self {
+            SiteDataKind::Cookies => "cookies",
+            SiteDataKind::LocalStorage => "local_storage",
+            SiteDataKind::IndexedDb => "indexeddb",
+            SiteDataKind::CacheStorage => "cache_storage",
+            SiteDataKind::ServiceWorkers => "service_workers",
+            SiteDataKind::FileSystems => "file_systems",
+            SiteDataKind::All => "all",
+        }
+    }
+}
+
+/// IndexedDB database metadata; records themselves are not copied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedDbDatabase {
+    pub origin: String,
+    pub name: String,
+    pub version: f64,
+    pub object_stores: Vec<IndexedDbStore>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedDbStore {
+    pub name: String,
+    pub record_count: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WsDirection {
+    Sent,
+    Received,
+}
+
+/// One WebSocket frame observed on the page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WsEvent {
+    pub url: String,
+    pub direction: WsDirection,
+    /// 1 for text, 2 for binary, as in RFC 6455
+    pub opcode: u8,
+    /// Text payload, or a hex preview of the first bytes of a binary frame
+    pub payload: String,
+    /// Full payload size in bytes
+    pub payload_size: usize,
+    pub binary: bool,
+    /// The text payload was cut at the configured limit
+    pub truncated: bool,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Traffic on one WebSocket connection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WsConnectionStats {
+    pub url: String,
+    pub frames_sent: usize,
+    pub frames_received: usize,
+    pub bytes_sent: usize,
+    pub bytes_received: usize,
+    pub open: bool,
+}
+
+/// A frame within the page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameInfo {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub url: String,
+    pub origin: String,
+    pub name: Option<String>,
+}
+
+impl FrameInfo {
+    pub fn is_main_frame(&self) -> bool {
+        self.parent_id.is_none()
+    }
+}
+
+/// An element resolved to a protocol node by `BrowserTrait::resolve_node`.
+///
+/// Only valid for the document it was resolved in; `describe_node` reports it
+/// stale after a navigation or once the element is removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeRef {
+    /// Backend node id, stable for the node's lifetime
+    pub backend_node_id: u32,
+    /// Backend node id of the owning document, to notice navigations
+    pub document_node_id: u32,
+    pub document_url: String,
+    /// Selector the node was resolved from
+    pub selector: String,
+}
+
+/// A node's current state, from `BrowserTrait::describe_node`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeDescription {
+    pub backend_node_id: u32,
+    /// Lower-case tag name for elements
+    pub local_name: String,
+    pub node_name: String,
+    pub attributes: Vec<(String, String)>,
+    pub child_count: usize,
+    /// Frame the node owns, for `<iframe>` and `<frame>` elements
+    pub frame_id: Option<String>,
+}
+
+/// How to locate a frame for frame-scoped operations
+#[derive(Debug, Clone)]
+pub enum FrameTarget {
+    /// CSS selector of the owning `<iframe>` element
+    Selector(String),
+    /// Glob (`*` wildcard) matched against the frame URL
+    UrlPattern(String),
+    /// Frame id as reported by `list_frames`
+    Id(String),
+}
+
+impl FrameTarget {
+    /// Whether a frame URL matches this target's URL pattern
+    pub fn matches_url(&self, url: &str) -> bool {
+        match self {
+            FrameTarget::UrlPattern(pattern) => regex::Regex::new(&Self::glob_to_regex(pattern))
+                .map(|re| re.is_match(url))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn glob_to_regex(pattern: &str) -> String {
+        format!(
+            "^{}$",
+            pattern
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".*")
+        )
+    }
+}
+
+impl std::fmt::Display for FrameTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameTarget::Selector(selector) => write!(f, "selector '{}'", selector),
+            FrameTarget::UrlPattern(pattern) => write!(f, "url '{}'", pattern),
+            FrameTarget::Id(id) => write!(f, "frame id '{}'", id),
+        }
+    }
 }
@@ -1,4 +1,13 @@
+use super::i18n::LanguagePack;
+use super::site_profile::SiteProfile;
+use crate::errors::{BrowserAgentError, Result};
+use crate::utils::credentials::CREDENTIAL_ENV_PREFIX;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Prefix of environment variables read by `Config::from_env`
+pub const ENV_PREFIX: &str = "SURFAI_";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -11,13 +20,103 @@ pub struct Config {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BrowserConfig {
     pub browser_type: BrowserType,
-    pub headless: bool,
+    /// Also accepts the old `true`/`false` values
+    pub headless: HeadlessMode,
     pub viewport: Viewport,
     pub user_agent: Option<String>,
     pub disable_images: bool,
     pub disable_javascript: bool,
     pub args: Vec<String>,
     pub timeout_ms: u64,
+    /// Browser binary, or a chrome-for-testing download directory resolved per platform.
+    /// Takes precedence over `channel`.
+    #[serde(default)]
+    pub executable_path: Option<PathBuf>,
+    /// Installed release channel to launch; `None` lets headless_chrome pick
+    #[serde(default)]
+    pub channel: Option<ChromeChannel>,
+    /// Lowest accepted major version, checked right after launch
+    #[serde(default)]
+    pub min_version: Option<u32>,
+    /// Pass `--disable-gpu`
+    #[serde(default = "default_true")]
+    pub disable_gpu: bool,
+    /// Pass `--no-first-run`. headless_chrome adds it on its own as well, so turning
+    /// this off only drops the generated copy.
+    #[serde(default = "default_true")]
+    pub no_first_run: bool,
+    /// UI and `Accept-Language` locale, e.g. `en-US`
+    #[serde(default)]
+    pub lang: Option<String>,
+    /// Screen position of the window's top-left corner in headful mode
+    #[serde(default)]
+    pub window_position: Option<WindowPosition>,
+    /// Remote end `WebDriverBrowser` creates its session on, e.g. a chromedriver at
+    /// `http://localhost:9515` or a Selenium Grid hub
+    #[serde(default)]
+    pub webdriver_url: Option<String>,
+}
+
+/// How Chrome runs without a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadlessMode {
+    /// Visible browser window
+    Off,
+    /// Original headless implementation (`--headless`), with its own rendering
+    /// differences and a distinct fingerprint
+    Legacy,
+    /// Headless mode sharing the regular browser's code (`--headless=new`)
+    #[default]
+    New,
+}
+
+impl HeadlessMode {
+    pub fn is_headless(&self) -> bool {
+        *self != HeadlessMode::Off
+    }
+}
+
+impl From<bool> for HeadlessMode {
+    fn from(headless: bool) -> Self {
+        if headless {
+            HeadlessMode::New
+        } else {
+            HeadlessMode::Off
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for HeadlessMode {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Flag(bool),
+            Name(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Flag(headless) => Ok(headless.into()),
+            Repr::Name(name) => match name.to_lowercase().as_str() {
+                "off" | "false" => Ok(HeadlessMode::Off),
+                "legacy" | "old" => Ok(HeadlessMode::Legacy),
+                "new" | "true" => Ok(HeadlessMode::New),
+                other => Err(serde::de::Error::custom(format!(
+                    "unknown headless mode '{}', expected off, legacy or new",
+                    other
+                ))),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowPosition {
+    pub x: i32,
+    pub y: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +126,58 @@ pub struct DomConfig {
     pub max_text_length: usize,
     pub enable_ai_labels: bool,
     pub screenshot_quality: u8,
+    /// How `highlight_interactive_elements` labels each element
+    #[serde(default)]
+    pub highlight_style: HighlightStyle,
+    /// Where `highlight_interactive_elements` draws
+    #[serde(default)]
+    pub highlight_backend: HighlightBackend,
+    /// Per-site extraction and consent overrides, picked by the page's host
+    #[serde(default)]
+    pub site_profiles: Vec<SiteProfile>,
+    /// Record per-selector match counts and extraction timings on every `DomState`
+    #[serde(default)]
+    pub collect_stats: bool,
+    /// Give every interactive element a `data-surfai-id` attribute during
+    /// extraction and select elements by it, so selectors stay unique and keep
+    /// working when the page reorders nodes. Stamps last until navigation.
+    #[serde(default)]
+    pub stamp_elements: bool,
+    /// CSS selectors of containers whose elements are left unstamped, for pages
+    /// that checksum their markup; forms by default
+    #[serde(default = "default_stamp_exclusions")]
+    pub stamp_exclusions: Vec<String>,
+    /// Record on every element why extraction kept it, for
+    /// `DomState::explain`. Costs an allocation or two per element.
+    #[serde(default)]
+    pub explain: bool,
+}
+
+fn default_stamp_exclusions() -> Vec<String> {
+    vec!["form".to_string()]
+}
+
+/// Label drawn next to each highlighted element
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightStyle {
+    /// Numbered tag placed clear of its element and the other labels
+    #[default]
+    Full,
+    /// Number badge only, in a 14px circle
+    Compact,
+}
+
+/// How highlights reach the screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightBackend {
+    /// Outlines and labels injected into the page
+    #[default]
+    DomOverlay,
+    /// Outlines drawn by the browser's overlay layer without touching the DOM.
+    /// Numbers appear only in `BrowserSession::screenshot_highlighted`.
+    CdpOverlay,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +186,517 @@ pub struct SessionConfig {
     pub element_timeout_ms: u64,
     pub retry_attempts: u32,
     pub enable_logging: bool,
+    /// Host globs the session may navigate to; empty allows every host
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Host globs the session must never navigate to, checked before `allowed_domains`
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+    /// Also fail subresource requests (scripts, images, XHR) to disallowed hosts
+    #[serde(default)]
+    pub restrict_subresources: bool,
+    /// Also collect localStorage from child frames during session extraction
+    #[serde(default)]
+    pub aggregate_frame_storage: bool,
+    /// Have `navigate_smart` also wait for in-flight requests to settle
+    #[serde(default)]
+    pub wait_for_network_idle: bool,
+    /// Quiet period required before the network counts as idle
+    #[serde(default = "default_network_idle_ms")]
+    pub network_idle_ms: u64,
+    /// What an operation does when the session is busy navigating or recovering
+    #[serde(default)]
+    pub busy_policy: BusyPolicy,
+    /// Look for overlays before highlighting and warn about covered elements
+    #[serde(default)]
+    pub check_overlays_before_highlight: bool,
+    /// Smallest share of the viewport (0.0-1.0) a positioned element must cover to
+    /// count as an overlay
+    #[serde(default = "default_overlay_min_coverage")]
+    pub overlay_min_coverage: f64,
+    /// Largest response body, in bytes, kept by response capture; bigger bodies are
+    /// reported without content
+    #[serde(default = "default_max_response_body_bytes")]
+    pub max_response_body_bytes: usize,
+    /// Longest WebSocket text payload, in bytes, reported by `observe_websockets`
+    #[serde(default = "default_max_websocket_payload_bytes")]
+    pub max_websocket_payload_bytes: usize,
+    /// Have `navigate_smart` send requests past service workers straight to the network
+    #[serde(default)]
+    pub bypass_service_worker: bool,
+    /// Limits applied when `extract_session` reads web storage
+    #[serde(default)]
+    pub storage_extraction: StorageExtractionOptions,
+    /// Which storage keys, cookies and values `extract_session` treats as auth tokens
+    #[serde(default)]
+    pub token_probe: TokenProbeOptions,
+    /// Where the session looks for the page's CSRF token and which header carries it
+    #[serde(default)]
+    pub csrf: CsrfOptions,
+    /// Periodic activity for sessions held open for hours, used once
+    /// `SessionHandle::start_keepalive` is called
+    #[serde(default)]
+    pub keepalive: KeepaliveOptions,
+    /// Signs that the site logged the session out; off until given a URL pattern
+    /// or DOM indicator
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+    /// Pacing of clicks, typing and scrolling; `Instant` adds no delays
+    #[serde(default)]
+    pub interaction_profile: InteractionProfile,
+    /// Events `BrowserSession::events` buffers per subscriber before the oldest are
+    /// dropped and the subscriber is told it lagged
+    #[serde(default = "default_event_buffer")]
+    pub event_buffer: usize,
+    /// Background tabs `BrowserSession::prefetch` keeps loaded; 0 disables
+    /// prefetching
+    #[serde(default = "default_prefetch_limit")]
+    pub prefetch_limit: usize,
+    /// Approximate bytes the pages `prefetch` keeps may take together; the least
+    /// recently prefetched are dropped first once exceeded
+    #[serde(default = "default_prefetch_max_bytes")]
+    pub prefetch_max_bytes: usize,
+    /// DOM change events kept for `ElementMonitor::change_history`
+    #[serde(default = "default_change_history_len")]
+    pub change_history_len: usize,
+    /// Delay between checks while polling for a condition
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Seed for session and element handle ids; set it for reproducible runs
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Fixed session id, e.g. to correlate logs across runs; generated when unset
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Language of the keywords used by login and overlay detection, e.g. `de`;
+    /// read from the page's `lang` attribute or `Content-Language` meta tag when unset
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Extra keyword packs, adding languages or extending the built-in ones
+    #[serde(default)]
+    pub language_packs: Vec<LanguagePack>,
+}
+
+/// Per-call overrides for waiting and interaction methods (`*_with_options`).
+///
+/// `CallOptions::from(&config.session)`, or `BrowserSession::call_options()`,
+/// fills every field from the session config:
+///
+/// | Field              | Default | Taken from                           |
+/// |--------------------|---------|--------------------------------------|
+/// | `timeout_ms`       | 2000    | `SessionConfig::element_timeout_ms`  |
+/// | `poll_interval_ms` | 100     | `SessionConfig::poll_interval_ms`    |
+/// | `retries`          | 3       | `SessionConfig::retry_attempts`      |
+///
+/// Methods that wait for a page load rather than an element
+/// (`inject_session`, `auto_login_and_extract_session`) use
+/// `SessionConfig::navigation_timeout_ms` (5000) as their timeout instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallOptions {
+    /// Upper bound for the whole call, including retries
+    pub timeout_ms: u64,
+    pub poll_interval_ms: u64,
+    /// Extra attempts after a failed interaction; waits are not retried
+    pub retries: u32,
+}
+
+impl CallOptions {
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+
+    pub fn with_poll_interval_ms(mut self, poll_interval_ms: u64) -> Self {
+        self.poll_interval_ms = poll_interval_ms;
+        self
+    }
+
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub(crate) fn poll_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.poll_interval_ms.max(1))
+    }
+}
+
+impl From<&SessionConfig> for CallOptions {
+    fn from(session: &SessionConfig) -> Self {
+        Self {
+            timeout_ms: session.element_timeout_ms,
+            poll_interval_ms: session.poll_interval_ms,
+            retries: session.retry_attempts,
+        }
+    }
+}
+
+impl Default for CallOptions {
+    fn default() -> Self {
+        Self::from(&SessionConfig::default())
+    }
+}
+
+/// What `extract_session` copies out of localStorage, sessionStorage and IndexedDB
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageExtractionOptions {
+    /// Values longer than this many characters are cut, marked and recorded as
+    /// truncated in `SessionData`
+    pub max_value_length: usize,
+    /// Key globs (`*` wildcard) to keep; empty keeps every key
+    #[serde(default)]
+    pub include_keys: Vec<String>,
+    /// Key globs to drop, checked before `include_keys`
+    #[serde(default)]
+    pub exclude_keys: Vec<String>,
+    /// Record IndexedDB database and object store names with record counts. Contents
+    /// are never copied.
+    #[serde(default)]
+    pub indexed_db_metadata: bool,
+}
+
+impl Default for StorageExtractionOptions {
+    fn default() -> Self {
+        Self {
+            max_value_length: 64 * 1024,
+            include_keys: Vec::new(),
+            exclude_keys: Vec::new(),
+            indexed_db_metadata: false,
+        }
+    }
+}
+
+impl StorageExtractionOptions {
+    /// Whether a storage key passes the include and exclude globs
+    pub fn keeps_key(&self, key: &str) -> bool {
+        let matches = |pattern: &String| {
+            regex::Regex::new(&crate::core::FrameTarget::glob_to_regex(pattern))
+                .map(|re| re.is_match(key))
+                .unwrap_or(false)
+        };
+        !self.exclude_keys.iter().any(matches)
+            && (self.include_keys.is_empty() || self.include_keys.iter().any(matches))
+    }
+}
+
+/// What the auth token probe looks for, on top of well-known names such as
+/// `access_token`, `jwt` and `id_token`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenProbeOptions {
+    /// Further localStorage and sessionStorage keys to read, e.g. `app.session.v2`
+    #[serde(default)]
+    pub extra_keys: Vec<String>,
+    /// Regular expressions (JavaScript syntax, case-insensitive) matched against
+    /// storage keys
+    #[serde(default)]
+    pub key_patterns: Vec<String>,
+    /// Regular expressions (JavaScript syntax, case-insensitive) matched against
+    /// cookie names
+    #[serde(default)]
+    pub cookie_patterns: Vec<String>,
+    /// Also keep storage values and cookies that look like tokens whatever their
+    /// name: JWTs, and opaque strings of 32 or more token characters
+    #[serde(default)]
+    pub detect_token_values: bool,
+    /// Most candidates collected per frame
+    #[serde(default = "default_max_token_candidates")]
+    pub max_candidates: usize,
+}
+
+impl Default for TokenProbeOptions {
+    fn default() -> Self {
+        Self {
+            extra_keys: Vec::new(),
+            key_patterns: Vec::new(),
+            cookie_patterns: Vec::new(),
+            detect_token_values: false,
+            max_candidates: default_max_token_candidates(),
+        }
+    }
+}
+
+fn default_max_token_candidates() -> usize {
+    50
+}
+
+/// Where a page keeps its CSRF token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsrfSource {
+    /// Cookie readable by the page, as double-submit-cookie schemes set
+    Cookie,
+    /// `<meta>` tag in the document head
+    Meta,
+    /// Hidden form field
+    HiddenInput,
+    /// localStorage or sessionStorage entry
+    Storage,
+}
+
+/// How `CsrfManager` finds the page's CSRF token.
+///
+/// Names containing `csrf` or `xsrf` match in every source, along with the
+/// anti-forgery fields of common frameworks and `extra_names`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsrfOptions {
+    /// Sources read, in order of preference; empty turns CSRF handling off
+    #[serde(default = "default_csrf_sources")]
+    pub sources: Vec<CsrfSource>,
+    /// Further cookie, meta, field or storage names holding a token
+    #[serde(default)]
+    pub extra_names: Vec<String>,
+    /// Header the token is sent in; picked from the token's name when unset
+    #[serde(default)]
+    pub header: Option<String>,
+}
+
+impl Default for CsrfOptions {
+    fn default() -> Self {
+        Self {
+            sources: default_csrf_sources(),
+            extra_names: Vec::new(),
+            header: None,
+        }
+    }
+}
+
+fn default_csrf_sources() -> Vec<CsrfSource> {
+    // The cookie of a double-submit scheme is what the server checks against,
+    // and the first to change when the token rotates
+    vec![
+        CsrfSource::Cookie,
+        CsrfSource::Meta,
+        CsrfSource::HiddenInput,
+        CsrfSource::Storage,
+    ]
+}
+
+/// What the keepalive does on each tick
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeepaliveActivity {
+    /// Dispatch a `mousemove` on the document, which resets most idle timers
+    MouseMove,
+    /// Read `document.title`, enough to keep the renderer busy without user events
+    TitlePing,
+    /// Evaluate this script in the page, e.g. a call to the app's session refresh
+    Script(String),
+}
+
+/// Keeps a long-lived session logged in and its tab from being frozen or discarded.
+///
+/// Nothing runs until `SessionHandle::start_keepalive` is called.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeepaliveOptions {
+    #[serde(default = "default_keepalive_interval_ms")]
+    pub interval_ms: u64,
+    #[serde(default = "default_keepalive_activity")]
+    pub activity: KeepaliveActivity,
+    /// Mark the page's lifecycle state active on every tick so the browser does
+    /// not freeze it
+    #[serde(default = "default_true")]
+    pub prevent_freezing: bool,
+}
+
+impl Default for KeepaliveOptions {
+    fn default() -> Self {
+        Self {
+            interval_ms: default_keepalive_interval_ms(),
+            activity: default_keepalive_activity(),
+            prevent_freezing: true,
+        }
+    }
+}
+
+fn default_keepalive_interval_ms() -> u64 {
+    60_000
+}
+
+fn default_keepalive_activity() -> KeepaliveActivity {
+    KeepaliveActivity::MouseMove
+}
+
+/// Detects the site logging the session out mid-run, e.g. a silent redirect to
+/// the login page.
+///
+/// When tripped the session publishes `SessionEvent::LoggedOut`; only
+/// `BrowserSession::run_with_relogin` logs back in and retries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    /// URL globs (`*` wildcard) of pages meaning "logged out", e.g. `*/login*`
+    #[serde(default)]
+    pub url_patterns: Vec<String>,
+    /// CSS selectors only present when logged out, e.g. `form#login`
+    #[serde(default)]
+    pub dom_indicators: Vec<String>,
+    /// Check after every `navigate_smart` whose target is not itself a logged-out page
+    #[serde(default = "default_true")]
+    pub check_after_navigation: bool,
+    /// Check on every keepalive tick
+    #[serde(default = "default_true")]
+    pub check_on_keepalive: bool,
+    /// Re-logins a single `run_with_relogin` call may attempt; a failed
+    /// re-login ends the call regardless
+    #[serde(default = "default_max_relogin_attempts")]
+    pub max_relogin_attempts: u32,
+}
+
+impl WatchdogConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.url_patterns.is_empty() || !self.dom_indicators.is_empty()
+    }
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            url_patterns: Vec::new(),
+            dom_indicators: Vec::new(),
+            check_after_navigation: true,
+            check_on_keepalive: true,
+            max_relogin_attempts: default_max_relogin_attempts(),
+        }
+    }
+}
+
+fn default_max_relogin_attempts() -> u32 {
+    1
+}
+
+/// How fast the session clicks, types and scrolls, for targets that flag
+/// machine-speed interaction.
+///
+/// Delays are drawn from the session's seedable generator, so runs with
+/// `SessionConfig::seed` set pace identically.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionProfile {
+    /// No delays; text is set in one go and clicks and scrolls jump
+    #[default]
+    Instant,
+    /// Short delays, enough to let debounced handlers run
+    Fast,
+    /// Delays, typing cadence, pointer paths and scroll easing close to a person's
+    Human,
+    Custom(PacingOptions),
+}
+
+impl InteractionProfile {
+    pub fn pacing(&self) -> PacingOptions {
+        match self {
+            InteractionProfile::Instant => PacingOptions::default(),
+            InteractionProfile::Fast => PacingOptions {
+                action_delay_ms: DelayRange::new(20, 80),
+                keystroke_delay_ms: DelayRange::new(10, 30),
+                pause_probability: 0.0,
+                pause_ms: DelayRange::default(),
+                mouse_steps: 5,
+                mouse_step_delay_ms: DelayRange::new(4, 10),
+                scroll_steps: 4,
+                scroll_step_delay_ms: DelayRange::new(10, 20),
+            },
+            InteractionProfile::Human => PacingOptions {
+                action_delay_ms: DelayRange::new(250, 900),
+                keystroke_delay_ms: DelayRange::new(60, 180),
+                pause_probability: 0.05,
+                pause_ms: DelayRange::new(300, 900),
+                mouse_steps: 20,
+                mouse_step_delay_ms: DelayRange::new(8, 20),
+                scroll_steps: 12,
+                scroll_step_delay_ms: DelayRange::new(15, 35),
+            },
+            InteractionProfile::Custom(options) => options.clone(),
+        }
+    }
+}
+
+/// Inclusive bounds of a random delay, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelayRange {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl DelayRange {
+    pub fn new(min: u64, max: u64) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.max == 0
+    }
+}
+
+/// Delays and motion behind an `InteractionProfile`. The default adds none.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PacingOptions {
+    /// Before every click, typed text and scroll
+    pub action_delay_ms: DelayRange,
+    /// Between typed characters; zero sets the whole text at once
+    pub keystroke_delay_ms: DelayRange,
+    /// Chance per character of an extra `pause_ms` hesitation
+    pub pause_probability: f64,
+    pub pause_ms: DelayRange,
+    /// Points along the curved pointer path to a native click; 0 jumps there
+    pub mouse_steps: u32,
+    pub mouse_step_delay_ms: DelayRange,
+    /// Eased increments a page scroll is split into; 0 scrolls at once
+    pub scroll_steps: u32,
+    pub scroll_step_delay_ms: DelayRange,
+}
+
+/// Behaviour for operations that arrive while the session is busy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum BusyPolicy {
+    /// Wait for the session to become available, up to the navigation timeout
+    #[default]
+    Wait,
+    /// Fail immediately with `SessionBusy`
+    Error,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_poll_interval_ms() -> u64 {
+    100
+}
+
+fn default_network_idle_ms() -> u64 {
+    500
+}
+
+fn default_overlay_min_coverage() -> f64 {
+    0.05
+}
+
+fn default_max_response_body_bytes() -> usize {
+    2 * 1024 * 1024
+}
+
+fn default_max_websocket_payload_bytes() -> usize {
+    4096
+}
+
+fn default_event_buffer() -> usize {
+    256
+}
+
+fn default_prefetch_limit() -> usize {
+    2
+}
+
+fn default_prefetch_max_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
+fn default_change_history_len() -> usize {
+    100
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,11 +715,347 @@ pub enum BrowserType {
     Edge,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChromeChannel {
+    Stable,
+    Beta,
+    Chromium,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Viewport {
     pub width: u32,
     pub height: u32,
     pub device_scale_factor: f64,
+    /// Emulate a phone: mobile meta viewport handling and overlay scrollbars
+    #[serde(default)]
+    pub mobile: bool,
+}
+
+impl Viewport {
+    /// Desktop viewport of `width` x `height` CSS pixels at scale factor 1
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            ..Self::default()
+        }
+    }
+
+    /// Phone viewport of `width` x `height` CSS pixels at scale factor 2
+    pub fn mobile(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            device_scale_factor: 2.0,
+            mobile: true,
+        }
+    }
+
+    pub fn with_device_scale_factor(mut self, device_scale_factor: f64) -> Self {
+        self.device_scale_factor = device_scale_factor;
+        self
+    }
+
+    /// Short name such as `390x844@2x-mobile`, for screenshot and report names
+    pub fn label(&self) -> String {
+        format!(
+            "{}x{}@{}x{}",
+            self.width,
+            self.height,
+            self.device_scale_factor,
+            if self.mobile { "-mobile" } else { "" }
+        )
+    }
+}
+
+impl Config {
+    /// Load a TOML or JSON file (chosen by extension) over the defaults. The file
+    /// only needs the settings it changes.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let mut layered = serde_json::to_value(Self::default())?;
+        merge_values(&mut layered, read_config_file(path.as_ref())?);
+        Self::from_layers(layered)
+    }
+
+    /// Defaults with `SURFAI_*` environment overrides applied
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Defaults, then `path` if given, then environment overrides, validated.
+    /// Programmatic changes go on the returned value; call `validate` again after.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let mut config = match path {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+        config.apply_env()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Apply `SURFAI_<SECTION>_<FIELD>` variables from the process environment,
+    /// e.g. `SURFAI_BROWSER_HEADLESS=false` or `SURFAI_SESSION_NAVIGATION_TIMEOUT_MS=8000`.
+    ///
+    /// `SURFAI_VIEWPORT=1920x1080` (optionally `@2` for the scale factor) is accepted
+    /// as a shorthand. Values are parsed by the type of the setting they replace;
+    /// lists are comma-separated. Variables that name no setting are skipped with a
+    /// warning, so other `SURFAI_*` variables in the environment do not stop loading.
+    /// `EnvVarProvider` credentials, under `CREDENTIAL_ENV_PREFIX`, are skipped
+    /// without one.
+    pub fn apply_env(&mut self) -> Result<()> {
+        let ignored =
+            self.apply_overrides(std::env::vars().filter(|(key, _)| key.starts_with(ENV_PREFIX)))?;
+        for warning in ignored {
+            println!("⚠️ {}", warning);
+        }
+        Ok(())
+    }
+
+    /// `apply_env` over explicit `(variable, value)` pairs, returning a warning for
+    /// each variable that names no setting. Values that cannot be parsed fail the
+    /// whole call, with every problem in one error, and leave `self` unchanged.
+    pub fn apply_overrides<I, K, V>(&mut self, vars: I) -> Result<Vec<String>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut layered = serde_json::to_value(&*self)?;
+        let mut problems = Vec::new();
+        let mut ignored = Vec::new();
+
+        for (key, raw) in vars {
+            let (key, raw) = (key.as_ref(), raw.as_ref());
+            if key.starts_with(CREDENTIAL_ENV_PREFIX) {
+                continue;
+            }
+            let Some(name) = key.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let name = name.to_lowercase();
+
+            if name == "viewport" {
+                match parse_viewport(raw) {
+                    Some(viewport) => layered["browser"]["viewport"] = viewport,
+                    None => problems.push(format!("{}: expected WIDTHxHEIGHT[@SCALE]", key)),
+                }
+                continue;
+            }
+
+            let slot = name
+                .split_once('_')
+                .and_then(|(section, field)| layered.get_mut(section)?.get_mut(field));
+            let Some(slot) = slot else {
+                ignored.push(format!("ignoring {}: unknown setting", key));
+                continue;
+            };
+            match parse_like(slot, raw) {
+                Some(value) => *slot = value,
+                None => problems.push(format!("{}: cannot parse '{}'", key, raw)),
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(problems_error(problems));
+        }
+        *self = Self::from_layers(layered)?;
+        Ok(ignored)
+    }
+
+    /// Check for out-of-range and contradictory settings, reporting every problem
+    /// in one error
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        let browser = &self.browser;
+        let session = &self.session;
+
+        if browser.viewport.width == 0 || browser.viewport.height == 0 {
+            problems.push("browser.viewport: width and height must be positive".to_string());
+        }
+        if browser.viewport.device_scale_factor <= 0.0 {
+            problems.push("browser.viewport.device_scale_factor must be positive".to_string());
+        }
+        if browser.timeout_ms == 0 {
+            problems.push("browser.timeout_ms must be positive".to_string());
+        }
+        if let Some(path) = &browser.executable_path {
+            if !path.exists() {
+                problems.push(format!(
+                    "browser.executable_path: {} does not exist",
+                    path.display()
+                ));
+            }
+        }
+        if let Some(url) = &browser.webdriver_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                problems.push(format!(
+                    "browser.webdriver_url: '{}' is not an http(s) URL",
+                    url
+                ));
+            }
+        }
+        if browser.disable_javascript {
+            problems.push(
+                "browser.disable_javascript: DOM extraction and scripted actions need JavaScript"
+                    .to_string(),
+            );
+        }
+        if self.dom.screenshot_quality > 100 {
+            problems.push("dom.screenshot_quality must be at most 100".to_string());
+        }
+        for profile in &self.dom.site_profiles {
+            problems.extend(
+                profile
+                    .problems()
+                    .into_iter()
+                    .map(|problem| format!("dom.{}", problem)),
+            );
+        }
+        if session.navigation_timeout_ms == 0 {
+            problems.push("session.navigation_timeout_ms must be positive".to_string());
+        }
+        if session.poll_interval_ms == 0 {
+            problems.push("session.poll_interval_ms must be positive".to_string());
+        }
+        if session.event_buffer == 0 {
+            problems.push("session.event_buffer must be positive".to_string());
+        }
+        if session.wait_for_network_idle && session.network_idle_ms >= session.navigation_timeout_ms
+        {
+            problems.push(format!(
+                "session.network_idle_ms ({}) must be shorter than navigation_timeout_ms ({})",
+                session.network_idle_ms, session.navigation_timeout_ms
+            ));
+        }
+        for domain in &session.allowed_domains {
+            if session.blocked_domains.contains(domain) {
+                problems.push(format!("'{}' is both allowed and blocked", domain));
+            }
+        }
+        if session.restrict_subresources
+            && session.allowed_domains.is_empty()
+            && session.blocked_domains.is_empty()
+        {
+            problems.push(
+                "session.restrict_subresources has no effect without allowed_domains or blocked_domains"
+                    .to_string(),
+            );
+        }
+        if let Err(e) = crate::core::DomainPolicy::from_config(session) {
+            problems.push(format!("session domain rules: {}", e));
+        }
+        if !(session.overlay_min_coverage > 0.0 && session.overlay_min_coverage <= 1.0) {
+            problems.push("session.overlay_min_coverage must be in (0, 1]".to_string());
+        }
+        if session.check_overlays_before_highlight && !self.features.enable_highlighting {
+            problems.push(
+                "session.check_overlays_before_highlight needs features.enable_highlighting"
+                    .to_string(),
+            );
+        }
+        if session.storage_extraction.max_value_length == 0 {
+            problems
+                .push("session.storage_extraction.max_value_length must be positive".to_string());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems_error(problems))
+        }
+    }
+
+    fn from_layers(layered: Value) -> Result<Self> {
+        serde_json::from_value(layered)
+            .map_err(|e| BrowserAgentError::ConfigurationError(format!("invalid config: {}", e)))
+    }
+}
+
+fn problems_error(problems: Vec<String>) -> BrowserAgentError {
+    BrowserAgentError::ConfigurationError(format!(
+        "{} problem(s): {}",
+        problems.len(),
+        problems.join("; ")
+    ))
+}
+
+pub(crate) fn read_config_file(path: &Path) -> Result<Value> {
+    let contents = std::fs::read_to_string(path)?;
+    let invalid = |e: &dyn std::fmt::Display| {
+        BrowserAgentError::ConfigurationError(format!("{}: {}", path.display(), e))
+    };
+
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => {
+            let parsed: toml::Value = toml::from_str(&contents).map_err(|e| invalid(&e))?;
+            serde_json::to_value(parsed).map_err(|e| invalid(&e))
+        }
+        Some("json") => serde_json::from_str(&contents).map_err(|e| invalid(&e)),
+        _ => Err(invalid(&"expected a .toml or .json file")),
+    }
+}
+
+/// Overlay `layer` onto `base`, merging tables and replacing everything else
+fn merge_values(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+/// Parse `raw` as the same JSON type as `current`
+fn parse_like(current: &Value, raw: &str) -> Option<Value> {
+    let raw = raw.trim();
+    match current {
+        Value::Bool(_) => match raw.to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Some(Value::Bool(true)),
+            "false" | "0" | "no" | "off" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        Value::Number(_) => serde_json::from_str::<serde_json::Number>(raw)
+            .ok()
+            .map(Value::Number),
+        Value::Array(_) => Some(Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        )),
+        Value::Object(_) => serde_json::from_str(raw).ok(),
+        Value::Null if raw.is_empty() => Some(Value::Null),
+        // Unset optional numbers such as `min_version`
+        Value::Null if raw.parse::<u64>().is_ok() => serde_json::from_str(raw).ok(),
+        _ => Some(Value::String(raw.to_string())),
+    }
+}
+
+/// `1920x1080` or `1920x1080@2`
+fn parse_viewport(raw: &str) -> Option<Value> {
+    let (size, scale) = match raw.trim().split_once('@') {
+        Some((size, scale)) => (size, scale.parse::<f64>().ok()?),
+        None => (raw.trim(), 1.0),
+    };
+    let (width, height) = size.split_once(['x', 'X'])?;
+    Some(serde_json::json!({
+        "width": width.trim().parse::<u32>().ok()?,
+        "height": height.trim().parse::<u32>().ok()?,
+        "device_scale_factor": scale,
+    }))
 }
 
 impl Default for Config {
@@ -75,13 +1073,21 @@ impl Default for BrowserConfig {
     fn default() -> Self {
         Self {
             browser_type: BrowserType::Chrome,
-            headless: true,
+            headless: HeadlessMode::New,
             viewport: Viewport::default(),
             user_agent: None,
             disable_images: false,
             disable_javascript: false,
             args: vec![],
             timeout_ms: 30000,
+            executable_path: None,
+            channel: None,
+            min_version: None,
+            disable_gpu: true,
+            no_first_run: true,
+            lang: None,
+            window_position: None,
+            webdriver_url: None,
         }
     }
 }
@@ -94,6 +1100,13 @@ impl Default for DomConfig {
             max_text_length: 1000,
             enable_ai_labels: false,
             screenshot_quality: 80,
+            highlight_style: HighlightStyle::Full,
+            highlight_backend: HighlightBackend::DomOverlay,
+            site_profiles: Vec::new(),
+            collect_stats: false,
+            stamp_elements: false,
+            stamp_exclusions: default_stamp_exclusions(),
+            explain: false,
         }
     }
 }
@@ -105,6 +1118,33 @@ impl Default for SessionConfig {
             element_timeout_ms: 2000,
             retry_attempts: 3,
             enable_logging: true,
+            allowed_domains: vec![],
+            blocked_domains: vec![],
+            restrict_subresources: false,
+            aggregate_frame_storage: false,
+            wait_for_network_idle: false,
+            network_idle_ms: default_network_idle_ms(),
+            busy_policy: BusyPolicy::default(),
+            check_overlays_before_highlight: false,
+            overlay_min_coverage: default_overlay_min_coverage(),
+            max_response_body_bytes: default_max_response_body_bytes(),
+            max_websocket_payload_bytes: default_max_websocket_payload_bytes(),
+            bypass_service_worker: false,
+            storage_extraction: StorageExtractionOptions::default(),
+            token_probe: TokenProbeOptions::default(),
+            csrf: CsrfOptions::default(),
+            keepalive: KeepaliveOptions::default(),
+            watchdog: WatchdogConfig::default(),
+            interaction_profile: InteractionProfile::default(),
+            event_buffer: default_event_buffer(),
+            prefetch_limit: default_prefetch_limit(),
+            prefetch_max_bytes: default_prefetch_max_bytes(),
+            change_history_len: default_change_history_len(),
+            poll_interval_ms: default_poll_interval_ms(),
+            seed: None,
+            session_id: None,
+            language: None,
+            language_packs: Vec::new(),
         }
     }
 }
@@ -126,6 +1166,212 @@ impl Default for Viewport {
             width: 1280,
             height: 720,
             device_scale_factor: 1.0,
+            mobile: false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Path of a new config file in the temp dir holding `contents`
+    fn config_file(extension: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "surfai-config-{}.{}",
+            uuid::Uuid::new_v4().simple(),
+            extension
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn env_overrides_file_which_overrides_defaults() {
+        let path = config_file(
+            "toml",
+            "[browser]\ntimeout_ms = 5000\nlang = \"de-DE\"\n\n[session]\nnavigation_timeout_ms = 9000\n",
+        );
+        let mut config = Config::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.browser.timeout_ms, 5000);
+        assert_eq!(config.session.navigation_timeout_ms, 9000);
+        // Settings the file leaves out keep their defaults
+        assert_eq!(
+            config.session.poll_interval_ms,
+            SessionConfig::default().poll_interval_ms
+        );
+
+        let ignored = config
+            .apply_overrides([
+                ("SURFAI_BROWSER_TIMEOUT_MS", "7000"),
+                (
+                    "SURFAI_SESSION_ALLOWED_DOMAINS",
+                    "example.com, *.example.org",
+                ),
+                ("SURFAI_VIEWPORT", "1920x1080@2"),
+                ("PATH", "/usr/bin"),
+            ])
+            .unwrap();
+        assert!(ignored.is_empty());
+        assert_eq!(config.browser.timeout_ms, 7000);
+        assert_eq!(config.browser.lang.as_deref(), Some("de-DE"));
+        assert_eq!(config.session.navigation_timeout_ms, 9000);
+        assert_eq!(
+            config.session.allowed_domains,
+            ["example.com", "*.example.org"]
+        );
+        assert_eq!(
+            (
+                config.browser.viewport.width,
+                config.browser.viewport.height
+            ),
+            (1920, 1080)
+        );
+        assert_eq!(config.browser.viewport.device_scale_factor, 2.0);
+    }
+
+    #[test]
+    fn later_override_of_the_same_setting_wins() {
+        let mut config = Config::default();
+        config
+            .apply_overrides([
+                ("SURFAI_BROWSER_TIMEOUT_MS", "1000"),
+                ("SURFAI_BROWSER_TIMEOUT_MS", "2000"),
+            ])
+            .unwrap();
+        assert_eq!(config.browser.timeout_ms, 2000);
+    }
+
+    #[test]
+    fn unparseable_values_are_reported_together() {
+        let mut config = Config::default();
+        let error = config
+            .apply_overrides([
+                ("SURFAI_BROWSER_TIMEOUT_MS", "soon"),
+                ("SURFAI_SESSION_NAVIGATION_TIMEOUT_MS", "8000"),
+                ("SURFAI_FEATURES_ENABLE_HIGHLIGHTING", "maybe"),
+                ("SURFAI_VIEWPORT", "wide"),
+            ])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("3 problem(s)"), "{}", error);
+        assert!(error.contains("SURFAI_BROWSER_TIMEOUT_MS: cannot parse 'soon'"));
+        assert!(error.contains("SURFAI_FEATURES_ENABLE_HIGHLIGHTING: cannot parse 'maybe'"));
+        assert!(error.contains("SURFAI_VIEWPORT: expected WIDTHxHEIGHT[@SCALE]"));
+        // Nothing is applied when any value is rejected
+        assert_eq!(
+            config.session.navigation_timeout_ms,
+            SessionConfig::default().navigation_timeout_ms
+        );
+    }
+
+    #[test]
+    fn unknown_settings_are_warnings() {
+        let mut config = Config::default();
+        let ignored = config
+            .apply_overrides([
+                ("SURFAI_LOG_FORMAT", "json"),
+                ("SURFAI_BROWSER_NO_SUCH_FIELD", "1"),
+                ("SURFAI_TOKEN", "abc"),
+                ("SURFAI_BROWSER_TIMEOUT_MS", "4000"),
+            ])
+            .unwrap();
+        assert_eq!(
+            ignored,
+            [
+                "ignoring SURFAI_LOG_FORMAT: unknown setting",
+                "ignoring SURFAI_BROWSER_NO_SUCH_FIELD: unknown setting",
+                "ignoring SURFAI_TOKEN: unknown setting",
+            ]
+        );
+        assert_eq!(config.browser.timeout_ms, 4000);
+    }
+
+    #[tokio::test]
+    async fn credential_variables_are_not_settings() {
+        use crate::utils::{CredentialProvider, CredentialPurpose, EnvVarProvider};
+
+        let provider = EnvVarProvider::new();
+        let (username, password) =
+            provider.variable_names("config-test.example.com", &CredentialPurpose::Login);
+        assert_eq!(
+            password,
+            "SURFAI_CRED__CONFIG_TEST_EXAMPLE_COM_LOGIN_PASSWORD"
+        );
+        std::env::set_var(&username, "alice");
+        std::env::set_var(&password, "hunter2");
+        std::env::set_var("SURFAI_DOM_MAX_TEXT_LENGTH", "321");
+
+        let loaded = Config::load(None);
+        let credential = provider
+            .get("config-test.example.com", &CredentialPurpose::Login)
+            .await;
+        for name in [&username, &password, "SURFAI_DOM_MAX_TEXT_LENGTH"] {
+            std::env::remove_var(name);
+        }
+
+        assert_eq!(loaded.unwrap().dom.max_text_length, 321);
+        assert_eq!(credential.unwrap().unwrap().username, "alice");
+        let mut config = Config::default();
+        assert!(config
+            .apply_overrides([(password.as_str(), "hunter2")])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let mut config = Config::default();
+        config.browser.timeout_ms = 0;
+        config.session.poll_interval_ms = 0;
+        config.dom.screenshot_quality = 101;
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("3 problem(s)"), "{}", error);
+        assert!(error.contains("browser.timeout_ms must be positive"));
+        assert!(error.contains("session.poll_interval_ms must be positive"));
+        assert!(error.contains("dom.screenshot_quality must be at most 100"));
+    }
+
+    #[test]
+    fn headless_accepts_the_old_bool() {
+        let mode = |value: &str| serde_json::from_str::<HeadlessMode>(value);
+        assert_eq!(mode("true").unwrap(), HeadlessMode::New);
+        assert_eq!(mode("false").unwrap(), HeadlessMode::Off);
+        assert_eq!(mode("\"Legacy\"").unwrap(), HeadlessMode::Legacy);
+        assert_eq!(mode("\"new\"").unwrap(), HeadlessMode::New);
+        assert!(mode("\"shell\"")
+            .unwrap_err()
+            .to_string()
+            .contains("unknown headless mode 'shell'"));
+    }
+
+    #[test]
+    fn token_probe_fills_missing_fields() {
+        let probe: TokenProbeOptions =
+            serde_json::from_value(serde_json::json!({ "extra_keys": ["app.session.v2"] }))
+                .unwrap();
+        assert_eq!(probe.extra_keys, ["app.session.v2"]);
+        assert!(probe.key_patterns.is_empty());
+        assert!(probe.cookie_patterns.is_empty());
+        assert!(!probe.detect_token_values);
+        assert_eq!(
+            probe.max_candidates,
+            TokenProbeOptions::default().max_candidates
+        );
+    }
+
+    #[test]
+    fn memory_caps_come_from_the_file_or_the_environment() {
+        let path = config_file("toml", "[session]\nprefetch_max_bytes = 1048576\n");
+        let mut config = Config::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.session.prefetch_max_bytes, 1024 * 1024);
+        assert_eq!(config.session.change_history_len, 100);
+
+        config
+            .apply_overrides([("SURFAI_SESSION_CHANGE_HISTORY_LEN", "20")])
+            .unwrap();
+        assert_eq!(config.session.change_history_len, 20);
+    }
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+
+/// A span of a script's source. Offsets count characters, as the profiler
+/// reports them, and are called bytes like in browser devtools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl CoverageRange {
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// How often a span ran, as the profiler reports it per function. Spans nest;
+/// the innermost one covering a character decides its count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountedRange {
+    pub start: usize,
+    pub end: usize,
+    pub count: u64,
+}
+
+/// How much of one script ran
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptCoverage {
+    /// Empty for scripts without a source URL, such as `execute_script` calls
+    pub url: String,
+    pub used_bytes: usize,
+    pub total_bytes: usize,
+    /// Spans that never ran, in order; empty unless
+    /// `JsCoverageOptions::unused_ranges` is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unused_ranges: Vec<CoverageRange>,
+}
+
+impl ScriptCoverage {
+    /// Coverage of a script from every span the profiler reported for it
+    pub fn from_ranges(url: impl Into<String>, ranges: &[CountedRange]) -> Self {
+        let mut sorted: Vec<&CountedRange> = ranges
+            .iter()
+            .filter(|range| range.end > range.start)
+            .collect();
+        sorted.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+        // Sweep the nested spans, handing each stretch to the innermost open one
+        let mut segments: Vec<(CoverageRange, u64)> = Vec::new();
+        let mut emit = |start: usize, end: usize, count: u64| {
+            if end > start {
+                segments.push((CoverageRange { start, end }, count));
+            }
+        };
+        let mut open: Vec<&CountedRange> = Vec::new();
+        let mut cursor = 0;
+        for range in sorted {
+            while let Some(top) = open.last().filter(|top| top.end <= range.start) {
+                emit(cursor.max(top.start), top.end, top.count);
+                cursor = cursor.max(top.end);
+                open.pop();
+            }
+            if let Some(top) = open.last() {
+                emit(cursor.max(top.start), range.start, top.count);
+            }
+            cursor = cursor.max(range.start);
+            open.push(range);
+        }
+        while let Some(top) = open.pop() {
+            emit(cursor.max(top.start), top.end, top.count);
+            cursor = cursor.max(top.end);
+        }
+
+        let total_bytes = segments
+            .iter()
+            .map(|(range, _)| range.end)
+            .max()
+            .unwrap_or(0);
+        let used_bytes = segments
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(range, _)| range.len())
+            .sum();
+        let mut unused_ranges: Vec<CoverageRange> = Vec::new();
+        for (range, _) in segments.iter().filter(|(_, count)| *count == 0) {
+            match unused_ranges.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => unused_ranges.push(*range),
+            }
+        }
+        Self {
+            url: url.into(),
+            used_bytes,
+            total_bytes,
+            unused_ranges,
+        }
+    }
+
+    pub fn unused_bytes(&self) -> usize {
+        self.total_bytes.saturating_sub(self.used_bytes)
+    }
+
+    /// Share of the script that ran, 0-100
+    pub fn percentage(&self) -> f64 {
+        percentage(self.used_bytes, self.total_bytes)
+    }
+}
+
+/// What `BrowserSession::start_js_coverage_with_options` records
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsCoverageOptions {
+    /// Keep each script's never-run spans in `ScriptCoverage::unused_ranges`
+    #[serde(default)]
+    pub unused_ranges: bool,
+    /// Also report scripts without a source URL, which includes everything the
+    /// session itself injects
+    #[serde(default)]
+    pub include_anonymous: bool,
+}
+
+impl JsCoverageOptions {
+    pub fn with_unused_ranges(mut self) -> Self {
+        self.unused_ranges = true;
+        self
+    }
+
+    pub fn with_anonymous(mut self) -> Self {
+        self.include_anonymous = true;
+        self
+    }
+}
+
+/// JavaScript coverage collected between `start_js_coverage` and `stop_js_coverage`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JsCoverage {
+    /// Largest first
+    pub scripts: Vec<ScriptCoverage>,
+    pub used_bytes: usize,
+    pub total_bytes: usize,
+}
+
+impl JsCoverage {
+    pub fn from_scripts(mut scripts: Vec<ScriptCoverage>) -> Self {
+        scripts.sort_by_key(|script| std::cmp::Reverse(script.total_bytes));
+        Self {
+            used_bytes: scripts.iter().map(|script| script.used_bytes).sum(),
+            total_bytes: scripts.iter().map(|script| script.total_bytes).sum(),
+            scripts,
+        }
+    }
+
+    /// Share of all recorded script source that ran, 0-100
+    pub fn percentage(&self) -> f64 {
+        percentage(self.used_bytes, self.total_bytes)
+    }
+
+    /// First script whose URL contains `fragment`
+    pub fn script(&self, fragment: &str) -> Option<&ScriptCoverage> {
+        self.scripts
+            .iter()
+            .find(|script| script.url.contains(fragment))
+    }
+}
+
+impl std::fmt::Display for JsCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.1}% of {} bytes in {} scripts used",
+            self.percentage(),
+            self.total_bytes,
+            self.scripts.len()
+        )
+    }
+}
+
+fn percentage(used: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 * 100.0 / total as f64
+    }
+}
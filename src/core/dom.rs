@@ -1,6 +1,7 @@
 use crate::dom::{DomElement, DomState};
 use crate::errors::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
 /// Core DOM processing trait
 ///
@@ -34,8 +35,32 @@ pub trait DomProcessorTrait: Send + Sync {
     fn generate_selector(&self, element: &DomElement, selector_type: SelectorType) -> String;
 }
 
+/// `DomProcessorTrait` for one browser type, in a form that can be boxed
+#[async_trait]
+pub(crate) trait SessionDomProcessor<B: crate::core::BrowserTrait>: Send + Sync {
+    async fn extract_dom_state(
+        &self,
+        browser: &B,
+        tab: &B::TabHandle,
+        include_screenshot: bool,
+    ) -> Result<DomState>;
+}
+
+#[async_trait]
+impl<B: crate::core::BrowserTrait, D: DomProcessorTrait> SessionDomProcessor<B> for D {
+    async fn extract_dom_state(
+        &self,
+        browser: &B,
+        tab: &B::TabHandle,
+        include_screenshot: bool,
+    ) -> Result<DomState> {
+        DomProcessorTrait::extract_dom_state(self, browser, tab, include_screenshot).await
+    }
+}
+
 /// Criteria for filtering DOM elements
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ElementFilter {
     pub tag_names: Option<Vec<String>>,
     pub has_text: Option<String>,
@@ -44,6 +69,53 @@ pub struct ElementFilter {
     pub has_attribute: Option<(String, Option<String>)>,
 }
 
+impl ElementFilter {
+    /// Whether `element` satisfies every criterion that is set
+    pub fn matches(&self, element: &DomElement) -> bool {
+        if let Some(ref tag_names) = self.tag_names {
+            if !tag_names.contains(&element.tag_name) {
+                return false;
+            }
+        }
+
+        if let Some(ref text) = self.has_text {
+            if let Some(ref element_text) = element.text_content {
+                if !element_text.to_lowercase().contains(&text.to_lowercase()) {
+                    return false;
+                }
+            } else {
+                return false;
+            }
+        }
+
+        if let Some(visible) = self.is_visible {
+            if element.is_visible != visible {
+                return false;
+            }
+        }
+
+        if let Some(interactive) = self.is_interactive {
+            if element.is_interactable != interactive {
+                return false;
+            }
+        }
+
+        if let Some((ref attr_name, ref attr_value)) = self.has_attribute {
+            if let Some(element_attr_value) = element.attributes.get(attr_name) {
+                if let Some(ref expected_value) = attr_value {
+                    if element_attr_value != expected_value {
+                        return false;
+                    }
+                }
+            } else {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 /// Types of selectors that can be generated
 #[derive(Debug, Clone)]
 pub enum SelectorType {
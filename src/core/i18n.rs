@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+
+/// Words the text-based detectors look for on pages in one language.
+///
+/// Built-in packs cover `en`, `de`, `es` and `fr`. Packs in
+/// `SessionConfig::language_packs` add languages or extend a built-in pack with
+/// the same `language`. Matching is case-insensitive except for `success` and
+/// `failure`, which are checked against the page text as written.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguagePack {
+    /// Primary ISO 639-1 subtag, e.g. `de`
+    pub language: String,
+    /// Text of login submit buttons
+    #[serde(default)]
+    pub login: Vec<String>,
+    /// Fragments of username field names, ids and placeholders
+    #[serde(default)]
+    pub username: Vec<String>,
+    /// Fragments of password field names, ids and placeholders
+    #[serde(default)]
+    pub password: Vec<String>,
+    /// Text shown once logged in
+    #[serde(default)]
+    pub success: Vec<String>,
+    /// Text shown when a login is rejected
+    #[serde(default)]
+    pub failure: Vec<String>,
+    /// Labels of controls that close or accept an overlay
+    #[serde(default)]
+    pub dismiss: Vec<String>,
+    /// Words marking a cookie or consent notice
+    #[serde(default)]
+    pub consent: Vec<String>,
+}
+
+fn words(list: &[&str]) -> Vec<String> {
+    list.iter().map(|word| word.to_string()).collect()
+}
+
+impl LanguagePack {
+    /// Built-in pack for `language`, by its primary subtag
+    pub fn builtin(language: &str) -> Option<Self> {
+        let pack = match primary_subtag(language).as_str() {
+            "en" => Self {
+                language: "en".to_string(),
+                login: words(&["Login", "Log in", "Sign in"]),
+                username: words(&["username", "email"]),
+                password: words(&["password"]),
+                success: words(&["dashboard", "profile", "logout", "welcome"]),
+                failure: words(&["error", "invalid", "incorrect", "failed"]),
+                dismiss: words(&[
+                    "close",
+                    "dismiss",
+                    "no thanks",
+                    "no, thanks",
+                    "not now",
+                    "got it",
+                    "accept",
+                    "ok",
+                ]),
+                consent: words(&["cookie", "consent", "gdpr", "privacy"]),
+            },
+            "de" => Self {
+                language: "de".to_string(),
+                login: words(&["Anmelden", "Einloggen", "Login"]),
+                username: words(&["benutzer", "e-mail", "anmeldename"]),
+                password: words(&["passwort", "kennwort"]),
+                success: words(&["Abmelden", "Mein Konto", "Willkommen", "Profil"]),
+                failure: words(&["Fehler", "ungültig", "falsch", "fehlgeschlagen"]),
+                dismiss: words(&[
+                    "schließen",
+                    "ablehnen",
+                    "nein danke",
+                    "später",
+                    "verstanden",
+                    "akzeptieren",
+                    "alle akzeptieren",
+                    "zustimmen",
+                    "ok",
+                ]),
+                consent: words(&["einwilligung", "datenschutz", "zustimmung"]),
+            },
+            "es" => Self {
+                language: "es".to_string(),
+                login: words(&["Iniciar sesión", "Entrar", "Acceder", "Ingresar"]),
+                username: words(&["usuario", "correo"]),
+                password: words(&["contraseña", "clave"]),
+                success: words(&["Cerrar sesión", "Mi cuenta", "Bienvenido", "Perfil"]),
+                failure: words(&["error", "inválid", "incorrect", "fallido"]),
+                dismiss: words(&[
+                    "cerrar",
+                    "rechazar",
+                    "no, gracias",
+                    "ahora no",
+                    "entendido",
+                    "aceptar",
+                    "aceptar todo",
+                    "aceptar todas",
+                ]),
+                consent: words(&["consentimiento", "privacidad"]),
+            },
+            "fr" => Self {
+                language: "fr".to_string(),
+                login: words(&["Se connecter", "Connexion", "Connectez-vous"]),
+                username: words(&["utilisateur", "identifiant", "courriel"]),
+                password: words(&["mot de passe", "mdp"]),
+                success: words(&["Déconnexion", "Mon compte", "Bienvenue", "Profil"]),
+                failure: words(&["erreur", "invalide", "incorrect", "échec"]),
+                dismiss: words(&[
+                    "fermer",
+                    "refuser",
+                    "non merci",
+                    "plus tard",
+                    "compris",
+                    "accepter",
+                    "tout accepter",
+                    "j'accepte",
+                ]),
+                consent: words(&["consentement", "confidentialité", "vie privée"]),
+            },
+            _ => return None,
+        };
+        Some(pack)
+    }
+
+    /// Append the words of `other` this pack does not have yet
+    pub fn extend(&mut self, other: &LanguagePack) {
+        let lists = [
+            (&mut self.login, &other.login),
+            (&mut self.username, &other.username),
+            (&mut self.password, &other.password),
+            (&mut self.success, &other.success),
+            (&mut self.failure, &other.failure),
+            (&mut self.dismiss, &other.dismiss),
+            (&mut self.consent, &other.consent),
+        ];
+        for (mine, theirs) in lists {
+            for word in theirs {
+                if !mine.contains(word) {
+                    mine.push(word.clone());
+                }
+            }
+        }
+    }
+
+    /// Pack for a page in `language`, with the built-in and `custom` words for
+    /// that language first and English after them, since localized pages often
+    /// keep some English labels. Unknown or missing languages give English.
+    pub fn resolve(language: Option<&str>, custom: &[LanguagePack]) -> Self {
+        let primary = language
+            .map(primary_subtag)
+            .filter(|primary| !primary.is_empty())
+            .unwrap_or_else(|| "en".to_string());
+        let packs_for = |language: &str| {
+            let mut pack = Self::builtin(language).unwrap_or_else(|| Self {
+                language: language.to_string(),
+                ..Self::default()
+            });
+            for extra in custom
+                .iter()
+                .filter(|extra| primary_subtag(&extra.language) == language)
+            {
+                pack.extend(extra);
+            }
+            pack
+        };
+
+        let mut pack = packs_for(&primary);
+        if primary != "en" {
+            pack.extend(&packs_for("en"));
+        }
+        pack
+    }
+}
+
+/// `de` for `de-AT`, `de_DE` or `DE`
+fn primary_subtag(language: &str) -> String {
+    language
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::{Html, Selector};
+
+    fn pack(language: &str) -> LanguagePack {
+        LanguagePack::resolve(Some(language), &[])
+    }
+
+    #[test]
+    fn builtin_packs_are_found_by_primary_subtag() {
+        for (tag, language) in [("de-AT", "de"), ("DE", "de"), ("es_MX", "es"), ("fr", "fr")] {
+            assert_eq!(LanguagePack::builtin(tag).unwrap().language, language);
+        }
+        assert_eq!(LanguagePack::builtin("pt-BR"), None);
+        assert_eq!(LanguagePack::builtin(""), None);
+    }
+
+    #[test]
+    fn resolve_puts_the_page_language_before_english() {
+        let german = pack("de-DE");
+        assert_eq!(german.language, "de");
+        assert_eq!(
+            german.login,
+            ["Anmelden", "Einloggen", "Login", "Log in", "Sign in"]
+        );
+        assert_eq!(german.password, ["passwort", "kennwort", "password"]);
+
+        let english = LanguagePack::builtin("en").unwrap();
+        assert_eq!(LanguagePack::resolve(None, &[]), english);
+        assert_eq!(LanguagePack::resolve(Some(" "), &[]), english);
+        // Unknown languages keep their tag but fall back to the English words
+        let portuguese = pack("pt");
+        assert_eq!(portuguese.language, "pt");
+        assert_eq!(portuguese.login, english.login);
+    }
+
+    #[test]
+    fn custom_packs_extend_or_add_languages() {
+        let custom = [
+            LanguagePack {
+                language: "de-CH".to_string(),
+                login: vec!["Anmeldung".to_string(), "Anmelden".to_string()],
+                ..LanguagePack::default()
+            },
+            LanguagePack {
+                language: "pt".to_string(),
+                login: vec!["Entrar".to_string()],
+                ..LanguagePack::default()
+            },
+        ];
+        let german = LanguagePack::resolve(Some("de"), &custom);
+        assert_eq!(
+            &german.login[..4],
+            ["Anmelden", "Einloggen", "Login", "Anmeldung"]
+        );
+        assert_eq!(german.login.iter().filter(|w| *w == "Anmelden").count(), 1);
+
+        let portuguese = LanguagePack::resolve(Some("pt-BR"), &custom);
+        assert_eq!(portuguese.login[0], "Entrar");
+        assert!(portuguese.login.contains(&"Sign in".to_string()));
+    }
+
+    /// Text, `name` and `placeholder` of every element `selector` matches in `html`
+    fn texts(html: &Html, selector: &str) -> Vec<String> {
+        html.select(&Selector::parse(selector).unwrap())
+            .map(|element| {
+                let attribute = |name| element.value().attr(name).unwrap_or_default();
+                format!(
+                    "{} {} {}",
+                    element.text().collect::<String>().trim(),
+                    attribute("name"),
+                    attribute("placeholder")
+                )
+                .to_lowercase()
+            })
+            .collect()
+    }
+
+    /// The pack for `language` has a word for every control of a localized
+    /// login fixture the detectors need to find
+    fn assert_covers_login_fixture(language: &str, fixture: &str) {
+        let html = Html::parse_document(fixture);
+        let pack = pack(language);
+        let lower = |words: &[String]| words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>();
+        let contains_any =
+            |text: &str, words: &[String]| lower(words).iter().any(|w| text.contains(w));
+
+        let username = &texts(&html, "input[type=text]")[0];
+        assert!(
+            contains_any(username, &pack.username),
+            "{}: {}",
+            language,
+            username
+        );
+        let password = &texts(&html, "input[type=password]")[0];
+        assert!(
+            contains_any(password, &pack.password),
+            "{}: {}",
+            language,
+            password
+        );
+
+        let submit = html
+            .select(&Selector::parse("#login-form button, #login-form [role=button]").unwrap())
+            .map(|e| e.text().collect::<String>().trim().to_string())
+            .next()
+            .unwrap();
+        assert!(pack.login.contains(&submit), "{}: {}", language, submit);
+
+        let notice = &texts(&html, "div[id]")[0];
+        assert!(
+            contains_any(notice, &pack.consent),
+            "{}: {}",
+            language,
+            notice
+        );
+        let accept = texts(&html, "div[id] button")[0].trim().to_string();
+        assert!(
+            lower(&pack.dismiss).contains(&accept),
+            "{}: {}",
+            language,
+            accept
+        );
+    }
+
+    #[test]
+    fn german_pack_covers_the_german_login_fixture() {
+        assert_covers_login_fixture("de", include_str!("../testing/fixtures/login_de.html"));
+    }
+
+    #[test]
+    fn spanish_pack_covers_the_spanish_login_fixture() {
+        assert_covers_login_fixture("es-ES", include_str!("../testing/fixtures/login_es.html"));
+    }
+}
@@ -1,9 +1,30 @@
 pub mod browser;
 pub mod config;
+pub mod coverage;
 pub mod dom;
+pub mod i18n;
+pub mod policy;
+pub mod selector;
 pub mod session;
+pub mod site_profile;
+pub mod throttling;
+pub mod user_agent;
 
-pub use browser::{BrowserCapabilities, BrowserTrait}; // Added BrowserCapabilities
-pub use config::Config;
+pub use browser::{
+    BrowserCapabilities, BrowserFeature, BrowserTrait, CapturedResponse, ConsoleLevel,
+    ConsoleMessage, FrameInfo, FrameTarget, IndexedDbDatabase, IndexedDbStore, NetworkIdleStats,
+    NodeDescription, NodeRef, ResponseMatcher, ServiceWorkerInfo, SiteDataKind, WsConnectionStats,
+    WsDirection, WsEvent,
+}; // Added BrowserCapabilities
+pub use config::{CallOptions, Config};
+pub use coverage::{CountedRange, CoverageRange, JsCoverage, JsCoverageOptions, ScriptCoverage};
 pub use dom::{DomProcessorTrait, ElementFilter, SelectorType}; // Added exports
+pub use i18n::LanguagePack;
+pub use policy::DomainPolicy;
+pub use selector::Selector;
 pub use session::SessionTrait;
+pub use site_profile::{ConsentChoice, SiteProfile};
+pub use throttling::{NetworkConditions, NetworkProfile, Throttling};
+pub use user_agent::{
+    UserAgentBrand, UserAgentMetadata, UserAgentOverride, UserAgentProfile, UserAgentRun,
+};
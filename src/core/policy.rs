@@ -0,0 +1,342 @@
+use crate::core::config::SessionConfig;
+use crate::errors::{BrowserAgentError, Result};
+use serde_json::json;
+
+/// Domain allow/block rules derived from `SessionConfig`.
+///
+/// Patterns are host globs where `*` matches any run of characters, optionally
+/// followed by `:port`. A pattern without a port matches every port. `*.example.com`
+/// matches subdomains only; list `example.com` as well to include the apex.
+/// IPv6 literals are written in brackets, e.g. `[::1]:8080`. The trailing dot of a
+/// fully qualified host, as in `example.com.`, is ignored on both sides.
+#[derive(Debug, Clone, Default)]
+pub struct DomainPolicy {
+    allowed: Vec<DomainPattern>,
+    blocked: Vec<DomainPattern>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DomainPattern {
+    raw: String,
+    host: regex::Regex,
+    port: Option<u16>,
+}
+
+impl DomainPolicy {
+    pub fn new(allowed: &[String], blocked: &[String]) -> Result<Self> {
+        Ok(Self {
+            allowed: allowed
+                .iter()
+                .map(|p| DomainPattern::parse(p))
+                .collect::<Result<_>>()?,
+            blocked: blocked
+                .iter()
+                .map(|p| DomainPattern::parse(p))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    pub fn from_config(config: &SessionConfig) -> Result<Self> {
+        Self::new(&config.allowed_domains, &config.blocked_domains)
+    }
+
+    /// Whether any rule is configured at all
+    pub fn is_active(&self) -> bool {
+        !self.allowed.is_empty() || !self.blocked.is_empty()
+    }
+
+    /// Returns the rule that blocks `url`, or `None` when it may be visited.
+    ///
+    /// URLs without a host (`data:`, `file:`, `javascript:`) and strings that do
+    /// not parse as URLs match no pattern. Blocked domains let them through; an
+    /// allowlist fails closed and blocks all of them except `about:blank`.
+    pub fn blocking_rule(&self, url: &str) -> Option<String> {
+        let parsed = url::Url::parse(url).ok();
+        let Some(host) = parsed.as_ref().and_then(host_of) else {
+            let blank = url.trim().eq_ignore_ascii_case("about:blank");
+            return (!self.allowed.is_empty() && !blank)
+                .then(|| "allowed_domains: URL has no host".to_string());
+        };
+        let port = parsed.and_then(|parsed| parsed.port_or_known_default());
+
+        if let Some(rule) = self.blocked.iter().find(|p| p.matches(&host, port)) {
+            return Some(format!("blocked_domains: {}", rule.raw));
+        }
+
+        if !self.allowed.is_empty() && !self.allowed.iter().any(|p| p.matches(&host, port)) {
+            return Some("allowed_domains: no pattern matched".to_string());
+        }
+
+        None
+    }
+
+    /// Check `url` against the policy, returning `NavigationBlocked` on a match
+    pub fn check(&self, url: &str) -> Result<()> {
+        match self.blocking_rule(url) {
+            Some(rule) => Err(BrowserAgentError::NavigationBlocked {
+                url: url.to_string(),
+                rule,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Script that wraps `window.open` so popups to disallowed origins are refused
+    pub fn window_open_guard_script(&self) -> String {
+        let to_js = |patterns: &[DomainPattern]| {
+            patterns
+                .iter()
+                .map(|p| json!({ "raw": p.raw, "host": p.host.as_str(), "port": p.port }))
+                .collect::<Vec<_>>()
+        };
+
+        format!(
+            r#"
+            (function() {{
+                const allowed = {};
+                const blocked = {};
+                const defaultPorts = {{ 'http:': 80, 'https:': 443, 'ws:': 80, 'wss:': 443, 'ftp:': 21 }};
+
+                const matches = (pattern, host, port) => {{
+                    if (!new RegExp(pattern.host).test(host)) return false;
+                    return pattern.port === null || pattern.port === port;
+                }};
+
+                const blockingRule = (target) => {{
+                    let parsed;
+                    try {{
+                        parsed = new URL(target, window.location.href);
+                    }} catch (e) {{
+                        return allowed.length > 0 ? 'allowed_domains: URL has no host' : null;
+                    }}
+                    if (!parsed.hostname) {{
+                        const blank = parsed.href === 'about:blank';
+                        return allowed.length > 0 && !blank ? 'allowed_domains: URL has no host' : null;
+                    }}
+                    const host = parsed.hostname.toLowerCase().replace(/\.$/, '');
+                    const port = parsed.port ? parseInt(parsed.port, 10) : (defaultPorts[parsed.protocol] ?? null);
+                    const hit = blocked.find(p => matches(p, host, port));
+                    if (hit) return 'blocked_domains: ' + hit.raw;
+                    if (allowed.length > 0 && !allowed.some(p => matches(p, host, port))) {{
+                        return 'allowed_domains: no pattern matched';
+                    }}
+                    return null;
+                }};
+
+                if (!window.__surfaiOriginalOpen) {{
+                    window.__surfaiOriginalOpen = window.open;
+                }}
+                window.__surfaiBlockedOpens = window.__surfaiBlockedOpens || [];
+                window.open = function(target, ...rest) {{
+                    const rule = target ? blockingRule(String(target)) : null;
+                    if (rule) {{
+                        window.__surfaiBlockedOpens.push({{ url: String(target), rule: rule }});
+                        console.warn('surfai: window.open blocked', target, rule);
+                        return null;
+                    }}
+                    return window.__surfaiOriginalOpen.call(window, target, ...rest);
+                }};
+
+                return {{ success: true }};
+            }})()
+            "#,
+            serde_json::Value::Array(to_js(&self.allowed)),
+            serde_json::Value::Array(to_js(&self.blocked)),
+        )
+    }
+}
+
+impl DomainPattern {
+    pub(crate) fn parse(raw: &str) -> Result<Self> {
+        let trimmed = raw.trim().to_lowercase();
+        let invalid =
+            || BrowserAgentError::ConfigurationError(format!("Invalid domain pattern: {}", raw));
+
+        let (host, port) = if let Some(rest) = trimmed.strip_prefix('[') {
+            // IPv6 literal, optionally followed by :port
+            let end = rest.find(']').ok_or_else(invalid)?;
+            let port = match &rest[end + 1..] {
+                "" => None,
+                p => Some(p.strip_prefix(':').ok_or_else(invalid)?.to_string()),
+            };
+            (format!("[{}]", &rest[..end]), port)
+        } else {
+            match trimmed.rsplit_once(':') {
+                Some((host, port)) => (host.to_string(), Some(port.to_string())),
+                None => (trimmed.clone(), None),
+            }
+        };
+
+        let host = host.strip_suffix('.').unwrap_or(&host);
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        let port = port
+            .map(|p| p.parse::<u16>().map_err(|_| invalid()))
+            .transpose()?;
+
+        let host_regex = format!(
+            "^{}$",
+            host.split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join("[^/]*")
+        );
+
+        Ok(Self {
+            raw: raw.to_string(),
+            host: regex::Regex::new(&host_regex).map_err(|_| invalid())?,
+            port,
+        })
+    }
+
+    /// Whether the host of `url` matches; URLs without a host never do
+    pub(crate) fn matches_url(&self, url: &str) -> bool {
+        url::Url::parse(url)
+            .ok()
+            .and_then(|parsed| {
+                let host = host_of(&parsed)?;
+                Some(self.matches(&host, parsed.port_or_known_default()))
+            })
+            .unwrap_or(false)
+    }
+
+    fn matches(&self, host: &str, port: Option<u16>) -> bool {
+        if !self.host.is_match(host) {
+            return false;
+        }
+        match self.port {
+            Some(expected) => port == Some(expected),
+            None => true,
+        }
+    }
+}
+
+/// Lowercased host of `url` without the trailing dot of a fully qualified
+/// name, so `evil.com.` is matched as `evil.com` the way the browser resolves it
+fn host_of(url: &url::Url) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    Some(host.strip_suffix('.').map(str::to_string).unwrap_or(host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allowed: &[&str], blocked: &[&str]) -> DomainPolicy {
+        let owned = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        DomainPolicy::new(&owned(allowed), &owned(blocked)).unwrap()
+    }
+
+    fn pattern(raw: &str) -> DomainPattern {
+        DomainPattern::parse(raw).unwrap()
+    }
+
+    #[test]
+    fn glob_matches_any_run_of_characters() {
+        let p = pattern("shop-*.example.com");
+        assert!(p.matches("shop-eu.example.com", Some(443)));
+        assert!(p.matches("shop-.example.com", Some(443)));
+        assert!(!p.matches("shop.example.com", Some(443)));
+        assert!(!p.matches("shop-eu.example.org", Some(443)));
+    }
+
+    #[test]
+    fn wildcard_subdomain_excludes_the_apex() {
+        let p = pattern("*.example.com");
+        assert!(p.matches("www.example.com", None));
+        assert!(p.matches("a.b.example.com", None));
+        assert!(!p.matches("example.com", None));
+        assert!(!p.matches("badexample.com", None));
+    }
+
+    #[test]
+    fn port_is_matched_only_when_given() {
+        let any = pattern("example.com");
+        assert!(any.matches("example.com", Some(80)));
+        assert!(any.matches("example.com", Some(8080)));
+
+        let fixed = pattern("example.com:8080");
+        assert!(fixed.matches("example.com", Some(8080)));
+        assert!(!fixed.matches("example.com", Some(80)));
+        assert!(pattern("example.com:443").matches_url("https://example.com/"));
+    }
+
+    #[test]
+    fn ip_literals() {
+        let v4 = pattern("127.0.0.1:3000");
+        assert!(v4.matches_url("http://127.0.0.1:3000/x"));
+        assert!(!v4.matches_url("http://127.0.0.1:3001/x"));
+        assert!(!pattern("127.0.0.1").matches_url("http://127.0.0.10/"));
+
+        let v6 = pattern("[::1]:8080");
+        assert!(v6.matches_url("http://[::1]:8080/"));
+        assert!(!v6.matches_url("http://[::1]:8081/"));
+        assert!(pattern("[::1]").matches_url("http://[::1]/"));
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert!(DomainPattern::parse("").is_err());
+        assert!(DomainPattern::parse("example.com:http").is_err());
+        assert!(DomainPattern::parse("[::1").is_err());
+    }
+
+    #[test]
+    fn blocked_domains_win_over_allowed() {
+        let policy = policy(&["*.example.com"], &["admin.example.com"]);
+        assert!(policy.blocking_rule("https://www.example.com/").is_none());
+        assert_eq!(
+            policy
+                .blocking_rule("https://admin.example.com/")
+                .as_deref(),
+            Some("blocked_domains: admin.example.com")
+        );
+        assert!(policy.blocking_rule("https://other.org/").is_some());
+    }
+
+    #[test]
+    fn allowlist_fails_closed_on_urls_without_a_host() {
+        let policy = policy(&["example.com"], &[]);
+        for url in [
+            "data:text/html,<h1>hi</h1>",
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "evil.com/x",
+            "",
+        ] {
+            assert!(policy.check(url).is_err(), "{} was allowed", url);
+        }
+        assert!(policy.check("about:blank").is_ok());
+        assert!(policy.check("https://example.com/").is_ok());
+    }
+
+    #[test]
+    fn trailing_dot_hosts_match_their_domain() {
+        let blocklist = policy(&[], &["evil.com", "*.evil.com"]);
+        for url in [
+            "https://evil.com./",
+            "https://a.evil.com./",
+            "https://EVIL.COM.:443/x",
+        ] {
+            assert!(blocklist.check(url).is_err(), "{} was allowed", url);
+        }
+        assert!(pattern("evil.com.").matches_url("https://evil.com/"));
+        assert!(pattern("*.evil.com").matches_url("https://a.evil.com./"));
+
+        let allowlist = policy(&["example.com"], &[]);
+        assert!(allowlist.check("https://example.com./").is_ok());
+
+        let script = policy(&[], &["evil.com"]).window_open_guard_script();
+        assert!(script.contains(".toLowerCase().replace(/\\.$/, '')"));
+    }
+
+    #[test]
+    fn blocklist_alone_lets_hostless_urls_through() {
+        let policy = policy(&[], &["example.com"]);
+        assert!(policy.check("data:text/html,ok").is_ok());
+        assert!(policy.check("about:blank").is_ok());
+        assert!(policy.check("https://example.com/").is_err());
+    }
+}
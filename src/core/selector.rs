@@ -0,0 +1,414 @@
+use crate::core::dom::SelectorType;
+use crate::dom::xpath::{first_node_expression, xpath_literal};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How an interaction finds its element.
+///
+/// Methods taking `impl Into<Selector>` accept a plain `&str` or `String` as CSS,
+/// so `session.click("#submit")` and `session.click(Selector::text("Sign in"))`
+/// both work.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Selector {
+    Css(String),
+    XPath(String),
+    /// Value of `data-testid`
+    TestId(String),
+    /// Visible text, with whitespace collapsed and the case of ASCII letters
+    /// ignored; other letters match in the case given. Exact matches come before
+    /// elements merely containing the text, and text inside a link, button or
+    /// label resolves to that control.
+    Text(String),
+    /// ARIA role, explicit or implied by the tag, e.g. `button` for
+    /// `<input type="submit">`, and optionally the accessible name, matched like
+    /// `Text`
+    Role {
+        role: String,
+        name: Option<String>,
+    },
+}
+
+/// JavaScript defining `byText(xpath, wanted)` and `byRole(role, name)`, each
+/// returning matching elements, best match first
+const RESOLVE_JS: &str = r#"
+                const normalize = (value) => (value || '').replace(/\s+/g, ' ').trim().toLowerCase();
+                const isShown = (el) => typeof el.checkVisibility === 'function'
+                    ? el.checkVisibility()
+                    : !!(el.offsetWidth || el.offsetHeight || el.getClientRects().length);
+                const ranked = (matches, exact) => {
+                    const seen = new Set();
+                    return matches
+                        .map((el, index) => ({ el, index, exact: exact(el), shown: isShown(el) }))
+                        .sort((a, b) => (b.exact - a.exact) || (b.shown - a.shown) || (a.index - b.index))
+                        .map(match => match.el)
+                        .filter(el => !seen.has(el) && seen.add(el));
+                };
+                const byText = (xpath, wanted) => {
+                    const result = document.evaluate(xpath, document, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null);
+                    const matches = [];
+                    for (let i = 0; i < result.snapshotLength; i++) {
+                        const node = result.snapshotItem(i);
+                        matches.push(node.closest('a[href], button, label, summary, [role="button"], [role="link"], [role="tab"], [role="menuitem"]') || node);
+                    }
+                    const own = (el) => el.tagName === 'INPUT' ? el.value : el.textContent;
+                    return ranked(matches, el => normalize(own(el)) === normalize(wanted));
+                };
+
+                const implicitRole = (el) => {
+                    const tag = el.tagName.toLowerCase();
+                    const type = (el.getAttribute('type') || 'text').toLowerCase();
+                    switch (tag) {
+                        case 'a': case 'area': return el.hasAttribute('href') ? 'link' : null;
+                        case 'button': return 'button';
+                        case 'input':
+                            if (['button', 'submit', 'reset', 'image'].includes(type)) return 'button';
+                            if (type === 'checkbox') return 'checkbox';
+                            if (type === 'radio') return 'radio';
+                            if (type === 'range') return 'slider';
+                            if (type === 'number') return 'spinbutton';
+                            if (type === 'search') return 'searchbox';
+                            if (type === 'hidden') return null;
+                            return 'textbox';
+                        case 'textarea': return 'textbox';
+                        case 'select': return el.multiple || el.size > 1 ? 'listbox' : 'combobox';
+                        case 'option': return 'option';
+                        case 'h1': case 'h2': case 'h3': case 'h4': case 'h5': case 'h6': return 'heading';
+                        case 'img': return el.getAttribute('alt') === '' ? 'presentation' : 'img';
+                        case 'nav': return 'navigation';
+                        case 'main': return 'main';
+                        case 'form': return 'form';
+                        case 'dialog': return 'dialog';
+                        case 'ul': case 'ol': return 'list';
+                        case 'li': return 'listitem';
+                        case 'table': return 'table';
+                        case 'tr': return 'row';
+                        case 'td': return 'cell';
+                        case 'th': return 'columnheader';
+                        case 'summary': return 'button';
+                        case 'progress': return 'progressbar';
+                        default: return null;
+                    }
+                };
+                const roleOf = (el) => (el.getAttribute('role') || '').trim().split(/\s+/)[0].toLowerCase() || implicitRole(el);
+                const textOf = (id) => {
+                    const el = document.getElementById(id);
+                    return el ? el.textContent : '';
+                };
+                const accessibleName = (el) => {
+                    const labelledBy = el.getAttribute('aria-labelledby');
+                    if (labelledBy) {
+                        const text = labelledBy.split(/\s+/).map(textOf).join(' ');
+                        if (normalize(text)) return text;
+                    }
+                    const label = el.getAttribute('aria-label');
+                    if (normalize(label)) return label;
+                    if (el.labels && el.labels.length) {
+                        return Array.from(el.labels).map(label => label.textContent).join(' ');
+                    }
+                    if (el.tagName === 'INPUT' && ['button', 'submit', 'reset'].includes(el.type)) {
+                        return el.value || (el.type === 'submit' ? 'Submit' : el.type === 'reset' ? 'Reset' : '');
+                    }
+                    if (el.tagName === 'IMG' || (el.tagName === 'INPUT' && el.type === 'image')) {
+                        return el.getAttribute('alt') || el.getAttribute('title') || '';
+                    }
+                    if (!['INPUT', 'TEXTAREA', 'SELECT'].includes(el.tagName)) {
+                        const text = el.textContent || Array.from(el.querySelectorAll('img[alt]')).map(img => img.alt).join(' ');
+                        if (normalize(text)) return text;
+                    }
+                    return el.getAttribute('title') || el.getAttribute('placeholder') || '';
+                };
+                const byRole = (role, wanted) => {
+                    const name = wanted === null ? null : normalize(wanted);
+                    const matches = Array.from(document.querySelectorAll('*')).filter(el =>
+                        roleOf(el) === role
+                        && !el.closest('[aria-hidden="true"], [hidden], [data-surfai-internal]')
+                        && (name === null || normalize(accessibleName(el)).includes(name)));
+                    return ranked(matches, el => name === null || normalize(accessibleName(el)) === name);
+                };
+"#;
+
+impl Selector {
+    pub fn css(selector: impl Into<String>) -> Self {
+        Self::Css(selector.into())
+    }
+
+    pub fn xpath(xpath: impl Into<String>) -> Self {
+        Self::XPath(xpath.into())
+    }
+
+    pub fn test_id(test_id: impl Into<String>) -> Self {
+        Self::TestId(test_id.into())
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    /// Any element with `role`, whatever its name
+    pub fn role(role: impl Into<String>) -> Self {
+        Self::Role {
+            role: role.into(),
+            name: None,
+        }
+    }
+
+    /// An element with `role` whose accessible name is or contains `name`
+    pub fn role_named(role: impl Into<String>, name: impl Into<String>) -> Self {
+        Self::Role {
+            role: role.into(),
+            name: Some(name.into()),
+        }
+    }
+
+    /// `selector` read as `selector_type` says
+    pub fn from_type(selector: &str, selector_type: &SelectorType) -> Self {
+        match selector_type {
+            SelectorType::Css => Self::css(selector),
+            SelectorType::XPath => Self::xpath(selector),
+            SelectorType::TestId => Self::test_id(selector),
+        }
+    }
+
+    /// The CSS selector, for the variants that have one
+    pub fn as_css(&self) -> Option<String> {
+        match self {
+            Self::Css(selector) => Some(selector.clone()),
+            Self::TestId(test_id) => Some(format!(
+                "[data-testid={}]",
+                serde_json::to_string(test_id).unwrap_or_default()
+            )),
+            _ => None,
+        }
+    }
+
+    /// JavaScript expression evaluating to the first matching element, or `null`
+    pub(crate) fn element_expression(&self) -> String {
+        match self {
+            Self::Css(_) | Self::TestId(_) => format!(
+                "document.querySelector({})",
+                json(&self.as_css().unwrap_or_default())
+            ),
+            Self::XPath(xpath) => first_node_expression(xpath),
+            _ => format!("({}[0] || null)", self.elements_expression()),
+        }
+    }
+
+    /// JavaScript expression evaluating to an array of every matching element,
+    /// best match first
+    pub(crate) fn elements_expression(&self) -> String {
+        match self {
+            Self::Css(_) | Self::TestId(_) => format!(
+                "Array.from(document.querySelectorAll({}))",
+                json(&self.as_css().unwrap_or_default())
+            ),
+            Self::XPath(xpath) => format!(
+                r#"(function() {{
+                const result = document.evaluate({}, document, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null);
+                const nodes = [];
+                for (let i = 0; i < result.snapshotLength; i++) nodes.push(result.snapshotItem(i));
+                return nodes.filter(node => node.nodeType === Node.ELEMENT_NODE);
+            }})()"#,
+                json(xpath)
+            ),
+            Self::Text(text) => {
+                let wanted = normalized(text);
+                format!(
+                    "(function() {{\n{}\n                return byText({}, {});\n            }})()",
+                    RESOLVE_JS,
+                    json(&text_xpath(&wanted)),
+                    json(&wanted)
+                )
+            }
+            Self::Role { role, name } => format!(
+                "(function() {{\n{}\n                return byRole({}, {});\n            }})()",
+                RESOLVE_JS,
+                json(&role.trim().to_lowercase()),
+                name.as_deref()
+                    .map(|name| json(&normalized(name)))
+                    .unwrap_or_else(|| "null".to_string())
+            ),
+        }
+    }
+}
+
+fn json(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "''".to_string())
+}
+
+/// Runs of whitespace collapsed and ASCII letters lowercased, as XPath's
+/// `translate` in `text_xpath` folds them. `normalize` in `RESOLVE_JS` folds
+/// every letter, so ranking compares both sides through it.
+fn normalized(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_ascii_lowercase()
+}
+
+/// The innermost elements whose text contains `wanted`, plus buttons whose value
+/// does; `wanted` is already normalized. Only ASCII letters are folded, the rest
+/// of the text has to match in case.
+fn text_xpath(wanted: &str) -> String {
+    const UPPER: &str = "'ABCDEFGHIJKLMNOPQRSTUVWXYZ'";
+    const LOWER: &str = "'abcdefghijklmnopqrstuvwxyz'";
+    let literal = xpath_literal(wanted);
+    let folded = |of: &str| format!("translate(normalize-space({}), {}, {})", of, UPPER, LOWER);
+    let contains = |of: &str| format!("contains({}, {})", folded(of), literal);
+    format!(
+        "//body//*[not(self::script or self::style or self::noscript or ancestor-or-self::*[@data-surfai-internal])]\
+         [{}][not(*[{}])] | //input[@type='submit' or @type='button' or @type='reset'][{}]",
+        contains("."),
+        contains("."),
+        contains("@value")
+    )
+}
+
+impl From<&str> for Selector {
+    fn from(selector: &str) -> Self {
+        Self::Css(selector.to_string())
+    }
+}
+
+impl From<String> for Selector {
+    fn from(selector: String) -> Self {
+        Self::Css(selector)
+    }
+}
+
+impl From<&String> for Selector {
+    fn from(selector: &String) -> Self {
+        Self::Css(selector.clone())
+    }
+}
+
+impl From<&Selector> for Selector {
+    fn from(selector: &Selector) -> Self {
+        selector.clone()
+    }
+}
+
+/// CSS selectors as written, so messages about `&str` targets read as before;
+/// the other variants as `kind 'value'`
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Css(selector) => f.write_str(selector),
+            Self::XPath(xpath) => write!(f, "xpath '{}'", xpath),
+            Self::TestId(test_id) => write!(f, "test_id '{}'", test_id),
+            Self::Text(text) => write!(f, "text '{}'", text),
+            Self::Role { role, name: None } => write!(f, "role '{}'", role),
+            Self::Role {
+                role,
+                name: Some(name),
+            } => write!(f, "role '{}' named '{}'", role, name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn css_is_queried_as_written() {
+        let selector = Selector::from("#submit > span");
+        assert_eq!(selector, Selector::css("#submit > span"));
+        assert_eq!(selector.as_css().as_deref(), Some("#submit > span"));
+        assert_eq!(
+            selector.element_expression(),
+            r##"document.querySelector("#submit > span")"##
+        );
+        assert_eq!(
+            selector.elements_expression(),
+            r##"Array.from(document.querySelectorAll("#submit > span"))"##
+        );
+        assert_eq!(selector.to_string(), "#submit > span");
+        assert_eq!(Selector::from(String::from("a")), Selector::css("a"));
+    }
+
+    #[test]
+    fn xpath_is_evaluated_and_keeps_elements_only() {
+        let selector = Selector::xpath("//button[@name='archive']");
+        assert_eq!(selector.as_css(), None);
+        assert_eq!(
+            selector.element_expression(),
+            first_node_expression("//button[@name='archive']")
+        );
+        let all = selector.elements_expression();
+        assert!(all.contains(r#"document.evaluate("//button[@name='archive']""#));
+        assert!(all.contains("node.nodeType === Node.ELEMENT_NODE"));
+        assert_eq!(selector.to_string(), "xpath '//button[@name='archive']'");
+    }
+
+    #[test]
+    fn test_id_becomes_a_quoted_attribute_selector() {
+        let selector = Selector::test_id(r#"save "draft""#);
+        assert_eq!(
+            selector.as_css().as_deref(),
+            Some(r#"[data-testid="save \"draft\""]"#)
+        );
+        assert!(selector
+            .element_expression()
+            .starts_with("document.querySelector("));
+        assert_eq!(
+            Selector::from_type("save", &SelectorType::TestId),
+            Selector::test_id("save")
+        );
+    }
+
+    #[test]
+    fn text_folds_only_ascii_like_xpath() {
+        assert_eq!(
+            normalized("  Forgot   your\nPASSWORD? "),
+            "forgot your password?"
+        );
+        // XPath's translate leaves the Ü alone, so neither side may fold it
+        assert_eq!(normalized("Über  UNS"), "Über uns");
+
+        let xpath = text_xpath(&normalized("Über uns"));
+        assert!(xpath.contains("contains(translate(normalize-space(.), 'ABCDEFGHIJKLMNOPQRSTUVWXYZ', 'abcdefghijklmnopqrstuvwxyz'), 'Über uns')"));
+        assert!(xpath.contains("//input[@type='submit' or @type='button' or @type='reset']"));
+        assert!(xpath.contains("[not(*["));
+
+        let expression = Selector::text("Über uns").elements_expression();
+        assert!(expression.contains("return byText("));
+        assert!(expression.contains(r#", "Über uns");"#));
+        assert!(expression.contains("normalize(own(el)) === normalize(wanted)"));
+        assert!(Selector::text("Delete")
+            .element_expression()
+            .ends_with("[0] || null)"));
+    }
+
+    #[test]
+    fn text_with_quotes_stays_one_literal() {
+        let xpath = text_xpath(&normalized(r#"It's "here""#));
+        assert!(xpath.contains(&xpath_literal(r#"it's "here""#)));
+    }
+
+    #[test]
+    fn role_passes_role_and_name_to_the_resolver() {
+        let named = Selector::role_named(" Button ", "Close   Dialog");
+        let expression = named.elements_expression();
+        assert!(expression.contains(r#"return byRole("button", "close dialog");"#));
+        assert_eq!(named.to_string(), "role ' Button ' named 'Close   Dialog'");
+
+        let any = Selector::role("heading");
+        assert!(any
+            .elements_expression()
+            .contains(r#"return byRole("heading", null);"#));
+        assert_eq!(any.as_css(), None);
+        assert_eq!(any.to_string(), "role 'heading'");
+    }
+
+    #[test]
+    fn serializes_by_variant_name() {
+        let selector: Selector =
+            serde_json::from_str(r#"{"role": {"role": "link", "name": "Home"}}"#).unwrap();
+        assert_eq!(selector, Selector::role_named("link", "Home"));
+        assert_eq!(
+            serde_json::to_string(&Selector::test_id("x")).unwrap(),
+            r#"{"test_id":"x"}"#
+        );
+    }
+}
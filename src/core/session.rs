@@ -1,4 +1,4 @@
-use crate::core::{BrowserTrait, Config};
+use crate::core::{BrowserTrait, Config, Selector};
 use crate::dom::DomState;
 use crate::errors::Result;
 use async_trait::async_trait;
@@ -13,9 +13,10 @@ pub trait SessionTrait<B: BrowserTrait>: Send + Sync {
 
     async fn get_page_state(&self, include_screenshot: bool) -> Result<DomState>;
 
-    async fn click(&self, selector: &str) -> Result<()>;
+    /// Click the first element `selector` matches; a `&str` is read as CSS
+    async fn click<S: Into<Selector> + Send>(&self, selector: S) -> Result<()>;
 
-    async fn type_text(&self, selector: &str, text: &str) -> Result<()>;
+    async fn type_text<S: Into<Selector> + Send>(&self, selector: S, text: &str) -> Result<()>;
 
     async fn execute_script(&self, script: &str) -> Result<serde_json::Value>;
 
@@ -0,0 +1,302 @@
+use crate::core::policy::DomainPattern;
+use crate::errors::{BrowserAgentError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Extraction tweaks for the sites whose host matches `domains`.
+///
+/// Profiles live in `DomConfig::site_profiles`, or in files of their own read with
+/// [`SiteProfile::load`]. Every profile matching a page applies, in order: lists are
+/// combined and later profiles override the single-valued settings of earlier ones.
+///
+/// ```toml
+/// [[dom.site_profiles]]
+/// domains = ["shop.example.com"]
+/// excluded_selectors = ["#newsletter", ".recommendations"]
+/// root = "main"
+///
+/// [dom.site_profiles.labels]
+/// "div.qty-stepper" = "quantity picker"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SiteProfile {
+    /// Host globs as in `allowed_domains`, e.g. `*.example.com`
+    pub domains: Vec<String>,
+    /// Elements extracted as interactive on top of the built-in selectors, for
+    /// widgets built from plain elements
+    #[serde(default)]
+    pub extra_selectors: Vec<String>,
+    /// Elements left out of extraction together with everything inside them
+    #[serde(default)]
+    pub excluded_selectors: Vec<String>,
+    /// Only extract inside the first element matching this selector
+    #[serde(default)]
+    pub root: Option<String>,
+    /// AI labels by selector, replacing the generated ones
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// What `BrowserSession::handle_consent` does on these sites
+    #[serde(default)]
+    pub consent: Option<ConsentChoice>,
+}
+
+/// How consent and cookie notices are handled on a site
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentChoice {
+    /// Detect notices and close them through their own controls
+    #[default]
+    Dismiss,
+    /// Click this selector, e.g. the site's "reject all" button
+    Click(String),
+    /// Hide detected notices without involving the page's code
+    Hide,
+    /// Leave notices alone
+    Ignore,
+}
+
+/// Shape of a file read by `SiteProfile::load`
+#[derive(Deserialize)]
+struct ProfileFile {
+    #[serde(default)]
+    profiles: Vec<SiteProfile>,
+}
+
+impl SiteProfile {
+    pub fn new(domains: &[&str]) -> Self {
+        Self {
+            domains: domains.iter().map(|domain| domain.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Profiles from a `.toml` or `.json` file listing them under `profiles`
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<SiteProfile>> {
+        let path = path.as_ref();
+        let value = crate::core::config::read_config_file(path)?;
+        let file: ProfileFile = serde_json::from_value(value).map_err(|e| {
+            BrowserAgentError::ConfigurationError(format!("{}: {}", path.display(), e))
+        })?;
+        Ok(file.profiles)
+    }
+
+    /// Whether a page at `url` gets this profile. URLs without a host match nothing.
+    pub fn matches(&self, url: &str) -> bool {
+        self.domains.iter().any(|domain| {
+            DomainPattern::parse(domain)
+                .map(|pattern| pattern.matches_url(url))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Apply `other` on top of this profile: its selectors and domains are added,
+    /// and its root, labels and consent choice take precedence
+    pub fn merge(&mut self, other: &SiteProfile) {
+        let lists = [
+            (&mut self.domains, &other.domains),
+            (&mut self.extra_selectors, &other.extra_selectors),
+            (&mut self.excluded_selectors, &other.excluded_selectors),
+        ];
+        for (mine, theirs) in lists {
+            for item in theirs {
+                if !mine.contains(item) {
+                    mine.push(item.clone());
+                }
+            }
+        }
+        if other.root.is_some() {
+            self.root = other.root.clone();
+        }
+        self.labels.extend(other.labels.clone());
+        if other.consent.is_some() {
+            self.consent = other.consent.clone();
+        }
+    }
+
+    /// Every profile in `profiles` matching `url`, merged in order; `None` when
+    /// none matches
+    pub fn resolve(profiles: &[SiteProfile], url: &str) -> Option<SiteProfile> {
+        profiles.iter().filter(|profile| profile.matches(url)).fold(
+            None,
+            |merged: Option<SiteProfile>, profile| {
+                let mut merged = merged.unwrap_or_default();
+                merged.merge(profile);
+                Some(merged)
+            },
+        )
+    }
+
+    /// Problems with the domain globs and selectors, for `Config::validate`
+    pub(crate) fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.domains.is_empty() {
+            problems.push("site_profiles: a profile without domains never applies".to_string());
+        }
+        for domain in &self.domains {
+            if DomainPattern::parse(domain).is_err() {
+                problems.push(format!("site_profiles: invalid domain '{}'", domain));
+            }
+        }
+        let selectors = self
+            .extra_selectors
+            .iter()
+            .chain(&self.excluded_selectors)
+            .chain(&self.root)
+            .chain(self.labels.keys());
+        for selector in selectors {
+            if scraper::Selector::parse(selector).is_err() {
+                problems.push(format!("site_profiles: invalid selector '{}'", selector));
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(domains: &[&str], excluded: &[&str], root: Option<&str>) -> SiteProfile {
+        SiteProfile {
+            excluded_selectors: excluded.iter().map(|s| s.to_string()).collect(),
+            root: root.map(str::to_string),
+            ..SiteProfile::new(domains)
+        }
+    }
+
+    #[test]
+    fn matches_by_host_glob() {
+        let shop = SiteProfile::new(&["shop.example.com", "*.shop.example.net"]);
+        for url in [
+            "https://shop.example.com/cart",
+            "http://SHOP.example.com./",
+            "https://eu.shop.example.net/",
+        ] {
+            assert!(shop.matches(url), "{}", url);
+        }
+        for url in [
+            "https://example.com/",
+            "https://www.shop.example.com/",
+            "https://shop.example.net/",
+            "https://shop.example.com.evil.test/",
+            "about:blank",
+            "data:text/html,shop.example.com",
+            "not a url",
+        ] {
+            assert!(!shop.matches(url), "{}", url);
+        }
+    }
+
+    #[test]
+    fn ports_and_invalid_globs() {
+        let local = SiteProfile::new(&["localhost:8080", "bad:port"]);
+        assert!(local.matches("http://localhost:8080/"));
+        assert!(!local.matches("http://localhost:9090/"));
+        assert!(!SiteProfile::new(&[]).matches("https://example.com/"));
+    }
+
+    #[test]
+    fn merge_combines_lists_and_later_settings_win() {
+        let mut merged = profile(&["*.example.com"], &["#newsletter", ".ads"], Some("main"));
+        merged
+            .labels
+            .insert("div.qty".to_string(), "quantity".to_string());
+        merged.consent = Some(ConsentChoice::Hide);
+
+        let mut later = profile(&["shop.example.com"], &[".ads", "#chat"], None);
+        later
+            .labels
+            .insert("div.qty".to_string(), "quantity picker".to_string());
+        later
+            .labels
+            .insert("div.size".to_string(), "size picker".to_string());
+        later.extra_selectors.push("div[data-action]".to_string());
+        merged.merge(&later);
+
+        assert_eq!(merged.domains, ["*.example.com", "shop.example.com"]);
+        assert_eq!(merged.excluded_selectors, ["#newsletter", ".ads", "#chat"]);
+        assert_eq!(merged.extra_selectors, ["div[data-action]"]);
+        // Unset settings leave the earlier ones in place
+        assert_eq!(merged.root.as_deref(), Some("main"));
+        assert_eq!(merged.consent, Some(ConsentChoice::Hide));
+        assert_eq!(merged.labels["div.qty"], "quantity picker");
+        assert_eq!(merged.labels["div.size"], "size picker");
+
+        let mut app = profile(&["shop.example.com"], &[], Some("#app"));
+        app.consent = Some(ConsentChoice::Click("#reject-all".to_string()));
+        merged.merge(&app);
+        assert_eq!(merged.root.as_deref(), Some("#app"));
+        assert_eq!(
+            merged.consent,
+            Some(ConsentChoice::Click("#reject-all".to_string()))
+        );
+    }
+
+    #[test]
+    fn resolve_merges_matching_profiles_in_order() {
+        let profiles = [
+            profile(&["*.example.com"], &["#newsletter"], Some("main")),
+            profile(&["other.test"], &["#other"], Some("#other-root")),
+            profile(&["shop.example.com"], &["#chat"], Some("#app")),
+        ];
+
+        let shop = SiteProfile::resolve(&profiles, "https://shop.example.com/cart").unwrap();
+        assert_eq!(shop.domains, ["*.example.com", "shop.example.com"]);
+        assert_eq!(shop.excluded_selectors, ["#newsletter", "#chat"]);
+        assert_eq!(shop.root.as_deref(), Some("#app"));
+
+        let blog = SiteProfile::resolve(&profiles, "https://blog.example.com/").unwrap();
+        assert_eq!(blog, profiles[0]);
+
+        assert_eq!(
+            SiteProfile::resolve(&profiles, "https://example.org/"),
+            None
+        );
+        assert_eq!(SiteProfile::resolve(&[], "https://shop.example.com/"), None);
+    }
+
+    #[test]
+    fn problems_name_bad_domains_and_selectors() {
+        let mut bad = profile(&["shop.example.com:http"], &["div[", "#ok"], Some(">>"));
+        bad.labels.insert("p:nope".to_string(), "label".to_string());
+        assert_eq!(
+            bad.problems(),
+            [
+                "site_profiles: invalid domain 'shop.example.com:http'",
+                "site_profiles: invalid selector 'div['",
+                "site_profiles: invalid selector '>>'",
+                "site_profiles: invalid selector 'p:nope'",
+            ]
+        );
+        assert_eq!(
+            SiteProfile::default().problems(),
+            ["site_profiles: a profile without domains never applies"]
+        );
+        assert!(profile(&["*.example.com"], &["#ad"], Some("main"))
+            .problems()
+            .is_empty());
+    }
+
+    #[test]
+    fn load_reads_profiles_from_toml() {
+        let path = std::env::temp_dir().join(format!(
+            "surfai-profiles-{}.toml",
+            uuid::Uuid::new_v4().simple()
+        ));
+        std::fs::write(
+            &path,
+            "[[profiles]]\ndomains = [\"shop.example.com\"]\nroot = \"main\"\nconsent = { click = \"#reject\" }\n\n[profiles.labels]\n\"div.qty\" = \"quantity picker\"\n",
+        )
+        .unwrap();
+        let profiles = SiteProfile::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut expected = profile(&["shop.example.com"], &[], Some("main"));
+        expected.consent = Some(ConsentChoice::Click("#reject".to_string()));
+        expected
+            .labels
+            .insert("div.qty".to_string(), "quantity picker".to_string());
+        assert_eq!(profiles, [expected]);
+    }
+}
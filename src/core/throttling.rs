@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+
+/// Latency and throughput applied to a tab's requests
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConditions {
+    /// Fail every request as if the connection dropped
+    pub offline: bool,
+    /// Added round-trip time per request
+    pub latency_ms: f64,
+    /// Bytes per second; 0 leaves it unthrottled
+    pub download_bytes_per_sec: f64,
+    /// Bytes per second; 0 leaves it unthrottled
+    pub upload_bytes_per_sec: f64,
+}
+
+impl NetworkConditions {
+    /// `latency_ms` round trips with throughput in kilobits per second, the unit
+    /// network profiles are usually quoted in
+    pub fn new(latency_ms: f64, download_kbps: f64, upload_kbps: f64) -> Self {
+        Self {
+            offline: false,
+            latency_ms,
+            download_bytes_per_sec: download_kbps * 1000.0 / 8.0,
+            upload_bytes_per_sec: upload_kbps * 1000.0 / 8.0,
+        }
+    }
+}
+
+/// A connection to emulate with `BrowserSession::emulate_network`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetworkProfile {
+    Offline,
+    /// Chrome DevTools' "Slow 3G": 2s round trips, 400 kbit/s both ways
+    #[serde(rename = "slow-3g", alias = "slow3g")]
+    Slow3g,
+    /// Chrome DevTools' "Fast 3G": 563ms round trips, 1.44 Mbit/s down, 675 kbit/s up
+    #[serde(rename = "fast-3g", alias = "fast3g")]
+    Fast3g,
+    Custom(NetworkConditions),
+}
+
+impl NetworkProfile {
+    pub fn conditions(&self) -> NetworkConditions {
+        match self {
+            NetworkProfile::Offline => NetworkConditions {
+                offline: true,
+                ..NetworkConditions::new(0.0, 0.0, 0.0)
+            },
+            NetworkProfile::Slow3g => NetworkConditions::new(2000.0, 400.0, 400.0),
+            NetworkProfile::Fast3g => NetworkConditions::new(562.5, 1440.0, 675.0),
+            NetworkProfile::Custom(conditions) => *conditions,
+        }
+    }
+}
+
+impl std::fmt::Display for NetworkProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkProfile::Offline => f.write_str("offline"),
+            NetworkProfile::Slow3g => f.write_str("slow-3g"),
+            NetworkProfile::Fast3g => f.write_str("fast-3g"),
+            NetworkProfile::Custom(conditions) => write!(
+                f,
+                "{}ms, {:.0} kbit/s down, {:.0} kbit/s up",
+                conditions.latency_ms,
+                conditions.download_bytes_per_sec * 8.0 / 1000.0,
+                conditions.upload_bytes_per_sec * 8.0 / 1000.0
+            ),
+        }
+    }
+}
+
+impl std::str::FromStr for NetworkProfile {
+    type Err = String;
+
+    fn from_str(name: &str) -> std::result::Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "offline" => Ok(NetworkProfile::Offline),
+            "slow-3g" | "slow3g" => Ok(NetworkProfile::Slow3g),
+            "fast-3g" | "fast3g" => Ok(NetworkProfile::Fast3g),
+            other => Err(format!(
+                "unknown network profile '{}' (expected offline, slow-3g or fast-3g)",
+                other
+            )),
+        }
+    }
+}
+
+/// Network and CPU throttling in effect for the session's tab
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Throttling {
+    pub network: Option<NetworkProfile>,
+    /// How many times slower the CPU runs, e.g. 4.0; `None` for full speed
+    pub cpu_slowdown: Option<f64>,
+}
+
+impl Throttling {
+    pub fn is_active(&self) -> bool {
+        self.network.is_some() || self.cpu_slowdown.is_some()
+    }
+}
+
+impl std::fmt::Display for Throttling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.network, self.cpu_slowdown) {
+            (None, None) => f.write_str("unthrottled"),
+            (Some(network), None) => write!(f, "{}", network),
+            (None, Some(slowdown)) => write!(f, "{}x CPU slowdown", slowdown),
+            (Some(network), Some(slowdown)) => {
+                write!(f, "{}, {}x CPU slowdown", network, slowdown)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_convert_kilobits_to_bytes() {
+        let slow = NetworkProfile::Slow3g.conditions();
+        assert!(!slow.offline);
+        assert_eq!(slow.latency_ms, 2000.0);
+        assert_eq!(slow.download_bytes_per_sec, 50_000.0);
+        assert_eq!(slow.upload_bytes_per_sec, 50_000.0);
+
+        let fast = NetworkProfile::Fast3g.conditions();
+        assert_eq!(fast.latency_ms, 562.5);
+        assert_eq!(fast.download_bytes_per_sec, 180_000.0);
+        assert_eq!(fast.upload_bytes_per_sec, 84_375.0);
+    }
+
+    #[test]
+    fn offline_only_sets_the_flag() {
+        let offline = NetworkProfile::Offline.conditions();
+        assert!(offline.offline);
+        assert_eq!(offline.latency_ms, 0.0);
+        assert_eq!(offline.download_bytes_per_sec, 0.0);
+    }
+
+    #[test]
+    fn custom_profiles_keep_their_conditions() {
+        let conditions = NetworkConditions::new(150.0, 8000.0, 2000.0);
+        assert_eq!(NetworkProfile::Custom(conditions).conditions(), conditions);
+        assert_eq!(
+            NetworkProfile::Custom(conditions).to_string(),
+            "150ms, 8000 kbit/s down, 2000 kbit/s up"
+        );
+    }
+
+    #[test]
+    fn profile_names_round_trip() {
+        for profile in [
+            NetworkProfile::Offline,
+            NetworkProfile::Slow3g,
+            NetworkProfile::Fast3g,
+        ] {
+            assert_eq!(profile.to_string().parse::<NetworkProfile>(), Ok(profile));
+        }
+        assert_eq!("Slow3G".parse(), Ok(NetworkProfile::Slow3g));
+        assert_eq!("fast3g".parse(), Ok(NetworkProfile::Fast3g));
+        let err = "4g".parse::<NetworkProfile>().unwrap_err();
+        assert!(err.contains("'4g'"), "{}", err);
+    }
+
+    #[test]
+    fn serialized_names_match_the_display_names() {
+        for profile in [NetworkProfile::Slow3g, NetworkProfile::Fast3g] {
+            let name = serde_json::to_value(profile).unwrap();
+            assert_eq!(name, profile.to_string());
+            assert_eq!(
+                serde_json::from_value::<NetworkProfile>(name).unwrap(),
+                profile
+            );
+        }
+        assert_eq!(
+            serde_json::from_value::<NetworkProfile>("slow3g".into()).unwrap(),
+            NetworkProfile::Slow3g
+        );
+        let custom: NetworkProfile = serde_json::from_value(serde_json::json!({
+            "custom": {
+                "offline": false,
+                "latency_ms": 80.0,
+                "download_bytes_per_sec": 1000.0,
+                "upload_bytes_per_sec": 500.0
+            }
+        }))
+        .unwrap();
+        assert_eq!(custom.conditions().download_bytes_per_sec, 1000.0);
+    }
+
+    #[test]
+    fn throttling_describes_what_is_active() {
+        let none = Throttling::default();
+        assert!(!none.is_active());
+        assert_eq!(none.to_string(), "unthrottled");
+
+        let cpu = Throttling {
+            network: None,
+            cpu_slowdown: Some(4.0),
+        };
+        assert!(cpu.is_active());
+        assert_eq!(cpu.to_string(), "4x CPU slowdown");
+
+        let both = Throttling {
+            network: Some(NetworkProfile::Slow3g),
+            cpu_slowdown: Some(4.0),
+        };
+        assert!(both.is_active());
+        assert_eq!(both.to_string(), "slow-3g, 4x CPU slowdown");
+    }
+}
@@ -0,0 +1,295 @@
+use crate::dom::PageReport;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Placeholder brand Chrome adds to `Sec-CH-UA` so sites do not rely on the list
+const GREASE_BRAND: &str = "Not.A/Brand";
+
+/// A brand and version in `Sec-CH-UA` and `navigator.userAgentData.brands`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserAgentBrand {
+    pub brand: String,
+    pub version: String,
+}
+
+impl UserAgentBrand {
+    pub fn new(brand: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            brand: brand.into(),
+            version: version.into(),
+        }
+    }
+}
+
+/// User-Agent Client Hints reported with an overridden user agent, as the
+/// `Sec-CH-UA-*` headers and `navigator.userAgentData`.
+///
+/// Without them Chrome drops the client hints for the override, which sites
+/// comparing hints with the user agent string can notice.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserAgentMetadata {
+    /// Brands with major versions
+    pub brands: Vec<UserAgentBrand>,
+    /// Brands with full versions
+    pub full_version_list: Vec<UserAgentBrand>,
+    /// e.g. `Windows`, `macOS`, `Android`
+    pub platform: String,
+    pub platform_version: String,
+    pub architecture: String,
+    /// Device model on Android, empty elsewhere
+    pub model: String,
+    pub mobile: bool,
+    pub bitness: String,
+}
+
+impl UserAgentMetadata {
+    /// Hints matching a Chrome, Edge or Chrome-based crawler user agent string;
+    /// `None` when it names no Chrome version
+    pub fn from_chrome_user_agent(user_agent: &str) -> Option<Self> {
+        let chrome_version = product_version(user_agent, "Chrome/")?;
+        let mut full_versions = vec![UserAgentBrand::new("Chromium", chrome_version)];
+        if let Some(edge_version) = product_version(user_agent, "Edg/") {
+            full_versions.push(UserAgentBrand::new("Microsoft Edge", edge_version));
+        } else if !user_agent.contains("bot") {
+            full_versions.push(UserAgentBrand::new("Google Chrome", chrome_version));
+        }
+        full_versions.push(UserAgentBrand::new(GREASE_BRAND, "8.0.0.0"));
+        let brands = full_versions
+            .iter()
+            .map(|brand| {
+                let major = brand.version.split('.').next().unwrap_or_default();
+                UserAgentBrand::new(brand.brand.clone(), major)
+            })
+            .collect();
+
+        let system = system_info(user_agent);
+        let android = user_agent.contains("Android");
+        let (platform, platform_version) = if android {
+            (
+                "Android",
+                system_token(system, "Android ").unwrap_or_default(),
+            )
+        } else if system.contains("Windows") {
+            ("Windows", "10.0.0".to_string())
+        } else if system.contains("Mac OS X") {
+            (
+                "macOS",
+                system_token(system, "Mac OS X ")
+                    .unwrap_or_default()
+                    .replace('_', "."),
+            )
+        } else if system.contains("CrOS") {
+            ("Chrome OS", String::new())
+        } else {
+            ("Linux", String::new())
+        };
+        let model = if android {
+            system
+                .split(';')
+                .map(str::trim)
+                .find(|part| part.contains(" Build/"))
+                .and_then(|part| part.split(" Build/").next())
+                .unwrap_or_default()
+                .to_string()
+        } else {
+            String::new()
+        };
+
+        Some(Self {
+            brands,
+            full_version_list: full_versions,
+            platform: platform.to_string(),
+            platform_version,
+            architecture: if android { "" } else { "x86" }.to_string(),
+            model,
+            mobile: user_agent.contains("Mobile"),
+            bitness: if android { "" } else { "64" }.to_string(),
+        })
+    }
+}
+
+/// Version following `product` in a user agent, e.g. `124.0.6367.91` for `Chrome/`
+fn product_version<'a>(user_agent: &'a str, product: &str) -> Option<&'a str> {
+    let start = user_agent.find(product)? + product.len();
+    let version = user_agent[start..]
+        .split(|c: char| c.is_whitespace() || c == ')' || c == ';')
+        .next()
+        .filter(|version| version.contains('.'))?;
+    Some(version)
+}
+
+/// The first parenthesized part of a user agent, e.g. `Windows NT 10.0; Win64; x64`
+fn system_info(user_agent: &str) -> &str {
+    user_agent
+        .split_once('(')
+        .and_then(|(_, rest)| rest.split_once(')'))
+        .map(|(system, _)| system)
+        .unwrap_or_default()
+}
+
+/// Word following `prefix` in the system part, e.g. `6.0.1` for `Android `
+fn system_token(system: &str, prefix: &str) -> Option<String> {
+    let start = system.find(prefix)? + prefix.len();
+    system[start..]
+        .split(|c: char| c == ';' || c == ')' || c.is_whitespace())
+        .next()
+        .map(str::to_string)
+}
+
+/// User agent reported by one tab instead of the browser's own
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct UserAgentOverride {
+    pub user_agent: String,
+    /// Sent as `Accept-Language` and reported by `navigator.languages`
+    pub accept_language: Option<String>,
+    /// `navigator.platform`, e.g. `Win32`
+    pub platform: Option<String>,
+    /// Client hints; `None` sends none
+    pub metadata: Option<UserAgentMetadata>,
+}
+
+impl UserAgentOverride {
+    /// `user_agent` with the client hints and `navigator.platform` it implies
+    pub fn from_user_agent(user_agent: impl Into<String>) -> Self {
+        let user_agent = user_agent.into();
+        let system = system_info(&user_agent);
+        let platform = if user_agent.contains("Android") {
+            Some("Linux armv8l")
+        } else if system.contains("Windows") {
+            Some("Win32")
+        } else if system.contains("Macintosh") {
+            Some("MacIntel")
+        } else if system.contains("Linux") || system.contains("CrOS") {
+            Some("Linux x86_64")
+        } else {
+            None
+        };
+        Self {
+            metadata: UserAgentMetadata::from_chrome_user_agent(&user_agent),
+            platform: platform.map(str::to_string),
+            accept_language: None,
+            user_agent,
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: Option<UserAgentMetadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn with_accept_language(mut self, accept_language: impl Into<String>) -> Self {
+        self.accept_language = Some(accept_language.into());
+        self
+    }
+}
+
+/// Split custom headers into the user agent override to apply and the headers to
+/// add to requests.
+///
+/// An explicit override wins over a `User-Agent` header; without one the header
+/// becomes the override, so `navigator.userAgent` and the client hints agree with
+/// what is sent. `Accept-Language` is left to the override when it sets one.
+pub(crate) fn split_headers(
+    headers: &HashMap<String, String>,
+    explicit: Option<&UserAgentOverride>,
+) -> (Option<UserAgentOverride>, HashMap<String, String>) {
+    let mut extra = HashMap::new();
+    let mut from_header = None;
+    for (name, value) in headers {
+        if name.eq_ignore_ascii_case("user-agent") {
+            from_header = Some(value.clone());
+        } else {
+            extra.insert(name.clone(), value.clone());
+        }
+    }
+
+    let user_agent = match (explicit, from_header) {
+        (Some(explicit), Some(header)) => {
+            if header != explicit.user_agent {
+                tracing::warn!(
+                    header = %header,
+                    "User-Agent header ignored in favour of the user agent override"
+                );
+            }
+            Some(explicit.clone())
+        }
+        (Some(explicit), None) => Some(explicit.clone()),
+        (None, Some(header)) => Some(UserAgentOverride::from_user_agent(header)),
+        (None, None) => None,
+    };
+    if user_agent
+        .as_ref()
+        .is_some_and(|user_agent| user_agent.accept_language.is_some())
+    {
+        extra.retain(|name, _| !name.eq_ignore_ascii_case("accept-language"));
+    }
+    (user_agent, extra)
+}
+
+/// A named user agent to load a page as with `BrowserSession::compare_as`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserAgentProfile {
+    pub name: String,
+    /// `None` keeps the browser's own user agent
+    pub user_agent: Option<UserAgentOverride>,
+}
+
+impl UserAgentProfile {
+    pub fn new(name: impl Into<String>, user_agent: UserAgentOverride) -> Self {
+        Self {
+            name: name.into(),
+            user_agent: Some(user_agent),
+        }
+    }
+
+    /// `user_agent` with the client hints it implies
+    pub fn from_user_agent(name: impl Into<String>, user_agent: impl Into<String>) -> Self {
+        Self::new(name, UserAgentOverride::from_user_agent(user_agent))
+    }
+
+    /// The browser as launched
+    pub fn browser_default() -> Self {
+        Self {
+            name: "browser".to_string(),
+            user_agent: None,
+        }
+    }
+
+    /// Googlebot's desktop crawler rendering with Chrome `chrome_version`, e.g.
+    /// `124.0.6367.91`
+    pub fn googlebot_desktop(chrome_version: &str) -> Self {
+        Self::from_user_agent(
+            "googlebot-desktop",
+            format!(
+                "Mozilla/5.0 AppleWebKit/537.36 (KHTML, like Gecko; compatible; Googlebot/2.1; \
+                 +http://www.google.com/bot.html) Chrome/{} Safari/537.36",
+                chrome_version
+            ),
+        )
+    }
+
+    /// Googlebot's smartphone crawler rendering with Chrome `chrome_version`
+    pub fn googlebot_smartphone(chrome_version: &str) -> Self {
+        Self::from_user_agent(
+            "googlebot-smartphone",
+            format!(
+                "Mozilla/5.0 (Linux; Android 6.0.1; Nexus 5X Build/MMB29P) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/{} Mobile Safari/537.36 (compatible; Googlebot/2.1; \
+                 +http://www.google.com/bot.html)",
+                chrome_version
+            ),
+        )
+    }
+}
+
+/// One profile's load of the page compared by `BrowserSession::compare_as`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserAgentRun {
+    pub profile: String,
+    /// `navigator.userAgent` as the page saw it
+    pub user_agent: String,
+    /// Where the page ended up, which may differ per user agent
+    pub final_url: String,
+    pub report: PageReport,
+}
@@ -0,0 +1,479 @@
+use crate::dom::xpath::{element_from_value, DESCRIBE_ELEMENT_JS};
+use crate::dom::DomElement;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessibilityRule {
+    ImageAlt,
+    ControlName,
+    FormLabel,
+    ColorContrast,
+    DuplicateId,
+    DocumentLang,
+}
+
+impl AccessibilityRule {
+    pub fn description(&self) -> &'static str {
+        match self {
+            AccessibilityRule::ImageAlt => "Images must have alternative text",
+            AccessibilityRule::ControlName => "Buttons and links must have an accessible name",
+            AccessibilityRule::FormLabel => "Form fields must have a label",
+            AccessibilityRule::ColorContrast => "Text must meet WCAG AA contrast",
+            AccessibilityRule::DuplicateId => "Element ids must be unique",
+            AccessibilityRule::DocumentLang => "The html element must have a lang attribute",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Minor,
+    Serious,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessibilityIssue {
+    pub rule: AccessibilityRule,
+    pub severity: Severity,
+    pub message: String,
+    /// Offending element; `None` for document-level rules
+    pub element: Option<DomElement>,
+}
+
+/// Result of `BrowserSession::accessibility_audit`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditReport {
+    pub url: String,
+    pub elements_checked: usize,
+    pub issues: Vec<AccessibilityIssue>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl AuditReport {
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn issues_for(&self, rule: AccessibilityRule) -> Vec<&AccessibilityIssue> {
+        self.issues.iter().filter(|i| i.rule == rule).collect()
+    }
+
+    pub fn count_at_least(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity >= severity)
+            .count()
+    }
+
+    /// Human-readable summary grouped by rule
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Accessibility audit: {}\n\n", self.url);
+        out.push_str(&format!(
+            "{} issue(s) across {} element(s) checked ({} critical, {} serious, {} minor).\n",
+            self.issues.len(),
+            self.elements_checked,
+            self.issues
+                .iter()
+                .filter(|i| i.severity == Severity::Critical)
+                .count(),
+            self.issues
+                .iter()
+                .filter(|i| i.severity == Severity::Serious)
+                .count(),
+            self.issues
+                .iter()
+                .filter(|i| i.severity == Severity::Minor)
+                .count(),
+        ));
+
+        let rules = [
+            AccessibilityRule::ImageAlt,
+            AccessibilityRule::ControlName,
+            AccessibilityRule::FormLabel,
+            AccessibilityRule::ColorContrast,
+            AccessibilityRule::DuplicateId,
+            AccessibilityRule::DocumentLang,
+        ];
+        for rule in rules {
+            let issues = self.issues_for(rule);
+            if issues.is_empty() {
+                continue;
+            }
+            out.push_str(&format!(
+                "\n## {} ({})\n\n",
+                rule.description(),
+                issues.len()
+            ));
+            for issue in issues {
+                let location = issue
+                    .element
+                    .as_ref()
+                    .map(|e| format!(" `{}`", e.css_selector))
+                    .unwrap_or_default();
+                out.push_str(&format!(
+                    "- **{:?}**{}: {}\n",
+                    issue.severity, location, issue.message
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// WCAG AA minimum contrast for normal text
+const AA_CONTRAST: f64 = 4.5;
+/// WCAG AA minimum contrast for large text
+const AA_LARGE_TEXT_CONTRAST: f64 = 3.0;
+/// Text from 18pt, in CSS pixels, is large
+const LARGE_TEXT_PX: f64 = 24.0;
+/// Bold text from 14pt, in CSS pixels, is large
+const LARGE_BOLD_TEXT_PX: f64 = 18.66;
+const BOLD_WEIGHT: u32 = 700;
+
+/// Contrast the `color_contrast` rule asks of text in this font
+pub fn required_contrast(font_size_px: f64, font_weight: u32) -> f64 {
+    let bold = font_weight >= BOLD_WEIGHT;
+    if font_size_px >= LARGE_TEXT_PX || (bold && font_size_px >= LARGE_BOLD_TEXT_PX) {
+        AA_LARGE_TEXT_CONTRAST
+    } else {
+        AA_CONTRAST
+    }
+}
+
+/// WCAG contrast ratio, 1 to 21, of RGBA `text` over opaque RGB `background`,
+/// channels 0-255 and alpha 0-1; the audit script computes the same in the page
+pub fn contrast_ratio(text: [f64; 4], background: [f64; 3]) -> f64 {
+    let [r, g, b, a] = text;
+    let blended = [
+        r * a + background[0] * (1.0 - a),
+        g * a + background[1] * (1.0 - a),
+        b * a + background[2] * (1.0 - a),
+    ];
+    let luminance = |[r, g, b]: [f64; 3]| {
+        let channel = |v: f64| {
+            let v = v / 255.0;
+            if v <= 0.03928 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+    };
+    let (l1, l2) = (luminance(blended), luminance(background));
+    (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+}
+
+/// Script collecting raw findings for every rule
+pub(crate) fn audit_script() -> String {
+    format!(
+        r#"
+        (function() {{
+{describe}
+            const LIMIT = 200;
+            const findings = [];
+            const report = (rule, severity, message, node) => {{
+                if (findings.filter(f => f.rule === rule).length >= LIMIT) return;
+                findings.push({{ rule, severity, message, element: node ? describeElement(node) : null }});
+            }};
+            const isHidden = (el) => {{
+                if (el.closest('[aria-hidden="true"], [hidden]')) return true;
+                const style = window.getComputedStyle(el);
+                return style.display === 'none' || style.visibility === 'hidden';
+            }};
+            const textOf = (id) => {{
+                const el = document.getElementById(id);
+                return el ? (el.textContent || '').trim() : '';
+            }};
+            const accessibleName = (el) => {{
+                const labelledBy = el.getAttribute('aria-labelledby');
+                if (labelledBy) {{
+                    const text = labelledBy.split(/\s+/).map(textOf).join(' ').trim();
+                    if (text) return text;
+                }}
+                const label = (el.getAttribute('aria-label') || '').trim();
+                if (label) return label;
+                const text = (el.innerText || el.textContent || '').trim();
+                if (text) return text;
+                for (const img of el.querySelectorAll('img[alt], svg[aria-label], [role="img"][aria-label]')) {{
+                    const alt = (img.getAttribute('alt') || img.getAttribute('aria-label') || '').trim();
+                    if (alt) return alt;
+                }}
+                if (el.tagName === 'INPUT' && ['button', 'submit', 'reset'].includes(el.type)) {{
+                    return (el.value || (el.type === 'submit' ? 'Submit' : el.type === 'reset' ? 'Reset' : '')).trim();
+                }}
+                return (el.getAttribute('title') || '').trim();
+            }};
+
+            let checked = 0;
+
+            // Images
+            for (const img of document.querySelectorAll('img, input[type="image"], [role="img"]')) {{
+                if (isHidden(img)) continue;
+                checked++;
+                const role = img.getAttribute('role');
+                if (role === 'presentation' || role === 'none') continue;
+                const hasAlt = img.hasAttribute('alt') || img.getAttribute('aria-label') || img.getAttribute('aria-labelledby');
+                if (!hasAlt) {{
+                    report('image_alt', 'critical', 'image has no alt text (use alt="" if decorative)', img);
+                }}
+            }}
+
+            // Buttons and links
+            for (const el of document.querySelectorAll('button, a[href], [role="button"], [role="link"], input[type="button"], input[type="submit"], input[type="reset"]')) {{
+                if (isHidden(el)) continue;
+                checked++;
+                if (!accessibleName(el)) {{
+                    const kind = el.tagName === 'A' || el.getAttribute('role') === 'link' ? 'link' : 'button';
+                    report('control_name', 'critical', kind + ' has no accessible name', el);
+                }}
+            }}
+
+            // Form fields
+            const skipTypes = ['hidden', 'submit', 'button', 'reset', 'image'];
+            for (const field of document.querySelectorAll('input, select, textarea')) {{
+                if (skipTypes.includes(field.type) || isHidden(field)) continue;
+                checked++;
+                const labelled = (field.labels && field.labels.length > 0 && Array.from(field.labels).some(l => l.textContent.trim()))
+                    || (field.getAttribute('aria-label') || '').trim()
+                    || field.getAttribute('aria-labelledby')
+                    || (field.getAttribute('title') || '').trim();
+                if (!labelled) {{
+                    if (field.getAttribute('placeholder')) {{
+                        report('form_label', 'serious', 'field is labelled only by its placeholder', field);
+                    }} else {{
+                        report('form_label', 'critical', 'field has no label', field);
+                    }}
+                }}
+            }}
+
+            // Contrast of elements that render their own text
+            const parseColor = (value) => {{
+                const m = value.match(/rgba?\(([^)]+)\)/);
+                if (!m) return null;
+                const parts = m[1].split(/[\s,\/]+/).filter(Boolean).map(parseFloat);
+                return {{ r: parts[0], g: parts[1], b: parts[2], a: parts.length > 3 ? parts[3] : 1 }};
+            }};
+            const blend = (top, bottom) => ({{
+                r: top.r * top.a + bottom.r * (1 - top.a),
+                g: top.g * top.a + bottom.g * (1 - top.a),
+                b: top.b * top.a + bottom.b * (1 - top.a),
+                a: 1
+            }});
+            const background = (el) => {{
+                const layers = [];
+                for (let node = el; node && node.nodeType === 1; node = node.parentElement) {{
+                    const style = window.getComputedStyle(node);
+                    // Background images make the backdrop unknowable from styles alone
+                    if (style.backgroundImage && style.backgroundImage !== 'none') return null;
+                    const color = parseColor(style.backgroundColor);
+                    if (color && color.a > 0) {{
+                        layers.push(color);
+                        if (color.a >= 1) break;
+                    }}
+                }}
+                let result = {{ r: 255, g: 255, b: 255, a: 1 }};
+                for (let i = layers.length - 1; i >= 0; i--) result = blend(layers[i], result);
+                return result;
+            }};
+            const luminance = (c) => {{
+                const channel = (v) => {{
+                    v /= 255;
+                    return v <= 0.03928 ? v / 12.92 : Math.pow((v + 0.055) / 1.055, 2.4);
+                }};
+                return 0.2126 * channel(c.r) + 0.7152 * channel(c.g) + 0.0722 * channel(c.b);
+            }};
+            for (const el of document.body.querySelectorAll('*')) {{
+                const ownText = Array.from(el.childNodes).some(n => n.nodeType === 3 && n.textContent.trim());
+                if (!ownText || isHidden(el) || ['SCRIPT', 'STYLE', 'NOSCRIPT'].includes(el.tagName)) continue;
+                const style = window.getComputedStyle(el);
+                const rect = el.getBoundingClientRect();
+                if (rect.width === 0 || rect.height === 0 || parseFloat(style.opacity) === 0) continue;
+                checked++;
+                const fg = parseColor(style.color);
+                const bg = background(el);
+                if (!fg || !bg) continue;
+                const text = blend(fg, bg);
+                const [l1, l2] = [luminance(text), luminance(bg)].sort((a, b) => b - a);
+                const ratio = (l1 + 0.05) / (l2 + 0.05);
+                const size = parseFloat(style.fontSize);
+                const bold = parseInt(style.fontWeight, 10) >= {bold_weight};
+                const large = size >= {large_px} || (bold && size >= {large_bold_px});
+                const required = large ? {large_contrast} : {contrast};
+                if (ratio < required) {{
+                    report('color_contrast', 'serious',
+                        'contrast ' + ratio.toFixed(2) + ':1 is below ' + required + ':1', el);
+                }}
+            }}
+
+            // Duplicate ids
+            const byId = new Map();
+            for (const el of document.querySelectorAll('[id]')) {{
+                if (!el.id) continue;
+                byId.set(el.id, (byId.get(el.id) || []).concat([el]));
+            }}
+            for (const [id, elements] of byId) {{
+                if (elements.length > 1) {{
+                    report('duplicate_id', 'minor', 'id "' + id + '" is used by ' + elements.length + ' elements', elements[1]);
+                }}
+            }}
+
+            // Document language
+            if (!(document.documentElement.getAttribute('lang') || '').trim()) {{
+                report('document_lang', 'serious', 'the html element has no lang attribute', null);
+            }}
+
+            return {{ url: location.href, checked: checked, findings: findings }};
+        }})()
+        "#,
+        describe = DESCRIBE_ELEMENT_JS,
+        bold_weight = BOLD_WEIGHT,
+        large_px = LARGE_TEXT_PX,
+        large_bold_px = LARGE_BOLD_TEXT_PX,
+        large_contrast = AA_LARGE_TEXT_CONTRAST,
+        contrast = AA_CONTRAST
+    )
+}
+
+fn field<T: serde::de::DeserializeOwned>(value: &Value, name: &str) -> Option<T> {
+    serde_json::from_value(value.get(name)?.clone()).ok()
+}
+
+pub(crate) fn report_from_value(value: &Value) -> AuditReport {
+    let issues = value
+        .get("findings")
+        .and_then(|v| v.as_array())
+        .map(|findings| {
+            findings
+                .iter()
+                .enumerate()
+                .filter_map(|(index, finding)| {
+                    Some(AccessibilityIssue {
+                        rule: field(finding, "rule")?,
+                        severity: field(finding, "severity")?,
+                        message: finding
+                            .get("message")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("")
+                            .to_string(),
+                        element: finding
+                            .get("element")
+                            .filter(|e| !e.is_null())
+                            .map(|e| element_from_value(e, format!("a11y_{}", index + 1))),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    AuditReport {
+        url: value
+            .get("url")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        elements_checked: value.get("checked").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+        issues,
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WHITE: [f64; 3] = [255.0, 255.0, 255.0];
+
+    fn opaque(hex: u32) -> [f64; 4] {
+        [
+            (hex >> 16 & 0xff) as f64,
+            (hex >> 8 & 0xff) as f64,
+            (hex & 0xff) as f64,
+            1.0,
+        ]
+    }
+
+    fn rounded(ratio: f64) -> f64 {
+        (ratio * 100.0).round() / 100.0
+    }
+
+    #[test]
+    fn contrast_of_known_colour_pairs() {
+        assert_eq!(rounded(contrast_ratio(opaque(0x000000), WHITE)), 21.0);
+        assert_eq!(rounded(contrast_ratio(opaque(0xffffff), WHITE)), 1.0);
+        // The lightest greys on white that pass and just miss normal text
+        assert_eq!(rounded(contrast_ratio(opaque(0x767676), WHITE)), 4.54);
+        assert_eq!(rounded(contrast_ratio(opaque(0x777777), WHITE)), 4.48);
+        assert_eq!(rounded(contrast_ratio(opaque(0x0000ff), WHITE)), 8.59);
+        // Order does not matter
+        assert_eq!(
+            contrast_ratio(opaque(0xffffff), [0.0, 0.0, 0.0]),
+            contrast_ratio(opaque(0x000000), WHITE)
+        );
+        // Half-transparent black on white reads as mid grey
+        assert_eq!(
+            rounded(contrast_ratio([0.0, 0.0, 0.0, 0.5], WHITE)),
+            rounded(contrast_ratio([127.5, 127.5, 127.5, 1.0], WHITE))
+        );
+        assert_eq!(contrast_ratio([0.0, 0.0, 0.0, 0.0], WHITE), 1.0);
+    }
+
+    #[test]
+    fn large_text_thresholds_start_at_24px_or_bold_18_66px() {
+        assert_eq!(required_contrast(16.0, 400), 4.5);
+        assert_eq!(required_contrast(23.99, 400), 4.5);
+        assert_eq!(required_contrast(24.0, 400), 3.0);
+        assert_eq!(required_contrast(18.65, 700), 4.5);
+        assert_eq!(required_contrast(18.66, 700), 3.0);
+        assert_eq!(required_contrast(18.66, 699), 4.5);
+        assert_eq!(required_contrast(20.0, 900), 3.0);
+
+        // #777 passes as large text only
+        let grey = contrast_ratio(opaque(0x777777), WHITE);
+        assert!(grey < required_contrast(16.0, 400));
+        assert!(grey >= required_contrast(24.0, 400));
+    }
+
+    #[test]
+    fn the_script_embeds_the_thresholds() {
+        let script = audit_script();
+        assert!(script.contains("parseInt(style.fontWeight, 10) >= 700"));
+        assert!(script.contains("size >= 24 || (bold && size >= 18.66)"));
+        assert!(script.contains("const required = large ? 3 : 4.5;"));
+    }
+
+    #[test]
+    fn findings_become_issues_by_severity() {
+        let report = report_from_value(&json!({
+            "url": "http://localhost/form",
+            "checked": 12,
+            "findings": [
+                { "rule": "form_label", "severity": "critical", "message": "field has no label",
+                  "element": { "tagName": "input", "attributes": { "id": "email" } } },
+                { "rule": "form_label", "severity": "serious",
+                  "message": "field is labelled only by its placeholder", "element": null },
+                { "rule": "color_contrast", "severity": "serious", "message": "contrast 4.48:1 is below 4.5:1" },
+                { "rule": "duplicate_id", "severity": "minor", "message": "id \"a\" is used by 2 elements" },
+                { "rule": "no_such_rule", "severity": "minor", "message": "dropped" }
+            ]
+        }));
+        assert_eq!(report.elements_checked, 12);
+        assert_eq!(report.issues.len(), 4);
+        assert!(!report.passed());
+        assert_eq!(report.issues_for(AccessibilityRule::FormLabel).len(), 2);
+        assert!(report.issues[0].element.is_some());
+        assert!(report.issues[1].element.is_none());
+        assert_eq!(report.count_at_least(Severity::Critical), 1);
+        assert_eq!(report.count_at_least(Severity::Serious), 3);
+        assert_eq!(report.count_at_least(Severity::Minor), 4);
+        let markdown = report.to_markdown();
+        assert!(markdown
+            .contains("4 issue(s) across 12 element(s) checked (1 critical, 2 serious, 1 minor)"));
+        assert!(markdown.contains("## Form fields must have a label (2)"));
+    }
+}
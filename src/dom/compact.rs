@@ -0,0 +1,727 @@
+//! Compact binary encoding for `DomState`.
+//!
+//! Every distinct string is stored once in a table and referenced by index, and each
+//! element is stored once: the clickable, input and text lists are written as
+//! indexes into the element list. Integers are LEB128 varints, floats little-endian.
+
+use crate::dom::{
+    CoordinateSpace, DocumentKind, DomElement, DomState, ElementRect, ExtractionStats, LinkKind,
+    OptionInfo, SelectorStats, Viewport,
+};
+use crate::errors::Result;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+
+/// Followed by the format version as an ASCII digit
+const MAGIC: &[u8; 3] = b"SDS";
+const VERSION: u8 = b'1';
+
+/// Attributes selectors, labels and element classification read; the lossy profile
+/// keeps only these
+pub const REFERENCED_ATTRIBUTES: &[&str] = &[
+    "id",
+    "class",
+    "name",
+    "type",
+    "role",
+    "href",
+    "value",
+    "placeholder",
+    "title",
+    "aria-label",
+    "contenteditable",
+    "data-testid",
+];
+
+/// What `DomState::to_bytes_with` keeps
+#[derive(Debug, Clone)]
+pub struct SerializationProfile {
+    /// Keep elements that are only text, neither clickable nor form controls
+    pub keep_text_elements: bool,
+    /// Keep attributes outside `REFERENCED_ATTRIBUTES`
+    pub keep_all_attributes: bool,
+    pub keep_screenshot: bool,
+}
+
+impl SerializationProfile {
+    /// Everything but element provenance; decodes to an equal `DomState` otherwise
+    pub fn full() -> Self {
+        Self {
+            keep_text_elements: true,
+            keep_all_attributes: true,
+            keep_screenshot: true,
+        }
+    }
+
+    /// Interactive elements with the attributes they are addressed by, no screenshot.
+    /// Element ids, selectors and the order of `clickable_elements` are unchanged.
+    pub fn lossy() -> Self {
+        Self {
+            keep_text_elements: false,
+            keep_all_attributes: false,
+            keep_screenshot: false,
+        }
+    }
+}
+
+impl Default for SerializationProfile {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+pub(crate) fn encode(state: &DomState, profile: &SerializationProfile) -> Vec<u8> {
+    let keep = |element: &DomElement| {
+        profile.keep_text_elements
+            || element.is_clickable
+            || element.is_interactable
+            || matches!(
+                element.tag_name.as_str(),
+                "input" | "textarea" | "select" | "canvas"
+            )
+    };
+
+    // Elements first, then list members missing from it, each once
+    let mut pool: Vec<&DomElement> = state.elements.iter().filter(|e| keep(e)).collect();
+    let in_elements = pool.len();
+    let mut position: HashMap<&str, usize> = HashMap::new();
+    for (i, element) in pool.iter().enumerate() {
+        position.entry(element.id.as_str()).or_insert(i);
+    }
+    let mut lists: Vec<Vec<usize>> = Vec::new();
+    for elements in [
+        &state.clickable_elements,
+        &state.input_elements,
+        &state.text_elements,
+    ] {
+        let mut list = Vec::new();
+        for element in elements.iter().filter(|e| keep(e)) {
+            let index = *position.entry(element.id.as_str()).or_insert_with(|| {
+                pool.push(element);
+                pool.len() - 1
+            });
+            list.push(index);
+        }
+        lists.push(list);
+    }
+
+    let mut strings = StringTable::default();
+    let mut body = Vec::new();
+    put_uint(&mut body, strings.index(&state.url));
+    put_uint(&mut body, strings.index(&state.title));
+    body.extend_from_slice(&state.timestamp.timestamp_millis().to_le_bytes());
+    match (&state.screenshot_base64, profile.keep_screenshot) {
+        (Some(screenshot), true) => {
+            body.push(1);
+            put_bytes(&mut body, screenshot.as_bytes());
+        }
+        _ => body.push(0),
+    }
+    match &state.coordinate_space {
+        Some(space) => {
+            body.push(1);
+            for value in [space.device_scale_factor, space.scroll_x, space.scroll_y] {
+                body.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        None => body.push(0),
+    }
+    match &state.viewport {
+        Some(viewport) => {
+            body.push(1);
+            for value in [
+                viewport.width,
+                viewport.height,
+                viewport.scroll_x,
+                viewport.scroll_y,
+                viewport.page_width,
+                viewport.page_height,
+                viewport.device_scale_factor,
+            ] {
+                body.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        None => body.push(0),
+    }
+    body.push(match state.document_kind {
+        DocumentKind::Html => 0,
+        DocumentKind::Pdf => 1,
+        DocumentKind::Image => 2,
+        DocumentKind::Plaintext => 3,
+        DocumentKind::Unknown => 4,
+    });
+    match &state.stats {
+        Some(stats) => {
+            body.push(1);
+            put_stats(&mut body, stats, &mut strings);
+        }
+        None => body.push(0),
+    }
+
+    put_uint(&mut body, pool.len());
+    put_uint(&mut body, in_elements);
+    for element in &pool {
+        put_element(&mut body, element, profile, &mut strings);
+    }
+    for list in &lists {
+        put_uint(&mut body, list.len());
+        for index in list {
+            put_uint(&mut body, *index);
+        }
+    }
+
+    let mut out = MAGIC.to_vec();
+    out.push(VERSION);
+    put_uint(&mut out, strings.values.len());
+    for value in &strings.values {
+        put_bytes(&mut out, value.as_bytes());
+    }
+    out.extend_from_slice(&body);
+    out
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<DomState> {
+    let mut reader = Reader { bytes, at: 0 };
+    let header = reader.take(4)?;
+    if &header[..3] != MAGIC || header[3] != VERSION {
+        return Err(invalid("not an encoded DomState").into());
+    }
+    let count = reader.uint()?;
+    let mut strings = Vec::with_capacity(count.min(bytes.len()));
+    for _ in 0..count {
+        let raw = reader.bytes()?;
+        strings.push(String::from_utf8(raw.to_vec()).map_err(|e| invalid(&e.to_string()))?);
+    }
+    let strings = Strings(strings);
+
+    let url = strings.get(reader.uint()?)?;
+    let title = strings.get(reader.uint()?)?;
+    let mut state = DomState::new(url, title);
+    let millis = i64::from_le_bytes(reader.array()?);
+    state.timestamp = chrono::DateTime::from_timestamp_millis(millis)
+        .ok_or_else(|| invalid("timestamp out of range"))?;
+    if reader.byte()? == 1 {
+        let raw = reader.bytes()?;
+        state.screenshot_base64 =
+            Some(String::from_utf8(raw.to_vec()).map_err(|e| invalid(&e.to_string()))?);
+    }
+    if reader.byte()? == 1 {
+        state.coordinate_space = Some(CoordinateSpace {
+            device_scale_factor: reader.float()?,
+            scroll_x: reader.float()?,
+            scroll_y: reader.float()?,
+        });
+    }
+    if reader.byte()? == 1 {
+        state.viewport = Some(Viewport {
+            width: reader.float()?,
+            height: reader.float()?,
+            scroll_x: reader.float()?,
+            scroll_y: reader.float()?,
+            page_width: reader.float()?,
+            page_height: reader.float()?,
+            device_scale_factor: reader.float()?,
+        });
+    }
+    state.document_kind = match reader.byte()? {
+        0 => DocumentKind::Html,
+        1 => DocumentKind::Pdf,
+        2 => DocumentKind::Image,
+        3 => DocumentKind::Plaintext,
+        _ => DocumentKind::Unknown,
+    };
+    if reader.byte()? == 1 {
+        state.stats = Some(read_stats(&mut reader, &strings)?);
+    }
+
+    let total = reader.uint()?;
+    let in_elements = reader.uint()?;
+    let mut pool = Vec::with_capacity(total.min(bytes.len()));
+    for _ in 0..total {
+        pool.push(read_element(&mut reader, &strings)?);
+    }
+    let mut lists = Vec::new();
+    for _ in 0..3 {
+        let len = reader.uint()?;
+        let mut list = Vec::with_capacity(len.min(bytes.len()));
+        for _ in 0..len {
+            let element = pool
+                .get(reader.uint()?)
+                .ok_or_else(|| invalid("element index out of range"))?;
+            list.push(element.clone());
+        }
+        lists.push(list);
+    }
+    state.text_elements = lists.pop().unwrap_or_default();
+    state.input_elements = lists.pop().unwrap_or_default();
+    state.clickable_elements = lists.pop().unwrap_or_default();
+    pool.truncate(in_elements);
+    state.elements = pool;
+    Ok(state)
+}
+
+fn put_stats(out: &mut Vec<u8>, stats: &ExtractionStats, strings: &mut StringTable) {
+    put_uint(out, stats.selectors.len());
+    for selector in &stats.selectors {
+        put_uint(out, strings.index(&selector.selector));
+        put_uint(out, selector.matched);
+        put_uint(out, selector.added);
+    }
+    for count in [
+        stats.duplicates,
+        stats.excluded,
+        stats.hidden,
+        stats.round_trips,
+    ] {
+        put_uint(out, count);
+    }
+    for ms in [stats.parse_ms, stats.match_ms] {
+        out.extend_from_slice(&ms.to_le_bytes());
+    }
+}
+
+fn read_stats(reader: &mut Reader<'_>, strings: &Strings) -> std::io::Result<ExtractionStats> {
+    let mut stats = ExtractionStats::default();
+    for _ in 0..reader.uint()? {
+        stats.selectors.push(SelectorStats {
+            selector: strings.get(reader.uint()?)?,
+            matched: reader.uint()?,
+            added: reader.uint()?,
+        });
+    }
+    stats.duplicates = reader.uint()?;
+    stats.excluded = reader.uint()?;
+    stats.hidden = reader.uint()?;
+    stats.round_trips = reader.uint()?;
+    stats.parse_ms = reader.float()?;
+    stats.match_ms = reader.float()?;
+    Ok(stats)
+}
+
+fn put_element(
+    out: &mut Vec<u8>,
+    element: &DomElement,
+    profile: &SerializationProfile,
+    strings: &mut StringTable,
+) {
+    put_uint(out, strings.index(&element.id));
+    put_uint(out, strings.index(&element.tag_name));
+    put_opt(out, element.element_id.as_deref(), strings);
+    put_opt(out, element.class_name.as_deref(), strings);
+    put_opt(out, element.text_content.as_deref(), strings);
+
+    let mut attributes: Vec<(&String, &String)> = element
+        .attributes
+        .iter()
+        .filter(|(key, _)| {
+            profile.keep_all_attributes || REFERENCED_ATTRIBUTES.contains(&key.as_str())
+        })
+        .collect();
+    attributes.sort();
+    put_uint(out, attributes.len());
+    for (key, value) in attributes {
+        put_uint(out, strings.index(key));
+        put_uint(out, strings.index(value));
+    }
+
+    match &element.rect {
+        Some(rect) => {
+            out.push(1);
+            for value in [rect.x, rect.y, rect.width, rect.height] {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        None => out.push(0),
+    }
+    let flags = element.is_clickable as u8
+        | (element.is_visible as u8) << 1
+        | (element.is_interactable as u8) << 2
+        | (element.is_occluded as u8) << 3
+        | (element.is_disabled as u8) << 4
+        | (element.is_readonly as u8) << 5
+        | (element.is_aria_disabled as u8) << 6;
+    out.push(flags);
+    put_uint(out, strings.index(&element.xpath));
+    put_uint(out, strings.index(&element.css_selector));
+    put_uint(out, element.scroll_parents.len());
+    for parent in &element.scroll_parents {
+        put_uint(out, strings.index(parent));
+    }
+    put_opt(out, element.ai_label.as_deref(), strings);
+    put_opt(out, element.current_value.as_deref(), strings);
+    out.push(match element.checked {
+        None => 0,
+        Some(false) => 1,
+        Some(true) => 2,
+    });
+    put_uint(out, element.selected_options.len());
+    for option in &element.selected_options {
+        put_uint(out, strings.index(option));
+    }
+    put_opt(out, element.graphic_label.as_deref(), strings);
+    put_uint(out, element.options.len());
+    for option in &element.options {
+        put_uint(out, strings.index(&option.value));
+        put_uint(out, strings.index(&option.label));
+        out.push(option.selected as u8);
+        put_opt(out, option.group.as_deref(), strings);
+    }
+    put_opt(out, element.field_label.as_deref(), strings);
+    put_uint(out, element.own_text_len);
+    put_uint(out, element.full_text_len);
+    put_opt(out, element.resolved_href.as_deref(), strings);
+    out.push(match element.link_kind {
+        None => 0,
+        Some(LinkKind::Internal) => 1,
+        Some(LinkKind::External) => 2,
+        Some(LinkKind::Anchor) => 3,
+        Some(LinkKind::Mailto) => 4,
+        Some(LinkKind::Tel) => 5,
+        Some(LinkKind::Javascript) => 6,
+    });
+}
+
+fn read_element(reader: &mut Reader<'_>, strings: &Strings) -> std::io::Result<DomElement> {
+    let id = strings.get(reader.uint()?)?;
+    let tag_name = strings.get(reader.uint()?)?;
+    let mut element = DomElement::new(tag_name, id);
+    element.element_id = strings.opt(reader.uint()?)?;
+    element.class_name = strings.opt(reader.uint()?)?;
+    element.text_content = strings.opt(reader.uint()?)?;
+    for _ in 0..reader.uint()? {
+        let key = strings.get(reader.uint()?)?;
+        let value = strings.get(reader.uint()?)?;
+        element.attributes.insert(key, value);
+    }
+    if reader.byte()? == 1 {
+        element.rect = Some(ElementRect {
+            x: reader.float()?,
+            y: reader.float()?,
+            width: reader.float()?,
+            height: reader.float()?,
+        });
+    }
+    let flags = reader.byte()?;
+    element.is_clickable = flags & 1 != 0;
+    element.is_visible = flags & 2 != 0;
+    element.is_interactable = flags & 4 != 0;
+    element.is_occluded = flags & 8 != 0;
+    element.is_disabled = flags & 16 != 0;
+    element.is_readonly = flags & 32 != 0;
+    element.is_aria_disabled = flags & 64 != 0;
+    element.xpath = strings.get(reader.uint()?)?;
+    element.css_selector = strings.get(reader.uint()?)?;
+    for _ in 0..reader.uint()? {
+        element.scroll_parents.push(strings.get(reader.uint()?)?);
+    }
+    element.ai_label = strings.opt(reader.uint()?)?;
+    element.current_value = strings.opt(reader.uint()?)?;
+    element.checked = match reader.byte()? {
+        0 => None,
+        checked => Some(checked == 2),
+    };
+    for _ in 0..reader.uint()? {
+        element.selected_options.push(strings.get(reader.uint()?)?);
+    }
+    element.graphic_label = strings.opt(reader.uint()?)?;
+    for _ in 0..reader.uint()? {
+        element.options.push(OptionInfo {
+            value: strings.get(reader.uint()?)?,
+            label: strings.get(reader.uint()?)?,
+            selected: reader.byte()? == 1,
+            group: strings.opt(reader.uint()?)?,
+        });
+    }
+    element.field_label = strings.opt(reader.uint()?)?;
+    element.own_text_len = reader.uint()?;
+    element.full_text_len = reader.uint()?;
+    element.resolved_href = strings.opt(reader.uint()?)?;
+    element.link_kind = match reader.byte()? {
+        1 => Some(LinkKind::Internal),
+        2 => Some(LinkKind::External),
+        3 => Some(LinkKind::Anchor),
+        4 => Some(LinkKind::Mailto),
+        5 => Some(LinkKind::Tel),
+        6 => Some(LinkKind::Javascript),
+        _ => None,
+    };
+    Ok(element)
+}
+
+#[derive(Default)]
+struct StringTable {
+    values: Vec<String>,
+    indexes: HashMap<String, usize>,
+}
+
+impl StringTable {
+    fn index(&mut self, value: &str) -> usize {
+        if let Some(&index) = self.indexes.get(value) {
+            return index;
+        }
+        self.values.push(value.to_string());
+        self.indexes
+            .insert(value.to_string(), self.values.len() - 1);
+        self.values.len() - 1
+    }
+}
+
+struct Strings(Vec<String>);
+
+impl Strings {
+    fn get(&self, index: usize) -> std::io::Result<String> {
+        self.0
+            .get(index)
+            .cloned()
+            .ok_or_else(|| invalid("string index out of range"))
+    }
+
+    /// Optional strings are stored as index + 1, with 0 for `None`
+    fn opt(&self, raw: usize) -> std::io::Result<Option<String>> {
+        match raw {
+            0 => Ok(None),
+            index => self.get(index - 1).map(Some),
+        }
+    }
+}
+
+fn put_opt(out: &mut Vec<u8>, value: Option<&str>, strings: &mut StringTable) {
+    put_uint(out, value.map_or(0, |value| strings.index(value) + 1));
+}
+
+fn put_uint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    put_uint(out, bytes.len());
+    out.extend_from_slice(bytes);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> std::io::Result<&'a [u8]> {
+        let end = self
+            .at
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))?;
+        let slice = &self.bytes[self.at..end];
+        self.at = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> std::io::Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn byte(&mut self) -> std::io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn float(&mut self) -> std::io::Result<f64> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    fn uint(&mut self) -> std::io::Result<usize> {
+        let mut value = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint too long"))
+    }
+
+    fn bytes(&mut self) -> std::io::Result<&'a [u8]> {
+        let len = self.uint()?;
+        self.take(len)
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An element with every field set, so a field the encoding drops shows up
+    fn element(index: usize) -> DomElement {
+        let mut element = DomElement::new(
+            if index.is_multiple_of(3) {
+                "select"
+            } else {
+                "a"
+            }
+            .to_string(),
+            format!("elem_{}", index),
+        );
+        element.element_id = Some(format!("item-{}", index));
+        element.class_name = Some("nav-link btn".to_string());
+        element.text_content = Some(format!("Item {}", index));
+        element.own_text_len = 6;
+        element.full_text_len = 9;
+        for (key, value) in [
+            ("class", "nav-link btn"),
+            ("href", "/items"),
+            ("data-track", "nav"),
+            ("style", "color: red"),
+        ] {
+            element
+                .attributes
+                .insert(key.to_string(), value.to_string());
+        }
+        element.rect = Some(ElementRect {
+            x: 10.5,
+            y: 20.0 * index as f64,
+            width: 100.25,
+            height: 18.0,
+        });
+        element.is_clickable = index.is_multiple_of(2);
+        element.is_visible = !index.is_multiple_of(5);
+        element.is_interactable = true;
+        element.is_occluded = index.is_multiple_of(7);
+        element.is_disabled = index.is_multiple_of(11);
+        element.is_readonly = index.is_multiple_of(13);
+        element.is_aria_disabled = index.is_multiple_of(17);
+        element.xpath = format!("/html/body/nav/a[{}]", index + 1);
+        element.css_selector = format!("#item-{}", index);
+        element.scroll_parents = vec!["#sidebar".to_string()];
+        element.ai_label = Some("Navigation link".to_string());
+        element.field_label = Some("Items".to_string());
+        element.graphic_label = Some("Arrow".to_string());
+        element.resolved_href = Some("https://example.com/items".to_string());
+        element.link_kind = Some(LinkKind::Internal);
+        element.current_value = Some("b".to_string());
+        element.checked = Some(!index.is_multiple_of(2));
+        element.selected_options = vec!["b".to_string()];
+        element.options = vec![
+            OptionInfo {
+                value: "a".to_string(),
+                label: "A".to_string(),
+                selected: false,
+                group: Some("Letters".to_string()),
+            },
+            OptionInfo {
+                value: "b".to_string(),
+                label: "B".to_string(),
+                selected: true,
+                group: None,
+            },
+        ];
+        element
+    }
+
+    fn state(elements: usize) -> DomState {
+        let mut state = DomState::new("https://example.com/".to_string(), "Items".to_string());
+        state.timestamp = chrono::DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        state.screenshot_base64 = Some("iVBORw0KGgo=".to_string());
+        state.coordinate_space = Some(CoordinateSpace {
+            device_scale_factor: 2.0,
+            scroll_x: 0.0,
+            scroll_y: 120.5,
+        });
+        state.viewport = Some(Viewport {
+            width: 1280.0,
+            height: 720.0,
+            scroll_x: 0.0,
+            scroll_y: 120.5,
+            page_width: 1280.0,
+            page_height: 4000.0,
+            device_scale_factor: 2.0,
+        });
+        state.document_kind = DocumentKind::Html;
+        let mut stats = ExtractionStats {
+            duplicates: 3,
+            excluded: 1,
+            hidden: 4,
+            parse_ms: 1.25,
+            match_ms: 0.5,
+            round_trips: 5,
+            ..ExtractionStats::default()
+        };
+        stats.record("a[href]", 12, 10);
+        stats.record(".btn", 2, 0);
+        state.stats = Some(stats);
+        for index in 0..elements {
+            state.add_element(element(index));
+        }
+        let mut text = DomElement::new("p".to_string(), "text_1".to_string())
+            .with_text_content("Only text".to_string());
+        text.xpath = "/html/body/p".to_string();
+        state.add_element(text);
+        state
+    }
+
+    fn json(state: &DomState) -> serde_json::Value {
+        serde_json::to_value(state).unwrap()
+    }
+
+    #[test]
+    fn full_profile_round_trips_every_field() {
+        let original = state(20);
+        let decoded = decode(&encode(&original, &SerializationProfile::full())).unwrap();
+        assert_eq!(json(&decoded), json(&original));
+    }
+
+    #[test]
+    fn lossy_profile_keeps_ids_selectors_and_order() {
+        let original = state(20);
+        let decoded = decode(&encode(&original, &SerializationProfile::lossy())).unwrap();
+        assert_eq!(decoded.screenshot_base64, None);
+        assert!(decoded.text_elements.iter().all(|e| e.id != "text_1"));
+        assert_eq!(decoded.elements.len(), original.elements.len() - 1);
+
+        let addresses = |elements: &[DomElement]| -> Vec<(String, String, String)> {
+            elements
+                .iter()
+                .filter(|e| e.id != "text_1")
+                .map(|e| (e.id.clone(), e.css_selector.clone(), e.xpath.clone()))
+                .collect()
+        };
+        assert_eq!(
+            addresses(&decoded.clickable_elements),
+            addresses(&original.clickable_elements)
+        );
+        assert_eq!(addresses(&decoded.elements), addresses(&original.elements));
+        let attributes = &decoded.elements[0].attributes;
+        assert_eq!(attributes.get("href").map(String::as_str), Some("/items"));
+        assert!(!attributes.contains_key("data-track"));
+        assert!(!attributes.contains_key("style"));
+    }
+
+    #[test]
+    fn encoding_is_a_fraction_of_the_json() {
+        let original = state(500);
+        let json = serde_json::to_vec(&original).unwrap().len();
+        let full = encode(&original, &SerializationProfile::full()).len();
+        let lossy = encode(&original, &SerializationProfile::lossy()).len();
+        assert!(full * 3 < json, "full {} bytes, JSON {}", full, json);
+        assert!(lossy < full, "lossy {} bytes, full {}", lossy, full);
+    }
+
+    #[test]
+    fn rejects_other_data() {
+        assert!(decode(b"").is_err());
+        assert!(decode(br#"{"url": "x"}"#).is_err());
+        let mut bytes = encode(&state(3), &SerializationProfile::full());
+        bytes[3] = b'2';
+        assert!(decode(&bytes).is_err());
+        let bytes = encode(&state(3), &SerializationProfile::full());
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use crate::dom::ElementRect;
+use serde::{Deserialize, Serialize};
+
+/// Viewport geometry at the moment a screenshot was captured.
+///
+/// Element rects come from `getBoundingClientRect`, so they are CSS pixels relative
+/// to the viewport at the time they were measured. Screenshots are device pixels of
+/// the viewport at capture time. Converting between the two needs the device scale
+/// factor and, if the page scrolled in between, both scroll offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CoordinateSpace {
+    pub device_scale_factor: f64,
+    pub scroll_x: f64,
+    pub scroll_y: f64,
+}
+
+impl Default for CoordinateSpace {
+    fn default() -> Self {
+        Self {
+            device_scale_factor: 1.0,
+            scroll_x: 0.0,
+            scroll_y: 0.0,
+        }
+    }
+}
+
+impl CoordinateSpace {
+    pub fn new(device_scale_factor: f64, scroll_x: f64, scroll_y: f64) -> Self {
+        Self {
+            device_scale_factor: if device_scale_factor > 0.0 {
+                device_scale_factor
+            } else {
+                1.0
+            },
+            scroll_x,
+            scroll_y,
+        }
+    }
+
+    /// Script returning the current space, for use with `from_value`
+    pub(crate) const CAPTURE_JS: &'static str =
+        "({ dpr: window.devicePixelRatio, x: window.scrollX, y: window.scrollY })";
+
+    pub(crate) fn from_value(value: &serde_json::Value) -> Self {
+        let field = |name: &str| value.get(name).and_then(|v| v.as_f64());
+        Self::new(
+            field("dpr").unwrap_or(1.0),
+            field("x").unwrap_or(0.0),
+            field("y").unwrap_or(0.0),
+        )
+    }
+
+    /// Viewport CSS point measured in this space to a screenshot device pixel
+    pub fn point_to_device(&self, x: f64, y: f64) -> (f64, f64) {
+        (x * self.device_scale_factor, y * self.device_scale_factor)
+    }
+
+    /// Screenshot device pixel to a viewport CSS point in this space
+    pub fn point_from_device(&self, x: f64, y: f64) -> (f64, f64) {
+        (x / self.device_scale_factor, y / self.device_scale_factor)
+    }
+}
+
+/// Viewport size, scroll position and page size when a `DomState` was extracted,
+/// in CSS pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Viewport {
+    pub width: f64,
+    pub height: f64,
+    pub scroll_x: f64,
+    pub scroll_y: f64,
+    /// Scrollable size of the document
+    pub page_width: f64,
+    pub page_height: f64,
+    pub device_scale_factor: f64,
+}
+
+impl Viewport {
+    /// Expression evaluating to the current viewport, in the shape of `Viewport`
+    pub(crate) const CAPTURE_JS: &'static str = r#"({
+                width: window.innerWidth,
+                height: window.innerHeight,
+                scroll_x: window.scrollX,
+                scroll_y: window.scrollY,
+                page_width: Math.max(document.documentElement.scrollWidth, document.body ? document.body.scrollWidth : 0),
+                page_height: Math.max(document.documentElement.scrollHeight, document.body ? document.body.scrollHeight : 0),
+                device_scale_factor: window.devicePixelRatio || 1
+            })"#;
+
+    /// Page y coordinate of the viewport's bottom edge
+    pub fn fold_y(&self) -> f64 {
+        self.scroll_y + self.height
+    }
+
+    /// CSS pixels of page below the viewport
+    pub fn remaining_below(&self) -> f64 {
+        (self.page_height - self.fold_y()).max(0.0)
+    }
+
+    /// Scrolled as far down as the page currently goes
+    pub fn is_at_bottom(&self) -> bool {
+        self.remaining_below() < 1.0
+    }
+
+    /// Share of the page height above the viewport's bottom edge, from 0 to 1
+    pub fn scroll_progress(&self) -> f64 {
+        if self.page_height <= 0.0 {
+            1.0
+        } else {
+            (self.fold_y() / self.page_height).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Scale and scroll offset, for mapping rects measured in this viewport
+    pub fn coordinate_space(&self) -> CoordinateSpace {
+        CoordinateSpace::new(self.device_scale_factor, self.scroll_x, self.scroll_y)
+    }
+}
+
+impl ElementRect {
+    /// Whether the two rects share any area
+    pub fn intersects(&self, other: &ElementRect) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+
+    /// Map a viewport rect in CSS pixels onto the device pixels of a screenshot taken
+    /// with `scale` and scrolled `scroll` (x, y) CSS pixels further than when the rect
+    /// was measured
+    pub fn to_device_pixels(&self, scale: f64, scroll: (f64, f64)) -> ElementRect {
+        ElementRect {
+            x: (self.x - scroll.0) * scale,
+            y: (self.y - scroll.1) * scale,
+            width: self.width * scale,
+            height: self.height * scale,
+        }
+    }
+
+    /// Inverse of `to_device_pixels`
+    pub fn from_device_pixels(&self, scale: f64, scroll: (f64, f64)) -> ElementRect {
+        ElementRect {
+            x: self.x / scale + scroll.0,
+            y: self.y / scale + scroll.1,
+            width: self.width / scale,
+            height: self.height / scale,
+        }
+    }
+
+    /// Device-pixel rect in a screenshot captured in `capture`, for a rect measured
+    /// in `measured`
+    pub fn in_screenshot(
+        &self,
+        measured: &CoordinateSpace,
+        capture: &CoordinateSpace,
+    ) -> ElementRect {
+        self.to_device_pixels(
+            capture.device_scale_factor,
+            (
+                capture.scroll_x - measured.scroll_x,
+                capture.scroll_y - measured.scroll_y,
+            ),
+        )
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> ElementRect {
+        ElementRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn assert_rect(actual: &ElementRect, expected: &ElementRect) {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert!(
+            close(actual.x, expected.x)
+                && close(actual.y, expected.y)
+                && close(actual.width, expected.width)
+                && close(actual.height, expected.height),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// Grayscale PNG of a `width` x `height` CSS pixel viewport captured in `capture`,
+    /// white where the page has the element at `page_rect` and black elsewhere
+    fn screenshot(
+        page_rect: &ElementRect,
+        capture: &CoordinateSpace,
+        width: u32,
+        height: u32,
+    ) -> Vec<u8> {
+        let scale = capture.device_scale_factor;
+        let (device_width, device_height) = (
+            (width as f64 * scale) as u32,
+            (height as f64 * scale) as u32,
+        );
+        let pixels: Vec<u8> = (0..device_height)
+            .flat_map(|py| (0..device_width).map(move |px| (px, py)))
+            .map(|(px, py)| {
+                // Page point under the center of the device pixel
+                let x = (px as f64 + 0.5) / scale + capture.scroll_x;
+                let y = (py as f64 + 0.5) / scale + capture.scroll_y;
+                let inside = x >= page_rect.x
+                    && x < page_rect.x + page_rect.width
+                    && y >= page_rect.y
+                    && y < page_rect.y + page_rect.height;
+                if inside {
+                    255
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, device_width, device_height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&pixels).unwrap();
+        writer.finish().unwrap();
+        out
+    }
+
+    /// Width, height and grayscale pixels of `png`
+    fn decode(png: &[u8]) -> (u32, u32, Vec<u8>) {
+        let mut reader = png::Decoder::new(png).read_info().unwrap();
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).unwrap();
+        buffer.truncate(info.buffer_size());
+        (info.width, info.height, buffer)
+    }
+
+    #[test]
+    fn device_pixels_round_trip() {
+        let measured = rect(40.25, 300.5, 120.0, 32.75);
+        for (scale, scroll) in [(2.0, (15.0, 150.0)), (1.5, (-8.0, 0.0)), (1.0, (0.0, 0.0))] {
+            let back = measured
+                .to_device_pixels(scale, scroll)
+                .from_device_pixels(scale, scroll);
+            assert_rect(&back, &measured);
+        }
+    }
+
+    #[test]
+    fn scrolling_down_after_measuring_moves_the_rect_up_the_screenshot() {
+        // Measured at scroll (0, 100); captured at (10, 250) on a 2x display
+        let measured = CoordinateSpace::new(2.0, 0.0, 100.0);
+        let capture = CoordinateSpace::new(2.0, 10.0, 250.0);
+        let device = rect(40.0, 300.0, 50.0, 20.0).in_screenshot(&measured, &capture);
+        assert_rect(&device, &rect(60.0, 300.0, 100.0, 40.0));
+    }
+
+    #[test]
+    fn measured_rect_lands_on_its_screenshot_pixels() {
+        let measured = CoordinateSpace::new(2.0, 0.0, 100.0);
+        let capture = CoordinateSpace::new(2.0, 10.0, 250.0);
+        let viewport_rect = rect(40.0, 300.0, 50.0, 20.0);
+        let page_rect = rect(
+            viewport_rect.x + measured.scroll_x,
+            viewport_rect.y + measured.scroll_y,
+            viewport_rect.width,
+            viewport_rect.height,
+        );
+        let (width, height, pixels) = decode(&screenshot(&page_rect, &capture, 200, 300));
+        assert_eq!((width, height), (400, 600));
+
+        let device = viewport_rect.in_screenshot(&measured, &capture);
+        let pixel = |x: f64, y: f64| pixels[(y as u32 * width + x as u32) as usize];
+        let (left, top) = (device.x, device.y);
+        let (right, bottom) = (device.x + device.width, device.y + device.height);
+        assert_eq!(pixel(left, top), 255, "top-left corner");
+        assert_eq!(pixel(right - 1.0, bottom - 1.0), 255, "bottom-right corner");
+        assert_eq!(pixel(left - 1.0, top), 0, "left of the rect");
+        assert_eq!(pixel(left, top - 1.0), 0, "above the rect");
+        assert_eq!(pixel(right, bottom - 1.0), 0, "right of the rect");
+        assert_eq!(pixel(right - 1.0, bottom), 0, "below the rect");
+        let white = pixels.iter().filter(|&&p| p == 255).count();
+        assert_eq!(white as f64, device.width * device.height);
+    }
+
+    #[test]
+    fn screenshot_pixel_maps_back_to_the_current_viewport() {
+        // What click_at_screenshot_pixel does: a pixel of a screenshot captured at
+        // scroll (10, 250), clicked after the page scrolled on to (10, 400)
+        let capture = CoordinateSpace::new(2.0, 10.0, 250.0);
+        let current = CoordinateSpace::new(2.0, 10.0, 400.0);
+        let page_rect = rect(40.0, 400.0, 50.0, 20.0);
+
+        let (device_x, device_y) = (70.0 * 2.0, 160.0 * 2.0);
+        let (_, _, pixels) = decode(&screenshot(&page_rect, &capture, 200, 300));
+        assert_eq!(pixels[(device_y as usize) * 400 + device_x as usize], 255);
+
+        let point = rect(device_x, device_y, 0.0, 0.0).from_device_pixels(
+            capture.device_scale_factor,
+            (
+                capture.scroll_x - current.scroll_x,
+                capture.scroll_y - current.scroll_y,
+            ),
+        );
+        // Page (80, 410) is 10 CSS pixels into the element, now at viewport y 10
+        assert_eq!((point.x, point.y), (70.0, 10.0));
+        assert!(point.x + current.scroll_x >= page_rect.x);
+        assert!(point.y + current.scroll_y >= page_rect.y);
+        assert!(point.y + current.scroll_y < page_rect.y + page_rect.height);
+    }
+
+    #[test]
+    fn point_conversion_ignores_scroll() {
+        let space = CoordinateSpace::new(2.0, 30.0, 40.0);
+        assert_eq!(space.point_to_device(10.0, 20.5), (20.0, 41.0));
+        assert_eq!(space.point_from_device(20.0, 41.0), (10.0, 20.5));
+        assert_eq!(CoordinateSpace::new(0.0, 0.0, 0.0).device_scale_factor, 1.0);
+    }
+}
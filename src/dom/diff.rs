@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+/// Element-level changes to a page between two moments
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DomDiff {
+    #[serde(default)]
+    pub added: Vec<DiffEntry>,
+    #[serde(default)]
+    pub removed: Vec<DiffEntry>,
+    #[serde(default)]
+    pub changed: Vec<AttributeChange>,
+}
+
+/// An element that appeared or disappeared
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffEntry {
+    /// Selector of the element, or of its former parent for removed elements
+    pub selector: String,
+    pub tag_name: String,
+    /// Start of the element's text
+    #[serde(default)]
+    pub text: String,
+}
+
+/// An attribute that was set, changed or removed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeChange {
+    pub selector: String,
+    pub attribute: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl DomDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// One line counting each kind of change, for logs
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} removed, {} attribute changes",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+}
+
+/// One line per change: `+` added, `-` removed, `~` attribute changed
+impl std::fmt::Display for DomDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (sign, entry) in self
+            .removed
+            .iter()
+            .map(|entry| ('-', entry))
+            .chain(self.added.iter().map(|entry| ('+', entry)))
+        {
+            write!(f, "  {} <{}>", sign, entry.tag_name)?;
+            if !entry.text.is_empty() {
+                write!(f, " \"{}\"", entry.text)?;
+            }
+            if !entry.selector.is_empty() {
+                write!(f, " at {}", entry.selector)?;
+            }
+            writeln!(f)?;
+        }
+        for change in &self.changed {
+            writeln!(
+                f,
+                "  ~ {} {}: {:?} → {:?}",
+                change.selector,
+                change.attribute,
+                change.old_value.as_deref().unwrap_or(""),
+                change.new_value.as_deref().unwrap_or("")
+            )?;
+        }
+        Ok(())
+    }
+}
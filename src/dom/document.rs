@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// JavaScript evaluating to the document's MIME type
+pub(crate) const CONTENT_TYPE_JS: &str = "document.contentType || ''";
+
+/// What the tab is showing. Only `Html` has a DOM worth extracting; the
+/// browser renders the others with a viewer of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentKind {
+    /// HTML or XHTML, including `about:blank`
+    #[default]
+    Html,
+    Pdf,
+    Image,
+    /// Text, JSON, XML, source listings and `view-source:` pages
+    Plaintext,
+    Unknown,
+}
+
+impl DocumentKind {
+    /// Kind for a `document.contentType` such as `application/pdf`
+    pub fn from_content_type(content_type: &str) -> Self {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match mime.as_str() {
+            "text/html" | "application/xhtml+xml" => DocumentKind::Html,
+            "application/pdf" => DocumentKind::Pdf,
+            "application/json" | "application/xml" | "application/javascript" => {
+                DocumentKind::Plaintext
+            }
+            m if m.starts_with("image/") => DocumentKind::Image,
+            m if m.starts_with("text/") || m.ends_with("+json") || m.ends_with("+xml") => {
+                DocumentKind::Plaintext
+            }
+            _ => DocumentKind::Unknown,
+        }
+    }
+
+    /// Best guess from the URL alone, for when the page runs no script
+    pub fn from_url(url: &str) -> Self {
+        if url.starts_with("view-source:") {
+            return DocumentKind::Plaintext;
+        }
+        if url.is_empty() || url.starts_with("about:") {
+            return DocumentKind::Html;
+        }
+        let path = url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let extension = path
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.rsplit_once('.'))
+            .map(|(_, extension)| extension)
+            .unwrap_or_default();
+        match extension {
+            "pdf" => DocumentKind::Pdf,
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" | "bmp" | "ico" | "avif" => {
+                DocumentKind::Image
+            }
+            "txt" | "json" | "xml" | "csv" | "md" => DocumentKind::Plaintext,
+            "" | "html" | "htm" | "xhtml" | "php" | "asp" | "aspx" | "jsp" => DocumentKind::Html,
+            _ => DocumentKind::Unknown,
+        }
+    }
+
+    /// Kind from the content type the page reported, falling back to the URL.
+    /// `view-source:` pages are HTML underneath but count as `Plaintext`.
+    pub fn detect(content_type: Option<&str>, url: &str) -> Self {
+        match content_type {
+            _ if url.starts_with("view-source:") => DocumentKind::Plaintext,
+            Some(content_type) if !content_type.is_empty() => Self::from_content_type(content_type),
+            _ => Self::from_url(url),
+        }
+    }
+
+    pub fn is_html(&self) -> bool {
+        *self == DocumentKind::Html
+    }
+}
+
+impl fmt::Display for DocumentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DocumentKind::Html => "HTML",
+            DocumentKind::Pdf => "PDF",
+            DocumentKind::Image => "image",
+            DocumentKind::Plaintext => "plain text",
+            DocumentKind::Unknown => "unknown",
+        })
+    }
+}
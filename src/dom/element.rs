@@ -1,3 +1,4 @@
+use crate::dom::{LinkKind, Provenance, Viewport};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -7,15 +8,85 @@ pub struct DomElement {
     pub tag_name: String,
     pub element_id: Option<String>,
     pub class_name: Option<String>,
+    /// Normalized text: all of it for a control, the element's direct text for
+    /// a container of other controls such as a menu
     pub text_content: Option<String>,
+    /// Characters of normalized text directly in the element
+    #[serde(default)]
+    pub own_text_len: usize,
+    /// Characters of normalized text in the element and its descendants; more
+    /// than `text_content` has when that is a container's
+    #[serde(default)]
+    pub full_text_len: usize,
     pub attributes: HashMap<String, String>,
     pub rect: Option<ElementRect>,
     pub is_clickable: bool,
     pub is_visible: bool,
     pub is_interactable: bool,
+    /// Another element sits on top of this one's center point. Only set by live
+    /// queries that measure layout, such as `query_xpath`
+    #[serde(default)]
+    pub is_occluded: bool,
+    /// `disabled`, or inside a disabled fieldset
+    #[serde(default)]
+    pub is_disabled: bool,
+    /// `readonly` input or textarea
+    #[serde(default)]
+    pub is_readonly: bool,
+    /// `aria-disabled="true"` on the element or an ancestor
+    #[serde(default)]
+    pub is_aria_disabled: bool,
     pub xpath: String,
     pub css_selector: String,
+    /// Selectors of the scrollable ancestors, nearest first, excluding the page
+    /// itself. Only set by live queries that measure layout
+    #[serde(default)]
+    pub scroll_parents: Vec<String>,
     pub ai_label: Option<String>,
+    /// Text of the elements `aria-labelledby` names, else of the `<label>`
+    /// elements pointing at or wrapping this field
+    #[serde(default)]
+    pub field_label: Option<String>,
+    /// Name of an SVG graphic from its `aria-label` or `<title>`: the element's
+    /// own, or for a link or button without text, that of the icon inside it
+    #[serde(default)]
+    pub graphic_label: Option<String>,
+    /// Absolute URL of a link's `href`, resolved against the document's base URI
+    #[serde(default)]
+    pub resolved_href: Option<String>,
+    /// Where following the link goes; `None` for anything but links with an `href`
+    #[serde(default)]
+    pub link_kind: Option<LinkKind>,
+    /// Live value of an input, textarea or select when the page was read; differs
+    /// from the `value` attribute once something has typed into the field
+    #[serde(default)]
+    pub current_value: Option<String>,
+    /// Live checked state of a checkbox or radio
+    #[serde(default)]
+    pub checked: Option<bool>,
+    /// Values of the options selected in a select
+    #[serde(default)]
+    pub selected_options: Vec<String>,
+    /// Every option of a select, in order. Options and optgroups are not
+    /// elements of their own.
+    #[serde(default)]
+    pub options: Vec<OptionInfo>,
+    /// Why extraction kept the element, when `DomConfig::explain` is set. Not
+    /// kept by `DomState::to_bytes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+/// One option of a select
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionInfo {
+    pub value: String,
+    /// Text the option shows
+    pub label: String,
+    pub selected: bool,
+    /// Label of the optgroup the option is in, if any
+    #[serde(default)]
+    pub group: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,17 +105,47 @@ impl DomElement {
             element_id: None,
             class_name: None,
             text_content: None,
+            own_text_len: 0,
+            full_text_len: 0,
             attributes: HashMap::new(),
             rect: None,
             is_clickable: false,
             is_visible: true,
             is_interactable: false,
+            is_occluded: false,
+            is_disabled: false,
+            is_readonly: false,
+            is_aria_disabled: false,
             xpath: String::new(),
             css_selector: String::new(),
+            scroll_parents: Vec::new(),
             ai_label: None,
+            field_label: None,
+            graphic_label: None,
+            resolved_href: None,
+            link_kind: None,
+            current_value: None,
+            checked: None,
+            selected_options: Vec::new(),
+            options: Vec::new(),
+            provenance: None,
         }
     }
 
+    /// Neither disabled nor `aria-disabled`, so clicks reach it
+    pub fn is_enabled(&self) -> bool {
+        !self.is_disabled && !self.is_aria_disabled
+    }
+
+    /// Whether the element starts above the bottom edge of `viewport`, so it is on
+    /// screen without scrolling further down. Needs a rect measured in that
+    /// viewport; `false` without one.
+    pub fn is_above_fold(&self, viewport: &Viewport) -> bool {
+        self.rect
+            .as_ref()
+            .is_some_and(|rect| rect.y < viewport.height)
+    }
+
     pub fn with_text_content(mut self, text: String) -> Self {
         self.text_content = Some(text);
         self
@@ -102,6 +203,10 @@ impl DomElement {
             label_parts.push(format!("placeholder '{}'", placeholder));
         }
 
+        if let Some(field_label) = &self.field_label {
+            label_parts.push(format!("labeled '{}'", field_label));
+        }
+
         if let Some(aria_label) = self.attributes.get("aria-label") {
             label_parts.push(format!("labeled '{}'", aria_label));
         }
@@ -110,6 +215,10 @@ impl DomElement {
             label_parts.push(format!("titled '{}'", title));
         }
 
+        if let Some(graphic) = &self.graphic_label {
+            label_parts.push(format!("showing '{}'", graphic));
+        }
+
         // Add text content if available and meaningful
         if let Some(text) = &self.text_content {
             let clean_text = text.trim();
@@ -119,11 +228,18 @@ impl DomElement {
         }
 
         // Add interaction information
-        if self.is_clickable {
+        if !self.is_enabled() {
+            label_parts.push("(disabled)".to_string());
+        } else if self.is_clickable {
             label_parts.push("(clickable)".to_string());
         }
 
-        if self.is_interactable && matches!(self.tag_name.as_str(), "input" | "textarea") {
+        if self.is_readonly {
+            label_parts.push("(read-only)".to_string());
+        } else if self.is_enabled()
+            && self.is_interactable
+            && matches!(self.tag_name.as_str(), "input" | "textarea")
+        {
             label_parts.push("(can type here)".to_string());
         }
 
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Where the text matched by `find_field_by_label` came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelSource {
+    /// `<label for>` pointing at the field
+    For,
+    /// `<label>` wrapping the field
+    Wrapping,
+    AriaLabel,
+    AriaLabelledBy,
+    Placeholder,
+    /// Text right before or above a field with no label of its own
+    Proximity,
+}
+
+/// A form field found by its label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabeledField {
+    pub selector: String,
+    pub tag_name: String,
+    /// `type` attribute of inputs
+    pub input_type: Option<String>,
+    pub source: LabelSource,
+    /// Label text as written on the page
+    pub text: String,
+    /// 3 for an exact match, 2 when one text starts with the other, 1 when it contains it
+    pub score: u8,
+}
+
+impl LabeledField {
+    /// `selector (source 'text')`, for error messages
+    pub fn describe(&self) -> String {
+        format!("{} ({:?} '{}')", self.selector, self.source, self.text)
+    }
+}
+
+/// Script listing visible fields whose label text matches `label`.
+///
+/// Texts are compared ignoring case, diacritics, `*` and `:` required markers and
+/// repeated whitespace. When no field's own labels, aria attributes or placeholder
+/// match, fields without any of those are looked up by nearby text. Evaluates to
+/// the best match of every field, in the shape of `LabeledField`.
+pub(crate) fn field_by_label_script(label: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+{describe}
+            const normalize = (text) => (text || '').normalize('NFD').replace(/[\u0300-\u036f]/g, '')
+                .replace(/[*:]/g, ' ').replace(/\s+/g, ' ').trim().toLowerCase();
+            const query = normalize({label});
+            if (!query) return [];
+            const score = (text) => {{
+                const value = normalize(text);
+                if (!value) return 0;
+                if (value === query) return 3;
+                if (value.startsWith(query) || query.startsWith(value)) return 2;
+                if (value.includes(query) || query.includes(value)) return 1;
+                return 0;
+            }};
+            const visible = (node) => {{
+                const rect = node.getBoundingClientRect();
+                const style = window.getComputedStyle(node);
+                return rect.width > 0 && rect.height > 0 && style.visibility !== 'hidden';
+            }};
+            const ownText = (node) => {{
+                const copy = node.cloneNode(true);
+                copy.querySelectorAll('input, select, textarea, button').forEach(child => child.remove());
+                return copy.textContent.trim().replace(/\s+/g, ' ');
+            }};
+
+            const fields = Array.from(document.querySelectorAll(
+                'input:not([type="hidden"]):not([type="submit"]):not([type="button"]):not([type="reset"]):not([type="image"]),'
+                + ' textarea, select, [contenteditable="true"], [role="textbox"], [role="combobox"]'
+            )).filter(visible);
+
+            const names = (field) => {{
+                const found = [];
+                for (const label of field.labels || []) {{
+                    found.push({{ source: label.contains(field) ? 'wrapping' : 'for', text: ownText(label) }});
+                }}
+                const aria = field.getAttribute('aria-label');
+                if (aria) found.push({{ source: 'aria_label', text: aria.trim() }});
+                const ids = (field.getAttribute('aria-labelledby') || '').split(/\s+/).filter(Boolean);
+                if (ids.length) {{
+                    const text = ids.map(id => document.getElementById(id))
+                        .filter(Boolean).map(ownText).join(' ');
+                    found.push({{ source: 'aria_labelled_by', text: text }});
+                }}
+                const placeholder = field.getAttribute('placeholder');
+                if (placeholder) found.push({{ source: 'placeholder', text: placeholder.trim() }});
+                return found;
+            }};
+
+            const describe = (field, source, text, value) => ({{
+                selector: cssPath(field),
+                tag_name: field.tagName.toLowerCase(),
+                input_type: field.tagName === 'INPUT' ? (field.getAttribute('type') || 'text') : null,
+                source: source,
+                text: text,
+                score: value
+            }});
+
+            const matches = [];
+            const unlabeled = [];
+            for (const field of fields) {{
+                const candidates = names(field);
+                if (!candidates.some(name => normalize(name.text))) unlabeled.push(field);
+                let best = null;
+                for (const name of candidates) {{
+                    const value = score(name.text);
+                    if (value > 0 && (!best || value > best.score)) best = describe(field, name.source, name.text, value);
+                }}
+                if (best) matches.push(best);
+            }}
+            if (matches.length > 0 || unlabeled.length === 0) return matches;
+
+            // Nearest unlabeled field right of or below a matching piece of text
+            const texts = Array.from(document.querySelectorAll(
+                'label, span, div, p, td, th, dt, dd, legend, strong, b'
+            )).filter(node => !node.querySelector('input, select, textarea') && visible(node));
+            const nearby = new Map();
+            for (const node of texts) {{
+                const text = ownText(node);
+                if (!text || text.length > 80) continue;
+                const value = score(text);
+                if (value < 2) continue;
+                const from = node.getBoundingClientRect();
+                let nearest = null;
+                let nearestDistance = Infinity;
+                for (const field of unlabeled) {{
+                    const to = field.getBoundingClientRect();
+                    const sameRow = to.top < from.bottom && from.top < to.bottom && to.left >= from.left;
+                    const below = to.top >= from.top && Math.abs(to.left - from.left) < 200;
+                    if (!sameRow && !below) continue;
+                    const distance = Math.hypot(Math.max(0, to.left - from.right), Math.max(0, to.top - from.bottom));
+                    if (distance < nearestDistance && distance < 200) {{
+                        nearest = field;
+                        nearestDistance = distance;
+                    }}
+                }}
+                if (!nearest) continue;
+                const previous = nearby.get(nearest);
+                if (!previous || value > previous.score) nearby.set(nearest, describe(nearest, 'proximity', text, value));
+            }}
+            return Array.from(nearby.values());
+        }})()
+        "#,
+        describe = crate::dom::xpath::DESCRIBE_ELEMENT_JS,
+        label = serde_json::to_string(label).unwrap_or_else(|_| "''".to_string())
+    )
+}
+
+/// The fields with the best score, each once
+pub(crate) fn best_matches(value: Value) -> Vec<LabeledField> {
+    let fields: Vec<LabeledField> = serde_json::from_value(value).unwrap_or_default();
+    let best = fields.iter().map(|field| field.score).max().unwrap_or(0);
+    let mut kept: Vec<LabeledField> = Vec::new();
+    for field in fields.into_iter().filter(|field| field.score == best) {
+        if !kept.iter().any(|other| other.selector == field.selector) {
+            kept.push(field);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(selector: &str, source: &str, text: &str, score: u8) -> Value {
+        json!({
+            "selector": selector,
+            "tag_name": "input",
+            "input_type": "text",
+            "source": source,
+            "text": text,
+            "score": score
+        })
+    }
+
+    #[test]
+    fn keeps_only_the_best_score() {
+        let matches = best_matches(json!([
+            field("input#email", "for", "Email address:", 3),
+            field("input#email-confirm", "for", "Confirm email address", 1),
+            field("input#phone", "placeholder", "Phone number", 2),
+        ]));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].selector, "input#email");
+        assert_eq!(matches[0].source, LabelSource::For);
+        assert_eq!(matches[0].text, "Email address:");
+    }
+
+    #[test]
+    fn ties_are_all_kept_once_each() {
+        let matches = best_matches(json!([
+            field("input#billing-street", "for", "Street", 3),
+            field("input#shipping-street", "for", "Street", 3),
+            field("input#billing-street", "aria_label", "Street", 3),
+        ]));
+        let selectors: Vec<&str> = matches.iter().map(|m| m.selector.as_str()).collect();
+        assert_eq!(selectors, ["input#billing-street", "input#shipping-street"]);
+        assert_eq!(
+            matches[1].describe(),
+            "input#shipping-street (For 'Street')"
+        );
+    }
+
+    #[test]
+    fn sources_parse_from_the_script() {
+        let sources = [
+            ("for", LabelSource::For),
+            ("wrapping", LabelSource::Wrapping),
+            ("aria_label", LabelSource::AriaLabel),
+            ("aria_labelled_by", LabelSource::AriaLabelledBy),
+            ("placeholder", LabelSource::Placeholder),
+            ("proximity", LabelSource::Proximity),
+        ];
+        for (name, source) in sources {
+            let matches = best_matches(json!([field("input#field", name, "Name", 3)]));
+            assert_eq!(matches[0].source, source, "{}", name);
+        }
+    }
+
+    #[test]
+    fn nothing_matches_without_fields() {
+        assert!(best_matches(json!([])).is_empty());
+        assert!(best_matches(Value::Null).is_empty());
+    }
+
+    #[test]
+    fn script_embeds_the_label_as_a_string() {
+        let script = field_by_label_script("Nom d'utilisateur \"*\"");
+        assert!(script.contains(r#"const query = normalize("Nom d'utilisateur \"*\"");"#));
+    }
+}
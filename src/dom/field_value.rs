@@ -0,0 +1,158 @@
+use crate::dom::{DomElement, OptionInfo};
+use serde::{Deserialize, Serialize};
+
+/// What a form field contains right now, rather than what its markup says
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldValue {
+    pub tag_name: String,
+    /// `type` of an input, e.g. `checkbox`
+    #[serde(default)]
+    pub input_type: Option<String>,
+    /// `value` of an input, textarea or select; the first selected option's value
+    /// for a select
+    #[serde(default)]
+    pub value: String,
+    /// Checked state of a checkbox or radio
+    #[serde(default)]
+    pub checked: Option<bool>,
+    /// Values of every selected option of a select
+    #[serde(default)]
+    pub selected_options: Vec<String>,
+    /// Every option of a select
+    #[serde(default)]
+    pub options: Vec<OptionInfo>,
+    /// Whether `value` is masked, because the field is a password or was filled
+    /// by `type_secret`
+    #[serde(default)]
+    pub secret: bool,
+}
+
+impl FieldValue {
+    /// Whether the field's content must never leave the page
+    pub fn is_secret(&self) -> bool {
+        self.secret || self.input_type.as_deref() == Some("password")
+    }
+
+    pub(crate) fn apply_to(&self, element: &mut DomElement) {
+        if self.is_secret() {
+            // The markup value of a password field is as secret as the live one
+            let masked = |value: &str| {
+                if value.is_empty() {
+                    String::new()
+                } else {
+                    MASKED_VALUE.to_string()
+                }
+            };
+            if let Some(value) = element.attributes.get_mut("value") {
+                *value = masked(value);
+            }
+            element.current_value = Some(masked(&self.value));
+        } else {
+            element.current_value = Some(self.value.clone());
+        }
+        element.checked = self.checked;
+        element.selected_options = self.selected_options.clone();
+        element.options = self.options.clone();
+    }
+}
+
+/// Stands in for the value of a password field or one filled by `type_secret`
+pub(crate) const MASKED_VALUE: &str = "[REDACTED]";
+
+/// Fields whose live value is read, in document order
+pub(crate) const FIELD_SELECTOR: &str = "input, textarea, select";
+
+/// `fieldValue(element)`, evaluating to the shape of `FieldValue`, and
+/// `optionInfo(option)`, to that of `OptionInfo`
+pub(crate) const FIELD_VALUE_JS: &str = r#"
+            const optionInfo = (option) => ({
+                value: option.value,
+                label: (option.label || option.textContent || '').trim(),
+                selected: option.selected,
+                group: option.parentElement && option.parentElement.tagName === 'OPTGROUP'
+                    ? option.parentElement.label
+                    : null
+            });
+            const fieldValue = (element) => {
+                const tagName = element.tagName.toLowerCase();
+                const inputType = tagName === 'input' ? (element.type || 'text').toLowerCase() : null;
+                const checkable = inputType === 'checkbox' || inputType === 'radio';
+                // Passwords and whatever `type_secret` filled are masked before
+                // they leave the page
+                const secret = inputType === 'password' || element.__surfaiSecret === true;
+                const value = element.value == null ? '' : String(element.value);
+                const selected = tagName === 'select'
+                    ? Array.from(element.selectedOptions || []).map(option => option.value)
+                    : [];
+                return {
+                    tag_name: tagName,
+                    input_type: inputType,
+                    value: secret ? (value ? '[REDACTED]' : '') : value,
+                    checked: checkable ? !!element.checked : null,
+                    selected_options: selected,
+                    options: tagName === 'select' ? Array.from(element.options).map(optionInfo) : [],
+                    secret: secret
+                };
+            };
+"#;
+
+/// Script reading the live value of the first element matching `selector`, or
+/// `null` when nothing matches
+pub(crate) fn field_value_script(selector: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+{field_value}
+            const element = document.querySelector({selector});
+            return element ? fieldValue(element) : null;
+        }})()
+        "#,
+        field_value = FIELD_VALUE_JS,
+        selector = serde_json::to_string(selector).unwrap_or_else(|_| "''".to_string())
+    )
+}
+
+/// Script marking the element `element` evaluates to as holding a secret, so
+/// `fieldValue` masks it from then on. Evaluates to whether it was found.
+pub(crate) fn mark_secret_script(element: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            const element = {element};
+            if (!element) return false;
+            element.__surfaiSecret = true;
+            return true;
+        }})()
+        "#,
+        element = element
+    )
+}
+
+/// Script choosing the option of the select `element` evaluates to whose value
+/// or text matches `value`, ignoring case, diacritics and spacing. Evaluates to
+/// the chosen option's `OptionInfo`, or `null` when the select or the option is
+/// missing.
+pub(crate) fn select_option_script(element: &str, value: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+{field_value}
+            const normalize = (text) => (text || '').normalize('NFD').replace(/[\u0300-\u036f]/g, '')
+                .replace(/\s+/g, ' ').trim().toLowerCase();
+            const select = {element};
+            if (!select || !select.options) return null;
+            const wanted = normalize({value});
+            const option = Array.from(select.options)
+                .find(option => normalize(option.value) === wanted || normalize(option.textContent) === wanted);
+            if (!option) return null;
+            select.value = option.value;
+            select.dispatchEvent(new Event('input', {{ bubbles: true }}));
+            select.dispatchEvent(new Event('change', {{ bubbles: true }}));
+            return optionInfo(option);
+        }})()
+        "#,
+        field_value = FIELD_VALUE_JS,
+        element = element,
+        value = serde_json::to_string(value).unwrap_or_else(|_| "''".to_string())
+    )
+}
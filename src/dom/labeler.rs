@@ -0,0 +1,523 @@
+use crate::dom::{DomElement, DomState, LinkKind};
+use crate::errors::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Page an element is labeled on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PageContext {
+    pub url: String,
+    pub title: String,
+}
+
+impl From<&DomState> for PageContext {
+    fn from(state: &DomState) -> Self {
+        Self {
+            url: state.url.clone(),
+            title: state.title.clone(),
+        }
+    }
+}
+
+/// What a labeler says about one element
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElementAnnotation {
+    /// Short name, e.g. the aria-label or placeholder
+    pub label: Option<String>,
+    pub description: String,
+    /// How an agent should interact with the element
+    pub instructions: String,
+    /// 0.0 to 1.0; higher elements are kept first when a list is truncated
+    pub importance: f64,
+}
+
+/// Produces the label, description, instructions and importance of the elements
+/// `get_ai_elements` returns.
+///
+/// Implement `label` for per-element rules, or override `label_all` to annotate a
+/// whole page at once, e.g. with a single request to a language model.
+#[async_trait]
+pub trait ElementLabeler: Send + Sync {
+    fn label(&self, element: &DomElement, page: &PageContext) -> ElementAnnotation;
+
+    /// Annotate `elements` in order; the result must have one entry per element
+    async fn label_all(
+        &self,
+        elements: &[DomElement],
+        page: &PageContext,
+    ) -> Result<Vec<ElementAnnotation>> {
+        Ok(elements
+            .iter()
+            .map(|element| self.label(element, page))
+            .collect())
+    }
+}
+
+/// The built-in rules, based on tag, input type and attributes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultLabeler;
+
+impl ElementLabeler for DefaultLabeler {
+    fn label(&self, element: &DomElement, _page: &PageContext) -> ElementAnnotation {
+        ElementAnnotation {
+            label: element_label(element),
+            description: element_description(element),
+            instructions: element_instructions(element),
+            importance: element_importance(element),
+        }
+    }
+}
+
+fn input_type(element: &DomElement) -> &str {
+    element
+        .attributes
+        .get("type")
+        .map(|s| s.as_str())
+        .unwrap_or("text")
+}
+
+/// Coarse kind of an element, e.g. `text_input`, `button` or `link`
+pub(crate) fn element_type(element: &DomElement) -> String {
+    match element.tag_name.as_str() {
+        "input" => match input_type(element) {
+            "text" | "email" | "password" | "search" | "url" | "tel" => "text_input".to_string(),
+            "checkbox" => "checkbox".to_string(),
+            "radio" => "radio_button".to_string(),
+            "submit" | "button" => "button".to_string(),
+            "file" => "file_upload".to_string(),
+            other => format!("input_{}", other),
+        },
+        "textarea" => "text_area".to_string(),
+        "select" => "dropdown".to_string(),
+        "button" => "button".to_string(),
+        "a" => "link".to_string(),
+        "canvas" => "canvas".to_string(),
+        "svg" if !element.is_clickable => "graphic".to_string(),
+        _ => {
+            if element.is_clickable {
+                "clickable_element".to_string()
+            } else {
+                "text_element".to_string()
+            }
+        }
+    }
+}
+
+/// Actions an element supports, e.g. `clickable` or `can_select_options`
+pub(crate) fn element_capabilities(element: &DomElement) -> Vec<String> {
+    let mut capabilities = Vec::new();
+
+    // Disabled controls take no input at all
+    if !element.is_enabled() {
+        return capabilities;
+    }
+
+    if element.is_clickable {
+        capabilities.push("clickable".to_string());
+    }
+
+    if element.is_interactable && !element.is_readonly {
+        capabilities.push("can_receive_text_input".to_string());
+    }
+
+    if matches!(element.tag_name.as_str(), "select") {
+        capabilities.push("can_select_options".to_string());
+    }
+
+    if matches!(element.tag_name.as_str(), "input") {
+        if let Some(input_type) = element.attributes.get("type") {
+            match input_type.as_str() {
+                "checkbox" => capabilities.push("can_check_uncheck".to_string()),
+                "radio" => capabilities.push("can_select".to_string()),
+                "file" => capabilities.push("can_upload_files".to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    capabilities
+}
+
+fn element_label(element: &DomElement) -> Option<String> {
+    if let Some(label) = &element.field_label {
+        return Some(label.clone());
+    }
+
+    for attribute in ["aria-label", "title"] {
+        if let Some(value) = element.attributes.get(attribute) {
+            return Some(value.clone());
+        }
+    }
+
+    if let Some(graphic) = &element.graphic_label {
+        return Some(graphic.clone());
+    }
+
+    for attribute in ["placeholder", "name"] {
+        if let Some(value) = element.attributes.get(attribute) {
+            return Some(value.clone());
+        }
+    }
+
+    if let Some(text) = &element.text_content {
+        if !text.trim().is_empty() && text.len() < 100 {
+            return Some(text.clone());
+        }
+    }
+
+    None
+}
+
+fn element_description(element: &DomElement) -> String {
+    let mut description_parts = Vec::new();
+
+    description_parts.push(format!(
+        "A {} element",
+        element_type(element).replace("_", " ")
+    ));
+
+    if let Some(label) = element_label(element) {
+        description_parts.push(format!("labeled '{}'", label));
+    }
+
+    if let Some(id) = &element.element_id {
+        description_parts.push(format!("with ID '{}'", id));
+    }
+
+    if !element.is_enabled() {
+        description_parts.push("that is currently disabled".to_string());
+        return description_parts.join(" ");
+    }
+    if element.is_readonly {
+        description_parts.push("that is read-only".to_string());
+        return description_parts.join(" ");
+    }
+
+    match element.tag_name.as_str() {
+        "input" => match input_type(element) {
+            "search" => description_parts.push("for entering search queries".to_string()),
+            "email" => description_parts.push("for entering email addresses".to_string()),
+            "password" => description_parts.push("for entering passwords".to_string()),
+            "submit" => description_parts.push("for submitting forms".to_string()),
+            _ => description_parts.push("for text input".to_string()),
+        },
+        "textarea" => description_parts.push("for multi-line text input".to_string()),
+        "select" => match element.options.iter().find(|option| option.selected) {
+            Some(selected) => description_parts.push(format!(
+                "for selecting from {} options, '{}' selected",
+                element.options.len(),
+                selected.label
+            )),
+            None => description_parts.push("for selecting from options".to_string()),
+        },
+        "button" => description_parts.push("that can be clicked".to_string()),
+        "a" | "area" => {
+            let href = element
+                .resolved_href
+                .as_ref()
+                .or_else(|| element.attributes.get("href"));
+            let target = |href: &str| {
+                href.split_once(':')
+                    .map(|(_, target)| target.split('?').next().unwrap_or_default().to_string())
+                    .unwrap_or_default()
+            };
+            match (element.link_kind, href) {
+                (Some(LinkKind::External), Some(href)) => {
+                    description_parts.push(format!("linking off-site to '{}'", href))
+                }
+                (Some(LinkKind::Anchor), Some(href)) => description_parts.push(format!(
+                    "pointing to '#{}' on this page",
+                    href.rsplit_once('#')
+                        .map(|(_, fragment)| fragment)
+                        .unwrap_or_default()
+                )),
+                (Some(LinkKind::Mailto), Some(href)) => {
+                    description_parts.push(format!("that starts an email to '{}'", target(href)))
+                }
+                (Some(LinkKind::Tel), Some(href)) => {
+                    description_parts.push(format!("that calls '{}'", target(href)))
+                }
+                (Some(LinkKind::Javascript), _) => {
+                    description_parts.push("that runs a script when clicked".to_string())
+                }
+                (_, Some(href)) => description_parts.push(format!("linking to '{}'", href)),
+                (_, None) => description_parts.push("that can be clicked".to_string()),
+            }
+        }
+        "canvas" => match &element.rect {
+            Some(rect) => description_parts.push(format!(
+                "drawn at {:.0}x{:.0} with no DOM of its own",
+                rect.width, rect.height
+            )),
+            None => description_parts.push("drawn with no DOM of its own".to_string()),
+        },
+        _ => {}
+    }
+
+    description_parts.join(" ")
+}
+
+fn element_instructions(element: &DomElement) -> String {
+    if !element.is_enabled() {
+        return "Currently disabled — look for prerequisites, such as required fields or a checkbox to accept, before using it".to_string();
+    }
+    if element.is_readonly {
+        return "Read-only — its value can be read but not changed".to_string();
+    }
+    match element.tag_name.as_str() {
+        "input" => match input_type(element) {
+            "search" => "Use type_in_element_by_number() to enter search terms, then look for a search button to click or press Enter".to_string(),
+            "text" | "email" | "password" | "url" | "tel" => "Use type_in_element_by_number() to enter text".to_string(),
+            "checkbox" => "Use click_element_by_number() to check/uncheck".to_string(),
+            "radio" => "Use click_element_by_number() to select this option".to_string(),
+            "submit" | "button" => "Use click_element_by_number() to submit the form".to_string(),
+            _ => "Use click_element_by_number() to interact".to_string(),
+        },
+        "textarea" => "Use type_in_element_by_number() to enter multi-line text".to_string(),
+        "select" => {
+            "Use select_option() with the value or text of one of its options".to_string()
+        }
+        "button" => "Use click_element_by_number() to activate this button".to_string(),
+        "a" | "area" => match element.link_kind {
+            Some(LinkKind::External) => "Use click_element_by_number() to follow this link; it leaves the site".to_string(),
+            Some(LinkKind::Anchor) => "In-page anchor — clicking scrolls, does not navigate".to_string(),
+            Some(LinkKind::Mailto) => "Email link — clicking opens a mail client, not a page; read the address instead".to_string(),
+            Some(LinkKind::Tel) => "Phone link — clicking starts a call, not a page load; read the number instead".to_string(),
+            Some(LinkKind::Javascript) => "Use click_element_by_number() to run its script; it may not navigate".to_string(),
+            _ => "Use click_element_by_number() to follow this link".to_string(),
+        },
+        "canvas" => "Take a screenshot and use click_rect() on what it shows inside this element's rect".to_string(),
+        _ => {
+            if element.is_clickable {
+                "Use click_element_by_number() to interact with this element".to_string()
+            } else {
+                "This element contains text content for reference".to_string()
+            }
+        }
+    }
+}
+
+/// Form controls first, then buttons, links and other clickables, then text;
+/// hidden and disabled elements count for less
+fn element_importance(element: &DomElement) -> f64 {
+    let base: f64 = match element_type(element).as_str() {
+        "text_input" | "text_area" | "dropdown" | "file_upload" => 0.9,
+        "button" => 0.8,
+        "checkbox" | "radio_button" => 0.7,
+        "link" => 0.6,
+        "clickable_element" | "canvas" => 0.5,
+        "graphic" => 0.3,
+        "text_element" => 0.2,
+        _ => 0.5,
+    };
+    let labeled = if element_label(element).is_some() {
+        0.05
+    } else {
+        0.0
+    };
+    let visibility = if element.is_visible { 1.0 } else { 0.5 };
+    let usable = if element.is_enabled() { 1.0 } else { 0.5 };
+    ((base + labeled) * visibility * usable).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::OptionInfo;
+
+    fn element(tag: &str, attributes: &[(&str, &str)]) -> DomElement {
+        attributes.iter().fold(
+            DomElement::new(tag.to_string(), format!("el-{}", tag)),
+            |element, (key, value)| element.with_attribute(key.to_string(), value.to_string()),
+        )
+    }
+
+    /// The controls of the `/login` fixture, as extraction reads them
+    fn login_form() -> Vec<DomElement> {
+        let mut username = element("input", &[("type", "text"), ("name", "username")])
+            .set_interactable(true)
+            .set_clickable(true);
+        username.element_id = Some("username".to_string());
+        username.field_label = Some("Username".to_string());
+
+        let mut password = element("input", &[("type", "password"), ("name", "password")])
+            .set_interactable(true)
+            .set_clickable(true);
+        password.element_id = Some("password".to_string());
+        password.field_label = Some("Password".to_string());
+
+        let mut submit = element("button", &[("type", "submit")])
+            .with_text_content("Sign in".to_string())
+            .set_clickable(true);
+        submit.element_id = Some("login-button".to_string());
+
+        let heading = element("h1", &[]).with_text_content("Sign in".to_string());
+        vec![username, password, submit, heading]
+    }
+
+    /// One line per element: label, description, instructions and importance
+    fn snapshot(elements: &[DomElement]) -> String {
+        let page = PageContext::default();
+        elements
+            .iter()
+            .map(|element| {
+                let annotation = DefaultLabeler.label(element, &page);
+                format!(
+                    "{} | {} | {} | {:.3}",
+                    annotation.label.as_deref().unwrap_or("-"),
+                    annotation.description,
+                    annotation.instructions,
+                    annotation.importance
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Controls in the states that change the wording: disabled, read-only,
+    /// hidden, a select with a choice, each kind of link and a canvas
+    fn states() -> Vec<DomElement> {
+        let mut disabled = element("button", &[])
+            .with_text_content("Place order".to_string())
+            .set_clickable(true);
+        disabled.is_disabled = true;
+
+        let mut readonly = element("textarea", &[("name", "notes")]).set_interactable(true);
+        readonly.is_readonly = true;
+
+        let mut country = element("select", &[("name", "country")]).set_interactable(true);
+        country.options = ["France", "Germany"]
+            .iter()
+            .map(|label| OptionInfo {
+                value: label.to_lowercase(),
+                label: label.to_string(),
+                selected: *label == "Germany",
+                group: None,
+            })
+            .collect();
+
+        let mut hidden = element(
+            "input",
+            &[("type", "checkbox"), ("aria-label", "Remember me")],
+        )
+        .set_clickable(true);
+        hidden.is_visible = false;
+
+        let link = |kind: LinkKind, href: &str, text: &str| {
+            let mut link = element("a", &[("href", href)])
+                .with_text_content(text.to_string())
+                .set_clickable(true);
+            link.link_kind = Some(kind);
+            link.resolved_href = Some(href.to_string());
+            link
+        };
+
+        let canvas = element("canvas", &[]).with_rect(crate::dom::ElementRect {
+            x: 0.0,
+            y: 0.0,
+            width: 640.0,
+            height: 480.0,
+        });
+
+        vec![
+            disabled,
+            readonly,
+            country,
+            hidden,
+            link(LinkKind::External, "https://example.org/help", "Help"),
+            link(LinkKind::Anchor, "http://127.0.0.1/links#faq", "FAQ"),
+            link(
+                LinkKind::Mailto,
+                "mailto:support@example.com?subject=Hi",
+                "Email us",
+            ),
+            link(LinkKind::Javascript, "javascript:void(0)", "Open chat"),
+            canvas,
+        ]
+    }
+
+    #[test]
+    fn login_form_snapshot() {
+        assert_eq!(
+            snapshot(&login_form()),
+            "\
+Username | A text input element labeled 'Username' with ID 'username' for text input | Use type_in_element_by_number() to enter text | 0.950
+Password | A text input element labeled 'Password' with ID 'password' for entering passwords | Use type_in_element_by_number() to enter text | 0.950
+Sign in | A button element labeled 'Sign in' with ID 'login-button' that can be clicked | Use click_element_by_number() to activate this button | 0.850
+Sign in | A text element element labeled 'Sign in' | This element contains text content for reference | 0.250"
+        );
+    }
+
+    #[test]
+    fn element_states_snapshot() {
+        assert_eq!(
+            snapshot(&states()),
+            "\
+Place order | A button element labeled 'Place order' that is currently disabled | Currently disabled — look for prerequisites, such as required fields or a checkbox to accept, before using it | 0.425
+notes | A text area element labeled 'notes' that is read-only | Read-only — its value can be read but not changed | 0.950
+country | A dropdown element labeled 'country' for selecting from 2 options, 'Germany' selected | Use select_option() with the value or text of one of its options | 0.950
+Remember me | A checkbox element labeled 'Remember me' for text input | Use click_element_by_number() to check/uncheck | 0.375
+Help | A link element labeled 'Help' linking off-site to 'https://example.org/help' | Use click_element_by_number() to follow this link; it leaves the site | 0.650
+FAQ | A link element labeled 'FAQ' pointing to '#faq' on this page | In-page anchor — clicking scrolls, does not navigate | 0.650
+Email us | A link element labeled 'Email us' that starts an email to 'support@example.com' | Email link — clicking opens a mail client, not a page; read the address instead | 0.650
+Open chat | A link element labeled 'Open chat' that runs a script when clicked | Use click_element_by_number() to run its script; it may not navigate | 0.650
+- | A canvas element drawn at 640x480 with no DOM of its own | Take a screenshot and use click_rect() on what it shows inside this element's rect | 0.500"
+        );
+    }
+
+    #[test]
+    fn element_type_by_tag_and_input_type() {
+        let cases = [
+            (element("input", &[]), "text_input"),
+            (element("input", &[("type", "email")]), "text_input"),
+            (element("input", &[("type", "checkbox")]), "checkbox"),
+            (element("input", &[("type", "radio")]), "radio_button"),
+            (element("input", &[("type", "submit")]), "button"),
+            (element("input", &[("type", "file")]), "file_upload"),
+            (element("input", &[("type", "range")]), "input_range"),
+            (element("textarea", &[]), "text_area"),
+            (element("select", &[]), "dropdown"),
+            (element("a", &[]), "link"),
+            (element("canvas", &[]), "canvas"),
+            (element("svg", &[]), "graphic"),
+            (element("svg", &[]).set_clickable(true), "clickable_element"),
+            (element("div", &[]).set_clickable(true), "clickable_element"),
+            (element("p", &[]), "text_element"),
+        ];
+        for (element, expected) in cases {
+            assert_eq!(element_type(&element), expected, "{:?}", element.attributes);
+        }
+    }
+
+    #[test]
+    fn capabilities_follow_state() {
+        let text = element("input", &[("type", "text")])
+            .set_clickable(true)
+            .set_interactable(true);
+        assert_eq!(
+            element_capabilities(&text),
+            ["clickable", "can_receive_text_input"]
+        );
+
+        let mut readonly = text.clone();
+        readonly.is_readonly = true;
+        assert_eq!(element_capabilities(&readonly), ["clickable"]);
+
+        let mut aria_disabled = text.clone();
+        aria_disabled.is_aria_disabled = true;
+        assert!(element_capabilities(&aria_disabled).is_empty());
+
+        let cases = [
+            (element("select", &[]), "can_select_options"),
+            (
+                element("input", &[("type", "checkbox")]),
+                "can_check_uncheck",
+            ),
+            (element("input", &[("type", "radio")]), "can_select"),
+            (element("input", &[("type", "file")]), "can_upload_files"),
+        ];
+        for (element, expected) in cases {
+            assert_eq!(element_capabilities(&element), [expected]);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use url::Url;
+
+/// Where following a link's `href` takes the agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    /// Another page on the same origin
+    Internal,
+    /// A page on another origin
+    External,
+    /// A fragment of the current document; clicking scrolls instead of navigating
+    Anchor,
+    Mailto,
+    Tel,
+    /// A `javascript:` URL, which runs a script instead of navigating
+    Javascript,
+}
+
+impl LinkKind {
+    /// Resolve `href` against the document's `base` URI and classify it relative
+    /// to the `page` showing it. Returns the absolute URL, which `javascript:`
+    /// links and hrefs that do not parse have none of.
+    pub fn classify(href: &str, base: &Url, page: &Url) -> (Option<String>, LinkKind) {
+        let href = href.trim();
+        let scheme = href
+            .split_once(':')
+            .map(|(scheme, _)| scheme.to_ascii_lowercase())
+            .unwrap_or_default();
+        match scheme.as_str() {
+            "javascript" => return (None, LinkKind::Javascript),
+            "mailto" => return (Some(href.to_string()), LinkKind::Mailto),
+            "tel" => return (Some(href.to_string()), LinkKind::Tel),
+            _ => {}
+        }
+
+        let Ok(resolved) = base.join(href) else {
+            return (None, LinkKind::Internal);
+        };
+        let without_fragment = |url: &Url| {
+            let mut url = url.clone();
+            url.set_fragment(None);
+            url
+        };
+        let kind = if resolved.fragment().is_some()
+            && without_fragment(&resolved) == without_fragment(page)
+        {
+            LinkKind::Anchor
+        } else if resolved.origin() == page.origin() {
+            LinkKind::Internal
+        } else {
+            LinkKind::External
+        };
+        (Some(resolved.to_string()), kind)
+    }
+
+    /// Whether clicking loads another document
+    pub fn navigates(&self) -> bool {
+        matches!(self, LinkKind::Internal | LinkKind::External)
+    }
+}
+
+impl fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LinkKind::Internal => "internal",
+            LinkKind::External => "external",
+            LinkKind::Anchor => "in-page anchor",
+            LinkKind::Mailto => "email",
+            LinkKind::Tel => "phone",
+            LinkKind::Javascript => "script",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_by_href() {
+        let page = Url::parse("https://shop.example/products/list?page=2").unwrap();
+        // As under `<base href="/products/">`, and with no base element
+        let base = Url::parse("https://shop.example/products/").unwrap();
+        let none = page.clone();
+        let cases: &[(&str, &Url, Option<&str>, LinkKind)] = &[
+            // Relative
+            (
+                "item/1",
+                &base,
+                Some("https://shop.example/products/item/1"),
+                LinkKind::Internal,
+            ),
+            (
+                "../about",
+                &base,
+                Some("https://shop.example/about"),
+                LinkKind::Internal,
+            ),
+            (
+                "  /cart  ",
+                &none,
+                Some("https://shop.example/cart"),
+                LinkKind::Internal,
+            ),
+            (
+                "?page=3",
+                &none,
+                Some("https://shop.example/products/list?page=3"),
+                LinkKind::Internal,
+            ),
+            // Fragment
+            (
+                "#reviews",
+                &none,
+                Some("https://shop.example/products/list?page=2#reviews"),
+                LinkKind::Anchor,
+            ),
+            (
+                "list?page=2#top",
+                &base,
+                Some("https://shop.example/products/list?page=2#top"),
+                LinkKind::Anchor,
+            ),
+            // A fragment of the base document, which is another page
+            (
+                "#reviews",
+                &base,
+                Some("https://shop.example/products/#reviews"),
+                LinkKind::Internal,
+            ),
+            (
+                "/products/other#top",
+                &none,
+                Some("https://shop.example/products/other#top"),
+                LinkKind::Internal,
+            ),
+            // javascript:
+            ("javascript:void(0)", &base, None, LinkKind::Javascript),
+            (" JavaScript:openCart()", &none, None, LinkKind::Javascript),
+            // mailto: and tel:
+            (
+                "mailto:help@shop.example?subject=Order",
+                &base,
+                Some("mailto:help@shop.example?subject=Order"),
+                LinkKind::Mailto,
+            ),
+            (
+                "MAILTO:help@shop.example",
+                &none,
+                Some("MAILTO:help@shop.example"),
+                LinkKind::Mailto,
+            ),
+            ("tel:+15550100", &base, Some("tel:+15550100"), LinkKind::Tel),
+            // Cross-origin
+            (
+                "https://pay.example/checkout",
+                &base,
+                Some("https://pay.example/checkout"),
+                LinkKind::External,
+            ),
+            (
+                "//cdn.shop.example/catalog.pdf",
+                &base,
+                Some("https://cdn.shop.example/catalog.pdf"),
+                LinkKind::External,
+            ),
+            (
+                "http://shop.example/products/",
+                &base,
+                Some("http://shop.example/products/"),
+                LinkKind::External,
+            ),
+            (
+                "https://shop.example:8443/",
+                &none,
+                Some("https://shop.example:8443/"),
+                LinkKind::External,
+            ),
+            // Unparseable
+            ("http://[::1", &base, None, LinkKind::Internal),
+        ];
+
+        for (href, base, url, kind) in cases {
+            assert_eq!(
+                LinkKind::classify(href, base, &page),
+                (url.map(str::to_string), *kind),
+                "{} against {}",
+                href,
+                base
+            );
+        }
+    }
+
+    #[test]
+    fn only_page_links_navigate() {
+        assert!(LinkKind::Internal.navigates());
+        assert!(LinkKind::External.navigates());
+        for kind in [
+            LinkKind::Anchor,
+            LinkKind::Mailto,
+            LinkKind::Tel,
+            LinkKind::Javascript,
+        ] {
+            assert!(!kind.navigates(), "{}", kind);
+        }
+    }
+}
@@ -1,7 +1,39 @@
+pub mod accessibility;
+pub mod compact;
+pub mod coordinates;
+pub mod diff;
+pub mod document;
 pub mod element;
+pub mod field_label;
+pub mod field_value;
+pub mod labeler;
+pub mod link;
 pub mod processor;
+pub mod provenance;
+pub mod report;
+pub mod scroll;
+pub mod snapshot;
 pub mod state;
+pub mod stats;
+pub mod text_search;
+pub mod xpath;
 
-pub use element::{DomElement, ElementRect};
-pub use processor::DomProcessor;
+pub use accessibility::{AccessibilityIssue, AccessibilityRule, AuditReport, Severity};
+pub use compact::SerializationProfile;
+pub use coordinates::{CoordinateSpace, Viewport};
+pub use diff::{AttributeChange, DiffEntry, DomDiff};
+pub use document::DocumentKind;
+pub use element::{DomElement, ElementRect, OptionInfo};
+pub use field_label::{LabelSource, LabeledField};
+pub use field_value::FieldValue;
+pub use labeler::{DefaultLabeler, ElementAnnotation, ElementLabeler, PageContext};
+pub use link::LinkKind;
+pub use processor::{DomProcessor, STAMP_ATTRIBUTE};
+pub use provenance::{Provenance, RuleKind};
+pub use report::{LargestImage, PageMetadata, PageReport, PageStats, PerformanceMetrics};
+pub use scroll::ScrollAlignment;
+pub use snapshot::{SnapshotOptions, StructuralEntry, StructuralSnapshot};
 pub use state::DomState;
+pub use stats::{ExtractionStats, SelectorStats};
+pub use text_search::{TextMatch, TextSearchOptions};
+pub use xpath::xpath_literal;
@@ -1,10 +1,118 @@
 use crate::core::config::DomConfig;
-use crate::core::{BrowserTrait, DomProcessorTrait, ElementFilter, SelectorType};
-use crate::dom::{DomElement, DomState};
+use crate::core::{BrowserTrait, DomProcessorTrait, ElementFilter, SelectorType, SiteProfile};
+use crate::dom::field_value::{self, FieldValue};
+use crate::dom::{
+    CoordinateSpace, DomElement, DomState, ElementRect, ExtractionStats, LinkKind, OptionInfo,
+    Provenance, RuleKind, Viewport,
+};
 use crate::errors::Result;
 use async_trait::async_trait;
 use scraper::{ElementRef, Html, Selector};
-use std::collections::HashMap;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use url::Url;
+
+/// Attribute `DomConfig::stamp_elements` gives each interactive element
+pub const STAMP_ATTRIBUTE: &str = "data-surfai-id";
+
+/// Characters of descendant text kept for a container without text of its own
+pub const TEXT_PREVIEW_CHARS: usize = 100;
+
+/// Comprehensive list of interactive element selectors
+const INTERACTIVE_SELECTORS: &[&str] = &[
+    // Standard form elements
+    "input",
+    "button",
+    "select",
+    "textarea",
+    "label",
+    "fieldset",
+    "legend",
+    "datalist",
+    // Links and navigation
+    "a",
+    "area",
+    // Interactive content
+    "details",
+    "summary",
+    "dialog",
+    "menu",
+    "menuitem",
+    // Media controls
+    "audio[controls]",
+    "video[controls]",
+    // Graphics; decorative SVGs and icons inside controls are dropped again
+    "svg",
+    "canvas",
+    // Custom interactive elements
+    "[onclick]",
+    "[onchange]",
+    "[onsubmit]",
+    "[onkeydown]",
+    "[onkeyup]",
+    "[onfocus]",
+    "[onblur]",
+    // ARIA roles
+    "[role='button']",
+    "[role='link']",
+    "[role='checkbox']",
+    "[role='radio']",
+    "[role='textbox']",
+    "[role='searchbox']",
+    "[role='combobox']",
+    "[role='listbox']",
+    "[role='tab']",
+    "[role='tabpanel']",
+    "[role='menuitem']",
+    "[role='menubar']",
+    "[role='menu']",
+    "[role='dialog']",
+    "[role='alertdialog']",
+    "[role='tooltip']",
+    "[role='slider']",
+    "[role='spinbutton']",
+    "[role='progressbar']",
+    "[role='switch']",
+    "[role='tree']",
+    "[role='grid']",
+    "[role='gridcell']",
+    // Accessibility attributes
+    "[tabindex]",
+    "[aria-expanded]",
+    "[aria-haspopup]",
+    "[aria-controls]",
+    "[aria-owns]",
+    "[draggable='true']",
+    "[contenteditable='true']",
+    // Google-specific and common website patterns
+    "[data-ved]",
+    "[jsaction]",
+    "[data-testid]",
+    "[data-cy]",
+    "[data-test]",
+    "[data-automation]",
+    "[id*='search']",
+    "[name*='search']",
+    "[class*='search']",
+    "[placeholder*='search']",
+    "[aria-label*='search']",
+    "[title*='search']",
+    // Common interactive classes
+    ".btn",
+    ".button",
+    ".link",
+    ".clickable",
+    ".interactive",
+    ".control",
+    ".input",
+    ".field",
+    ".search",
+    // Elements that might contain clickable children
+    "[data-href]",
+    "[data-url]",
+    "[data-link]",
+];
 
 pub struct DomProcessor {
     config: DomConfig,
@@ -24,19 +132,63 @@ impl DomProcessorTrait for DomProcessor {
         tab: &B::TabHandle,
         include_screenshot: bool,
     ) -> Result<DomState> {
+        let mut stats = self.config.collect_stats.then(|| ExtractionStats {
+            // URL, title and the snapshot script
+            round_trips: 3,
+            ..ExtractionStats::default()
+        });
         let url = browser.get_url(tab).await?;
         let title = browser.get_title(tab).await?;
-
-        // Get HTML content
-        let html_content = browser
-            .execute_script(tab, "document.documentElement.outerHTML")
+        let profile = SiteProfile::resolve(&self.config.site_profiles, &url);
+
+        // Stamp the elements extraction will pick up in the same round trip, so
+        // the markup already carries the stamps
+        let stamped: Vec<&str> = if self.config.stamp_elements {
+            INTERACTIVE_SELECTORS
+                .iter()
+                .copied()
+                .chain(
+                    profile
+                        .iter()
+                        .flat_map(|profile| profile.extra_selectors.iter().map(String::as_str)),
+                )
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Get HTML content, the live values of form fields and the viewport
+        let snapshot = browser
+            .execute_script(
+                tab,
+                &snapshot_script(&stamped, &self.config.stamp_exclusions),
+            )
             .await?;
-        let html_str = html_content.as_str().unwrap_or("");
+        let html_str = snapshot.get("html").and_then(|v| v.as_str()).unwrap_or("");
+        let fields: Vec<FieldValue> = snapshot
+            .get("fields")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let rendered: Option<Rendered> = snapshot
+            .get("rendered")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
 
         let mut dom_state = DomState::new(url, title);
+        dom_state.viewport = snapshot
+            .get("viewport")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
 
         // Extract elements using multiple methods
-        let mut elements = self.extract_all_interactive_elements(html_str).await?;
+        let mut elements = self
+            .extract_all_interactive_elements(
+                html_str,
+                &dom_state.url,
+                &fields,
+                rendered.as_ref(),
+                profile.as_ref(),
+                stats.as_mut(),
+            )
+            .await?;
 
         // Add AI labels if enabled
         if self.config.enable_ai_labels {
@@ -49,11 +201,22 @@ impl DomProcessorTrait for DomProcessor {
         }
 
         if include_screenshot {
+            let space = browser
+                .execute_script(tab, CoordinateSpace::CAPTURE_JS)
+                .await?;
+            dom_state.set_coordinate_space(CoordinateSpace::from_value(&space));
             let screenshot_bytes = browser.take_screenshot(tab).await?;
             let screenshot_base64 = base64::encode(screenshot_bytes);
             dom_state.set_screenshot(screenshot_base64);
+            if let Some(stats) = stats.as_mut() {
+                stats.round_trips += 2;
+            }
         }
 
+        if let Some(stats) = stats.as_mut() {
+            stats.hidden = dom_state.elements.iter().filter(|e| !e.is_visible).count();
+        }
+        dom_state.stats = stats;
         Ok(dom_state)
     }
 
@@ -67,7 +230,8 @@ impl DomProcessorTrait for DomProcessor {
     }
 
     async fn add_ai_labels(&self, elements: &mut Vec<DomElement>) -> Result<()> {
-        for element in elements.iter_mut() {
+        // Labels given by a site profile stay
+        for element in elements.iter_mut().filter(|e| e.ai_label.is_none()) {
             element.generate_ai_label();
         }
         Ok(())
@@ -80,49 +244,7 @@ impl DomProcessorTrait for DomProcessor {
     ) -> Vec<DomElement> {
         elements
             .iter()
-            .filter(|element| {
-                if let Some(ref tag_names) = criteria.tag_names {
-                    if !tag_names.contains(&element.tag_name) {
-                        return false;
-                    }
-                }
-
-                if let Some(ref text) = criteria.has_text {
-                    if let Some(ref element_text) = element.text_content {
-                        if !element_text.to_lowercase().contains(&text.to_lowercase()) {
-                            return false;
-                        }
-                    } else {
-                        return false;
-                    }
-                }
-
-                if let Some(visible) = criteria.is_visible {
-                    if element.is_visible != visible {
-                        return false;
-                    }
-                }
-
-                if let Some(interactive) = criteria.is_interactive {
-                    if element.is_interactable != interactive {
-                        return false;
-                    }
-                }
-
-                if let Some((ref attr_name, ref attr_value)) = criteria.has_attribute {
-                    if let Some(element_attr_value) = element.attributes.get(attr_name) {
-                        if let Some(ref expected_value) = attr_value {
-                            if element_attr_value != expected_value {
-                                return false;
-                            }
-                        }
-                    } else {
-                        return false;
-                    }
-                }
-
-                true
-            })
+            .filter(|element| criteria.matches(element))
             .cloned()
             .collect()
     }
@@ -143,112 +265,153 @@ impl DomProcessorTrait for DomProcessor {
 }
 
 impl DomProcessor {
-    async fn extract_all_interactive_elements(&self, html: &str) -> Result<Vec<DomElement>> {
+    async fn extract_all_interactive_elements(
+        &self,
+        html: &str,
+        url: &str,
+        fields: &[FieldValue],
+        rendered: Option<&Rendered>,
+        profile: Option<&SiteProfile>,
+        mut stats: Option<&mut ExtractionStats>,
+    ) -> Result<Vec<DomElement>> {
+        let started = Instant::now();
         let document = Html::parse_document(html);
+        if let Some(stats) = stats.as_deref_mut() {
+            stats.parse_ms = started.elapsed().as_secs_f64() * 1000.0;
+        }
+        let started = Instant::now();
         let mut elements = Vec::new();
+        let profile = profile.cloned().unwrap_or_default();
+        let select_all = |selector: &str| -> Vec<ElementRef> {
+            Selector::parse(selector)
+                .map(|selector| document.select(&selector).collect())
+                .unwrap_or_default()
+        };
+
+        // A site profile can narrow extraction to one container and leave noisy
+        // ones out
+        let scope = profile
+            .root
+            .as_deref()
+            .and_then(|root| select_all(root).into_iter().next())
+            .unwrap_or_else(|| document.root_element());
+        let excluded: HashSet<_> = profile
+            .excluded_selectors
+            .iter()
+            .flat_map(|selector| select_all(selector))
+            .map(|element| element.id())
+            .collect();
+        let is_excluded = |element_ref: &ElementRef| {
+            excluded.contains(&element_ref.id())
+                || element_ref
+                    .ancestors()
+                    .any(|ancestor| excluded.contains(&ancestor.id()))
+        };
+        // Links resolve against the first `<base href>`, as in the browser
+        let page_url = Url::parse(url).ok();
+        let base_url = page_url.as_ref().map(|page| {
+            Selector::parse("base[href]")
+                .ok()
+                .and_then(|selector| document.select(&selector).next())
+                .and_then(|base| base.value().attr("href"))
+                .and_then(|href| page.join(href.trim()).ok())
+                .unwrap_or_else(|| page.clone())
+        });
+        let field_labels: HashMap<_, _> = field_labels(&document)
+            .into_iter()
+            .map(|(field, label)| (field.id(), label))
+            .collect();
+        let mut hinted_labels = HashMap::new();
+        for (selector, label) in &profile.labels {
+            for element in select_all(selector) {
+                hinted_labels.insert(element.id(), label.clone());
+            }
+        }
+        let extra: HashSet<_> = profile
+            .extra_selectors
+            .iter()
+            .flat_map(|selector| select_all(selector))
+            .map(|element| element.id())
+            .collect();
+
+        // Live values come in document order; pair them with the parsed fields
+        // unless the two disagree on how many there are
+        let mut live_values = HashMap::new();
+        if let Ok(selector) = Selector::parse(field_value::FIELD_SELECTOR) {
+            let parsed: Vec<_> = document.select(&selector).map(|field| field.id()).collect();
+            if parsed.len() == fields.len() {
+                live_values.extend(parsed.into_iter().zip(fields));
+            }
+        }
+        // Whether the browser renders each element, under the same condition;
+        // markup heuristics decide when the two disagree
+        let mut not_rendered = None;
+        if let (Some(rendered), Ok(selector)) = (rendered, Selector::parse("*")) {
+            let parsed: Vec<_> = document
+                .select(&selector)
+                .map(|element| element.id())
+                .collect();
+            if parsed.len() == rendered.count {
+                not_rendered = Some(
+                    rendered
+                        .hidden
+                        .iter()
+                        .filter_map(|&index| parsed.get(index).copied())
+                        .collect::<HashSet<_>>(),
+                );
+            }
+        }
+        // Canvas boxes likewise, so vision-based clicks know where to aim
+        let mut canvas_rects = HashMap::new();
+        if let (Some(rendered), Ok(selector)) = (rendered, Selector::parse("canvas")) {
+            let parsed: Vec<_> = document
+                .select(&selector)
+                .map(|canvas| canvas.id())
+                .collect();
+            if parsed.len() == rendered.canvases.len() {
+                canvas_rects.extend(parsed.into_iter().zip(&rendered.canvases));
+            }
+        }
+        let is_visible =
+            |element_ref: &ElementRef, attributes: &HashMap<String, String>| match &not_rendered {
+                Some(hidden) => !hidden.contains(&element_ref.id()),
+                None => !self.is_hidden_element(attributes),
+            };
+        let visibility = |visible: bool| match (&not_rendered, visible) {
+            (Some(_), true) => "rendered by the browser",
+            (Some(_), false) => "not rendered by the browser",
+            (None, true) => "no hiding attribute or class in the markup",
+            (None, false) => "hidden by an attribute or class in the markup",
+        };
         let mut element_counter = 0;
-        let mut processed_elements = std::collections::HashSet::new();
-
-        // Comprehensive list of interactive element selectors
-        let interactive_selectors = vec![
-            // Standard form elements
-            "input",
-            "button",
-            "select",
-            "textarea",
-            "label",
-            "fieldset",
-            "legend",
-            "optgroup",
-            "option",
-            "datalist",
-            // Links and navigation
-            "a",
-            "area",
-            // Interactive content
-            "details",
-            "summary",
-            "dialog",
-            "menu",
-            "menuitem",
-            // Media controls
-            "audio[controls]",
-            "video[controls]",
-            // Custom interactive elements
-            "[onclick]",
-            "[onchange]",
-            "[onsubmit]",
-            "[onkeydown]",
-            "[onkeyup]",
-            "[onfocus]",
-            "[onblur]",
-            // ARIA roles
-            "[role='button']",
-            "[role='link']",
-            "[role='checkbox']",
-            "[role='radio']",
-            "[role='textbox']",
-            "[role='searchbox']",
-            "[role='combobox']",
-            "[role='listbox']",
-            "[role='tab']",
-            "[role='tabpanel']",
-            "[role='menuitem']",
-            "[role='menubar']",
-            "[role='menu']",
-            "[role='dialog']",
-            "[role='alertdialog']",
-            "[role='tooltip']",
-            "[role='slider']",
-            "[role='spinbutton']",
-            "[role='progressbar']",
-            "[role='switch']",
-            "[role='tree']",
-            "[role='grid']",
-            "[role='gridcell']",
-            // Accessibility attributes
-            "[tabindex]",
-            "[aria-expanded]",
-            "[aria-haspopup]",
-            "[aria-controls]",
-            "[aria-owns]",
-            "[draggable='true']",
-            "[contenteditable='true']",
-            // Google-specific and common website patterns
-            "[data-ved]",
-            "[jsaction]",
-            "[data-testid]",
-            "[data-cy]",
-            "[data-test]",
-            "[data-automation]",
-            "[id*='search']",
-            "[name*='search']",
-            "[class*='search']",
-            "[placeholder*='search']",
-            "[aria-label*='search']",
-            "[title*='search']",
-            // Common interactive classes
-            ".btn",
-            ".button",
-            ".link",
-            ".clickable",
-            ".interactive",
-            ".control",
-            ".input",
-            ".field",
-            ".search",
-            // Elements that might contain clickable children
-            "[data-href]",
-            "[data-url]",
-            "[data-link]",
-        ];
-
-        // Process each selector
-        for selector_str in &interactive_selectors {
+        // Element each tag-and-attributes key was first seen on
+        let mut processed_elements = HashMap::new();
+        // Index in `elements` of each extracted element, kept when explaining
+        let mut extracted = HashMap::new();
+        let (mut duplicates, mut excluded) = (0, 0);
+
+        // Process each selector, then the site profile's own
+        let selectors = INTERACTIVE_SELECTORS
+            .iter()
+            .copied()
+            .chain(profile.extra_selectors.iter().map(String::as_str));
+        for (rule_index, selector_str) in selectors.enumerate() {
             if let Ok(selector) = Selector::parse(selector_str) {
-                for element_ref in document.select(&selector) {
+                let (mut matched, added_before) = (0, elements.len());
+                for element_ref in scope.select(&selector) {
+                    matched += 1;
+                    if is_excluded(&element_ref) {
+                        excluded += 1;
+                        continue;
+                    }
                     let element = element_ref.value();
 
+                    // Options belong to their select's `options`, whatever else
+                    // they match
+                    if in_list(&element_ref) {
+                        continue;
+                    }
+
                     // Create a unique identifier for this element to avoid duplicates
                     let element_id = format!(
                         "{}_{}",
@@ -260,23 +423,60 @@ impl DomProcessor {
                             .join("_")
                     );
 
-                    if processed_elements.contains(&element_id) {
+                    if let Some(first) = processed_elements.get(&element_id) {
+                        duplicates += 1;
+                        if let Some(provenance) = extracted
+                            .get(first)
+                            .and_then(|&index| elements.get_mut(index))
+                            .and_then(|element: &mut DomElement| element.provenance.as_mut())
+                        {
+                            if *first == element_ref.id() {
+                                provenance.also_matched.push(selector_str.to_string());
+                            } else {
+                                provenance.lookalikes_dropped += 1;
+                            }
+                        }
                         continue;
                     }
-                    processed_elements.insert(element_id);
+                    processed_elements.insert(element_id, element_ref.id());
 
                     let mut attributes = HashMap::new();
                     for (name, value) in element.attrs() {
                         attributes.insert(name.to_string(), value.to_string());
                     }
 
-                    // Get text content (both direct text and inner text)
-                    let text_content = element_ref.text().collect::<Vec<_>>().join(" ");
-                    let text_content = if text_content.trim().is_empty() {
-                        None
+                    // A select's text would be every option's
+                    let text = element_text(&element_ref);
+                    let text_content = Some(text.text.clone())
+                        .filter(|text| !text.is_empty() && element.name() != "select");
+
+                    // An icon drawn inside a button or link is part of that control
+                    let graphic = in_svg(&element_ref);
+                    if graphic && enclosing_control(&element_ref).is_some() {
+                        continue;
+                    }
+                    let graphic_label = if graphic {
+                        svg_label(&element_ref)
+                    } else if text_content.is_none()
+                        && is_control(&element_ref)
+                        && !attributes.contains_key("aria-label")
+                        && !attributes.contains_key("title")
+                    {
+                        element_ref
+                            .descendants()
+                            .filter_map(ElementRef::wrap)
+                            .filter(|child| child.value().name() == "svg")
+                            .find_map(|svg| svg_label(&svg))
                     } else {
-                        Some(text_content.trim().to_string())
+                        None
                     };
+                    if element.name() == "svg"
+                        && graphic_label.is_none()
+                        && !attributes.contains_key("role")
+                        && !self.is_clickable_element(&element_ref)
+                    {
+                        continue;
+                    }
 
                     element_counter += 1;
                     let id = format!("elem_{}", element_counter);
@@ -303,15 +503,68 @@ impl DomProcessor {
                         self.generate_css_selector_for_element(&element_ref, &attributes);
 
                     // Determine interaction capabilities
+                    let extra_widget = extra
+                        .contains(&element_ref.id())
+                        .then_some("site profile extra selector");
+                    let clickable = extra_widget.or_else(|| self.clickable_reason(&element_ref));
+                    let interactable =
+                        extra_widget.or_else(|| self.interactable_reason(&element_ref));
                     dom_element = dom_element
-                        .set_clickable(self.is_clickable_element(&element_ref))
-                        .set_interactable(self.is_interactable_element(&element_ref));
+                        .set_clickable(clickable.is_some())
+                        .set_interactable(interactable.is_some());
+                    dom_element.ai_label = hinted_labels.get(&element_ref.id()).cloned();
+                    dom_element.field_label = field_labels.get(&element_ref.id()).cloned();
+                    dom_element.graphic_label = graphic_label;
+                    if let (Some(href), Some(page), Some(base), "a" | "area") =
+                        (attributes.get("href"), &page_url, &base_url, element.name())
+                    {
+                        let (resolved, kind) = LinkKind::classify(href, base, page);
+                        dom_element.resolved_href = resolved;
+                        dom_element.link_kind = Some(kind);
+                    }
+                    dom_element.own_text_len = text.own_len;
+                    dom_element.full_text_len = text.full_len;
+                    dom_element.is_disabled = is_disabled(&element_ref);
+                    dom_element.is_readonly = matches!(element.name(), "input" | "textarea")
+                        && element.attr("readonly").is_some();
+                    dom_element.is_aria_disabled = element_ref
+                        .ancestors()
+                        .filter_map(ElementRef::wrap)
+                        .chain(std::iter::once(element_ref))
+                        .any(|node| node.value().attr("aria-disabled") == Some("true"));
+                    dom_element.rect = canvas_rects
+                        .get(&element_ref.id())
+                        .map(|&rect| rect.clone());
+
+                    dom_element.is_visible = is_visible(&element_ref, &attributes);
+                    if self.config.explain {
+                        extracted.insert(element_ref.id(), elements.len());
+                        dom_element.provenance = Some(Provenance {
+                            rule: selector_str.to_string(),
+                            rule_kind: if rule_index < INTERACTIVE_SELECTORS.len() {
+                                RuleKind::Interactive
+                            } else {
+                                RuleKind::SiteProfile
+                            },
+                            clickable_because: clickable.map(str::to_string),
+                            interactable_because: interactable.map(str::to_string),
+                            visibility: visibility(dom_element.is_visible).to_string(),
+                            ..Provenance::default()
+                        });
+                    }
 
-                    // Set visibility (basic check)
-                    dom_element.is_visible = !self.is_hidden_element(&attributes);
+                    if let Some(value) = live_values.get(&element_ref.id()) {
+                        value.apply_to(&mut dom_element);
+                    }
+                    if element.name() == "select" && dom_element.options.is_empty() {
+                        dom_element.options = markup_options(&element_ref);
+                    }
 
                     elements.push(dom_element);
                 }
+                if let Some(stats) = stats.as_deref_mut() {
+                    stats.record(selector_str, matched, elements.len() - added_before);
+                }
             }
         }
 
@@ -322,11 +575,17 @@ impl DomProcessor {
             ];
             for selector_str in &text_selectors {
                 if let Ok(selector) = Selector::parse(selector_str) {
-                    for element_ref in document.select(&selector) {
+                    let (mut matched, added_before) = (0, elements.len());
+                    for element_ref in scope.select(&selector) {
+                        matched += 1;
+                        if is_excluded(&element_ref) {
+                            excluded += 1;
+                            continue;
+                        }
                         let element = element_ref.value();
-                        let text_content = element_ref.text().collect::<Vec<_>>().join(" ");
+                        let text = element_text(&element_ref);
 
-                        if !text_content.trim().is_empty() && text_content.trim().len() > 3 {
+                        if text.text.len() > 3 {
                             let element_id = format!(
                                 "{}_{}",
                                 element.name(),
@@ -337,10 +596,24 @@ impl DomProcessor {
                                     .join("_")
                             );
 
-                            if processed_elements.contains(&element_id) {
+                            if let Some(first) = processed_elements.get(&element_id) {
+                                duplicates += 1;
+                                if let Some(provenance) = extracted
+                                    .get(first)
+                                    .and_then(|&index| elements.get_mut(index))
+                                    .and_then(|element: &mut DomElement| {
+                                        element.provenance.as_mut()
+                                    })
+                                {
+                                    if *first == element_ref.id() {
+                                        provenance.also_matched.push(selector_str.to_string());
+                                    } else {
+                                        provenance.lookalikes_dropped += 1;
+                                    }
+                                }
                                 continue;
                             }
-                            processed_elements.insert(element_id);
+                            processed_elements.insert(element_id, element_ref.id());
 
                             let mut attributes = HashMap::new();
                             for (name, value) in element.attrs() {
@@ -351,7 +624,9 @@ impl DomProcessor {
                             let id = format!("elem_{}", element_counter);
 
                             let mut dom_element = DomElement::new(element.name().to_string(), id)
-                                .with_text_content(text_content.trim().to_string());
+                                .with_text_content(text.text);
+                            dom_element.own_text_len = text.own_len;
+                            dom_element.full_text_len = text.full_len;
 
                             for (key, value) in &attributes {
                                 if key == "id" {
@@ -367,15 +642,33 @@ impl DomProcessor {
                                 self.generate_xpath_for_element(&element_ref, &attributes);
                             dom_element.css_selector =
                                 self.generate_css_selector_for_element(&element_ref, &attributes);
-                            dom_element.is_visible = !self.is_hidden_element(&attributes);
+                            dom_element.is_visible = is_visible(&element_ref, &attributes);
+                            dom_element.ai_label = hinted_labels.get(&element_ref.id()).cloned();
+                            if self.config.explain {
+                                extracted.insert(element_ref.id(), elements.len());
+                                dom_element.provenance = Some(Provenance {
+                                    rule: selector_str.to_string(),
+                                    rule_kind: RuleKind::Text,
+                                    visibility: visibility(dom_element.is_visible).to_string(),
+                                    ..Provenance::default()
+                                });
+                            }
 
                             elements.push(dom_element);
                         }
                     }
+                    if let Some(stats) = stats.as_deref_mut() {
+                        stats.record(selector_str, matched, elements.len() - added_before);
+                    }
                 }
             }
         }
 
+        if let Some(stats) = stats {
+            stats.duplicates = duplicates;
+            stats.excluded = excluded;
+            stats.match_ms = started.elapsed().as_secs_f64() * 1000.0;
+        }
         Ok(elements)
     }
 
@@ -387,7 +680,12 @@ impl DomProcessor {
         let tag_name = element_ref.value().name();
 
         // Priority order for XPath generation
-        if let Some(id) = attributes.get("id") {
+        if let Some(stamp) = attributes
+            .get(STAMP_ATTRIBUTE)
+            .filter(|_| self.config.stamp_elements)
+        {
+            format!("//{}[@{}='{}']", tag_name, STAMP_ATTRIBUTE, stamp)
+        } else if let Some(id) = attributes.get("id") {
             format!("//{}[@id='{}']", tag_name, id)
         } else if let Some(name) = attributes.get("name") {
             format!("//{}[@name='{}']", tag_name, name)
@@ -411,7 +709,12 @@ impl DomProcessor {
         let tag_name = element_ref.value().name();
 
         // Priority order for CSS selector generation
-        if let Some(id) = attributes.get("id") {
+        if let Some(stamp) = attributes
+            .get(STAMP_ATTRIBUTE)
+            .filter(|_| self.config.stamp_elements)
+        {
+            format!("{}[{}='{}']", tag_name, STAMP_ATTRIBUTE, stamp)
+        } else if let Some(id) = attributes.get("id") {
             format!("{}#{}", tag_name, css_escape(id))
         } else if let Some(name) = attributes.get("name") {
             format!("{}[name='{}']", tag_name, name)
@@ -434,18 +737,23 @@ impl DomProcessor {
     }
 
     fn is_clickable_element(&self, element_ref: &ElementRef) -> bool {
+        self.clickable_reason(element_ref).is_some()
+    }
+
+    /// The check that makes the element clickable, if one does
+    fn clickable_reason(&self, element_ref: &ElementRef) -> Option<&'static str> {
         let tag_name = element_ref.value().name();
         let attributes = element_ref.value().attrs().collect::<HashMap<_, _>>();
 
         // Standard clickable elements
         if matches!(tag_name, "a" | "button" | "summary" | "area" | "menuitem") {
-            return true;
+            return Some("clickable tag");
         }
 
         // Input elements (most types are clickable)
         if tag_name == "input" {
             let input_type = attributes.get("type").unwrap_or(&"text");
-            return !matches!(*input_type, "hidden");
+            return (!matches!(*input_type, "hidden")).then_some("input that is not hidden");
         }
 
         // Elements with click handlers
@@ -453,7 +761,7 @@ impl DomProcessor {
             || attributes.contains_key("onchange")
             || attributes.contains_key("onsubmit")
         {
-            return true;
+            return Some("onclick, onchange or onsubmit handler");
         }
 
         // Elements with clickable roles
@@ -470,7 +778,7 @@ impl DomProcessor {
                     | "switch"
                     | "slider"
             ) {
-                return true;
+                return Some("clickable ARIA role");
             }
         }
 
@@ -480,25 +788,26 @@ impl DomProcessor {
             || attributes.contains_key("aria-haspopup")
             || attributes.get("draggable") == Some(&"true")
         {
-            return true;
+            return Some("tabindex, aria-expanded, aria-haspopup or draggable");
         }
 
-        false
+        None
     }
 
-    fn is_interactable_element(&self, element_ref: &ElementRef) -> bool {
+    /// The check that makes the element interactable, if one does
+    fn interactable_reason(&self, element_ref: &ElementRef) -> Option<&'static str> {
         let tag_name = element_ref.value().name();
         let attributes = element_ref.value().attrs().collect::<HashMap<_, _>>();
 
         // Standard form elements
         if matches!(tag_name, "input" | "textarea" | "select" | "button") {
             let input_type = attributes.get("type").unwrap_or(&"text");
-            return !matches!(*input_type, "hidden");
+            return (!matches!(*input_type, "hidden")).then_some("form control that is not hidden");
         }
 
         // Content editable elements
         if attributes.get("contenteditable") == Some(&"true") {
-            return true;
+            return Some("contenteditable");
         }
 
         // Elements with interactive roles
@@ -513,7 +822,7 @@ impl DomProcessor {
                     | "spinbutton"
                     | "switch"
             ) {
-                return true;
+                return Some("input ARIA role");
             }
         }
 
@@ -524,10 +833,10 @@ impl DomProcessor {
             || attributes.contains_key("onkeydown")
             || attributes.contains_key("onkeyup")
         {
-            return true;
+            return Some("tabindex, focus or key handler");
         }
 
-        false
+        None
     }
 
     fn is_hidden_element(&self, attributes: &HashMap<String, String>) -> bool {
@@ -568,7 +877,378 @@ impl DomProcessor {
     }
 }
 
+/// Elements the browser does not render, from the snapshot script
+#[derive(Deserialize)]
+struct Rendered {
+    count: usize,
+    hidden: Vec<usize>,
+    /// Viewport boxes of the canvases, in document order
+    #[serde(default)]
+    canvases: Vec<ElementRect>,
+}
+
+/// Text of an element, normalized by `normalize_text`
+struct ElementText {
+    /// What `DomElement::text_content` gets
+    text: String,
+    own_len: usize,
+    full_len: usize,
+}
+
+/// Text of `element_ref` with the lengths of its direct and of all its text.
+///
+/// A container of other controls, such as a menu, gets its direct text, or the
+/// first `TEXT_PREVIEW_CHARS` of all its text when it has none; any other
+/// element gets all its text. Script and style contents never count.
+fn element_text(element_ref: &ElementRef) -> ElementText {
+    let own = normalize_text(
+        &element_ref
+            .children()
+            .filter_map(|child| child.value().as_text().map(|text| text.to_string()))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    let full = normalize_text(
+        &element_ref
+            .descendants()
+            .filter(|node| {
+                !node.ancestors().any(|ancestor| {
+                    ancestor.value().as_element().is_some_and(|element| {
+                        matches!(element.name(), "script" | "style" | "template")
+                    })
+                })
+            })
+            .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    let container = element_ref
+        .descendants()
+        .skip(1)
+        .filter_map(ElementRef::wrap)
+        .any(|descendant| {
+            is_control(&descendant)
+                || matches!(descendant.value().name(), "input" | "select" | "textarea")
+        });
+
+    let text = if !container {
+        full.clone()
+    } else if !own.is_empty() {
+        own.clone()
+    } else {
+        full.chars().take(TEXT_PREVIEW_CHARS).collect()
+    };
+    ElementText {
+        text,
+        own_len: own.chars().count(),
+        full_len: full.chars().count(),
+    }
+}
+
+/// `text` with zero-width characters and soft hyphens removed and runs of
+/// whitespace collapsed to single spaces
+fn normalize_text(text: &str) -> String {
+    text.chars()
+        .filter(|c| {
+            !matches!(
+                c,
+                '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}'
+            )
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Fields a `<label>` or `aria-labelledby` names, with the text naming them.
+///
+/// `aria-labelledby` wins; otherwise the texts of every label pointing at the
+/// field with `for` or wrapping it are joined. Required markers and trailing
+/// colons are dropped.
+fn field_labels(document: &Html) -> Vec<(ElementRef<'_>, String)> {
+    let select_all = |selector: &str| -> Vec<ElementRef> {
+        Selector::parse(selector)
+            .map(|selector| document.select(&selector).collect())
+            .unwrap_or_default()
+    };
+    let by_id: HashMap<&str, ElementRef> = select_all("[id]")
+        .into_iter()
+        .filter_map(|element| element.value().attr("id").map(|id| (id, element)))
+        .collect();
+    let labelable = |element: &ElementRef| match element.value().name() {
+        "input" => element.value().attr("type") != Some("hidden"),
+        "select" | "textarea" | "button" | "meter" | "output" | "progress" => true,
+        _ => false,
+    };
+
+    // Keyed by node, keeping the field for the result
+    let mut texts: HashMap<_, (ElementRef, Vec<String>)> = HashMap::new();
+    for label in select_all("label") {
+        let field = match label.value().attr("for") {
+            Some(id) => by_id.get(id).copied(),
+            None => label
+                .descendants()
+                .filter_map(ElementRef::wrap)
+                .find(|element| labelable(element)),
+        };
+        let text = label_text(&label);
+        if let Some(field) = field.filter(|field| labelable(field) && !text.is_empty()) {
+            texts
+                .entry(field.id())
+                .or_insert_with(|| (field, Vec::new()))
+                .1
+                .push(text);
+        }
+    }
+    let mut labels: HashMap<_, _> = texts
+        .into_iter()
+        .map(|(id, (field, texts))| (id, (field, texts.join(" "))))
+        .collect();
+
+    for element in select_all("[aria-labelledby]") {
+        let text = element
+            .value()
+            .attr("aria-labelledby")
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|id| by_id.get(id))
+            .map(label_text)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !text.is_empty() {
+            labels.insert(element.id(), (element, text));
+        }
+    }
+    labels.into_values().collect()
+}
+
+/// Text of `label` without that of the fields inside it, trimmed of required
+/// markers and colons
+fn label_text(label: &ElementRef) -> String {
+    let text = label
+        .descendants()
+        .filter(|node| {
+            !node
+                .ancestors()
+                .take_while(|ancestor| ancestor.id() != label.id())
+                .any(|ancestor| {
+                    ancestor.value().as_element().is_some_and(|element| {
+                        matches!(element.name(), "select" | "textarea" | "script" | "style")
+                    })
+                })
+        })
+        .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    normalize_text(&text)
+        .trim_end_matches(|c: char| c == '*' || c == ':' || c.is_whitespace())
+        .to_string()
+}
+
+/// Whether `element_ref` is a form control that is `disabled`, or inside a
+/// disabled fieldset but not in that fieldset's first legend
+fn is_disabled(element_ref: &ElementRef) -> bool {
+    let element = element_ref.value();
+    if !matches!(
+        element.name(),
+        "button" | "input" | "select" | "textarea" | "fieldset"
+    ) {
+        return false;
+    }
+    if element.attr("disabled").is_some() {
+        return true;
+    }
+    element_ref
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .filter(|ancestor| {
+            ancestor.value().name() == "fieldset" && ancestor.value().attr("disabled").is_some()
+        })
+        .any(|fieldset| {
+            let legend = fieldset
+                .children()
+                .filter_map(ElementRef::wrap)
+                .find(|child| child.value().name() == "legend");
+            !legend.is_some_and(|legend| {
+                element_ref
+                    .ancestors()
+                    .any(|ancestor| ancestor.id() == legend.id())
+            })
+        })
+}
+
+/// Whether `element_ref` is inside a select or datalist, like its options
+fn in_list(element_ref: &ElementRef) -> bool {
+    element_ref
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|ancestor| matches!(ancestor.value().name(), "select" | "datalist"))
+}
+
+/// Options of a select as its markup declares them, for when live values are
+/// not available
+fn markup_options(select: &ElementRef) -> Vec<OptionInfo> {
+    let Ok(selector) = Selector::parse("option") else {
+        return Vec::new();
+    };
+    select
+        .select(&selector)
+        .map(|option| {
+            let element = option.value();
+            let text = option.text().collect::<String>();
+            let group = option
+                .parent()
+                .and_then(ElementRef::wrap)
+                .filter(|parent| parent.value().name() == "optgroup")
+                .and_then(|parent| parent.value().attr("label"))
+                .map(str::to_string);
+            OptionInfo {
+                value: element
+                    .attr("value")
+                    .map_or_else(|| text.trim().to_string(), str::to_string),
+                label: element
+                    .attr("label")
+                    .map_or_else(|| text.trim().to_string(), |label| label.trim().to_string()),
+                selected: element.attr("selected").is_some(),
+                group,
+            }
+        })
+        .collect()
+}
+
+/// Whether `element_ref` is an SVG root or drawn inside one
+fn in_svg(element_ref: &ElementRef) -> bool {
+    element_ref.value().name() == "svg"
+        || element_ref
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|ancestor| ancestor.value().name() == "svg")
+}
+
+/// Whether `element_ref` is a link, a button or something acting as one
+fn is_control(element_ref: &ElementRef) -> bool {
+    let element = element_ref.value();
+    matches!(element.name(), "a" | "button" | "summary")
+        || element.attr("onclick").is_some()
+        || matches!(
+            element.attr("role"),
+            Some("button" | "link" | "menuitem" | "tab" | "checkbox" | "switch")
+        )
+}
+
+/// Nearest control around `element_ref`, such as the button of an icon
+fn enclosing_control<'a>(element_ref: &ElementRef<'a>) -> Option<ElementRef<'a>> {
+    element_ref
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(is_control)
+}
+
+/// Name of an SVG element: its `aria-label`, else the text of its `<title>` child
+fn svg_label(element_ref: &ElementRef) -> Option<String> {
+    let label = element_ref.value().attr("aria-label").or_else(|| {
+        element_ref
+            .children()
+            .filter_map(ElementRef::wrap)
+            .find(|child| child.value().name() == "title")
+            .and_then(|title| title.text().next())
+    })?;
+    let label = normalize_text(label);
+    (!label.is_empty()).then_some(label)
+}
+
 // Helper function to escape CSS selectors
+/// Script reading the page markup together with the live value of every field
+/// matching `FIELD_SELECTOR`, which elements are not rendered and the viewport, so
+/// all of them describe the same moment. Evaluates to
+/// `{ html, fields, rendered: { count, hidden }, viewport }`, `hidden` holding
+/// document-order indices among all `count` elements.
+/// Script stamping the elements matching `stamped` outside `exclusions`, then
+/// capturing the document. Elements keep the stamps they already have.
+fn snapshot_script(stamped: &[&str], exclusions: &[String]) -> String {
+    format!(
+        r#"
+        (function() {{
+{field_value}
+            const stamped = {stamped};
+            const exclusions = {exclusions};
+            if (stamped.length) {{
+                const excluded = (element) => exclusions.some(selector => {{
+                    try {{
+                        return !!element.closest(selector);
+                    }} catch (e) {{
+                        return false;
+                    }}
+                }});
+                let next = window.__surfaiStampSeq || 0;
+                for (const selector of stamped) {{
+                    let matches = [];
+                    try {{
+                        matches = document.querySelectorAll(selector);
+                    }} catch (e) {{
+                        continue;
+                    }}
+                    for (const element of matches) {{
+                        if (element.hasAttribute({attribute}) || excluded(element)) continue;
+                        element.setAttribute({attribute}, String(++next));
+                    }}
+                }}
+                window.__surfaiStampSeq = next;
+            }}
+            const all = document.querySelectorAll('*');
+            const hidden = [];
+            all.forEach((element, index) => {{
+                // Options and image map areas have no boxes of their own
+                if (element.closest('select, datalist, map')) return;
+                const shown = element.checkVisibility
+                    ? element.checkVisibility({{ visibilityProperty: true }})
+                    : element.getClientRects().length > 0
+                        && getComputedStyle(element).visibility !== 'hidden';
+                if (!shown) hidden.push(index);
+            }});
+            return {{
+                html: document.documentElement.outerHTML,
+                fields: Array.from(document.querySelectorAll({selector})).map(fieldValue),
+                rendered: {{
+                    count: all.length,
+                    hidden,
+                    canvases: Array.from(document.querySelectorAll('canvas')).map(canvas => {{
+                        const rect = canvas.getBoundingClientRect();
+                        return {{ x: rect.x, y: rect.y, width: rect.width, height: rect.height }};
+                    }})
+                }},
+                viewport: {viewport}
+            }};
+        }})()
+        "#,
+        field_value = field_value::FIELD_VALUE_JS,
+        stamped = serde_json::to_string(stamped).unwrap_or_else(|_| "[]".to_string()),
+        exclusions = serde_json::to_string(exclusions).unwrap_or_else(|_| "[]".to_string()),
+        attribute = serde_json::to_string(STAMP_ATTRIBUTE).unwrap_or_default(),
+        selector =
+            serde_json::to_string(field_value::FIELD_SELECTOR).unwrap_or_else(|_| "''".to_string()),
+        viewport = Viewport::CAPTURE_JS
+    )
+}
+
+/// Script removing every stamp and restarting the numbering. Evaluates to the
+/// number of stamps removed.
+pub(crate) fn unstamp_script() -> String {
+    format!(
+        r#"
+        (function() {{
+            const stamped = document.querySelectorAll('[' + {attribute} + ']');
+            stamped.forEach(element => element.removeAttribute({attribute}));
+            delete window.__surfaiStampSeq;
+            return stamped.length;
+        }})()
+        "#,
+        attribute = serde_json::to_string(STAMP_ATTRIBUTE).unwrap_or_default()
+    )
+}
+
 fn css_escape(s: &str) -> String {
     s.chars()
         .map(|c| match c {
@@ -586,3 +1266,144 @@ fn css_escape(s: &str) -> String {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn extract(html: &str, fields: &[FieldValue]) -> DomState {
+        let processor = DomProcessor::new(DomConfig::default());
+        let mut state = DomState::new("https://example.com/".into(), "Test".into());
+        for element in processor
+            .extract_all_interactive_elements(
+                html,
+                "https://example.com/",
+                fields,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+        {
+            state.add_element(element);
+        }
+        state
+    }
+
+    async fn stats_for(html: &str) -> ExtractionStats {
+        let config = DomConfig {
+            collect_stats: true,
+            ..DomConfig::default()
+        };
+        let mut stats = ExtractionStats::default();
+        DomProcessor::new(config)
+            .extract_all_interactive_elements(
+                html,
+                "https://example.com/",
+                &[],
+                None,
+                None,
+                Some(&mut stats),
+            )
+            .await
+            .unwrap();
+        stats
+    }
+
+    fn input(input_type: &str, value: &str, secret: bool) -> FieldValue {
+        FieldValue {
+            tag_name: "input".into(),
+            input_type: Some(input_type.into()),
+            value: value.into(),
+            secret,
+            ..FieldValue::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn secrets_never_reach_the_state() {
+        let html = r#"<html><body><form>
+            <input id="user" type="text">
+            <input id="pass" type="password" value="markup-hunter2">
+            <input id="pin" type="text">
+        </form></body></html>"#;
+        // The password as the browser would report it unmasked, and a text field
+        // filled by `type_secret`
+        let fields = [
+            input("text", "alice", false),
+            input("password", "live-hunter2", false),
+            input("text", "typed-hunter2", true),
+        ];
+        let state = extract(html, &fields).await;
+
+        let values: Vec<_> = state
+            .input_elements
+            .iter()
+            .map(|element| element.current_value.as_deref())
+            .collect();
+        assert_eq!(
+            values,
+            [Some("alice"), Some("[REDACTED]"), Some("[REDACTED]")]
+        );
+        let json = serde_json::to_string(&state).unwrap();
+        let bytes = String::from_utf8_lossy(&state.to_bytes()).into_owned();
+        for encoded in [json, bytes] {
+            assert!(!encoded.contains("hunter2"));
+            assert!(encoded.contains("alice"));
+        }
+        // The page-side mask is the same
+        assert!(field_value::FIELD_VALUE_JS.contains(&format!("'{}'", field_value::MASKED_VALUE)));
+    }
+
+    /// Class-name guesses in the interactive selector list, as opposed to tags,
+    /// roles and event attributes
+    const HEURISTIC_SELECTORS: &[&str] = &[
+        "[class*='search']",
+        ".btn",
+        ".button",
+        ".link",
+        ".clickable",
+        ".interactive",
+        ".control",
+        ".input",
+        ".field",
+        ".search",
+    ];
+
+    #[tokio::test]
+    async fn class_heuristics_add_little_on_fixture_pages() {
+        let pages = [
+            ("/", include_str!("../testing/fixtures/index.html")),
+            ("/login", include_str!("../testing/fixtures/login.html")),
+            (
+                "/dashboard",
+                include_str!("../testing/fixtures/dashboard.html"),
+            ),
+            ("/spa", include_str!("../testing/fixtures/spa.html")),
+            (
+                "/form-labels",
+                include_str!("../testing/fixtures/form_labels.html"),
+            ),
+            (
+                "/type-modes",
+                include_str!("../testing/fixtures/type_modes.html"),
+            ),
+            (
+                "/sticky-header",
+                include_str!("../testing/fixtures/sticky_header.html"),
+            ),
+        ];
+        for (page, html) in pages {
+            let stats = stats_for(html).await;
+            assert!(stats.added() > 0, "{}: nothing extracted", page);
+            let share = stats.share_of(HEURISTIC_SELECTORS);
+            assert!(
+                share <= 0.25,
+                "{}: class heuristics added {:.0}% of elements",
+                page,
+                share * 100.0
+            );
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Why extraction kept an element, recorded when `DomConfig::explain` is set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Selector that extracted the element
+    pub rule: String,
+    pub rule_kind: RuleKind,
+    /// Later selectors that matched the same element and were skipped for it
+    #[serde(default)]
+    pub also_matched: Vec<String>,
+    /// Other elements with the same tag and attributes, dropped as duplicates of
+    /// this one
+    #[serde(default)]
+    pub lookalikes_dropped: usize,
+    /// Check that made the element clickable, if any
+    pub clickable_because: Option<String>,
+    /// Check that made the element interactable, if any
+    pub interactable_because: Option<String>,
+    /// How `is_visible` was decided
+    pub visibility: String,
+}
+
+/// Which list of selectors a `Provenance::rule` came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// The built-in interactive selectors
+    #[default]
+    Interactive,
+    /// `SiteProfile::extra_selectors`
+    SiteProfile,
+    /// Text selectors, run when `DomConfig::extract_all_elements` is set
+    Text,
+}
+
+impl std::fmt::Display for RuleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RuleKind::Interactive => "interactive selector",
+            RuleKind::SiteProfile => "site profile selector",
+            RuleKind::Text => "text selector",
+        })
+    }
+}
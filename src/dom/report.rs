@@ -0,0 +1,159 @@
+use crate::browser::element_monitor::MutationActivity;
+use crate::dom::{DomState, ExtractionStats};
+use serde::{Deserialize, Serialize};
+
+/// Element counts for a page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageStats {
+    /// Elements extracted into the `DomState`
+    pub total_elements: usize,
+    pub clickable_elements: usize,
+    pub input_elements: usize,
+    pub text_elements: usize,
+    pub links: usize,
+    pub images: usize,
+    pub headings: usize,
+    pub forms: usize,
+    pub iframes: usize,
+}
+
+impl PageStats {
+    /// Counts from an extracted page; `links`, `images`, `headings`, `forms` and
+    /// `iframes` count its elements by tag, so they only cover extracted elements
+    pub fn from_state(state: &DomState) -> Self {
+        let tagged = |tags: &[&str]| {
+            state
+                .elements
+                .iter()
+                .filter(|element| tags.contains(&element.tag_name.as_str()))
+                .count()
+        };
+        Self {
+            total_elements: state.elements.len(),
+            clickable_elements: state.clickable_elements.len(),
+            input_elements: state.input_elements.len(),
+            text_elements: state.text_elements.len(),
+            links: tagged(&["a"]),
+            images: tagged(&["img"]),
+            headings: tagged(&["h1", "h2", "h3", "h4", "h5", "h6"]),
+            forms: tagged(&["form"]),
+            iframes: tagged(&["iframe"]),
+        }
+    }
+}
+
+/// Document metadata from `<head>`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageMetadata {
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub canonical_url: Option<String>,
+    pub keywords: Option<String>,
+    pub og_title: Option<String>,
+    pub og_image: Option<String>,
+    pub charset: Option<String>,
+}
+
+/// Navigation and paint timings, in milliseconds from navigation start
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    pub time_to_first_byte_ms: Option<f64>,
+    pub dom_content_loaded_ms: Option<f64>,
+    pub load_ms: Option<f64>,
+    pub first_contentful_paint_ms: Option<f64>,
+    /// Resources fetched so far
+    pub resource_count: usize,
+    /// Bytes transferred for the document and its resources, where reported
+    pub transfer_bytes: u64,
+}
+
+/// The image covering the most CSS pixels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LargestImage {
+    pub src: String,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Summary of a page for dashboards and crawl reports, from `analyze_page`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageReport {
+    pub stats: PageStats,
+    pub metadata: PageMetadata,
+    pub performance: PerformanceMetrics,
+    /// Console errors and uncaught exceptions so far; `None` when the browser
+    /// does not capture the console
+    pub console_errors: Option<usize>,
+    pub largest_image: Option<LargestImage>,
+    /// Words in the visible body text
+    pub word_count: usize,
+    /// From the `lang` attribute or `Content-Language` meta tag
+    pub language: Option<String>,
+    /// Telemetry of the extraction behind `stats`, when `DomConfig::collect_stats`
+    /// is set
+    pub extraction: Option<ExtractionStats>,
+    /// DOM mutations over the last few seconds, for the session's own tab
+    #[serde(default)]
+    pub dom_activity: Option<MutationActivity>,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Script reading metadata, timings, the largest image and the word count.
+/// Evaluates to `{ metadata, performance, largest_image, word_count, forms, iframes }`.
+pub(crate) fn page_report_script() -> &'static str {
+    r#"
+        (function() {
+            const meta = (selector) => {
+                const node = document.querySelector(selector);
+                return node ? (node.getAttribute('content') || node.getAttribute('href') || null) : null;
+            };
+            const metadata = {
+                url: location.href,
+                title: document.title,
+                description: meta('meta[name="description" i]'),
+                canonical_url: meta('link[rel="canonical" i]'),
+                keywords: meta('meta[name="keywords" i]'),
+                og_title: meta('meta[property="og:title" i]'),
+                og_image: meta('meta[property="og:image" i]'),
+                charset: document.characterSet || null
+            };
+
+            const navigation = performance.getEntriesByType('navigation')[0];
+            const paint = performance.getEntriesByType('paint')
+                .find(entry => entry.name === 'first-contentful-paint');
+            const resources = performance.getEntriesByType('resource');
+            const positive = (value) => (value > 0 ? value : null);
+            const performanceMetrics = {
+                time_to_first_byte_ms: navigation ? positive(navigation.responseStart) : null,
+                dom_content_loaded_ms: navigation ? positive(navigation.domContentLoadedEventEnd) : null,
+                load_ms: navigation ? positive(navigation.loadEventEnd) : null,
+                first_contentful_paint_ms: paint ? paint.startTime : null,
+                resource_count: resources.length,
+                transfer_bytes: Math.round(resources.concat(navigation ? [navigation] : [])
+                    .reduce((total, entry) => total + (entry.transferSize || 0), 0))
+            };
+
+            let largest = null;
+            for (const image of document.images) {
+                const rect = image.getBoundingClientRect();
+                const area = rect.width * rect.height;
+                if (area > 0 && (!largest || area > largest.width * largest.height)) {
+                    largest = { src: image.currentSrc || image.src, width: rect.width, height: rect.height };
+                }
+            }
+
+            const text = document.body ? document.body.innerText : '';
+            const words = text.match(/[\p{L}\p{N}]+(?:['’-][\p{L}\p{N}]+)*/gu) || [];
+
+            return {
+                metadata: metadata,
+                performance: performanceMetrics,
+                largest_image: largest,
+                word_count: words.length,
+                forms: document.forms.length,
+                iframes: document.querySelectorAll('iframe').length
+            };
+        })()
+    "#
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+/// Where `scroll_to_element` places the element within each scroll container
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollAlignment {
+    /// Center the element in every container and the viewport
+    #[default]
+    Center,
+    /// Scroll the least distance that brings the element fully into view
+    Nearest,
+}
+
+impl ScrollAlignment {
+    fn as_js(&self) -> &'static str {
+        match self {
+            ScrollAlignment::Center => "center",
+            ScrollAlignment::Nearest => "nearest",
+        }
+    }
+}
+
+/// Offset that moves `start..end` into `viewStart..viewEnd` with `alignment`
+const ALIGN_JS: &str = r#"
+            const delta = (start, end, viewStart, viewEnd, alignment) => {
+                if (alignment === 'center') return (start + end) / 2 - (viewStart + viewEnd) / 2;
+                if (start < viewStart) return start - viewStart;
+                if (end > viewEnd) return Math.min(end - viewEnd, start - viewStart);
+                return 0;
+            };
+"#;
+
+/// Script scrolling every scroll container around the element `element_expression`
+/// yields, nearest first, then the window. Evaluates to `{ found, containers, inViewport }`.
+pub(crate) fn scroll_into_view_script(
+    element_expression: &str,
+    alignment: ScrollAlignment,
+) -> String {
+    format!(
+        r#"
+        (function() {{
+{describe}
+{align}
+            const element = {element};
+            if (!element || !element.isConnected) return {{ found: false }};
+
+            const alignment = '{alignment}';
+            const parents = scrollParents(element);
+            for (const container of parents) {{
+                const target = element.getBoundingClientRect();
+                const view = container.getBoundingClientRect();
+                container.scrollTop += delta(target.top, target.bottom,
+                    view.top + container.clientTop, view.top + container.clientTop + container.clientHeight, alignment);
+                container.scrollLeft += delta(target.left, target.right,
+                    view.left + container.clientLeft, view.left + container.clientLeft + container.clientWidth, alignment);
+            }}
+
+            const target = element.getBoundingClientRect();
+            window.scrollBy({{
+                top: delta(target.top, target.bottom, 0, window.innerHeight, alignment),
+                left: delta(target.left, target.right, 0, window.innerWidth, alignment),
+                behavior: 'instant'
+            }});
+
+            const rect = element.getBoundingClientRect();
+            const inViewport = rect.bottom > 0 && rect.right > 0
+                && rect.top < window.innerHeight && rect.left < window.innerWidth;
+            return {{ found: true, containers: parents.length, inViewport: inViewport }};
+        }})()
+        "#,
+        describe = crate::dom::xpath::DESCRIBE_ELEMENT_JS,
+        align = ALIGN_JS,
+        element = element_expression,
+        alignment = alignment.as_js()
+    )
+}
+
+/// Script measuring how far the window has to scroll to place the element
+/// `element_expression` yields with `alignment`, leaving scroll containers alone.
+/// Evaluates to `{ found, top, left }`.
+pub(crate) fn window_scroll_offset_script(
+    element_expression: &str,
+    alignment: ScrollAlignment,
+) -> String {
+    format!(
+        r#"
+        (function() {{
+{align}
+            const element = {element};
+            if (!element || !element.isConnected) return {{ found: false }};
+            const target = element.getBoundingClientRect();
+            return {{
+                found: true,
+                top: delta(target.top, target.bottom, 0, window.innerHeight, '{alignment}'),
+                left: delta(target.left, target.right, 0, window.innerWidth, '{alignment}')
+            }};
+        }})()
+        "#,
+        align = ALIGN_JS,
+        element = element_expression,
+        alignment = alignment.as_js()
+    )
+}
+
+/// Script scrolling `container` (or the window when `None`) to its end and
+/// evaluating to the number of `item_selector` matches inside it, or -1 when the
+/// container is missing
+pub(crate) fn scroll_to_end_script(item_selector: &str, container: Option<&str>) -> String {
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| "''".to_string());
+    format!(
+        r#"
+        (function() {{
+            const container = {container};
+            if (container === undefined) return -1;
+            if (container) {{
+                container.scrollTop = container.scrollHeight;
+            }} else {{
+                window.scrollTo({{ top: document.documentElement.scrollHeight, behavior: 'instant' }});
+            }}
+            return (container || document).querySelectorAll({items}).length;
+        }})()
+        "#,
+        container = container
+            .map(|selector| format!("document.querySelector({}) || undefined", quote(selector)))
+            .unwrap_or_else(|| "null".to_string()),
+        items = quote(item_selector)
+    )
+}
+
+/// Script counting the `item_selector` matches inside `container` (or the document)
+pub(crate) fn count_items_script(item_selector: &str, container: Option<&str>) -> String {
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| "''".to_string());
+    format!(
+        "(({}) || document).querySelectorAll({}).length",
+        container
+            .map(|selector| format!("document.querySelector({})", quote(selector)))
+            .unwrap_or_else(|| "null".to_string()),
+        quote(item_selector)
+    )
+}
+
+/// Script describing up to `max_items` `item_selector` matches inside `container`
+/// (or the document), in the shape `query_script` returns
+pub(crate) fn describe_items_script(
+    item_selector: &str,
+    container: Option<&str>,
+    max_items: usize,
+) -> String {
+    format!(
+        r#"
+        (function() {{
+{describe}
+            const root = {root};
+            let nodes;
+            try {{
+                nodes = Array.from(root.querySelectorAll({items})).slice(0, {max});
+            }} catch (e) {{
+                return {{ success: false, error: e.message }};
+            }}
+            return {{ success: true, elements: nodes.map(describeElement) }};
+        }})()
+        "#,
+        describe = crate::dom::xpath::DESCRIBE_ELEMENT_JS,
+        root = container
+            .map(|selector| format!(
+                "document.querySelector({}) || document",
+                serde_json::to_string(selector).unwrap_or_else(|_| "''".to_string())
+            ))
+            .unwrap_or_else(|| "document".to_string()),
+        items = serde_json::to_string(item_selector).unwrap_or_else(|_| "''".to_string()),
+        max = max_items
+    )
+}
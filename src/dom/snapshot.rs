@@ -0,0 +1,510 @@
+use crate::dom::{AttributeChange, DiffEntry, DomDiff, DomElement, DomState};
+use crate::errors::{BrowserAgentError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Replaced in labels unless `SnapshotOptions::without_default_patterns`: digit
+/// runs, which covers dates, times, counters and prices
+const DEFAULT_IGNORE_PATTERNS: &[&str] = &[r"\d+"];
+
+/// What replaces ignored text, so `Updated 5 minutes ago` reads `Updated # minutes ago`
+const IGNORED_PLACEHOLDER: &str = "#";
+
+/// Labels are cut to this many characters; long text changes little about structure
+const MAX_LABEL_CHARS: usize = 60;
+
+/// Set to rewrite snapshots instead of comparing against them
+pub const UPDATE_SNAPSHOTS_ENV: &str = "SURFAI_UPDATE_SNAPSHOTS";
+
+/// How a `DomState` is reduced to its interactive structure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotOptions {
+    /// Regular expressions whose matches in labels are replaced before hashing
+    pub ignore_patterns: Vec<String>,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            ignore_patterns: DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl SnapshotOptions {
+    /// Also ignore text matching the regular expression `pattern`
+    pub fn ignoring(mut self, pattern: &str) -> Self {
+        self.ignore_patterns.push(pattern.to_string());
+        self
+    }
+
+    /// Keep digits in labels
+    pub fn without_default_patterns(mut self) -> Self {
+        self.ignore_patterns
+            .retain(|pattern| !DEFAULT_IGNORE_PATTERNS.contains(&pattern.as_str()));
+        self
+    }
+
+    fn compiled(&self) -> Result<Vec<regex::Regex>> {
+        self.ignore_patterns
+            .iter()
+            .map(|pattern| {
+                regex::Regex::new(pattern).map_err(|e| {
+                    BrowserAgentError::ConfigurationError(format!(
+                        "invalid ignore pattern '{}': {}",
+                        pattern, e
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// One interactive element as the snapshot sees it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuralEntry {
+    pub tag: String,
+    /// Explicit or implicit ARIA role
+    pub role: String,
+    /// Accessible name or text, normalized by the ignore patterns
+    pub label: String,
+    /// Selector the element was extracted with, for locating it in a diff
+    pub selector: String,
+}
+
+impl StructuralEntry {
+    fn key(&self) -> (&str, &str, &str) {
+        (&self.tag, &self.role, &self.label)
+    }
+
+    fn describe(&self) -> String {
+        match self.role.as_str() {
+            "" => self.tag.clone(),
+            role if role == self.tag => self.tag.clone(),
+            role => format!("{}[{}]", self.tag, role),
+        }
+    }
+}
+
+/// The interactive structure of a page, written by `assert_matches_snapshot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuralSnapshot {
+    pub url: String,
+    pub fingerprint: String,
+    pub options: SnapshotOptions,
+    pub entries: Vec<StructuralEntry>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl StructuralSnapshot {
+    pub fn from_state(state: &DomState, options: &SnapshotOptions) -> Result<Self> {
+        let ignored = options.compiled()?;
+        let entries: Vec<StructuralEntry> = state
+            .elements
+            .iter()
+            .filter(|element| is_interactive(element))
+            .map(|element| StructuralEntry {
+                tag: element.tag_name.to_lowercase(),
+                role: role(element),
+                label: normalize(&label(element), &ignored),
+                selector: element.css_selector.clone(),
+            })
+            .collect();
+        Ok(Self {
+            url: state.url.clone(),
+            fingerprint: fingerprint(&entries),
+            options: options.clone(),
+            entries,
+            created_at: chrono::Utc::now(),
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Elements added and removed going from this snapshot to `current`, in page
+    /// order. An element whose role or label changed in place is reported as an
+    /// attribute change of its selector.
+    pub fn diff(&self, current: &StructuralSnapshot) -> DomDiff {
+        let (old, new) = (&self.entries, &current.entries);
+        let common = longest_common_subsequence(old, new);
+
+        let mut removed: Vec<&StructuralEntry> = Vec::new();
+        let mut added: Vec<&StructuralEntry> = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        for (matched_old, matched_new) in common
+            .into_iter()
+            .chain(std::iter::once((old.len(), new.len())))
+        {
+            removed.extend(&old[i..matched_old]);
+            added.extend(&new[j..matched_new]);
+            i = matched_old + 1;
+            j = matched_new + 1;
+        }
+
+        let mut diff = DomDiff::default();
+        let mut unmatched_added: Vec<&StructuralEntry> = Vec::new();
+        for entry in added {
+            let same_place = removed.iter().position(|old| {
+                !old.selector.is_empty() && old.selector == entry.selector && old.tag == entry.tag
+            });
+            match same_place {
+                Some(index) => {
+                    let old = removed.remove(index);
+                    for (attribute, before, after) in [
+                        ("role", &old.role, &entry.role),
+                        ("label", &old.label, &entry.label),
+                    ] {
+                        if before != after {
+                            diff.changed.push(AttributeChange {
+                                selector: entry.selector.clone(),
+                                attribute: attribute.to_string(),
+                                old_value: Some(before.clone()),
+                                new_value: Some(after.clone()),
+                            });
+                        }
+                    }
+                }
+                None => unmatched_added.push(entry),
+            }
+        }
+        diff.removed = removed.into_iter().map(diff_entry).collect();
+        diff.added = unmatched_added.into_iter().map(diff_entry).collect();
+        diff
+    }
+}
+
+fn diff_entry(entry: &StructuralEntry) -> DiffEntry {
+    DiffEntry {
+        selector: entry.selector.clone(),
+        tag_name: entry.describe(),
+        text: entry.label.clone(),
+    }
+}
+
+/// Index pairs of the longest run of entries both lists share in order
+fn longest_common_subsequence(
+    old: &[StructuralEntry],
+    new: &[StructuralEntry],
+) -> Vec<(usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+    let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old[i].key() == new[j].key() {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i].key() == new[j].key() {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// Hash over the ordered (tag, role, label) tuples
+fn fingerprint(entries: &[StructuralEntry]) -> String {
+    let mut hasher = Sha256::new();
+    for entry in entries {
+        for part in [&entry.tag, &entry.role, &entry.label] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update([0xff]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn is_interactive(element: &DomElement) -> bool {
+    element.is_clickable
+        || element.is_interactable
+        || matches!(
+            element.tag_name.to_lowercase().as_str(),
+            "input" | "textarea" | "select"
+        )
+}
+
+fn role(element: &DomElement) -> String {
+    if let Some(role) = element.attributes.get("role").map(|role| role.trim()) {
+        if !role.is_empty() {
+            return role.to_lowercase();
+        }
+    }
+    let input_type = element
+        .attributes
+        .get("type")
+        .map(|kind| kind.to_lowercase())
+        .unwrap_or_default();
+    match element.tag_name.to_lowercase().as_str() {
+        "a" if element.attributes.contains_key("href") => "link",
+        "button" | "summary" => "button",
+        "select" => "combobox",
+        "textarea" => "textbox",
+        "input" => match input_type.as_str() {
+            "button" | "submit" | "reset" | "image" => "button",
+            "checkbox" => "checkbox",
+            "radio" => "radio",
+            "range" => "slider",
+            "search" => "searchbox",
+            "hidden" => "",
+            _ => "textbox",
+        },
+        _ => "",
+    }
+    .to_string()
+}
+
+/// Accessible name, falling back to text and the attributes that usually describe
+/// a control
+fn label(element: &DomElement) -> String {
+    let attribute = |name: &str| element.attributes.get(name).map(String::as_str);
+    [
+        attribute("aria-label"),
+        element.text_content.as_deref(),
+        attribute("placeholder"),
+        attribute("title"),
+        attribute("alt"),
+        attribute("name"),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+    .find(|text| !text.is_empty())
+    .unwrap_or_default()
+}
+
+fn normalize(label: &str, ignored: &[regex::Regex]) -> String {
+    let mut label = label.to_string();
+    for pattern in ignored {
+        label = pattern
+            .replace_all(&label, IGNORED_PLACEHOLDER)
+            .into_owned();
+    }
+    label.chars().take(MAX_LABEL_CHARS).collect()
+}
+
+/// Compare `state` with the snapshot at `path`, writing it when there is none or
+/// `SURFAI_UPDATE_SNAPSHOTS` is set. `Err` describes the structural differences.
+pub(crate) fn check_snapshot(
+    state: &DomState,
+    path: &Path,
+    options: &SnapshotOptions,
+) -> Result<std::result::Result<(), String>> {
+    let updating = std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some_and(|value| value != "0");
+    if updating || !path.exists() {
+        let snapshot = StructuralSnapshot::from_state(state, options)?;
+        snapshot.save(path)?;
+        println!(
+            "📸 Wrote structural snapshot of {} ({} elements) to {}",
+            state.url,
+            snapshot.entries.len(),
+            path.display()
+        );
+        return Ok(Ok(()));
+    }
+
+    let expected = StructuralSnapshot::load(path)?;
+    // The snapshot's own options, so normalization matches what was recorded
+    let current = StructuralSnapshot::from_state(state, &expected.options)?;
+    if current.fingerprint == expected.fingerprint {
+        return Ok(Ok(()));
+    }
+    let diff = expected.diff(&current);
+    Ok(Err(format!(
+        "structure of {} differs from {} ({}):\n{}",
+        state.url,
+        path.display(),
+        diff.summary(),
+        diff
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interactive element located by `selector`
+    fn control(tag: &str, selector: &str, text: &str, attributes: &[(&str, &str)]) -> DomElement {
+        let mut element = attributes.iter().fold(
+            DomElement::new(tag.to_string(), selector.to_string())
+                .with_text_content(text.to_string())
+                .set_clickable(true),
+            |element, (key, value)| element.with_attribute(key.to_string(), value.to_string()),
+        );
+        element.css_selector = selector.to_string();
+        element
+    }
+
+    fn state(elements: Vec<DomElement>) -> DomState {
+        let mut state = DomState::new("https://shop.example/cart".to_string(), "Cart".to_string());
+        state.elements = elements;
+        state
+    }
+
+    fn cart(total: &str) -> Vec<DomElement> {
+        vec![
+            control("a", "#home", "Home", &[("href", "/")]),
+            control("button", "#checkout", total, &[]),
+            control("input", "#coupon", "", &[("placeholder", "Coupon code")]),
+            DomElement::new("p".to_string(), "#note".to_string())
+                .with_text_content("Free returns".to_string()),
+        ]
+    }
+
+    fn snapshot(elements: Vec<DomElement>, options: &SnapshotOptions) -> StructuralSnapshot {
+        StructuralSnapshot::from_state(&state(elements), options).unwrap()
+    }
+
+    #[test]
+    fn entries_keep_interactive_elements_with_role_and_label() {
+        let snapshot = snapshot(cart("Pay 42.50 now"), &SnapshotOptions::default());
+        let entries: Vec<(&str, &str, &str, &str)> = snapshot
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.tag.as_str(),
+                    entry.role.as_str(),
+                    entry.label.as_str(),
+                    entry.selector.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("a", "link", "Home", "#home"),
+                ("button", "button", "Pay #.# now", "#checkout"),
+                ("input", "textbox", "Coupon code", "#coupon"),
+            ]
+        );
+        assert_eq!(snapshot.url, "https://shop.example/cart");
+        assert_eq!(snapshot.fingerprint.len(), 16);
+    }
+
+    #[test]
+    fn ignore_patterns_keep_the_fingerprint_stable() {
+        let options = SnapshotOptions::default();
+        assert_eq!(
+            snapshot(cart("Pay 42.50 now"), &options).fingerprint,
+            snapshot(cart("Pay 7.00 now"), &options).fingerprint
+        );
+
+        let exact = SnapshotOptions::default().without_default_patterns();
+        assert!(exact.ignore_patterns.is_empty());
+        assert_ne!(
+            snapshot(cart("Pay 42.50 now"), &exact).fingerprint,
+            snapshot(cart("Pay 7.00 now"), &exact).fingerprint
+        );
+
+        let words = exact.ignoring(r"(?i)\b(now|later)\b");
+        let later = snapshot(cart("Pay 7.00 later"), &words);
+        assert_eq!(later.entries[1].label, "Pay 7.00 #");
+        assert_eq!(
+            later.fingerprint,
+            snapshot(cart("Pay 7.00 now"), &words).fingerprint
+        );
+    }
+
+    #[test]
+    fn invalid_ignore_patterns_are_configuration_errors() {
+        let options = SnapshotOptions::default().ignoring("(");
+        assert!(matches!(
+            StructuralSnapshot::from_state(&state(cart("Pay")), &options),
+            Err(BrowserAgentError::ConfigurationError(_))
+        ));
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_nodes() {
+        let options = SnapshotOptions::default();
+        let before = snapshot(cart("Pay 42.50 now"), &options);
+
+        let mut elements = cart("Pay 42.50 now");
+        // Coupon field gone, a newsletter checkbox added, checkout relabeled in place
+        elements.remove(2);
+        elements[1] = control("button", "#checkout", "Place order", &[]);
+        elements.push(control(
+            "input",
+            "#newsletter",
+            "",
+            &[("type", "checkbox"), ("name", "newsletter")],
+        ));
+        let after = snapshot(elements, &options);
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff,
+            DomDiff {
+                added: vec![DiffEntry {
+                    selector: "#newsletter".to_string(),
+                    tag_name: "input[checkbox]".to_string(),
+                    text: "newsletter".to_string(),
+                }],
+                removed: vec![DiffEntry {
+                    selector: "#coupon".to_string(),
+                    tag_name: "input[textbox]".to_string(),
+                    text: "Coupon code".to_string(),
+                }],
+                changed: vec![AttributeChange {
+                    selector: "#checkout".to_string(),
+                    attribute: "label".to_string(),
+                    old_value: Some("Pay #.# now".to_string()),
+                    new_value: Some("Place order".to_string()),
+                }],
+            }
+        );
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_files() {
+        let path = std::env::temp_dir()
+            .join(format!("surfai-snapshot-{}", uuid::Uuid::new_v4().simple()))
+            .join("cart.json");
+        let saved = snapshot(cart("Pay 42.50 now"), &SnapshotOptions::default());
+        saved.save(&path).unwrap();
+        let loaded = StructuralSnapshot::load(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(loaded.fingerprint, saved.fingerprint);
+        assert_eq!(loaded.entries, saved.entries);
+        assert_eq!(loaded.options, saved.options);
+    }
+}
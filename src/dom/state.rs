@@ -1,5 +1,10 @@
-use crate::dom::DomElement;
+use crate::dom::{
+    CoordinateSpace, DocumentKind, DomElement, ElementRect, ExtractionStats, SerializationProfile,
+    SnapshotOptions, StructuralSnapshot, Viewport,
+};
+use crate::errors::{BrowserAgentError, Result};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DomState {
@@ -10,6 +15,18 @@ pub struct DomState {
     pub input_elements: Vec<DomElement>,
     pub text_elements: Vec<DomElement>,
     pub screenshot_base64: Option<String>,
+    /// Scale and scroll offset at screenshot capture, for mapping element rects onto it
+    #[serde(default)]
+    pub coordinate_space: Option<CoordinateSpace>,
+    /// Viewport and page size at extraction
+    #[serde(default)]
+    pub viewport: Option<Viewport>,
+    /// Extraction telemetry, when `DomConfig::collect_stats` is set
+    #[serde(default)]
+    pub stats: Option<ExtractionStats>,
+    /// What the page is; anything but `Html` comes without elements
+    #[serde(default)]
+    pub document_kind: DocumentKind,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
@@ -23,6 +40,10 @@ impl DomState {
             input_elements: Vec::new(),
             text_elements: Vec::new(),
             screenshot_base64: None,
+            coordinate_space: None,
+            viewport: None,
+            stats: None,
+            document_kind: DocumentKind::Html,
             timestamp: chrono::Utc::now(),
         }
     }
@@ -47,6 +68,78 @@ impl DomState {
         self.screenshot_base64 = Some(screenshot);
     }
 
+    /// What each selector contributed, how many matches were dropped and what the
+    /// extraction cost; `None` unless `DomConfig::collect_stats` is set
+    pub fn extraction_stats(&self) -> Option<&ExtractionStats> {
+        self.stats.as_ref()
+    }
+
+    pub fn set_coordinate_space(&mut self, space: CoordinateSpace) {
+        self.coordinate_space = Some(space);
+    }
+
+    /// Device-pixel rect of `element` within the captured screenshot, assuming its
+    /// rect was measured while the page was at the capture scroll position
+    pub fn screenshot_rect(&self, element: &DomElement) -> Option<ElementRect> {
+        let space = self.coordinate_space?;
+        element
+            .rect
+            .as_ref()
+            .map(|rect| rect.in_screenshot(&space, &space))
+    }
+
+    /// Compact binary encoding keeping everything but element provenance; see
+    /// `to_bytes_with`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with(&SerializationProfile::full())
+    }
+
+    /// Compact binary encoding, usually a fraction of the JSON size, dropping what
+    /// `profile` leaves out. Element ids, selectors and list order survive the round
+    /// trip through `from_bytes`.
+    pub fn to_bytes_with(&self, profile: &SerializationProfile) -> Vec<u8> {
+        crate::dom::compact::encode(self, profile)
+    }
+
+    /// Decode a state written by `to_bytes` or `to_bytes_with`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        crate::dom::compact::decode(bytes)
+    }
+
+    /// Hash of the interactive elements' tags, roles and labels in page order, with
+    /// digits ignored; equal for pages that differ only in volatile text
+    pub fn structural_fingerprint(&self) -> String {
+        self.structural_fingerprint_with(&SnapshotOptions::default())
+            .unwrap_or_default()
+    }
+
+    /// `structural_fingerprint` with custom ignore patterns; fails on an invalid one
+    pub fn structural_fingerprint_with(&self, options: &SnapshotOptions) -> Result<String> {
+        Ok(StructuralSnapshot::from_state(self, options)?.fingerprint)
+    }
+
+    pub fn structural_snapshot(&self, options: &SnapshotOptions) -> Result<StructuralSnapshot> {
+        StructuralSnapshot::from_state(self, options)
+    }
+
+    /// Compare the page structure with the snapshot at `path`. The first run writes
+    /// it, as does any run with `SURFAI_UPDATE_SNAPSHOTS=1`; a mismatch fails with
+    /// `AssertionFailed` listing the elements added, removed and relabelled.
+    pub fn assert_matches_snapshot(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.assert_matches_snapshot_with(path, &SnapshotOptions::default())
+    }
+
+    /// `assert_matches_snapshot` with custom ignore patterns for a new snapshot;
+    /// existing snapshots are compared with the options they were written with
+    pub fn assert_matches_snapshot_with(
+        &self,
+        path: impl AsRef<Path>,
+        options: &SnapshotOptions,
+    ) -> Result<()> {
+        crate::dom::snapshot::check_snapshot(self, path.as_ref(), options)?
+            .map_err(BrowserAgentError::AssertionFailed)
+    }
+
     pub fn element_count(&self) -> usize {
         self.elements.len()
     }
@@ -58,6 +151,91 @@ impl DomState {
             .collect()
     }
 
+    /// Why extraction kept the element with `DomElement::id` (or, failing that,
+    /// HTML id) `element_id`, one fact per line. Provenance is only recorded
+    /// when `DomConfig::explain` is set; the rule's match counts only when
+    /// `DomConfig::collect_stats` is.
+    pub fn explain(&self, element_id: &str) -> String {
+        let Some(element) = self
+            .elements
+            .iter()
+            .find(|e| e.id == element_id)
+            .or_else(|| {
+                self.elements
+                    .iter()
+                    .find(|e| e.element_id.as_deref() == Some(element_id))
+            })
+        else {
+            return format!("{}: no such element", element_id);
+        };
+
+        let mut lines = vec![format!(
+            "{} <{}{}>{}",
+            element.id,
+            element.tag_name,
+            element
+                .element_id
+                .as_ref()
+                .map(|id| format!("#{}", id))
+                .unwrap_or_default(),
+            element
+                .text_content
+                .as_ref()
+                .map(|text| format!(" '{}'", text.chars().take(60).collect::<String>()))
+                .unwrap_or_default()
+        )];
+        let Some(provenance) = &element.provenance else {
+            lines.push("  no provenance; extract with DomConfig::explain set".to_string());
+            return lines.join("\n");
+        };
+
+        let counts = self
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.selector(&provenance.rule))
+            .map(|stats| {
+                format!(
+                    "; matched {}, added {} on this page",
+                    stats.matched, stats.added
+                )
+            })
+            .unwrap_or_default();
+        lines.push(format!(
+            "  extracted by `{}` ({}{})",
+            provenance.rule, provenance.rule_kind, counts
+        ));
+        if !provenance.also_matched.is_empty() {
+            lines.push(format!(
+                "  also matched `{}`, skipped as already extracted",
+                provenance.also_matched.join("`, `")
+            ));
+        }
+        if provenance.lookalikes_dropped > 0 {
+            lines.push(format!(
+                "  kept as the first of {} elements with the same tag and attributes",
+                provenance.lookalikes_dropped + 1
+            ));
+        }
+        lines.push(format!(
+            "  clickable: {}",
+            provenance.clickable_because.as_deref().unwrap_or("no")
+        ));
+        lines.push(format!(
+            "  interactable: {}",
+            provenance.interactable_because.as_deref().unwrap_or("no")
+        ));
+        lines.push(format!(
+            "  {}: {}",
+            if element.is_visible {
+                "visible"
+            } else {
+                "hidden"
+            },
+            provenance.visibility
+        ));
+        lines.join("\n")
+    }
+
     pub fn find_elements_by_text(&self, text: &str) -> Vec<&DomElement> {
         self.elements
             .iter()
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// What one extraction rule contributed to a page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectorStats {
+    pub selector: String,
+    /// Elements the selector matched inside the extraction root
+    pub matched: usize,
+    /// Elements extracted because of this selector; the rest were already taken by
+    /// an earlier selector, excluded by a site profile or, for text selectors,
+    /// too short
+    pub added: usize,
+}
+
+/// Telemetry from one `DomState` extraction, collected when
+/// `DomConfig::collect_stats` is set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionStats {
+    /// Every selector tried, in the order they ran
+    pub selectors: Vec<SelectorStats>,
+    /// Matches skipped because an earlier selector already extracted the element
+    pub duplicates: usize,
+    /// Matches skipped because a site profile excludes them
+    pub excluded: usize,
+    /// Extracted elements whose attributes mark them hidden; they are kept with
+    /// `is_visible` unset
+    pub hidden: usize,
+    /// Time spent parsing the page's HTML
+    pub parse_ms: f64,
+    /// Time spent matching selectors and building elements
+    pub match_ms: f64,
+    /// Calls to the browser made by the extraction, scripts included
+    pub round_trips: usize,
+}
+
+impl ExtractionStats {
+    /// Stats for `selector`, if it ran
+    pub fn selector(&self, selector: &str) -> Option<&SelectorStats> {
+        self.selectors
+            .iter()
+            .find(|stats| stats.selector == selector)
+    }
+
+    /// Elements extracted across all selectors
+    pub fn added(&self) -> usize {
+        self.selectors.iter().map(|stats| stats.added).sum()
+    }
+
+    /// Share of the extracted elements added by `selectors`, from 0 to 1
+    pub fn share_of(&self, selectors: &[&str]) -> f64 {
+        let total = self.added();
+        if total == 0 {
+            return 0.0;
+        }
+        let added: usize = self
+            .selectors
+            .iter()
+            .filter(|stats| selectors.contains(&stats.selector.as_str()))
+            .map(|stats| stats.added)
+            .sum();
+        added as f64 / total as f64
+    }
+
+    pub(crate) fn record(&mut self, selector: &str, matched: usize, added: usize) {
+        self.selectors.push(SelectorStats {
+            selector: selector.to_string(),
+            matched,
+            added,
+        });
+    }
+}
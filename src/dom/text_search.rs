@@ -0,0 +1,272 @@
+use crate::browser::highlight::HIGHLIGHT_CLASS;
+use crate::dom::xpath::{element_from_value, DESCRIBE_ELEMENT_JS};
+use crate::dom::{DomElement, ElementRect};
+use crate::errors::{BrowserAgentError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How `find_text` interprets and reports the query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextSearchOptions {
+    /// Treat the query as a JavaScript regular expression
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Only match where the query is not surrounded by letters or digits
+    pub whole_word: bool,
+    /// Characters of surrounding text included in each snippet
+    pub context_chars: usize,
+    pub max_matches: usize,
+    /// Scroll to and highlight this match (0-based)
+    pub highlight_match: Option<usize>,
+}
+
+impl Default for TextSearchOptions {
+    fn default() -> Self {
+        Self {
+            regex: false,
+            case_sensitive: false,
+            whole_word: false,
+            context_chars: 40,
+            max_matches: 100,
+            highlight_match: None,
+        }
+    }
+}
+
+impl TextSearchOptions {
+    pub fn regex(mut self) -> Self {
+        self.regex = true;
+        self
+    }
+
+    pub fn case_sensitive(mut self) -> Self {
+        self.case_sensitive = true;
+        self
+    }
+
+    pub fn whole_word(mut self) -> Self {
+        self.whole_word = true;
+        self
+    }
+
+    pub fn with_context(mut self, chars: usize) -> Self {
+        self.context_chars = chars;
+        self
+    }
+
+    pub fn with_max_matches(mut self, max: usize) -> Self {
+        self.max_matches = max;
+        self
+    }
+
+    pub fn highlight(mut self, index: usize) -> Self {
+        self.highlight_match = Some(index);
+        self
+    }
+}
+
+/// One occurrence of the query in the rendered text
+#[derive(Debug, Clone)]
+pub struct TextMatch {
+    pub index: usize,
+    /// The matched text as rendered
+    pub text: String,
+    /// The match with surrounding context
+    pub snippet: String,
+    /// Viewport rect covering the match, which may span several inline elements
+    pub rect: ElementRect,
+    /// Innermost element containing the whole match
+    pub element: DomElement,
+}
+
+/// Script that finds `query` in the visible text nodes of the page.
+///
+/// Text nodes are concatenated in document order, with a line break between nodes
+/// that belong to different block elements, so a match may span inline tags such as
+/// `<b>` or `<span>` but not separate paragraphs.
+pub(crate) fn find_text_script(query: &str, options: &TextSearchOptions) -> Result<String> {
+    Ok(format!(
+        r#"
+        (function() {{
+            const query = {query};
+            const options = {options};
+{describe}
+            const escapeRegex = (s) => s.replace(/[.*+?^${{}}()|[\]\\]/g, '\\$&');
+            let source = options.regex ? query : escapeRegex(query);
+            if (options.whole_word) {{
+                source = '(?<![\\p{{L}}\\p{{N}}_])(?:' + source + ')(?![\\p{{L}}\\p{{N}}_])';
+            }}
+            let pattern;
+            try {{
+                pattern = new RegExp(source, 'gu' + (options.case_sensitive ? '' : 'i'));
+            }} catch (e) {{
+                return {{ success: false, error: e.message }};
+            }}
+
+            const blockOf = (node) => {{
+                let el = node.parentElement;
+                while (el && el !== document.body) {{
+                    const display = window.getComputedStyle(el).display;
+                    if (display !== 'inline' && display !== 'inline-block' && display !== 'contents') return el;
+                    el = el.parentElement;
+                }}
+                return document.body;
+            }};
+
+            const walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT, {{
+                acceptNode: (node) => {{
+                    const parent = node.parentElement;
+                    if (!parent || ['SCRIPT', 'STYLE', 'NOSCRIPT', 'TEMPLATE'].includes(parent.tagName)) {{
+                        return NodeFilter.FILTER_REJECT;
+                    }}
+                    if (!node.textContent) return NodeFilter.FILTER_REJECT;
+                    const style = window.getComputedStyle(parent);
+                    if (style.display === 'none' || style.visibility === 'hidden') {{
+                        return NodeFilter.FILTER_REJECT;
+                    }}
+                    return NodeFilter.FILTER_ACCEPT;
+                }}
+            }});
+
+            let text = '';
+            const segments = [];
+            let lastBlock = null;
+            while (walker.nextNode()) {{
+                const node = walker.currentNode;
+                const block = blockOf(node);
+                if (lastBlock && block !== lastBlock) text += '\n';
+                lastBlock = block;
+                segments.push({{ node: node, start: text.length }});
+                text += node.textContent;
+            }}
+
+            const locate = (offset, preferNext) => {{
+                for (let i = segments.length - 1; i >= 0; i--) {{
+                    const seg = segments[i];
+                    const end = seg.start + seg.node.textContent.length;
+                    if (offset > seg.start || (offset === seg.start && (preferNext || i === 0))) {{
+                        if (offset <= end) return {{ node: seg.node, offset: offset - seg.start }};
+                        return preferNext && segments[i + 1]
+                            ? {{ node: segments[i + 1].node, offset: 0 }}
+                            : {{ node: seg.node, offset: seg.node.textContent.length }};
+                    }}
+                }}
+                return null;
+            }};
+
+            const matches = [];
+            let found;
+            while ((found = pattern.exec(text)) !== null && matches.length < options.max_matches) {{
+                if (found[0].length === 0) {{
+                    pattern.lastIndex++;
+                    continue;
+                }}
+                const startPos = locate(found.index, true);
+                const endPos = locate(found.index + found[0].length, false);
+                if (!startPos || !endPos) continue;
+
+                const range = document.createRange();
+                range.setStart(startPos.node, startPos.offset);
+                range.setEnd(endPos.node, endPos.offset);
+                const rect = range.getBoundingClientRect();
+                let owner = range.commonAncestorContainer;
+                if (owner.nodeType !== 1) owner = owner.parentElement;
+
+                const from = Math.max(0, found.index - options.context_chars);
+                const to = Math.min(text.length, found.index + found[0].length + options.context_chars);
+                matches.push({{
+                    text: found[0],
+                    snippet: (from > 0 ? '…' : '') + text.slice(from, to).replace(/\s+/g, ' ') + (to < text.length ? '…' : ''),
+                    rect: {{ x: rect.x, y: rect.y, width: rect.width, height: rect.height }},
+                    element: describeElement(owner),
+                    range: range
+                }});
+            }}
+
+            const target = options.highlight_match;
+            if (target !== null && target !== undefined && matches[target]) {{
+                const range = matches[target].range;
+                const owner = range.commonAncestorContainer.nodeType === 1
+                    ? range.commonAncestorContainer
+                    : range.commonAncestorContainer.parentElement;
+                owner.scrollIntoView({{ block: 'center', inline: 'nearest' }});
+                for (const r of range.getClientRects()) {{
+                    const mark = document.createElement('div');
+                    mark.className = '{highlight_class} surfai-text-match';
+                    mark.setAttribute('data-surfai-internal', '');
+                    mark.style.cssText = 'position:absolute;pointer-events:none;z-index:2147483647;'
+                        + 'background:rgba(255,213,0,0.45);outline:2px solid #ff9800;'
+                        + 'left:' + (r.left + window.scrollX) + 'px;top:' + (r.top + window.scrollY) + 'px;'
+                        + 'width:' + r.width + 'px;height:' + r.height + 'px;';
+                    document.body.appendChild(mark);
+                }}
+                const rect = range.getBoundingClientRect();
+                matches[target].rect = {{ x: rect.x, y: rect.y, width: rect.width, height: rect.height }};
+            }}
+
+            return {{
+                success: true,
+                matches: matches.map(m => ({{ text: m.text, snippet: m.snippet, rect: m.rect, element: m.element }}))
+            }};
+        }})()
+        "#,
+        query = serde_json::to_string(query)?,
+        options = serde_json::to_string(options)?,
+        describe = DESCRIBE_ELEMENT_JS,
+        highlight_class = HIGHLIGHT_CLASS
+    ))
+}
+
+/// Parse the result of `find_text_script`
+pub(crate) fn matches_from_value(result: &Value) -> Result<Vec<TextMatch>> {
+    if !result
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        let error = result
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("text search failed");
+        return Err(BrowserAgentError::JavaScriptFailed(error.to_string()));
+    }
+
+    let matches = result
+        .get("matches")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(matches
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let field = |name: &str| {
+                value
+                    .get(name)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            let rect = value
+                .get("rect")
+                .and_then(|r| serde_json::from_value(r.clone()).ok())
+                .unwrap_or(ElementRect {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 0.0,
+                    height: 0.0,
+                });
+            TextMatch {
+                index,
+                text: field("text"),
+                snippet: field("snippet"),
+                rect,
+                element: element_from_value(
+                    value.get("element").unwrap_or(&Value::Null),
+                    format!("text_match_{}", index + 1),
+                ),
+            }
+        })
+        .collect())
+}
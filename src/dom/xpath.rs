@@ -0,0 +1,243 @@
+use crate::dom::{DomElement, ElementRect};
+use serde_json::Value;
+
+/// Quote `value` as an XPath string literal.
+///
+/// XPath 1.0 has no escape sequences, so a value containing both quote types
+/// is split and rebuilt with `concat()`.
+pub fn xpath_literal(value: &str) -> String {
+    if !value.contains('\'') {
+        format!("'{}'", value)
+    } else if !value.contains('"') {
+        format!("\"{}\"", value)
+    } else {
+        let parts: Vec<String> = value
+            .split('\'')
+            .map(|part| format!("'{}'", part))
+            .collect();
+        format!("concat({})", parts.join(", \"'\", "))
+    }
+}
+
+/// JavaScript expression resolving the first node matching `xpath`
+pub(crate) fn first_node_expression(xpath: &str) -> String {
+    format!(
+        "document.evaluate({}, document, null, XPathResult.FIRST_ORDERED_NODE_TYPE, null).singleNodeValue",
+        serde_json::to_string(xpath).unwrap_or_else(|_| "''".to_string())
+    )
+}
+
+/// JavaScript defining `describeElement(node)`, which returns the element fields read
+/// by `element_from_value`, and `scrollParents(node)`, the scrollable ancestors of
+/// `node` from the nearest outwards
+pub(crate) const DESCRIBE_ELEMENT_JS: &str = r#"
+            const absoluteXPath = (node) => {
+                const parts = [];
+                while (node && node.nodeType === 1) {
+                    let index = 1;
+                    let sibling = node.previousElementSibling;
+                    while (sibling) {
+                        if (sibling.tagName === node.tagName) index++;
+                        sibling = sibling.previousElementSibling;
+                    }
+                    parts.unshift(node.tagName.toLowerCase() + '[' + index + ']');
+                    node = node.parentElement;
+                }
+                return '/' + parts.join('/');
+            };
+
+            const cssPath = (node) => {
+                if (node.id) return node.tagName.toLowerCase() + '#' + CSS.escape(node.id);
+                const parts = [];
+                while (node && node.nodeType === 1 && node !== document.documentElement) {
+                    let index = 1;
+                    let sibling = node.previousElementSibling;
+                    while (sibling) {
+                        if (sibling.tagName === node.tagName) index++;
+                        sibling = sibling.previousElementSibling;
+                    }
+                    parts.unshift(node.tagName.toLowerCase() + ':nth-of-type(' + index + ')');
+                    node = node.parentElement;
+                }
+                return 'html > ' + parts.join(' > ');
+            };
+
+            const isScrollable = (node) => {
+                const style = window.getComputedStyle(node);
+                const scrollsY = /(auto|scroll|overlay)/.test(style.overflowY) && node.scrollHeight > node.clientHeight;
+                const scrollsX = /(auto|scroll|overlay)/.test(style.overflowX) && node.scrollWidth > node.clientWidth;
+                return scrollsY || scrollsX;
+            };
+
+            const scrollParents = (node) => {
+                const parents = [];
+                let parent = node.parentElement;
+                while (parent && parent !== document.body && parent !== document.documentElement) {
+                    if (isScrollable(parent)) parents.push(parent);
+                    parent = parent.parentElement;
+                }
+                return parents;
+            };
+
+            const describeElement = (node) => {
+                const attributes = {};
+                for (const attr of node.attributes) {
+                    attributes[attr.name] = attr.value;
+                }
+                const rect = node.getBoundingClientRect();
+                const style = window.getComputedStyle(node);
+                const centerX = rect.left + rect.width / 2;
+                const centerY = rect.top + rect.height / 2;
+                let occludedBy = null;
+                if (centerX >= 0 && centerY >= 0 && centerX < window.innerWidth && centerY < window.innerHeight) {
+                    const top = document.elementFromPoint(centerX, centerY);
+                    if (top && top !== node && !node.contains(top) && !top.contains(node)) {
+                        occludedBy = cssPath(top);
+                    }
+                }
+                return {
+                    tagName: node.tagName.toLowerCase(),
+                    attributes: attributes,
+                    text: (node.innerText || node.textContent || '').trim(),
+                    rect: { x: rect.x, y: rect.y, width: rect.width, height: rect.height },
+                    visible: rect.width > 0 && rect.height > 0 && style.visibility !== 'hidden' && style.display !== 'none',
+                    occludedBy: occludedBy,
+                    xpath: absoluteXPath(node),
+                    cssSelector: cssPath(node),
+                    scrollParents: scrollParents(node).map(cssPath)
+                };
+            };
+"#;
+
+/// Script returning a description of every element matching `xpath`
+pub(crate) fn query_script(xpath: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            let snapshot;
+            try {{
+                snapshot = document.evaluate({}, document, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null);
+            }} catch (e) {{
+                return {{ success: false, error: e.message }};
+            }}
+{}
+            const elements = [];
+            for (let i = 0; i < snapshot.snapshotLength; i++) {{
+                const node = snapshot.snapshotItem(i);
+                if (!node || node.nodeType !== 1) continue;
+                elements.push(describeElement(node));
+            }}
+            return {{ success: true, elements: elements }};
+        }})()
+        "#,
+        serde_json::to_string(xpath).unwrap_or_else(|_| "''".to_string()),
+        DESCRIBE_ELEMENT_JS
+    )
+}
+
+/// Build a `DomElement` from the output of `describeElement`
+pub(crate) fn element_from_value(value: &Value, id: String) -> DomElement {
+    let tag_name = value
+        .get("tagName")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let mut element = DomElement::new(tag_name, id);
+
+    if let Some(attributes) = value.get("attributes").and_then(|v| v.as_object()) {
+        for (key, attr_value) in attributes {
+            let attr_value = attr_value.as_str().unwrap_or("").to_string();
+            if key == "id" {
+                element.element_id = Some(attr_value.clone());
+            } else if key == "class" {
+                element.class_name = Some(attr_value.clone());
+            }
+            element = element.with_attribute(key.clone(), attr_value);
+        }
+    }
+
+    if let Some(text) = value.get("text").and_then(|v| v.as_str()) {
+        if !text.is_empty() {
+            element = element.with_text_content(text.to_string());
+        }
+    }
+
+    if let Some(rect) = value.get("rect") {
+        let field = |name: &str| rect.get(name).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        element = element.with_rect(ElementRect {
+            x: field("x"),
+            y: field("y"),
+            width: field("width"),
+            height: field("height"),
+        });
+    }
+
+    element.is_visible = value
+        .get("visible")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    element.is_occluded = value.get("occludedBy").is_some_and(|v| v.is_string());
+    element.xpath = value
+        .get("xpath")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    element.css_selector = value
+        .get("cssSelector")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    element.scroll_parents = value
+        .get("scrollParents")
+        .and_then(|v| v.as_array())
+        .map(|parents| {
+            parents
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let clickable = matches!(
+        element.tag_name.as_str(),
+        "a" | "button" | "input" | "select" | "summary" | "area"
+    ) || element.attributes.contains_key("onclick")
+        || element.attributes.get("role").map(|r| r.as_str()) == Some("button");
+    let interactable = matches!(element.tag_name.as_str(), "input" | "textarea" | "select")
+        || element
+            .attributes
+            .get("contenteditable")
+            .map(|v| v.as_str())
+            == Some("true");
+
+    element
+        .set_clickable(clickable)
+        .set_interactable(interactable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_without_single_quotes_uses_them() {
+        assert_eq!(xpath_literal("Sign in"), "'Sign in'");
+        assert_eq!(xpath_literal(""), "''");
+        assert_eq!(xpath_literal("say \"hi\""), "'say \"hi\"'");
+    }
+
+    #[test]
+    fn literal_with_single_quotes_uses_double_quotes() {
+        assert_eq!(xpath_literal("O'Brien"), "\"O'Brien\"");
+    }
+
+    #[test]
+    fn literal_with_both_quotes_is_concatenated() {
+        assert_eq!(
+            xpath_literal("it's \"here\""),
+            "concat('it', \"'\", 's \"here\"')"
+        );
+        assert_eq!(xpath_literal("'\"'"), "concat('', \"'\", '\"', \"'\", '')");
+    }
+}
@@ -5,6 +5,12 @@ pub enum BrowserAgentError {
     #[error("Browser launch failed: {0}")]
     LaunchFailed(String),
 
+    #[error("Browser version {found} is older than the required {minimum}")]
+    UnsupportedBrowserVersion { found: String, minimum: u32 },
+
+    #[error("{feature} is not available on the {backend} backend")]
+    FeatureUnavailable { feature: String, backend: String },
+
     #[error("Browser not launched")]
     BrowserNotLaunched,
 
@@ -14,15 +20,65 @@ pub enum BrowserAgentError {
     #[error("No active tab")]
     NoActiveTab,
 
+    #[error("Tab crashed at {0}")]
+    TabCrashed(String),
+
+    #[error("Session is closed")]
+    SessionClosed,
+
+    #[error("Session busy: {0}")]
+    SessionBusy(String),
+
     #[error("Navigation failed: {0}")]
     NavigationFailed(String),
 
+    #[error("Navigation to {url} blocked by {rule}")]
+    NavigationBlocked { url: String, rule: String },
+
     #[error("DOM extraction failed: {0}")]
     DomExtractionFailed(String),
 
     #[error("Element not found: {0}")]
     ElementNotFound(String),
 
+    #[error("Element {selector} is covered by {covering_selector}")]
+    ElementOccluded {
+        selector: String,
+        covering_selector: String,
+    },
+
+    #[error("Element {selector} is not actionable: {check} check failed ({detail})")]
+    NotActionable {
+        selector: String,
+        check: String,
+        detail: String,
+    },
+
+    #[error("Label '{label}' matches several fields: {}", .candidates.join(", "))]
+    AmbiguousLabel {
+        label: String,
+        candidates: Vec<String>,
+    },
+
+    #[error("Login failed: {0}")]
+    LoginFailed(String),
+
+    #[error("Logged out: {0}")]
+    LoggedOut(String),
+
+    #[error("Element detached from the document: {0}")]
+    ElementDetached(String),
+
+    #[error("{operation} needs an HTML page, but {url} is a {kind} document")]
+    UnsupportedDocument {
+        operation: String,
+        kind: crate::dom::DocumentKind,
+        url: String,
+    },
+
+    #[error("Frame not found: {0}")]
+    FrameNotFound(String),
+
     #[error("JavaScript execution failed: {0}")]
     JavaScriptFailed(String),
 
@@ -50,9 +106,21 @@ pub enum BrowserAgentError {
     #[error("Timeout error: {0}")]
     TimeoutError(String),
 
+    #[error("Operation cancelled: {0}")]
+    Cancelled(String),
+
     #[error("Chrome error: {0}")]
     ChromeError(String),
 
+    #[error("WebDriver error: {0}")]
+    WebDriverError(String),
+
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+
+    #[error("Audit log integrity check failed: {0}")]
+    AuditIntegrity(String),
+
     #[error("Anyhow error: {0}")]
     AnyhowError(String),
 }
@@ -9,6 +9,9 @@
 //! - **Browser**: Browser implementation (Chrome, Firefox, etc.)
 //! - **DOM**: DOM processing and state management
 //! - **Actions**: Action registry and execution system
+//! - **Testing**: Assertions for browser-driven test suites
+//! - **Tools**: Higher-level utilities built on sessions, such as link checking
+//! - **Replay**: Recorded runs with per-step page states, for stepping through afterwards
 //! - **Utils**: Shared utilities
 //! - **Errors**: Comprehensive error handling
 
@@ -17,14 +20,32 @@ pub mod browser;
 pub mod core;
 pub mod dom;
 pub mod errors;
+pub mod replay;
+pub mod testing;
+pub mod tools;
 pub mod utils;
 
 // Re-export commonly used types for convenience
-pub use actions::{ActionRegistry, ActionResult};
-pub use browser::{AIElement, BrowserSession, ChromeBrowser, LoginConfig, NavigationResult};
-pub use core::{BrowserTrait, Config, DomProcessorTrait, SessionTrait};
-pub use dom::{DomElement, DomProcessor, DomState};
+pub use actions::{ActionRegistry, ActionResult, ConflictPolicy};
+pub use browser::{
+    AIElement, BrowserSession, ChromeBrowser, ClickRectOptions, DryRunReport, EventStream,
+    InjectOptions, InjectReport, LoginConfig, NavigationResult, SessionEvent, SessionHandle,
+    SubmitOutcome, SubmitResult, TypeMode, TypeResult,
+};
+pub use core::{
+    BrowserTrait, CallOptions, Config, ConsentChoice, DomProcessorTrait, LanguagePack, Selector,
+    SessionTrait, SiteProfile, UserAgentOverride, UserAgentProfile,
+};
+pub use dom::{
+    AuditReport, CoordinateSpace, DefaultLabeler, DomDiff, DomElement, DomProcessor, DomState,
+    ElementAnnotation, ElementLabeler, ExtractionStats, FieldValue, LabelSource, LabeledField,
+    PageContext, PageReport, PageStats, ScrollAlignment, TextMatch, TextSearchOptions, Viewport,
+};
 pub use errors::{BrowserAgentError, Result};
+pub use utils::{
+    ArtifactRef, ArtifactSink, CancellationToken, InMemorySink, LocalDirSink, RetryPolicy,
+    SecretString,
+};
 
 // Type aliases for convenience
 pub type DefaultBrowser = ChromeBrowser;
@@ -0,0 +1,138 @@
+use crate::actions::ActionResult;
+use crate::core::ConsoleMessage;
+use crate::errors::{BrowserAgentError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Version written into new bundles. `ReplayRun::open` reads this version and
+/// older ones.
+pub const RUN_BUNDLE_VERSION: u32 = 1;
+
+/// File describing the run, at the root of the bundle
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Bytes of states and screenshots a bundle holds unless set otherwise
+pub const DEFAULT_BUDGET_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Width screenshots are downsampled to unless set otherwise
+pub const DEFAULT_SCREENSHOT_WIDTH: u32 = 800;
+
+/// Screenshots are not shrunk below this width to fit the budget; they are
+/// dropped instead
+pub(crate) const MIN_SCREENSHOT_WIDTH: u32 = 160;
+
+/// What a `RunRecorder` keeps for each step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunBundleOptions {
+    /// Screenshot the page after each step
+    pub screenshots: bool,
+    /// Screenshots wider than this are downsampled
+    pub screenshot_max_width: u32,
+    /// Bytes of states and screenshots the bundle may hold. Screenshots are
+    /// shrunk further, then left out, and states left out once it is spent.
+    pub max_bytes: u64,
+}
+
+impl Default for RunBundleOptions {
+    fn default() -> Self {
+        Self {
+            screenshots: true,
+            screenshot_max_width: DEFAULT_SCREENSHOT_WIDTH,
+            max_bytes: DEFAULT_BUDGET_BYTES,
+        }
+    }
+}
+
+impl RunBundleOptions {
+    pub fn without_screenshots(mut self) -> Self {
+        self.screenshots = false;
+        self
+    }
+
+    pub fn with_screenshot_width(mut self, max_width: u32) -> Self {
+        self.screenshot_max_width = max_width;
+        self
+    }
+
+    pub fn with_budget(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+}
+
+/// One recorded step, as stored in the manifest. File names are relative to
+/// the bundle directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepRecord {
+    /// 1-based position in the run
+    pub index: usize,
+    pub command: String,
+    #[serde(default)]
+    pub params: Value,
+    pub result: ActionResult,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// URL before the step ran
+    #[serde(default)]
+    pub url: Option<String>,
+    /// URL after the step ran
+    #[serde(default)]
+    pub post_url: Option<String>,
+    /// `DomState::to_bytes` encoding of the page before and after the step
+    #[serde(default)]
+    pub pre_state: Option<String>,
+    #[serde(default)]
+    pub post_state: Option<String>,
+    /// PNG of the page after the step
+    #[serde(default)]
+    pub screenshot: Option<String>,
+    /// Console errors and uncaught exceptions while the step ran
+    #[serde(default)]
+    pub console_errors: Vec<ConsoleMessage>,
+    /// What was left out to stay within the budget, e.g. `screenshot`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<String>,
+}
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub version: u32,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Set by `RunRecorder::finish`; `None` for a run that stopped midway
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub options: RunBundleOptions,
+    /// Bytes of states and screenshots written so far
+    #[serde(default)]
+    pub bytes_written: u64,
+    pub steps: Vec<StepRecord>,
+}
+
+impl RunManifest {
+    pub(crate) fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let manifest: Self = serde_json::from_slice(&std::fs::read(&path)?)?;
+        if manifest.version > RUN_BUNDLE_VERSION {
+            return Err(BrowserAgentError::ConfigurationError(format!(
+                "{} is a version {} run bundle; this build reads up to version {}",
+                dir.display(),
+                manifest.version,
+                RUN_BUNDLE_VERSION
+            )));
+        }
+        Ok(manifest)
+    }
+
+    /// Write the manifest through a temporary file, so a reader never sees half
+    /// of it
+    pub(crate) fn save(&self, dir: &Path) -> Result<()> {
+        let temporary = dir.join(format!("{}.tmp", MANIFEST_FILE));
+        std::fs::write(&temporary, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&temporary, dir.join(MANIFEST_FILE))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+//! Recorded runs for stepping through after the fact.
+//!
+//! A `RunRecorder` writes a bundle directory while a run executes:
+//!
+//! ```text
+//! manifest.json          format version, options and one record per step
+//! states/001-pre.bin     page before step 1, as `DomState::to_bytes`
+//! states/001-post.bin    page after step 1
+//! screenshots/001.png    downsampled screenshot after step 1
+//! ```
+//!
+//! The manifest is rewritten after every step, so a crashed run still opens.
+//! `ReplayRun` reads a bundle back, giving each step's command, result, console
+//! errors, states and the diff between them, and `ReportBuilder` turns a run or an
+//! audit log into a standalone HTML report.
+
+pub mod bundle;
+pub mod recorder;
+pub mod report;
+pub mod run;
+
+pub use bundle::{
+    RunBundleOptions, RunManifest, StepRecord, DEFAULT_BUDGET_BYTES, DEFAULT_SCREENSHOT_WIDTH,
+    MANIFEST_FILE, RUN_BUNDLE_VERSION,
+};
+pub use recorder::RunRecorder;
+pub use report::{NavigationEntry, NetworkSummary, ReportBuilder, TimelineEntry};
+pub use run::{ReplayRun, ReplayStep};
@@ -0,0 +1,228 @@
+use super::bundle::{RunBundleOptions, RunManifest, StepRecord, MIN_SCREENSHOT_WIDTH};
+use crate::actions::ActionResult;
+use crate::browser::BrowserSession;
+use crate::core::{BrowserTrait, ConsoleLevel, SessionTrait};
+use crate::dom::DomState;
+use crate::errors::Result;
+use crate::utils::downscale_png;
+use chrono::Utc;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Writes a run bundle while the run executes.
+///
+/// Each `record` call captures the page before and after one step, a
+/// downsampled screenshot, the console errors the step caused and its
+/// `ActionResult`, then rewrites the manifest, so a run that crashes midway
+/// still leaves a readable bundle. Recording problems are logged and never fail
+/// the step.
+pub struct RunRecorder {
+    dir: PathBuf,
+    manifest: Mutex<RunManifest>,
+    /// Post state file of the last step with the URL and fingerprint it had, reused
+    /// as the next step's pre state when the page has not changed in between
+    last_state: Mutex<Option<(String, String, String)>>,
+}
+
+impl RunRecorder {
+    /// Start a bundle named `name` in `dir`, creating the directory. An existing
+    /// bundle there is replaced.
+    pub fn create(
+        dir: impl AsRef<Path>,
+        name: impl Into<String>,
+        options: RunBundleOptions,
+    ) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        for sub in ["states", "screenshots"] {
+            let path = dir.join(sub);
+            if path.exists() {
+                std::fs::remove_dir_all(&path)?;
+            }
+            std::fs::create_dir_all(&path)?;
+        }
+        let manifest = RunManifest {
+            version: super::RUN_BUNDLE_VERSION,
+            name: name.into(),
+            created_at: Utc::now(),
+            finished_at: None,
+            options,
+            bytes_written: 0,
+            steps: Vec::new(),
+        };
+        manifest.save(&dir)?;
+        Ok(Self {
+            dir,
+            manifest: Mutex::new(manifest),
+            last_state: Mutex::new(None),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Run `step` on `session` and record it as `command` with `params`. The
+    /// step's own result is returned unchanged; an `Err` is recorded as a failed
+    /// `ActionResult`.
+    pub async fn record<B, F>(
+        &self,
+        session: &BrowserSession<B>,
+        command: &str,
+        params: Value,
+        step: F,
+    ) -> Result<ActionResult>
+    where
+        B: BrowserTrait,
+        F: std::future::Future<Output = Result<ActionResult>>,
+    {
+        let index = self.manifest.lock().unwrap().steps.len() + 1;
+        let pre = session.get_page_state(false).await.ok();
+        let console_before = session
+            .console_messages()
+            .await
+            .map(|messages| messages.len())
+            .unwrap_or(0);
+
+        let started_at = Utc::now();
+        let started = std::time::Instant::now();
+        let outcome = step.await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let result = match &outcome {
+            Ok(result) => result.clone(),
+            Err(e) => ActionResult::failure(e.to_string()).with_execution_time(duration_ms),
+        };
+
+        let post = session.get_page_state(false).await.ok();
+        let console_errors = session
+            .console_messages()
+            .await
+            .map(|messages| {
+                messages
+                    .into_iter()
+                    .skip(console_before)
+                    .filter(|message| message.level == ConsoleLevel::Error)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let screenshot = if self.options().screenshots {
+            session.screenshot().await.ok()
+        } else {
+            None
+        };
+
+        let mut record = StepRecord {
+            index,
+            command: command.to_string(),
+            params,
+            result,
+            started_at,
+            duration_ms,
+            url: pre.as_ref().map(|state| state.url.clone()),
+            post_url: post.as_ref().map(|state| state.url.clone()),
+            pre_state: None,
+            post_state: None,
+            screenshot: None,
+            console_errors,
+            dropped: Vec::new(),
+        };
+        if let Err(e) = self.store(&mut record, pre, post, screenshot) {
+            tracing::warn!(dir = %self.dir.display(), step = index, error = %e, "recording a run step failed");
+        }
+        outcome
+    }
+
+    /// Mark the run as finished and return the bundle directory
+    pub fn finish(self) -> Result<PathBuf> {
+        let mut manifest = self.manifest.into_inner().unwrap();
+        manifest.finished_at = Some(Utc::now());
+        manifest.save(&self.dir)?;
+        println!(
+            "🎞️ Recorded {} steps to {} ({} bytes)",
+            manifest.steps.len(),
+            self.dir.display(),
+            manifest.bytes_written
+        );
+        Ok(self.dir)
+    }
+
+    fn options(&self) -> RunBundleOptions {
+        self.manifest.lock().unwrap().options
+    }
+
+    /// Write the step's files within the budget and add it to the manifest
+    fn store(
+        &self,
+        record: &mut StepRecord,
+        pre: Option<DomState>,
+        post: Option<DomState>,
+        screenshot: Option<Vec<u8>>,
+    ) -> Result<()> {
+        let options = self.options();
+        let mut last_state = self.last_state.lock().unwrap();
+
+        if let Some(pre) = pre {
+            let fingerprint = pre.structural_fingerprint();
+            record.pre_state = match last_state.as_ref() {
+                Some((file, url, last)) if *url == pre.url && *last == fingerprint => {
+                    Some(file.clone())
+                }
+                _ => self.write_state(record, "pre", &pre)?,
+            };
+        }
+        if let Some(post) = post {
+            record.post_state = self.write_state(record, "post", &post)?;
+            *last_state = record
+                .post_state
+                .clone()
+                .map(|file| (file, post.url.clone(), post.structural_fingerprint()));
+        }
+        if let Some(png) = screenshot {
+            let mut width = options.screenshot_max_width;
+            let mut scaled = downscale_png(&png, width)?;
+            while !self.fits(scaled.len()) && width / 2 >= MIN_SCREENSHOT_WIDTH {
+                width /= 2;
+                scaled = downscale_png(&png, width)?;
+            }
+            let name = format!("screenshots/{:03}.png", record.index);
+            record.screenshot = self.write(record, "screenshot", &name, &scaled)?;
+        }
+
+        let mut manifest = self.manifest.lock().unwrap();
+        manifest.steps.push(record.clone());
+        manifest.save(&self.dir)
+    }
+
+    fn write_state(
+        &self,
+        record: &mut StepRecord,
+        kind: &str,
+        state: &DomState,
+    ) -> Result<Option<String>> {
+        let name = format!("states/{:03}-{}.bin", record.index, kind);
+        self.write(record, &format!("{}_state", kind), &name, &state.to_bytes())
+    }
+
+    fn fits(&self, len: usize) -> bool {
+        let manifest = self.manifest.lock().unwrap();
+        manifest.bytes_written + len as u64 <= manifest.options.max_bytes
+    }
+
+    /// Write `bytes` under `name` if the budget allows, noting `what` as dropped
+    /// otherwise
+    fn write(
+        &self,
+        record: &mut StepRecord,
+        what: &str,
+        name: &str,
+        bytes: &[u8],
+    ) -> Result<Option<String>> {
+        if !self.fits(bytes.len()) {
+            record.dropped.push(what.to_string());
+            return Ok(None);
+        }
+        std::fs::write(self.dir.join(name), bytes)?;
+        self.manifest.lock().unwrap().bytes_written += bytes.len() as u64;
+        Ok(Some(name.to_string()))
+    }
+}
@@ -0,0 +1,747 @@
+use super::run::ReplayRun;
+use crate::actions::AuditEntry;
+use crate::browser::NavigationResult;
+use crate::core::NetworkIdleStats;
+use crate::dom::PageReport;
+use crate::errors::Result;
+use crate::testing::AssertionFailure;
+use crate::utils::{ArtifactRef, ArtifactSink};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+const TEMPLATE: &str = include_str!("report.html");
+
+/// One action in the report's timeline
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    pub index: usize,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub action: String,
+    pub params: Value,
+    pub success: bool,
+    pub message: String,
+    pub duration_ms: u64,
+    /// URL after the action
+    pub url: Option<String>,
+    /// PNG of the page after the action
+    pub screenshot: Option<Vec<u8>>,
+    pub console_errors: Vec<String>,
+}
+
+/// A page load in the report, from a `NavigationResult` or a step that changed
+/// the URL
+#[derive(Debug, Clone)]
+pub struct NavigationEntry {
+    pub url: String,
+    pub success: bool,
+    /// Load reason, or the action that moved the page
+    pub reason: String,
+    pub duration_ms: Option<u64>,
+    /// Timeline entry that caused the load
+    pub step: Option<usize>,
+    pub network_idle: Option<NetworkIdleStats>,
+}
+
+/// Request totals across the report's navigations and final page
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkSummary {
+    pub navigations: usize,
+    pub failed_navigations: usize,
+    /// Requests seen while waiting for network idle
+    pub requests_seen: usize,
+    pub long_polls_ignored: usize,
+    pub slowest_request: Option<(String, u64)>,
+    /// From the final `PageReport`
+    pub resource_count: Option<usize>,
+    pub transfer_bytes: Option<u64>,
+    pub console_errors: usize,
+}
+
+/// Collects a run's actions, navigations, failures and final page into one
+/// standalone HTML file, with styles and screenshots inlined so it can be shared
+/// as a single artifact.
+///
+/// ```no_run
+/// use surfai::replay::{ReplayRun, ReportBuilder};
+/// use surfai::LocalDirSink;
+///
+/// # async fn run(session: &surfai::DefaultSession) -> surfai::Result<()> {
+/// let report = ReportBuilder::new("Nightly sign-in")
+///     .with_run(&ReplayRun::open("runs/sign-in")?)?
+///     .with_page_report(session.analyze_page().await?);
+/// report.write(&LocalDirSink::new("reports"), "sign-in.html").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReportBuilder {
+    title: String,
+    timeline: Vec<TimelineEntry>,
+    navigations: Vec<NavigationEntry>,
+    failures: Vec<AssertionFailure>,
+    page: Option<PageReport>,
+}
+
+impl ReportBuilder {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            timeline: Vec::new(),
+            navigations: Vec::new(),
+            failures: Vec::new(),
+            page: None,
+        }
+    }
+
+    /// Add every step of a recorded run, with its screenshot and console errors.
+    /// Steps that changed the URL are listed as navigations.
+    pub fn with_run(mut self, run: &ReplayRun) -> Result<Self> {
+        for step in run.steps() {
+            let index = self.timeline.len() + 1;
+            let url = step.post_url().or(step.url()).map(str::to_string);
+            if step.post_url().is_some() && step.post_url() != step.url() {
+                self.navigations.push(NavigationEntry {
+                    url: step.post_url().unwrap_or_default().to_string(),
+                    success: step.result().success,
+                    reason: step.command().to_string(),
+                    duration_ms: Some(step.duration_ms()),
+                    step: Some(index),
+                    network_idle: None,
+                });
+            }
+            self.timeline.push(TimelineEntry {
+                index,
+                timestamp: Some(step.started_at()),
+                action: step.command().to_string(),
+                params: step.params().clone(),
+                success: step.result().success,
+                message: step.result().message.clone(),
+                duration_ms: step.duration_ms(),
+                url,
+                screenshot: step.screenshot()?,
+                console_errors: step
+                    .console_errors()
+                    .iter()
+                    .map(|message| message.text.clone())
+                    .collect(),
+            });
+        }
+        Ok(self)
+    }
+
+    /// Add the actions of an audit log. Entries whose URL changed are listed as
+    /// navigations.
+    pub fn with_audit(mut self, entries: &[AuditEntry]) -> Self {
+        for entry in entries {
+            let index = self.timeline.len() + 1;
+            if let Some(post_url) = entry
+                .post_url
+                .as_ref()
+                .filter(|url| entry.pre_url.as_ref() != Some(*url))
+            {
+                self.navigations.push(NavigationEntry {
+                    url: post_url.clone(),
+                    success: entry.success,
+                    reason: entry.action.clone(),
+                    duration_ms: Some(entry.duration_ms),
+                    step: Some(index),
+                    network_idle: None,
+                });
+            }
+            self.timeline.push(TimelineEntry {
+                index,
+                timestamp: Some(entry.timestamp),
+                action: entry.action.clone(),
+                params: entry.params.clone(),
+                success: entry.success,
+                message: entry.message.clone(),
+                duration_ms: entry.duration_ms,
+                url: entry.post_url.clone().or(entry.pre_url.clone()),
+                screenshot: None,
+                console_errors: Vec::new(),
+            });
+        }
+        self
+    }
+
+    pub fn with_timeline_entry(mut self, entry: TimelineEntry) -> Self {
+        self.timeline.push(entry);
+        self
+    }
+
+    /// Add a load the session reported, e.g. from `navigate_smart`
+    pub fn with_navigation(mut self, result: &NavigationResult) -> Self {
+        self.navigations.push(NavigationEntry {
+            url: result.url.clone(),
+            success: result.success,
+            reason: result.reason.clone(),
+            duration_ms: Some(result.duration_ms),
+            step: None,
+            network_idle: result.network_idle.clone(),
+        });
+        self
+    }
+
+    /// Add failed assertions, e.g. `Expectations::failures`. Screenshots they
+    /// point to are inlined when readable.
+    pub fn with_failures(mut self, failures: &[AssertionFailure]) -> Self {
+        self.failures.extend_from_slice(failures);
+        self
+    }
+
+    /// Page the run ended on, from `analyze_page`
+    pub fn with_page_report(mut self, report: PageReport) -> Self {
+        self.page = Some(report);
+        self
+    }
+
+    pub fn timeline(&self) -> &[TimelineEntry] {
+        &self.timeline
+    }
+
+    pub fn navigations(&self) -> &[NavigationEntry] {
+        &self.navigations
+    }
+
+    /// Whether every action passed and no assertion failed
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty() && self.timeline.iter().all(|entry| entry.success)
+    }
+
+    pub fn network_summary(&self) -> NetworkSummary {
+        let mut summary = NetworkSummary {
+            navigations: self.navigations.len(),
+            failed_navigations: self.navigations.iter().filter(|nav| !nav.success).count(),
+            resource_count: self
+                .page
+                .as_ref()
+                .map(|page| page.performance.resource_count),
+            transfer_bytes: self
+                .page
+                .as_ref()
+                .map(|page| page.performance.transfer_bytes),
+            console_errors: self
+                .timeline
+                .iter()
+                .map(|entry| entry.console_errors.len())
+                .sum(),
+            ..Default::default()
+        };
+        for idle in self
+            .navigations
+            .iter()
+            .filter_map(|nav| nav.network_idle.as_ref())
+        {
+            summary.requests_seen += idle.requests_seen;
+            summary.long_polls_ignored += idle.long_polls_ignored;
+            if let Some(url) = &idle.longest_request_url {
+                if summary
+                    .slowest_request
+                    .as_ref()
+                    .is_none_or(|(_, ms)| idle.longest_request_ms > *ms)
+                {
+                    summary.slowest_request = Some((url.clone(), idle.longest_request_ms));
+                }
+            }
+        }
+        summary
+    }
+
+    /// The report as a standalone HTML document
+    pub fn render(&self) -> String {
+        fill_template(
+            TEMPLATE,
+            &[
+                ("title", html_escape(&self.title)),
+                ("generated_at", Utc::now().to_rfc3339()),
+                ("summary", self.render_summary()),
+                ("timeline", self.render_timeline()),
+                ("navigation", self.render_navigation()),
+                ("failures", self.render_failures()),
+                ("network", self.render_network()),
+                ("page", self.render_page()),
+            ],
+        )
+    }
+
+    /// Render the report and store it under `name` in `sink`
+    pub async fn write(&self, sink: &dyn ArtifactSink, name: &str) -> Result<ArtifactRef> {
+        let stored = sink
+            .store(name, self.render().as_bytes(), "text/html")
+            .await?;
+        println!("📝 Report stored at {}", stored.location);
+        Ok(stored)
+    }
+
+    fn render_summary(&self) -> String {
+        let failed = self.timeline.iter().filter(|entry| !entry.success).count();
+        let duration: u64 = self.timeline.iter().map(|entry| entry.duration_ms).sum();
+        let (class, verdict) = if self.passed() {
+            ("passed", "Passed")
+        } else {
+            ("failed", "Failed")
+        };
+        format!(
+            "<div class=\"{}\"><strong>{}</strong>result</div>\
+             <div><strong>{}</strong>actions</div>\
+             <div><strong>{}</strong>failed actions</div>\
+             <div><strong>{}</strong>failed assertions</div>\
+             <div><strong>{}</strong>navigations</div>\
+             <div><strong>{}ms</strong>in actions</div>",
+            class,
+            verdict,
+            self.timeline.len(),
+            failed,
+            self.failures.len(),
+            self.navigations.len(),
+            duration
+        )
+    }
+
+    fn render_timeline(&self) -> String {
+        if self.timeline.is_empty() {
+            return empty("No actions recorded.");
+        }
+        let mut html = String::from(
+            "<table><tr><th>#</th><th>Time</th><th>Action</th><th>Result</th><th>Duration</th><th>URL</th></tr>",
+        );
+        for entry in &self.timeline {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td><code>{}</code>{}</td><td class=\"{}\">{}{}</td><td>{}ms</td><td><code>{}</code></td></tr>",
+                entry.index,
+                entry
+                    .timestamp
+                    .map(|at| at.format("%H:%M:%S%.3f").to_string())
+                    .unwrap_or_default(),
+                html_escape(&entry.action),
+                params(&entry.params),
+                if entry.success { "passed" } else { "failed" },
+                if entry.success { "✅ " } else { "❌ " },
+                html_escape(&entry.message),
+                entry.duration_ms,
+                html_escape(entry.url.as_deref().unwrap_or_default())
+            ));
+            if !entry.console_errors.is_empty() || entry.screenshot.is_some() {
+                html.push_str("<tr><td></td><td colspan=\"5\">");
+                for error in &entry.console_errors {
+                    html.push_str(&format!(
+                        "<div class=\"failed\">console: {}</div>",
+                        html_escape(error)
+                    ));
+                }
+                if let Some(png) = &entry.screenshot {
+                    html.push_str(&screenshot("Screenshot", png));
+                }
+                html.push_str("</td></tr>");
+            }
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn render_navigation(&self) -> String {
+        if self.navigations.is_empty() {
+            return empty("No navigations recorded.");
+        }
+        let mut html = String::from(
+            "<table><tr><th>URL</th><th>Result</th><th>Reason</th><th>Duration</th><th>Step</th><th>Requests</th></tr>",
+        );
+        for nav in &self.navigations {
+            html.push_str(&format!(
+                "<tr><td><code>{}</code></td><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                html_escape(&nav.url),
+                if nav.success { "passed" } else { "failed" },
+                if nav.success { "✅" } else { "❌" },
+                html_escape(&nav.reason),
+                nav.duration_ms
+                    .map(|ms| format!("{}ms", ms))
+                    .unwrap_or_default(),
+                nav.step.map(|step| step.to_string()).unwrap_or_default(),
+                nav.network_idle
+                    .as_ref()
+                    .map(|idle| idle.requests_seen.to_string())
+                    .unwrap_or_default()
+            ));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn render_failures(&self) -> String {
+        let failed_steps: Vec<&TimelineEntry> = self
+            .timeline
+            .iter()
+            .filter(|entry| !entry.success)
+            .collect();
+        if failed_steps.is_empty() && self.failures.is_empty() {
+            return empty("No failures.");
+        }
+        let mut html = String::new();
+        for entry in failed_steps {
+            html.push_str(&format!(
+                "<div class=\"failure\"><p><strong>Step {}: {}</strong></p><p>{}</p>",
+                entry.index,
+                html_escape(&entry.action),
+                html_escape(&entry.message)
+            ));
+            if let Some(png) = &entry.screenshot {
+                html.push_str(&screenshot("Page after the failure", png));
+            }
+            html.push_str("</div>");
+        }
+        for failure in &self.failures {
+            html.push_str(&format!(
+                "<div class=\"failure\"><p><strong>Expected {}</strong></p><p>{}</p>",
+                html_escape(&failure.description),
+                html_escape(&failure.message)
+            ));
+            if let Some(png) = failure
+                .screenshot
+                .as_ref()
+                .and_then(|path| std::fs::read(path).ok())
+            {
+                html.push_str(&screenshot("Page at the failure", &png));
+            }
+            html.push_str("</div>");
+        }
+        html
+    }
+
+    fn render_network(&self) -> String {
+        let summary = self.network_summary();
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "—".to_string());
+        let rows = [
+            ("Navigations", summary.navigations.to_string()),
+            ("Failed navigations", summary.failed_navigations.to_string()),
+            ("Requests while loading", summary.requests_seen.to_string()),
+            ("Long polls ignored", summary.long_polls_ignored.to_string()),
+            (
+                "Slowest request",
+                optional(
+                    summary
+                        .slowest_request
+                        .map(|(url, ms)| format!("<code>{}</code> ({}ms)", html_escape(&url), ms)),
+                ),
+            ),
+            (
+                "Resources on the final page",
+                optional(summary.resource_count.map(|count| count.to_string())),
+            ),
+            (
+                "Bytes transferred",
+                optional(summary.transfer_bytes.map(|bytes| bytes.to_string())),
+            ),
+            ("Console errors", summary.console_errors.to_string()),
+        ];
+        table(&rows)
+    }
+
+    fn render_page(&self) -> String {
+        let Some(page) = &self.page else {
+            return empty("No page report.");
+        };
+        let ms = |value: Option<f64>| {
+            value
+                .map(|ms| format!("{:.0}ms", ms))
+                .unwrap_or_else(|| "—".to_string())
+        };
+        let rows = [
+            (
+                "URL",
+                format!("<code>{}</code>", html_escape(&page.metadata.url)),
+            ),
+            ("Title", html_escape(&page.metadata.title)),
+            (
+                "Language",
+                html_escape(page.language.as_deref().unwrap_or("—")),
+            ),
+            (
+                "Elements",
+                format!(
+                    "{} ({} clickable, {} inputs, {} links, {} forms)",
+                    page.stats.total_elements,
+                    page.stats.clickable_elements,
+                    page.stats.input_elements,
+                    page.stats.links,
+                    page.stats.forms
+                ),
+            ),
+            ("Words", page.word_count.to_string()),
+            (
+                "Time to first byte",
+                ms(page.performance.time_to_first_byte_ms),
+            ),
+            (
+                "First contentful paint",
+                ms(page.performance.first_contentful_paint_ms),
+            ),
+            ("Load", ms(page.performance.load_ms)),
+            (
+                "Console errors",
+                page.console_errors
+                    .map(|count| count.to_string())
+                    .unwrap_or_else(|| "—".to_string()),
+            ),
+            ("Analyzed", page.generated_at.to_rfc3339()),
+        ];
+        table(&rows)
+    }
+}
+
+/// Replace each `{{name}}` in `template` with its value in one pass, so values
+/// that contain placeholders are left alone
+fn fill_template(template: &str, values: &[(&str, String)]) -> String {
+    let mut html = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        html.push_str(&rest[..start]);
+        let end = start + 2 + length + 2;
+        match values
+            .iter()
+            .find(|(name, _)| *name == &rest[start + 2..end - 2])
+        {
+            Some((_, value)) => html.push_str(value),
+            None => html.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    html.push_str(rest);
+    html
+}
+
+fn table(rows: &[(&str, String)]) -> String {
+    let mut html = String::from("<table>");
+    for (name, value) in rows {
+        html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>", name, value));
+    }
+    html.push_str("</table>");
+    html
+}
+
+fn empty(note: &str) -> String {
+    format!("<p class=\"muted\">{}</p>", note)
+}
+
+fn params(params: &Value) -> String {
+    match params {
+        Value::Null => String::new(),
+        Value::Object(map) if map.is_empty() => String::new(),
+        params => format!(
+            " <span class=\"muted\"><code>{}</code></span>",
+            html_escape(&params.to_string())
+        ),
+    }
+}
+
+fn screenshot(summary: &str, png: &[u8]) -> String {
+    format!(
+        "<details><summary>{}</summary><img alt=\"{}\" src=\"data:image/png;base64,{}\"></details>",
+        summary,
+        summary,
+        base64::engine::general_purpose::STANDARD.encode(png)
+    )
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::DocumentKind;
+
+    fn audit(sequence: u64, action: &str, success: bool, urls: (&str, &str)) -> AuditEntry {
+        AuditEntry {
+            sequence,
+            timestamp: Utc::now(),
+            session_id: "s1".to_string(),
+            action: action.to_string(),
+            params: serde_json::json!({ "selector": "#go", "password": "[REDACTED]" }),
+            success,
+            duration_ms: 40,
+            message: if success {
+                format!("{} done", action)
+            } else {
+                "Element <#go> not found".to_string()
+            },
+            pre_url: Some(urls.0.to_string()),
+            post_url: Some(urls.1.to_string()),
+            prev_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    fn navigation(url: &str, requests_seen: usize, longest_request_ms: u64) -> NavigationResult {
+        NavigationResult {
+            success: true,
+            reason: "load".to_string(),
+            url: url.to_string(),
+            ready_state: "complete".to_string(),
+            duration_ms: 300,
+            actual_load_time: 280,
+            network_quiet: true,
+            has_content: true,
+            network_idle: Some(NetworkIdleStats {
+                requests_seen,
+                long_polls_ignored: 1,
+                longest_request_ms,
+                longest_request_url: Some(format!("{}/api", url)),
+                waited_ms: 100,
+            }),
+            throttling: None,
+            document_kind: DocumentKind::Html,
+        }
+    }
+
+    /// Inner HTML of `<section id="{id}">`
+    fn section<'a>(html: &'a str, id: &str) -> &'a str {
+        let open = format!("<section id=\"{}\"", id);
+        let start = html.find(&open).unwrap();
+        let start = start + html[start..].find('>').unwrap() + 1;
+        let end = start + html[start..].find("</section>").unwrap();
+        &html[start..end]
+    }
+
+    /// A sign-in run: the login succeeded, the next click failed and an
+    /// expectation was not met
+    fn synthetic_run() -> ReportBuilder {
+        ReportBuilder::new("Sign-in <nightly>")
+            .with_navigation(&navigation("https://app.example/login", 12, 150))
+            .with_audit(&[
+                audit(
+                    1,
+                    "login",
+                    true,
+                    ("https://app.example/login", "https://app.example/home"),
+                ),
+                audit(
+                    2,
+                    "click",
+                    false,
+                    ("https://app.example/home", "https://app.example/home"),
+                ),
+            ])
+            .with_timeline_entry(TimelineEntry {
+                index: 3,
+                timestamp: None,
+                action: "extract".to_string(),
+                params: Value::Null,
+                success: true,
+                message: "read 3 rows".to_string(),
+                duration_ms: 20,
+                url: Some("https://app.example/home".to_string()),
+                screenshot: Some(vec![1, 2, 3]),
+                console_errors: vec!["TypeError: x is undefined".to_string()],
+            })
+            .with_failures(&[AssertionFailure {
+                description: "text 'Welcome' present".to_string(),
+                message: "not found".to_string(),
+                screenshot: None,
+            }])
+    }
+
+    #[test]
+    fn render_fills_every_section() {
+        let html = synthetic_run().render();
+        assert!(!html.contains("{{"), "unfilled placeholder in {}", html);
+        assert!(html.contains("<title>Sign-in &lt;nightly&gt;</title>"));
+
+        let summary = section(&html, "summary");
+        assert!(summary.starts_with("<div class=\"failed\"><strong>Failed</strong>result</div>"));
+        for count in [
+            "<strong>3</strong>actions",
+            "<strong>1</strong>failed actions",
+            "<strong>1</strong>failed assertions",
+            "<strong>2</strong>navigations",
+            "<strong>100ms</strong>in actions",
+        ] {
+            assert!(summary.contains(count), "{} in {}", count, summary);
+        }
+
+        let timeline = section(&html, "timeline");
+        assert_eq!(timeline.matches("<td class=").count(), 3);
+        assert!(timeline.contains("<code>login</code>"));
+        assert!(timeline.contains("&quot;password&quot;:&quot;[REDACTED]&quot;"));
+        assert!(timeline.contains("❌ Element &lt;#go&gt; not found"));
+        assert!(timeline.contains("console: TypeError: x is undefined"));
+        assert!(timeline.contains("src=\"data:image/png;base64,AQID\""));
+
+        let navigation = section(&html, "navigation");
+        assert!(navigation.contains("<code>https://app.example/login</code>"));
+        // The login step moved the page; the failed click did not
+        assert!(navigation.contains("<code>https://app.example/home</code>"));
+        assert_eq!(navigation.matches("<tr><td>").count(), 2);
+
+        let failures = section(&html, "failures");
+        assert!(failures.contains("<strong>Step 2: click</strong>"));
+        assert!(failures.contains("<strong>Expected text &#39;Welcome&#39; present</strong>"));
+
+        let network = section(&html, "network");
+        assert!(network.contains("<tr><th>Navigations</th><td>2</td></tr>"));
+        assert!(network.contains("<tr><th>Requests while loading</th><td>12</td></tr>"));
+        assert!(network.contains(
+            "<tr><th>Slowest request</th><td><code>https://app.example/login/api</code> (150ms)</td></tr>"
+        ));
+        assert!(network.contains("<tr><th>Console errors</th><td>1</td></tr>"));
+
+        assert_eq!(
+            section(&html, "page"),
+            "<p class=\"muted\">No page report.</p>"
+        );
+    }
+
+    #[test]
+    fn empty_report_passes_and_says_so() {
+        let report = ReportBuilder::new("Empty");
+        assert!(report.passed());
+        let html = report.render();
+        assert!(section(&html, "summary").contains("<strong>Passed</strong>result"));
+        assert_eq!(
+            section(&html, "timeline"),
+            "<p class=\"muted\">No actions recorded.</p>"
+        );
+        assert_eq!(
+            section(&html, "navigation"),
+            "<p class=\"muted\">No navigations recorded.</p>"
+        );
+        assert_eq!(
+            section(&html, "failures"),
+            "<p class=\"muted\">No failures.</p>"
+        );
+    }
+
+    #[test]
+    fn network_summary_keeps_the_slowest_request() {
+        let report = synthetic_run()
+            .with_navigation(&navigation("https://app.example/reports", 30, 900))
+            .with_navigation(&navigation("https://app.example/help", 2, 10));
+        let summary = report.network_summary();
+        assert_eq!(summary.navigations, 4);
+        assert_eq!(summary.requests_seen, 44);
+        assert_eq!(summary.long_polls_ignored, 3);
+        assert_eq!(
+            summary.slowest_request,
+            Some(("https://app.example/reports/api".to_string(), 900))
+        );
+    }
+
+    #[test]
+    fn template_values_are_not_expanded_again() {
+        assert_eq!(
+            fill_template(
+                "<h1>{{title}}</h1>{{unknown}}{{",
+                &[("title", "{{title}}".to_string())]
+            ),
+            "<h1>{{title}}</h1>{{unknown}}{{"
+        );
+    }
+}
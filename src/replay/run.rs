@@ -0,0 +1,212 @@
+use super::bundle::{RunManifest, StepRecord};
+use crate::actions::ActionResult;
+use crate::core::ConsoleMessage;
+use crate::dom::{DomDiff, DomState, SnapshotOptions, StructuralSnapshot};
+use crate::errors::Result;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// A recorded run bundle opened for stepping through.
+///
+/// ```no_run
+/// use surfai::replay::ReplayRun;
+///
+/// # fn run() -> surfai::Result<()> {
+/// let run = ReplayRun::open("runs/sign-in")?;
+/// for step in run.steps() {
+///     println!("{}", step.describe());
+///     if let Some(diff) = step.diff()? {
+///         println!("{}", diff);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplayRun {
+    dir: PathBuf,
+    manifest: RunManifest,
+}
+
+impl ReplayRun {
+    /// Read the bundle in `dir`. Fails with `ConfigurationError` for a bundle
+    /// written by a newer format version.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let manifest = RunManifest::load(&dir)?;
+        Ok(Self { dir, manifest })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    /// Format version the bundle was written with
+    pub fn version(&self) -> u32 {
+        self.manifest.version
+    }
+
+    pub fn manifest(&self) -> &RunManifest {
+        &self.manifest
+    }
+
+    /// Whether the recorder was finished, rather than the run stopping midway
+    pub fn finished(&self) -> bool {
+        self.manifest.finished_at.is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.manifest.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.manifest.steps.is_empty()
+    }
+
+    pub fn steps(&self) -> Vec<ReplayStep<'_>> {
+        self.manifest
+            .steps
+            .iter()
+            .map(|record| ReplayStep { run: self, record })
+            .collect()
+    }
+
+    /// Step at 1-based `index`
+    pub fn step(&self, index: usize) -> Option<ReplayStep<'_>> {
+        self.manifest
+            .steps
+            .iter()
+            .find(|record| record.index == index)
+            .map(|record| ReplayStep { run: self, record })
+    }
+}
+
+/// One step of a `ReplayRun`. States and screenshots are read from the bundle
+/// when asked for.
+#[derive(Debug, Clone, Copy)]
+pub struct ReplayStep<'a> {
+    run: &'a ReplayRun,
+    record: &'a StepRecord,
+}
+
+impl<'a> ReplayStep<'a> {
+    pub fn index(&self) -> usize {
+        self.record.index
+    }
+
+    pub fn command(&self) -> &'a str {
+        &self.record.command
+    }
+
+    pub fn params(&self) -> &'a Value {
+        &self.record.params
+    }
+
+    pub fn result(&self) -> &'a ActionResult {
+        &self.record.result
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.record.started_at
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.record.duration_ms
+    }
+
+    /// URL before the step ran
+    pub fn url(&self) -> Option<&'a str> {
+        self.record.url.as_deref()
+    }
+
+    /// URL after the step ran
+    pub fn post_url(&self) -> Option<&'a str> {
+        self.record.post_url.as_deref()
+    }
+
+    pub fn console_errors(&self) -> &'a [ConsoleMessage] {
+        &self.record.console_errors
+    }
+
+    /// What the recorder left out to stay within its budget
+    pub fn dropped(&self) -> &'a [String] {
+        &self.record.dropped
+    }
+
+    pub fn record(&self) -> &'a StepRecord {
+        self.record
+    }
+
+    /// Page before the step, if it was captured
+    pub fn pre_state(&self) -> Result<Option<DomState>> {
+        self.state(self.record.pre_state.as_deref())
+    }
+
+    /// Page after the step, if it was captured
+    pub fn post_state(&self) -> Result<Option<DomState>> {
+        self.state(self.record.post_state.as_deref())
+    }
+
+    /// Interactive elements the step added, removed and relabelled, compared
+    /// as `DomState::structural_snapshot` does; `None` unless both states
+    /// were captured
+    pub fn diff(&self) -> Result<Option<DomDiff>> {
+        let (Some(pre), Some(post)) = (self.pre_state()?, self.post_state()?) else {
+            return Ok(None);
+        };
+        let options = SnapshotOptions::default();
+        let before = StructuralSnapshot::from_state(&pre, &options)?;
+        let after = StructuralSnapshot::from_state(&post, &options)?;
+        Ok(Some(before.diff(&after)))
+    }
+
+    /// PNG of the page after the step, downsampled as the recorder was told to
+    pub fn screenshot(&self) -> Result<Option<Vec<u8>>> {
+        self.screenshot_path()
+            .map(std::fs::read)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    pub fn screenshot_path(&self) -> Option<PathBuf> {
+        self.record
+            .screenshot
+            .as_ref()
+            .map(|name| self.run.dir.join(name))
+    }
+
+    /// One line for listings, e.g. `3. fill ✅ (120ms, 1 console error)`
+    pub fn describe(&self) -> String {
+        let mut line = format!(
+            "{}. {} {} ({}ms",
+            self.record.index,
+            self.record.command,
+            if self.record.result.success {
+                "✅"
+            } else {
+                "❌"
+            },
+            self.record.duration_ms
+        );
+        match self.record.console_errors.len() {
+            0 => {}
+            1 => line.push_str(", 1 console error"),
+            count => line.push_str(&format!(", {} console errors", count)),
+        }
+        line.push(')');
+        if !self.record.result.success {
+            line.push_str(&format!(": {}", self.record.result.message));
+        }
+        line
+    }
+
+    fn state(&self, name: Option<&str>) -> Result<Option<DomState>> {
+        name.map(|name| DomState::from_bytes(&std::fs::read(self.run.dir.join(name))?))
+            .transpose()
+    }
+}
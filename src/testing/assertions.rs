@@ -0,0 +1,463 @@
+use super::Assertion;
+use crate::browser::BrowserSession;
+use crate::core::{BrowserTrait, ConsoleLevel, SessionTrait};
+use crate::dom::SnapshotOptions;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// How an expected string is compared with the actual one
+#[derive(Debug, Clone)]
+pub enum TextMatcher {
+    Exact(String),
+    Contains(String),
+    /// Regular expression, searched anywhere in the text
+    Regex(String),
+}
+
+impl TextMatcher {
+    pub fn matches(&self, actual: &str) -> std::result::Result<bool, String> {
+        Ok(match self {
+            TextMatcher::Exact(expected) => actual == expected,
+            TextMatcher::Contains(expected) => actual.contains(expected.as_str()),
+            TextMatcher::Regex(pattern) => regex::Regex::new(pattern)
+                .map_err(|e| format!("invalid pattern '{}': {}", pattern, e))?
+                .is_match(actual),
+        })
+    }
+
+    fn exact(self) -> Self {
+        match self {
+            TextMatcher::Contains(expected) => TextMatcher::Exact(expected),
+            other => other,
+        }
+    }
+}
+
+impl std::fmt::Display for TextMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextMatcher::Exact(expected) => write!(f, "'{}'", expected),
+            TextMatcher::Contains(expected) => write!(f, "containing '{}'", expected),
+            TextMatcher::Regex(pattern) => write!(f, "matching /{}/", pattern),
+        }
+    }
+}
+
+/// Expect an element matching `selector` to exist
+pub fn expect_element(selector: &str) -> ElementExpectation {
+    ElementExpectation {
+        selector: selector.to_string(),
+        visible: None,
+        text: None,
+        count: None,
+    }
+}
+
+/// Expect the page URL to contain `fragment`
+pub fn expect_url(fragment: &str) -> UrlExpectation {
+    UrlExpectation {
+        matcher: TextMatcher::Contains(fragment.to_string()),
+    }
+}
+
+/// Expect the page URL to match a regular expression
+pub fn expect_url_matches(pattern: &str) -> UrlExpectation {
+    UrlExpectation {
+        matcher: TextMatcher::Regex(pattern.to_string()),
+    }
+}
+
+/// Expect the page title to contain `text`
+pub fn expect_title(text: &str) -> TitleExpectation {
+    TitleExpectation {
+        matcher: TextMatcher::Contains(text.to_string()),
+    }
+}
+
+/// Expect no console errors or uncaught exceptions since the session started
+pub fn expect_no_console_errors() -> ConsoleExpectation {
+    ConsoleExpectation {
+        ignored: Vec::new(),
+    }
+}
+
+/// Expect the page to have requested a URL matching the regular expression `pattern`
+pub fn expect_network_request(pattern: &str) -> NetworkRequestExpectation {
+    NetworkRequestExpectation {
+        pattern: pattern.to_string(),
+        min_count: 1,
+    }
+}
+
+/// Expect the page's interactive structure to match the snapshot at `path`,
+/// written on the first run; see `DomState::assert_matches_snapshot`
+pub fn expect_snapshot(path: impl Into<PathBuf>) -> SnapshotExpectation {
+    SnapshotExpectation {
+        path: path.into(),
+        options: SnapshotOptions::default(),
+    }
+}
+
+/// See [`expect_element`]
+#[derive(Debug, Clone)]
+pub struct ElementExpectation {
+    selector: String,
+    visible: Option<bool>,
+    text: Option<TextMatcher>,
+    count: Option<usize>,
+}
+
+impl ElementExpectation {
+    pub fn visible(mut self) -> Self {
+        self.visible = Some(true);
+        self
+    }
+
+    pub fn hidden(mut self) -> Self {
+        self.visible = Some(false);
+        self
+    }
+
+    /// The element's rendered text contains `text`
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = Some(TextMatcher::Contains(text.to_string()));
+        self
+    }
+
+    /// The element's rendered text, trimmed, equals `text`
+    pub fn with_exact_text(mut self, text: &str) -> Self {
+        self.text = Some(TextMatcher::Exact(text.to_string()));
+        self
+    }
+
+    /// Exactly `count` elements match the selector
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    fn script(&self) -> String {
+        format!(
+            r#"
+            (function() {{
+                let nodes;
+                try {{
+                    nodes = document.querySelectorAll({});
+                }} catch (e) {{
+                    return {{ error: e.message }};
+                }}
+                return {{
+                    elements: Array.from(nodes).slice(0, 50).map(el => {{
+                        const style = window.getComputedStyle(el);
+                        const rect = el.getBoundingClientRect();
+                        return {{
+                            visible: style.display !== 'none' && style.visibility !== 'hidden'
+                                && style.opacity !== '0' && rect.width > 0 && rect.height > 0,
+                            text: (el.innerText || el.value || '').trim()
+                        }};
+                    }}),
+                    total: nodes.length
+                }};
+            }})()
+            "#,
+            serde_json::to_string(&self.selector).unwrap_or_default()
+        )
+    }
+}
+
+#[async_trait]
+impl<B: BrowserTrait> Assertion<B> for ElementExpectation {
+    fn description(&self) -> String {
+        let mut parts = vec![format!("element '{}'", self.selector)];
+        if let Some(count) = self.count {
+            parts.push(format!("to match {} time(s)", count));
+        }
+        match self.visible {
+            Some(true) => parts.push("to be visible".to_string()),
+            Some(false) => parts.push("to be hidden".to_string()),
+            None => {}
+        }
+        if let Some(text) = &self.text {
+            parts.push(format!("with text {}", text));
+        }
+        if parts.len() == 1 {
+            parts.push("to exist".to_string());
+        }
+        parts.join(" ")
+    }
+
+    async fn check(&self, session: &BrowserSession<B>) -> std::result::Result<(), String> {
+        let result = session
+            .execute_script(&self.script())
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(error) = result.get("error").and_then(|v| v.as_str()) {
+            return Err(format!("invalid selector: {}", error));
+        }
+
+        let total = result.get("total").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let elements = result
+            .get("elements")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        if let Some(expected) = self.count {
+            if total != expected {
+                return Err(format!("found {} matching element(s)", total));
+            }
+        }
+        if total == 0 {
+            // An absent element counts as hidden
+            return match (self.visible, &self.text) {
+                (Some(false), None) => Ok(()),
+                _ => Err("no element matches the selector".to_string()),
+            };
+        }
+
+        let visible = |element: &Value| {
+            element
+                .get("visible")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        };
+        let text = |element: &Value| {
+            element
+                .get("text")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+
+        for element in &elements {
+            let visibility_ok = self.visible.is_none_or(|want| visible(element) == want);
+            let text_ok = match &self.text {
+                Some(matcher) => matcher.matches(&text(element))?,
+                None => true,
+            };
+            if visibility_ok && text_ok {
+                return Ok(());
+            }
+        }
+
+        // Describe the first element, which is what a reader usually looks at
+        let first = &elements[0];
+        let mut found = vec![format!(
+            "{} match(es); first is {}",
+            total,
+            if visible(first) { "visible" } else { "hidden" }
+        )];
+        if self.text.is_some() {
+            found.push(format!("with text '{}'", text(first)));
+        }
+        Err(found.join(" "))
+    }
+}
+
+/// See [`expect_url`]
+#[derive(Debug, Clone)]
+pub struct UrlExpectation {
+    matcher: TextMatcher,
+}
+
+impl UrlExpectation {
+    /// Require the whole URL to equal the expected string
+    pub fn exact(mut self) -> Self {
+        self.matcher = self.matcher.exact();
+        self
+    }
+}
+
+#[async_trait]
+impl<B: BrowserTrait> Assertion<B> for UrlExpectation {
+    fn description(&self) -> String {
+        format!("URL {}", self.matcher)
+    }
+
+    async fn check(&self, session: &BrowserSession<B>) -> std::result::Result<(), String> {
+        let url = session.current_url().await.map_err(|e| e.to_string())?;
+        if self.matcher.matches(&url)? {
+            Ok(())
+        } else {
+            Err(format!("URL was '{}'", url))
+        }
+    }
+}
+
+/// See [`expect_title`]
+#[derive(Debug, Clone)]
+pub struct TitleExpectation {
+    matcher: TextMatcher,
+}
+
+impl TitleExpectation {
+    /// Require the whole title to equal the expected string
+    pub fn exact(mut self) -> Self {
+        self.matcher = self.matcher.exact();
+        self
+    }
+}
+
+#[async_trait]
+impl<B: BrowserTrait> Assertion<B> for TitleExpectation {
+    fn description(&self) -> String {
+        format!("title {}", self.matcher)
+    }
+
+    async fn check(&self, session: &BrowserSession<B>) -> std::result::Result<(), String> {
+        let title = session
+            .execute_script("document.title")
+            .await
+            .map_err(|e| e.to_string())?;
+        let title = title.as_str().unwrap_or("");
+        if self.matcher.matches(title)? {
+            Ok(())
+        } else {
+            Err(format!("title was '{}'", title))
+        }
+    }
+}
+
+/// See [`expect_no_console_errors`]
+#[derive(Debug, Clone)]
+pub struct ConsoleExpectation {
+    ignored: Vec<String>,
+}
+
+impl ConsoleExpectation {
+    /// Skip errors whose text matches the regular expression `pattern`
+    pub fn ignoring(mut self, pattern: &str) -> Self {
+        self.ignored.push(pattern.to_string());
+        self
+    }
+}
+
+#[async_trait]
+impl<B: BrowserTrait> Assertion<B> for ConsoleExpectation {
+    fn description(&self) -> String {
+        "no console errors".to_string()
+    }
+
+    async fn check(&self, session: &BrowserSession<B>) -> std::result::Result<(), String> {
+        let ignored = self
+            .ignored
+            .iter()
+            .map(|pattern| {
+                regex::Regex::new(pattern)
+                    .map_err(|e| format!("invalid pattern '{}': {}", pattern, e))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let errors: Vec<String> = session
+            .console_messages()
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|message| message.level == ConsoleLevel::Error)
+            .filter(|message| !ignored.iter().any(|re| re.is_match(&message.text)))
+            .map(|message| match message.url {
+                Some(url) if !url.is_empty() => format!("{} ({})", message.text, url),
+                _ => message.text,
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} error(s) logged: {}",
+                errors.len(),
+                errors.join("; ")
+            ))
+        }
+    }
+}
+
+/// See [`expect_network_request`]
+#[derive(Debug, Clone)]
+pub struct NetworkRequestExpectation {
+    pattern: String,
+    min_count: usize,
+}
+
+impl NetworkRequestExpectation {
+    /// Require at least `count` matching requests
+    pub fn at_least(mut self, count: usize) -> Self {
+        self.min_count = count;
+        self
+    }
+}
+
+#[async_trait]
+impl<B: BrowserTrait> Assertion<B> for NetworkRequestExpectation {
+    fn description(&self) -> String {
+        format!(
+            "at least {} request(s) matching /{}/",
+            self.min_count, self.pattern
+        )
+    }
+
+    async fn check(&self, session: &BrowserSession<B>) -> std::result::Result<(), String> {
+        let re = regex::Regex::new(&self.pattern)
+            .map_err(|e| format!("invalid pattern '{}': {}", self.pattern, e))?;
+
+        // The resource timing buffer covers the current document and its subresources
+        let urls = session
+            .execute_script(
+                r#"
+                [location.href].concat(
+                    performance.getEntriesByType('resource').map(entry => entry.name)
+                )
+                "#,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        let urls: Vec<&str> = urls
+            .as_array()
+            .map(|urls| urls.iter().filter_map(|u| u.as_str()).collect())
+            .unwrap_or_default();
+
+        let matched = urls.iter().filter(|url| re.is_match(url)).count();
+        if matched >= self.min_count {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} matching request(s) among {} recorded",
+                matched,
+                urls.len()
+            ))
+        }
+    }
+}
+
+/// See [`expect_snapshot`]
+#[derive(Debug, Clone)]
+pub struct SnapshotExpectation {
+    path: PathBuf,
+    options: SnapshotOptions,
+}
+
+impl SnapshotExpectation {
+    /// Ignore text matching the regular expression `pattern` in a new snapshot
+    pub fn ignoring(mut self, pattern: &str) -> Self {
+        self.options = self.options.ignoring(pattern);
+        self
+    }
+}
+
+#[async_trait]
+impl<B: BrowserTrait> Assertion<B> for SnapshotExpectation {
+    fn description(&self) -> String {
+        format!("structure matching {}", self.path.display())
+    }
+
+    async fn check(&self, session: &BrowserSession<B>) -> std::result::Result<(), String> {
+        let state = session
+            .get_page_state(false)
+            .await
+            .map_err(|e| e.to_string())?;
+        crate::dom::snapshot::check_snapshot(&state, &self.path, &self.options)
+            .map_err(|e| e.to_string())?
+    }
+}
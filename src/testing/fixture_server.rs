@@ -0,0 +1,368 @@
+use crate::errors::Result;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// HTTP server on an ephemeral localhost port serving the bundled fixture pages.
+///
+/// | Path                      | Page                                                 |
+/// |---------------------------|------------------------------------------------------|
+/// | `/`                       | index linking every fixture                          |
+/// | `/login`                  | login form; POST redirects to `/dashboard`           |
+/// | `/login-de`               | German login form and cookie notice, `lang="de"`     |
+/// | `/login-es`               | Spanish login form and cookie notice, meta language  |
+/// | `/dashboard`              | landing page after login                             |
+/// | `/spa`, `/spa/*`          | single-page app routed with `pushState`              |
+/// | `/infinite-scroll`        | list that appends items as the sentinel scrolls in   |
+/// | `/iframe`                 | page embedding the form at `/iframe/form`            |
+/// | `/dialog`                 | alert, confirm, prompt and `<dialog>` buttons        |
+/// | `/sticky-header`          | buttons under a sticky header and a full-page cover  |
+/// | `/scroll-panel`           | button at the bottom of a tall panel, panel list     |
+/// | `/form-labels`            | fields labeled by wrapping, for, placeholder, nearby |
+/// | `/auth-tokens`            | tokens on the page and in a frame of another origin  |
+/// | `/type-modes`             | prefilled inputs, a textarea and a rich text editor  |
+/// | `/responsive`             | desktop nav swapped for a menu button below 600px    |
+/// | `/rich-editor`            | model-based editor taking input as `beforeinput`     |
+/// | `/selectors`              | controls found by CSS, XPath, test id, text and role |
+/// | `/history`                | page linking to `/history/next`, both count restores |
+/// | `/user-agent`             | user agent, platform and client hint brands          |
+/// | `/headers`                | request headers as JSON                              |
+/// | `/echo`                   | method, path, query, headers and body as JSON        |
+/// | `/hang`                   | never answers; held open until the client leaves     |
+/// | `/wizard`                 | 3-step checkout wizard switched by URL hash          |
+/// | `/sortable`               | product list that reorders its rows in place         |
+/// | `/csrf`                   | form guarded by a double-submit CSRF cookie          |
+/// | `/graphics`               | SVG icon toolbar, SVG legend and a chart canvas      |
+/// | `/countries`              | form with a 200-option country select in optgroups   |
+/// | `/disabled`               | disabled, readonly and aria-disabled order controls  |
+/// | `/label-for`              | sign-up form labeled only by `<label for>`           |
+/// | `/menus`                  | nested menubar with zero-width and messy text        |
+/// | `/links`                  | one link of each kind under a `<base href>`          |
+/// | `/document.pdf`           | one-page PDF, served as `application/pdf`            |
+/// | `/image.png`              | 64x48 gradient, served as `image/png`                |
+/// | `/sitemap.xml`            | sitemap index of the two sitemaps below              |
+/// | `/sitemap-pages.xml`      | urlset with an image extension namespace             |
+/// | `/sitemap-nested.xml`     | index of a gzipped urlset, looping back to the root  |
+/// | `/sitemap-archive.xml.gz` | gzipped urlset, without `Content-Encoding`           |
+///
+/// Extra pages can be added with [`FixtureServer::with_page`]. The server stops when
+/// dropped.
+///
+/// ```no_run
+/// use surfai::testing::FixtureServer;
+///
+/// # async fn run() -> surfai::Result<()> {
+/// let server = FixtureServer::start().await?;
+/// let session = surfai::DefaultSession::quick_start().await?;
+/// session.navigate_smart(&server.url("/login")).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FixtureServer {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+type Pages = HashMap<String, String>;
+
+/// Documents other than HTML: path, content type and bytes
+const DOCUMENTS: &[(&str, &str, &[u8])] = &[
+    (
+        "/document.pdf",
+        "application/pdf",
+        include_bytes!("fixtures/document.pdf"),
+    ),
+    (
+        "/image.png",
+        "image/png",
+        include_bytes!("fixtures/image.png"),
+    ),
+    (
+        "/sitemap-archive.xml.gz",
+        "application/gzip",
+        include_bytes!("fixtures/sitemap_archive.xml.gz"),
+    ),
+];
+
+fn bundled_pages() -> Pages {
+    [
+        ("/", include_str!("fixtures/index.html")),
+        ("/login", include_str!("fixtures/login.html")),
+        ("/login-de", include_str!("fixtures/login_de.html")),
+        ("/login-es", include_str!("fixtures/login_es.html")),
+        ("/dashboard", include_str!("fixtures/dashboard.html")),
+        ("/spa", include_str!("fixtures/spa.html")),
+        (
+            "/infinite-scroll",
+            include_str!("fixtures/infinite_scroll.html"),
+        ),
+        ("/iframe", include_str!("fixtures/iframe.html")),
+        ("/iframe/form", include_str!("fixtures/iframe_form.html")),
+        ("/dialog", include_str!("fixtures/dialog.html")),
+        (
+            "/sticky-header",
+            include_str!("fixtures/sticky_header.html"),
+        ),
+        ("/scroll-panel", include_str!("fixtures/scroll_panel.html")),
+        ("/form-labels", include_str!("fixtures/form_labels.html")),
+        ("/auth-tokens", include_str!("fixtures/auth_tokens.html")),
+        ("/type-modes", include_str!("fixtures/type_modes.html")),
+        ("/responsive", include_str!("fixtures/responsive.html")),
+        ("/rich-editor", include_str!("fixtures/rich_editor.html")),
+        ("/selectors", include_str!("fixtures/selectors.html")),
+        ("/history", include_str!("fixtures/history.html")),
+        ("/history/next", include_str!("fixtures/history_next.html")),
+        ("/user-agent", include_str!("fixtures/user_agent.html")),
+        ("/wizard", include_str!("fixtures/wizard.html")),
+        ("/sortable", include_str!("fixtures/sortable.html")),
+        ("/csrf", include_str!("fixtures/csrf.html")),
+        ("/graphics", include_str!("fixtures/graphics.html")),
+        ("/countries", include_str!("fixtures/countries.html")),
+        ("/disabled", include_str!("fixtures/disabled.html")),
+        ("/label-for", include_str!("fixtures/label_for.html")),
+        ("/menus", include_str!("fixtures/menus.html")),
+        ("/links", include_str!("fixtures/links.html")),
+        (
+            "/auth-tokens/frame",
+            include_str!("fixtures/auth_tokens_frame.html"),
+        ),
+        ("/sitemap.xml", include_str!("fixtures/sitemap_index.xml")),
+        (
+            "/sitemap-pages.xml",
+            include_str!("fixtures/sitemap_pages.xml"),
+        ),
+        (
+            "/sitemap-nested.xml",
+            include_str!("fixtures/sitemap_nested.xml"),
+        ),
+    ]
+    .into_iter()
+    .map(|(path, html)| (path.to_string(), html.to_string()))
+    .collect()
+}
+
+impl FixtureServer {
+    /// Serve the bundled fixtures
+    pub async fn start() -> Result<Self> {
+        Self::serve(bundled_pages()).await
+    }
+
+    /// Serve the bundled fixtures plus `pages`, keyed by path. A page given here
+    /// replaces a bundled page at the same path.
+    pub async fn with_pages<I, P, H>(pages: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (P, H)>,
+        P: Into<String>,
+        H: Into<String>,
+    {
+        let mut all = bundled_pages();
+        all.extend(
+            pages
+                .into_iter()
+                .map(|(path, html)| (path.into(), html.into())),
+        );
+        Self::serve(all).await
+    }
+
+    /// Serve the bundled fixtures plus one extra page
+    pub async fn with_page(path: &str, html: &str) -> Result<Self> {
+        Self::with_pages([(path, html)]).await
+    }
+
+    async fn serve(pages: Pages) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let pages = Arc::new(pages);
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let pages = pages.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &pages).await {
+                        println!("⚠️ Fixture server connection error: {}", e);
+                    }
+                });
+            }
+        });
+
+        println!("🧪 Fixture server listening on http://{}", addr);
+        Ok(Self { addr, task })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Absolute URL for `path` on this server
+    pub fn url(&self, path: &str) -> String {
+        let path = path.trim_start_matches('/');
+        format!("http://{}/{}", self.addr, path)
+    }
+}
+
+impl Drop for FixtureServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle_connection(stream: TcpStream, pages: &Pages) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).await? == 0 {
+        return Ok(());
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = parts.next().unwrap_or("/").to_string();
+
+    // Read headers, keeping the body length so request bodies are consumed
+    let mut content_length = 0usize;
+    let mut headers = serde_json::Map::new();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+            headers.insert(name.trim().to_lowercase(), value.trim().into());
+        }
+    }
+    let mut body = vec![0; content_length.min(64 * 1024)];
+    if !body.is_empty() {
+        reader.read_exact(&mut body).await?;
+    }
+
+    let path = target.split(['?', '#']).next().unwrap_or("/");
+    let path = match path.trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    };
+
+    let document = DOCUMENTS.iter().find(|(document, _, _)| *document == path);
+    if let (Some((_, content_type, bytes)), "GET" | "HEAD") = (document, method.as_str()) {
+        let mut stream = reader.into_inner();
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+                    content_type,
+                    bytes.len()
+                )
+                .as_bytes(),
+            )
+            .await?;
+        if method == "GET" {
+            stream.write_all(bytes).await?;
+        }
+        return stream.shutdown().await;
+    }
+
+    if path == "/hang" {
+        let mut discard = [0u8; 512];
+        while reader.read(&mut discard).await? > 0 {}
+        return Ok(());
+    }
+
+    let response = match (method.as_str(), path) {
+        ("POST", "/login") => redirect("/dashboard"),
+        ("GET", "/csrf") => with_cookie(
+            html_response("200 OK", &pages["/csrf"], false),
+            &format!(
+                "XSRF-TOKEN={}; Path=/; SameSite=Strict",
+                uuid::Uuid::new_v4().simple()
+            ),
+        ),
+        ("POST", "/csrf") => {
+            if csrf_accepted(&headers, &body) {
+                html_response("200 OK", "<h1 id=\"csrf-result\">Accepted</h1>", false)
+            } else {
+                html_response(
+                    "403 Forbidden",
+                    "<h1 id=\"csrf-result\">Rejected</h1>",
+                    false,
+                )
+            }
+        }
+        ("GET", "/headers") => json_response(&serde_json::Value::Object(headers).to_string()),
+        (_, "/echo") => json_response(
+            &serde_json::json!({
+                "method": method,
+                "path": path,
+                "query": target.split_once('?').map(|(_, query)| query).unwrap_or(""),
+                "headers": headers,
+                "body": String::from_utf8_lossy(&body),
+            })
+            .to_string(),
+        ),
+        ("GET" | "HEAD", path) => {
+            let page = pages.get(path).or_else(|| {
+                // Client-side routes of the SPA fixture all load the same document
+                path.starts_with("/spa/")
+                    .then(|| pages.get("/spa"))
+                    .flatten()
+            });
+            match page {
+                Some(html) => html_response("200 OK", html, method == "HEAD"),
+                None => html_response("404 Not Found", "<h1>Not found</h1>", method == "HEAD"),
+            }
+        }
+        _ => html_response("405 Method Not Allowed", "", false),
+    };
+
+    let mut stream = reader.into_inner();
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn html_response(status: &str, body: &str, head_only: bool) -> String {
+    // no-cache rather than no-store, which keeps pages out of the back-forward cache
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        if head_only { "" } else { body }
+    )
+}
+
+/// Double-submit check: the `X-XSRF-TOKEN` header or `_csrf` form field must
+/// repeat the `XSRF-TOKEN` cookie
+fn csrf_accepted(headers: &serde_json::Map<String, serde_json::Value>, body: &[u8]) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|v| v.as_str()).unwrap_or("");
+    let cookie = header("cookie")
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == "XSRF-TOKEN")
+        .map(|(_, value)| value.to_string());
+    let Some(cookie) = cookie.filter(|value| !value.is_empty()) else {
+        return false;
+    };
+    header("x-xsrf-token") == cookie
+        || url::form_urlencoded::parse(body).any(|(name, value)| name == "_csrf" && value == cookie)
+}
+
+fn with_cookie(response: String, cookie: &str) -> String {
+    response.replacen("\r\n", &format!("\r\nSet-Cookie: {}\r\n", cookie), 1)
+}
+
+fn json_response(body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+fn redirect(location: &str) -> String {
+    format!(
+        "HTTP/1.1 303 See Other\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        location
+    )
+}
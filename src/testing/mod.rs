@@ -0,0 +1,187 @@
+//! Assertions for tests that drive a `BrowserSession`.
+//!
+//! Every expectation implements [`Assertion`], so it can either fail fast with
+//! [`Assertion::assert`] or be collected by [`Expectations`], which keeps going after a
+//! failure and reports everything at the end.
+//!
+//! ```no_run
+//! use surfai::testing::{
+//!     expect_element, expect_network_request, expect_no_console_errors, expect_title,
+//!     expect_url, Assertion, Expectations,
+//! };
+//!
+//! # async fn run(session: &surfai::DefaultSession) -> surfai::Result<()> {
+//! expect_element("#status")
+//!     .visible()
+//!     .with_text("Saved")
+//!     .assert(session)
+//!     .await?;
+//!
+//! let mut suite = Expectations::new(session).capture_failures_to("target/failures");
+//! suite.check(&expect_url("/dashboard")).await;
+//! suite.check(&expect_title("Dashboard").exact()).await;
+//! suite.check(&expect_no_console_errors()).await;
+//! suite.check(&expect_network_request(r"/api/profile$")).await;
+//! suite.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `test-util` feature, [`FixtureServer`] serves bundled fixture pages on
+//! localhost so suites do not depend on external sites, and
+//! [`VirtualClock`](crate::utils::VirtualClock) runs the session's waits on paused
+//! tokio time. Setting `session.seed` makes generated ids repeat between runs.
+
+pub mod assertions;
+#[cfg(feature = "test-util")]
+pub mod fixture_server;
+
+pub use assertions::{
+    expect_element, expect_network_request, expect_no_console_errors, expect_snapshot,
+    expect_title, expect_url, expect_url_matches, ConsoleExpectation, ElementExpectation,
+    NetworkRequestExpectation, SnapshotExpectation, TextMatcher, TitleExpectation, UrlExpectation,
+};
+#[cfg(feature = "test-util")]
+pub use fixture_server::FixtureServer;
+
+use crate::browser::BrowserSession;
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::errors::{BrowserAgentError, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// A check against the current state of a session
+#[async_trait]
+pub trait Assertion<B: BrowserTrait>: Send + Sync {
+    /// What is expected, e.g. `element #status to be visible`
+    fn description(&self) -> String;
+
+    /// `Err` explains what was found instead
+    async fn check(&self, session: &BrowserSession<B>) -> std::result::Result<(), String>;
+
+    /// Fail with `AssertionFailed` unless the assertion holds
+    async fn assert(&self, session: &BrowserSession<B>) -> Result<()> {
+        self.check(session).await.map_err(|message| {
+            BrowserAgentError::AssertionFailed(
+                AssertionFailure {
+                    description: self.description(),
+                    message,
+                    screenshot: None,
+                }
+                .to_string(),
+            )
+        })
+    }
+}
+
+/// A failed assertion as collected by `Expectations`
+#[derive(Debug, Clone)]
+pub struct AssertionFailure {
+    pub description: String,
+    pub message: String,
+    /// Page screenshot taken at the time of failure, when capture is enabled
+    pub screenshot: Option<PathBuf>,
+}
+
+impl std::fmt::Display for AssertionFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected {}: {}", self.description, self.message)?;
+        if let Some(path) = &self.screenshot {
+            write!(f, " (screenshot: {})", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs assertions without stopping at the first failure
+pub struct Expectations<'a, B: BrowserTrait> {
+    session: &'a BrowserSession<B>,
+    capture_dir: Option<PathBuf>,
+    checked: usize,
+    failures: Vec<AssertionFailure>,
+}
+
+impl<'a, B: BrowserTrait> Expectations<'a, B> {
+    pub fn new(session: &'a BrowserSession<B>) -> Self {
+        Self {
+            session,
+            capture_dir: None,
+            checked: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Save a screenshot into `dir` for every failed assertion
+    pub fn capture_failures_to<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.capture_dir = Some(dir.into());
+        self
+    }
+
+    /// Run `assertion`, recording it if it fails. Returns whether it passed.
+    pub async fn check(&mut self, assertion: &dyn Assertion<B>) -> bool {
+        self.checked += 1;
+        let Err(message) = assertion.check(self.session).await else {
+            return true;
+        };
+
+        let description = assertion.description();
+        println!("❌ Expected {}: {}", description, message);
+        let screenshot = self.capture_screenshot(&description).await;
+        self.failures.push(AssertionFailure {
+            description,
+            message,
+            screenshot,
+        });
+        false
+    }
+
+    async fn capture_screenshot(&self, description: &str) -> Option<PathBuf> {
+        let dir = self.capture_dir.as_ref()?;
+        let slug: String = description
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .take(60)
+            .collect();
+        let path = dir.join(format!("{:03}-{}.png", self.checked, slug));
+
+        let saved = async {
+            let bytes = self.session.screenshot().await?;
+            tokio::fs::create_dir_all(dir).await?;
+            tokio::fs::write(&path, bytes).await?;
+            Ok::<_, BrowserAgentError>(())
+        }
+        .await;
+
+        match saved {
+            Ok(()) => Some(path),
+            Err(e) => {
+                println!("⚠️ Could not capture failure screenshot: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn failures(&self) -> &[AssertionFailure] {
+        &self.failures
+    }
+
+    /// `AssertionFailed` listing every failure, or `Ok` if all assertions passed
+    pub fn finish(self) -> Result<()> {
+        if self.failures.is_empty() {
+            println!("✅ {} assertion(s) passed", self.checked);
+            return Ok(());
+        }
+
+        let report: Vec<String> = self
+            .failures
+            .iter()
+            .map(|failure| format!("  - {}", failure))
+            .collect();
+        Err(BrowserAgentError::AssertionFailed(format!(
+            "{} of {} assertion(s) failed:\n{}",
+            self.failures.len(),
+            self.checked,
+            report.join("\n")
+        )))
+    }
+}
@@ -0,0 +1,980 @@
+use crate::actions::{substitute_variables, ActionResult};
+use crate::browser::BrowserSession;
+use crate::core::{BrowserTrait, Selector, SessionTrait};
+use crate::dom::TextSearchOptions;
+use crate::errors::{BrowserAgentError, Result};
+use crate::replay::RunRecorder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+
+/// A user flow written as data, for people who would rather not write Rust.
+///
+/// Journeys are read from YAML or JSON and checked before anything runs:
+///
+/// ```yaml
+/// name: Sign in
+/// steps:
+///   - navigate: /login
+///   - fill: { label: Username, value: alice }
+///   - fill: { label: Password, value: secret }
+///   - click: { css: "#login-button" }
+///   - wait: { css: "#welcome", timeout_ms: 5000 }
+///   - extract: { css: "#welcome", as: greeting }
+///   - assert: { url_contains: /dashboard, text_present: "${greeting}" }
+///   - screenshot: signed-in.png
+///     continue_on_error: true
+/// ```
+///
+/// Strings may reference `{{name}}` or `${name}` variables set by earlier `extract`
+/// steps, the same way action parameters do. Relative `navigate` targets are joined to
+/// `base_url`. `examples/journeys/fixture_login.yaml` runs against the fixture server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journey {
+    pub name: String,
+    /// Base for relative `navigate` targets
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Keep going after a failed step unless the step says otherwise
+    #[serde(default)]
+    pub continue_on_error: bool,
+    /// Initial variables
+    #[serde(default)]
+    pub variables: HashMap<String, Value>,
+    pub steps: Vec<JourneyStep>,
+}
+
+/// One step: an action plus how failures are handled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JourneyStep {
+    /// Shown in reports instead of the generated description
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Overrides `Journey::continue_on_error`
+    #[serde(default)]
+    pub continue_on_error: Option<bool>,
+    #[serde(flatten)]
+    pub action: StepAction,
+}
+
+/// What a step does
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepAction {
+    /// URL, or path relative to `base_url`
+    Navigate(String),
+    Click(Locator),
+    Fill(FillStep),
+    Assert(AssertStep),
+    Extract(ExtractStep),
+    /// Artifact name the screenshot is stored under
+    Screenshot(String),
+    Wait(WaitStep),
+}
+
+/// How a step finds its element; exactly one field is set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Locator {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub css: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_id: Option<String>,
+    /// Visible text of the element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Label of a form field, matched as `find_field_by_label` does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillStep {
+    #[serde(flatten)]
+    pub target: Locator,
+    pub value: String,
+}
+
+/// Conditions checked together; every one that is set must hold
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssertStep {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_contains: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_contains: Option<String>,
+    /// Text that must appear on the page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_present: Option<String>,
+    /// Element that must be present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<Locator>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractStep {
+    #[serde(flatten)]
+    pub target: Locator,
+    /// Variable the value is stored under
+    #[serde(rename = "as")]
+    pub variable: String,
+    /// Read this attribute instead of the element's text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribute: Option<String>,
+}
+
+/// Wait for an element, or for a fixed time when only `ms` is given
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitStep {
+    #[serde(flatten)]
+    pub target: Locator,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ms: Option<u64>,
+}
+
+const STEP_FIELDS: &[&str] = &["name", "continue_on_error"];
+const ACTIONS: &[&str] = &[
+    "navigate",
+    "click",
+    "fill",
+    "assert",
+    "extract",
+    "screenshot",
+    "wait",
+];
+const LOCATOR_FIELDS: &[&str] = &["css", "xpath", "test_id", "text", "label"];
+
+impl Journey {
+    /// Parse and validate a YAML journey
+    pub fn from_yaml_str(source: &str) -> Result<Self> {
+        let value: Value = serde_yaml::from_str(source)
+            .map_err(|e| BrowserAgentError::ConfigurationError(format!("invalid YAML: {}", e)))?;
+        Self::from_value(value)
+    }
+
+    /// Parse and validate a JSON journey
+    pub fn from_json_str(source: &str) -> Result<Self> {
+        Self::from_value(serde_json::from_str(source)?)
+    }
+
+    /// Read a journey file; `.json` files are parsed as JSON, anything else as YAML
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json_str(&source)
+        } else {
+            Self::from_yaml_str(&source)
+        }
+    }
+
+    /// Validate `value` and build the journey, failing with `ConfigurationError`
+    /// listing every problem found
+    pub fn from_value(value: Value) -> Result<Self> {
+        let problems = Self::validate(&value);
+        if !problems.is_empty() {
+            return Err(BrowserAgentError::ConfigurationError(format!(
+                "invalid journey: {}",
+                problems.join("; ")
+            )));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Problems that would stop `value` from loading or running: unknown fields,
+    /// missing or repeated actions, wrongly typed values and malformed locators
+    pub fn validate(value: &Value) -> Vec<String> {
+        let mut problems = Vec::new();
+        let Some(journey) = value.as_object() else {
+            return vec!["a journey must be a mapping with `name` and `steps`".to_string()];
+        };
+        unknown_fields(
+            journey,
+            &[
+                "name",
+                "base_url",
+                "continue_on_error",
+                "variables",
+                "steps",
+            ],
+            "journey",
+            &mut problems,
+        );
+        if !journey.get("name").is_some_and(Value::is_string) {
+            problems.push("journey: `name` must be a string".to_string());
+        }
+        let Some(steps) = journey.get("steps").and_then(Value::as_array) else {
+            problems.push("journey: `steps` must be a list".to_string());
+            return problems;
+        };
+
+        for (index, step) in steps.iter().enumerate() {
+            let at = format!("step {}", index + 1);
+            let Some(fields) = step.as_object() else {
+                problems.push(format!("{}: must be a mapping", at));
+                continue;
+            };
+            let actions: Vec<&str> = fields
+                .keys()
+                .map(String::as_str)
+                .filter(|key| ACTIONS.contains(key))
+                .collect();
+            let allowed: Vec<&str> = STEP_FIELDS.iter().chain(ACTIONS).copied().collect();
+            unknown_fields(fields, &allowed, &at, &mut problems);
+            let action = match actions.as_slice() {
+                [action] => *action,
+                [] => {
+                    problems.push(format!("{}: needs one of {}", at, ACTIONS.join(", ")));
+                    continue;
+                }
+                several => {
+                    problems.push(format!(
+                        "{}: has several actions ({}); split it into steps",
+                        at,
+                        several.join(", ")
+                    ));
+                    continue;
+                }
+            };
+
+            let at = format!("{} ({})", at, action);
+            let payload = &fields[action];
+            if let Some(payload) = payload.as_object() {
+                let extra: &[&str] = match action {
+                    "fill" => &["value"],
+                    "extract" => &["as", "attribute"],
+                    "wait" => &["timeout_ms", "ms"],
+                    "assert" => &["url_contains", "title_contains", "text_present", "element"],
+                    _ => &[],
+                };
+                let allowed: Vec<&str> = if action == "assert" {
+                    extra.to_vec()
+                } else {
+                    LOCATOR_FIELDS.iter().chain(extra).copied().collect()
+                };
+                unknown_fields(payload, &allowed, &at, &mut problems);
+            }
+
+            match serde_json::from_value::<JourneyStep>(step.clone()) {
+                Ok(parsed) => {
+                    if let Err(problem) = parsed.action.check() {
+                        problems.push(format!("{}: {}", at, problem));
+                    }
+                }
+                Err(e) => problems.push(format!("{}: {}", at, e)),
+            }
+        }
+        problems
+    }
+
+    /// Use `base_url` for relative `navigate` targets, e.g. a fixture server's root
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+}
+
+fn unknown_fields(
+    fields: &serde_json::Map<String, Value>,
+    allowed: &[&str],
+    at: &str,
+    problems: &mut Vec<String>,
+) {
+    for key in fields.keys() {
+        if !allowed.contains(&key.as_str()) {
+            problems.push(format!(
+                "{}: unknown field `{}`, expected one of {}",
+                at,
+                key,
+                allowed.join(", ")
+            ));
+        }
+    }
+}
+
+impl StepAction {
+    /// Checks serde cannot express
+    fn check(&self) -> std::result::Result<(), String> {
+        match self {
+            StepAction::Navigate(target) if target.trim().is_empty() => {
+                Err("target is empty".to_string())
+            }
+            StepAction::Screenshot(name) if name.trim().is_empty() => {
+                Err("name is empty".to_string())
+            }
+            StepAction::Click(target) => target.check(),
+            StepAction::Fill(fill) => {
+                if fill.target.text.is_some() {
+                    return Err("fields cannot be found by `text`; use `label`".to_string());
+                }
+                fill.target.check()
+            }
+            StepAction::Extract(extract) => {
+                if extract.variable.trim().is_empty() {
+                    return Err("`as` is empty".to_string());
+                }
+                extract.target.check()
+            }
+            StepAction::Wait(wait) => match (wait.target.is_empty(), wait.ms) {
+                (true, Some(_)) => Ok(()),
+                (true, None) => Err("needs a locator or `ms`".to_string()),
+                (false, Some(_)) => Err("takes a locator or `ms`, not both".to_string()),
+                (false, None) => wait.target.check(),
+            },
+            StepAction::Assert(assert) => {
+                if assert.url_contains.is_none()
+                    && assert.title_contains.is_none()
+                    && assert.text_present.is_none()
+                    && assert.element.is_none()
+                {
+                    return Err("checks nothing".to_string());
+                }
+                assert.element.as_ref().map_or(Ok(()), Locator::check)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Short description for reports
+    pub fn describe(&self) -> String {
+        match self {
+            StepAction::Navigate(target) => format!("navigate {}", target),
+            StepAction::Click(target) => format!("click {}", target),
+            StepAction::Fill(fill) => format!("fill {}", fill.target),
+            StepAction::Assert(assert) => {
+                let mut checks = Vec::new();
+                if let Some(url) = &assert.url_contains {
+                    checks.push(format!("url contains '{}'", url));
+                }
+                if let Some(title) = &assert.title_contains {
+                    checks.push(format!("title contains '{}'", title));
+                }
+                if let Some(text) = &assert.text_present {
+                    checks.push(format!("text '{}' present", text));
+                }
+                if let Some(element) = &assert.element {
+                    checks.push(format!("{} present", element));
+                }
+                format!("assert {}", checks.join(", "))
+            }
+            StepAction::Extract(extract) => {
+                format!("extract {} as {}", extract.target, extract.variable)
+            }
+            StepAction::Screenshot(name) => format!("screenshot {}", name),
+            StepAction::Wait(wait) => match wait.ms {
+                Some(ms) => format!("wait {}ms", ms),
+                None => format!("wait for {}", wait.target),
+            },
+        }
+    }
+}
+
+impl Locator {
+    fn set(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("css", &self.css),
+            ("xpath", &self.xpath),
+            ("test_id", &self.test_id),
+            ("text", &self.text),
+            ("label", &self.label),
+        ]
+        .into_iter()
+        .filter_map(|(kind, value)| value.as_deref().map(|value| (kind, value)))
+        .collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.set().is_empty()
+    }
+
+    /// The `Selector` this locator stands for; `None` for `label`, which is
+    /// resolved through `find_field_by_label`, and for an empty locator
+    pub fn selector(&self) -> Option<Selector> {
+        match self.set().first()? {
+            ("css", css) => Some(Selector::css(*css)),
+            ("xpath", xpath) => Some(Selector::xpath(*xpath)),
+            ("test_id", test_id) => Some(Selector::test_id(*test_id)),
+            ("text", text) => Some(Selector::text(*text)),
+            _ => None,
+        }
+    }
+
+    /// Exactly one non-empty field, and a plausible CSS or XPath expression
+    fn check(&self) -> std::result::Result<(), String> {
+        let set = self.set();
+        let (kind, value) = match set.as_slice() {
+            [one] => *one,
+            [] => return Err(format!("needs a locator ({})", LOCATOR_FIELDS.join(", "))),
+            _ => {
+                return Err("locator sets more than one of css, xpath, test_id, text, label".into())
+            }
+        };
+        if value.trim().is_empty() {
+            return Err(format!("locator `{}` is empty", kind));
+        }
+        match kind {
+            "css" if !balanced(value) => {
+                Err(format!("css `{}` has unbalanced brackets or quotes", value))
+            }
+            "xpath"
+                if !(value.starts_with('/')
+                    || value.starts_with('(')
+                    || value.starts_with('.')) =>
+            {
+                Err(format!("xpath `{}` must start with `/`, `(` or `.`", value))
+            }
+            "xpath" if !balanced(value) => Err(format!(
+                "xpath `{}` has unbalanced brackets or quotes",
+                value
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Locator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.set().first() {
+            Some((kind, value)) => write!(f, "{} '{}'", kind, value),
+            None => f.write_str("nothing"),
+        }
+    }
+}
+
+/// Brackets, parentheses and quotes pair up
+fn balanced(expression: &str) -> bool {
+    let mut open = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in expression.chars() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (_, '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '(') => open.push(c),
+            (None, ']') if open.pop() != Some('[') => return false,
+            (None, ')') if open.pop() != Some('(') => return false,
+            _ => {}
+        }
+    }
+    open.is_empty() && quote.is_none()
+}
+
+/// How a step ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    /// Not run because an earlier step failed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepReport {
+    /// 1-based position in the journey
+    pub index: usize,
+    pub name: String,
+    pub status: StepStatus,
+    /// Failure message, or a note on what the step produced
+    pub message: Option<String>,
+    /// Extracted value or stored screenshot
+    pub output: Option<Value>,
+    pub duration_ms: u64,
+}
+
+/// Outcome of `run_journey`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JourneyReport {
+    pub name: String,
+    pub steps: Vec<StepReport>,
+    /// Variables after the last step, including extracted values
+    pub variables: HashMap<String, Value>,
+    pub duration_ms: u64,
+}
+
+impl JourneyReport {
+    pub fn passed(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|step| step.status == StepStatus::Passed)
+    }
+
+    pub fn count(&self, status: StepStatus) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| step.status == status)
+            .count()
+    }
+
+    /// The report as a JUnit XML test suite with one test case per step, for CI
+    /// systems that display JUnit results
+    pub fn to_junit_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+            xml_escape(&self.name),
+            self.steps.len(),
+            self.count(StepStatus::Failed),
+            self.count(StepStatus::Skipped),
+            self.duration_ms as f64 / 1000.0
+        ));
+        for step in &self.steps {
+            xml.push_str(&format!(
+                "  <testcase classname=\"{}\" name=\"{}. {}\" time=\"{:.3}\"",
+                xml_escape(&self.name),
+                step.index,
+                xml_escape(&step.name),
+                step.duration_ms as f64 / 1000.0
+            ));
+            let message = xml_escape(step.message.as_deref().unwrap_or_default());
+            match step.status {
+                StepStatus::Passed => xml.push_str("/>\n"),
+                StepStatus::Failed => xml.push_str(&format!(
+                    ">\n    <failure message=\"{}\">{}</failure>\n  </testcase>\n",
+                    message, message
+                )),
+                StepStatus::Skipped => xml.push_str(">\n    <skipped/>\n  </testcase>\n"),
+            }
+        }
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Run `journey` step by step on `session`.
+///
+/// A failing step stops the journey unless it or the journey continues on error;
+/// the remaining steps are then reported as skipped. Only errors outside any step,
+/// such as an invalid base URL, are returned as `Err`.
+pub async fn run_journey<B: BrowserTrait>(
+    session: &BrowserSession<B>,
+    journey: &Journey,
+) -> Result<JourneyReport> {
+    run_journey_with(session, journey, None).await
+}
+
+/// `run_journey`, recording every step that runs into `recorder`'s bundle. Each
+/// step is recorded under its action name, such as `fill`, with the action's
+/// fields as parameters. The recorder is left open for further steps.
+pub async fn run_journey_recorded<B: BrowserTrait>(
+    session: &BrowserSession<B>,
+    journey: &Journey,
+    recorder: &RunRecorder,
+) -> Result<JourneyReport> {
+    run_journey_with(session, journey, Some(recorder)).await
+}
+
+async fn run_journey_with<B: BrowserTrait>(
+    session: &BrowserSession<B>,
+    journey: &Journey,
+    recorder: Option<&RunRecorder>,
+) -> Result<JourneyReport> {
+    let started = Instant::now();
+    let mut variables = journey.variables.clone();
+    let mut steps = Vec::new();
+    let mut stopped = false;
+
+    println!(
+        "🧭 Running journey '{}' ({} steps)",
+        journey.name,
+        journey.steps.len()
+    );
+
+    for (index, step) in journey.steps.iter().enumerate() {
+        let name = step.name.clone().unwrap_or_else(|| step.action.describe());
+        if stopped {
+            steps.push(StepReport {
+                index: index + 1,
+                name,
+                status: StepStatus::Skipped,
+                message: None,
+                output: None,
+                duration_ms: 0,
+            });
+            continue;
+        }
+
+        let step_started = Instant::now();
+        let outcome = match serde_json::to_value(&step.action)
+            .map(|action| substitute_variables(&action, &variables))
+            .and_then(serde_json::from_value::<StepAction>)
+        {
+            Ok(action) => match recorder {
+                Some(recorder) => {
+                    let (command, params) = match serde_json::to_value(&action).unwrap_or_default()
+                    {
+                        Value::Object(map) => map.into_iter().next().unwrap_or_default(),
+                        other => (String::new(), other),
+                    };
+                    let step = async {
+                        let output = run_step(session, journey, &action, &mut variables).await?;
+                        Ok(match output {
+                            Some(output) => ActionResult::success_with_data(name.clone(), output),
+                            None => ActionResult::success(name.clone()),
+                        })
+                    };
+                    recorder
+                        .record(session, &command, params, step)
+                        .await
+                        .map(|result| result.data)
+                }
+                None => run_step(session, journey, &action, &mut variables).await,
+            },
+            Err(e) => Err(e.into()),
+        };
+        let duration_ms = step_started.elapsed().as_millis() as u64;
+
+        let report = match outcome {
+            Ok(output) => {
+                println!("   ✅ {}. {}", index + 1, name);
+                StepReport {
+                    index: index + 1,
+                    name,
+                    status: StepStatus::Passed,
+                    message: None,
+                    output,
+                    duration_ms,
+                }
+            }
+            Err(e) => {
+                println!("   ❌ {}. {}: {}", index + 1, name, e);
+                stopped = !step.continue_on_error.unwrap_or(journey.continue_on_error);
+                StepReport {
+                    index: index + 1,
+                    name,
+                    status: StepStatus::Failed,
+                    message: Some(e.to_string()),
+                    output: None,
+                    duration_ms,
+                }
+            }
+        };
+        steps.push(report);
+    }
+
+    let report = JourneyReport {
+        name: journey.name.clone(),
+        steps,
+        variables,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    println!(
+        "🧭 Journey '{}': {} passed, {} failed, {} skipped",
+        report.name,
+        report.count(StepStatus::Passed),
+        report.count(StepStatus::Failed),
+        report.count(StepStatus::Skipped)
+    );
+    Ok(report)
+}
+
+async fn run_step<B: BrowserTrait>(
+    session: &BrowserSession<B>,
+    journey: &Journey,
+    action: &StepAction,
+    variables: &mut HashMap<String, Value>,
+) -> Result<Option<Value>> {
+    match action {
+        StepAction::Navigate(target) => {
+            let url = match &journey.base_url {
+                Some(base) if url::Url::parse(target).is_err() => url::Url::parse(base)
+                    .and_then(|base| base.join(target))
+                    .map_err(|e| BrowserAgentError::NavigationFailed(format!("{}: {}", target, e)))?
+                    .to_string(),
+                _ => target.clone(),
+            };
+            let navigation = session.navigate_smart(&url).await?;
+            Ok(Some(Value::String(navigation.url)))
+        }
+        StepAction::Click(target) => {
+            session.click(resolve(session, target).await?).await?;
+            Ok(None)
+        }
+        StepAction::Fill(fill) => {
+            match &fill.target.label {
+                Some(label) => session.fill_field_by_label(label, &fill.value).await?,
+                None => {
+                    let selector = resolve(session, &fill.target).await?;
+                    session.type_text_enhanced(selector, &fill.value).await?
+                }
+            }
+            Ok(None)
+        }
+        StepAction::Assert(assert) => {
+            let mut failures = Vec::new();
+            if let Some(expected) = &assert.url_contains {
+                let url = session.current_url().await?;
+                if !url.contains(expected.as_str()) {
+                    failures.push(format!("url '{}' does not contain '{}'", url, expected));
+                }
+            }
+            if let Some(expected) = &assert.title_contains {
+                let title = session.execute_script("document.title").await?;
+                let title = title.as_str().unwrap_or_default();
+                if !title.contains(expected.as_str()) {
+                    failures.push(format!("title '{}' does not contain '{}'", title, expected));
+                }
+            }
+            if let Some(expected) = &assert.text_present {
+                let options = TextSearchOptions {
+                    max_matches: 1,
+                    ..TextSearchOptions::default()
+                };
+                if session.find_text(expected, &options).await?.is_empty() {
+                    failures.push(format!("text '{}' not found", expected));
+                }
+            }
+            if let Some(element) = &assert.element {
+                if resolve(session, element).await.is_err() {
+                    failures.push(format!("{} not found", element));
+                }
+            }
+            if failures.is_empty() {
+                Ok(None)
+            } else {
+                Err(BrowserAgentError::AssertionFailed(failures.join("; ")))
+            }
+        }
+        StepAction::Extract(extract) => {
+            let lookup = resolve(session, &extract.target)
+                .await?
+                .element_expression();
+            let read = match &extract.attribute {
+                Some(attribute) => {
+                    format!("node.getAttribute({})", serde_json::to_string(attribute)?)
+                }
+                None => "('value' in node && node.tagName !== 'LI' && node.tagName !== 'BUTTON') \
+                    ? node.value : (node.innerText || node.textContent || '').trim()"
+                    .to_string(),
+            };
+            let value = session
+                .execute_script(&format!(
+                    "(function() {{ const node = {}; return node ? {} : null; }})()",
+                    lookup, read
+                ))
+                .await?;
+            if value.is_null() {
+                return Err(BrowserAgentError::ElementNotFound(
+                    extract.target.to_string(),
+                ));
+            }
+            variables.insert(extract.variable.clone(), value.clone());
+            Ok(Some(value))
+        }
+        StepAction::Screenshot(name) => {
+            let stored = session.save_screenshot(name).await?;
+            Ok(Some(serde_json::to_value(stored)?))
+        }
+        StepAction::Wait(wait) => {
+            if let Some(ms) = wait.ms {
+                tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+                return Ok(None);
+            }
+            let timeout_ms = wait.timeout_ms.unwrap_or(session.call_options().timeout_ms);
+            let deadline = Instant::now() + std::time::Duration::from_millis(timeout_ms);
+            loop {
+                if resolve(session, &wait.target).await.is_ok() {
+                    return Ok(None);
+                }
+                if Instant::now() >= deadline {
+                    return Err(BrowserAgentError::TimeoutError(format!(
+                        "{} did not appear within {}ms",
+                        wait.target, timeout_ms
+                    )));
+                }
+                tokio::time::sleep(session.call_options().poll_interval()).await;
+            }
+        }
+    }
+}
+
+/// Selector for `locator`, failing with `ElementNotFound` when nothing matches
+async fn resolve<B: BrowserTrait>(
+    session: &BrowserSession<B>,
+    locator: &Locator,
+) -> Result<Selector> {
+    let selector = match &locator.label {
+        Some(label) => Selector::Css(session.find_field_by_label(label).await?.selector),
+        None => locator
+            .selector()
+            .ok_or_else(|| BrowserAgentError::ElementNotFound(locator.to_string()))?,
+    };
+    let present = session
+        .execute_script(&format!("!!{}", selector.element_expression()))
+        .await?;
+    if present.as_bool() == Some(true) {
+        Ok(selector)
+    } else {
+        Err(BrowserAgentError::ElementNotFound(locator.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/journeys/fixture_login.yaml"
+    );
+
+    #[test]
+    fn example_journey_validates() {
+        let journey = Journey::from_path(EXAMPLE).unwrap();
+        assert_eq!(journey.name, "Fixture login");
+        assert_eq!(journey.variables["user"], "alice");
+        let steps: Vec<String> = journey
+            .steps
+            .iter()
+            .map(|step| step.name.clone().unwrap_or_else(|| step.action.describe()))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                "navigate /login",
+                "assert title contains 'Sign in', css '#login-form' present",
+                "fill label 'Username'",
+                "fill label 'Password'",
+                "click css '#login-button'",
+                "Dashboard shows up",
+                "extract css '#welcome' as greeting",
+                "assert url contains '/dashboard', text '${greeting}' present",
+                "screenshot journeys/fixture-login-dashboard.png",
+                "click text 'Sign out'",
+                "assert url contains '/login'",
+            ]
+        );
+        assert_eq!(journey.steps[8].continue_on_error, Some(true));
+    }
+
+    #[test]
+    fn example_journey_targets_the_fixture_pages() {
+        let login = scraper::Html::parse_document(include_str!("../testing/fixtures/login.html"));
+        let dashboard =
+            scraper::Html::parse_document(include_str!("../testing/fixtures/dashboard.html"));
+        let found = |page: &scraper::Html, css: &str| {
+            page.select(&scraper::Selector::parse(css).unwrap())
+                .next()
+                .is_some()
+        };
+        assert!(found(&login, "#login-form"));
+        assert!(found(&login, "#login-button"));
+        assert!(found(&login, "label[for=username]"));
+        assert!(found(&login, "label[for=password]"));
+        assert!(found(&dashboard, "#welcome"));
+        assert!(dashboard.html().contains(">Sign out</a>"));
+    }
+
+    #[test]
+    fn validation_lists_every_problem() {
+        let problems = Journey::validate(&serde_json::json!({
+            "name": "Broken",
+            "colour": "red",
+            "steps": [
+                { "navigate": "" },
+                { "click": { "css": "#a", "xpath": "//a" } },
+                { "fill": { "text": "Email", "value": "x" } },
+                { "click": { "css": "a[href" } },
+                { "click": { "xpath": "a" } },
+                { "wait": { "css": "#a", "ms": 10 } },
+                { "assert": {} },
+                { "extract": { "css": "#a", "as": " " } },
+                { "navigate": "/", "click": { "css": "#a" } },
+                { "name": "nothing" },
+                { "click": { "css": "#a", "delay": 5 } },
+            ],
+        }));
+        assert_eq!(
+            problems,
+            [
+                "journey: unknown field `colour`, expected one of name, base_url, \
+                 continue_on_error, variables, steps",
+                "step 1 (navigate): target is empty",
+                "step 2 (click): locator sets more than one of css, xpath, test_id, text, label",
+                "step 3 (fill): fields cannot be found by `text`; use `label`",
+                "step 4 (click): css `a[href` has unbalanced brackets or quotes",
+                "step 5 (click): xpath `a` must start with `/`, `(` or `.`",
+                "step 6 (wait): takes a locator or `ms`, not both",
+                "step 7 (assert): checks nothing",
+                "step 8 (extract): `as` is empty",
+                "step 9: has several actions (click, navigate); split it into steps",
+                "step 10: needs one of navigate, click, fill, assert, extract, screenshot, wait",
+                "step 11 (click): unknown field `delay`, expected one of css, xpath, test_id, \
+                 text, label",
+            ]
+        );
+        assert!(
+            Journey::from_value(serde_json::json!({ "name": "Broken", "steps": [{}] })).is_err()
+        );
+    }
+
+    #[test]
+    fn brackets_and_quotes_must_pair_up() {
+        assert!(balanced("form input[name='q']"));
+        assert!(balanced("//a[contains(., \"a]b\")]"));
+        assert!(balanced(r"input[name='it\'s']"));
+        assert!(!balanced("a[href"));
+        assert!(!balanced("(//a)[1"));
+        assert!(!balanced("a[title='x]"));
+        assert!(!balanced("a)"));
+    }
+
+    #[test]
+    fn junit_xml_has_a_case_per_step() {
+        let step = |index, name: &str, status, message: Option<&str>| StepReport {
+            index,
+            name: name.to_string(),
+            status,
+            message: message.map(str::to_string),
+            output: None,
+            duration_ms: 250,
+        };
+        let report = JourneyReport {
+            name: "Sign <in>".to_string(),
+            steps: vec![
+                step(1, "navigate /login", StepStatus::Passed, None),
+                step(
+                    2,
+                    "click css '#go'",
+                    StepStatus::Failed,
+                    Some("not \"found\""),
+                ),
+                step(3, "wait 10ms", StepStatus::Skipped, None),
+            ],
+            variables: HashMap::new(),
+            duration_ms: 1500,
+        };
+        assert!(!report.passed());
+        assert_eq!(
+            report.to_junit_xml(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuite name=\"Sign &lt;in&gt;\" tests=\"3\" failures=\"1\" skipped=\"1\" time=\"1.500\">\n  \
+             <testcase classname=\"Sign &lt;in&gt;\" name=\"1. navigate /login\" time=\"0.250\"/>\n  \
+             <testcase classname=\"Sign &lt;in&gt;\" name=\"2. click css &apos;#go&apos;\" time=\"0.250\">\n    \
+             <failure message=\"not &quot;found&quot;\">not &quot;found&quot;</failure>\n  \
+             </testcase>\n  \
+             <testcase classname=\"Sign &lt;in&gt;\" name=\"3. wait 10ms\" time=\"0.250\">\n    \
+             <skipped/>\n  \
+             </testcase>\n\
+             </testsuite>\n"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::browser::SessionHandle;
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::errors::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkCheckOptions {
+    /// Links probed at the same time
+    pub concurrency: usize,
+    /// Skip links whose origin differs from the start page
+    pub same_origin_only: bool,
+    pub max_links: usize,
+    pub timeout_ms: u64,
+    /// Probe with HEAD first, retrying with GET when the server rejects it
+    pub use_head: bool,
+}
+
+impl Default for LinkCheckOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            same_origin_only: true,
+            max_links: 500,
+            timeout_ms: 10000,
+            use_head: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusClass {
+    Success,
+    Redirect,
+    ClientError,
+    ServerError,
+    /// Network error, timeout or blocked request
+    Failed,
+    /// Cross-origin response the page is not allowed to read
+    Opaque,
+}
+
+/// Outcome of probing one link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkStatus {
+    pub url: String,
+    pub status: Option<u16>,
+    /// Final URL when the request was redirected
+    pub redirected_to: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl LinkStatus {
+    pub fn class(&self) -> StatusClass {
+        if self.error.is_some() {
+            return StatusClass::Failed;
+        }
+        match self.status {
+            None | Some(0) => StatusClass::Opaque,
+            Some(code) if code >= 500 => StatusClass::ServerError,
+            Some(code) if code >= 400 => StatusClass::ClientError,
+            _ if self.redirected_to.is_some() => StatusClass::Redirect,
+            Some(code) if (300..400).contains(&code) => StatusClass::Redirect,
+            Some(_) => StatusClass::Success,
+        }
+    }
+
+    pub fn is_broken(&self) -> bool {
+        matches!(
+            self.class(),
+            StatusClass::ClientError | StatusClass::ServerError | StatusClass::Failed
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkReport {
+    pub start_url: String,
+    pub results: Vec<LinkStatus>,
+    /// Links found on the page but not checked because of the options
+    pub skipped: Vec<String>,
+}
+
+impl LinkReport {
+    pub fn counts(&self) -> HashMap<StatusClass, usize> {
+        let mut counts = HashMap::new();
+        for result in &self.results {
+            *counts.entry(result.class()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn broken(&self) -> Vec<&LinkStatus> {
+        self.results.iter().filter(|r| r.is_broken()).collect()
+    }
+}
+
+/// A running link check; results arrive in completion order
+pub struct LinkCheck {
+    start_url: String,
+    skipped: Vec<String>,
+    total: usize,
+    receiver: mpsc::Receiver<LinkStatus>,
+    task: JoinHandle<()>,
+}
+
+impl LinkCheck {
+    /// Number of links being probed
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Next finished probe, or `None` once every link has been checked
+    pub async fn next(&mut self) -> Option<LinkStatus> {
+        self.receiver.recv().await
+    }
+
+    /// Wait for the remaining probes and collect everything into a report
+    pub async fn into_report(mut self) -> LinkReport {
+        let mut results = Vec::with_capacity(self.total);
+        while let Some(status) = self.receiver.recv().await {
+            results.push(status);
+        }
+        LinkReport {
+            start_url: self.start_url.clone(),
+            results,
+            skipped: std::mem::take(&mut self.skipped),
+        }
+    }
+
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for LinkCheck {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Navigate to `start_url`, collect its links and probe each one from inside the
+/// page with `fetch`, so cookies and the session's network setup apply.
+///
+/// Probes share the session's tab; `concurrency` bounds how many run at once.
+pub async fn check_links<B: BrowserTrait + 'static>(
+    session: &SessionHandle<B>,
+    start_url: &str,
+    options: LinkCheckOptions,
+) -> Result<LinkCheck> {
+    session.navigate_smart(start_url).await?;
+    let links = session.extract_links().await?;
+    let origin = url::Url::parse(&session.current_url().await?)
+        .map(|u| u.origin())
+        .ok();
+
+    let mut checked = Vec::new();
+    let mut skipped = Vec::new();
+    for link in links {
+        let same_origin = url::Url::parse(&link)
+            .ok()
+            .is_some_and(|u| Some(u.origin()) == origin);
+        if (options.same_origin_only && !same_origin) || checked.len() >= options.max_links {
+            skipped.push(link);
+        } else {
+            checked.push(link);
+        }
+    }
+
+    println!(
+        "🔗 Checking {} link(s) from {} ({} skipped)",
+        checked.len(),
+        start_url,
+        skipped.len()
+    );
+
+    let total = checked.len();
+    let (sender, receiver) = mpsc::channel(total.max(1));
+    let semaphore = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let handle = session.clone();
+    let task = tokio::spawn(async move {
+        let mut probes = Vec::with_capacity(checked.len());
+        for link in checked {
+            let permit = match semaphore.clone().acquire_owned().await {
+                Ok(permit) => permit,
+                Err(_) => break,
+            };
+            let handle = handle.clone();
+            let sender = sender.clone();
+            let options = options.clone();
+            probes.push(tokio::spawn(async move {
+                let status = probe(&handle, &link, &options).await;
+                drop(permit);
+                let _ = sender.send(status).await;
+            }));
+        }
+        for probe in probes {
+            let _ = probe.await;
+        }
+    });
+
+    Ok(LinkCheck {
+        start_url: start_url.to_string(),
+        skipped,
+        total,
+        receiver,
+        task,
+    })
+}
+
+async fn probe<B: BrowserTrait>(
+    session: &SessionHandle<B>,
+    url: &str,
+    options: &LinkCheckOptions,
+) -> LinkStatus {
+    let script = format!(
+        r#"
+        (async function() {{
+            const url = {url};
+            const started = Date.now();
+            const request = async (method, mode) => {{
+                const controller = new AbortController();
+                const timer = setTimeout(() => controller.abort(), {timeout});
+                try {{
+                    return await fetch(url, {{
+                        method, mode, redirect: 'follow', cache: 'no-store',
+                        credentials: 'include', signal: controller.signal
+                    }});
+                }} finally {{
+                    clearTimeout(timer);
+                }}
+            }};
+            const describe = (response) => ({{
+                status: response.type === 'opaque' ? null : response.status,
+                redirected_to: response.redirected ? response.url : null,
+                error: null,
+                duration_ms: Date.now() - started
+            }});
+            try {{
+                let response = await request({method}, 'cors');
+                if ([405, 501].includes(response.status) && {method} === 'HEAD') {{
+                    response = await request('GET', 'cors');
+                }}
+                return describe(response);
+            }} catch (e) {{
+                if (e.name === 'AbortError') {{
+                    return {{ status: null, redirected_to: null, error: 'timed out', duration_ms: Date.now() - started }};
+                }}
+                // CORS rejections look like network errors; an opaque request tells them apart
+                try {{
+                    return describe(await request('GET', 'no-cors'));
+                }} catch (inner) {{
+                    return {{
+                        status: null, redirected_to: null,
+                        error: inner.name === 'AbortError' ? 'timed out' : String(inner.message || inner),
+                        duration_ms: Date.now() - started
+                    }};
+                }}
+            }}
+        }})()
+        "#,
+        url = serde_json::to_string(url).unwrap_or_default(),
+        timeout = options.timeout_ms,
+        method = if options.use_head { "'HEAD'" } else { "'GET'" },
+    );
+
+    match session.execute_script(&script).await {
+        Ok(value) => {
+            let field = |name: &str| value.get(name).filter(|v| !v.is_null());
+            LinkStatus {
+                url: url.to_string(),
+                status: field("status")
+                    .and_then(|v| v.as_u64())
+                    .map(|code| code as u16),
+                redirected_to: field("redirected_to")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                error: field("error").and_then(|v| v.as_str()).map(String::from),
+                duration_ms: field("duration_ms").and_then(|v| v.as_u64()).unwrap_or(0),
+            }
+        }
+        Err(e) => LinkStatus {
+            url: url.to_string(),
+            status: None,
+            redirected_to: None,
+            error: Some(e.to_string()),
+            duration_ms: 0,
+        },
+    }
+}
@@ -0,0 +1,9 @@
+pub mod journey;
+pub mod links;
+pub mod sitemap;
+
+pub use journey::{
+    run_journey, run_journey_recorded, Journey, JourneyReport, JourneyStep, StepAction, StepStatus,
+};
+pub use links::{check_links, LinkCheck, LinkCheckOptions, LinkReport, LinkStatus, StatusClass};
+pub use sitemap::{crawl_sitemap, fetch_sitemap, CrawlPolicy, CrawlSummary, SitemapEntry};
@@ -0,0 +1,312 @@
+use crate::browser::{BrowserSession, SessionHandle};
+use crate::core::{BrowserTrait, SessionTrait};
+use crate::errors::{BrowserAgentError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// One `<url>` entry of a sitemap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SitemapEntry {
+    pub url: String,
+    pub lastmod: Option<String>,
+    pub changefreq: Option<String>,
+    pub priority: Option<f64>,
+}
+
+/// Pace and scope limits for a crawl
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlPolicy {
+    /// Minimum time between the start of two page visits
+    pub min_delay_ms: u64,
+    /// Stop after visiting this many pages in one run
+    pub max_pages: Option<usize>,
+    /// Upper bound on sitemap files fetched, guarding against index loops
+    pub max_sitemaps: usize,
+}
+
+impl Default for CrawlPolicy {
+    fn default() -> Self {
+        Self {
+            min_delay_ms: 1000,
+            max_pages: None,
+            max_sitemaps: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrawlSummary {
+    /// URLs listed across all sitemaps
+    pub discovered: usize,
+    pub visited: usize,
+    /// URLs already recorded in the checkpoint
+    pub resumed: usize,
+    /// URL and error for every page that failed; these stay out of the checkpoint
+    pub failed: Vec<(String, String)>,
+}
+
+/// Completed URLs persisted one per line, appended as pages finish
+struct Checkpoint {
+    completed: HashSet<String>,
+    file: Option<std::fs::File>,
+}
+
+impl Checkpoint {
+    fn open(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self {
+                completed: HashSet::new(),
+                file: None,
+            });
+        };
+
+        let completed = match std::fs::read_to_string(path) {
+            Ok(contents) => contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        Ok(Self {
+            completed,
+            file: Some(file),
+        })
+    }
+
+    fn mark_done(&mut self, url: &str) -> Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            writeln!(file, "{}", url)?;
+            file.flush()?;
+        }
+        self.completed.insert(url.to_string());
+        Ok(())
+    }
+}
+
+fn parse_script(url: &str) -> String {
+    format!(
+        r#"
+        (async function() {{
+            let response;
+            try {{
+                response = await fetch({url}, {{ credentials: 'include', cache: 'no-store' }});
+            }} catch (e) {{
+                return {{ error: String(e.message || e) }};
+            }}
+            if (!response.ok) return {{ error: 'HTTP ' + response.status, status: response.status }};
+
+            let bytes = new Uint8Array(await response.arrayBuffer());
+            // .xml.gz files usually arrive without Content-Encoding, so inflate by magic number
+            if (bytes.length > 1 && bytes[0] === 0x1f && bytes[1] === 0x8b) {{
+                const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream('gzip'));
+                bytes = new Uint8Array(await new Response(stream).arrayBuffer());
+            }}
+
+            const doc = new DOMParser().parseFromString(new TextDecoder().decode(bytes), 'application/xml');
+            if (doc.getElementsByTagName('parsererror').length > 0) return {{ error: 'invalid XML' }};
+
+            // Direct children only: extensions such as image:loc reuse the same local names
+            const child = (node, name) => {{
+                const el = Array.from(node.children).find(c => c.localName === name);
+                return el ? el.textContent.trim() : null;
+            }};
+            const resolve = (loc) => {{
+                try {{ return loc ? new URL(loc, {url}).href : null; }} catch (e) {{ return null; }}
+            }};
+            const root = doc.documentElement;
+            const items = (name) => Array.from(root.children).filter(c => c.localName === name);
+
+            if (root.localName === 'sitemapindex') {{
+                return {{ kind: 'index', sitemaps: items('sitemap').map(s => resolve(child(s, 'loc'))).filter(Boolean) }};
+            }}
+            if (root.localName === 'urlset') {{
+                return {{
+                    kind: 'urlset',
+                    entries: items('url').map(u => {{
+                        const priority = parseFloat(child(u, 'priority'));
+                        return {{
+                            url: resolve(child(u, 'loc')),
+                            lastmod: child(u, 'lastmod'),
+                            changefreq: child(u, 'changefreq'),
+                            priority: isNaN(priority) ? null : priority
+                        }};
+                    }}).filter(e => e.url)
+                }};
+            }}
+            return {{ error: 'unexpected root element <' + root.localName + '>' }};
+        }})()
+        "#,
+        url = serde_json::to_string(url).unwrap_or_default()
+    )
+}
+
+/// Fetch `/sitemap.xml` (or `/sitemap.xml.gz`) of `origin`, following sitemap index
+/// files, and return every listed URL once.
+///
+/// The session navigates to `origin` first so the fetches are same-origin.
+pub async fn fetch_sitemap<B: BrowserTrait>(
+    session: &BrowserSession<B>,
+    origin: &str,
+    policy: &CrawlPolicy,
+) -> Result<Vec<SitemapEntry>> {
+    let invalid = |e: url::ParseError| {
+        BrowserAgentError::NavigationFailed(format!("invalid origin {}: {}", origin, e))
+    };
+    let base = url::Url::parse(origin).map_err(invalid)?;
+    session.navigate_smart(base.as_str()).await?;
+
+    let mut queue = VecDeque::from([base.join("/sitemap.xml").map_err(invalid)?.to_string()]);
+    let mut fallback = Some(base.join("/sitemap.xml.gz").map_err(invalid)?.to_string());
+    let mut found = false;
+    let mut fetched = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+
+    while let Some(sitemap) = queue.pop_front() {
+        if fetched.len() >= policy.max_sitemaps {
+            println!(
+                "⚠️ Sitemap limit of {} reached; {} file(s) not fetched",
+                policy.max_sitemaps,
+                queue.len() + 1
+            );
+            break;
+        }
+        if !fetched.insert(sitemap.clone()) {
+            continue;
+        }
+
+        let result: Value = session.execute_script(&parse_script(&sitemap)).await?;
+        if let Some(error) = result.get("error").and_then(|v| v.as_str()) {
+            // Only the root sitemap has a fallback location
+            if let Some(gz) = fallback.take() {
+                println!("⚠️ {}: {}, trying {}", sitemap, error, gz);
+                queue.push_back(gz);
+            } else {
+                println!("⚠️ Skipping sitemap {}: {}", sitemap, error);
+            }
+            continue;
+        }
+        fallback = None;
+        found = true;
+
+        match result.get("kind").and_then(|v| v.as_str()) {
+            Some("index") => {
+                let children: Vec<String> = result
+                    .get("sitemaps")
+                    .cloned()
+                    .map(serde_json::from_value)
+                    .transpose()?
+                    .unwrap_or_default();
+                println!(
+                    "🗺️ Sitemap index {} lists {} sitemap(s)",
+                    sitemap,
+                    children.len()
+                );
+                queue.extend(children);
+            }
+            _ => {
+                let listed: Vec<SitemapEntry> = result
+                    .get("entries")
+                    .cloned()
+                    .map(serde_json::from_value)
+                    .transpose()?
+                    .unwrap_or_default();
+                println!("🗺️ Sitemap {} lists {} URL(s)", sitemap, listed.len());
+                entries.extend(listed.into_iter().filter(|e| seen.insert(e.url.clone())));
+            }
+        }
+    }
+
+    if !found {
+        return Err(BrowserAgentError::NavigationFailed(format!(
+            "no sitemap found for {}",
+            origin
+        )));
+    }
+
+    Ok(entries)
+}
+
+/// Visit every page listed in the sitemaps of `origin`, calling `visit` after each
+/// navigation.
+///
+/// With a `checkpoint` file, URLs whose visit succeeded are appended to it and skipped
+/// by later runs, so an interrupted crawl continues where it stopped.
+pub async fn crawl_sitemap<B, F, Fut>(
+    session: &SessionHandle<B>,
+    origin: &str,
+    policy: &CrawlPolicy,
+    checkpoint: Option<&Path>,
+    mut visit: F,
+) -> Result<CrawlSummary>
+where
+    B: BrowserTrait,
+    F: FnMut(SessionHandle<B>, SitemapEntry) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let entries = fetch_sitemap(session, origin, policy).await?;
+    let mut checkpoint = Checkpoint::open(checkpoint)?;
+    let mut summary = CrawlSummary {
+        discovered: entries.len(),
+        ..CrawlSummary::default()
+    };
+
+    let delay = Duration::from_millis(policy.min_delay_ms);
+    let mut next_start = Instant::now();
+
+    for entry in entries {
+        if checkpoint.completed.contains(&entry.url) {
+            summary.resumed += 1;
+            continue;
+        }
+        if policy
+            .max_pages
+            .is_some_and(|max| summary.visited + summary.failed.len() >= max)
+        {
+            break;
+        }
+
+        tokio::time::sleep_until(next_start).await;
+        next_start = Instant::now() + delay;
+
+        let url = entry.url.clone();
+        let outcome = match session.navigate_smart(&url).await {
+            Ok(_) => visit(session.clone(), entry).await,
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(()) => {
+                checkpoint.mark_done(&url)?;
+                summary.visited += 1;
+            }
+            Err(e) => {
+                println!("❌ Crawl of {} failed: {}", url, e);
+                summary.failed.push((url, e.to_string()));
+            }
+        }
+    }
+
+    println!(
+        "✅ Sitemap crawl finished: {} visited, {} resumed, {} failed of {} discovered",
+        summary.visited,
+        summary.resumed,
+        summary.failed.len(),
+        summary.discovered
+    );
+    Ok(summary)
+}
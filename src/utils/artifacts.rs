@@ -0,0 +1,267 @@
+use crate::errors::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Where a stored artifact ended up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactRef {
+    /// Name it was stored under, e.g. `baselines/home.actual.png`
+    pub name: String,
+    /// Sink-specific location: a file path, a URL, or `memory:<name>`
+    pub location: String,
+    pub content_type: String,
+    pub size: usize,
+}
+
+/// Destination for screenshots and other files the session produces.
+///
+/// The session writes through `LocalDirSink` unless given another sink with
+/// `BrowserSession::with_artifact_sink`, so features keep working where the file
+/// system is read-only. A sink for object storage only has to map names to keys:
+///
+/// ```ignore
+/// struct BucketSink {
+///     client: my_storage::Client,
+///     bucket: String,
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl surfai::ArtifactSink for BucketSink {
+///     async fn store(&self, name: &str, bytes: &[u8], content_type: &str) -> surfai::Result<surfai::ArtifactRef> {
+///         self.client
+///             .put_object(&self.bucket, name, bytes.to_vec(), content_type)
+///             .await
+///             .map_err(|e| surfai::BrowserAgentError::IoError(std::io::Error::other(e)))?;
+///         Ok(surfai::ArtifactRef {
+///             name: name.to_string(),
+///             location: format!("s3://{}/{}", self.bucket, name),
+///             content_type: content_type.to_string(),
+///             size: bytes.len(),
+///         })
+///     }
+///
+///     async fn load(&self, name: &str) -> surfai::Result<Option<Vec<u8>>> {
+///         self.client
+///             .get_object(&self.bucket, name)
+///             .await
+///             .map_err(|e| surfai::BrowserAgentError::IoError(std::io::Error::other(e)))
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait ArtifactSink: Send + Sync {
+    /// Store `bytes` under `name`, replacing any artifact already there
+    async fn store(&self, name: &str, bytes: &[u8], content_type: &str) -> Result<ArtifactRef>;
+
+    /// Artifact stored under `name`, if any; used for inputs such as visual baselines
+    async fn load(&self, name: &str) -> Result<Option<Vec<u8>>>;
+}
+
+/// Writes artifacts as files under a directory, creating parent directories.
+///
+/// Names are paths relative to the root; absolute names are used as they are.
+#[derive(Debug, Clone)]
+pub struct LocalDirSink {
+    root: PathBuf,
+}
+
+impl LocalDirSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+}
+
+impl Default for LocalDirSink {
+    /// Rooted at the working directory
+    fn default() -> Self {
+        Self::new(".")
+    }
+}
+
+#[async_trait]
+impl ArtifactSink for LocalDirSink {
+    async fn store(&self, name: &str, bytes: &[u8], content_type: &str) -> Result<ArtifactRef> {
+        let path = self.path(name);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, bytes).await?;
+        Ok(ArtifactRef {
+            name: name.to_string(),
+            location: path.display().to_string(),
+            content_type: content_type.to_string(),
+            size: bytes.len(),
+        })
+    }
+
+    async fn load(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(name)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Keeps artifacts in memory, for read-only file systems and for inspecting what
+/// a run produced
+#[derive(Debug, Default)]
+pub struct InMemorySink {
+    artifacts: Mutex<HashMap<String, (String, Vec<u8>)>>,
+}
+
+impl InMemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bytes and content type stored under `name`
+    pub fn get(&self, name: &str) -> Option<(Vec<u8>, String)> {
+        self.lock()
+            .get(name)
+            .map(|(content_type, bytes)| (bytes.clone(), content_type.clone()))
+    }
+
+    /// Names of every stored artifact, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.lock().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (String, Vec<u8>)>> {
+        self.artifacts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl ArtifactSink for InMemorySink {
+    async fn store(&self, name: &str, bytes: &[u8], content_type: &str) -> Result<ArtifactRef> {
+        self.lock()
+            .insert(name.to_string(), (content_type.to_string(), bytes.to_vec()));
+        Ok(ArtifactRef {
+            name: name.to_string(),
+            location: format!("memory:{}", name),
+            content_type: content_type.to_string(),
+            size: bytes.len(),
+        })
+    }
+
+    async fn load(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.lock().get(name).map(|(_, bytes)| bytes.clone()))
+    }
+}
+
+/// Lets one sink be shared, e.g. an `InMemorySink` the caller inspects afterwards
+#[async_trait]
+impl<S: ArtifactSink + ?Sized> ArtifactSink for std::sync::Arc<S> {
+    async fn store(&self, name: &str, bytes: &[u8], content_type: &str) -> Result<ArtifactRef> {
+        (**self).store(name, bytes, content_type).await
+    }
+
+    async fn load(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        (**self).load(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::{ReportBuilder, TimelineEntry};
+    use std::path::Path;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn in_memory_sink_stores_and_loads() {
+        let sink = InMemorySink::new();
+        assert!(sink.is_empty());
+
+        let stored = sink
+            .store("baselines/home.png", b"\x89PNG", "image/png")
+            .await
+            .unwrap();
+        assert_eq!(
+            stored,
+            ArtifactRef {
+                name: "baselines/home.png".to_string(),
+                location: "memory:baselines/home.png".to_string(),
+                content_type: "image/png".to_string(),
+                size: 4,
+            }
+        );
+        assert_eq!(
+            sink.load("baselines/home.png").await.unwrap(),
+            Some(b"\x89PNG".to_vec())
+        );
+        assert_eq!(sink.load("baselines/missing.png").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn in_memory_sink_replaces_by_name() {
+        let sink = InMemorySink::new();
+        sink.store("b.txt", b"first", "text/plain").await.unwrap();
+        sink.store("a.json", b"{}", "application/json")
+            .await
+            .unwrap();
+        sink.store("b.txt", b"second", "text/markdown")
+            .await
+            .unwrap();
+
+        assert_eq!(sink.len(), 2);
+        assert_eq!(sink.names(), ["a.json", "b.txt"]);
+        assert_eq!(
+            sink.get("b.txt"),
+            Some((b"second".to_vec(), "text/markdown".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn shared_sink_is_inspectable_afterwards() {
+        let sink = Arc::new(InMemorySink::new());
+        let shared: Arc<dyn ArtifactSink> = Arc::new(sink.clone());
+        shared.store("shot.png", b"png", "image/png").await.unwrap();
+
+        assert_eq!(sink.names(), ["shot.png"]);
+    }
+
+    #[tokio::test]
+    async fn report_is_produced_without_touching_disk() {
+        let name = "surfai-in-memory-report/run.html";
+        let report = ReportBuilder::new("In memory").with_timeline_entry(TimelineEntry {
+            index: 1,
+            timestamp: None,
+            action: "click".to_string(),
+            params: serde_json::json!({ "selector": "#go" }),
+            success: true,
+            message: "clicked".to_string(),
+            duration_ms: 12,
+            url: None,
+            screenshot: Some(b"png".to_vec()),
+            console_errors: Vec::new(),
+        });
+        let sink = InMemorySink::new();
+        let stored = report.write(&sink, name).await.unwrap();
+
+        assert_eq!(stored.location, format!("memory:{}", name));
+        let (bytes, content_type) = sink.get(name).unwrap();
+        assert_eq!(content_type, "text/html");
+        assert_eq!(bytes.len(), stored.size);
+        assert!(String::from_utf8(bytes).unwrap().contains("In memory"));
+        assert!(!Path::new("surfai-in-memory-report").exists());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::errors::{BrowserAgentError, Result};
+use std::future::Future;
+
+pub use tokio_util::sync::CancellationToken;
+
+/// Run `future` unless `token` is cancelled first.
+///
+/// The future is dropped at its next await point once the token trips, so work
+/// that blocks the thread inside a single call is only interrupted afterwards.
+pub async fn run_cancellable<T, F>(
+    token: &CancellationToken,
+    operation: &str,
+    future: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    if token.is_cancelled() {
+        return Err(BrowserAgentError::Cancelled(operation.to_string()));
+    }
+
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(BrowserAgentError::Cancelled(operation.to_string())),
+        result = future => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn cancelled_token_skips_the_operation() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = run_cancellable(&token, "op", async { Ok(1) }).await;
+        assert!(matches!(result, Err(BrowserAgentError::Cancelled(op)) if op == "op"));
+    }
+
+    #[tokio::test]
+    async fn cancel_interrupts_blocking_work_moved_off_the_executor() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        let started = Instant::now();
+        let result = run_cancellable(&token, "wait", async {
+            tokio::task::spawn_blocking(|| std::thread::sleep(Duration::from_secs(1)))
+                .await
+                .map_err(|e| BrowserAgentError::ChromeError(e.to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(BrowserAgentError::Cancelled(_))));
+        assert!(started.elapsed() < Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn finished_operation_returns_its_result() {
+        let token = CancellationToken::new();
+        assert_eq!(
+            run_cancellable(&token, "op", async { Ok(7) })
+                .await
+                .unwrap(),
+            7
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Source of the current time and of delays.
+///
+/// Polling loops, retry backoff and fixed settle delays go through a clock so tests
+/// can run them on virtual time.
+#[async_trait]
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    fn now(&self) -> Instant;
+
+    async fn sleep(&self, duration: Duration);
+
+    fn elapsed_since(&self, start: Instant) -> Duration {
+        self.now().saturating_duration_since(start)
+    }
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Tokio's clock. In a runtime whose time is paused it is already virtual.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+#[async_trait]
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+}
+
+pub fn default_clock() -> SharedClock {
+    Arc::new(TokioClock)
+}
+
+/// Clock for tests that pauses tokio time on creation.
+///
+/// While the runtime has nothing else to do, pending sleeps complete at once and
+/// time jumps forward; `advance` moves it explicitly. Needs a current-thread runtime.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug)]
+pub struct VirtualClock {
+    started: Instant,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl VirtualClock {
+    pub fn start() -> Self {
+        tokio::time::pause();
+        Self {
+            started: Instant::now(),
+        }
+    }
+
+    pub async fn advance(&self, duration: Duration) {
+        tokio::time::advance(duration).await
+    }
+
+    /// Virtual time passed since `start`
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_since(self.started)
+    }
+
+    pub fn shared(self) -> SharedClock {
+        Arc::new(self)
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+#[async_trait]
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+}
@@ -0,0 +1,736 @@
+use crate::errors::{BrowserAgentError, Result};
+use crate::utils::SecretString;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use zeroize::Zeroize;
+
+/// What a credential is used for, so a site can have separate accounts for
+/// logging in and, say, its API
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialPurpose {
+    /// Login forms, including the re-login after a logout
+    Login,
+    Named(String),
+}
+
+impl fmt::Display for CredentialPurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialPurpose::Login => f.write_str("login"),
+            CredentialPurpose::Named(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Username and password for a site. The password only leaves the
+/// `SecretString` where it is typed into the page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub username: String,
+    pub password: SecretString,
+    /// Also hand the credential to subdomains of the domain it is stored for.
+    /// Off by default, so a secret for `example.com` never reaches, say, a
+    /// user-content host under it.
+    pub include_subdomains: bool,
+}
+
+impl Credential {
+    pub fn new(username: impl Into<String>, password: impl Into<SecretString>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+            include_subdomains: false,
+        }
+    }
+
+    /// Opt in to `include_subdomains`
+    pub fn with_subdomains(mut self) -> Self {
+        self.include_subdomains = true;
+        self
+    }
+}
+
+/// Source of credentials by domain, such as a secrets manager or vault.
+///
+/// `BrowserSession::with_credential_provider` registers providers, which
+/// `auto_login_with_credentials` and `ReloginFlow::from_provider` ask at the
+/// point of use, so rotated secrets are picked up. Providers are asked for the
+/// exact host, and for parent domains only to find credentials opted in with
+/// `include_subdomains`; see `CredentialChain` for the precedence.
+///
+/// ```ignore
+/// struct VaultProvider {
+///     client: my_vault::Client,
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl surfai::utils::CredentialProvider for VaultProvider {
+///     fn name(&self) -> &str {
+///         "vault"
+///     }
+///
+///     async fn get(&self, domain: &str, purpose: &CredentialPurpose) -> surfai::Result<Option<Credential>> {
+///         let path = format!("sites/{}/{}", domain, purpose);
+///         Ok(self
+///             .client
+///             .read(&path)
+///             .await
+///             .ok()
+///             .map(|secret| Credential::new(secret.username, secret.password)))
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait CredentialProvider: Send + Sync {
+    /// Short name for logs; never the credential itself
+    fn name(&self) -> &str;
+
+    /// Credential for exactly `domain`, or `None` when this provider has none
+    async fn get(&self, domain: &str, purpose: &CredentialPurpose) -> Result<Option<Credential>>;
+}
+
+/// Default prefix of `EnvVarProvider` variables. `Config::apply_env` skips every
+/// variable starting with it, so credentials are never read as settings.
+pub const CREDENTIAL_ENV_PREFIX: &str = "SURFAI_CRED_";
+
+/// Reads credentials from environment variables named after the domain and
+/// purpose, upper-cased with everything but letters and digits turned into `_`:
+/// `SURFAI_CRED__EXAMPLE_COM_LOGIN_USERNAME` and `..._PASSWORD`. Setting
+/// `..._INCLUDE_SUBDOMAINS` to `true` or `1` opts in to `include_subdomains`.
+///
+/// The prefix can be changed with `with_prefix`.
+#[derive(Debug, Clone)]
+pub struct EnvVarProvider {
+    prefix: String,
+}
+
+impl Default for EnvVarProvider {
+    fn default() -> Self {
+        Self {
+            prefix: CREDENTIAL_ENV_PREFIX.to_string(),
+        }
+    }
+}
+
+impl EnvVarProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Names of the username and password variables for `domain` and `purpose`
+    pub fn variable_names(&self, domain: &str, purpose: &CredentialPurpose) -> (String, String) {
+        let key = format!("{}_{}_{}", self.prefix, domain, purpose)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        (format!("{}_USERNAME", key), format!("{}_PASSWORD", key))
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for EnvVarProvider {
+    fn name(&self) -> &str {
+        "env"
+    }
+
+    async fn get(&self, domain: &str, purpose: &CredentialPurpose) -> Result<Option<Credential>> {
+        let (username, password) = self.variable_names(domain, purpose);
+        let subdomains = format!(
+            "{}_INCLUDE_SUBDOMAINS",
+            password.trim_end_matches("_PASSWORD")
+        );
+        match (std::env::var(username), std::env::var(password)) {
+            (Ok(username), Ok(password)) => Ok(Some(Credential {
+                include_subdomains: matches!(
+                    std::env::var(subdomains).as_deref(),
+                    Ok("true" | "1")
+                ),
+                ..Credential::new(username, password)
+            })),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FileEntry {
+    domain: String,
+    #[serde(default = "default_purpose")]
+    purpose: CredentialPurpose,
+    username: String,
+    password: SecretString,
+    #[serde(default)]
+    include_subdomains: bool,
+}
+
+fn default_purpose() -> CredentialPurpose {
+    CredentialPurpose::Login
+}
+
+/// Variable `CredentialKey::from_env` reads, holding the key in base64
+pub const CREDENTIAL_KEY_ENV: &str = "SURFAI_CRED_KEY";
+
+/// AES-256-GCM key of a `JsonFileProvider` file. `Debug` never shows it, and
+/// it is zeroized on drop.
+#[derive(Clone)]
+pub struct CredentialKey([u8; 32]);
+
+impl CredentialKey {
+    /// New random key; store `to_base64` somewhere only the automation reads
+    pub fn generate() -> Self {
+        Self(Aes256Gcm::generate_key(OsRng).into())
+    }
+
+    /// Key from its base64 form, as `to_base64` writes it
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let mut bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .unwrap_or_default();
+        let key = <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| {
+            BrowserAgentError::ConfigurationError(
+                "a credential key is 32 bytes in base64".to_string(),
+            )
+        });
+        bytes.zeroize();
+        key.map(Self)
+    }
+
+    /// Key from `CREDENTIAL_KEY_ENV`
+    pub fn from_env() -> Result<Self> {
+        let encoded = std::env::var(CREDENTIAL_KEY_ENV).map_err(|_| {
+            BrowserAgentError::ConfigurationError(format!("{} is not set", CREDENTIAL_KEY_ENV))
+        })?;
+        Self::from_base64(&encoded)
+    }
+
+    /// Key from a keyfile holding its base64 form
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let mut encoded = std::fs::read_to_string(path)?;
+        let key = Self::from_base64(&encoded);
+        encoded.zeroize();
+        key
+    }
+
+    pub fn to_base64(&self) -> SecretString {
+        SecretString::new(base64::engine::general_purpose::STANDARD.encode(self.0))
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0))
+    }
+}
+
+impl fmt::Debug for CredentialKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CredentialKey([REDACTED])")
+    }
+}
+
+impl Drop for CredentialKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// On-disk form of a `JsonFileProvider` file
+#[derive(Serialize, Deserialize)]
+struct SealedFile {
+    /// base64 of the 96-bit AES-GCM nonce
+    nonce: String,
+    /// base64 of the encrypted entries and their tag
+    ciphertext: String,
+}
+
+/// Reads credentials from a file encrypted with AES-256-GCM under a
+/// `CredentialKey`. Decrypted, it is a JSON list of
+/// `{ "domain", "purpose", "username", "password", "include_subdomains" }`
+/// entries, `purpose` defaulting to `login` and `include_subdomains` to false.
+/// The file is read on every lookup, so edits apply without a restart; `write`
+/// encrypts a new list into it.
+#[derive(Debug, Clone)]
+pub struct JsonFileProvider {
+    path: PathBuf,
+    key: CredentialKey,
+}
+
+impl JsonFileProvider {
+    pub fn new(path: impl Into<PathBuf>, key: CredentialKey) -> Self {
+        Self {
+            path: path.into(),
+            key,
+        }
+    }
+
+    /// Encrypt `entries`, a JSON list as described above, into the file
+    pub async fn write(&self, entries: &[u8]) -> Result<()> {
+        self.parse(entries)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext =
+            self.key.cipher().encrypt(&nonce, entries).map_err(|_| {
+                BrowserAgentError::ConfigurationError("encryption failed".to_string())
+            })?;
+        let sealed = SealedFile {
+            nonce: base64::engine::general_purpose::STANDARD.encode(nonce),
+            ciphertext: base64::engine::general_purpose::STANDARD.encode(ciphertext),
+        };
+        tokio::fs::write(&self.path, serde_json::to_vec(&sealed)?).await?;
+        Ok(())
+    }
+
+    fn not_a_credentials_file(&self, detail: &str) -> BrowserAgentError {
+        BrowserAgentError::ConfigurationError(format!(
+            "{} is not a credentials file: {}",
+            self.path.display(),
+            detail
+        ))
+    }
+
+    /// The decrypted entries, which the caller zeroizes
+    async fn decrypt(&self) -> Result<Vec<u8>> {
+        let bytes = tokio::fs::read(&self.path).await?;
+        let sealed: SealedFile = serde_json::from_slice(&bytes)
+            .map_err(|_| self.not_a_credentials_file("not encrypted with a CredentialKey"))?;
+        let decode = |field: &str| {
+            base64::engine::general_purpose::STANDARD
+                .decode(field)
+                .map_err(|_| self.not_a_credentials_file("malformed base64"))
+        };
+        let nonce = decode(&sealed.nonce)?;
+        if nonce.len() != 12 {
+            return Err(self.not_a_credentials_file("malformed nonce"));
+        }
+        self.key
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                decode(&sealed.ciphertext)?.as_slice(),
+            )
+            .map_err(|_| self.not_a_credentials_file("wrong key, or the file was altered"))
+    }
+
+    fn parse(&self, entries: &[u8]) -> Result<Vec<FileEntry>> {
+        serde_json::from_slice(entries).map_err(|e| {
+            // serde_json errors quote the offending input, which may be a password
+            self.not_a_credentials_file(&format!("line {}, column {}", e.line(), e.column()))
+        })
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for JsonFileProvider {
+    fn name(&self) -> &str {
+        "json_file"
+    }
+
+    async fn get(&self, domain: &str, purpose: &CredentialPurpose) -> Result<Option<Credential>> {
+        let mut plaintext = self.decrypt().await?;
+        let entries = self.parse(&plaintext);
+        plaintext.zeroize();
+        Ok(entries?
+            .into_iter()
+            .find(|entry| entry.domain.eq_ignore_ascii_case(domain) && entry.purpose == *purpose)
+            .map(|entry| Credential {
+                username: entry.username,
+                password: entry.password,
+                include_subdomains: entry.include_subdomains,
+            }))
+    }
+}
+
+/// Providers in the order they were registered.
+///
+/// A lookup for `app.eu.example.com` asks every provider for that host, and the
+/// first one holding it wins. Only when none does are they asked for
+/// `eu.example.com`, then `example.com`, and a credential found there is used
+/// only if it opted in with `include_subdomains`: the most specific domain
+/// wins, and among providers holding the same domain the one registered first.
+/// A provider that fails is logged by name and skipped.
+#[derive(Clone, Default)]
+pub struct CredentialChain {
+    providers: Vec<Arc<dyn CredentialProvider>>,
+}
+
+impl CredentialChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push<P: CredentialProvider + 'static>(&mut self, provider: P) {
+        self.providers.push(Arc::new(provider));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    /// Names of the registered providers, in order
+    pub fn names(&self) -> Vec<String> {
+        self.providers
+            .iter()
+            .map(|provider| provider.name().to_string())
+            .collect()
+    }
+
+    /// Credential for `host`, or for its closest parent domain that opted in to
+    /// `include_subdomains`
+    pub async fn get(&self, host: &str, purpose: &CredentialPurpose) -> Option<Credential> {
+        for (depth, domain) in domain_candidates(host).into_iter().enumerate() {
+            for provider in &self.providers {
+                match provider.get(&domain, purpose).await {
+                    Ok(Some(credential)) if depth > 0 && !credential.include_subdomains => {
+                        tracing::debug!(provider = provider.name(), %domain, %purpose, "credential not shared with subdomains");
+                    }
+                    Ok(Some(credential)) => {
+                        tracing::debug!(provider = provider.name(), %domain, %purpose, "credential found");
+                        return Some(credential);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::warn!(provider = provider.name(), %domain, %e, "credential provider failed")
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// `host` and its parent domains down to the last two labels, most specific first
+fn domain_candidates(host: &str) -> Vec<String> {
+    let host = host.trim_end_matches('.').to_lowercase();
+    let labels: Vec<&str> = host.split('.').collect();
+    let is_ip = host.parse::<std::net::IpAddr>().is_ok();
+    let last = if is_ip || labels.len() < 2 {
+        1
+    } else {
+        labels.len() - 1
+    };
+    (0..last).map(|i| labels[i..].join(".")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Holds credentials for fixed domains, or fails every lookup. A leading
+    /// dot, as in cookie domains, opts the entry in to `include_subdomains`.
+    struct Fixed {
+        name: &'static str,
+        domains: &'static [&'static str],
+        failing: bool,
+    }
+
+    #[async_trait]
+    impl CredentialProvider for Fixed {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn get(
+            &self,
+            domain: &str,
+            purpose: &CredentialPurpose,
+        ) -> Result<Option<Credential>> {
+            if self.failing {
+                return Err(BrowserAgentError::ConfigurationError("vault sealed".into()));
+            }
+            Ok(self
+                .domains
+                .iter()
+                .find(|entry| entry.trim_start_matches('.') == domain)
+                .map(|entry| Credential {
+                    include_subdomains: entry.starts_with('.'),
+                    ..Credential::new(format!("{}@{}/{}", self.name, domain, purpose), "pw")
+                }))
+        }
+    }
+
+    fn chain(providers: Vec<Fixed>) -> CredentialChain {
+        let mut chain = CredentialChain::new();
+        for provider in providers {
+            chain.push(provider);
+        }
+        chain
+    }
+
+    async fn username(chain: &CredentialChain, host: &str) -> Option<String> {
+        chain
+            .get(host, &CredentialPurpose::Login)
+            .await
+            .map(|credential| credential.username)
+    }
+
+    #[tokio::test]
+    async fn first_registered_provider_wins_for_the_same_domain() {
+        let chain = chain(vec![
+            Fixed {
+                name: "vault",
+                domains: &["example.com"],
+                failing: false,
+            },
+            Fixed {
+                name: "env",
+                domains: &["example.com"],
+                failing: false,
+            },
+        ]);
+        assert_eq!(chain.names(), ["vault", "env"]);
+        assert_eq!(
+            username(&chain, "example.com").await.as_deref(),
+            Some("vault@example.com/login")
+        );
+    }
+
+    #[tokio::test]
+    async fn subdomains_only_get_credentials_that_opted_in() {
+        let chain = chain(vec![Fixed {
+            name: "vault",
+            domains: &["example.com"],
+            failing: false,
+        }]);
+        assert_eq!(
+            username(&chain, "example.com").await.as_deref(),
+            Some("vault@example.com/login")
+        );
+        assert_eq!(username(&chain, "user-content.example.com").await, None);
+    }
+
+    #[tokio::test]
+    async fn most_specific_opted_in_domain_wins_over_registration_order() {
+        let chain = chain(vec![
+            Fixed {
+                name: "vault",
+                domains: &[".example.com"],
+                failing: false,
+            },
+            Fixed {
+                name: "env",
+                domains: &[".eu.example.com", "app.eu.example.com"],
+                failing: false,
+            },
+        ]);
+        assert_eq!(
+            username(&chain, "App.EU.example.com.").await.as_deref(),
+            Some("env@app.eu.example.com/login")
+        );
+        assert_eq!(
+            username(&chain, "cdn.eu.example.com").await.as_deref(),
+            Some("env@eu.example.com/login")
+        );
+        assert_eq!(
+            username(&chain, "us.example.com").await.as_deref(),
+            Some("vault@example.com/login")
+        );
+        assert_eq!(username(&chain, "example.org").await, None);
+    }
+
+    #[tokio::test]
+    async fn failing_providers_are_skipped() {
+        let chain = chain(vec![
+            Fixed {
+                name: "vault",
+                domains: &[],
+                failing: true,
+            },
+            Fixed {
+                name: "env",
+                domains: &["example.com"],
+                failing: false,
+            },
+        ]);
+        assert_eq!(
+            username(&chain, "example.com").await.as_deref(),
+            Some("env@example.com/login")
+        );
+        assert_eq!(username(&CredentialChain::new(), "example.com").await, None);
+    }
+
+    #[test]
+    fn candidates_stop_at_the_registrable_domain() {
+        assert_eq!(
+            domain_candidates("app.eu.example.com"),
+            ["app.eu.example.com", "eu.example.com", "example.com"]
+        );
+        assert_eq!(domain_candidates("localhost"), ["localhost"]);
+        assert_eq!(domain_candidates("10.0.0.1"), ["10.0.0.1"]);
+    }
+
+    #[test]
+    fn variable_names_follow_domain_and_purpose() {
+        let env = EnvVarProvider::new();
+        assert_eq!(
+            env.variable_names("example.com", &CredentialPurpose::Login),
+            (
+                "SURFAI_CRED__EXAMPLE_COM_LOGIN_USERNAME".to_string(),
+                "SURFAI_CRED__EXAMPLE_COM_LOGIN_PASSWORD".to_string()
+            )
+        );
+        assert_eq!(
+            env.variable_names(
+                "my-app.example.com",
+                &CredentialPurpose::Named("api".into())
+            ),
+            (
+                "SURFAI_CRED__MY_APP_EXAMPLE_COM_API_USERNAME".to_string(),
+                "SURFAI_CRED__MY_APP_EXAMPLE_COM_API_PASSWORD".to_string()
+            )
+        );
+        assert_eq!(
+            EnvVarProvider::with_prefix("ci")
+                .variable_names("localhost:8080", &CredentialPurpose::Login)
+                .0,
+            "CI_LOCALHOST_8080_LOGIN_USERNAME"
+        );
+    }
+
+    #[tokio::test]
+    async fn env_provider_needs_both_variables() {
+        let env = EnvVarProvider::with_prefix("SURFAI_TEST_CRED");
+        let (username, password) = env.variable_names("env.example", &CredentialPurpose::Login);
+        std::env::set_var(&username, "ada");
+        assert_eq!(
+            env.get("env.example", &CredentialPurpose::Login)
+                .await
+                .unwrap(),
+            None
+        );
+        std::env::set_var(&password, "correct horse");
+        assert_eq!(
+            env.get("env.example", &CredentialPurpose::Login)
+                .await
+                .unwrap(),
+            Some(Credential::new("ada", "correct horse"))
+        );
+        std::env::set_var(
+            "SURFAI_TEST_CRED_ENV_EXAMPLE_LOGIN_INCLUDE_SUBDOMAINS",
+            "true",
+        );
+        assert!(
+            env.get("env.example", &CredentialPurpose::Login)
+                .await
+                .unwrap()
+                .unwrap()
+                .include_subdomains
+        );
+        std::env::remove_var("SURFAI_TEST_CRED_ENV_EXAMPLE_LOGIN_INCLUDE_SUBDOMAINS");
+        std::env::remove_var(&username);
+        std::env::remove_var(&password);
+    }
+
+    fn credentials_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "surfai-credentials-{}.json",
+            uuid::Uuid::new_v4().simple()
+        ))
+    }
+
+    #[tokio::test]
+    async fn json_file_round_trips_encrypted() {
+        let path = credentials_path();
+        let key = CredentialKey::generate();
+        let file = JsonFileProvider::new(&path, key.clone());
+        file.write(
+            br#"[{ "domain": "Example.com", "username": "ada", "password": "pw-1" },
+                 { "domain": "example.com", "purpose": { "named": "api" }, "username": "bot",
+                   "password": "pw-2", "include_subdomains": true }]"#,
+        )
+        .await
+        .unwrap();
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            !on_disk.contains("pw-1") && !on_disk.contains("ada"),
+            "{}",
+            on_disk
+        );
+
+        let reopened = JsonFileProvider::new(
+            &path,
+            CredentialKey::from_base64(key.to_base64().expose_secret()).unwrap(),
+        );
+        assert_eq!(
+            reopened
+                .get("example.com", &CredentialPurpose::Login)
+                .await
+                .unwrap(),
+            Some(Credential::new("ada", "pw-1"))
+        );
+        assert_eq!(
+            reopened
+                .get("example.com", &CredentialPurpose::Named("api".into()))
+                .await
+                .unwrap(),
+            Some(Credential::new("bot", "pw-2").with_subdomains())
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(format!("{:?}", key), "CredentialKey([REDACTED])");
+    }
+
+    #[tokio::test]
+    async fn json_file_errors_do_not_quote_passwords() {
+        let path = credentials_path();
+        let file = JsonFileProvider::new(&path, CredentialKey::generate());
+        let error = file
+            .write(br#"[{ "domain": "example.com", "password": "leaky-secret" "#)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("is not a credentials file"), "{}", error);
+        assert!(!error.contains("leaky-secret"), "{}", error);
+
+        file.write(br#"[{ "domain": "example.com", "username": "ada", "password": "pw-1" }]"#)
+            .await
+            .unwrap();
+        let wrong_key = JsonFileProvider::new(&path, CredentialKey::generate());
+        let error = wrong_key
+            .get("example.com", &CredentialPurpose::Login)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("wrong key"), "{}", error);
+
+        std::fs::write(
+            &path,
+            r#"[{ "domain": "example.com", "username": "ada", "password": "leaky-secret" }]"#,
+        )
+        .unwrap();
+        let error = file
+            .get("example.com", &CredentialPurpose::Login)
+            .await
+            .unwrap_err()
+            .to_string();
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.contains("not encrypted"), "{}", error);
+        assert!(!error.contains("leaky-secret"), "{}", error);
+    }
+
+    #[test]
+    fn keys_are_32_bytes_of_base64() {
+        assert!(CredentialKey::from_base64("c2hvcnQ=").is_err());
+        assert!(CredentialKey::from_base64("not base64!").is_err());
+        let key = CredentialKey::generate();
+        let path = credentials_path();
+        std::fs::write(&path, format!("{}\n", key.to_base64().expose_secret())).unwrap();
+        let from_file = CredentialKey::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(from_file.0, key.0);
+    }
+}
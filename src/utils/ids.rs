@@ -0,0 +1,46 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Ids for sessions and element handles: random v4 UUIDs, or a reproducible
+/// UUID-shaped sequence when seeded
+#[derive(Debug)]
+pub struct IdGenerator {
+    seed: Option<u64>,
+    counter: AtomicU64,
+}
+
+impl IdGenerator {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            seed,
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_seeded(&self) -> bool {
+        self.seed.is_some()
+    }
+
+    pub fn next_id(&self) -> String {
+        match self.seed {
+            Some(seed) => {
+                let n = self.counter.fetch_add(1, Ordering::Relaxed);
+                let high = splitmix64(seed ^ n.wrapping_mul(2));
+                let low = splitmix64(seed ^ n.wrapping_mul(2).wrapping_add(1));
+                let mut bytes = [0u8; 16];
+                bytes[..8].copy_from_slice(&high.to_be_bytes());
+                bytes[8..].copy_from_slice(&low.to_be_bytes());
+                uuid::Builder::from_random_bytes(bytes)
+                    .into_uuid()
+                    .to_string()
+            }
+            None => uuid::Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+pub(crate) fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
@@ -0,0 +1,529 @@
+use crate::dom::ElementRect;
+use crate::errors::{BrowserAgentError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Rectangle in CSS pixels, e.g. a clock or ad slot to leave out of a comparison
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct IgnoreRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl IgnoreRegion {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn contains(&self, px: u32, py: u32, scale: f64) -> bool {
+        let end = |start: u32, length: u32| (start as usize).saturating_add(length as usize);
+        let left = (self.x as f64 * scale).floor();
+        let top = (self.y as f64 * scale).floor();
+        let right = (end(self.x, self.width) as f64 * scale).ceil();
+        let bottom = (end(self.y, self.height) as f64 * scale).ceil();
+        let (px, py) = (px as f64, py as f64);
+        px >= left && px < right && py >= top && py < bottom
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareOptions {
+    /// Largest per-channel difference (0-255) still treated as equal, absorbing
+    /// anti-aliasing noise
+    pub channel_tolerance: u8,
+    pub ignore_regions: Vec<IgnoreRegion>,
+    /// Image pixels per CSS pixel, used to map `ignore_regions` onto the images
+    pub device_scale_factor: f64,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            channel_tolerance: 16,
+            ignore_regions: Vec::new(),
+            device_scale_factor: 1.0,
+        }
+    }
+}
+
+impl CompareOptions {
+    pub fn ignore(mut self, region: IgnoreRegion) -> Self {
+        self.ignore_regions.push(region);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffResult {
+    pub width: u32,
+    pub height: u32,
+    pub mismatched_pixels: u64,
+    /// Mismatched pixels over compared (non-ignored) pixels
+    pub mismatch_ratio: f64,
+    /// Whether the two images had different dimensions
+    pub size_mismatch: bool,
+    /// PNG with mismatches in red over a faded copy of the first image; ignored
+    /// regions are tinted blue
+    pub diff_image: Vec<u8>,
+}
+
+/// Decoded image as tightly packed RGBA8
+struct Rgba {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Rgba {
+    /// Offset of pixel `(x, y)` in `pixels`, when it lies inside the image
+    fn offset(&self, x: i64, y: i64) -> Option<usize> {
+        let x = usize::try_from(x)
+            .ok()
+            .filter(|&x| x < self.width as usize)?;
+        let y = usize::try_from(y)
+            .ok()
+            .filter(|&y| y < self.height as usize)?;
+        y.checked_mul(self.width as usize)?
+            .checked_add(x)?
+            .checked_mul(4)
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        let i = self.offset(x as i64, y as i64)?;
+        self.pixels.get(i..i.checked_add(4)?)?.try_into().ok()
+    }
+
+    fn put(&mut self, x: i64, y: i64, color: [u8; 4]) {
+        let Some(i) = self.offset(x, y) else {
+            return;
+        };
+        if let Some(pixel) = self.pixels.get_mut(i..i.saturating_add(4)) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+}
+
+/// Bytes of a `width` x `height` RGBA8 buffer
+fn buffer_len(width: u32, height: u32) -> Result<usize> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(4))
+        .ok_or_else(|| {
+            BrowserAgentError::ScreenshotFailed(format!("{}x{} image is too large", width, height))
+        })
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Rgba> {
+    let failed = |e: &dyn std::fmt::Display| {
+        BrowserAgentError::ScreenshotFailed(format!("cannot decode PNG: {}", e))
+    };
+
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(|e| failed(&e))?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(|e| failed(&e))?;
+    buffer.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => {
+            return Err(failed(&"palette was not expanded"));
+        }
+    };
+
+    Ok(Rgba {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+fn encode_png(width: u32, height: u32, pixels: &[u8]) -> Result<Vec<u8>> {
+    let failed = |e: png::EncodingError| {
+        BrowserAgentError::ScreenshotFailed(format!("cannot encode PNG: {}", e))
+    };
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(failed)?;
+    writer.write_image_data(pixels).map_err(failed)?;
+    writer.finish().map_err(failed)?;
+    Ok(out)
+}
+
+/// Pixel-diff two PNG screenshots.
+///
+/// Images of different sizes are compared over the larger extent, with every pixel
+/// present in only one of them counted as a mismatch.
+pub fn compare_screenshots(a: &[u8], b: &[u8], options: &CompareOptions) -> Result<DiffResult> {
+    let a = decode_png(a)?;
+    let b = decode_png(b)?;
+    let width = a.width.max(b.width);
+    let height = a.height.max(b.height);
+    let scale = if options.device_scale_factor > 0.0 {
+        options.device_scale_factor
+    } else {
+        1.0
+    };
+
+    let mut diff = Vec::with_capacity(buffer_len(width, height)?);
+    let mut compared = 0u64;
+    let mut mismatched = 0u64;
+
+    for y in 0..height {
+        for x in 0..width {
+            if options
+                .ignore_regions
+                .iter()
+                .any(|region| region.contains(x, y, scale))
+            {
+                diff.extend_from_slice(&[180, 200, 255, 255]);
+                continue;
+            }
+
+            compared += 1;
+            let (pa, pb) = (a.pixel(x, y), b.pixel(x, y));
+            let equal = match (pa, pb) {
+                (Some(pa), Some(pb)) => pa
+                    .iter()
+                    .zip(pb.iter())
+                    .all(|(ca, cb)| ca.abs_diff(*cb) <= options.channel_tolerance),
+                _ => false,
+            };
+
+            if equal {
+                // Faded grayscale of the original keeps the layout readable
+                let p = pa.unwrap_or([255, 255, 255, 255]);
+                let luma = (p[0] as u32 * 299 + p[1] as u32 * 587 + p[2] as u32 * 114) / 1000;
+                let faded = (255 - (255 - luma) / 4) as u8;
+                diff.extend_from_slice(&[faded, faded, faded, 255]);
+            } else {
+                mismatched += 1;
+                diff.extend_from_slice(&[255, 0, 0, 255]);
+            }
+        }
+    }
+
+    Ok(DiffResult {
+        width,
+        height,
+        mismatched_pixels: mismatched,
+        mismatch_ratio: if compared == 0 {
+            0.0
+        } else {
+            mismatched as f64 / compared as f64
+        },
+        size_mismatch: a.width != b.width || a.height != b.height,
+        diff_image: encode_png(width, height, &diff)?,
+    })
+}
+
+/// Shrink a PNG to at most `max_width` pixels wide, averaging the pixels each
+/// output pixel covers. Narrower images come back unchanged.
+pub fn downscale_png(png: &[u8], max_width: u32) -> Result<Vec<u8>> {
+    let image = decode_png(png)?;
+    if image.width <= max_width || max_width == 0 {
+        return Ok(png.to_vec());
+    }
+    let width = max_width;
+    let height = ((image.height as u64 * width as u64) / image.width as u64).max(1) as u32;
+    let mut pixels = Vec::with_capacity(buffer_len(width, height)?);
+    for y in 0..height {
+        let (y0, y1) = span(y, height, image.height);
+        for x in 0..width {
+            let (x0, x1) = span(x, width, image.width);
+            let mut sum = [0u64; 4];
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    let pixel = image.pixel(sx, sy).unwrap_or_default();
+                    for (total, channel) in sum.iter_mut().zip(pixel) {
+                        *total += channel as u64;
+                    }
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)).max(1) as u64;
+            pixels.extend(sum.map(|total| (total / count) as u8));
+        }
+    }
+    encode_png(width, height, &pixels)
+}
+
+/// Source pixels `[start, end)` that output pixel `index` of `out` covers
+fn span(index: u32, out: u32, source: u32) -> (u32, u32) {
+    let start = (index as u64 * source as u64 / out as u64) as u32;
+    let end = ((index as u64 + 1) * source as u64 / out as u64) as u32;
+    (start, end.max(start + 1).min(source))
+}
+
+/// Outline `rects`, given in device pixels, on a PNG screenshot
+pub fn draw_rects(png: &[u8], rects: &[ElementRect], color: [u8; 4]) -> Result<Vec<u8>> {
+    let mut image = decode_png(png)?;
+    for rect in rects {
+        outline(&mut image, rect, color);
+    }
+    encode_png(image.width, image.height, &image.pixels)
+}
+
+/// Outline `rects`, given in device pixels, and tag each with its number.
+///
+/// Tags sit above the top-left corner, or inside it at the image edge; `scale` is
+/// the size in pixels of one dot of the 3x5 digit font.
+pub fn draw_numbered_rects(
+    png: &[u8],
+    rects: &[(ElementRect, usize)],
+    color: [u8; 4],
+    scale: u32,
+) -> Result<Vec<u8>> {
+    let mut image = decode_png(png)?;
+    let scale = scale.max(1) as i64;
+    for (rect, number) in rects {
+        outline(&mut image, rect, color);
+
+        let digits: Vec<usize> = number
+            .to_string()
+            .bytes()
+            .map(|b| (b - b'0') as usize)
+            .collect();
+        let tag_width = (digits.len() as i64 * 4 + 1) * scale;
+        let tag_height = 7 * scale;
+        let left = (rect.x.round() as i64).clamp(0, (image.width as i64 - tag_width).max(0));
+        let above = rect.y.round() as i64 - tag_height;
+        let top = if above >= 0 {
+            above
+        } else {
+            rect.y.round() as i64
+        }
+        .clamp(0, (image.height as i64 - tag_height).max(0));
+
+        fill(&mut image, left, top, tag_width, tag_height, color);
+        for (i, digit) in digits.iter().enumerate() {
+            for (row, bits) in DIGITS[*digit].iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        fill(
+                            &mut image,
+                            left + (1 + i as i64 * 4 + col) * scale,
+                            top + (1 + row as i64) * scale,
+                            scale,
+                            scale,
+                            [255, 255, 255, 255],
+                        );
+                    }
+                }
+            }
+        }
+    }
+    encode_png(image.width, image.height, &image.pixels)
+}
+
+/// 3x5 dot patterns for 0-9, one row per entry with the leftmost dot in bit 2
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+fn outline(image: &mut Rgba, rect: &ElementRect, color: [u8; 4]) {
+    const THICKNESS: i64 = 2;
+    let (width, height) = (image.width as i64, image.height as i64);
+    let left = rect.x.round() as i64;
+    let top = rect.y.round() as i64;
+    let right = (rect.x + rect.width).round() as i64 - 1;
+    let bottom = (rect.y + rect.height).round() as i64 - 1;
+
+    for y in top.max(0)..=bottom.min(height - 1) {
+        for x in left.max(0)..=right.min(width - 1) {
+            let on_edge = x - left < THICKNESS
+                || right - x < THICKNESS
+                || y - top < THICKNESS
+                || bottom - y < THICKNESS;
+            if on_edge {
+                image.put(x, y, color);
+            }
+        }
+    }
+}
+
+fn fill(image: &mut Rgba, left: i64, top: i64, w: i64, h: i64, color: [u8; 4]) {
+    let (width, height) = (image.width as i64, image.height as i64);
+    for y in top.max(0)..(top + h).min(height) {
+        for x in left.max(0)..(left + w).min(width) {
+            image.put(x, y, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    /// `width` x `height` PNG in `color`, with `changes` applied as (x, y, color)
+    fn png(width: u32, height: u32, color: [u8; 4], changes: &[(u32, u32, [u8; 4])]) -> Vec<u8> {
+        let mut image = Rgba {
+            width,
+            height,
+            pixels: color.repeat((width * height) as usize),
+        };
+        for &(x, y, color) in changes {
+            image.put(x as i64, y as i64, color);
+        }
+        encode_png(width, height, &image.pixels).unwrap()
+    }
+
+    fn pixel(png: &[u8], x: u32, y: u32) -> [u8; 4] {
+        decode_png(png).unwrap().pixel(x, y).unwrap()
+    }
+
+    #[test]
+    fn mismatch_ratio_counts_pixels_beyond_the_tolerance() {
+        let grey = [150, 150, 150, 255];
+        let near_white = [245, 245, 245, 255];
+        let changes: Vec<_> = (0..5)
+            .map(|x| (x, 0, grey))
+            .chain((0..5).map(|x| (x, 1, near_white)))
+            .collect();
+        let diff = compare_screenshots(
+            &png(10, 10, WHITE, &[]),
+            &png(10, 10, WHITE, &changes),
+            &CompareOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(diff.mismatched_pixels, 5);
+        assert_eq!(diff.mismatch_ratio, 0.05);
+        assert!(!diff.size_mismatch);
+        assert_eq!(pixel(&diff.diff_image, 0, 0), [255, 0, 0, 255]);
+        assert_ne!(pixel(&diff.diff_image, 0, 1), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn ignore_regions_are_scaled_to_device_pixels() {
+        let black = [0, 0, 0, 255];
+        let block: Vec<_> = (10..14)
+            .flat_map(|y| (10..14).map(move |x| (x, y, black)))
+            .collect();
+        let (a, b) = (png(20, 20, WHITE, &[]), png(20, 20, WHITE, &block));
+        // Five to seven CSS pixels are ten to fourteen device pixels at 2x
+        let options = CompareOptions {
+            device_scale_factor: 2.0,
+            ..CompareOptions::default()
+        }
+        .ignore(IgnoreRegion::new(5, 5, 2, 2));
+        let diff = compare_screenshots(&a, &b, &options).unwrap();
+        assert_eq!(diff.mismatched_pixels, 0);
+        assert_eq!(pixel(&diff.diff_image, 12, 12), [180, 200, 255, 255]);
+
+        let options = CompareOptions::default().ignore(IgnoreRegion::new(5, 5, 2, 2));
+        let diff = compare_screenshots(&a, &b, &options).unwrap();
+        assert_eq!(diff.mismatched_pixels, 16);
+        assert_eq!(diff.mismatch_ratio, 16.0 / 396.0);
+
+        // A region running past the end of u32 covers the rest of the image
+        let options = CompareOptions::default().ignore(IgnoreRegion::new(0, 0, u32::MAX, u32::MAX));
+        assert_eq!(
+            compare_screenshots(&a, &b, &options)
+                .unwrap()
+                .mismatch_ratio,
+            0.0
+        );
+    }
+
+    #[test]
+    fn size_mismatch_is_flagged_and_counted() {
+        let diff = compare_screenshots(
+            &png(10, 10, WHITE, &[]),
+            &png(10, 12, WHITE, &[]),
+            &CompareOptions::default(),
+        )
+        .unwrap();
+        assert!(diff.size_mismatch);
+        assert_eq!((diff.width, diff.height), (10, 12));
+        assert_eq!(diff.mismatched_pixels, 20);
+        assert_eq!(pixel(&diff.diff_image, 3, 11), [255, 0, 0, 255]);
+
+        assert!(matches!(
+            compare_screenshots(
+                b"not a png",
+                &png(1, 1, WHITE, &[]),
+                &CompareOptions::default()
+            ),
+            Err(BrowserAgentError::ScreenshotFailed(_))
+        ));
+    }
+
+    #[test]
+    fn downscale_averages_the_covered_pixels() {
+        let black = [0, 0, 0, 255];
+        let left_half: Vec<_> = (0..2)
+            .flat_map(|y| (0..2).map(move |x| (x, y, black)))
+            .collect();
+        let image = png(4, 2, WHITE, &left_half);
+        let small = downscale_png(&image, 2).unwrap();
+        let decoded = decode_png(&small).unwrap();
+        assert_eq!((decoded.width, decoded.height), (2, 1));
+        assert_eq!(decoded.pixel(0, 0), Some(black));
+        assert_eq!(decoded.pixel(1, 0), Some(WHITE));
+        assert_eq!(downscale_png(&image, 4).unwrap(), image);
+    }
+
+    #[test]
+    fn numbered_rects_are_outlined_and_tagged() {
+        let red = [255, 0, 0, 255];
+        let rect = |x, y| ElementRect {
+            x,
+            y,
+            width: 20.0,
+            height: 10.0,
+        };
+        let drawn = draw_numbered_rects(
+            &png(60, 40, WHITE, &[]),
+            &[(rect(10.0, 20.0), 7), (rect(1000.0, -50.0), 12)],
+            red,
+            1,
+        )
+        .unwrap();
+        assert_eq!(pixel(&drawn, 10, 20), red);
+        assert_eq!(pixel(&drawn, 29, 29), red);
+        assert_eq!(pixel(&drawn, 20, 25), WHITE);
+        // The tag sits above the corner, white dots on the outline colour
+        assert_eq!(pixel(&drawn, 10, 13), red);
+        assert_eq!(pixel(&drawn, 11, 14), WHITE);
+    }
+
+    #[test]
+    fn pixel_offsets_do_not_overflow() {
+        let huge = Rgba {
+            width: u32::MAX,
+            height: u32::MAX,
+            pixels: Vec::new(),
+        };
+        assert_eq!(huge.pixel(u32::MAX - 1, u32::MAX - 1), None);
+        assert_eq!(huge.offset(-1, 0), None);
+    }
+}
@@ -0,0 +1,294 @@
+//! Operational metrics through the [`metrics`](https://docs.rs/metrics) facade.
+//!
+//! With the `metrics` feature enabled, sessions and the action registry record
+//! the metrics below into whatever recorder the application installed, such as
+//! `metrics-exporter-prometheus`. Without the feature every call here compiles to
+//! nothing. Without an installed recorder the calls cost a few atomic loads.
+//!
+//! | Metric                                   | Kind      | Labels                 |
+//! |------------------------------------------|-----------|------------------------|
+//! | `surfai_navigations_total`               | counter   | `outcome`              |
+//! | `surfai_navigation_duration_seconds`     | histogram | `outcome`              |
+//! | `surfai_dom_extraction_duration_seconds` | histogram |                        |
+//! | `surfai_actions_total`                   | counter   | `action`, `result`     |
+//! | `surfai_action_duration_seconds`         | histogram | `action`               |
+//! | `surfai_page_replaced_total`             | counter   | `discarded`            |
+//! | `surfai_relogins_total`                  | counter   | `outcome`              |
+//! | `surfai_tab_crashes_total`               | counter   |                        |
+//! | `surfai_active_sessions`                 | gauge     |                        |
+//!
+//! Navigations count `navigate_smart` calls, with `outcome` one of `success`,
+//! `no_content`, `timeout`, `cancelled` or `error`. A page replaced with
+//! `discarded="true"` is a tab the browser threw away, usually for memory, and
+//! restored; relogins are the session recovering from a logout. Tab crashes
+//! count renderers lost to a crash or running out of memory.
+//! `surfai_active_sessions` counts sessions created and not yet closed or dropped.
+//!
+//! # Cardinality
+//!
+//! Every distinct label set is a separate time series in most backends. The
+//! labels above have a handful of values each; `action` has one per registered
+//! action, and names the registry does not know are recorded as `unknown` so
+//! that typos cannot grow it. Session ids are left out unless
+//! [`set_session_labels`] turns them on, which adds a `session_id` label to
+//! everything but the gauge: one series per session, which suits a few
+//! long-lived sessions and overwhelms backends when sessions are short-lived.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+pub const NAVIGATIONS_TOTAL: &str = "surfai_navigations_total";
+pub const NAVIGATION_DURATION_SECONDS: &str = "surfai_navigation_duration_seconds";
+pub const DOM_EXTRACTION_DURATION_SECONDS: &str = "surfai_dom_extraction_duration_seconds";
+pub const ACTIONS_TOTAL: &str = "surfai_actions_total";
+pub const ACTION_DURATION_SECONDS: &str = "surfai_action_duration_seconds";
+pub const PAGE_REPLACED_TOTAL: &str = "surfai_page_replaced_total";
+pub const RELOGINS_TOTAL: &str = "surfai_relogins_total";
+pub const TAB_CRASHES_TOTAL: &str = "surfai_tab_crashes_total";
+pub const ACTIVE_SESSIONS: &str = "surfai_active_sessions";
+
+static SESSION_LABELS: AtomicBool = AtomicBool::new(false);
+
+/// Add a `session_id` label to per-session metrics; off by default, see the
+/// cardinality notes above
+pub fn set_session_labels(enabled: bool) {
+    SESSION_LABELS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn session_labels() -> bool {
+    SESSION_LABELS.load(Ordering::Relaxed)
+}
+
+/// Register units and descriptions of every metric with the installed recorder,
+/// for exporters that publish them
+#[cfg(feature = "metrics")]
+pub fn describe_metrics() {
+    use metrics::Unit;
+    metrics::describe_counter!(NAVIGATIONS_TOTAL, "navigate_smart calls by outcome");
+    metrics::describe_histogram!(
+        NAVIGATION_DURATION_SECONDS,
+        Unit::Seconds,
+        "Time navigate_smart took, by outcome"
+    );
+    metrics::describe_histogram!(
+        DOM_EXTRACTION_DURATION_SECONDS,
+        Unit::Seconds,
+        "Time extracting the page's DOM state took"
+    );
+    metrics::describe_counter!(
+        ACTIONS_TOTAL,
+        "Registry actions executed by name and result"
+    );
+    metrics::describe_histogram!(
+        ACTION_DURATION_SECONDS,
+        Unit::Seconds,
+        "Time registry actions took, by name"
+    );
+    metrics::describe_counter!(
+        PAGE_REPLACED_TOTAL,
+        "Pages replaced underneath a session, by whether the browser discarded the tab"
+    );
+    metrics::describe_counter!(
+        RELOGINS_TOTAL,
+        "Re-login attempts after a logout, by outcome"
+    );
+    metrics::describe_gauge!(ACTIVE_SESSIONS, "Sessions created and not yet closed");
+}
+
+#[cfg(feature = "metrics")]
+fn labels(session_id: &str, pairs: &[(&'static str, String)]) -> Vec<metrics::Label> {
+    let mut labels: Vec<metrics::Label> = pairs
+        .iter()
+        .map(|(key, value)| metrics::Label::new(*key, value.clone()))
+        .collect();
+    if session_labels() {
+        labels.push(metrics::Label::new("session_id", session_id.to_string()));
+    }
+    labels
+}
+
+pub(crate) fn navigation(session_id: &str, outcome: &str, duration: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        let labels = labels(session_id, &[("outcome", outcome.to_string())]);
+        metrics::counter!(NAVIGATIONS_TOTAL, labels.clone()).increment(1);
+        metrics::histogram!(NAVIGATION_DURATION_SECONDS, labels).record(duration.as_secs_f64());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (session_id, outcome, duration);
+}
+
+pub(crate) fn dom_extraction(session_id: &str, duration: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(DOM_EXTRACTION_DURATION_SECONDS, labels(session_id, &[]))
+        .record(duration.as_secs_f64());
+    #[cfg(not(feature = "metrics"))]
+    let _ = (session_id, duration);
+}
+
+/// `result` is `success` or `failure` for an `ActionResult`, `error` for an `Err`
+pub(crate) fn action(session_id: &str, action: &str, result: &str, duration: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        let name = ("action", action.to_string());
+        metrics::counter!(
+            ACTIONS_TOTAL,
+            labels(session_id, &[name.clone(), ("result", result.to_string())])
+        )
+        .increment(1);
+        metrics::histogram!(ACTION_DURATION_SECONDS, labels(session_id, &[name]))
+            .record(duration.as_secs_f64());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (session_id, action, result, duration);
+}
+
+pub(crate) fn page_replaced(session_id: &str, discarded: bool) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        PAGE_REPLACED_TOTAL,
+        labels(session_id, &[("discarded", discarded.to_string())])
+    )
+    .increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (session_id, discarded);
+}
+
+pub(crate) fn relogin(session_id: &str, success: bool) {
+    #[cfg(feature = "metrics")]
+    {
+        let outcome = if success { "success" } else { "failure" };
+        metrics::counter!(
+            RELOGINS_TOTAL,
+            labels(session_id, &[("outcome", outcome.to_string())])
+        )
+        .increment(1);
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (session_id, success);
+}
+
+pub(crate) fn tab_crashed(session_id: &str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(TAB_CRASHES_TOTAL, labels(session_id, &[])).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = session_id;
+}
+
+/// Counts its session in `surfai_active_sessions` until released or dropped
+pub(crate) struct ActiveSession {
+    released: AtomicBool,
+}
+
+impl ActiveSession {
+    pub fn new() -> Self {
+        #[cfg(feature = "metrics")]
+        metrics::gauge!(ACTIVE_SESSIONS).increment(1.0);
+        Self {
+            released: AtomicBool::new(false),
+        }
+    }
+
+    /// Stop counting the session; later calls do nothing
+    pub fn release(&self) {
+        if !self.released.swap(true, Ordering::Relaxed) {
+            #[cfg(feature = "metrics")]
+            metrics::gauge!(ACTIVE_SESSIONS).decrement(1.0);
+        }
+    }
+}
+
+impl Drop for ActiveSession {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::MetricKind;
+
+    /// Kind, name, sorted labels and value of one metric
+    type Captured = (MetricKind, String, Vec<(String, String)>, DebugValue);
+
+    /// Everything `record` emits, by name
+    fn capture(record: impl FnOnce()) -> Vec<Captured> {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, record);
+        let mut captured: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let mut labels: Vec<_> = key
+                    .key()
+                    .labels()
+                    .map(|label| (label.key().to_string(), label.value().to_string()))
+                    .collect();
+                labels.sort();
+                (key.kind(), key.key().name().to_string(), labels, value)
+            })
+            .collect();
+        captured.sort_by(|a, b| a.1.cmp(&b.1));
+        captured
+    }
+
+    fn label(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    fn histogram(seconds: &[f64]) -> DebugValue {
+        DebugValue::Histogram(seconds.iter().map(|&s| s.into()).collect())
+    }
+
+    #[test]
+    fn navigations_and_actions_are_recorded_with_their_labels() {
+        let captured = capture(|| {
+            navigation("s1", "timeout", Duration::from_millis(1500));
+            navigation("s1", "timeout", Duration::from_millis(500));
+            action("s1", "click", "failure", Duration::from_millis(250));
+        });
+        let outcome = vec![label("outcome", "timeout")];
+        assert_eq!(
+            captured,
+            [
+                (
+                    MetricKind::Histogram,
+                    ACTION_DURATION_SECONDS.to_string(),
+                    vec![label("action", "click")],
+                    histogram(&[0.25])
+                ),
+                (
+                    MetricKind::Counter,
+                    ACTIONS_TOTAL.to_string(),
+                    vec![label("action", "click"), label("result", "failure")],
+                    DebugValue::Counter(1)
+                ),
+                (
+                    MetricKind::Histogram,
+                    NAVIGATION_DURATION_SECONDS.to_string(),
+                    outcome.clone(),
+                    histogram(&[1.5, 0.5])
+                ),
+                (
+                    MetricKind::Counter,
+                    NAVIGATIONS_TOTAL.to_string(),
+                    outcome,
+                    DebugValue::Counter(2)
+                ),
+            ]
+        );
+
+        // Session ids only when turned on
+        set_session_labels(true);
+        let captured = capture(|| navigation("s1", "success", Duration::ZERO));
+        set_session_labels(false);
+        assert!(captured
+            .iter()
+            .all(|(_, _, labels, _)| labels.contains(&label("session_id", "s1"))));
+        assert_eq!(captured.len(), 2);
+    }
+}
@@ -1,5 +1,32 @@
+pub mod artifacts;
+pub mod cancel;
+pub mod clock;
+pub mod credentials;
+pub mod ids;
+pub mod image;
 pub mod javascript;
+pub mod metrics;
+pub mod retry;
+pub mod rng;
 pub mod screenshot;
+pub mod secret;
 
+pub use artifacts::{ArtifactRef, ArtifactSink, InMemorySink, LocalDirSink};
+pub use cancel::{run_cancellable, CancellationToken};
+#[cfg(any(test, feature = "test-util"))]
+pub use clock::VirtualClock;
+pub use clock::{default_clock, Clock, SharedClock, TokioClock};
+pub use credentials::{
+    Credential, CredentialChain, CredentialKey, CredentialProvider, CredentialPurpose,
+    EnvVarProvider, JsonFileProvider, CREDENTIAL_KEY_ENV,
+};
+pub use ids::IdGenerator;
+pub use image::{
+    compare_screenshots, downscale_png, draw_numbered_rects, draw_rects, CompareOptions,
+    DiffResult, IgnoreRegion,
+};
 pub use javascript::JavaScriptRunner;
+pub use retry::RetryPolicy;
+pub use rng::SeededRng;
 pub use screenshot::ScreenshotManager;
+pub use secret::SecretString;
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// Attempt count and exponential backoff between attempts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub multiplier: f64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 250,
+            multiplier: 2.0,
+            max_backoff_ms: 5000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_backoff(mut self, initial_backoff_ms: u64, multiplier: f64) -> Self {
+        self.initial_backoff_ms = initial_backoff_ms;
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff_ms: u64) -> Self {
+        self.max_backoff_ms = max_backoff_ms;
+        self
+    }
+
+    /// Delay to wait after the given failed attempt (1-based)
+    pub fn delay_for(&self, attempt: u32) -> std::time::Duration {
+        let exponent = attempt.saturating_sub(1) as i32;
+        let delay = self.initial_backoff_ms as f64 * self.multiplier.max(1.0).powi(exponent);
+        std::time::Duration::from_millis(delay.min(self.max_backoff_ms as f64) as u64)
+    }
+}
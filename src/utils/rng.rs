@@ -0,0 +1,97 @@
+use crate::utils::ids::splitmix64;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Random numbers for jitter such as interaction pacing: random per run, or a
+/// reproducible sequence when seeded like `IdGenerator`
+#[derive(Debug)]
+pub struct SeededRng {
+    seed: u64,
+    counter: AtomicU64,
+}
+
+impl SeededRng {
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0);
+        Self {
+            seed,
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    pub fn next_u64(&self) -> u64 {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        splitmix64(self.seed ^ n.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+    }
+
+    /// Uniform in `[0, 1)`
+    pub fn next_f64(&self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `min..=max`
+    pub fn range(&self, min: u64, max: u64) -> u64 {
+        if max <= min {
+            return min;
+        }
+        let span = max - min;
+        min + if span == u64::MAX {
+            self.next_u64()
+        } else {
+            self.next_u64() % (span + 1)
+        }
+    }
+
+    /// `true` with probability `p`
+    pub fn chance(&self, p: f64) -> bool {
+        p > 0.0 && self.next_f64() < p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_stays_within_inclusive_bounds() {
+        let rng = SeededRng::new(Some(7));
+        let mut seen = [false; 4];
+        for _ in 0..1000 {
+            let value = rng.range(10, 13);
+            assert!((10..=13).contains(&value), "{} out of range", value);
+            seen[(value - 10) as usize] = true;
+        }
+        assert_eq!(seen, [true; 4], "both ends of the range are reachable");
+    }
+
+    #[test]
+    fn range_handles_degenerate_and_full_spans() {
+        let rng = SeededRng::new(Some(7));
+        assert_eq!(rng.range(5, 5), 5);
+        assert_eq!(rng.range(9, 2), 9);
+        for _ in 0..100 {
+            rng.range(0, u64::MAX);
+            assert!(rng.range(u64::MAX - 1, u64::MAX) >= u64::MAX - 1);
+        }
+    }
+
+    #[test]
+    fn same_seed_repeats_the_sequence() {
+        let a = SeededRng::new(Some(42));
+        let b = SeededRng::new(Some(42));
+        let first: Vec<u64> = (0..8).map(|_| a.range(0, 1000)).collect();
+        let second: Vec<u64> = (0..8).map(|_| b.range(0, 1000)).collect();
+        assert_eq!(first, second);
+        assert_ne!(first, (0..8).map(|_| a.range(0, 1000)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn next_f64_is_in_unit_interval() {
+        let rng = SeededRng::new(None);
+        for _ in 0..1000 {
+            let value = rng.next_f64();
+            assert!((0.0..1.0).contains(&value));
+        }
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+    }
+}
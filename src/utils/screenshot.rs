@@ -1,5 +1,6 @@
 use crate::core::BrowserTrait;
 use crate::errors::Result;
+use crate::utils::artifacts::{ArtifactRef, ArtifactSink};
 use base64;
 pub struct ScreenshotManager;
 
@@ -19,6 +20,16 @@ impl ScreenshotManager {
             .map_err(|e| crate::errors::BrowserAgentError::IoError(e))?;
         Ok(())
     }
+    /// Store a screenshot in `sink` under `name`
+    pub async fn save_to_sink<B: BrowserTrait>(
+        browser: &B,
+        tab: &B::TabHandle,
+        sink: &dyn ArtifactSink,
+        name: &str,
+    ) -> Result<ArtifactRef> {
+        let screenshot_bytes = browser.take_screenshot(tab).await?;
+        sink.store(name, &screenshot_bytes, "image/png").await
+    }
     pub async fn take_element_screenshot<B: BrowserTrait>(
         browser: &B,
         tab: &B::TabHandle,
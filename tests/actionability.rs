@@ -0,0 +1,199 @@
+//! Actionability checks run before clicking and typing, on the `/sticky-header`
+//! fixture, whose header covers a button, and the `/disabled` order form.
+
+mod common;
+
+use surfai::browser::{ActionabilityCheck, ActionabilityReport};
+use surfai::testing::FixtureServer;
+use surfai::{BrowserAgentError, Config, DefaultSession, SessionTrait};
+
+/// Session that gives up on an element after a second
+async fn impatient_session() -> DefaultSession {
+    let mut config = Config::default();
+    config.session.element_timeout_ms = 1000;
+    common::headless_session_with(config).await
+}
+
+/// The check `report` failed at and its detail
+fn failure(report: &ActionabilityReport) -> (ActionabilityCheck, String) {
+    let failure = report
+        .failure()
+        .unwrap_or_else(|| panic!("{} is actionable", report.selector));
+    (failure.check, failure.detail.clone().unwrap_or_default())
+}
+
+/// Assert `result` is a `NotActionable` error for the check named `check`
+fn assert_not_actionable(result: surfai::Result<()>, check: &str) {
+    match result {
+        Err(BrowserAgentError::NotActionable { check: failed, .. }) => assert_eq!(failed, check),
+        other => panic!("expected NotActionable({}), got {:?}", check, other),
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn clicking_under_the_sticky_header_is_occluded() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = impatient_session().await;
+    session
+        .navigate_smart(&server.url("/sticky-header"))
+        .await
+        .unwrap();
+
+    match session.click("#under-header").await {
+        Err(BrowserAgentError::ElementOccluded {
+            selector,
+            covering_selector,
+        }) => {
+            assert_eq!(selector, "#under-header");
+            assert!(
+                covering_selector.contains("site-header"),
+                "covered by {}",
+                covering_selector
+            );
+        }
+        other => panic!("expected ElementOccluded, got {:?}", other),
+    }
+
+    // Scrolled to the middle of the viewport, a button is clear of the header
+    session.click("#below-fold").await.unwrap();
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn disabled_controls_fail_the_enabled_check() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/disabled"))
+        .await
+        .unwrap();
+
+    let report = session.check_actionable("#place").await.unwrap();
+    assert_eq!(
+        failure(&report),
+        (
+            ActionabilityCheck::Enabled,
+            "element is disabled".to_string()
+        )
+    );
+    let report = session.check_actionable("#gift-message").await.unwrap();
+    assert_eq!(
+        failure(&report),
+        (
+            ActionabilityCheck::Enabled,
+            "inside a disabled fieldset".to_string()
+        )
+    );
+    let report = session.check_actionable("#express").await.unwrap();
+    assert_eq!(failure(&report).0, ActionabilityCheck::Enabled);
+    // The first legend of a disabled fieldset stays usable
+    assert!(session
+        .check_actionable("#gift-help")
+        .await
+        .unwrap()
+        .is_actionable());
+
+    // Disabled fails at once rather than after element_timeout_ms
+    let started = std::time::Instant::now();
+    assert_not_actionable(session.click("#place").await, "enabled");
+    assert!(started.elapsed() < std::time::Duration::from_millis(1500));
+
+    session.click("#agree").await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert!(session
+        .check_actionable("#place")
+        .await
+        .unwrap()
+        .is_actionable());
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn readonly_fields_click_but_do_not_type() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/disabled"))
+        .await
+        .unwrap();
+
+    // Clicking only needs the field enabled; typing also needs it editable
+    assert!(session
+        .check_actionable("#reference")
+        .await
+        .unwrap()
+        .is_actionable());
+    assert_not_actionable(session.type_text("#reference", "ORD-9").await, "editable");
+    let value = session
+        .execute_script("document.getElementById('reference').value")
+        .await
+        .unwrap();
+    assert_eq!(value, "ORD-1042");
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn zero_size_elements_are_not_visible() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = impatient_session().await;
+    session
+        .navigate_smart(&server.url("/disabled"))
+        .await
+        .unwrap();
+    session
+        .execute_script(
+            "document.getElementById('note').style.cssText = \
+             'width: 0; height: 0; padding: 0; border: 0'",
+        )
+        .await
+        .unwrap();
+
+    let report = session.check_actionable("#note").await.unwrap();
+    assert_eq!(
+        failure(&report),
+        (
+            ActionabilityCheck::Visible,
+            "element has an empty box".to_string()
+        )
+    );
+    assert_not_actionable(session.click("#note").await, "visible");
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn covered_elements_wait_for_the_cover_to_go() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = impatient_session().await;
+    session
+        .navigate_smart(&server.url("/sticky-header"))
+        .await
+        .unwrap();
+    session.click("#show-backdrop").await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let report = session.check_actionable("#below-fold").await.unwrap();
+    assert_eq!(failure(&report).0, ActionabilityCheck::ReceivesEvents);
+    let covered_by = report.covered_by.clone().unwrap_or_default();
+    assert!(covered_by.contains("modal-backdrop"), "{:?}", report);
+    match session.click("#below-fold").await {
+        Err(BrowserAgentError::ElementOccluded {
+            covering_selector, ..
+        }) => assert_eq!(covering_selector, covered_by),
+        other => panic!("expected ElementOccluded, got {:?}", other),
+    }
+
+    // The click waits for a cover that goes away within element_timeout_ms
+    session
+        .execute_script(
+            "setTimeout(() => document.getElementById('modal-backdrop').style.display = 'none', 200)",
+        )
+        .await
+        .unwrap();
+    session.click("#below-fold").await.unwrap();
+    session.close().await.unwrap();
+}
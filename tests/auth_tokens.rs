@@ -0,0 +1,279 @@
+//! Auth tokens of the `/auth-tokens` fixture, which stores tokens for its own
+//! origin and embeds a frame of the other loopback name storing its own.
+
+mod common;
+
+use surfai::browser::{SessionData, TokenSource};
+use surfai::core::config::TokenProbeOptions;
+use surfai::testing::FixtureServer;
+use surfai::{Config, DefaultSession, InjectOptions, SessionTrait};
+
+/// Session reading the storage of child frames too
+async fn frame_aware_session() -> DefaultSession {
+    probing_session(TokenProbeOptions::default()).await
+}
+
+/// Frame-aware session probing for tokens as `probe` says
+async fn probing_session(probe: TokenProbeOptions) -> DefaultSession {
+    let mut config = Config::default();
+    config.session.aggregate_frame_storage = true;
+    config.session.token_probe = probe;
+    common::headless_session_with(config).await
+}
+
+/// Names of the tokens found in `origin`, sorted
+fn names_in(data: &SessionData, origin: &str) -> Vec<String> {
+    let mut names: Vec<String> = data
+        .auth_tokens
+        .iter()
+        .filter(|token| token.origin == origin)
+        .map(|token| token.name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Origins of the fixture page and of its frame
+fn origins(server: &FixtureServer) -> (String, String) {
+    let port = server.addr().port();
+    (
+        format!("http://127.0.0.1:{}", port),
+        format!("http://localhost:{}", port),
+    )
+}
+
+/// Extract the session once the frame has stored its tokens
+async fn extract(session: &DefaultSession, server: &FixtureServer) -> SessionData {
+    let (_, frame_origin) = origins(server);
+    for _ in 0..50 {
+        let data = session.extract_session("127.0.0.1").await.unwrap();
+        if data.auth_tokens.iter().any(|t| t.origin == frame_origin) {
+            return data;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("the frame never stored its tokens");
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn tokens_are_tagged_with_the_origin_holding_them() {
+    let session = frame_aware_session().await;
+    let server = FixtureServer::start().await.unwrap();
+    session
+        .navigate_smart(&server.url("/auth-tokens"))
+        .await
+        .unwrap();
+
+    let data = extract(&session, &server).await;
+    let (top_origin, frame_origin) = origins(&server);
+    let mut found: Vec<(&str, &str, TokenSource, &str)> = data
+        .auth_tokens
+        .iter()
+        .map(|t| {
+            (
+                t.origin.as_str(),
+                t.name.as_str(),
+                t.source,
+                t.value.as_str(),
+            )
+        })
+        .collect();
+    found.sort_by_key(|&(origin, name, _, _)| (origin, name));
+    let mut expected = vec![
+        (
+            top_origin.as_str(),
+            "access_token",
+            TokenSource::LocalStorage,
+            "top-access",
+        ),
+        (
+            top_origin.as_str(),
+            "refresh_token",
+            TokenSource::SessionStorage,
+            "top-refresh",
+        ),
+        (
+            top_origin.as_str(),
+            "csrf-token",
+            TokenSource::Meta,
+            "fixture-csrf",
+        ),
+        (
+            frame_origin.as_str(),
+            "access_token",
+            TokenSource::LocalStorage,
+            "frame-access",
+        ),
+        (
+            frame_origin.as_str(),
+            "id_token",
+            TokenSource::LocalStorage,
+            "frame-id",
+        ),
+    ];
+    expected.sort_by_key(|&(origin, name, _, _)| (origin, name));
+    assert_eq!(found, expected);
+
+    for token in &data.auth_tokens {
+        if token.origin == frame_origin {
+            assert_eq!(
+                token.frame_path,
+                [format!("{}/auth-tokens/frame", frame_origin)]
+            );
+        } else {
+            assert!(token.frame_path.is_empty(), "{:?}", token);
+        }
+    }
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn injection_writes_each_token_into_its_own_origin() {
+    let session = frame_aware_session().await;
+    let server = FixtureServer::start().await.unwrap();
+    let page = server.url("/auth-tokens");
+    session.navigate_smart(&page).await.unwrap();
+    let mut data = extract(&session, &server).await;
+    for token in &mut data.auth_tokens {
+        token.value = format!("restored-{}", token.value);
+    }
+    // No frame of this origin is on the page, so it is skipped
+    let mut stray = data.auth_tokens[0].clone();
+    stray.origin = "https://elsewhere.example".to_string();
+    stray.name = "stray_token".to_string();
+    data.auth_tokens.push(stray);
+
+    // The page stores its own tokens again when loaded, so do not reload after
+    session.navigate_smart(&page).await.unwrap();
+    // Waits for the frame, which has to be there to receive its tokens
+    extract(&session, &server).await;
+    let report = session
+        .inject_session_with_options(
+            data,
+            &InjectOptions {
+                reload: false,
+                ..InjectOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+    // Meta tags are never written
+    assert_eq!(report.auth_tokens_injected, 4);
+
+    let read = r#"({
+        access: localStorage.getItem('access_token'),
+        id: localStorage.getItem('id_token'),
+        refresh: sessionStorage.getItem('refresh_token'),
+        stray: localStorage.getItem('stray_token')
+    })"#;
+    let top = session.execute_script(read).await.unwrap();
+    assert_eq!(
+        top,
+        serde_json::json!({
+            "access": "restored-top-access",
+            "id": null,
+            "refresh": "restored-top-refresh",
+            "stray": null,
+        })
+    );
+    let frame = session
+        .execute_script_in_frame(
+            surfai::core::FrameTarget::UrlPattern("*/auth-tokens/frame".to_string()),
+            read,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        frame,
+        serde_json::json!({
+            "access": "restored-frame-access",
+            "id": "restored-frame-id",
+            "refresh": null,
+            "stray": null,
+        })
+    );
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn probe_finds_app_keys_and_unnamed_jwts() {
+    let session = probing_session(TokenProbeOptions {
+        extra_keys: vec!["app.session.v2".to_string()],
+        detect_token_values: true,
+        ..TokenProbeOptions::default()
+    })
+    .await;
+    let server = FixtureServer::start().await.unwrap();
+    session
+        .navigate_smart(&server.url("/auth-tokens"))
+        .await
+        .unwrap();
+
+    let data = extract(&session, &server).await;
+    let (top_origin, frame_origin) = origins(&server);
+    assert_eq!(
+        names_in(&data, &top_origin),
+        [
+            "access_token",
+            "app.session.v2",
+            "csrf-token",
+            "profile_cache",
+            "refresh_token"
+        ]
+    );
+    assert_eq!(names_in(&data, &frame_origin), ["access_token", "id_token"]);
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn probe_patterns_skip_invalid_ones() {
+    let session = probing_session(TokenProbeOptions {
+        key_patterns: vec!["(".to_string(), "^app\\.session\\.".to_string()],
+        ..TokenProbeOptions::default()
+    })
+    .await;
+    let server = FixtureServer::start().await.unwrap();
+    session
+        .navigate_smart(&server.url("/auth-tokens"))
+        .await
+        .unwrap();
+
+    let data = extract(&session, &server).await;
+    let (top_origin, _) = origins(&server);
+    // Value detection is off, so the JWT under an unknown key stays unfound
+    assert_eq!(
+        names_in(&data, &top_origin),
+        [
+            "access_token",
+            "app.session.v2",
+            "csrf-token",
+            "refresh_token"
+        ]
+    );
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn probe_stops_at_max_candidates_per_frame() {
+    let session = probing_session(TokenProbeOptions {
+        max_candidates: 1,
+        ..TokenProbeOptions::default()
+    })
+    .await;
+    let server = FixtureServer::start().await.unwrap();
+    session
+        .navigate_smart(&server.url("/auth-tokens"))
+        .await
+        .unwrap();
+
+    let data = extract(&session, &server).await;
+    let (top_origin, frame_origin) = origins(&server);
+    assert_eq!(names_in(&data, &top_origin).len(), 1);
+    assert_eq!(names_in(&data, &frame_origin).len(), 1);
+    session.close().await.unwrap();
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use std::time::{Duration, Instant};
+use surfai::errors::BrowserAgentError;
+use surfai::testing::FixtureServer;
+use surfai::SessionTrait;
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn cancelled_navigation_to_a_hung_page_returns_promptly() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+
+    let token = session.cancellation_token();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        canceller.cancel();
+    });
+    let started = Instant::now();
+    let result = session
+        .navigate_smart_with_cancel(&server.url("/hang"), &token)
+        .await;
+    assert!(
+        matches!(result, Err(BrowserAgentError::Cancelled(_))),
+        "{:?}",
+        result.map(|r| r.url)
+    );
+    assert!(
+        started.elapsed() < Duration::from_secs(3),
+        "returned after {:?}",
+        started.elapsed()
+    );
+
+    // The session stays usable after the cancelled navigation
+    let login = server.url("/login");
+    assert_eq!(session.navigate_smart(&login).await.unwrap().url, login);
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn cancel_all_aborts_a_hung_navigation() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+    let session = std::sync::Arc::new(session);
+
+    let navigating = session.clone();
+    let hang = server.url("/hang");
+    let navigation = tokio::spawn(async move { navigating.navigate_smart(&hang).await });
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let started = Instant::now();
+    session.cancel_all();
+    let result = tokio::time::timeout(Duration::from_secs(3), navigation)
+        .await
+        .expect("navigate_smart did not return after cancel_all")
+        .unwrap();
+    assert!(matches!(result, Err(BrowserAgentError::Cancelled(_))));
+    assert!(started.elapsed() < Duration::from_secs(3));
+    session.close().await.unwrap();
+}
@@ -0,0 +1,34 @@
+//! Shared setup for the integration tests: sessions against the fixture server.
+//!
+//! Tests that drive a browser are marked `#[ignore = "needs Chrome"]` and run with
+//! `cargo test --features test-util -- --ignored` on a machine with Chrome. They
+//! fail, rather than pass vacuously, when Chrome cannot be found.
+
+// Each test binary uses a different subset of the helpers
+#![allow(dead_code)]
+
+use surfai::browser::ChromeBrowser;
+use surfai::core::config::HeadlessMode;
+use surfai::{Config, DefaultSession};
+
+/// Whether a Chrome or Chromium binary is installed, or one is named by `CHROME`
+pub fn chrome_available() -> bool {
+    std::env::var_os("CHROME").is_some() || !ChromeBrowser::detect_installations().is_empty()
+}
+
+/// A headless session; panics when Chrome is absent
+pub async fn headless_session() -> DefaultSession {
+    headless_session_with(Config::default()).await
+}
+
+/// A headless session with `config`; panics when Chrome is absent
+pub async fn headless_session_with(mut config: Config) -> DefaultSession {
+    assert!(
+        chrome_available(),
+        "Chrome is not installed; set CHROME to its path to run the browser tests"
+    );
+    config.browser.headless = HeadlessMode::New;
+    DefaultSession::with_config(config)
+        .await
+        .expect("launch headless Chrome")
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use std::time::Duration;
+use surfai::testing::FixtureServer;
+use surfai::{SessionHandle, SessionTrait};
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn highlight_refresh_and_ai_elements_run_concurrently() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+    let session = SessionHandle::new(session);
+    session.navigate_smart(&server.url("/login")).await.unwrap();
+
+    let highlighter = session.clone();
+    let reader = session.clone();
+    let highlighting = tokio::spawn(async move {
+        for _ in 0..10 {
+            highlighter.highlight_interactive_elements().await.unwrap();
+        }
+    });
+    let reading = tokio::spawn(async move {
+        let mut counts = Vec::new();
+        for _ in 0..10 {
+            counts.push(reader.get_ai_elements().await.unwrap().len());
+        }
+        counts
+    });
+
+    // A deadlock would leave both tasks hanging
+    let (highlighted, counts) = tokio::time::timeout(Duration::from_secs(60), async {
+        tokio::join!(highlighting, reading)
+    })
+    .await
+    .expect("highlighting and reading elements deadlocked");
+    highlighted.unwrap();
+    let counts = counts.unwrap();
+    assert!(counts.iter().all(|&count| count > 0), "{:?}", counts);
+
+    // The session is still usable once both are done
+    let highlights = session.highlight_interactive_elements().await.unwrap();
+    assert!(!highlights.is_empty());
+
+    session.close().await.unwrap();
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use std::time::Duration;
+use surfai::errors::BrowserAgentError;
+use surfai::testing::FixtureServer;
+use surfai::{SessionHandle, SessionTrait};
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn shared_session_recovers_from_a_crash() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+    let session = SessionHandle::new(session);
+    let other = session.clone();
+    session.start_keepalive().await.unwrap();
+
+    let login = server.url("/login");
+    session.navigate_smart(&login).await.unwrap();
+    let _ = tokio::time::timeout(
+        Duration::from_secs(5),
+        session.call_method_raw("Page.crash", serde_json::json!({})),
+    )
+    .await;
+    let mut crashed = false;
+    for _ in 0..50 {
+        if let Err(BrowserAgentError::TabCrashed(_)) = other.execute_script("1").await {
+            crashed = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(crashed, "the crash was never detected");
+
+    // Recovery goes through a handle while another clone is alive
+    let recovered = other.reload_after_crash().await.unwrap();
+    assert_eq!(recovered.url, login);
+    assert_eq!(session.handle_count(), 2);
+    assert_eq!(session.execute_script("1 + 1").await.unwrap(), 2);
+
+    session.stop_keepalive();
+    session.close().await.unwrap();
+}
@@ -0,0 +1,117 @@
+//! Disabled, readonly and aria-disabled controls of the `/disabled` order form
+//! are reported as such, lose the capabilities they cannot honour and are
+//! refused at once.
+
+mod common;
+
+use std::time::{Duration, Instant};
+use surfai::testing::FixtureServer;
+use surfai::{BrowserAgentError, SessionTrait};
+
+/// Well under the 2s element timeout, so a refusal did not wait it out
+const FAIL_FAST: Duration = Duration::from_millis(1000);
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn elements_report_disabled_and_readonly() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/disabled"))
+        .await
+        .unwrap();
+    let elements = session.get_ai_elements().await.unwrap();
+
+    // (id, disabled, readonly, aria-disabled)
+    for (id, disabled, readonly, aria_disabled) in [
+        ("place", true, false, false),
+        ("gift-message", true, false, false),
+        ("gift-help", false, false, false),
+        ("reference", false, true, false),
+        ("express", false, false, true),
+        ("note", false, false, false),
+    ] {
+        let element = elements
+            .iter()
+            .find(|element| element.attributes.get("id").map(String::as_str) == Some(id))
+            .unwrap_or_else(|| panic!("#{} is missing", id));
+        assert_eq!(
+            (
+                element.is_disabled,
+                element.is_readonly,
+                element.is_aria_disabled
+            ),
+            (disabled, readonly, aria_disabled),
+            "#{}",
+            id
+        );
+        let accepts_text = element
+            .capabilities
+            .iter()
+            .any(|capability| capability == "can_receive_text_input");
+        if disabled || aria_disabled {
+            assert!(
+                element.capabilities.is_empty(),
+                "#{} {:?}",
+                id,
+                element.capabilities
+            );
+            assert!(
+                element.ai_instructions.contains("disabled"),
+                "#{}: {}",
+                id,
+                element.ai_instructions
+            );
+        }
+        if readonly {
+            assert!(!accepts_text, "read-only #{} accepts text input", id);
+        }
+        if id == "note" {
+            assert!(accepts_text, "#note lost its text input capability");
+        }
+    }
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn dead_controls_are_refused_until_enabled() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/disabled"))
+        .await
+        .unwrap();
+
+    for (target, check) in [("#place", "enabled"), ("#express", "enabled")] {
+        let started = Instant::now();
+        match session.click(target).await {
+            Err(BrowserAgentError::NotActionable { check: failed, .. }) => {
+                assert_eq!(failed, check, "{}", target)
+            }
+            other => panic!("{} returned {:?}", target, other),
+        }
+        assert!(
+            started.elapsed() < FAIL_FAST,
+            "{} took {:?}",
+            target,
+            started.elapsed()
+        );
+    }
+    match session.type_text("#reference", "ORD-9").await {
+        Err(BrowserAgentError::NotActionable { check, .. }) => assert_eq!(check, "editable"),
+        other => panic!("#reference returned {:?}", other),
+    }
+
+    // Meeting the prerequisite enables the button
+    session.click("#agree").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    session.click("#place").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let status = session
+        .execute_script("document.getElementById('status').textContent")
+        .await
+        .unwrap();
+    assert_eq!(status, "placed");
+    session.close().await.unwrap();
+}
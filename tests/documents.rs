@@ -0,0 +1,74 @@
+//! Non-HTML documents: the fixture server's PDF and PNG are recognized after
+//! navigation, DOM operations refuse them, and screenshots, URLs and downloads
+//! still work.
+
+mod common;
+
+use surfai::dom::DocumentKind;
+use surfai::testing::FixtureServer;
+use surfai::{BrowserAgentError, SessionTrait};
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn pdf_and_png_refuse_dom_operations() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+
+    for (path, expected) in [
+        ("/document.pdf", DocumentKind::Pdf),
+        ("/image.png", DocumentKind::Image),
+    ] {
+        let result = session.navigate_smart(&server.url(path)).await.unwrap();
+        assert_eq!(result.document_kind, expected, "{}", path);
+
+        let state = session.get_page_state(true).await.unwrap();
+        assert_eq!(state.document_kind, expected, "{}", path);
+        assert!(state.elements.is_empty(), "{}", path);
+        assert!(state.screenshot_base64.is_some(), "{}", path);
+
+        match session.get_ai_elements().await {
+            Err(BrowserAgentError::UnsupportedDocument { kind, .. }) => {
+                assert_eq!(kind, expected, "{}", path)
+            }
+            other => panic!(
+                "get_ai_elements on {} returned {:?}",
+                path,
+                other.map(|elements| elements.len())
+            ),
+        }
+        assert!(
+            matches!(
+                session.highlight_interactive_elements().await,
+                Err(BrowserAgentError::UnsupportedDocument { .. })
+            ),
+            "{}",
+            path
+        );
+
+        assert!(!session.screenshot().await.unwrap().is_empty(), "{}", path);
+        let url = session.current_url().await.unwrap();
+        assert!(url.ends_with(path), "{} reported URL {}", path, url);
+    }
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn pdf_downloads_from_the_page() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+
+    session
+        .navigate_smart(&server.url("/document.pdf"))
+        .await
+        .unwrap();
+    let document = session.download_document(1024 * 1024).await.unwrap();
+    assert!(document.bytes().starts_with(b"%PDF-"));
+    assert!(!document.truncated);
+
+    // Back on an HTML page, DOM operations work again
+    session.navigate_smart(&server.url("/login")).await.unwrap();
+    assert_eq!(session.document_kind().await.unwrap(), DocumentKind::Html);
+    assert!(!session.get_ai_elements().await.unwrap().is_empty());
+    session.close().await.unwrap();
+}
@@ -0,0 +1,123 @@
+//! DOM extraction through each backend: Chrome over CDP, and a WebDriver remote
+//! end such as chromedriver. Both run the same checks, so a backend that drifts
+//! from the other fails the same test.
+//!
+//! The WebDriver tests need the `webdriver` feature and `WEBDRIVER_URL`
+//! pointing at a remote end that can reach this machine, for example
+//! `chromedriver --port=9515` and `WEBDRIVER_URL=http://localhost:9515`.
+
+mod common;
+
+use surfai::testing::FixtureServer;
+use surfai::{BrowserSession, BrowserTrait, SessionTrait};
+
+async fn extracts_login_form<B: BrowserTrait>(session: BrowserSession<B>) {
+    let server = FixtureServer::start().await.unwrap();
+    session.navigate_smart(&server.url("/login")).await.unwrap();
+    let state = session.get_page_state(false).await.unwrap();
+    assert_eq!(state.title, "Sign in");
+    let ids = |elements: &[surfai::dom::DomElement]| {
+        elements
+            .iter()
+            .filter_map(|element| element.element_id.clone())
+            .collect::<Vec<_>>()
+    };
+    let inputs = ids(&state.input_elements);
+    assert!(
+        inputs.contains(&"username".to_string()) && inputs.contains(&"password".to_string()),
+        "{:?}",
+        inputs
+    );
+    assert!(
+        ids(&state.clickable_elements).contains(&"login-button".to_string()),
+        "{:?}",
+        ids(&state.clickable_elements)
+    );
+    session.close().await.unwrap();
+}
+
+async fn extracts_numbered_elements<B: BrowserTrait>(session: BrowserSession<B>) {
+    let server = FixtureServer::start().await.unwrap();
+    session
+        .navigate_smart(&server.url("/selectors"))
+        .await
+        .unwrap();
+    let elements = session.get_ai_elements().await.unwrap();
+    let texts: Vec<_> = elements
+        .iter()
+        .filter_map(|element| element.text_content.as_deref())
+        .collect();
+    assert!(texts.contains(&"Save draft"), "{:?}", texts);
+    assert!(texts.contains(&"Delete all"), "{:?}", texts);
+    assert!(
+        elements
+            .iter()
+            .all(|element| element.attributes.get("id").map(String::as_str) != Some("hidden-send")),
+        "the hidden button was extracted"
+    );
+    let numbers: Vec<_> = elements
+        .iter()
+        .map(|element| element.element_number)
+        .collect();
+    assert_eq!(numbers, (1..=elements.len()).collect::<Vec<_>>());
+    session.close().await.unwrap();
+}
+
+async fn extracts_resolved_links<B: BrowserTrait>(session: BrowserSession<B>) {
+    let server = FixtureServer::start().await.unwrap();
+    session.navigate_smart(&server.url("/links")).await.unwrap();
+    let links = session.extract_links().await.unwrap();
+    assert!(links.contains(&server.url("/docs/guide")), "{:?}", links);
+    assert!(links.contains(&server.url("/about")), "{:?}", links);
+    assert!(
+        links.contains(&"https://example.com/pricing".to_string()),
+        "{:?}",
+        links
+    );
+    session.close().await.unwrap();
+}
+
+/// The checks above as tests against one backend
+macro_rules! backend_tests {
+    ($backend:ident, $reason:literal, $session:expr) => {
+        mod $backend {
+            use super::*;
+
+            #[tokio::test]
+            #[ignore = $reason]
+            async fn extracts_login_form() {
+                super::extracts_login_form($session).await;
+            }
+
+            #[tokio::test]
+            #[ignore = $reason]
+            async fn extracts_numbered_elements() {
+                super::extracts_numbered_elements($session).await;
+            }
+
+            #[tokio::test]
+            #[ignore = $reason]
+            async fn extracts_resolved_links() {
+                super::extracts_resolved_links($session).await;
+            }
+        }
+    };
+}
+
+backend_tests!(chrome, "needs Chrome", common::headless_session().await);
+
+#[cfg(feature = "webdriver")]
+async fn webdriver_session() -> BrowserSession<surfai::browser::WebDriverBrowser> {
+    let mut config = surfai::Config::default();
+    config.browser.webdriver_url = Some(
+        std::env::var("WEBDRIVER_URL")
+            .expect("set WEBDRIVER_URL to a chromedriver or grid endpoint"),
+    );
+    config.browser.headless = surfai::core::config::HeadlessMode::New;
+    BrowserSession::with_webdriver(config)
+        .await
+        .expect("open a WebDriver session")
+}
+
+#[cfg(feature = "webdriver")]
+backend_tests!(webdriver, "needs chromedriver", webdriver_session().await);
@@ -0,0 +1,283 @@
+//! Dry runs of a login plan on the `/login` fixture: every step is resolved and
+//! validated, failing steps say why, and the page is left untouched.
+
+mod common;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use surfai::actions::base::ActionContext;
+use surfai::actions::{Action, ActionError, SequenceAction};
+use surfai::testing::FixtureServer;
+use surfai::{
+    ActionRegistry, ActionResult, ChromeBrowser, Config, DryRunReport, SessionHandle, SessionTrait,
+};
+
+/// Clicks, types or navigates, or previews doing so in a dry run
+struct PageAction {
+    name: &'static str,
+    session: SessionHandle<ChromeBrowser>,
+}
+
+impl PageAction {
+    fn string<'a>(params: &'a Value, key: &str) -> surfai::Result<&'a str> {
+        params[key].as_str().ok_or_else(|| {
+            ActionError::InvalidParameters(format!("{} must be a string", key)).into()
+        })
+    }
+
+    fn preview(report: DryRunReport) -> surfai::Result<ActionResult> {
+        let message = if report.would_succeed() {
+            format!("Would {} {}", report.action, report.target)
+        } else {
+            format!("Would fail: {}", report.problems.join("; "))
+        };
+        Ok(ActionResult {
+            success: report.would_succeed(),
+            message,
+            data: Some(serde_json::to_value(&report)?),
+            execution_time_ms: 0,
+        })
+    }
+}
+
+#[async_trait]
+impl Action for PageAction {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        "Click a selector, type text into it, or navigate to a URL"
+    }
+
+    fn parameter_schema(&self) -> Value {
+        match self.name {
+            "navigate" => json!({
+                "type": "object",
+                "required": ["url"],
+                "properties": { "url": { "type": "string" } }
+            }),
+            "type" => json!({
+                "type": "object",
+                "required": ["selector", "text"],
+                "properties": {
+                    "selector": { "type": "string" },
+                    "text": { "type": "string" }
+                }
+            }),
+            _ => json!({
+                "type": "object",
+                "required": ["selector"],
+                "properties": { "selector": { "type": "string" } }
+            }),
+        }
+    }
+
+    async fn dry_run(
+        &self,
+        params: Value,
+        _context: &ActionContext,
+    ) -> surfai::Result<ActionResult> {
+        let report = match self.name {
+            "navigate" => {
+                self.session
+                    .dry_run_navigate(Self::string(&params, "url")?)
+                    .await?
+            }
+            "type" => {
+                self.session
+                    .dry_run_type(Self::string(&params, "selector")?)
+                    .await?
+            }
+            _ => {
+                self.session
+                    .dry_run_click(Self::string(&params, "selector")?)
+                    .await?
+            }
+        };
+        Self::preview(report)
+    }
+
+    async fn execute(
+        &self,
+        params: Value,
+        _context: &ActionContext,
+    ) -> surfai::Result<ActionResult> {
+        match self.name {
+            "navigate" => {
+                let result = self
+                    .session
+                    .navigate_smart(Self::string(&params, "url")?)
+                    .await?;
+                Ok(ActionResult::success_with_data(
+                    format!("Navigated to {}", result.url),
+                    json!({ "url": result.url }),
+                ))
+            }
+            "type" => {
+                let selector = Self::string(&params, "selector")?;
+                self.session
+                    .type_text(selector, Self::string(&params, "text")?)
+                    .await?;
+                Ok(ActionResult::success(format!("Typed into {}", selector)))
+            }
+            _ => {
+                let selector = Self::string(&params, "selector")?;
+                self.session.click(selector).await?;
+                Ok(ActionResult::success(format!("Clicked {}", selector)))
+            }
+        }
+    }
+}
+
+/// Registry of the page actions and `sequence`, on a session that may not visit
+/// example.com
+async fn registry() -> (SessionHandle<ChromeBrowser>, Arc<ActionRegistry>) {
+    let mut config = Config::default();
+    config.session.blocked_domains = vec!["example.com".to_string()];
+    let session = common::headless_session_with(config).await.into_handle();
+    let mut registry = ActionRegistry::new();
+    for name in ["click", "type", "navigate"] {
+        registry.register(PageAction {
+            name,
+            session: session.clone(),
+        });
+    }
+    registry.register(SequenceAction::new());
+    (session, Arc::new(registry))
+}
+
+/// Close the session once the registry, the last other holder of it, is gone
+async fn close(session: SessionHandle<ChromeBrowser>, registry: Arc<ActionRegistry>) {
+    drop(registry);
+    if let Ok(session) = session.try_unwrap() {
+        session.close().await.unwrap();
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn dry_run_plans_leave_the_page_untouched() {
+    let server = FixtureServer::start().await.unwrap();
+    let (session, registry) = registry().await;
+    let context = ActionContext::new("dry-run-test".to_string())
+        .with_registry(registry.clone())
+        .with_dry_run(true);
+
+    session.navigate_smart(&server.url("/login")).await.unwrap();
+    let before = session
+        .get_page_state(false)
+        .await
+        .unwrap()
+        .structural_fingerprint();
+    let dashboard = server.url("/dashboard");
+    let plan = json!({
+        "steps": [
+            { "action": "type", "params": { "selector": "#username", "text": "alice" } },
+            { "action": "type", "params": { "selector": "#password", "text": "secret" } },
+            { "action": "click", "params": { "selector": "#login-button" } },
+            { "action": "navigate", "params": { "url": dashboard } }
+        ]
+    });
+    let result = registry
+        .execute_action("sequence", plan, &context)
+        .await
+        .unwrap();
+    assert!(result.success, "{:?}", result);
+    assert!(result.is_dry_run());
+    let steps = result.data.as_ref().unwrap()["steps"]
+        .as_array()
+        .unwrap()
+        .clone();
+    assert_eq!(steps.len(), 4);
+    for step in &steps {
+        assert_eq!(step["result"]["dry_run"], json!(true), "{}", step["action"]);
+    }
+    assert_eq!(
+        steps[2]["result"]["data"]["resolved_url"].as_str(),
+        Some(server.url("/login").as_str())
+    );
+
+    // Plans that would fail say why, still without touching the page
+    for (action, params, problem) in [
+        (
+            "click",
+            json!({ "selector": "input" }),
+            "matches 2 elements",
+        ),
+        (
+            "click",
+            json!({ "selector": "#missing" }),
+            "matches no element",
+        ),
+        (
+            "type",
+            json!({ "selector": "#login-button", "text": "x" }),
+            "not editable",
+        ),
+        (
+            "navigate",
+            json!({ "url": "https://example.com/" }),
+            "blocked_domains: example.com",
+        ),
+    ] {
+        let result = registry
+            .execute_action(action, params, &context)
+            .await
+            .unwrap();
+        assert!(!result.success, "{}: {:?}", action, result);
+        assert!(result.is_dry_run(), "{}", action);
+        assert!(result.message.contains(problem), "{}", result.message);
+    }
+    assert!(matches!(
+        registry
+            .execute_action("type", json!({ "selector": "#username" }), &context)
+            .await,
+        Err(surfai::BrowserAgentError::ActionError(
+            ActionError::InvalidParameters(_)
+        ))
+    ));
+
+    let after = session
+        .get_page_state(false)
+        .await
+        .unwrap()
+        .structural_fingerprint();
+    assert_eq!(before, after);
+    assert!(session.current_url().await.unwrap().ends_with("/login"));
+    let typed = session
+        .execute_script("document.getElementById('username').value")
+        .await
+        .unwrap();
+    assert_eq!(typed, "");
+    drop(context);
+    close(session, registry).await;
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn real_runs_are_not_marked_as_dry_runs() {
+    let server = FixtureServer::start().await.unwrap();
+    let (session, registry) = registry().await;
+    let context = ActionContext::new("dry-run-test".to_string()).with_registry(registry.clone());
+
+    session.navigate_smart(&server.url("/login")).await.unwrap();
+    let result = registry
+        .execute_action(
+            "type",
+            json!({ "selector": "#username", "text": "alice" }),
+            &context,
+        )
+        .await
+        .unwrap();
+    assert!(result.success);
+    assert!(!result.is_dry_run());
+    let typed = session
+        .execute_script("document.getElementById('username').value")
+        .await
+        .unwrap();
+    assert_eq!(typed, "alice");
+    drop(context);
+    close(session, registry).await;
+}
@@ -0,0 +1,106 @@
+//! Element provenance with `DomConfig::explain`: off by default, and with it on,
+//! every element says which rule extracted it and why it was kept.
+
+mod common;
+
+use surfai::dom::RuleKind;
+use surfai::testing::FixtureServer;
+use surfai::{Config, DefaultSession, DomElement, DomState, SessionTrait};
+
+const PAGES: &[&str] = &["/login", "/menus", "/links", "/countries", "/graphics"];
+
+fn element<'a>(state: &'a DomState, id: &str) -> &'a DomElement {
+    state
+        .elements
+        .iter()
+        .find(|element| element.element_id.as_deref() == Some(id))
+        .unwrap_or_else(|| panic!("#{} is missing", id))
+}
+
+async fn session(explain: bool) -> DefaultSession {
+    let mut config = Config::default();
+    config.dom.explain = explain;
+    common::headless_session_with(config).await
+}
+
+/// Page state of each of `PAGES`
+async fn states(session: &DefaultSession, server: &FixtureServer) -> Vec<DomState> {
+    let mut states = Vec::new();
+    for page in PAGES {
+        session.navigate_smart(&server.url(page)).await.unwrap();
+        states.push(session.get_page_state(false).await.unwrap());
+    }
+    states
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn explain_only_adds_provenance() {
+    let server = FixtureServer::start().await.unwrap();
+    let plain_session = session(false).await;
+    let plain = states(&plain_session, &server).await;
+    plain_session.close().await.unwrap();
+    let explained_session = session(true).await;
+    let explained = states(&explained_session, &server).await;
+    explained_session.close().await.unwrap();
+
+    for (plain, explained) in plain.iter().zip(&explained) {
+        // Off by default, and absent from serialized states
+        assert!(
+            plain.elements.iter().all(|e| e.provenance.is_none()),
+            "{}",
+            plain.url
+        );
+        assert!(
+            !serde_json::to_string(plain).unwrap().contains("provenance"),
+            "{}",
+            plain.url
+        );
+        // Explaining changes nothing about what is extracted
+        assert_eq!(
+            plain.elements.len(),
+            explained.elements.len(),
+            "{}",
+            plain.url
+        );
+        assert!(
+            explained.elements.iter().all(|e| e.provenance.is_some()),
+            "{}",
+            explained.url
+        );
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn provenance_names_the_matching_rules() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = session(true).await;
+
+    session.navigate_smart(&server.url("/login")).await.unwrap();
+    let login = session.get_page_state(false).await.unwrap();
+    let provenance = element(&login, "username").provenance.as_ref().unwrap();
+    assert_eq!(provenance.rule, "input");
+    assert_eq!(provenance.rule_kind, RuleKind::Interactive);
+    assert!(provenance.interactable_because.is_some());
+    let explanation = login.explain("username");
+    assert!(
+        explanation.contains("extracted by `input`"),
+        "{}",
+        explanation
+    );
+    assert!(explanation.contains("matched"), "{}", explanation);
+
+    // A menu item matched by its role, then by its ARIA attributes and as a list item
+    session.navigate_smart(&server.url("/menus")).await.unwrap();
+    let menus = session.get_page_state(false).await.unwrap();
+    let provenance = element(&menus, "products").provenance.as_ref().unwrap();
+    assert_eq!(provenance.rule, "[role='menuitem']");
+    assert!(provenance
+        .also_matched
+        .iter()
+        .any(|rule| rule == "[aria-expanded]"));
+    assert!(provenance.also_matched.iter().any(|rule| rule == "li"));
+    assert!(menus.explain("nothing-here").contains("no such element"));
+    session.close().await.unwrap();
+}
@@ -0,0 +1,91 @@
+//! Fields of the `/form-labels` fixture found by their label text, however the
+//! page attaches it: wrapping label, `for`, placeholder, `aria-labelledby` or
+//! text in the neighbouring table cell.
+
+mod common;
+
+use surfai::testing::FixtureServer;
+use surfai::{BrowserAgentError, LabelSource, SessionTrait};
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn labels_resolve_to_their_fields() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/form-labels"))
+        .await
+        .unwrap();
+
+    // Case, diacritics, required markers and the trailing colon are ignored
+    let cases = [
+        ("full name", "input#full-name", LabelSource::Wrapping),
+        ("EMAIL ADDRESS", "input#email", LabelSource::For),
+        ("Phone number", "input#phone", LabelSource::Placeholder),
+        ("Ciudád", "input#city", LabelSource::AriaLabelledBy),
+        ("Country", "select#country", LabelSource::For),
+        ("Company", "input#company", LabelSource::Proximity),
+    ];
+    for (label, selector, source) in cases {
+        let field = session.find_field_by_label(label).await.unwrap();
+        assert_eq!(
+            (field.selector.as_str(), field.source),
+            (selector, source),
+            "{}",
+            label
+        );
+    }
+    let field = session.find_field_by_label("Full name").await.unwrap();
+    assert_eq!(field.text, "Full name *");
+    assert_eq!(field.score, 3);
+
+    match session.find_field_by_label("Street").await {
+        Err(BrowserAgentError::AmbiguousLabel { candidates, .. }) => {
+            assert_eq!(candidates.len(), 2, "{:?}", candidates);
+            assert!(candidates[0].starts_with("input#billing-street"));
+            assert!(candidates[1].starts_with("input#shipping-street"));
+        }
+        other => panic!("expected AmbiguousLabel, got {:?}", other),
+    }
+    assert!(matches!(
+        session.find_field_by_label("Fax").await,
+        Err(BrowserAgentError::ElementNotFound(_))
+    ));
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn fills_fields_by_label() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/form-labels"))
+        .await
+        .unwrap();
+
+    session
+        .fill_field_by_label("Email address", "ada@example.com")
+        .await
+        .unwrap();
+    session
+        .fill_field_by_label("Phone number", "+44 20 7946 0000")
+        .await
+        .unwrap();
+    session
+        .fill_field_by_label("Country", "Spain")
+        .await
+        .unwrap();
+
+    let values = session
+        .execute_script(
+            "['email', 'phone', 'country'].map(id => document.getElementById(id).value)",
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        values,
+        serde_json::json!(["ada@example.com", "+44 20 7946 0000", "es"])
+    );
+    session.close().await.unwrap();
+}
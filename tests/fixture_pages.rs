@@ -0,0 +1,115 @@
+mod common;
+
+use surfai::testing::FixtureServer;
+use surfai::SessionTrait;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Status code and body of a request sent to `server` as raw HTTP/1.1
+async fn request(server: &FixtureServer, method: &str, path: &str, body: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(server.addr()).await.unwrap();
+    stream
+        .write_all(
+            format!(
+                "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                method,
+                path,
+                server.addr(),
+                body.len(),
+                body
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .unwrap_or(0);
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    if (300..400).contains(&status) {
+        return (status, head.to_string());
+    }
+    (status, body.to_string())
+}
+
+#[tokio::test]
+async fn serves_bundled_pages() {
+    let server = FixtureServer::start().await.unwrap();
+    let (status, body) = request(&server, "GET", "/login", "").await;
+    assert_eq!(status, 200);
+    assert!(body.contains("<form"), "{}", body);
+
+    let (status, _) = request(&server, "GET", "/spa/settings", "").await;
+    assert_eq!(status, 200);
+    let (status, _) = request(&server, "GET", "/missing", "").await;
+    assert_eq!(status, 404);
+}
+
+#[tokio::test]
+async fn login_post_redirects_to_dashboard() {
+    let server = FixtureServer::start().await.unwrap();
+    let (status, head) = request(&server, "POST", "/login", "user=a&pass=b").await;
+    assert_eq!(status / 100, 3);
+    assert!(
+        head.to_lowercase().contains("location: /dashboard"),
+        "{}",
+        head
+    );
+}
+
+#[tokio::test]
+async fn echo_returns_the_request() {
+    let server = FixtureServer::start().await.unwrap();
+    let (status, body) = request(&server, "PUT", "/echo?x=1", "payload").await;
+    assert_eq!(status, 200);
+    let echoed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(echoed["method"], "PUT");
+    assert_eq!(echoed["query"], "x=1");
+    assert_eq!(echoed["body"], "payload");
+}
+
+#[tokio::test]
+async fn hang_never_answers() {
+    let server = FixtureServer::start().await.unwrap();
+    let answered = tokio::time::timeout(
+        std::time::Duration::from_millis(300),
+        request(&server, "GET", "/hang", ""),
+    )
+    .await;
+    assert!(answered.is_err());
+}
+
+#[tokio::test]
+async fn extra_page_replaces_a_bundled_one() {
+    let server = FixtureServer::with_page("/login", "<h1>Replaced</h1>")
+        .await
+        .unwrap();
+    let (_, body) = request(&server, "GET", "/login", "").await;
+    assert_eq!(body, "<h1>Replaced</h1>");
+    assert_eq!(
+        server.url("dashboard"),
+        format!("http://{}/dashboard", server.addr())
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn session_extracts_the_login_form() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+    let login = server.url("/login");
+
+    let result = session.navigate_smart(&login).await.unwrap();
+    assert_eq!(result.url, login);
+    let elements = session.get_ai_elements().await.unwrap();
+    assert!(
+        elements.iter().any(|e| e.tag_name == "input"),
+        "no inputs among {:?}",
+        elements
+    );
+    session.close().await.unwrap();
+}
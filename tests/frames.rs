@@ -0,0 +1,144 @@
+//! Scripts and storage inside frames: the `/iframe` fixture embeds the form at
+//! `/iframe/form`, and an extra page embeds the same form from the other loopback
+//! name (`localhost` or `127.0.0.1`) so the frame has an origin of its own.
+
+mod common;
+
+use surfai::core::FrameTarget;
+use surfai::testing::FixtureServer;
+use surfai::{Config, SessionTrait};
+
+/// `/iframe` with the form served from the other loopback name
+const CROSS_ORIGIN_IFRAME: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>Cross-origin iframe</title></head>
+<body>
+  <iframe id="form-frame" width="600" height="300"></iframe>
+  <script>
+    const otherHost = location.hostname === 'localhost' ? '127.0.0.1' : 'localhost';
+    document.getElementById('form-frame').src =
+      location.protocol + '//' + otherHost + ':' + location.port + '/iframe/form';
+  </script>
+</body>
+</html>"#;
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn scripts_run_inside_the_frame() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/iframe"))
+        .await
+        .unwrap();
+
+    let by_selector = FrameTarget::Selector("#form-frame".to_string());
+    assert!(session
+        .wait_for_element_in_frame(by_selector.clone(), "#frame-form", 5000)
+        .await
+        .unwrap());
+    let frames = session.list_frames().await.unwrap();
+    assert_eq!(frames.len(), 2, "{:?}", frames);
+
+    // The form exists only inside the frame
+    let top = session
+        .execute_script("document.getElementById('frame-form') === null")
+        .await
+        .unwrap();
+    assert_eq!(top, true);
+    let title = session
+        .execute_script_in_frame(by_selector.clone(), "document.title")
+        .await
+        .unwrap();
+    assert_eq!(title, "Framed form");
+    let by_url = FrameTarget::UrlPattern("*/iframe/form".to_string());
+    let title = session
+        .execute_script_in_frame(by_url.clone(), "document.title")
+        .await
+        .unwrap();
+    assert_eq!(title, "Framed form");
+
+    session
+        .execute_script_in_frame(
+            by_url.clone(),
+            "document.getElementById('email').value = 'ada@example.com'",
+        )
+        .await
+        .unwrap();
+    session
+        .click_in_frame(by_url.clone(), "#submit")
+        .await
+        .unwrap();
+    let result = session
+        .execute_script_in_frame(by_url, "document.getElementById('result').textContent")
+        .await
+        .unwrap();
+    assert_eq!(result, "Submitted ada@example.com");
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn storage_is_aggregated_per_frame_origin() {
+    let server = FixtureServer::with_page("/iframe/cross-origin", CROSS_ORIGIN_IFRAME)
+        .await
+        .unwrap();
+    let frame_origin = format!("http://localhost:{}", server.addr().port());
+    let mut config = Config::default();
+    config.session.aggregate_frame_storage = true;
+    let session = common::headless_session_with(config).await;
+
+    // A same-origin frame shares the top frame's storage, so nothing is aggregated
+    session
+        .navigate_smart(&server.url("/iframe"))
+        .await
+        .unwrap();
+    let same_origin = FrameTarget::Selector("#form-frame".to_string());
+    assert!(session
+        .wait_for_element_in_frame(same_origin.clone(), "#frame-form", 5000)
+        .await
+        .unwrap());
+    session
+        .execute_script_in_frame(same_origin, "localStorage.setItem('widget', 'shared')")
+        .await
+        .unwrap();
+    let data = session.extract_session("127.0.0.1").await.unwrap();
+    assert_eq!(
+        data.local_storage.get("widget").map(String::as_str),
+        Some("shared")
+    );
+    assert!(
+        data.frame_local_storage.is_empty(),
+        "{:?}",
+        data.frame_local_storage
+    );
+
+    // A frame of another origin is read separately and keyed by that origin
+    session
+        .navigate_smart(&server.url("/iframe/cross-origin"))
+        .await
+        .unwrap();
+    let cross_origin = FrameTarget::UrlPattern("http://localhost:*/iframe/form".to_string());
+    assert!(session
+        .wait_for_element_in_frame(cross_origin.clone(), "#frame-form", 5000)
+        .await
+        .unwrap());
+    session
+        .execute_script_in_frame(
+            cross_origin,
+            "localStorage.setItem('widget_token', 'frame-token')",
+        )
+        .await
+        .unwrap();
+    let data = session.extract_session("127.0.0.1").await.unwrap();
+    assert!(!data.local_storage.contains_key("widget_token"));
+    let frame_storage = data
+        .frame_local_storage
+        .get(&frame_origin)
+        .unwrap_or_else(|| panic!("no storage for {}", frame_origin));
+    assert_eq!(
+        frame_storage.get("widget_token").map(String::as_str),
+        Some("frame-token")
+    );
+    session.close().await.unwrap();
+}
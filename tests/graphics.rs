@@ -0,0 +1,87 @@
+//! SVG and canvas elements of the `/graphics` fixture: an icon-button toolbar,
+//! an SVG legend with a clickable entry and a chart canvas with fallback text.
+
+mod common;
+
+use std::time::Duration;
+use surfai::testing::FixtureServer;
+use surfai::{AIElement, ClickRectOptions, SessionTrait};
+
+fn find<'a>(elements: &'a [AIElement], id: &str) -> &'a AIElement {
+    elements
+        .iter()
+        .find(|element| element.attributes.get("id").map(String::as_str) == Some(id))
+        .unwrap_or_else(|| panic!("#{} is missing", id))
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn svg_controls_are_labeled_and_icons_folded_in() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/graphics"))
+        .await
+        .unwrap();
+    let elements = session.get_ai_elements().await.unwrap();
+
+    for (id, label) in [
+        ("zoom-in", "Zoom in"),
+        ("zoom-out", "Zoom out"),
+        ("download", "Download"),
+        ("legend", "Legend"),
+        ("toggle-revenue", "Toggle revenue"),
+        ("chart", "Monthly revenue chart"),
+    ] {
+        assert_eq!(find(&elements, id).label.as_deref(), Some(label), "#{}", id);
+    }
+
+    // Icons inside the buttons and the decorative divider are not elements
+    let svgs = elements
+        .iter()
+        .filter(|element| element.tag_name == "svg")
+        .count();
+    assert_eq!(svgs, 1, "only the legend SVG is listed");
+    assert!(!elements
+        .iter()
+        .any(|element| element.tag_name == "path" && !element.attributes.contains_key("id")));
+
+    let toggle = find(&elements, "toggle-revenue");
+    session.click(toggle.selector.as_str()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let series = session
+        .execute_script("document.getElementById('series').textContent")
+        .await
+        .unwrap();
+    assert_eq!(series, "hidden");
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn chart_canvas_is_clicked_by_its_box() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/graphics"))
+        .await
+        .unwrap();
+    let elements = session.get_ai_elements().await.unwrap();
+
+    let chart = find(&elements, "chart");
+    assert_eq!(chart.element_type, "canvas");
+    assert!(chart.text_content.is_some(), "canvas has no fallback text");
+    let rect = chart.rect.as_ref().expect("canvas has a box");
+    assert_eq!((rect.width, rect.height), (480.0, 240.0));
+
+    session
+        .click_rect(rect, &ClickRectOptions::default())
+        .await
+        .unwrap();
+    let clicks = session
+        .execute_script("document.getElementById('chart-clicks').textContent")
+        .await
+        .unwrap();
+    assert_eq!(clicks, "1");
+    session.close().await.unwrap();
+}
@@ -0,0 +1,41 @@
+//! The example journey, run against the fixture server
+
+mod common;
+
+use std::sync::Arc;
+use surfai::testing::FixtureServer;
+use surfai::tools::{run_journey, Journey, StepStatus};
+use surfai::{InMemorySink, SessionTrait};
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn example_journey_passes_on_the_fixture_site() {
+    let server = FixtureServer::start().await.unwrap();
+    let screenshots = Arc::new(InMemorySink::new());
+    let session = common::headless_session()
+        .await
+        .with_artifact_sink(screenshots.clone());
+    let journey = Journey::from_path(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/journeys/fixture_login.yaml"
+    ))
+    .unwrap()
+    .with_base_url(&server.url("/"));
+
+    let report = run_journey(&session, &journey).await.unwrap();
+    assert!(
+        report.passed(),
+        "{:?}",
+        report
+            .steps
+            .iter()
+            .filter(|step| step.status != StepStatus::Passed)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(report.variables["greeting"], "Welcome back");
+    assert_eq!(
+        screenshots.names(),
+        ["journeys/fixture-login-dashboard.png"]
+    );
+    session.close().await.unwrap();
+}
@@ -0,0 +1,80 @@
+//! AI labels of fields labeled only by `<label for>` on the `/label-for` sign-up
+//! form, and by each kind of label on `/form-labels`.
+
+mod common;
+
+use surfai::testing::FixtureServer;
+use surfai::{DefaultSession, SessionTrait};
+
+/// Assert the AI element with each id is labeled as given
+async fn assert_labels(session: &DefaultSession, expected: &[(&str, &str)]) {
+    let elements = session.get_ai_elements().await.unwrap();
+    for (id, label) in expected {
+        let found = elements
+            .iter()
+            .find(|element| element.attributes.get("id").map(String::as_str) == Some(*id))
+            .and_then(|element| element.label.as_deref());
+        assert_eq!(found, Some(*label), "#{}", id);
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn fields_take_the_text_of_their_label_for() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/label-for"))
+        .await
+        .unwrap();
+
+    assert_labels(
+        &session,
+        &[
+            ("f1", "First name"),
+            ("f2", "Last name"),
+            ("f3", "Work email"),
+            ("f4", "Password"),
+            ("f5", "Team size"),
+            ("f6", "About your project"),
+            ("f7", "Send me product updates"),
+        ],
+    )
+    .await;
+
+    // Fill-by-label resolves the same association
+    session
+        .fill_field_by_label("Work email", "ada@example.com")
+        .await
+        .unwrap();
+    let value = session
+        .execute_script("document.getElementById('f3').value")
+        .await
+        .unwrap();
+    assert_eq!(value, "ada@example.com");
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn wrapping_and_aria_labelledby_labels_are_used() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/form-labels"))
+        .await
+        .unwrap();
+
+    assert_labels(
+        &session,
+        &[
+            ("full-name", "Full name"),
+            ("email", "Email address"),
+            ("city", "Ciudad"),
+            ("country", "Country"),
+            ("billing-street", "Street"),
+        ],
+    )
+    .await;
+    session.close().await.unwrap();
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use surfai::browser::{DismissStrategy, OverlayKind};
+use surfai::testing::FixtureServer;
+use surfai::{DefaultSession, LoginConfig, SecretString, SessionTrait};
+
+/// Dismiss the cookie notice of the login fixture at `path`, then log in through
+/// it with the default, English-only `LoginConfig`
+async fn log_in(session: &DefaultSession, server: &FixtureServer, path: &str, language: &str) {
+    let login = server.url(path);
+    session.navigate_smart(&login).await.unwrap();
+    assert_eq!(session.language_pack().await.unwrap().language, language);
+
+    let overlays = session.detect_overlays().await.unwrap();
+    let notice = overlays
+        .iter()
+        .find(|overlay| overlay.kind == OverlayKind::CookieBanner)
+        .unwrap_or_else(|| panic!("no cookie notice among {:?}", overlays));
+    assert!(notice.close_selector.is_some(), "{:?}", notice);
+    let outcome = session
+        .dismiss_overlay(notice, DismissStrategy::CloseButton)
+        .await
+        .unwrap();
+    assert!(outcome.method.is_some(), "{:?}", outcome.attempts);
+
+    let data = session
+        .auto_login_and_extract_session(
+            &login,
+            "ada",
+            &SecretString::new("correct horse"),
+            LoginConfig::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(data.url, server.url("/dashboard"));
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn german_login_by_lang_attribute() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+    log_in(&session, &server, "/login-de", "de").await;
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn spanish_login_by_content_language() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+    log_in(&session, &server, "/login-es", "es").await;
+    session.close().await.unwrap();
+}
@@ -0,0 +1,87 @@
+//! Element text of the `/menus` fixture: a nested menubar with zero-width
+//! characters, soft hyphens and runs of whitespace in its entries.
+
+mod common;
+
+use surfai::dom::{DomElement, DomState};
+use surfai::testing::FixtureServer;
+use surfai::SessionTrait;
+
+fn by_id<'a>(state: &'a DomState, id: &str) -> &'a DomElement {
+    state
+        .elements
+        .iter()
+        .find(|element| element.attributes.get("id").map(String::as_str) == Some(id))
+        .unwrap_or_else(|| panic!("#{} is missing", id))
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn menu_text_is_normalized() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session.navigate_smart(&server.url("/menus")).await.unwrap();
+    let state = session.get_page_state(false).await.unwrap();
+
+    let expected = [
+        // Containers keep their own text rather than their whole submenu's
+        ("products", "Products"),
+        ("accessories", "Accessories"),
+        // Without text of their own they preview their descendants'
+        ("support", "Contact us Returns and refunds"),
+        (
+            "menubar",
+            "Products Laptops Phones Accessories Cases and covers Chargers Contact us Returns and refunds",
+        ),
+        // Zero-width characters, soft hyphens and whitespace runs are gone
+        ("laptops", "Laptops"),
+        ("phones", "Phones"),
+        ("cases", "Cases and covers"),
+        ("checkout", "Proceed to checkout"),
+    ];
+    for (id, text) in expected {
+        assert_eq!(
+            by_id(&state, id).text_content.as_deref(),
+            Some(text),
+            "#{}",
+            id
+        );
+    }
+
+    let products = by_id(&state, "products");
+    assert_eq!(products.own_text_len, 8);
+    assert!(products.full_text_len > 8, "{}", products.full_text_len);
+
+    // The compact encoding keeps both lengths
+    let lengths = |state: &DomState| {
+        state
+            .elements
+            .iter()
+            .map(|element| (element.own_text_len, element.full_text_len))
+            .collect::<Vec<_>>()
+    };
+    let decoded = DomState::from_bytes(&state.to_bytes()).unwrap();
+    assert_eq!(lengths(&decoded), lengths(&state));
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn labels_use_the_normalized_own_text() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session.navigate_smart(&server.url("/menus")).await.unwrap();
+    let elements = session.get_ai_elements().await.unwrap();
+
+    for (id, label) in [
+        ("products", "Products"),
+        ("checkout", "Proceed to checkout"),
+    ] {
+        let found = elements
+            .iter()
+            .find(|element| element.attributes.get("id").map(String::as_str) == Some(id))
+            .and_then(|element| element.label.as_deref());
+        assert_eq!(found, Some(label), "#{}", id);
+    }
+    session.close().await.unwrap();
+}
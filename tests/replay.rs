@@ -0,0 +1,79 @@
+//! Recording a run to a bundle, reading it back and replaying its steps
+
+mod common;
+
+use serde_json::{json, Value};
+use surfai::replay::{ReplayRun, RunBundleOptions, RunRecorder};
+use surfai::testing::FixtureServer;
+use surfai::tools::{run_journey_recorded, Journey};
+use surfai::SessionTrait;
+
+const SIGN_IN: &str = r##"
+name: Recorded sign-in
+steps:
+  - navigate: /login
+  - fill: { label: Username, value: alice }
+  - fill: { label: Password, value: correct-horse }
+  - click: { css: "#login-button" }
+  - assert: { url_contains: /dashboard }
+"##;
+
+/// Run `journey` on a fresh session, recording it into `dir`
+async fn record(server: &FixtureServer, journey: &Journey, dir: &std::path::Path) -> ReplayRun {
+    let session = common::headless_session().await;
+    let recorder = RunRecorder::create(dir, &journey.name, RunBundleOptions::default()).unwrap();
+    let report = run_journey_recorded(
+        &session,
+        &journey.clone().with_base_url(&server.url("/")),
+        &recorder,
+    )
+    .await
+    .unwrap();
+    assert!(report.passed(), "{:?}", report.steps);
+    recorder.finish().unwrap();
+    session.close().await.unwrap();
+    ReplayRun::open(dir).unwrap()
+}
+
+/// Path of a recorded URL, which carries the fixture server's port
+fn path(url: Option<&str>) -> Option<String> {
+    url.map(|url| url::Url::parse(url).unwrap().path().to_string())
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn recorded_steps_replay_to_the_same_pages() {
+    let server = FixtureServer::start().await.unwrap();
+    let root = std::env::temp_dir().join(format!("surfai-replay-{}", std::process::id()));
+    let original = record(
+        &server,
+        &Journey::from_yaml_str(SIGN_IN).unwrap(),
+        &root.join("original"),
+    )
+    .await;
+    assert!(original.finished());
+    assert_eq!(original.len(), 5);
+
+    // The journey again, rebuilt only from what the bundle stored
+    let steps: Vec<Value> = original
+        .steps()
+        .iter()
+        .map(|step| json!({ step.command(): step.params() }))
+        .collect();
+    let replayed_journey =
+        Journey::from_value(json!({ "name": original.name(), "steps": steps })).unwrap();
+    let replayed = record(&server, &replayed_journey, &root.join("replayed")).await;
+
+    assert_eq!(replayed.len(), original.len());
+    for (before, after) in original.steps().iter().zip(replayed.steps()) {
+        assert_eq!(before.command(), after.command());
+        assert_eq!(before.params(), after.params());
+        assert!(after.result().success, "{}", after.describe());
+        assert_eq!(path(before.post_url()), path(after.post_url()));
+    }
+    assert_eq!(
+        path(replayed.step(5).unwrap().post_url()).as_deref(),
+        Some("/dashboard")
+    );
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,102 @@
+//! Scrolling inside the `/scroll-panel` fixture: a button at the bottom of a
+//! 3000px div in a 400px `overflow: auto` panel, and a chat panel that loads its
+//! messages as its own scroll position nears the end.
+
+mod common;
+
+use surfai::testing::FixtureServer;
+use surfai::{DefaultSession, ScrollAlignment, SessionTrait};
+
+/// Text of `#result` once the page has set it; clicks are dispatched after a short delay
+async fn result_text(session: &DefaultSession) -> String {
+    for _ in 0..20 {
+        let text = session
+            .execute_script("document.getElementById('result').textContent")
+            .await
+            .unwrap();
+        if text != "" {
+            return text.as_str().unwrap().to_string();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("#result was never set");
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn scrolls_the_panel_to_the_button_at_its_bottom() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/scroll-panel"))
+        .await
+        .unwrap();
+
+    let state = session.get_page_state(false).await.unwrap();
+    let button = state
+        .elements
+        .iter()
+        .find(|element| element.element_id.as_deref() == Some("panel-button"))
+        .expect("the panel button is extracted");
+    assert!(
+        button
+            .scroll_parents
+            .iter()
+            .any(|parent| parent.contains("panel")),
+        "{:?}",
+        button.scroll_parents
+    );
+
+    session
+        .scroll_to_element("#panel-button", ScrollAlignment::Center)
+        .await
+        .unwrap();
+    let scroll_top = session
+        .execute_script("document.getElementById('panel').scrollTop")
+        .await
+        .unwrap()
+        .as_f64()
+        .unwrap();
+    assert!(scroll_top > 2000.0, "panel scrolled to {}", scroll_top);
+
+    let center = session
+        .execute_script(
+            "(() => { const r = document.getElementById('panel-button').getBoundingClientRect(); \
+             return [r.left + r.width / 2, r.top + r.height / 2]; })()",
+        )
+        .await
+        .unwrap();
+    let (x, y) = (center[0].as_f64().unwrap(), center[1].as_f64().unwrap());
+    let hit = session
+        .element_at(x, y)
+        .await
+        .unwrap()
+        .expect("the button is inside the viewport");
+    assert_eq!(hit.element_id.as_deref(), Some("panel-button"));
+
+    session.click("#panel-button").await.unwrap();
+    assert_eq!(result_text(&session).await, "clicked");
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn collects_items_loaded_by_scrolling_a_panel() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/scroll-panel"))
+        .await
+        .unwrap();
+
+    let messages = session
+        .collect_items(".message", Some("#chat"), 100)
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 100);
+    assert_eq!(
+        messages.last().unwrap().text_content.as_deref(),
+        Some("Message 100")
+    );
+    session.close().await.unwrap();
+}
@@ -0,0 +1,133 @@
+//! Lint-style check that a typed password never reaches what the crate prints,
+//! traces or writes. The scenario runs in a child process, so its stdout and
+//! stderr can be searched for the sentinel.
+
+mod common;
+
+use surfai::replay::{RunBundleOptions, RunRecorder};
+use surfai::testing::FixtureServer;
+use surfai::{ActionResult, LoginConfig, SecretString, SessionTrait, TypeMode};
+
+/// Stands in for a password; no part of the output may contain it
+const SENTINEL: &str = "sentinel-7f3a9c-hunter2";
+
+/// Set in the child process that runs the scenario
+const CHILD_ENV: &str = "SURFAI_SECRET_LEAK_CHILD";
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn typed_passwords_never_reach_logs_or_artifacts() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        return scenario().await;
+    }
+
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            "--exact",
+            "typed_passwords_never_reach_logs_or_artifacts",
+            "--ignored",
+            "--nocapture",
+        ])
+        .env(CHILD_ENV, "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "scenario failed\n{}\n{}",
+        stdout,
+        stderr
+    );
+    // Both the prints and the traces were captured, so an empty capture cannot pass
+    assert!(stdout.contains("Successfully typed"), "{}", stdout);
+    assert!(stdout.contains("secret leak scenario started"), "{}", stdout);
+    for (stream, text) in [("stdout", &stdout), ("stderr", &stderr)] {
+        assert!(
+            !text.contains(SENTINEL),
+            "the password leaked to {}:\n{}",
+            stream,
+            text
+        );
+    }
+}
+
+/// Types the sentinel every way a password gets typed, with traces on, and
+/// checks the values and artifacts that come back in-process
+async fn scenario() {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_writer(std::io::stdout)
+        .init();
+    tracing::info!("secret leak scenario started");
+
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    let login = server.url("/login");
+    session.navigate_smart(&login).await.unwrap();
+
+    let bundle = std::env::temp_dir().join(format!("surfai-secret-leak-{}", std::process::id()));
+    let recorder =
+        RunRecorder::create(&bundle, "secret leak", RunBundleOptions::default()).unwrap();
+    recorder
+        .record(
+            &session,
+            "type",
+            serde_json::json!({ "selector": "#password" }),
+            async {
+                session.type_text_enhanced("#password", SENTINEL).await?;
+                Ok(ActionResult::success("typed".to_string()))
+            },
+        )
+        .await
+        .unwrap();
+    recorder.finish().unwrap();
+
+    let result = session
+        .type_text_with_mode("#password", SENTINEL, TypeMode::Replace)
+        .await
+        .unwrap();
+    assert_eq!(result.after, "[REDACTED]");
+    session
+        .type_secret("#username", &SecretString::new(SENTINEL))
+        .await
+        .unwrap();
+
+    let state = serde_json::to_string(&session.get_page_state(false).await.unwrap()).unwrap();
+    assert!(!state.contains(SENTINEL), "{}", state);
+
+    session
+        .auto_login_and_extract_session(
+            &login,
+            "ada",
+            &SecretString::new(SENTINEL),
+            LoginConfig::default(),
+        )
+        .await
+        .unwrap();
+
+    for entry in walk(&bundle) {
+        let contents = std::fs::read(&entry).unwrap();
+        assert!(
+            !String::from_utf8_lossy(&contents).contains(SENTINEL),
+            "the password leaked to {}",
+            entry.display()
+        );
+    }
+    std::fs::remove_dir_all(&bundle).unwrap();
+    session.close().await.unwrap();
+}
+
+/// Every file under `dir`
+fn walk(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(walk(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
@@ -0,0 +1,104 @@
+//! The 200-option country select of the `/countries` fixture is one element
+//! carrying its options, not 200 elements of its own.
+
+mod common;
+
+use surfai::testing::FixtureServer;
+use surfai::{BrowserAgentError, DefaultSession, DomElement, SessionTrait};
+
+async fn country_select(session: &DefaultSession) -> DomElement {
+    session
+        .get_page_state(false)
+        .await
+        .unwrap()
+        .elements
+        .into_iter()
+        .find(|element| element.element_id.as_deref() == Some("country"))
+        .expect("#country is extracted")
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn options_stay_inside_their_select() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/countries"))
+        .await
+        .unwrap();
+
+    let state = session.get_page_state(false).await.unwrap();
+    assert!(!state
+        .elements
+        .iter()
+        .any(|element| matches!(element.tag_name.as_str(), "option" | "optgroup")));
+    assert!(
+        state.elements.len() <= 20,
+        "{} elements",
+        state.elements.len()
+    );
+
+    let select = country_select(&session).await;
+    assert_eq!(select.options.len(), 200);
+    let selected: Vec<&str> = select
+        .options
+        .iter()
+        .filter(|option| option.selected)
+        .map(|option| option.value.as_str())
+        .collect();
+    assert_eq!(selected, ["T042"]);
+    let austria = select
+        .options
+        .iter()
+        .find(|option| option.value == "AT")
+        .expect("AT is an option");
+    assert_eq!(austria.group.as_deref(), Some("Europe"));
+    assert!(select.text_content.is_none(), "select carries option text");
+
+    let elements = session.get_ai_elements().await.unwrap();
+    let highlights = session.highlight_interactive_elements().await.unwrap();
+    assert!(elements.len() <= 20, "{} AI elements", elements.len());
+    assert!(highlights.len() <= 10, "{} highlights", highlights.len());
+    let select = elements
+        .iter()
+        .find(|element| element.tag_name == "select")
+        .expect("the select is an AI element");
+    assert_eq!(select.options.len(), 200);
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn select_option_matches_the_listed_options() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/countries"))
+        .await
+        .unwrap();
+
+    // Text or value, in any case and without accents
+    let option = session
+        .select_option("#country", "osterreich")
+        .await
+        .unwrap();
+    assert_eq!(option.value, "AT");
+    let chosen = session
+        .execute_script("document.getElementById('chosen').textContent")
+        .await
+        .unwrap();
+    assert_eq!(chosen, "AT");
+    let selected = country_select(&session)
+        .await
+        .options
+        .into_iter()
+        .find(|option| option.selected)
+        .map(|option| option.value);
+    assert_eq!(selected.as_deref(), Some("AT"));
+
+    assert!(matches!(
+        session.select_option("#country", "Atlantis").await,
+        Err(BrowserAgentError::ElementNotFound(_))
+    ));
+    session.close().await.unwrap();
+}
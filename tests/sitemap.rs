@@ -0,0 +1,57 @@
+mod common;
+
+use surfai::testing::FixtureServer;
+use surfai::tools::{fetch_sitemap, CrawlPolicy};
+use surfai::SessionTrait;
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn fetch_follows_nested_indexes_and_gzip() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+
+    let entries = fetch_sitemap(&session, &server.url("/"), &CrawlPolicy::default())
+        .await
+        .unwrap();
+    let urls: Vec<&str> = entries.iter().map(|e| e.url.as_str()).collect();
+    // The nested index loops back to /sitemap.xml and the archive repeats /login;
+    // both are taken once
+    assert_eq!(
+        urls,
+        [
+            server.url("/"),
+            server.url("/login"),
+            server.url("/dialog"),
+            server.url("/links"),
+        ]
+    );
+
+    // Fields of the namespaced urlset; the image:loc extension is not a page
+    assert_eq!(entries[0].lastmod.as_deref(), Some("2024-01-15"));
+    assert_eq!(entries[0].changefreq.as_deref(), Some("daily"));
+    assert_eq!(entries[0].priority, Some(1.0));
+    assert_eq!(entries[1].priority, Some(0.5));
+    assert_eq!(entries[1].lastmod, None);
+    assert_eq!(entries[2].priority, None);
+    // Inflated from the gzipped sitemap
+    assert_eq!(entries[3].changefreq.as_deref(), Some("monthly"));
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn max_sitemaps_stops_before_the_nested_index() {
+    let session = common::headless_session().await;
+    let server = FixtureServer::start().await.unwrap();
+
+    let policy = CrawlPolicy {
+        max_sitemaps: 2,
+        ..CrawlPolicy::default()
+    };
+    let entries = fetch_sitemap(&session, &server.url("/"), &policy)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 3);
+    assert!(!entries.iter().any(|e| e.url == server.url("/links")));
+    session.close().await.unwrap();
+}
@@ -0,0 +1,131 @@
+//! Typing modes on the `/type-modes` fixture: a prefilled search input, a
+//! prefilled textarea and a rich text editor with bold and italic text.
+
+mod common;
+
+use surfai::testing::FixtureServer;
+use surfai::{DefaultSession, SessionTrait, TypeMode};
+
+async fn open(server: &FixtureServer) -> DefaultSession {
+    let session = common::headless_session().await;
+    session
+        .navigate_smart(&server.url("/type-modes"))
+        .await
+        .unwrap();
+    session
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn input_appends_prepends_and_replaces() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = open(&server).await;
+
+    let cases = [
+        (TypeMode::Append, " async", "rust", "rust async"),
+        (
+            TypeMode::Prepend,
+            "learn ",
+            "rust async",
+            "learn rust async",
+        ),
+        (TypeMode::Replace, "tokio", "learn rust async", "tokio"),
+    ];
+    for (mode, text, before, after) in cases {
+        let result = session
+            .type_text_with_mode("#search", text, mode)
+            .await
+            .unwrap();
+        assert_eq!(result.mode, mode);
+        assert_eq!(
+            (result.before.as_str(), result.after.as_str()),
+            (before, after),
+            "{:?}",
+            mode
+        );
+        assert_eq!(result.element_type, "input");
+    }
+    let value = session
+        .execute_script("document.getElementById('search').value")
+        .await
+        .unwrap();
+    assert_eq!(value, "tokio");
+
+    // Plain typing still replaces the content
+    session
+        .type_text("#email", "ada@example.com")
+        .await
+        .unwrap();
+    let value = session
+        .execute_script("document.getElementById('email').value")
+        .await
+        .unwrap();
+    assert_eq!(value, "ada@example.com");
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn textarea_replaces_the_selection() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = open(&server).await;
+    // Select "second" on the second line
+    session
+        .execute_script("document.getElementById('notes').setSelectionRange(11, 17)")
+        .await
+        .unwrap();
+
+    let result = session
+        .type_text_with_mode("#notes", "last", TypeMode::InsertAtCursor)
+        .await
+        .unwrap();
+    assert_eq!(result.before, "first line\nsecond line");
+    assert_eq!(result.after, "first line\nlast line");
+    assert_eq!(result.element_type, "textarea");
+    session.close().await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs Chrome"]
+async fn editor_keeps_its_formatting() {
+    let server = FixtureServer::start().await.unwrap();
+    let session = open(&server).await;
+
+    let result = session
+        .type_text_with_mode("#editor", " now", TypeMode::Append)
+        .await
+        .unwrap();
+    assert_eq!(result.before, "Keep bold and italic text");
+    assert_eq!(result.after, "Keep bold and italic text now");
+    let result = session
+        .type_text_with_mode("#editor", "Please ", TypeMode::Prepend)
+        .await
+        .unwrap();
+    assert_eq!(result.after, "Please Keep bold and italic text now");
+
+    let html = session
+        .execute_script("document.getElementById('editor').innerHTML")
+        .await
+        .unwrap();
+    let html = html.as_str().unwrap();
+    assert!(html.contains("<b>bold</b>"), "{}", html);
+    assert!(html.contains("<i>italic</i>"), "{}", html);
+
+    // The editor saw input events, not a wholesale innerHTML swap
+    let events = session
+        .execute_script(
+            "Array.from(document.querySelectorAll('#events li')).map(li => li.textContent)",
+        )
+        .await
+        .unwrap();
+    assert!(
+        events
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|event| event == "editor input insertText"),
+        "{}",
+        events
+    );
+    session.close().await.unwrap();
+}